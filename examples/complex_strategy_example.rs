@@ -127,6 +127,7 @@ impl HFTStrategy {
             let cmd = TaskCommand::WsMessage {
                 msg: ws_msg,
                 ack: AckHandle::none(),
+                reply: None,
             };
             handle.send_command(cmd, None).await?;
         } else {
@@ -238,6 +239,7 @@ impl AccountModule {
             let cmd = TaskCommand::WsMessage {
                 msg: login_msg,
                 ack: AckHandle::new(tx),
+                reply: None,
             };
             handle
                 .send_command(cmd, Some((AckStatus::WsMessage, rx)))
@@ -248,6 +250,7 @@ impl AccountModule {
             let cmd = TaskCommand::WsMessage {
                 msg: ws_msg,
                 ack: AckHandle::new(tx),
+                reply: None,
             };
             handle
                 .send_command(cmd, Some((AckStatus::WsMessage, rx)))
@@ -321,6 +324,7 @@ async fn main() -> InfraResult<()> {
         filter_channels: false,
         chunk: 1,
         task_base_id: None,
+        shards: None,
     };
 
     let okx_trade_task = WsTaskInfo {
@@ -329,6 +333,7 @@ async fn main() -> InfraResult<()> {
         filter_channels: false,
         chunk: 10, // Ten task instances; each can own a separate symbol group.
         task_base_id: None,
+        shards: None,
     };
 
     let place_order_task = AltTaskInfo {
@@ -90,6 +90,7 @@ impl BinanceStrategy {
             let cmd = TaskCommand::WsMessage {
                 msg: ws_msg,
                 ack: AckHandle::none(),
+                reply: None,
             };
             handle.send_command(cmd, None).await?;
         } else {
@@ -165,6 +166,7 @@ async fn main() -> InfraResult<()> {
         filter_channels: false, // Log websocket decode failures.
         chunk: 1,               // number of websocket connections for this task
         task_base_id: None,
+        shards: None,
     };
 
     // Five-second scheduler.
@@ -1,8 +1,8 @@
 //! Private account websocket example.
 //!
 //! This example wires private account websocket relays for Binance UM futures
-//! and OKX. Binance UM uses a listen-key websocket URL, while OKX connects,
-//! logs in, and subscribes over the private websocket.
+//! and OKX. The Binance UM relay creates and renews its own listen key, while
+//! OKX connects, logs in, and subscribes over the private websocket.
 //!
 //! Run it with:
 //!
@@ -13,7 +13,7 @@
 //! Private streams require the corresponding exchange API-key environment
 //! variables documented in the usage guide.
 
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 use tokio::sync::oneshot;
 use tracing::{error, info, warn};
 
@@ -37,10 +37,15 @@ impl AccountModule {
 
     pub async fn connect_binance_um_acc_channel(&mut self, channel: &WsChannel) -> InfraResult<()> {
         if let Some(handle) = self.find_ws_handle(channel, 1001) {
-            let ws_url = self.binance_um_cli.get_private_connect_msg(channel).await?;
+            // The target carries the client, so the relay keeps the listen
+            // key alive and rotates it when Binance expires it.
+            let target = self
+                .binance_um_cli
+                .get_private_connect_target(channel)
+                .await?;
             let (tx, rx) = oneshot::channel();
-            let cmd = TaskCommand::WsConnect {
-                msg: ws_url,
+            let cmd = TaskCommand::WsConnectWithTarget {
+                target,
                 ack: AckHandle::new(tx),
             };
             handle
@@ -64,24 +69,15 @@ impl AccountModule {
                 .send_command(cmd, Some((AckStatus::WsConnect, rx)))
                 .await?;
 
+            // Wait for the venue to confirm the login before subscribing.
             let login_msg = self.okx_cli.ws_login_msg()?;
-            let (tx, rx) = oneshot::channel();
-            let cmd = TaskCommand::WsMessage {
-                msg: login_msg,
-                ack: AckHandle::new(tx),
-            };
             handle
-                .send_command(cmd, Some((AckStatus::WsMessage, rx)))
+                .send_ws_request(login_msg, WsReplyMatcher::okx_event("login"))
                 .await?;
 
             let ws_msg = self.okx_cli.get_private_sub_msg(channel).await?;
-            let (tx, rx) = oneshot::channel();
-            let cmd = TaskCommand::WsMessage {
-                msg: ws_msg,
-                ack: AckHandle::new(tx),
-            };
             handle
-                .send_command(cmd, Some((AckStatus::WsMessage, rx)))
+                .send_ws_request(ws_msg, WsReplyMatcher::okx_event("subscribe"))
                 .await?;
         } else {
             warn!("No handle found for channel {:?}", channel);
@@ -109,12 +105,8 @@ impl CommandEmitter for AccountModule {
 }
 
 impl EventHandler for AccountModule {
-    async fn on_schedule(&mut self, msg: InfraMsg<AltScheduleEvent>) {
-        if let Err(e) = self.binance_um_cli.renew_listen_key().await {
-            error!("Renew Listen key failed: {:?}", e);
-        }
-
-        info!("Renew Binance Listen Key: {:?}", msg);
+    async fn on_ws_connection(&mut self, msg: InfraMsg<WsConnectionState>) {
+        info!("Task {} connection: {:?}", msg.task_id, msg.data);
     }
 
     async fn on_ws_event(&mut self, msg: InfraMsg<WsTaskInfo>) {
@@ -158,18 +150,13 @@ async fn main() -> InfraResult<()> {
     tracing_subscriber::fmt::init();
     info!("Logger initialized");
 
-    let alt_task = AltTaskInfo {
-        alt_task_type: AltTaskType::TimeScheduler(Duration::from_secs(1800)),
-        chunk: 1,
-        task_base_id: None,
-    };
-
     let binance_um_acc_bal_pos_task = WsTaskInfo {
         market: Market::BinanceUmFutures,
         ws_channel: WsChannel::AccountBalAndPos,
         filter_channels: true,
         chunk: 1,
         task_base_id: Some(1001),
+        shards: None,
     };
 
     let okx_acc_bal_pos_task = WsTaskInfo {
@@ -178,10 +165,10 @@ async fn main() -> InfraResult<()> {
         filter_channels: false,
         chunk: 1,
         task_base_id: Some(1002),
+        shards: None,
     };
 
     let env = EnvBuilder::new()
-        .with_task(alt_task)
         .with_task(binance_um_acc_bal_pos_task)
         .with_task(okx_acc_bal_pos_task)
         .with_strategy_module(AccountModule::new())
@@ -3,12 +3,19 @@
 //! This module contains the environment builder and mediator. Use
 //! [`env_builder::EnvBuilder`] in binaries to register tasks and strategy
 //! modules; the builder creates one broadcast stream per concrete task. Then call
-//! [`env_mediator::EnvMediator::execute`] to start the runtime.
+//! [`env_mediator::EnvMediator::execute`] to start the runtime, or
+//! [`env_mediator::EnvMediator::into_backtest`] to replay recorded events
+//! through the same strategy modules.
 //!
 //! The runtime owns task spawning and command-registry creation. Strategy
 //! modules own business logic and receive typed events after the mediator has
 //! initialized the environment.
 
+pub mod backtest;
+pub mod clock;
 pub mod env_builder;
 pub(crate) mod env_core;
 pub mod env_mediator;
+#[cfg(feature = "mock_venue")]
+pub mod mock_venue;
+pub mod shutdown;
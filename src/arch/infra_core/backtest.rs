@@ -0,0 +1,828 @@
+//! Deterministic replay of recorded market data through strategy modules.
+//!
+//! [`BacktestEnv`] runs the same modules registered in an [`EnvBuilder`]
+//! without spawning task relays. Recorded [`BacktestRecord`]s are published
+//! on the task keys they were recorded from, scheduler tasks tick on a
+//! simulated clock, and `TaskCommand::OrderExecute` is routed into a
+//! simulated matching engine whose order, fill, and position updates arrive
+//! through `on_acc_order`, `on_acc_fill`, and `on_acc_pos`.
+//!
+//! Events are dispatched one at a time. Each module finishes its callback
+//! before the next module, or the next event, is served, and commands sent
+//! from a callback are handled in task-declaration order. Two runs over the
+//! same records therefore produce the same callbacks and fills.
+//!
+//! [`EnvBuilder`]: crate::arch::infra_core::env_builder::EnvBuilder
+
+pub(crate) mod matching;
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    future::poll_fn,
+    mem::{Discriminant, discriminant},
+    pin::pin,
+    sync::Arc,
+    task::Poll,
+    time::Duration,
+};
+
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::arch::{
+    infra_core::clock::RuntimeClock,
+    market_assets::{
+        api_data::account_data::OrderAckData,
+        base_data::{InstrumentType, OrderSide, OrderStatus},
+        market_core::Market,
+    },
+    strategy_base::{
+        command::{
+            ack_handle::AckStatus,
+            command_core::{CommandHandle, CommandRegistry, TaskCommand},
+        },
+        handler::{
+            alt_events::{
+                AltCancel, AltOrder, AltOrderAck, AltOrderError, AltOrderRequest, AltScheduleEvent,
+            },
+            lob_events::{
+                FillLiquidity, WsAccOrder, WsAccPosition, WsCandle, WsFill, WsLob, WsTrade,
+            },
+            task_channel::{InfraMsg, RoutedEvent, TaskEvent},
+        },
+        risk::RiskGate,
+    },
+    task_execution::{
+        TaskInfo, TaskKey,
+        task_alt::AltTaskType,
+        task_ws::{WsChannel, WsTaskInfo},
+    },
+    traits::strategy::Strategy,
+};
+use crate::errors::{InfraError, InfraResult};
+
+use matching::{SimMatcher, SimOutput};
+
+/// Simulation settings for [`BacktestEnv`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BacktestConfig {
+    /// Simulated time between an `OrderExecute` command and its arrival at
+    /// the matching engine.
+    pub order_latency: Duration,
+    /// Fee charged on resting fills, as a fraction of notional.
+    pub maker_fee_rate: f64,
+    /// Fee charged on marketable fills, as a fraction of notional.
+    pub taker_fee_rate: f64,
+}
+
+/// Market data payload of one [`BacktestRecord`].
+#[derive(Clone, Debug)]
+pub enum BacktestData {
+    Trade(Vec<WsTrade>),
+    Lob(Vec<WsLob>),
+    Candle(Vec<WsCandle>),
+}
+
+impl BacktestData {
+    fn name(&self) -> &'static str {
+        match self {
+            BacktestData::Trade(_) => "Trade",
+            BacktestData::Lob(_) => "Lob",
+            BacktestData::Candle(_) => "Candle",
+        }
+    }
+
+    fn channel(&self) -> Discriminant<WsChannel> {
+        match self {
+            BacktestData::Trade(_) => discriminant(&WsChannel::Trades(None)),
+            BacktestData::Lob(_) => discriminant(&WsChannel::Lob(None)),
+            BacktestData::Candle(_) => discriminant(&WsChannel::Candles(None)),
+        }
+    }
+
+    fn into_event(self, task_id: u64, received_at: u64) -> TaskEvent {
+        match self {
+            BacktestData::Trade(data) => TaskEvent::Trade(InfraMsg {
+                task_id,
+                received_at,
+                data: Arc::new(data),
+            }),
+            BacktestData::Lob(data) => TaskEvent::Lob(InfraMsg {
+                task_id,
+                received_at,
+                data: Arc::new(data),
+            }),
+            BacktestData::Candle(data) => TaskEvent::Candle(InfraMsg {
+                task_id,
+                received_at,
+                data: Arc::new(data),
+            }),
+        }
+    }
+}
+
+/// One entry of a backtest event log.
+///
+/// `timestamp` is the simulated time in microseconds at which the payload is
+/// published. `task_id` selects the registered websocket task of the matching
+/// channel family, so a `Trade` record with `task_id = 1` is delivered exactly
+/// like a live batch from the `WsChannel::Trades(..)` task with id 1.
+#[derive(Clone, Debug)]
+pub struct BacktestRecord {
+    pub timestamp: u64,
+    pub task_id: u64,
+    pub data: BacktestData,
+}
+
+impl BacktestRecord {
+    pub fn new(timestamp: u64, task_id: u64, data: BacktestData) -> Self {
+        Self {
+            timestamp,
+            task_id,
+            data,
+        }
+    }
+}
+
+/// One simulated execution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BacktestFill {
+    pub timestamp: u64,
+    pub market: Market,
+    pub inst: String,
+    pub inst_type: InstrumentType,
+    pub side: OrderSide,
+    pub price: f64,
+    pub size: f64,
+    pub fee: f64,
+    /// `true` when a resting order was filled.
+    pub maker: bool,
+    pub order_id: Option<String>,
+    pub cli_order_id: Option<String>,
+}
+
+/// Net position held by the simulated account at the end of a run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BacktestPosition {
+    pub market: Market,
+    pub inst: String,
+    /// Signed net size; negative values are short.
+    pub size: f64,
+    pub avg_price: f64,
+    pub realized_pnl: f64,
+    pub fees: f64,
+}
+
+/// Summary returned by [`BacktestEnv::run`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BacktestReport {
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    /// Number of replayed records.
+    pub records: u64,
+    /// Number of events dispatched to strategy modules.
+    pub events: u64,
+    /// Number of orders received through `OrderExecute`.
+    pub orders: u64,
+    pub fills: Vec<BacktestFill>,
+    pub positions: Vec<BacktestPosition>,
+}
+
+/// Backtest runtime created by [`EnvMediator::into_backtest`].
+///
+/// Task declarations keep their live meaning where it can be simulated:
+///
+/// - websocket tasks publish the recorded `Trade`, `Lob`, and `Candle`
+///   batches addressed to them; their connect, message, and shutdown commands
+///   are acknowledged without network IO;
+/// - `TimeScheduler` tasks tick immediately at the first record and then
+///   every interval on the simulated clock, while records remain;
+/// - `OrderExecution` tasks route `OrderExecute` into the matching engine;
+///   `LobExecution` and `OrderEntry` websocket tasks do the same, also serve
+///   `OrderCancel`, and publish simulated `OrderAck`s;
+/// - `InstIntent` tasks publish intents as they do live;
+/// - `AccountOrders`, `AccountFills`, and `AccountPositions` tasks publish
+///   simulated updates for their market.
+///
+/// Model and `CancelAfter` tasks are not simulated. Strategies should read time through
+/// [`CommandEmitter::now_micros`] so it follows the simulated clock.
+///
+/// [`EnvMediator::into_backtest`]: crate::arch::infra_core::env_mediator::EnvMediator::into_backtest
+/// [`CommandEmitter::now_micros`]: crate::arch::traits::strategy::CommandEmitter::now_micros
+pub struct BacktestEnv<S> {
+    strategy: S,
+    tasks: Vec<TaskInfo>,
+    config: BacktestConfig,
+    risk_gate: Option<RiskGate>,
+}
+
+impl<S> BacktestEnv<S>
+where
+    S: Strategy,
+{
+    pub(crate) fn new(strategy: S, tasks: Vec<TaskInfo>) -> Self {
+        Self {
+            strategy,
+            tasks,
+            config: BacktestConfig::default(),
+            risk_gate: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: BacktestConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Runs every `LobExecution` order through `gate` at its simulated
+    /// arrival time. Rejected orders never reach the matching engine and are
+    /// acknowledged with [`AltOrderError::Risk`].
+    pub fn with_risk_gate(mut self, gate: RiskGate) -> Self {
+        self.risk_gate = Some(gate);
+        self
+    }
+
+    /// Replays `records` through the registered strategy modules.
+    ///
+    /// Records must be ordered by timestamp. The run initializes every module,
+    /// dispatches all records and the events they cause, runs every
+    /// `Strategy::on_shutdown` hook, and returns the simulated account state.
+    ///
+    /// Returns an error when a record goes back in time or addresses a task
+    /// that was not registered.
+    pub async fn run<I>(mut self, records: I) -> InfraResult<BacktestReport>
+    where
+        I: IntoIterator<Item = BacktestRecord>,
+    {
+        let mut feed = records.into_iter().peekable();
+        let start = feed.peek().map(|record| record.timestamp).unwrap_or(0);
+        let clock = RuntimeClock::simulated(start);
+
+        self.strategy.initialize().await;
+
+        let (handles, mut inbox) = CommandInbox::new(&self.tasks);
+        let registry = CommandRegistry::new(handles).with_clock(clock.clone());
+        self.strategy.command_init(Arc::new(registry));
+
+        let mut replay = Replay::new(&self.tasks, &self.config, clock, start);
+        replay.risk_gate = self.risk_gate.take();
+        if feed.peek().is_some() {
+            replay.push_startup_events();
+        }
+        if let Some(record) = feed.next() {
+            replay.push_record(record)?;
+        }
+
+        while let Some(item) = replay.pop() {
+            match item {
+                Scheduled::Record(record) => {
+                    if let Some(next) = feed.next() {
+                        replay.push_record(next)?;
+                    }
+                    replay.report.records += 1;
+                    let event = replay.record_event(record)?;
+                    self.dispatch(&mut replay, &mut inbox, event).await;
+                },
+                Scheduled::Event(event) => self.dispatch(&mut replay, &mut inbox, event).await,
+                Scheduled::Tick { key, duration } => {
+                    let event = replay.tick(key, duration);
+                    self.dispatch(&mut replay, &mut inbox, event).await;
+                },
+                Scheduled::Orders { orders, ack_key } => replay.submit(&orders, ack_key),
+                Scheduled::Cancels { cancels, ack_key } => replay.cancel(&cancels, ack_key),
+            }
+        }
+
+        self.strategy.on_shutdown().await;
+
+        let mut report = replay.report;
+        report.end_timestamp = replay.clock.now_micros();
+        report.positions = replay.matcher.positions().to_vec();
+        info!(
+            "Backtest finished: {} records, {} events, {} fills",
+            report.records,
+            report.events,
+            report.fills.len()
+        );
+        Ok(report)
+    }
+
+    /// Runs one event through the strategy list while serving commands sent
+    /// from its callbacks, so callbacks that wait for acks make progress.
+    async fn dispatch(
+        &mut self,
+        replay: &mut Replay,
+        inbox: &mut CommandInbox,
+        event: RoutedEvent,
+    ) {
+        replay.report.events += 1;
+        let mut callback = pin!(self.strategy._dispatch_event(event));
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = &mut callback => break,
+                (key, cmd) = inbox.recv() => replay.handle_command(key, cmd),
+            }
+        }
+
+        while let Some((key, cmd)) = inbox.try_recv() {
+            replay.handle_command(key, cmd);
+        }
+    }
+}
+
+/// Command receivers for every declared task, polled in declaration order.
+struct CommandInbox {
+    receivers: Vec<(TaskKey, mpsc::Receiver<TaskCommand>)>,
+}
+
+impl CommandInbox {
+    fn new(tasks: &[TaskInfo]) -> (Vec<Arc<CommandHandle>>, Self) {
+        let mut handles = Vec::new();
+        let mut receivers = Vec::new();
+
+        for task in tasks {
+            let task_ids = task
+                .task_ids()
+                .expect("EnvBuilder validated every task-id range");
+            for task_id in task_ids {
+                let (cmd_tx, cmd_rx) = mpsc::channel::<TaskCommand>(2048);
+                let handle = Arc::new(CommandHandle {
+                    cmd_tx,
+                    task_info: task.clone(),
+                    task_id,
+                });
+                receivers.push((handle.task_info.task_key(task_id), cmd_rx));
+                handles.push(handle);
+            }
+        }
+
+        (handles, Self { receivers })
+    }
+
+    /// Waits for the next command, preferring earlier declared tasks.
+    async fn recv(&mut self) -> (TaskKey, TaskCommand) {
+        poll_fn(|cx| {
+            for (key, receiver) in &mut self.receivers {
+                if let Poll::Ready(Some(cmd)) = receiver.poll_recv(cx) {
+                    return Poll::Ready((key.clone(), cmd));
+                }
+            }
+            Poll::Pending
+        })
+        .await
+    }
+
+    fn try_recv(&mut self) -> Option<(TaskKey, TaskCommand)> {
+        self.receivers
+            .iter_mut()
+            .find_map(|(key, receiver)| receiver.try_recv().ok().map(|cmd| (key.clone(), cmd)))
+    }
+}
+
+enum Scheduled {
+    Record(BacktestRecord),
+    Event(RoutedEvent),
+    Tick {
+        key: TaskKey,
+        duration: Duration,
+    },
+    /// Orders reaching the venue. Acks go to `ack_key` when set.
+    Orders {
+        orders: Vec<AltOrder>,
+        ack_key: Option<TaskKey>,
+    },
+    Cancels {
+        cancels: Vec<AltCancel>,
+        ack_key: TaskKey,
+    },
+}
+
+/// Simulation state shared by the replay loop and the command handler.
+struct Replay {
+    clock: RuntimeClock,
+    queue: BinaryHeap<Reverse<(u64, u64)>>,
+    items: HashMap<u64, Scheduled>,
+    next_seq: u64,
+    ticks_pending: usize,
+    last_record_at: u64,
+    order_latency: u64,
+    ws_tasks: Vec<(TaskKey, Arc<WsTaskInfo>)>,
+    alt_tasks: Vec<(TaskKey, TaskInfo)>,
+    matcher: SimMatcher,
+    risk_gate: Option<RiskGate>,
+    report: BacktestReport,
+}
+
+impl Replay {
+    fn new(tasks: &[TaskInfo], config: &BacktestConfig, clock: RuntimeClock, start: u64) -> Self {
+        let mut ws_tasks = Vec::new();
+        let mut alt_tasks = Vec::new();
+        for task in tasks {
+            let task_ids = task
+                .task_ids()
+                .expect("EnvBuilder validated every task-id range");
+            for task_id in task_ids {
+                let key = task.task_key(task_id);
+                match task {
+                    TaskInfo::WsTask(ws) => ws_tasks.push((key, ws.clone())),
+                    TaskInfo::AltTask(_) => alt_tasks.push((key, task.clone())),
+                }
+            }
+        }
+
+        Self {
+            clock,
+            queue: BinaryHeap::new(),
+            items: HashMap::new(),
+            next_seq: 0,
+            ticks_pending: 0,
+            last_record_at: start,
+            order_latency: config.order_latency.as_micros() as u64,
+            ws_tasks,
+            alt_tasks,
+            matcher: SimMatcher::new(config),
+            risk_gate: None,
+            report: BacktestReport {
+                start_timestamp: start,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn now(&self) -> u64 {
+        self.clock.now_micros()
+    }
+
+    fn push(&mut self, timestamp: u64, item: Scheduled) {
+        if matches!(item, Scheduled::Tick { .. }) {
+            self.ticks_pending += 1;
+        }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.queue.push(Reverse((timestamp, seq)));
+        self.items.insert(seq, item);
+    }
+
+    /// Pops the next item and advances the clock to it. Stops once only
+    /// scheduler ticks remain.
+    fn pop(&mut self) -> Option<Scheduled> {
+        if self.queue.len() == self.ticks_pending {
+            return None;
+        }
+
+        let Reverse((timestamp, seq)) = self.queue.pop()?;
+        let item = self.items.remove(&seq)?;
+        if matches!(item, Scheduled::Tick { .. }) {
+            self.ticks_pending -= 1;
+        }
+        self.clock.advance_to(timestamp);
+        Some(item)
+    }
+
+    fn push_event(&mut self, key: TaskKey, event: TaskEvent) {
+        self.push(self.now(), Scheduled::Event(RoutedEvent { key, event }));
+    }
+
+    fn push_record(&mut self, record: BacktestRecord) -> InfraResult<()> {
+        if record.timestamp < self.last_record_at {
+            return Err(InfraError::Msg(format!(
+                "backtest records out of order: {} after {}",
+                record.timestamp, self.last_record_at
+            )));
+        }
+        self.last_record_at = record.timestamp;
+        self.push(record.timestamp, Scheduled::Record(record));
+        Ok(())
+    }
+
+    /// Queues the lifecycle events live relays emit on startup and the first
+    /// scheduler ticks.
+    fn push_startup_events(&mut self) {
+        let now = self.now();
+        for (key, task) in self.alt_tasks.clone() {
+            let (TaskKey::Alt { task_id, .. }, TaskInfo::AltTask(alt_info)) = (&key, &task) else {
+                continue;
+            };
+            let event = TaskEvent::Alt(InfraMsg {
+                task_id: *task_id,
+                received_at: now,
+                data: alt_info.clone(),
+            });
+            self.push_event(key.clone(), event);
+            if let AltTaskType::TimeScheduler(duration) = &alt_info.alt_task_type {
+                self.push(
+                    now,
+                    Scheduled::Tick {
+                        key,
+                        duration: *duration,
+                    },
+                );
+            }
+        }
+
+        for (key, ws_info) in self.ws_tasks.clone() {
+            let event = TaskEvent::Ws(InfraMsg {
+                task_id: task_id_of(&key),
+                received_at: now,
+                data: ws_info,
+            });
+            self.push_event(key, event);
+        }
+    }
+
+    fn record_event(&mut self, record: BacktestRecord) -> InfraResult<RoutedEvent> {
+        let channel = record.data.channel();
+        let key = self
+            .ws_tasks
+            .iter()
+            .map(|(key, _)| key)
+            .find(|key| match key {
+                TaskKey::Ws {
+                    ws_channel,
+                    task_id,
+                } => discriminant(ws_channel) == channel && *task_id == record.task_id,
+                TaskKey::Alt { .. } => false,
+            })
+            .cloned()
+            .ok_or_else(|| {
+                InfraError::Msg(format!(
+                    "backtest record addresses an unregistered task: {} with id {}",
+                    record.data.name(),
+                    record.task_id
+                ))
+            })?;
+
+        let now = self.now();
+        let output = match &record.data {
+            BacktestData::Trade(trades) => self.matcher.on_trades(now, trades),
+            BacktestData::Lob(lobs) => self.matcher.on_lobs(now, lobs),
+            BacktestData::Candle(candles) => self.matcher.on_candles(now, candles),
+        };
+        self.publish(output);
+
+        Ok(RoutedEvent {
+            key,
+            event: record.data.into_event(record.task_id, now),
+        })
+    }
+
+    fn tick(&mut self, key: TaskKey, duration: Duration) -> RoutedEvent {
+        let now = self.now();
+        let next = now.saturating_add(duration.as_micros() as u64);
+        if !duration.is_zero() {
+            self.push(
+                next,
+                Scheduled::Tick {
+                    key: key.clone(),
+                    duration,
+                },
+            );
+        }
+
+        RoutedEvent {
+            event: TaskEvent::Schedule(InfraMsg {
+                task_id: task_id_of(&key),
+                received_at: now,
+                data: Arc::new(AltScheduleEvent {
+                    timestamp: now,
+                    duration,
+                }),
+            }),
+            key,
+        }
+    }
+
+    fn submit(&mut self, orders: &[AltOrder], ack_key: Option<TaskKey>) {
+        let now = self.now();
+        let mut acks = Vec::new();
+        for order in orders {
+            let approval = match (&self.risk_gate, &ack_key) {
+                (Some(gate), Some(_)) => gate.approve(&order.market, &order.order_params, now),
+                _ => Ok(()),
+            };
+            if let Err(rejection) = approval {
+                acks.push(AltOrderAck {
+                    timestamp: now,
+                    market: order.market.clone(),
+                    request: AltOrderRequest::Place(order.order_params.clone()),
+                    metadata: order.metadata.clone(),
+                    result: Err(AltOrderError::Risk(rejection)),
+                });
+                continue;
+            }
+
+            let output = self.matcher.submit(now, order);
+            let result = output
+                .orders
+                .first()
+                .map(|update| sim_ack(now, update))
+                .ok_or_else(|| AltOrderError::Request("Order was not accepted".into()));
+            acks.push(AltOrderAck {
+                timestamp: now,
+                market: order.market.clone(),
+                request: AltOrderRequest::Place(order.order_params.clone()),
+                metadata: order.metadata.clone(),
+                result,
+            });
+            self.publish(output);
+        }
+        if let Some(key) = ack_key {
+            self.push_acks(key, acks);
+        }
+    }
+
+    fn cancel(&mut self, cancels: &[AltCancel], ack_key: TaskKey) {
+        let now = self.now();
+        let mut acks = Vec::new();
+        for cancel in cancels {
+            let output = self
+                .matcher
+                .cancel(now, &cancel.market, &cancel.cancel_params);
+            let result = output
+                .orders
+                .first()
+                .map(|update| sim_ack(now, update))
+                .ok_or_else(|| AltOrderError::Request("Order not found".into()));
+            acks.push(AltOrderAck {
+                timestamp: now,
+                market: cancel.market.clone(),
+                request: AltOrderRequest::Cancel(cancel.cancel_params.clone()),
+                metadata: cancel.metadata.clone(),
+                result,
+            });
+            self.publish(output);
+        }
+        self.push_acks(ack_key, acks);
+    }
+
+    fn push_acks(&mut self, key: TaskKey, acks: Vec<AltOrderAck>) {
+        let now = self.now();
+        let event = TaskEvent::OrderAck(InfraMsg {
+            task_id: task_id_of(&key),
+            received_at: now,
+            data: Arc::new(acks),
+        });
+        self.push_event(key, event);
+    }
+
+    /// Queues simulated account updates on the account tasks of each market.
+    fn publish(&mut self, output: SimOutput) {
+        let SimOutput {
+            orders,
+            positions,
+            fills,
+        } = output;
+        let now = self.now();
+        for (key, ws_info) in self.ws_tasks.clone() {
+            let task_id = task_id_of(&key);
+            let event = match ws_info.ws_channel {
+                WsChannel::AccountOrders => {
+                    let data: Vec<WsAccOrder> = orders
+                        .iter()
+                        .filter(|order| order.market == ws_info.market)
+                        .cloned()
+                        .collect();
+                    (!data.is_empty()).then(|| {
+                        TaskEvent::AccOrder(InfraMsg {
+                            task_id,
+                            received_at: now,
+                            data: Arc::new(data),
+                        })
+                    })
+                },
+                WsChannel::AccountFills => {
+                    let data: Vec<WsFill> = fills
+                        .iter()
+                        .filter(|fill| fill.market == ws_info.market)
+                        .map(ws_fill)
+                        .collect();
+                    (!data.is_empty()).then(|| {
+                        TaskEvent::AccFill(InfraMsg {
+                            task_id,
+                            received_at: now,
+                            data: Arc::new(data),
+                        })
+                    })
+                },
+                WsChannel::AccountPositions => {
+                    let data: Vec<WsAccPosition> = positions
+                        .iter()
+                        .filter(|(market, _)| *market == ws_info.market)
+                        .map(|(_, position)| position.clone())
+                        .collect();
+                    (!data.is_empty()).then(|| {
+                        TaskEvent::AccPos(InfraMsg {
+                            task_id,
+                            received_at: now,
+                            data: Arc::new(data),
+                        })
+                    })
+                },
+                _ => None,
+            };
+
+            if let Some(event) = event {
+                self.push_event(key, event);
+            }
+        }
+        self.report.fills.extend(fills);
+    }
+
+    fn handle_command(&mut self, key: TaskKey, cmd: TaskCommand) {
+        let is_alt = |task_type: AltTaskType| matches!(&key, TaskKey::Alt { alt_task_type, .. } if *alt_task_type == task_type);
+        #[cfg(feature = "lob_clients")]
+        let acks = is_alt(AltTaskType::LobExecution)
+            || matches!(
+                &key,
+                TaskKey::Ws {
+                    ws_channel: WsChannel::OrderEntry,
+                    ..
+                }
+            );
+        #[cfg(not(feature = "lob_clients"))]
+        let acks = false;
+        let arrival = self.now().saturating_add(self.order_latency);
+
+        match cmd {
+            TaskCommand::OrderExecute(orders) if acks || is_alt(AltTaskType::OrderExecution) => {
+                self.report.orders += orders.len() as u64;
+                let ack_key = acks.then(|| key.clone());
+                self.push(arrival, Scheduled::Orders { orders, ack_key });
+            },
+            TaskCommand::OrderCancel(cancels) if acks => {
+                self.push(
+                    arrival,
+                    Scheduled::Cancels {
+                        cancels,
+                        ack_key: key,
+                    },
+                );
+            },
+            TaskCommand::InstIntent(intent) if is_alt(AltTaskType::InstIntent) => {
+                let event = TaskEvent::InstIntent(InfraMsg {
+                    task_id: task_id_of(&key),
+                    received_at: self.now(),
+                    data: Arc::new(intent),
+                });
+                self.push_event(key, event);
+            },
+            TaskCommand::WsConnect { ack, .. } | TaskCommand::WsConnectWithTarget { ack, .. } => {
+                ack.respond(AckStatus::WsConnect)
+            },
+            TaskCommand::WsMessage { ack, reply, .. }
+            | TaskCommand::WsBuiltMessage { ack, reply, .. } => match reply {
+                Some(_) => ack.respond(AckStatus::WsAccepted(String::new())),
+                None => ack.respond(AckStatus::WsMessage),
+            },
+            TaskCommand::Subscribe { ack, .. } | TaskCommand::Unsubscribe { ack, .. } => {
+                ack.respond(AckStatus::WsMessage)
+            },
+            TaskCommand::WsShutdown { ack, .. } => ack.respond(AckStatus::WsShutdown),
+            cmd => {
+                warn!("Backtest ignored command for {:?}: {:?}", key, cmd);
+                if let Some(ack) = cmd.get_ack() {
+                    ack.respond(AckStatus::AltTask);
+                }
+            },
+        }
+    }
+}
+
+/// Acknowledgement a venue would return for the first update of an order.
+fn sim_ack(now: u64, update: &WsAccOrder) -> OrderAckData {
+    OrderAckData {
+        timestamp: now,
+        order_status: update.status.clone(),
+        order_id: update.order_id.clone().unwrap_or_default(),
+        cli_order_id: update.cli_order_id.clone(),
+        msg: (update.status == OrderStatus::Rejected)
+            .then(|| "Rejected by the backtest matcher".to_string()),
+    }
+}
+
+fn ws_fill(fill: &BacktestFill) -> WsFill {
+    WsFill {
+        timestamp: fill.timestamp,
+        market: fill.market.clone(),
+        inst: fill.inst.clone(),
+        inst_type: fill.inst_type.clone(),
+        side: fill.side.clone(),
+        price: fill.price,
+        size: fill.size,
+        fee: fill.fee,
+        fee_currency: None,
+        liquidity: FillLiquidity::from_maker(Some(fill.maker)),
+        trade_id: None,
+        order_id: fill.order_id.clone(),
+        cli_order_id: fill.cli_order_id.clone(),
+    }
+}
+
+fn task_id_of(key: &TaskKey) -> u64 {
+    match key {
+        TaskKey::Alt { task_id, .. } | TaskKey::Ws { task_id, .. } => *task_id,
+    }
+}
@@ -0,0 +1,547 @@
+use std::collections::HashMap;
+
+use crate::arch::{
+    market_assets::{
+        api_general::CancelOrderParams,
+        base_data::{
+            InstrumentType, MarginMode, OrderSide, OrderStatus, OrderType, PositionSide,
+            TimeInForce,
+        },
+        market_core::Market,
+        order_book::LocalOrderBook,
+    },
+    strategy_base::handler::{
+        alt_events::AltOrder,
+        lob_events::{LobEventKind, LobLevel, WsAccOrder, WsAccPosition, WsCandle, WsLob, WsTrade},
+    },
+};
+
+use super::{BacktestConfig, BacktestFill, BacktestPosition};
+
+type InstKey = (Market, String);
+
+/// Updates produced by one matcher step, in the order they happened.
+#[derive(Debug, Default)]
+pub(crate) struct SimOutput {
+    pub orders: Vec<WsAccOrder>,
+    pub positions: Vec<(Market, WsAccPosition)>,
+    pub fills: Vec<BacktestFill>,
+}
+
+/// Top-of-book matching engine for replayed market data.
+///
+/// Marketable orders fill in full at the opposite best price, or at the last
+/// trade or candle close when no quote has been seen. Resting limit orders
+/// fill in full at their limit price once the opposite quote reaches it or a
+/// trade or candle prints through it. Queue position and book depth are not
+/// modelled.
+pub(crate) struct SimMatcher {
+    maker_fee_rate: f64,
+    taker_fee_rate: f64,
+    markets: HashMap<InstKey, MarketState>,
+    resting: Vec<RestingOrder>,
+    positions: Vec<BacktestPosition>,
+    margin_modes: HashMap<InstKey, MarginMode>,
+    next_order_id: u64,
+}
+
+struct MarketState {
+    book: LocalOrderBook,
+    bid: Option<f64>,
+    ask: Option<f64>,
+    last: Option<f64>,
+}
+
+impl MarketState {
+    fn new(market: &Market, inst: &str) -> Self {
+        Self {
+            book: LocalOrderBook::new(market.clone(), inst).with_checksum_verification(false),
+            bid: None,
+            ask: None,
+            last: None,
+        }
+    }
+
+    /// Price a taker on `side` would trade at right now.
+    fn taker_price(&self, side: &OrderSide) -> Option<f64> {
+        match side {
+            OrderSide::BUY => self.ask.or(self.last),
+            OrderSide::SELL => self.bid.or(self.last),
+            OrderSide::Unknown => None,
+        }
+    }
+}
+
+struct RestingOrder {
+    order: WsAccOrder,
+    price: f64,
+}
+
+impl SimMatcher {
+    pub(crate) fn new(config: &BacktestConfig) -> Self {
+        Self {
+            maker_fee_rate: config.maker_fee_rate,
+            taker_fee_rate: config.taker_fee_rate,
+            markets: HashMap::new(),
+            resting: Vec::new(),
+            positions: Vec::new(),
+            margin_modes: HashMap::new(),
+            next_order_id: 1,
+        }
+    }
+
+    pub(crate) fn positions(&self) -> &[BacktestPosition] {
+        &self.positions
+    }
+
+    /// Resting orders, oldest first.
+    #[cfg(feature = "mock_venue")]
+    pub(crate) fn open_orders(&self) -> impl Iterator<Item = &WsAccOrder> {
+        self.resting.iter().map(|resting| &resting.order)
+    }
+
+    pub(crate) fn on_trades(&mut self, now: u64, trades: &[WsTrade]) -> SimOutput {
+        let mut output = SimOutput::default();
+        for trade in trades {
+            self.state(&trade.market, &trade.inst).last = Some(trade.price);
+            self.match_resting(
+                now,
+                &trade.market,
+                &trade.inst,
+                Some((trade.price, trade.price)),
+                &mut output,
+            );
+        }
+        output
+    }
+
+    pub(crate) fn on_candles(&mut self, now: u64, candles: &[WsCandle]) -> SimOutput {
+        let mut output = SimOutput::default();
+        for candle in candles {
+            self.state(&candle.market, &candle.inst).last = Some(candle.close);
+            self.match_resting(
+                now,
+                &candle.market,
+                &candle.inst,
+                Some((candle.low, candle.high)),
+                &mut output,
+            );
+        }
+        output
+    }
+
+    pub(crate) fn on_lobs(&mut self, now: u64, lobs: &[WsLob]) -> SimOutput {
+        let mut output = SimOutput::default();
+        for lob in lobs {
+            let state = self.state(&lob.market, &lob.inst);
+            match lob.event {
+                LobEventKind::Bbo => {
+                    state.bid = best_price(&lob.bids, f64::max).or(state.bid);
+                    state.ask = best_price(&lob.asks, f64::min).or(state.ask);
+                },
+                LobEventKind::Snapshot | LobEventKind::Incremental => {
+                    state.book.apply(lob);
+                    if state.book.is_synced() {
+                        state.bid = state.book.best_bid().map(|(px, _)| px);
+                        state.ask = state.book.best_ask().map(|(px, _)| px);
+                    }
+                },
+                LobEventKind::Heartbeat => continue,
+            }
+            self.match_resting(now, &lob.market, &lob.inst, None, &mut output);
+        }
+        output
+    }
+
+    /// Accepts one order at the simulated venue.
+    pub(crate) fn submit(&mut self, now: u64, alt_order: &AltOrder) -> SimOutput {
+        let mut output = SimOutput::default();
+        let params = &alt_order.order_params;
+        let order_id = format!("bt-{}", self.next_order_id);
+        self.next_order_id += 1;
+
+        let mut order = WsAccOrder {
+            timestamp: now,
+            market: alt_order.market.clone(),
+            inst: params.inst.clone(),
+            inst_type: InstrumentType::Unknown,
+            price: 0.0,
+            size: params.size.parse().unwrap_or(0.0),
+            filled_size: 0.0,
+            side: params.side.clone(),
+            status: OrderStatus::Rejected,
+            order_type: params.order_type.clone(),
+            order_id: Some(order_id),
+            cli_order_id: params.client_order_id.clone(),
+        };
+
+        let limit = params
+            .price
+            .as_deref()
+            .and_then(|px| px.parse::<f64>().ok());
+        let needs_limit = !matches!(params.order_type, OrderType::Market);
+        if params.validate_side_and_type().is_err()
+            || order.size <= 0.0
+            || (needs_limit && limit.is_none())
+        {
+            output.orders.push(order);
+            return output;
+        }
+        if let Some(margin_mode) = &params.margin_mode {
+            self.margin_modes.insert(
+                (order.market.clone(), order.inst.clone()),
+                margin_mode.clone(),
+            );
+        }
+
+        let taker_price = self
+            .state(&order.market, &order.inst)
+            .taker_price(&order.side);
+        let marketable = match (limit, taker_price) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(limit), Some(px)) => match order.side {
+                OrderSide::BUY => px <= limit,
+                _ => px >= limit,
+            },
+        };
+        let immediate_only = matches!(params.order_type, OrderType::Ioc | OrderType::Fok)
+            || matches!(
+                params.time_in_force,
+                Some(TimeInForce::IOC) | Some(TimeInForce::FOK)
+            );
+
+        match (marketable, &params.order_type) {
+            (true, OrderType::PostOnly) => {
+                order.price = limit.unwrap_or_default();
+                output.orders.push(order);
+            },
+            (true, _) => {
+                let px = taker_price.expect("marketable orders have a taker price");
+                self.fill(now, order, px, false, &mut output);
+            },
+            (false, OrderType::Market) => output.orders.push(order),
+            (false, _) if immediate_only => {
+                order.price = limit.unwrap_or_default();
+                order.status = OrderStatus::Canceled;
+                output.orders.push(order);
+            },
+            (false, _) => {
+                let price = limit.unwrap_or_default();
+                order.price = price;
+                order.status = OrderStatus::Live;
+                output.orders.push(order.clone());
+                self.resting.push(RestingOrder { order, price });
+            },
+        }
+
+        output
+    }
+
+    /// Cancels a resting order by exchange or client order id.
+    ///
+    /// Produces no update when the order is not resting, for example because
+    /// it already filled.
+    pub(crate) fn cancel(
+        &mut self,
+        now: u64,
+        market: &Market,
+        params: &CancelOrderParams,
+    ) -> SimOutput {
+        let mut output = SimOutput::default();
+        let position = self.resting.iter().position(|resting| {
+            let order = &resting.order;
+            order.market == *market
+                && order.inst == params.inst
+                && ((params.order_id.is_some() && order.order_id == params.order_id)
+                    || (params.cli_order_id.is_some() && order.cli_order_id == params.cli_order_id))
+        });
+        if let Some(position) = position {
+            let mut order = self.resting.remove(position).order;
+            order.timestamp = now;
+            order.status = OrderStatus::Canceled;
+            output.orders.push(order);
+        }
+        output
+    }
+
+    fn state(&mut self, market: &Market, inst: &str) -> &mut MarketState {
+        self.markets
+            .entry((market.clone(), inst.to_string()))
+            .or_insert_with(|| MarketState::new(market, inst))
+    }
+
+    /// Fills resting orders crossed by the current quote or by a print range.
+    fn match_resting(
+        &mut self,
+        now: u64,
+        market: &Market,
+        inst: &str,
+        printed: Option<(f64, f64)>,
+        output: &mut SimOutput,
+    ) {
+        let state = self.state(market, inst);
+        let (bid, ask) = (state.bid, state.ask);
+
+        let mut filled = Vec::new();
+        self.resting.retain(|resting| {
+            if resting.order.market != *market || resting.order.inst != inst {
+                return true;
+            }
+
+            let crossed = match resting.order.side {
+                OrderSide::BUY => {
+                    ask.is_some_and(|ask| ask <= resting.price)
+                        || printed.is_some_and(|(low, _)| low < resting.price)
+                },
+                _ => {
+                    bid.is_some_and(|bid| bid >= resting.price)
+                        || printed.is_some_and(|(_, high)| high > resting.price)
+                },
+            };
+            if crossed {
+                filled.push((resting.order.clone(), resting.price));
+            }
+            !crossed
+        });
+
+        for (order, price) in filled {
+            self.fill(now, order, price, true, output);
+        }
+    }
+
+    fn fill(
+        &mut self,
+        now: u64,
+        mut order: WsAccOrder,
+        price: f64,
+        maker: bool,
+        output: &mut SimOutput,
+    ) {
+        let fee_rate = if maker {
+            self.maker_fee_rate
+        } else {
+            self.taker_fee_rate
+        };
+        let fee = price * order.size * fee_rate;
+
+        order.timestamp = now;
+        order.price = price;
+        order.filled_size = order.size;
+        order.status = OrderStatus::Filled;
+
+        output.fills.push(BacktestFill {
+            timestamp: now,
+            market: order.market.clone(),
+            inst: order.inst.clone(),
+            inst_type: order.inst_type.clone(),
+            side: order.side.clone(),
+            price,
+            size: order.size,
+            fee,
+            maker,
+            order_id: order.order_id.clone(),
+            cli_order_id: order.cli_order_id.clone(),
+        });
+
+        let signed = match order.side {
+            OrderSide::BUY => order.size,
+            _ => -order.size,
+        };
+        let margin_mode = self
+            .margin_modes
+            .get(&(order.market.clone(), order.inst.clone()))
+            .cloned()
+            .unwrap_or_default();
+        let position = self.position_mut(&order.market, &order.inst);
+        position.apply_fill(signed, price, fee);
+
+        let position = WsAccPosition {
+            inst: position.inst.clone(),
+            inst_type: InstrumentType::Unknown,
+            avg_price: position.avg_price,
+            size: position.size,
+            position_side: PositionSide::Both,
+            margin_mode,
+        };
+        output.positions.push((order.market.clone(), position));
+        output.orders.push(order);
+    }
+
+    fn position_mut(&mut self, market: &Market, inst: &str) -> &mut BacktestPosition {
+        let index = match self
+            .positions
+            .iter()
+            .position(|p| p.market == *market && p.inst == inst)
+        {
+            Some(index) => index,
+            None => {
+                self.positions.push(BacktestPosition {
+                    market: market.clone(),
+                    inst: inst.to_string(),
+                    ..Default::default()
+                });
+                self.positions.len() - 1
+            },
+        };
+        &mut self.positions[index]
+    }
+}
+
+impl BacktestPosition {
+    fn apply_fill(&mut self, signed_size: f64, price: f64, fee: f64) {
+        self.fees += fee;
+        let same_direction = self.size == 0.0 || self.size.signum() == signed_size.signum();
+
+        if same_direction {
+            let total = self.size.abs() + signed_size.abs();
+            self.avg_price = (self.avg_price * self.size.abs() + price * signed_size.abs()) / total;
+            self.size += signed_size;
+            return;
+        }
+
+        let closed = signed_size.abs().min(self.size.abs());
+        self.realized_pnl += closed * (price - self.avg_price) * self.size.signum();
+        self.size += signed_size;
+
+        if self.size.abs() <= f64::EPSILON {
+            self.size = 0.0;
+            self.avg_price = 0.0;
+        } else if self.size.signum() == signed_size.signum() {
+            self.avg_price = price;
+        }
+    }
+}
+
+fn best_price(levels: &[LobLevel], pick: fn(f64, f64) -> f64) -> Option<f64> {
+    levels
+        .iter()
+        .filter(|level| level.size > 0.0)
+        .map(|level| level.price)
+        .reduce(pick)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::arch::{
+        market_assets::api_general::OrderParams, strategy_base::handler::lob_events::LobLevelAction,
+    };
+
+    fn order(side: OrderSide, order_type: OrderType, size: &str, price: Option<&str>) -> AltOrder {
+        AltOrder {
+            timestamp: 0,
+            market: Market::Okx,
+            order_params: OrderParams {
+                inst: "BTC-USDT-SWAP".into(),
+                side,
+                size: size.into(),
+                order_type,
+                price: price.map(Into::into),
+                ..Default::default()
+            },
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn bbo(bid: f64, ask: f64) -> WsLob {
+        let level = |price| LobLevel {
+            price,
+            size: 1.0,
+            action: LobLevelAction::Upsert,
+            order_count: None,
+            level_update_id: None,
+        };
+        WsLob {
+            timestamp: 0,
+            market: Market::Okx,
+            inst: "BTC-USDT-SWAP".into(),
+            event: LobEventKind::Bbo,
+            bids: vec![level(bid)],
+            asks: vec![level(ask)],
+            seq: None,
+            checksum: None,
+        }
+    }
+
+    fn matcher() -> SimMatcher {
+        SimMatcher::new(&BacktestConfig {
+            taker_fee_rate: 0.001,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn market_order_takes_opposite_quote() {
+        let mut matcher = matcher();
+        matcher.on_lobs(1, &[bbo(99.0, 101.0)]);
+
+        let output = matcher.submit(2, &order(OrderSide::BUY, OrderType::Market, "2", None));
+
+        assert_eq!(output.orders[0].status, OrderStatus::Filled);
+        assert_eq!(output.fills[0].price, 101.0);
+        assert!((output.fills[0].fee - 0.202).abs() < 1e-9);
+        assert_eq!(output.positions[0].1.size, 2.0);
+    }
+
+    #[test]
+    fn resting_limit_fills_when_quote_crosses() {
+        let mut matcher = matcher();
+        matcher.on_lobs(1, &[bbo(99.0, 101.0)]);
+
+        let placed = matcher.submit(
+            2,
+            &order(OrderSide::BUY, OrderType::Limit, "1", Some("100")),
+        );
+        assert_eq!(placed.orders[0].status, OrderStatus::Live);
+        assert!(matcher.on_lobs(3, &[bbo(99.5, 100.5)]).orders.is_empty());
+
+        let filled = matcher.on_lobs(4, &[bbo(99.0, 100.0)]);
+        assert_eq!(filled.orders[0].status, OrderStatus::Filled);
+        assert_eq!(filled.fills[0].price, 100.0);
+        assert!(filled.fills[0].maker);
+    }
+
+    #[test]
+    fn post_only_and_ioc_do_not_rest() {
+        let mut matcher = matcher();
+        matcher.on_lobs(1, &[bbo(99.0, 101.0)]);
+
+        let post = matcher.submit(
+            2,
+            &order(OrderSide::BUY, OrderType::PostOnly, "1", Some("102")),
+        );
+        let ioc = matcher.submit(2, &order(OrderSide::BUY, OrderType::Ioc, "1", Some("100")));
+
+        assert_eq!(post.orders[0].status, OrderStatus::Rejected);
+        assert_eq!(ioc.orders[0].status, OrderStatus::Canceled);
+        assert!(matcher.resting.is_empty());
+    }
+
+    #[test]
+    fn position_tracks_avg_price_and_realized_pnl() {
+        let mut matcher = SimMatcher::new(&BacktestConfig::default());
+        matcher.on_trades(
+            1,
+            &[WsTrade {
+                timestamp: 1,
+                market: Market::Okx,
+                inst: "BTC-USDT-SWAP".into(),
+                price: 100.0,
+                size: 1.0,
+                side: OrderSide::BUY,
+                trade_id: 1,
+            }],
+        );
+        matcher.submit(2, &order(OrderSide::BUY, OrderType::Market, "2", None));
+        matcher.state(&Market::Okx, "BTC-USDT-SWAP").last = Some(110.0);
+        matcher.submit(3, &order(OrderSide::SELL, OrderType::Market, "3", None));
+
+        let position = &matcher.positions()[0];
+        assert_eq!(position.size, -1.0);
+        assert_eq!(position.avg_price, 110.0);
+        assert_eq!(position.realized_pnl, 20.0);
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use crate::arch::market_assets::api_general::get_micros_timestamp;
+
+/// Source of the current time for strategy modules.
+///
+/// Live runtimes read the system clock. The backtest runtime hands out a
+/// simulated clock that only moves when the next replayed event is
+/// dispatched, so strategies that read time through
+/// [`CommandEmitter::now_micros`] behave identically on every replay.
+///
+/// [`CommandEmitter::now_micros`]: crate::arch::traits::strategy::CommandEmitter::now_micros
+#[derive(Clone, Debug, Default)]
+pub struct RuntimeClock {
+    simulated: Option<Arc<AtomicU64>>,
+}
+
+impl RuntimeClock {
+    /// Clock backed by the system time.
+    pub fn system() -> Self {
+        Self { simulated: None }
+    }
+
+    /// Clock that only moves through [`RuntimeClock::advance_to`].
+    pub(crate) fn simulated(start_micros: u64) -> Self {
+        Self {
+            simulated: Some(Arc::new(AtomicU64::new(start_micros))),
+        }
+    }
+
+    /// Current time in microseconds since the Unix epoch.
+    pub fn now_micros(&self) -> u64 {
+        match &self.simulated {
+            Some(now) => now.load(Ordering::Acquire),
+            None => get_micros_timestamp(),
+        }
+    }
+
+    /// Current time in milliseconds since the Unix epoch.
+    pub fn now_millis(&self) -> u64 {
+        self.now_micros() / 1_000
+    }
+
+    /// Returns `true` when time is driven by a backtest.
+    pub fn is_simulated(&self) -> bool {
+        self.simulated.is_some()
+    }
+
+    /// Moves a simulated clock forward. Earlier times and system clocks are
+    /// left unchanged.
+    pub(crate) fn advance_to(&self, micros: u64) {
+        if let Some(now) = &self.simulated {
+            now.fetch_max(micros, Ordering::AcqRel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_clock_only_moves_forward() {
+        let clock = RuntimeClock::simulated(1_000);
+        let shared = clock.clone();
+
+        clock.advance_to(5_000);
+        clock.advance_to(2_000);
+
+        assert!(shared.is_simulated());
+        assert_eq!(shared.now_micros(), 5_000);
+        assert_eq!(shared.now_millis(), 5);
+    }
+
+    #[test]
+    fn system_clock_ignores_advance() {
+        let clock = RuntimeClock::system();
+        clock.advance_to(1);
+
+        assert!(!clock.is_simulated());
+        assert!(clock.now_micros() > 1);
+    }
+}
@@ -2,7 +2,11 @@ use std::sync::Arc;
 use tracing::info;
 
 use crate::arch::{
-    infra_core::{env_core::EnvCore, env_mediator::EnvMediator},
+    infra_core::{
+        env_core::EnvCore,
+        env_mediator::{DEFAULT_SHUTDOWN_TIMEOUT, EnvMediator},
+        shutdown::ShutdownHandle,
+    },
     strategy_base::{
         handler::task_channel::TaskChannels,
         hlist_core::{HCons, HNil},
@@ -235,6 +239,12 @@ where
                 strategy: self.strategies,
             },
             tasks: self.tasks,
+            shutdown: ShutdownHandle::new(),
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            #[cfg(feature = "lob_clients")]
+            exec_clients: Default::default(),
+            #[cfg(feature = "lob_clients")]
+            risk_gate: None,
         })
     }
 }
@@ -265,6 +275,7 @@ mod task_channel_tests {
             filter_channels: false,
             chunk: 1,
             task_base_id: Some(task_id),
+            shards: None,
         }))
     }
 
@@ -298,6 +309,7 @@ mod task_channel_tests {
             filter_channels: false,
             chunk: 1,
             task_base_id: Some(12),
+            shards: None,
         };
         let scheduler_key = TaskKey::alt(&scheduler.alt_task_type, 11);
         let trades_key = TaskKey::ws(&trades.ws_channel, 12);
@@ -406,6 +418,7 @@ mod task_channel_tests {
             filter_channels: false,
             chunk: 2,
             task_base_id: Some(u64::MAX),
+            shards: None,
         }));
 
         let error = EnvBuilder::new()
@@ -1,9 +1,23 @@
-use futures::future::pending;
-use std::sync::Arc;
-use tokio::sync::mpsc;
+#[cfg(feature = "lob_clients")]
+use std::collections::HashMap;
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::mpsc,
+    task::JoinHandle,
+    time::{Instant, timeout_at},
+};
+use tracing::{info, warn};
 
+#[cfg(feature = "lob_clients")]
+use crate::arch::market_assets::{exchange::lob_clients::LobClients, market_core::Market};
+#[cfg(feature = "lob_clients")]
+use crate::arch::strategy_base::risk::RiskGate;
 use crate::arch::{
-    infra_core::env_core::EnvCore,
+    infra_core::{
+        backtest::BacktestEnv,
+        env_core::EnvCore,
+        shutdown::{ShutdownHandle, os_shutdown_signal},
+    },
     strategy_base::command::command_core::{CommandHandle, CommandRegistry, TaskCommand},
     task_execution::{
         TaskInfo, alt_runner::AltTaskRunner, task_alt::AltTaskInfo, task_ws::WsTaskInfo,
@@ -16,14 +30,23 @@ use crate::arch::{
 ///
 /// The mediator initializes strategies, registers tasks, provides the command
 /// registry to strategies, spawns strategy event loops, and then keeps the
-/// runtime alive.
+/// runtime alive until shutdown is requested through a [`ShutdownHandle`].
 ///
 /// [`EnvBuilder`]: crate::arch::infra_core::env_builder::EnvBuilder
 pub struct EnvMediator<S> {
     pub(crate) core: EnvCore<S>,
     pub(crate) tasks: Vec<TaskInfo>,
+    pub(crate) shutdown: ShutdownHandle,
+    pub(crate) shutdown_timeout: Duration,
+    #[cfg(feature = "lob_clients")]
+    pub(crate) exec_clients: Arc<HashMap<Market, LobClients>>,
+    #[cfg(feature = "lob_clients")]
+    pub(crate) risk_gate: Option<RiskGate>,
 }
 
+/// Default time budget for draining task relays and strategy event loops.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl<S> EnvMediator<S>
 where
     S: Strategy,
@@ -33,9 +56,81 @@ where
         &self.tasks
     }
 
-    /// Starts the environment and waits forever.
+    /// Returns a handle that stops [`EnvMediator::execute`] when triggered.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// Sets the time budget for draining tasks and strategy loops on shutdown.
+    ///
+    /// Tasks still running when the deadline passes are aborted. Defaults to
+    /// [`DEFAULT_SHUTDOWN_TIMEOUT`].
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Registers the client a `LobExecution` task uses for `market`.
+    ///
+    /// Every `LobExecution` task and `WsChannel::OrderEntry` websocket task
+    /// shares the registered clients. Orders for a market without a client are
+    /// acknowledged with an error. Call `init_api_key` on the client before
+    /// registering it.
+    #[cfg(feature = "lob_clients")]
+    pub fn with_execution_client(mut self, market: Market, client: LobClients) -> Self {
+        Arc::make_mut(&mut self.exec_clients).insert(market, client);
+        self
+    }
+
+    /// Runs every `LobExecution` and `OrderEntry` order through `gate` before
+    /// it is sent.
+    ///
+    /// Rejected orders are acknowledged with [`AltOrderError::Risk`]. Keep a
+    /// clone of the gate to feed it prices and positions or to trip its kill
+    /// switch. The gate also applies to backtests created by
+    /// [`EnvMediator::into_backtest`].
+    ///
+    /// [`AltOrderError::Risk`]: crate::arch::strategy_base::handler::alt_events::AltOrderError::Risk
+    #[cfg(feature = "lob_clients")]
+    pub fn with_risk_gate(mut self, gate: RiskGate) -> Self {
+        self.risk_gate = Some(gate);
+        self
+    }
+
+    /// Requests shutdown when the process receives Ctrl-C, or SIGTERM on Unix.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn with_shutdown_signal(self) -> Self {
+        let handle = self.shutdown.clone();
+        tokio::spawn(async move {
+            os_shutdown_signal().await;
+            info!("Received OS shutdown signal");
+            handle.shutdown();
+        });
+        self
+    }
+
+    /// Turns this environment into a deterministic backtest runtime.
+    ///
+    /// The registered strategy modules and task declarations are reused as is;
+    /// no task relay is spawned. See [`BacktestEnv`] for how each task type is
+    /// simulated.
+    pub fn into_backtest(self) -> BacktestEnv<S> {
+        let backtest = BacktestEnv::new(self.core.strategy, self.tasks);
+        #[cfg(feature = "lob_clients")]
+        let backtest = match self.risk_gate {
+            Some(gate) => backtest.with_risk_gate(gate),
+            None => backtest,
+        };
+        backtest
+    }
+
+    /// Starts the environment and runs until shutdown is requested.
     ///
-    /// This method is intended to be the last awaited call in a strategy binary.
+    /// On shutdown, task relays stop and close their websockets, every
+    /// [`Strategy::on_shutdown`] hook runs, and strategy event loops drain the
+    /// remaining events. This method is intended to be the last awaited call in
+    /// a strategy binary.
     pub async fn execute(mut self) {
         self.core.strategy.initialize().await;
 
@@ -46,16 +141,30 @@ where
             .strategy
             .command_init(Arc::clone(&command_registry));
 
-        self.core
+        let strategy_handles = self
+            .core
             .strategy
             ._spawn_strategy_tasks(&self.core.task_channels)
             .await;
 
-        for task in prepared_tasks {
-            task.spawn();
-        }
+        let task_handles: Vec<_> = prepared_tasks.into_iter().map(|t| t.spawn()).collect();
+
+        self.shutdown.signal().recv().await;
+        info!("Shutdown requested, stopping runtime");
+        let deadline = Instant::now() + self.shutdown_timeout;
 
-        pending::<()>().await;
+        join_until(task_handles, deadline, "task relay").await;
+
+        let EnvCore {
+            task_channels,
+            mut strategy,
+        } = self.core;
+        strategy.on_shutdown().await;
+
+        // Strategy event loops end once every event sender has been dropped.
+        drop(task_channels);
+        join_until(strategy_handles, deadline, "strategy event loop").await;
+        info!("Runtime stopped");
     }
 
     fn prepare_tasks(&self) -> (Vec<Arc<CommandHandle>>, Vec<PreparedTask>) {
@@ -105,9 +214,19 @@ where
                     event_tx,
                     ws_info: ws_task_info.clone(),
                     task_id,
+                    shutdown: self.shutdown.signal(),
+                    session: None,
+                    replies: Default::default(),
+                    subscriptions: Default::default(),
+                    listen_key: None,
+                    snapshot_source: None,
+                    #[cfg(feature = "lob_clients")]
+                    exec_clients: self.exec_clients.clone(),
+                    #[cfg(feature = "lob_clients")]
+                    risk_gate: self.risk_gate.clone(),
                 };
 
-                (handle, PreparedTask::Ws(ws_task))
+                (handle, PreparedTask::Ws(Box::new(ws_task)))
             })
             .collect()
     }
@@ -138,6 +257,11 @@ where
                     event_tx,
                     alt_info: alt_task_info.clone(),
                     task_id,
+                    shutdown: self.shutdown.signal(),
+                    #[cfg(feature = "lob_clients")]
+                    exec_clients: self.exec_clients.clone(),
+                    #[cfg(feature = "lob_clients")]
+                    risk_gate: self.risk_gate.clone(),
                 };
 
                 (handle, PreparedTask::Alt(alt_task))
@@ -147,19 +271,33 @@ where
 }
 
 enum PreparedTask {
-    Ws(WsTaskRunner),
+    Ws(Box<WsTaskRunner>),
     Alt(AltTaskRunner),
 }
 
 impl PreparedTask {
-    fn spawn(self) {
+    fn spawn(self) -> JoinHandle<()> {
         match self {
-            Self::Ws(mut task) => {
-                tokio::spawn(async move { task.ws_mid_relay().await });
-            },
-            Self::Alt(mut task) => {
-                tokio::spawn(async move { task.alt_mid_relay().await });
-            },
+            Self::Ws(mut task) => tokio::spawn(async move { task.ws_mid_relay().await }),
+            Self::Alt(mut task) => tokio::spawn(async move { task.alt_mid_relay().await }),
+        }
+    }
+}
+
+/// Awaits every handle until `deadline`, then aborts whatever is still running.
+async fn join_until(handles: Vec<JoinHandle<()>>, deadline: Instant, label: &str) {
+    let mut aborted = 0;
+    for mut handle in handles {
+        if timeout_at(deadline, &mut handle).await.is_err() {
+            handle.abort();
+            aborted += 1;
         }
     }
+
+    if aborted > 0 {
+        warn!(
+            "Aborted {} {} task(s) after shutdown deadline",
+            aborted, label
+        );
+    }
 }
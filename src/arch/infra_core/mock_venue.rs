@@ -0,0 +1,243 @@
+//! In-process exchange for integration tests.
+//!
+//! [`MockVenue`] listens on localhost and speaks the part of the OKX v5 REST
+//! and websocket protocol used by [`OkxCli`] and the OKX websocket relays.
+//! Point a client at it with [`OkxCli::with_endpoints`] and
+//! [`MockVenue::okx_endpoints`]; the URLs the client returns from its
+//! `LobWebsocket` helpers then connect `WsTaskRunner` tasks to the venue, so a
+//! full [`EnvBuilder`] runtime runs without network access.
+//!
+//! The test drives the market by publishing trades and book updates. They are
+//! pushed to public subscribers and fed to the backtest matching engine, and
+//! the resulting order and position changes are pushed on the private
+//! `orders` and `positions` channels.
+//!
+//! Served REST routes: place, batch place, cancel, and batch cancel orders,
+//! `cancel-all-after`, `orders-pending`, `account/positions`, and
+//! `account/balance`. Websocket sessions accept `login`, `subscribe`,
+//! `unsubscribe`, and `ping`, and push `trades`, `trades-all`, `bbo-tbt`,
+//! `books5`, `books`, `books-l2-tbt`, `books50-l2-tbt`, `orders`, and
+//! `positions`. Logged-in private sessions also trade with the `order`,
+//! `batch-orders`, `cancel-order`, and `batch-cancel-orders` ops. Requests are
+//! not authenticated: any API key is accepted and every login succeeds.
+//!
+//! [`OkxCli`]: crate::arch::market_assets::exchange::okx::okx_cli::OkxCli
+//! [`OkxCli::with_endpoints`]: crate::arch::market_assets::exchange::okx::okx_cli::OkxCli::with_endpoints
+//! [`EnvBuilder`]: crate::arch::infra_core::env_builder::EnvBuilder
+
+mod http;
+mod okx;
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    io::BufReader,
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    task::{JoinHandle, JoinSet},
+};
+use tokio_tungstenite::accept_hdr_async;
+use tracing::warn;
+use tungstenite::{
+    handshake::server::{Request, Response},
+    protocol::Message,
+};
+
+use crate::arch::{
+    market_assets::exchange::endpoints::EndpointProfile,
+    strategy_base::handler::lob_events::{WsLob, WsTrade},
+};
+use crate::errors::InfraResult;
+
+use okx::OkxVenue;
+
+const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Settings for [`MockVenue::start`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MockVenueConfig {
+    /// Fee charged on resting fills, as a fraction of notional.
+    pub maker_fee_rate: f64,
+    /// Fee charged on marketable fills, as a fraction of notional.
+    pub taker_fee_rate: f64,
+    /// Starting equity per currency, e.g. `("USDT".into(), 10_000.0)`.
+    /// Realized PnL and fees are booked in the instrument's quote currency.
+    pub balances: Vec<(String, f64)>,
+}
+
+/// Running mock venue. Dropping it closes the listeners and every open
+/// connection.
+pub struct MockVenue {
+    rest_addr: SocketAddr,
+    ws_addr: SocketAddr,
+    venue: Arc<Mutex<OkxVenue>>,
+    servers: Vec<JoinHandle<()>>,
+}
+
+impl MockVenue {
+    /// Binds REST and websocket listeners on free localhost ports.
+    pub async fn start(config: MockVenueConfig) -> InfraResult<Self> {
+        let rest_listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let ws_listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let venue = Arc::new(Mutex::new(OkxVenue::new(&config)));
+
+        Ok(Self {
+            rest_addr: rest_listener.local_addr()?,
+            ws_addr: ws_listener.local_addr()?,
+            servers: vec![
+                tokio::spawn(serve(rest_listener, venue.clone(), serve_rest)),
+                tokio::spawn(serve(ws_listener, venue.clone(), serve_ws)),
+            ],
+            venue,
+        })
+    }
+
+    /// Endpoints that send an [`OkxCli`] to this venue.
+    ///
+    /// [`OkxCli`]: crate::arch::market_assets::exchange::okx::okx_cli::OkxCli
+    pub fn okx_endpoints(&self) -> EndpointProfile {
+        let ws = format!("ws://{}/ws/v5", self.ws_addr);
+        EndpointProfile::new(format!("http://{}", self.rest_addr), format!("{ws}/public"))
+            .with_ws_private(format!("{ws}/private"))
+            .with_ws_business(format!("{ws}/business"))
+            .with_ws_trade(format!("{ws}/private"))
+    }
+
+    /// Publishes trades to subscribers, then matches resting orders against
+    /// them. Trades are treated as OKX trades whatever their `market`.
+    pub fn publish_trades(&self, trades: &[WsTrade]) {
+        self.venue().publish_trades(trades);
+    }
+
+    /// Publishes book updates to subscribers, then matches resting orders
+    /// against the new top of book. Updates are treated as OKX books whatever
+    /// their `market`.
+    pub fn publish_lobs(&self, lobs: &[WsLob]) {
+        self.venue().publish_lobs(lobs);
+    }
+
+    /// When the countdown armed through `cancel-all-after` cancels every open
+    /// order, in Unix microseconds, or `None` while disarmed. The countdown
+    /// is checked on the next REST request or publish.
+    pub fn cancel_all_after(&self) -> Option<u64> {
+        self.venue().cancel_deadline()
+    }
+
+    /// Number of websocket subscriptions to `channel`, e.g. `"trades"` or
+    /// `"orders"`, across all connections.
+    pub fn subscriptions(&self, channel: &str) -> usize {
+        self.venue().subscriptions(channel)
+    }
+
+    /// Waits until at least `count` websocket subscriptions to `channel`
+    /// exist. Wrap it in a timeout; it never returns otherwise.
+    pub async fn wait_for_subscriptions(&self, channel: &str, count: usize) {
+        while self.subscriptions(channel) < count {
+            tokio::time::sleep(SUBSCRIPTION_POLL_INTERVAL).await;
+        }
+    }
+
+    fn venue(&self) -> MutexGuard<'_, OkxVenue> {
+        lock(&self.venue)
+    }
+}
+
+impl Drop for MockVenue {
+    fn drop(&mut self) {
+        for server in &self.servers {
+            server.abort();
+        }
+    }
+}
+
+fn lock(venue: &Mutex<OkxVenue>) -> MutexGuard<'_, OkxVenue> {
+    venue
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Accepts connections until aborted. Connections live in a `JoinSet` owned
+/// by this task, so aborting it also closes them.
+async fn serve<F, Fut>(listener: TcpListener, venue: Arc<Mutex<OkxVenue>>, handle: F)
+where
+    F: Fn(TcpStream, Arc<Mutex<OkxVenue>>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut connections = JoinSet::new();
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                connections.spawn(handle(stream, venue.clone()));
+            },
+            Err(e) => warn!("Mock venue accept failed: {:?}", e),
+        }
+        while connections.try_join_next().is_some() {}
+    }
+}
+
+async fn serve_rest(stream: TcpStream, venue: Arc<Mutex<OkxVenue>>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    loop {
+        let req = match http::read_request(&mut reader).await {
+            Ok(Some(req)) => req,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Mock venue dropped malformed request: {:?}", e);
+                return;
+            },
+        };
+
+        let (status, body) = lock(&venue).handle_rest(&req);
+        if http::write_response(&mut writer, status, &body)
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+async fn serve_ws(stream: TcpStream, venue: Arc<Mutex<OkxVenue>>) {
+    let mut path = String::new();
+    // The error type is fixed by tungstenite's handshake callback.
+    #[allow(clippy::result_large_err)]
+    let callback = |req: &Request, res: Response| {
+        path = req.uri().path().to_string();
+        Ok(res)
+    };
+    let ws_stream = match accept_hdr_async(stream, callback).await {
+        Ok(ws_stream) => ws_stream,
+        Err(e) => {
+            warn!("Mock venue websocket handshake failed: {:?}", e);
+            return;
+        },
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let session = lock(&venue).open_session(&path, tx);
+    let (mut sink, mut stream) = ws_stream.split();
+
+    loop {
+        tokio::select! {
+            Some(frame) = rx.recv() => {
+                if sink.send(frame).await.is_err() {
+                    break;
+                }
+            },
+            incoming = stream.next() => match incoming {
+                Some(Ok(Message::Text(text))) => lock(&venue).handle_ws_text(session, &text),
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {},
+            },
+        }
+    }
+
+    lock(&venue).close_session(session);
+}
@@ -0,0 +1,133 @@
+//! Just enough HTTP/1.1 to answer the REST clients: keep-alive requests with
+//! an optional `Content-Length` body, answered with JSON.
+
+use std::io;
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Longest request body the venue accepts.
+const MAX_BODY_BYTES: usize = 1 << 20;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub body: String,
+}
+
+impl HttpRequest {
+    /// Value of query parameter `key`, if present.
+    pub(super) fn query_param(&self, key: &str) -> Option<&str> {
+        self.query
+            .as_deref()?
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find_map(|(k, v)| (k == key).then_some(v))
+    }
+}
+
+/// Reads one request; `None` when the peer closed the connection.
+pub(super) async fn read_request<R>(reader: &mut R) -> io::Result<Option<HttpRequest>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(invalid(format!("malformed request line: {line:?}")));
+    };
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target.to_string(), None),
+    };
+    let method = method.to_string();
+
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value
+                .trim()
+                .parse()
+                .map_err(|_| invalid(format!("bad content-length: {value:?}")))?;
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err(invalid(format!("body too large: {content_length} bytes")));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+    let body = String::from_utf8(body).map_err(|e| invalid(e.to_string()))?;
+
+    Ok(Some(HttpRequest {
+        method,
+        path,
+        query,
+        body,
+    }))
+}
+
+pub(super) async fn write_response<W>(writer: &mut W, status: u16, body: &str) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        _ => "Error",
+    };
+    let head = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+        body.len()
+    );
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(body.as_bytes()).await?;
+    writer.flush().await
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reads_keep_alive_requests_with_bodies() {
+        let raw = b"GET /api/v5/trade/orders-pending?instId=BTC-USDT-SWAP&limit=5 HTTP/1.1\r\n\
+            Host: localhost\r\n\r\n\
+            POST /api/v5/trade/order HTTP/1.1\r\n\
+            content-length: 2\r\n\r\n{}";
+        let mut reader = &raw[..];
+
+        let get = read_request(&mut reader).await.unwrap().unwrap();
+        assert_eq!(get.method, "GET");
+        assert_eq!(get.path, "/api/v5/trade/orders-pending");
+        assert_eq!(get.query_param("instId"), Some("BTC-USDT-SWAP"));
+        assert_eq!(get.query_param("limit"), Some("5"));
+        assert_eq!(get.query_param("after"), None);
+
+        let post = read_request(&mut reader).await.unwrap().unwrap();
+        assert_eq!(post.method, "POST");
+        assert_eq!(post.body, "{}");
+
+        assert!(read_request(&mut reader).await.unwrap().is_none());
+    }
+}
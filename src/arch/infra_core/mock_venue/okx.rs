@@ -0,0 +1,921 @@
+//! OKX v5 side of the mock venue: REST routes, websocket sessions, and the
+//! frames pushed to subscribers.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tungstenite::protocol::Message;
+
+use crate::arch::{
+    infra_core::backtest::{
+        BacktestConfig, BacktestFill,
+        matching::{SimMatcher, SimOutput},
+    },
+    market_assets::{
+        api_general::{CancelOrderParams, OrderParams, get_micros_timestamp},
+        base_data::{MarginMode, OrderSide, OrderStatus, OrderType, PositionSide},
+        exchange::okx::{
+            api_utils::{cli_perp_to_okx_inst, okx_inst_to_cli},
+            config_assets::{
+                OKX_ACCOUNT_BALANCE, OKX_ACCOUNT_POSITIONS, OKX_PUBLIC_TIME,
+                OKX_TRADE_BATCH_ORDERS, OKX_TRADE_CANCEL_ALL_AFTER, OKX_TRADE_CANCEL_BATCH_ORDERS,
+                OKX_TRADE_CANCEL_ORDER, OKX_TRADE_ORDER, OKX_TRADE_ORDERS_PENDING,
+            },
+        },
+        market_core::Market,
+    },
+    strategy_base::handler::{
+        alt_events::AltOrder,
+        lob_events::{LobEventKind, LobLevel, WsAccOrder, WsAccPosition, WsLob, WsTrade},
+    },
+};
+
+use super::{MockVenueConfig, http::HttpRequest};
+
+/// Most orders OKX returns from one `orders-pending` page.
+const ORDERS_PENDING_PAGE_LIMIT: usize = 100;
+/// Channels only served to logged-in private sessions.
+const PRIVATE_CHANNELS: [&str; 2] = ["orders", "positions"];
+/// Channels carrying full-depth book snapshots and updates.
+const BOOK_CHANNELS: [&str; 3] = ["books", "books-l2-tbt", "books50-l2-tbt"];
+
+/// Order placement failed; OKX reuses it for parameter errors.
+const S_CODE_ORDER_FAILED: &str = "51000";
+/// Cancel target is already filled, canceled, or unknown.
+const S_CODE_CANCEL_FAILED: &str = "51400";
+
+/// Venue state shared by every REST request and websocket session.
+pub(super) struct OkxVenue {
+    matcher: SimMatcher,
+    balances: Vec<(String, f64)>,
+    orders: HashMap<String, OrderMeta>,
+    positions: BTreeMap<String, (WsAccPosition, u64)>,
+    marks: HashMap<String, f64>,
+    /// When the armed cancel-all-after countdown lapses, in Unix microseconds.
+    cancel_deadline: Option<u64>,
+    sessions: HashMap<u64, Session>,
+    next_session_id: u64,
+    next_trade_id: u64,
+}
+
+/// Request fields OKX echoes on order updates but the matcher does not keep.
+struct OrderMeta {
+    td_mode: &'static str,
+    pos_side: &'static str,
+    reduce_only: bool,
+    created: u64,
+}
+
+struct Session {
+    private: bool,
+    logged_in: bool,
+    subscriptions: Vec<Value>,
+    tx: mpsc::UnboundedSender<Message>,
+}
+
+impl Session {
+    fn send(&self, frame: Value) {
+        let _ = self.tx.send(Message::Text(frame.to_string().into()));
+    }
+}
+
+impl OkxVenue {
+    pub(super) fn new(config: &MockVenueConfig) -> Self {
+        let matcher = SimMatcher::new(&BacktestConfig {
+            maker_fee_rate: config.maker_fee_rate,
+            taker_fee_rate: config.taker_fee_rate,
+            ..Default::default()
+        });
+
+        Self {
+            matcher,
+            balances: config.balances.clone(),
+            orders: HashMap::new(),
+            positions: BTreeMap::new(),
+            marks: HashMap::new(),
+            cancel_deadline: None,
+            sessions: HashMap::new(),
+            next_session_id: 1,
+            next_trade_id: 1,
+        }
+    }
+
+    /// Answers one REST request with a status code and JSON body.
+    pub(super) fn handle_rest(&mut self, req: &HttpRequest) -> (u16, String) {
+        let now = get_micros_timestamp();
+        self.expire_cancel_after(now);
+        let res = match (req.method.as_str(), req.path.as_str()) {
+            ("POST", OKX_TRADE_ORDER) => parse_body(&req.body).map(|body| {
+                let ack = self.place(now, &body);
+                ack_response(vec![ack])
+            }),
+            ("POST", OKX_TRADE_BATCH_ORDERS) => parse_batch(&req.body).map(|bodies| {
+                let acks = bodies.iter().map(|body| self.place(now, body)).collect();
+                ack_response(acks)
+            }),
+            ("POST", OKX_TRADE_CANCEL_ORDER) => parse_body(&req.body).map(|body| {
+                let ack = self.cancel(now, &body);
+                ack_response(vec![ack])
+            }),
+            ("POST", OKX_TRADE_CANCEL_BATCH_ORDERS) => parse_batch(&req.body).map(|bodies| {
+                let acks = bodies.iter().map(|body| self.cancel(now, body)).collect();
+                ack_response(acks)
+            }),
+            ("POST", OKX_TRADE_CANCEL_ALL_AFTER) => parse_body(&req.body)
+                .and_then(|body| self.cancel_all_after(now, &body))
+                .map(|ack| data_response(vec![ack])),
+            ("GET", OKX_TRADE_ORDERS_PENDING) => Ok(data_response(self.orders_pending(req))),
+            ("GET", OKX_ACCOUNT_POSITIONS) => Ok(data_response(self.account_positions(req))),
+            ("GET", OKX_ACCOUNT_BALANCE) => Ok(data_response(self.account_balance(req, now))),
+            ("GET", OKX_PUBLIC_TIME) => Ok(data_response(vec![
+                json!({ "ts": (now / 1_000).to_string() }),
+            ])),
+            _ => {
+                let body = json!({
+                    "code": "404",
+                    "msg": format!("Mock venue does not serve {} {}", req.method, req.path),
+                    "data": [],
+                });
+                return (404, body.to_string());
+            },
+        };
+
+        match res {
+            Ok(body) => (200, body.to_string()),
+            Err(msg) => {
+                let body = json!({ "code": "50002", "msg": msg, "data": [] });
+                (400, body.to_string())
+            },
+        }
+    }
+
+    /// Registers a websocket connected to `path` and returns its session id.
+    pub(super) fn open_session(&mut self, path: &str, tx: mpsc::UnboundedSender<Message>) -> u64 {
+        let id = self.next_session_id;
+        self.next_session_id += 1;
+        self.sessions.insert(
+            id,
+            Session {
+                private: path.ends_with("/private"),
+                logged_in: false,
+                subscriptions: Vec::new(),
+                tx,
+            },
+        );
+        id
+    }
+
+    pub(super) fn close_session(&mut self, id: u64) {
+        self.sessions.remove(&id);
+    }
+
+    /// Handles one text frame sent by session `id`.
+    pub(super) fn handle_ws_text(&mut self, id: u64, text: &str) {
+        let Some(session) = self.sessions.get_mut(&id) else {
+            return;
+        };
+        if text == "ping" {
+            let _ = session.tx.send(Message::Text("pong".into()));
+            return;
+        }
+
+        let conn_id = id.to_string();
+        let request = serde_json::from_str::<Value>(text).unwrap_or_default();
+        let args = request["args"].as_array().cloned().unwrap_or_default();
+        match request["op"].as_str() {
+            Some("login") if session.private => {
+                session.logged_in = true;
+                session.send(json!({
+                    "event": "login",
+                    "code": "0",
+                    "msg": "",
+                    "connId": conn_id,
+                }));
+            },
+            Some("subscribe") => {
+                for arg in args {
+                    let channel = arg["channel"].as_str().unwrap_or_default();
+                    if PRIVATE_CHANNELS.contains(&channel) && !session.logged_in {
+                        session.send(ws_error("60011", "Please log in", &conn_id));
+                        continue;
+                    }
+                    session.send(json!({ "event": "subscribe", "arg": arg, "connId": conn_id }));
+                    session.subscriptions.push(arg);
+                }
+            },
+            Some("unsubscribe") => {
+                for arg in args {
+                    session.subscriptions.retain(|sub| *sub != arg);
+                    session.send(json!({ "event": "unsubscribe", "arg": arg, "connId": conn_id }));
+                }
+            },
+            Some(op @ ("order" | "batch-orders" | "cancel-order" | "batch-cancel-orders")) => {
+                self.ws_trade(id, op, &request["id"], &args);
+            },
+            _ => {
+                let msg = format!("Invalid request: {text}");
+                session.send(ws_error("60012", &msg, &conn_id));
+            },
+        }
+    }
+
+    /// Answers an order entry op; only logged-in private sessions may trade.
+    fn ws_trade(&mut self, id: u64, op: &str, req_id: &Value, args: &[Value]) {
+        let now = get_micros_timestamp();
+        self.expire_cancel_after(now);
+        let logged_in = self
+            .sessions
+            .get(&id)
+            .is_some_and(|session| session.private && session.logged_in);
+
+        let mut response = if !logged_in {
+            json!({ "code": "60011", "msg": "Please log in", "data": [] })
+        } else if op.contains("cancel") {
+            ack_response(args.iter().map(|arg| self.cancel(now, arg)).collect())
+        } else {
+            ack_response(args.iter().map(|arg| self.place(now, arg)).collect())
+        };
+        response["id"] = req_id.clone();
+        response["op"] = json!(op);
+
+        if let Some(session) = self.sessions.get(&id) {
+            session.send(response);
+        }
+    }
+
+    /// Number of websocket subscriptions to `channel` across all sessions.
+    pub(super) fn subscriptions(&self, channel: &str) -> usize {
+        self.sessions
+            .values()
+            .flat_map(|session| &session.subscriptions)
+            .filter(|arg| arg["channel"] == channel)
+            .count()
+    }
+
+    pub(super) fn publish_trades(&mut self, trades: &[WsTrade]) {
+        let trades: Vec<WsTrade> = trades
+            .iter()
+            .map(|trade| WsTrade {
+                market: Market::Okx,
+                ..trade.clone()
+            })
+            .collect();
+
+        for trade in &trades {
+            let inst_id = cli_perp_to_okx_inst(&trade.inst);
+            let data = vec![json!({
+                "instId": inst_id,
+                "tradeId": trade.trade_id.to_string(),
+                "px": trade.price.to_string(),
+                "sz": trade.size.to_string(),
+                "side": okx_side(&trade.side),
+                "ts": (trade.timestamp / 1_000).to_string(),
+                "count": "1",
+            })];
+            self.broadcast("trades", &inst_id, None, &data);
+            self.broadcast("trades-all", &inst_id, None, &data);
+            self.marks.insert(trade.inst.clone(), trade.price);
+        }
+
+        let now = get_micros_timestamp();
+        self.expire_cancel_after(now);
+        let output = self.matcher.on_trades(now, &trades);
+        self.push_output(output);
+    }
+
+    pub(super) fn publish_lobs(&mut self, lobs: &[WsLob]) {
+        let lobs: Vec<WsLob> = lobs
+            .iter()
+            .map(|lob| WsLob {
+                market: Market::Okx,
+                ..lob.clone()
+            })
+            .collect();
+
+        for lob in &lobs {
+            let inst_id = cli_perp_to_okx_inst(&lob.inst);
+            let data = vec![json!({
+                "asks": okx_levels(&lob.asks),
+                "bids": okx_levels(&lob.bids),
+                "ts": (lob.timestamp / 1_000).to_string(),
+            })];
+            match lob.event {
+                LobEventKind::Bbo => self.broadcast("bbo-tbt", &inst_id, None, &data),
+                LobEventKind::Snapshot => {
+                    self.broadcast("books5", &inst_id, None, &data);
+                    for channel in BOOK_CHANNELS {
+                        self.broadcast(channel, &inst_id, Some("snapshot"), &data);
+                    }
+                },
+                LobEventKind::Incremental | LobEventKind::Heartbeat => {
+                    for channel in BOOK_CHANNELS {
+                        self.broadcast(channel, &inst_id, Some("update"), &data);
+                    }
+                },
+            }
+        }
+
+        let now = get_micros_timestamp();
+        self.expire_cancel_after(now);
+        let output = self.matcher.on_lobs(now, &lobs);
+        self.push_output(output);
+    }
+
+    fn place(&mut self, now: u64, body: &Value) -> Value {
+        let cl_ord_id = str_field(body, "clOrdId").unwrap_or_default();
+        let order_params = match order_params(body) {
+            Ok(order_params) => order_params,
+            Err(msg) => return order_ack("", cl_ord_id, S_CODE_ORDER_FAILED, &msg),
+        };
+        let meta = OrderMeta {
+            td_mode: match order_params.margin_mode {
+                Some(MarginMode::Isolated) => "isolated",
+                _ => "cross",
+            },
+            pos_side: match order_params.position_side {
+                Some(PositionSide::Long) => "long",
+                Some(PositionSide::Short) => "short",
+                _ => "net",
+            },
+            reduce_only: order_params.reduce_only.unwrap_or_default(),
+            created: now,
+        };
+
+        let output = self.matcher.submit(
+            now,
+            &AltOrder {
+                timestamp: now,
+                market: Market::Okx,
+                order_params,
+                metadata: HashMap::new(),
+            },
+        );
+        let Some(order) = output.orders.first() else {
+            return order_ack("", cl_ord_id, S_CODE_ORDER_FAILED, "Order was not accepted");
+        };
+        let ord_id = order.order_id.clone().unwrap_or_default();
+        if order.status == OrderStatus::Rejected {
+            let msg = "Order rejected: it would not rest or fill";
+            return order_ack(&ord_id, cl_ord_id, S_CODE_ORDER_FAILED, msg);
+        }
+
+        self.orders.insert(ord_id.clone(), meta);
+        self.push_output(output);
+        order_ack(&ord_id, cl_ord_id, "0", "")
+    }
+
+    fn cancel(&mut self, now: u64, body: &Value) -> Value {
+        let ord_id = str_field(body, "ordId").unwrap_or_default();
+        let cl_ord_id = str_field(body, "clOrdId").unwrap_or_default();
+        let Some(inst_id) = str_field(body, "instId") else {
+            return order_ack(ord_id, cl_ord_id, S_CODE_ORDER_FAILED, "instId is required");
+        };
+
+        let cancel_params = CancelOrderParams {
+            inst: okx_inst_to_cli(inst_id),
+            order_id: (!ord_id.is_empty()).then(|| ord_id.into()),
+            cli_order_id: (!cl_ord_id.is_empty()).then(|| cl_ord_id.into()),
+        };
+        let output = self.matcher.cancel(now, &Market::Okx, &cancel_params);
+        let Some(order) = output.orders.first() else {
+            let msg = "Order cancellation failed as the order has been filled, canceled or does not exist";
+            return order_ack(ord_id, cl_ord_id, S_CODE_CANCEL_FAILED, msg);
+        };
+
+        let ack = order_ack(
+            order.order_id.as_deref().unwrap_or_default(),
+            order.cli_order_id.as_deref().unwrap_or_default(),
+            "0",
+            "",
+        );
+        self.push_output(output);
+        ack
+    }
+
+    /// Arms the countdown for `timeOut` seconds, or disarms it for `0`.
+    fn cancel_all_after(&mut self, now: u64, body: &Value) -> Result<Value, String> {
+        let secs: u64 = str_field(body, "timeOut")
+            .and_then(|secs| secs.parse().ok())
+            .ok_or("timeOut is required")?;
+        if secs != 0 && !(10..=120).contains(&secs) {
+            return Err("timeOut must be 0 or between 10 and 120".into());
+        }
+
+        self.cancel_deadline = (secs != 0).then(|| now + secs * 1_000_000);
+        let trigger_time = self.cancel_deadline.map_or(0, |deadline| deadline / 1_000);
+        Ok(json!({
+            "triggerTime": trigger_time.to_string(),
+            "tag": "",
+            "ts": (now / 1_000).to_string(),
+        }))
+    }
+
+    pub(super) fn cancel_deadline(&self) -> Option<u64> {
+        self.cancel_deadline
+    }
+
+    /// Cancels every open order once the armed countdown has lapsed.
+    fn expire_cancel_after(&mut self, now: u64) {
+        if self.cancel_deadline.is_none_or(|deadline| deadline > now) {
+            return;
+        }
+        self.cancel_deadline = None;
+
+        let open: Vec<CancelOrderParams> = self
+            .matcher
+            .open_orders()
+            .map(|order| CancelOrderParams {
+                inst: order.inst.clone(),
+                order_id: order.order_id.clone(),
+                cli_order_id: None,
+            })
+            .collect();
+        for cancel_params in open {
+            let output = self.matcher.cancel(now, &Market::Okx, &cancel_params);
+            self.push_output(output);
+        }
+    }
+
+    /// Open orders, newest first, paged with `after` and `limit`.
+    fn orders_pending(&self, req: &HttpRequest) -> Vec<Value> {
+        let inst = req.query_param("instId").map(okx_inst_to_cli);
+        let limit = req
+            .query_param("limit")
+            .and_then(|limit| limit.parse().ok())
+            .unwrap_or(ORDERS_PENDING_PAGE_LIMIT)
+            .min(ORDERS_PENDING_PAGE_LIMIT);
+
+        let mut orders: Vec<&WsAccOrder> = self
+            .matcher
+            .open_orders()
+            .filter(|order| inst.as_ref().is_none_or(|inst| order.inst == *inst))
+            .collect();
+        orders.reverse();
+        if let Some(after) = req.query_param("after")
+            && let Some(index) = orders
+                .iter()
+                .position(|order| order.order_id.as_deref() == Some(after))
+        {
+            orders.drain(..=index);
+        }
+
+        orders
+            .into_iter()
+            .take(limit)
+            .map(|order| self.order_json(order, None))
+            .collect()
+    }
+
+    fn account_positions(&self, req: &HttpRequest) -> Vec<Value> {
+        let insts: Option<Vec<String>> = req
+            .query_param("instId")
+            .map(|ids| ids.split(',').map(okx_inst_to_cli).collect());
+
+        self.positions
+            .iter()
+            .filter(|(inst, (position, _))| {
+                position.size != 0.0 && insts.as_ref().is_none_or(|insts| insts.contains(inst))
+            })
+            .map(|(_, (position, updated))| self.position_json(position, *updated))
+            .collect()
+    }
+
+    fn account_balance(&self, req: &HttpRequest, now: u64) -> Vec<Value> {
+        let ccys: Option<Vec<&str>> = req.query_param("ccy").map(|c| c.split(',').collect());
+        let u_time = (now / 1_000).to_string();
+
+        let details: Vec<Value> = self
+            .balances
+            .iter()
+            .filter(|(ccy, _)| {
+                ccys.as_ref()
+                    .is_none_or(|ccys| ccys.contains(&ccy.as_str()))
+            })
+            .map(|(ccy, initial)| {
+                let booked: f64 = self
+                    .matcher
+                    .positions()
+                    .iter()
+                    .filter(|position| settle_ccy(&cli_perp_to_okx_inst(&position.inst)) == ccy)
+                    .map(|position| position.realized_pnl - position.fees)
+                    .sum();
+                let eq = (initial + booked).to_string();
+                json!({
+                    "ccy": ccy,
+                    "eq": eq,
+                    "availBal": eq,
+                    "frozenBal": "0",
+                    "liab": "0",
+                    "uTime": u_time,
+                })
+            })
+            .collect();
+
+        vec![json!({ "details": details, "uTime": u_time })]
+    }
+
+    /// Pushes matcher updates on the private `orders` and `positions`
+    /// channels.
+    fn push_output(&mut self, output: SimOutput) {
+        let SimOutput {
+            orders,
+            positions,
+            fills,
+        } = output;
+
+        for order in &orders {
+            let fill = fills
+                .iter()
+                .find(|fill| order.status == OrderStatus::Filled && fill.order_id == order.order_id)
+                .map(|fill| {
+                    let trade_id = self.next_trade_id;
+                    self.next_trade_id += 1;
+                    (fill, trade_id)
+                });
+            let inst_id = cli_perp_to_okx_inst(&order.inst);
+            let data = vec![self.order_json(order, fill)];
+            self.broadcast("orders", &inst_id, None, &data);
+        }
+
+        for (_, position) in positions {
+            let inst_id = cli_perp_to_okx_inst(&position.inst);
+            let updated = get_micros_timestamp();
+            let data = vec![self.position_json(&position, updated)];
+            self.positions
+                .insert(position.inst.clone(), (position, updated));
+            self.broadcast("positions", &inst_id, None, &data);
+        }
+    }
+
+    /// Sends `data` to every session subscribed to `channel` for `inst_id`,
+    /// echoing the session's own subscription arg.
+    fn broadcast(&self, channel: &str, inst_id: &str, action: Option<&str>, data: &[Value]) {
+        for session in self.sessions.values() {
+            let Some(arg) = session
+                .subscriptions
+                .iter()
+                .find(|arg| subscription_matches(arg, channel, inst_id))
+            else {
+                continue;
+            };
+
+            let mut frame = json!({ "arg": arg, "data": data });
+            if let Some(action) = action {
+                frame["action"] = json!(action);
+            }
+            session.send(frame);
+        }
+    }
+
+    fn order_json(&self, order: &WsAccOrder, fill: Option<(&BacktestFill, u64)>) -> Value {
+        let inst_id = cli_perp_to_okx_inst(&order.inst);
+        let ccy = settle_ccy(&inst_id);
+        let meta = order.order_id.as_ref().and_then(|id| self.orders.get(id));
+        let filled = order.filled_size > 0.0;
+        let u_time = (order.timestamp / 1_000).to_string();
+        let c_time = meta.map_or(order.timestamp, |meta| meta.created) / 1_000;
+
+        let mut data = json!({
+            "instType": okx_inst_type(&inst_id),
+            "instId": inst_id,
+            "ordId": order.order_id.clone().unwrap_or_default(),
+            "clOrdId": order.cli_order_id.clone().unwrap_or_default(),
+            "tag": "",
+            "side": okx_side(&order.side),
+            "posSide": meta.map_or("net", |meta| meta.pos_side),
+            "tdMode": meta.map_or("cross", |meta| meta.td_mode),
+            "ordType": okx_ord_type(&order.order_type),
+            "state": okx_state(&order.status),
+            "px": if matches!(order.order_type, OrderType::Market) {
+                String::new()
+            } else {
+                order.price.to_string()
+            },
+            "sz": order.size.to_string(),
+            "accFillSz": order.filled_size.to_string(),
+            "avgPx": if filled { order.price.to_string() } else { String::new() },
+            "fillPx": "",
+            "fillSz": "0",
+            "fillTime": "",
+            "tradeId": "",
+            "fee": "0",
+            "feeCcy": ccy,
+            "fillFee": "0",
+            "fillFeeCcy": "",
+            "execType": "",
+            "reduceOnly": meta.is_some_and(|meta| meta.reduce_only).to_string(),
+            "cTime": c_time.to_string(),
+            "uTime": u_time,
+        });
+
+        if let Some((fill, trade_id)) = fill {
+            // OKX reports fees as negative amounts and rebates as positive.
+            let fee = (-fill.fee).to_string();
+            data["fillPx"] = json!(fill.price.to_string());
+            data["fillSz"] = json!(fill.size.to_string());
+            data["fillTime"] = json!(u_time);
+            data["tradeId"] = json!(trade_id.to_string());
+            data["fee"] = json!(fee);
+            data["fillFee"] = json!(fee);
+            data["fillFeeCcy"] = json!(ccy);
+            data["execType"] = json!(if fill.maker { "M" } else { "T" });
+        }
+
+        data
+    }
+
+    /// Position in the shape shared by the REST and websocket position feeds.
+    fn position_json(&self, position: &WsAccPosition, updated: u64) -> Value {
+        let inst_id = cli_perp_to_okx_inst(&position.inst);
+        let mark = self
+            .marks
+            .get(&position.inst)
+            .copied()
+            .unwrap_or(position.avg_price);
+
+        json!({
+            "instType": okx_inst_type(&inst_id),
+            "instId": inst_id,
+            "mgnMode": match position.margin_mode {
+                MarginMode::Isolated => "isolated",
+                _ => "cross",
+            },
+            "posSide": "net",
+            "pos": position.size.to_string(),
+            "avgPx": position.avg_price.to_string(),
+            "markPx": mark.to_string(),
+            "upl": ((mark - position.avg_price) * position.size).to_string(),
+            "lever": "1",
+            "ccy": settle_ccy(&inst_id),
+            "uTime": (updated / 1_000).to_string(),
+        })
+    }
+}
+
+fn parse_body(body: &str) -> Result<Value, String> {
+    serde_json::from_str::<Value>(body)
+        .ok()
+        .filter(Value::is_object)
+        .ok_or_else(|| format!("Expected a JSON object body: {body}"))
+}
+
+fn parse_batch(body: &str) -> Result<Vec<Value>, String> {
+    match serde_json::from_str::<Value>(body) {
+        Ok(Value::Array(items)) => Ok(items),
+        _ => Err(format!("Expected a JSON array body: {body}")),
+    }
+}
+
+fn str_field<'a>(body: &'a Value, key: &str) -> Option<&'a str> {
+    body.get(key).and_then(Value::as_str)
+}
+
+fn order_params(body: &Value) -> Result<OrderParams, String> {
+    let required = |key: &str| str_field(body, key).ok_or(format!("Parameter {key} error"));
+    let inst_id = required("instId")?;
+    let side = match required("side")? {
+        "buy" => OrderSide::BUY,
+        "sell" => OrderSide::SELL,
+        _ => return Err("Parameter side error".into()),
+    };
+    let order_type = match required("ordType")? {
+        "limit" => OrderType::Limit,
+        "market" => OrderType::Market,
+        "post_only" => OrderType::PostOnly,
+        "ioc" => OrderType::Ioc,
+        "fok" => OrderType::Fok,
+        _ => return Err("Parameter ordType error".into()),
+    };
+
+    Ok(OrderParams {
+        inst: okx_inst_to_cli(inst_id),
+        side,
+        size: required("sz")?.into(),
+        order_type,
+        price: str_field(body, "px").map(Into::into),
+        reduce_only: body.get("reduceOnly").and_then(Value::as_bool),
+        margin_mode: str_field(body, "tdMode").map(|mode| match mode {
+            "isolated" => MarginMode::Isolated,
+            "cross" => MarginMode::Cross,
+            _ => MarginMode::Unknown,
+        }),
+        position_side: str_field(body, "posSide").map(|side| match side {
+            "long" => PositionSide::Long,
+            "short" => PositionSide::Short,
+            "net" => PositionSide::Both,
+            _ => PositionSide::Unknown,
+        }),
+        client_order_id: str_field(body, "clOrdId")
+            .filter(|id| !id.is_empty())
+            .map(Into::into),
+        ..Default::default()
+    })
+}
+
+fn order_ack(ord_id: &str, cl_ord_id: &str, s_code: &str, s_msg: &str) -> Value {
+    json!({
+        "clOrdId": cl_ord_id,
+        "ordId": ord_id,
+        "tag": "",
+        "ts": (get_micros_timestamp() / 1_000).to_string(),
+        "sCode": s_code,
+        "sMsg": s_msg,
+    })
+}
+
+/// Wraps per-order acks with OKX's batch result code: `0` when all
+/// succeeded, `1` when all failed, `2` otherwise.
+fn ack_response(acks: Vec<Value>) -> Value {
+    let failed = acks.iter().filter(|ack| ack["sCode"] != "0").count();
+    let (code, msg) = match failed {
+        0 => ("0", ""),
+        n if n == acks.len() => ("1", "All operations failed"),
+        _ => ("2", "Bulk operation partially succeeded"),
+    };
+    json!({ "code": code, "msg": msg, "data": acks })
+}
+
+fn data_response(data: Vec<Value>) -> Value {
+    json!({ "code": "0", "msg": "", "data": data })
+}
+
+fn ws_error(code: &str, msg: &str, conn_id: &str) -> Value {
+    json!({ "event": "error", "code": code, "msg": msg, "connId": conn_id })
+}
+
+fn subscription_matches(arg: &Value, channel: &str, inst_id: &str) -> bool {
+    arg["channel"] == channel
+        && arg
+            .get("instId")
+            .and_then(Value::as_str)
+            .is_none_or(|id| id == inst_id)
+        && arg
+            .get("instType")
+            .and_then(Value::as_str)
+            .is_none_or(|kind| kind == "ANY" || kind == okx_inst_type(inst_id))
+}
+
+fn okx_levels(levels: &[LobLevel]) -> Vec<[String; 4]> {
+    levels
+        .iter()
+        .map(|level| {
+            [
+                level.price.to_string(),
+                level.size.to_string(),
+                "0".into(),
+                level.order_count.unwrap_or(1).to_string(),
+            ]
+        })
+        .collect()
+}
+
+fn okx_inst_type(inst_id: &str) -> &'static str {
+    match inst_id.split('-').collect::<Vec<_>>().as_slice() {
+        [_, _, "SWAP"] => "SWAP",
+        [_, _] => "SPOT",
+        _ => "FUTURES",
+    }
+}
+
+/// Quote currency of `inst_id`, which the mock settles PnL and fees in.
+fn settle_ccy(inst_id: &str) -> &str {
+    inst_id.split('-').nth(1).unwrap_or_default()
+}
+
+fn okx_side(side: &OrderSide) -> &'static str {
+    match side {
+        OrderSide::SELL => "sell",
+        _ => "buy",
+    }
+}
+
+fn okx_ord_type(order_type: &OrderType) -> &'static str {
+    match order_type {
+        OrderType::Market => "market",
+        OrderType::PostOnly => "post_only",
+        OrderType::Ioc => "ioc",
+        OrderType::Fok => "fok",
+        OrderType::Limit | OrderType::Unknown => "limit",
+    }
+}
+
+fn okx_state(status: &OrderStatus) -> &'static str {
+    match status {
+        OrderStatus::PartiallyFilled => "partially_filled",
+        OrderStatus::Filled => "filled",
+        OrderStatus::Canceled => "canceled",
+        _ => "live",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(rx: &mut mpsc::UnboundedReceiver<Message>) -> Vec<Value> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|msg| serde_json::from_str(msg.to_text().unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn private_channels_require_login() {
+        let mut venue = OkxVenue::new(&MockVenueConfig::default());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let id = venue.open_session("/ws/v5/private", tx);
+        let subscribe = r#"{"op":"subscribe","args":[{"channel":"orders","instType":"ANY"}]}"#;
+
+        venue.handle_ws_text(id, subscribe);
+        assert_eq!(frames(&mut rx)[0]["code"], "60011");
+        assert_eq!(venue.subscriptions("orders"), 0);
+
+        venue.handle_ws_text(id, r#"{"op":"login","args":[{}]}"#);
+        venue.handle_ws_text(id, subscribe);
+        let events: Vec<_> = frames(&mut rx)
+            .into_iter()
+            .map(|f| f["event"].clone())
+            .collect();
+        assert_eq!(events, ["login", "subscribe"]);
+        assert_eq!(venue.subscriptions("orders"), 1);
+
+        venue.close_session(id);
+        assert_eq!(venue.subscriptions("orders"), 0);
+    }
+
+    #[test]
+    fn private_sessions_trade_after_login() {
+        let mut venue = OkxVenue::new(&MockVenueConfig::default());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let id = venue.open_session("/ws/v5/private", tx);
+        let order = r#"{"id":"1","op":"order","args":[{"instId":"BTC-USDT-SWAP",
+            "side":"buy","ordType":"limit","px":"100","sz":"1","clOrdId":"a1"}]}"#;
+
+        venue.handle_ws_text(id, order);
+        assert_eq!(frames(&mut rx)[0]["code"], "60011");
+
+        venue.handle_ws_text(id, r#"{"op":"login","args":[{}]}"#);
+        venue.handle_ws_text(id, order);
+        let placed = frames(&mut rx).pop().unwrap();
+        assert_eq!(placed["id"], "1");
+        assert_eq!(placed["op"], "order");
+        assert_eq!(placed["code"], "0");
+        let ord_id = placed["data"][0]["ordId"].as_str().unwrap().to_string();
+
+        let cancel = json!({
+            "id": "2",
+            "op": "batch-cancel-orders",
+            "args": [
+                { "instId": "BTC-USDT-SWAP", "ordId": ord_id },
+                { "instId": "BTC-USDT-SWAP", "ordId": "missing" },
+            ],
+        });
+        venue.handle_ws_text(id, &cancel.to_string());
+        let canceled = frames(&mut rx)
+            .into_iter()
+            .find(|frame| frame["id"] == "2")
+            .unwrap();
+        assert_eq!(canceled["code"], "2");
+        assert_eq!(canceled["data"][0]["sCode"], "0");
+        assert_eq!(venue.matcher.open_orders().count(), 0);
+    }
+
+    #[test]
+    fn batch_acks_report_partial_failure() {
+        let ok = order_ack("1", "", "0", "");
+        let failed = order_ack("", "", S_CODE_ORDER_FAILED, "Parameter sz error");
+
+        assert_eq!(ack_response(vec![ok.clone()])["code"], "0");
+        assert_eq!(ack_response(vec![failed.clone()])["code"], "1");
+        assert_eq!(ack_response(vec![ok, failed])["code"], "2");
+    }
+
+    #[test]
+    fn lapsed_cancel_after_cancels_open_orders() {
+        let mut venue = OkxVenue::new(&MockVenueConfig::default());
+        let order = json!({
+            "instId": "BTC-USDT-SWAP",
+            "side": "buy",
+            "ordType": "limit",
+            "px": "100",
+            "sz": "1",
+        });
+        assert_eq!(venue.place(0, &order)["sCode"], "0");
+
+        let ack = venue
+            .cancel_all_after(0, &json!({ "timeOut": "10" }))
+            .unwrap();
+        assert_eq!(ack["triggerTime"], "10000");
+        assert!(
+            venue
+                .cancel_all_after(0, &json!({ "timeOut": "5" }))
+                .is_err()
+        );
+
+        venue.expire_cancel_after(9_999_999);
+        assert_eq!(venue.matcher.open_orders().count(), 1);
+        venue.expire_cancel_after(10_000_000);
+        assert_eq!(venue.matcher.open_orders().count(), 0);
+        assert_eq!(venue.cancel_deadline(), None);
+    }
+}
@@ -0,0 +1,130 @@
+use futures::future::pending;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::warn;
+
+/// Cloneable trigger that stops a running [`EnvMediator`].
+///
+/// Obtain one with [`EnvMediator::shutdown_handle`] before calling
+/// [`EnvMediator::execute`]. Calling [`ShutdownHandle::shutdown`] stops task
+/// relays, runs every [`Strategy::on_shutdown`] hook, drains strategy event
+/// loops within the configured deadline, and then lets `execute` return.
+///
+/// [`EnvMediator`]: crate::arch::infra_core::env_mediator::EnvMediator
+/// [`EnvMediator::shutdown_handle`]: crate::arch::infra_core::env_mediator::EnvMediator::shutdown_handle
+/// [`EnvMediator::execute`]: crate::arch::infra_core::env_mediator::EnvMediator::execute
+/// [`Strategy::on_shutdown`]: crate::arch::traits::strategy::Strategy::on_shutdown
+#[derive(Clone, Debug)]
+pub struct ShutdownHandle {
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl ShutdownHandle {
+    pub(crate) fn new() -> Self {
+        let (tx, _) = watch::channel(false);
+        Self { tx: Arc::new(tx) }
+    }
+
+    /// Requests runtime shutdown. Repeated calls are no-ops.
+    pub fn shutdown(&self) {
+        self.tx.send_replace(true);
+    }
+
+    /// Returns `true` after shutdown has been requested.
+    pub fn is_shutdown(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    pub(crate) fn signal(&self) -> ShutdownSignal {
+        ShutdownSignal {
+            rx: self.tx.subscribe(),
+        }
+    }
+}
+
+/// Receiver side of a [`ShutdownHandle`] held by runtime task relays.
+#[derive(Clone, Debug)]
+pub(crate) struct ShutdownSignal {
+    rx: watch::Receiver<bool>,
+}
+
+impl ShutdownSignal {
+    /// Completes once shutdown has been requested or every handle was dropped.
+    ///
+    /// The future is cancel-safe and can be used as a `select!` branch.
+    pub(crate) async fn recv(&mut self) {
+        let _ = self.rx.wait_for(|stop| *stop).await;
+    }
+
+    pub(crate) fn is_shutdown(&self) -> bool {
+        *self.rx.borrow()
+    }
+}
+
+/// Completes when the process receives Ctrl-C, or SIGTERM on Unix.
+///
+/// If no OS signal listener can be installed, the future never completes and
+/// the runtime can still be stopped through a [`ShutdownHandle`].
+pub(crate) async fn os_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = ctrl_c() => {},
+                    _ = sigterm.recv() => {},
+                }
+            },
+            Err(e) => {
+                warn!("Failed to install SIGTERM listener: {:?}", e);
+                ctrl_c().await;
+            },
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        ctrl_c().await;
+    }
+}
+
+async fn ctrl_c() {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        warn!("Failed to install Ctrl-C listener: {:?}", e);
+        pending::<()>().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn signal_completes_after_shutdown() {
+        let handle = ShutdownHandle::new();
+        let mut signal = handle.signal();
+
+        assert!(!signal.is_shutdown());
+        handle.clone().shutdown();
+
+        tokio::time::timeout(Duration::from_secs(1), signal.recv())
+            .await
+            .unwrap();
+        assert!(handle.is_shutdown());
+    }
+
+    #[tokio::test]
+    async fn late_signal_observes_earlier_shutdown() {
+        let handle = ShutdownHandle::new();
+        handle.shutdown();
+
+        let mut signal = handle.signal();
+        tokio::time::timeout(Duration::from_secs(1), signal.recv())
+            .await
+            .unwrap();
+    }
+}
@@ -8,7 +8,9 @@
 //!
 //! Use [`market_core::Market`] to identify venues, [`base_data`] for shared
 //! enums such as order side and instrument type, and [`api_data`] for normalized
-//! REST payloads. Built-in exchange clients live under [`exchange`] and are
+//! REST payloads. [`order_book::LocalOrderBook`] rebuilds L2 books from
+//! websocket deltas, and [`instrument_registry::InstrumentRegistry`] caches
+//! instrument metadata and snaps orders to the venue grid. Built-in exchange clients live under [`exchange`] and are
 //! enabled with crate features such as `binance`, `okx`, `gate`, and
 //! `hyperliquid`.
 
@@ -17,4 +19,6 @@ pub mod exchange;
 
 pub mod api_general;
 pub mod base_data;
+pub mod instrument_registry;
 pub mod market_core;
+pub mod order_book;
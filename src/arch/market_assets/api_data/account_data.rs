@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::arch::market_assets::base_data::*;
+use crate::errors::InfraResult;
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct BalanceData {
@@ -41,6 +42,16 @@ pub struct OrderAckData {
     pub msg: Option<String>,
 }
 
+/// Venue response to one websocket order entry request.
+#[derive(Debug)]
+pub struct WsOrderResponse {
+    /// Id the request was sent with.
+    pub id: u64,
+    /// One ack per order or cancel in the request, in request order, or why
+    /// the whole request failed.
+    pub result: InfraResult<Vec<OrderAckData>>,
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct OrderDetailData {
     pub timestamp: u64,
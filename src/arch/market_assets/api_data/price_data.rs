@@ -41,4 +41,5 @@ pub struct OrderBookData {
     pub inst: String,
     pub bids: Vec<(f64, f64)>, // (price, quantity)
     pub asks: Vec<(f64, f64)>,
+    pub update_id: Option<u64>, // venue book sequence, when the endpoint reports one
 }
@@ -8,7 +8,10 @@ use std::{
 };
 
 use crate::arch::{
-    market_assets::base_data::{MarginMode, OrderSide, OrderType, PositionSide, TimeInForce},
+    market_assets::{
+        base_data::{MarginMode, OrderSide, OrderType, PositionSide, TimeInForce},
+        exchange::exchange_error::{ExchangeError, Venue},
+    },
     task_execution::task_ws::CandleParam,
 };
 use crate::errors::{InfraError, InfraResult};
@@ -120,6 +123,27 @@ pub(crate) fn micros_to_seconds(timestamp_us: u64) -> u64 {
     timestamp_us / 1_000_000
 }
 
+/// Turns a subscribe message into its unsubscribe form by replacing the
+/// operation under `op_key`, e.g. OKX `"op"` or Binance `"method"`.
+#[cfg(any(
+    feature = "hyperliquid",
+    feature = "binance",
+    feature = "gate",
+    feature = "okx",
+    test
+))]
+pub(crate) fn ws_unsubscribe_msg(sub_msg: &str, op_key: &str, op: &str) -> InfraResult<String> {
+    let mut msg: Value = serde_json::from_str(sub_msg)?;
+    let Some(slot) = msg.get_mut(op_key) else {
+        return Err(InfraError::ApiCliError(format!(
+            "Subscribe message has no {op_key} to replace"
+        )));
+    };
+    *slot = Value::String(op.into());
+
+    Ok(msg.to_string())
+}
+
 pub fn candle_interval_millis(interval: &CandleParam) -> InfraResult<u64> {
     match interval {
         CandleParam::OneSecond => Ok(1_000),
@@ -254,6 +278,30 @@ where
 }
 
 pub async fn parse_json_response<T>(label: &str, response: reqwest::Response) -> InfraResult<T>
+where
+    T: DeserializeOwned,
+{
+    read_json_response(None, label, response).await
+}
+
+/// Like [`parse_json_response`], but fails non-success HTTP responses with an
+/// [`ExchangeError`] parsed from the venue's error body.
+pub async fn parse_venue_response<T>(
+    venue: Venue,
+    label: &str,
+    response: reqwest::Response,
+) -> InfraResult<T>
+where
+    T: DeserializeOwned,
+{
+    read_json_response(Some(venue), label, response).await
+}
+
+async fn read_json_response<T>(
+    venue: Option<Venue>,
+    label: &str,
+    response: reqwest::Response,
+) -> InfraResult<T>
 where
     T: DeserializeOwned,
 {
@@ -263,6 +311,14 @@ where
         .await
         .map_err(|e| InfraError::Msg(format!("[{label}] body read failed: {e}")))?;
 
+    if let Some(venue) = venue
+        && !status.is_success()
+    {
+        let error = ExchangeError::from_response(venue, status, &bytes);
+        tracing::warn!("[{label}] {error}");
+        return Err(error.into());
+    }
+
     let mut bytes = match bytes.try_into_mut() {
         Ok(bytes) => bytes,
         Err(bytes) => bytes.as_ref().into(),
@@ -316,6 +372,65 @@ pub struct CancelOrderParams {
     pub cli_order_id: Option<String>,
 }
 
+/// New price and/or size for one resting order, addressed by exchange order id
+/// or client order id.
+///
+/// Venues differ in what they need to amend in place:
+/// - OKX amends either field alone.
+/// - Gate futures amends either field alone, but needs `side` to sign a new
+///   size.
+/// - Binance UM and Hyperliquid resend the whole order and need `side`,
+///   `new_price`, and `new_size`. Hyperliquid also resends `order_type` and
+///   `reduce_only`, defaulting to a GTC limit that is not reduce-only.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AmendOrderParams {
+    pub inst: String,
+    pub order_id: Option<String>,
+    pub cli_order_id: Option<String>,
+    pub side: Option<OrderSide>,
+    pub new_price: Option<String>,
+    pub new_size: Option<String>,
+    pub order_type: Option<OrderType>,
+    pub reduce_only: Option<bool>,
+    pub extra: HashMap<String, String>, // general
+}
+
+impl AmendOrderParams {
+    pub fn validate_target(&self) -> InfraResult<()> {
+        if self.order_id.is_none() && self.cli_order_id.is_none() {
+            return Err(InfraError::ApiCliError(
+                "Amend requires order_id or cli_order_id".into(),
+            ));
+        }
+
+        if self.new_price.is_none() && self.new_size.is_none() {
+            return Err(InfraError::ApiCliError(
+                "Amend requires new_price or new_size".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Side, price, and size for venues that resend the whole order.
+    pub fn full_order(&self, venue: &str) -> InfraResult<(&OrderSide, &str, &str)> {
+        let missing = || {
+            InfraError::ApiCliError(format!(
+                "{venue} amend requires side, new_price, and new_size"
+            ))
+        };
+        let side = self
+            .side
+            .as_ref()
+            .filter(|side| !matches!(side, OrderSide::Unknown))
+            .ok_or_else(missing)?;
+        let price = self.new_price.as_deref().ok_or_else(missing)?;
+        let size = self.new_size.as_deref().ok_or_else(missing)?;
+
+        Ok((side, price, size))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -344,6 +459,21 @@ mod tests {
         assert_eq!(micros_to_seconds(timestamp_us), 1_783_580_000);
     }
 
+    #[test]
+    fn swaps_the_operation_of_subscribe_messages() {
+        let unsub = ws_unsubscribe_msg(
+            r#"{"method":"SUBSCRIBE","params":["btcusdt@aggTrade"],"id":1}"#,
+            "method",
+            "UNSUBSCRIBE",
+        )
+        .unwrap();
+        let unsub: Value = serde_json::from_str(&unsub).unwrap();
+        assert_eq!(unsub["method"], "UNSUBSCRIBE");
+        assert_eq!(unsub["params"][0], "btcusdt@aggTrade");
+
+        assert!(ws_unsubscribe_msg(r#"{"args":[]}"#, "op", "unsubscribe").is_err());
+    }
+
     #[test]
     fn rejects_custom_candle_interval_without_known_duration() {
         assert!(candle_interval_millis(&CandleParam::Custom("2m".into())).is_err());
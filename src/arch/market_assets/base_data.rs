@@ -102,3 +102,5 @@ pub const TRADING: &str = "TRADING";
 pub const TRADING_LOWER: &str = "trading";
 pub const SUBSCRIBE: &str = "SUBSCRIBE";
 pub const SUBSCRIBE_LOWER: &str = "subscribe";
+pub const UNSUBSCRIBE: &str = "UNSUBSCRIBE";
+pub const UNSUBSCRIBE_LOWER: &str = "unsubscribe";
@@ -1,5 +1,10 @@
 #![allow(dead_code)]
+pub mod clock_sync;
+pub mod endpoints;
+pub mod exchange_error;
 pub mod prelude;
+pub mod rate_limit;
+pub mod retry;
 
 #[cfg(feature = "hyperliquid")]
 pub mod hyperliquid;
@@ -1,9 +1,14 @@
 use data_encoding::HEXUPPER;
 use hmac::{KeyInit, Mac};
+use std::collections::BTreeMap;
 
-use reqwest::{Client, Response};
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value, json};
 
+use crate::arch::market_assets::exchange::clock_sync::ClockSync;
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
     market_assets::api_general::*,
     redaction::{redact_identifier, redact_secret},
@@ -56,8 +61,8 @@ impl BinanceKey {
         })
     }
 
-    fn sign_now(&self, query_string: Option<&str>) -> InfraResult<Signature<u64>> {
-        let timestamp = get_mills_timestamp();
+    fn sign_now(&self, rest: &str, query_string: Option<&str>) -> InfraResult<Signature<u64>> {
+        let timestamp = ClockSync::now_millis(Venue::Binance, rest);
 
         let query_with_timestamp = match query_string {
             Some(query) => format!("{}&timestamp={}", query, timestamp),
@@ -67,89 +72,128 @@ impl BinanceKey {
         self.sign(&query_with_timestamp, timestamp)
     }
 
-    pub fn ws_sign(&self, query_string: &str) -> InfraResult<Signature<u64>> {
-        let timestamp = get_mills_timestamp();
+    /// Signs `query_string` with the venue time of the deployment at `rest`.
+    pub fn ws_sign(&self, rest: &str, query_string: &str) -> InfraResult<Signature<u64>> {
+        let timestamp = ClockSync::now_millis(Venue::Binance, rest);
         let query_with_timestamp = format!("{}&timestamp={}", query_string, timestamp);
         let mut sign = self.sign(&query_with_timestamp, timestamp)?;
         sign.signature = sign.signature.to_lowercase();
         Ok(sign)
     }
 
-    pub(crate) async fn get_request(
+    /// Builds a signed WebSocket API request for `method`, e.g. `order.place`.
+    ///
+    /// `query_string` carries the request parameters in REST form. The API
+    /// key and timestamp are added, and the parameters are signed in
+    /// alphabetical order as the WebSocket API requires. The timestamp is
+    /// the venue time of the deployment whose REST base URL is `rest`.
+    pub fn ws_api_request(
+        &self,
+        rest: &str,
+        id: u64,
+        method: &str,
+        query_string: &str,
+    ) -> InfraResult<String> {
+        self.ws_api_request_at(
+            id,
+            method,
+            query_string,
+            ClockSync::now_millis(Venue::Binance, rest),
+        )
+    }
+
+    fn ws_api_request_at(
+        &self,
+        id: u64,
+        method: &str,
+        query_string: &str,
+        timestamp: u64,
+    ) -> InfraResult<String> {
+        let mut params: BTreeMap<&str, String> = query_string
+            .split('&')
+            .filter_map(|part| part.split_once('='))
+            .map(|(key, value)| (key, value.to_string()))
+            .collect();
+        params.insert("apiKey", self.api_key.clone());
+        params.insert("timestamp", timestamp.to_string());
+
+        let payload = params
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join("&");
+        let signature = self.sign(&payload, timestamp)?.signature.to_lowercase();
+
+        let mut params: Map<String, Value> = params
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), Value::String(value)))
+            .collect();
+        params.insert("timestamp".into(), json!(timestamp));
+        params.insert("signature".into(), json!(signature));
+
+        let msg = json!({
+            "id": id.to_string(),
+            "method": method,
+            "params": params,
+        });
+
+        Ok(msg.to_string())
+    }
+
+    pub(crate) fn get_request(
         &self,
         client: &Client,
         signature: &Signature<u64>,
         query_string: Option<&str>,
         url: &str,
-    ) -> InfraResult<Response> {
+    ) -> RequestBuilder {
         let full_url = binance_build_full_url(url, query_string, signature);
 
-        let res = client
-            .get(&full_url)
-            .header("X-MBX-APIKEY", &self.api_key)
-            .send()
-            .await?;
-
-        Ok(res)
+        client.get(&full_url).header("X-MBX-APIKEY", &self.api_key)
     }
 
-    pub(crate) async fn post_request(
+    pub(crate) fn post_request(
         &self,
         client: &Client,
         signature: &Signature<u64>,
         query_string: Option<&str>,
         url: &str,
-    ) -> InfraResult<Response> {
+    ) -> RequestBuilder {
         let full_url = binance_build_full_url(url, query_string, signature);
 
-        let res = client
-            .post(&full_url)
-            .header("X-MBX-APIKEY", &self.api_key)
-            .send()
-            .await?;
-
-        Ok(res)
+        client.post(&full_url).header("X-MBX-APIKEY", &self.api_key)
     }
 
-    pub(crate) async fn put_request(
+    pub(crate) fn put_request(
         &self,
         client: &Client,
         signature: &Signature<u64>,
         query_string: Option<&str>,
         url: &str,
-    ) -> InfraResult<Response> {
+    ) -> RequestBuilder {
         let full_url = binance_build_full_url(url, query_string, signature);
 
-        let res = client
-            .put(&full_url)
-            .header("X-MBX-APIKEY", &self.api_key)
-            .send()
-            .await?;
-
-        Ok(res)
+        client.put(&full_url).header("X-MBX-APIKEY", &self.api_key)
     }
 
-    pub(crate) async fn delete_request(
+    pub(crate) fn delete_request(
         &self,
         client: &Client,
         signature: &Signature<u64>,
         query_string: Option<&str>,
         url: &str,
-    ) -> InfraResult<Response> {
+    ) -> RequestBuilder {
         let full_url = binance_build_full_url(url, query_string, signature);
 
-        let res = client
+        client
             .delete(&full_url)
             .header("X-MBX-APIKEY", &self.api_key)
-            .send()
-            .await?;
-
-        Ok(res)
     }
 
     pub(crate) async fn send_signed_request<T>(
         &self,
         client: &Client,
+        retry: &RetryPolicy,
         method: RequestMethod,
         query_string: Option<&str>,
         base_url: &str,
@@ -159,30 +203,23 @@ impl BinanceKey {
         T: DeserializeOwned + Send,
     {
         let encoded_query = encode_query_string(query_string);
-        let signature = self.sign_now(encoded_query.as_deref())?;
+        let query = encoded_query.as_deref();
         let url = [base_url, endpoint].concat();
 
-        let response = match method {
-            RequestMethod::Get => {
-                self.get_request(client, &signature, encoded_query.as_deref(), &url)
-                    .await?
-            },
-            RequestMethod::Put => {
-                self.put_request(client, &signature, encoded_query.as_deref(), &url)
-                    .await?
-            },
-            RequestMethod::Post => {
-                self.post_request(client, &signature, encoded_query.as_deref(), &url)
-                    .await?
-            },
-            RequestMethod::Delete => {
-                self.delete_request(client, &signature, encoded_query.as_deref(), &url)
-                    .await?
-            },
-        };
+        let response = retry
+            .send_with(|| {
+                let signature = self.sign_now(base_url, query)?;
+                Ok(match method {
+                    RequestMethod::Get => self.get_request(client, &signature, query, &url),
+                    RequestMethod::Put => self.put_request(client, &signature, query, &url),
+                    RequestMethod::Post => self.post_request(client, &signature, query, &url),
+                    RequestMethod::Delete => self.delete_request(client, &signature, query, &url),
+                })
+            })
+            .await?;
 
         let label = format!("Binance {:?} {}", method, endpoint);
-        parse_json_response(&label, response).await
+        parse_venue_response(Venue::Binance, &label, response).await
     }
 }
 
@@ -220,4 +257,28 @@ mod tests {
         assert!(!debug.contains("binance_secret_key_1234567890"));
         assert!(debug.contains("[REDACTED]"));
     }
+
+    #[test]
+    fn signs_ws_api_requests_like_binance_docs() {
+        let key = BinanceKey::new(
+            "vmPUZE6mv9SD5VNHk4HlWFsOr6aKE2zvsw0MuIgwCIPy6utIco14y7Ju91duEh8A",
+            "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j",
+        );
+        let query = "symbol=BTCUSDT&side=SELL&type=LIMIT&timeInForce=GTC&quantity=0.01000000\
+                     &price=52000.00&newOrderRespType=ACK&recvWindow=100";
+
+        let msg = key
+            .ws_api_request_at(7, "order.place", query, 1645423376532)
+            .unwrap();
+        let msg: Value = serde_json::from_str(&msg).unwrap();
+
+        assert_eq!(msg["id"], "7");
+        assert_eq!(msg["method"], "order.place");
+        assert_eq!(msg["params"]["timestamp"], 1645423376532_u64);
+        assert_eq!(msg["params"]["price"], "52000.00");
+        assert_eq!(
+            msg["params"]["signature"],
+            "cc15477742bd704c29492d96c7ead9414dfd8e0ec4a00f947bb5bb454ddbd08a"
+        );
+    }
 }
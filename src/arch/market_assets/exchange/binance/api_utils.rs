@@ -6,7 +6,7 @@ use tracing::warn;
 
 use crate::arch::{
     market_assets::{
-        api_general::OrderParams,
+        api_general::{AmendOrderParams, OrderParams},
         base_data::{OrderSide, OrderType, PositionSide, SUBSCRIBE, TimeInForce},
     },
     task_execution::task_ws::{LobFrequency, LobParam},
@@ -92,6 +92,74 @@ impl TryFrom<&OrderParams> for RestBatchOrderParamsBinanceUM {
     }
 }
 
+/// One entry of a Binance UM modify request. Binance resends the whole order,
+/// so side, price, and quantity are all required.
+#[derive(Clone, Debug, Serialize)]
+pub struct RestAmendOrderParamsBinanceUM {
+    symbol: String,
+    #[serde(rename = "orderId", skip_serializing_if = "Option::is_none")]
+    order_id: Option<u64>,
+    #[serde(rename = "origClientOrderId", skip_serializing_if = "Option::is_none")]
+    orig_client_order_id: Option<String>,
+    side: &'static str,
+    quantity: String,
+    price: String,
+    #[serde(flatten)]
+    extra: HashMap<String, String>,
+}
+
+impl RestAmendOrderParamsBinanceUM {
+    pub(crate) fn to_query_string(&self) -> String {
+        let mut parts = vec![format!("symbol={}", self.symbol)];
+
+        if let Some(order_id) = self.order_id {
+            parts.push(format!("orderId={order_id}"));
+        }
+        if let Some(cli_order_id) = self.orig_client_order_id.as_deref() {
+            parts.push(format!("origClientOrderId={cli_order_id}"));
+        }
+        parts.push(format!("side={}", self.side));
+        parts.push(format!("quantity={}", self.quantity));
+        parts.push(format!("price={}", self.price));
+        for (k, v) in &self.extra {
+            parts.push(format!("{k}={v}"));
+        }
+
+        parts.join("&")
+    }
+}
+
+impl TryFrom<&AmendOrderParams> for RestAmendOrderParamsBinanceUM {
+    type Error = InfraError;
+
+    fn try_from(amend: &AmendOrderParams) -> Result<Self, Self::Error> {
+        amend.validate_target()?;
+        let (side, price, size) = amend.full_order("Binance UM")?;
+        let order_id = amend
+            .order_id
+            .as_deref()
+            .map(|order_id| {
+                order_id.parse::<u64>().map_err(|_| {
+                    InfraError::ApiCliError(format!("Invalid Binance UM order_id: {order_id}"))
+                })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            symbol: cli_perp_to_pure_uppercase(&amend.inst),
+            order_id,
+            orig_client_order_id: amend.cli_order_id.clone(),
+            side: match side {
+                OrderSide::BUY => "BUY",
+                _ => "SELL",
+            },
+            quantity: size.to_string(),
+            price: price.to_string(),
+            extra: amend.extra.clone(),
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BinanceUniversalTransferType {
     MainUmFuture,
@@ -941,4 +1009,38 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn builds_binance_um_amend_request() {
+        let amend = AmendOrderParams {
+            inst: "BTC_USDT_PERP".into(),
+            order_id: Some("42".into()),
+            side: Some(OrderSide::BUY),
+            new_price: Some("101".into()),
+            new_size: Some("2".into()),
+            ..Default::default()
+        };
+        let req = RestAmendOrderParamsBinanceUM::try_from(&amend).unwrap();
+
+        assert_eq!(
+            req.to_query_string(),
+            "symbol=BTCUSDT&orderId=42&side=BUY&quantity=2&price=101"
+        );
+        assert_eq!(
+            serde_json::to_value(&req).unwrap(),
+            json!({
+                "symbol": "BTCUSDT",
+                "orderId": 42,
+                "side": "BUY",
+                "quantity": "2",
+                "price": "101",
+            })
+        );
+
+        let price_only = AmendOrderParams {
+            new_size: None,
+            ..amend
+        };
+        assert!(RestAmendOrderParamsBinanceUM::try_from(&price_only).is_err());
+    }
 }
@@ -2,13 +2,19 @@ use reqwest::Client;
 use std::sync::Arc;
 use tracing::error;
 
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
     market_assets::{
         api_data::{account_data::*, utils_data::*},
-        api_general::{RequestMethod, parse_json_response},
+        api_general::{RequestMethod, parse_venue_response, ws_unsubscribe_msg},
         base_data::*,
-        exchange::binance::binance_rest_msg::RestResBinance,
+        exchange::{
+            binance::binance_rest_msg::{RestResBinance, RestServerTimeBinance},
+            endpoints::EndpointProfile,
+        },
     },
+    strategy_base::command::command_core::{ListenKeySource, WsConnectTarget},
     task_execution::task_ws::*,
     traits::{
         conversion::IntoInfraVec,
@@ -31,6 +37,8 @@ use super::{
 pub struct BinanceCmCli {
     pub client: Arc<Client>,
     pub api_key: Option<BinanceKey>,
+    pub endpoints: EndpointProfile,
+    pub retry: RetryPolicy,
 }
 
 impl Default for BinanceCmCli {
@@ -49,6 +57,10 @@ impl LobPublicRest for BinanceCmCli {
         self._get_instrument_info(inst_type).await
     }
 
+    async fn get_server_time(&self) -> InfraResult<u64> {
+        self._get_server_time().await
+    }
+
     async fn get_live_instruments(&self, inst_type: InstrumentType) -> InfraResult<Vec<String>> {
         self._get_live_instruments(inst_type).await
     }
@@ -80,6 +92,15 @@ impl LobWebsocket for BinanceCmCli {
         self._get_public_sub_msg(channel, insts)
     }
 
+    async fn get_public_unsub_msg(
+        &self,
+        channel: &WsChannel,
+        insts: Option<&[String]>,
+    ) -> InfraResult<String> {
+        let sub_msg = self._get_public_sub_msg(channel, insts)?;
+        ws_unsubscribe_msg(&sub_msg, "method", UNSUBSCRIBE)
+    }
+
     async fn get_private_sub_msg(&self, _channel: &WsChannel) -> InfraResult<String> {
         Ok(String::new())
     }
@@ -91,6 +112,22 @@ impl LobWebsocket for BinanceCmCli {
     async fn get_private_connect_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         self._get_private_connect_msg(channel).await
     }
+
+    async fn get_private_connect_target(
+        &self,
+        channel: &WsChannel,
+    ) -> InfraResult<WsConnectTarget> {
+        self._get_private_connect_target(channel)
+    }
+}
+
+/// User data stream events carrying `channel`.
+fn user_data_events(channel: &WsChannel) -> InfraResult<&'static str> {
+    match channel {
+        WsChannel::AccountOrders => Ok("ORDER_TRADE_UPDATE"),
+        WsChannel::AccountPositions | WsChannel::AccountBalAndPos => Ok("ACCOUNT_UPDATE"),
+        _ => Err(InfraError::Unimplemented),
+    }
 }
 
 impl BinanceCmCli {
@@ -98,9 +135,24 @@ impl BinanceCmCli {
         Self {
             client: shared_client,
             api_key: None,
+            endpoints: EndpointProfile::binance_cm_production(),
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Sends requests to `endpoints` instead of Binance COIN-M futures production.
+    pub fn with_endpoints(mut self, endpoints: EndpointProfile) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Times out and retries REST requests with `retry` instead of
+    /// [`RetryPolicy::default`].
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     pub async fn create_listen_key(&self) -> InfraResult<BinanceListenKey> {
         let api_key = self
             .api_key
@@ -110,9 +162,10 @@ impl BinanceCmCli {
         let res: RestResBinance<BinanceListenKey> = api_key
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 None,
-                BINANCE_CM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_CM_FUTURES_LISTEN_KEY,
             )
             .await?;
@@ -137,9 +190,10 @@ impl BinanceCmCli {
         let res: RestResBinance<BinanceListenKey> = api_key
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Put,
                 None,
-                BINANCE_CM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_CM_FUTURES_LISTEN_KEY,
             )
             .await?;
@@ -176,7 +230,7 @@ impl BinanceCmCli {
 
         let mut url = format!(
             "{}/futures/data/openInterestHist?pair={}&contractType={}&period={}",
-            BINANCE_CM_FUTURES_BASE_URL,
+            self.endpoints.rest,
             cli_perp_to_binance_cm_pair(inst),
             contract_type_str,
             period,
@@ -192,9 +246,13 @@ impl BinanceCmCli {
             url.push_str(&format!("&endTime={}", e));
         }
 
-        let response = self.client.get(url).send().await?;
-        let res: RestResBinance<RestOpenInterestBinanceCM> =
-            parse_json_response("BinanceCmFutures open_interest_hist", response).await?;
+        let response = self.retry.send(self.client.get(url)).await?;
+        let res: RestResBinance<RestOpenInterestBinanceCM> = parse_venue_response(
+            Venue::Binance,
+            "BinanceCmFutures open_interest_hist",
+            response,
+        )
+        .await?;
 
         let data = res
             .into_vec()?
@@ -205,19 +263,36 @@ impl BinanceCmCli {
         Ok(data)
     }
 
+    async fn _get_server_time(&self) -> InfraResult<u64> {
+        let url = [self.endpoints.rest.as_str(), BINANCE_CM_FUTURES_SERVER_TIME].concat();
+
+        let response = self.retry.send(self.client.get(url)).await?;
+        let res: RestResBinance<RestServerTimeBinance> =
+            parse_venue_response(Venue::Binance, "BinanceCmFutures server_time", response).await?;
+
+        let data = res
+            .into_vec()?
+            .into_iter()
+            .next()
+            .ok_or(InfraError::ApiCliError("No server time returned".into()))?;
+
+        Ok(data.serverTime)
+    }
+
     async fn _get_instrument_info(
         &self,
         inst_type: InstrumentType,
     ) -> InfraResult<Vec<InstrumentInfo>> {
         let url = [
-            BINANCE_CM_FUTURES_BASE_URL,
+            self.endpoints.rest.as_str(),
             BINANCE_CM_FUTURES_EXCHANGE_INFO,
         ]
         .concat();
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResBinance<RestExchangeInfoBinanceCM> =
-            parse_json_response("BinanceCmFutures instrument_info", response).await?;
+            parse_venue_response(Venue::Binance, "BinanceCmFutures instrument_info", response)
+                .await?;
 
         let data = res
             .into_vec()?
@@ -254,9 +329,10 @@ impl BinanceCmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 None,
-                BINANCE_CM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_CM_FUTURES_BALANCE_INFO,
             )
             .await?;
@@ -289,8 +365,8 @@ impl BinanceCmCli {
 
     fn _get_public_connect_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         let url = match channel {
-            WsChannel::Candles(_) | WsChannel::Trades(_) => BINANCE_CM_FUTURES_WS_MKT,
-            WsChannel::Lob(_) => BINANCE_CM_FUTURES_WS_PUB,
+            WsChannel::Candles(_) | WsChannel::Trades(_) => &self.endpoints.ws_business,
+            WsChannel::Lob(_) => &self.endpoints.ws_public,
             _ => return Err(InfraError::Unimplemented),
         };
 
@@ -298,20 +374,27 @@ impl BinanceCmCli {
     }
 
     async fn _get_private_connect_msg(&self, channel: &WsChannel) -> InfraResult<String> {
-        let events = match channel {
-            WsChannel::AccountOrders => "ORDER_TRADE_UPDATE",
-            WsChannel::AccountPositions | WsChannel::AccountBalAndPos => "ACCOUNT_UPDATE",
-            _ => return Err(InfraError::Unimplemented),
-        };
+        let events = user_data_events(channel)?;
 
         let listen_key = self.create_listen_key().await?;
 
         Ok(format!(
             "{}?listenKey={}&events={}",
-            BINANCE_CM_FUTURES_WS_PRI, listen_key.listenKey, events
+            self.endpoints.ws_private, listen_key.listenKey, events
         ))
     }
 
+    /// User data stream targets leave the listen key to the relay, which
+    /// creates it on connect and keeps it alive.
+    fn _get_private_connect_target(&self, channel: &WsChannel) -> InfraResult<WsConnectTarget> {
+        let events = user_data_events(channel)?;
+
+        Ok(
+            WsConnectTarget::new(format!("{}?events={}", self.endpoints.ws_private, events))
+                .with_listen_key(ListenKeySource::BinanceCm(self.clone())),
+        )
+    }
+
     fn _ws_subscribe_candle(
         &self,
         candle_param: &Option<CandleParam>,
@@ -2,8 +2,14 @@ use serde::{Deserialize, Deserializer, Serialize, de::DeserializeOwned, de::Erro
 use serde_json::Value;
 use tracing::warn;
 
-use crate::arch::traits::conversion::IntoInfraVec;
-use crate::errors::{InfraError, InfraResult};
+use crate::arch::{
+    market_assets::{
+        api_general::de_micros_from_int,
+        exchange::exchange_error::{ErrorCategory, ExchangeError, Venue},
+    },
+    traits::conversion::IntoInfraVec,
+};
+use crate::errors::InfraResult;
 
 #[derive(Clone, Debug, Serialize)]
 pub enum RestResBinance<T> {
@@ -18,6 +24,14 @@ pub struct BinanceCodeMsg {
     pub msg: String,
 }
 
+/// Body of the spot and futures `time` endpoints.
+#[allow(non_snake_case)]
+#[derive(Clone, Debug, Deserialize)]
+pub struct RestServerTimeBinance {
+    #[serde(deserialize_with = "de_micros_from_int")]
+    pub serverTime: u64,
+}
+
 impl<T> IntoInfraVec<T> for RestResBinance<T> {
     fn into_vec(self) -> InfraResult<Vec<T>> {
         match self {
@@ -25,10 +39,7 @@ impl<T> IntoInfraVec<T> for RestResBinance<T> {
             Self::Object(o) => Ok(vec![o]),
             Self::CodeMsg(BinanceCodeMsg { code, msg }) => {
                 warn!("Binance REST error {}: {}", code, msg);
-                Err(InfraError::ApiCliError(format!(
-                    "Binance REST error (code={}): {}",
-                    code, msg
-                )))
+                Err(ExchangeError::binance(code, msg).into())
             },
         }
     }
@@ -73,6 +84,43 @@ where
     }
 }
 
+impl ExchangeError {
+    /// Binance rejection with its numeric `code`.
+    pub(crate) fn binance(code: i64, msg: impl Into<String>) -> Self {
+        let msg = msg.into();
+        Self::new(
+            Venue::Binance,
+            binance_category(code, &msg),
+            code.to_string(),
+            msg,
+        )
+    }
+
+    pub(crate) fn binance_body(body: &[u8]) -> Option<Self> {
+        let body: BinanceCodeMsg = serde_json::from_slice(body).ok()?;
+        Some(Self::binance(body.code, body.msg))
+    }
+}
+
+fn binance_category(code: i64, msg: &str) -> ErrorCategory {
+    match code {
+        -2018 | -2019 => ErrorCategory::InsufficientBalance,
+        -1003 | -1015 => ErrorCategory::RateLimited,
+        -4014 | -4016 | -4024 => ErrorCategory::InvalidPrice,
+        -5022 => ErrorCategory::PostOnlyWouldCross,
+        // Spot rejects post-only (LIMIT_MAKER) orders with the generic
+        // "new order rejected" code.
+        -2010 if msg.contains("immediately match") => ErrorCategory::PostOnlyWouldCross,
+        -2010 if msg.contains("insufficient balance") => ErrorCategory::InsufficientBalance,
+        -2022 => ErrorCategory::ReduceOnlyRejected,
+        -2011 | -2013 => ErrorCategory::UnknownOrder,
+        -1022 | -2014 | -2015 => ErrorCategory::AuthFailed,
+        -1008 => ErrorCategory::Maintenance,
+        -1007 => ErrorCategory::Timeout,
+        _ => ErrorCategory::Other,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,6 +157,30 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn maps_codes_to_categories() {
+        let res: RestResBinance<Value> =
+            serde_json::from_str(r#"{"code":-2019,"msg":"Margin is insufficient."}"#).unwrap();
+        match res.into_vec() {
+            Err(crate::errors::InfraError::Exchange(e)) => {
+                assert_eq!(e.category, ErrorCategory::InsufficientBalance);
+                assert_eq!(e.code, "-2019");
+            },
+            other => panic!("unexpected result {other:?}"),
+        }
+
+        assert_eq!(
+            binance_category(-2010, "Order would immediately match and take."),
+            ErrorCategory::PostOnlyWouldCross
+        );
+        assert_eq!(
+            ExchangeError::binance_body(br#"{"code":-2015,"msg":"Invalid API-key"}"#)
+                .unwrap()
+                .category,
+            ErrorCategory::AuthFailed
+        );
+    }
+
     #[test]
     fn does_not_swallow_code_msg_error_as_value() {
         let res: RestResBinance<Value> =
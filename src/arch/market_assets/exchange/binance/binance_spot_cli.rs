@@ -3,17 +3,25 @@ use serde_json::json;
 use std::sync::Arc;
 use tracing::error;
 
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
     market_assets::{
         api_data::{account_data::*, price_data::*, utils_data::*},
         api_general::*,
         base_data::*,
-        exchange::binance::binance_rest_msg::RestResBinance,
+        exchange::{
+            binance::{
+                binance_rest_msg::{RestResBinance, RestServerTimeBinance},
+                binance_ws_msg::BinanceWsApiRes,
+            },
+            endpoints::EndpointProfile,
+        },
     },
     task_execution::task_ws::WsChannel,
     traits::{
         conversion::IntoInfraVec,
-        market_lob::{LobPrivateRest, LobPublicRest, LobWebsocket, MarketLobApi},
+        market_lob::{LobPrivateRest, LobPublicRest, LobWebsocket, LobWsOrderEntry, MarketLobApi},
     },
 };
 use crate::errors::{InfraError, InfraResult};
@@ -47,6 +55,8 @@ use super::{
 pub struct BinanceSpotCli {
     pub client: Arc<Client>,
     pub api_key: Option<BinanceKey>,
+    pub endpoints: EndpointProfile,
+    pub retry: RetryPolicy,
 }
 
 impl Default for BinanceSpotCli {
@@ -72,6 +82,10 @@ impl LobPublicRest for BinanceSpotCli {
     ) -> InfraResult<Vec<InstrumentInfo>> {
         self._get_instrument_info(inst_type).await
     }
+
+    async fn get_server_time(&self) -> InfraResult<u64> {
+        self._get_server_time().await
+    }
 }
 
 impl LobPrivateRest for BinanceSpotCli {
@@ -87,7 +101,9 @@ impl LobPrivateRest for BinanceSpotCli {
     }
 
     async fn place_order(&self, order_params: OrderParams) -> InfraResult<OrderAckData> {
-        self._place_order(order_params).await
+        self.retry
+            .place_order(self, order_params, |params| self._place_order(params))
+            .await
     }
 
     async fn cancel_order(
@@ -107,6 +123,15 @@ impl LobPrivateRest for BinanceSpotCli {
         self._get_open_orders(inst, limit).await
     }
 
+    async fn get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        self._get_order(inst, order_id, cli_order_id).await
+    }
+
     async fn get_balance(&self, assets: Option<&[String]>) -> InfraResult<Vec<BalanceData>> {
         self._get_balance(assets).await
     }
@@ -129,8 +154,27 @@ impl LobWebsocket for BinanceSpotCli {
         self._get_private_sub_msg(channel)
     }
 
-    async fn get_private_connect_msg(&self, _channel: &WsChannel) -> InfraResult<String> {
-        Ok(BINANCE_SPOT_WS_API.into())
+    async fn get_private_connect_msg(&self, channel: &WsChannel) -> InfraResult<String> {
+        match channel {
+            WsChannel::OrderEntry => Ok(self.endpoints.ws_trade.clone()),
+            _ => Ok(self.endpoints.ws_private.clone()),
+        }
+    }
+}
+
+impl LobWsOrderEntry for BinanceSpotCli {
+    fn ws_place_orders_msg(&self, id: u64, orders: &[OrderParams]) -> InfraResult<String> {
+        self._ws_place_orders_msg(id, orders)
+    }
+
+    fn ws_cancel_orders_msg(&self, id: u64, cancels: &[CancelOrderParams]) -> InfraResult<String> {
+        self._ws_cancel_orders_msg(id, cancels)
+    }
+
+    fn parse_ws_order_response(&self, frame: &[u8]) -> Option<WsOrderResponse> {
+        serde_json::from_slice::<BinanceWsApiRes<RestOrderAckBinanceSpot>>(frame)
+            .ok()?
+            .into_order_response()
     }
 }
 
@@ -139,9 +183,24 @@ impl BinanceSpotCli {
         Self {
             client: shared_client,
             api_key: None,
+            endpoints: EndpointProfile::binance_spot_production(),
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Sends requests to `endpoints` instead of Binance spot production.
+    pub fn with_endpoints(mut self, endpoints: EndpointProfile) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Times out and retries REST requests with `retry` instead of
+    /// [`RetryPolicy::default`].
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     pub async fn user_universal_transfer(
         &self,
         req: BinanceUniversalTransferReq,
@@ -152,9 +211,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 Some(&req.to_query_string()),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_USER_UNIVERSAL_TRANSFER,
             )
             .await?;
@@ -181,9 +241,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 Some(&query),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_SUB_ACCOUNT_UNIVERSAL_TRANSFER,
             )
             .await?;
@@ -250,9 +311,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_WITHDRAW_ADDRESS_LIST,
             )
             .await?;
@@ -270,9 +332,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&req.to_query_string()),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_USER_UNIVERSAL_TRANSFER,
             )
             .await?;
@@ -299,9 +362,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 query.as_deref(),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_SUB_ACCOUNT_UNIVERSAL_TRANSFER,
             )
             .await?;
@@ -324,9 +388,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 Some(&req.to_query_string()),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_WITHDRAW_APPLY,
             )
             .await?;
@@ -354,9 +419,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 query.as_deref(),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_WITHDRAW_HISTORY,
             )
             .await?;
@@ -376,9 +442,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 query.as_deref(),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_CAPITAL_CONFIG_GETALL,
             )
             .await?;
@@ -412,9 +479,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_DEPOSIT_ADDRESS,
             )
             .await?;
@@ -447,9 +515,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_DEPOSIT_ADDRESS_LIST,
             )
             .await?;
@@ -469,9 +538,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 query.as_deref(),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_DEPOSIT_HISTORY,
             )
             .await?;
@@ -484,10 +554,10 @@ impl BinanceSpotCli {
         insts: Option<&[String]>,
         _inst_type: Option<InstrumentType>,
     ) -> InfraResult<Vec<TickerData>> {
-        let url = format!("{}{}", BINANCE_SPOT_BASE_URL, BINANCE_SPOT_TICKERS);
-        let response = self.client.get(url).send().await?;
+        let url = format!("{}{}", self.endpoints.rest, BINANCE_SPOT_TICKERS);
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResBinance<RestTickerBinanceSpot> =
-            parse_json_response("BinanceSpot tickers", response).await?;
+            parse_venue_response(Venue::Binance, "BinanceSpot tickers", response).await?;
 
         let data = res
             .into_vec()?
@@ -504,15 +574,31 @@ impl BinanceSpotCli {
         Ok(data)
     }
 
+    async fn _get_server_time(&self) -> InfraResult<u64> {
+        let url = [self.endpoints.rest.as_str(), BINANCE_SPOT_SERVER_TIME].concat();
+
+        let response = self.retry.send(self.client.get(url)).await?;
+        let res: RestResBinance<RestServerTimeBinance> =
+            parse_venue_response(Venue::Binance, "BinanceSpot server_time", response).await?;
+
+        let data = res
+            .into_vec()?
+            .into_iter()
+            .next()
+            .ok_or(InfraError::ApiCliError("No server time returned".into()))?;
+
+        Ok(data.serverTime)
+    }
+
     async fn _get_instrument_info(
         &self,
         _inst_type: InstrumentType,
     ) -> InfraResult<Vec<InstrumentInfo>> {
-        let url = [BINANCE_SPOT_BASE_URL, BINANCE_SPOT_EXCHANGE_INFO].concat();
+        let url = [self.endpoints.rest.as_str(), BINANCE_SPOT_EXCHANGE_INFO].concat();
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.retry.send(self.client.get(&url)).await?;
         let res: RestResBinance<RestExchangeInfoBinanceSpot> =
-            parse_json_response("BinanceSpot instrument_info", response).await?;
+            parse_venue_response(Venue::Binance, "BinanceSpot instrument_info", response).await?;
 
         let data = res
             .into_vec()?
@@ -532,57 +618,7 @@ impl BinanceSpotCli {
     async fn _place_order(&self, order_params: OrderParams) -> InfraResult<OrderAckData> {
         order_params.validate_side_and_type()?;
 
-        let (quantity_param, quantity) =
-            if let Some(quote_order_qty) = order_params.extra.get("quoteOrderQty") {
-                ("quoteOrderQty", quote_order_qty)
-            } else {
-                ("quantity", &order_params.size)
-            };
-        let mut query_string = format!(
-            "symbol={}&side={}&type={}&{}={}&newOrderRespType=RESULT",
-            order_params.inst.to_uppercase(),
-            match order_params.side {
-                OrderSide::BUY => "BUY",
-                OrderSide::SELL => "SELL",
-                _ => "BUY",
-            },
-            match order_params.order_type {
-                OrderType::Limit => "LIMIT",
-                OrderType::Market => "MARKET",
-                OrderType::PostOnly => "LIMIT_MAKER",
-                OrderType::Fok => "FOK",
-                OrderType::Ioc => "IOC",
-                OrderType::Unknown => "MARKET",
-            },
-            quantity_param,
-            quantity,
-        );
-
-        if let Some(price) = &order_params.price {
-            query_string.push_str(&format!("&price={}", price));
-        }
-
-        if let Some(tif) = &order_params.time_in_force {
-            let tif_str = match tif {
-                TimeInForce::GTC => "GTC",
-                TimeInForce::IOC => "IOC",
-                TimeInForce::FOK => "FOK",
-                TimeInForce::GTD => "GTD",
-                TimeInForce::Unknown => "GTC",
-            };
-            query_string.push_str(&format!("&timeInForce={}", tif_str));
-        }
-
-        if let Some(cid) = &order_params.client_order_id {
-            query_string.push_str(&format!("&newClientOrderId={}", cid));
-        }
-
-        for (k, v) in &order_params.extra {
-            if k == "quoteOrderQty" {
-                continue;
-            }
-            query_string.push_str(&format!("&{}={}", k, v));
-        }
+        let query_string = spot_order_query(&order_params);
 
         let res: RestResBinance<RestOrderAckBinanceSpot> = self
             .api_key
@@ -590,9 +626,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 Some(&query_string),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_SPOT_PLACE_ORDER,
             )
             .await?;
@@ -619,15 +656,7 @@ impl BinanceSpotCli {
             ));
         }
 
-        let mut query_string = format!("symbol={}", cli_spot_to_binance_spot(inst));
-
-        if let Some(oid) = order_id {
-            query_string.push_str(&format!("&orderId={}", oid));
-        }
-
-        if let Some(cid) = cli_order_id {
-            query_string.push_str(&format!("&origClientOrderId={}", cid));
-        }
+        let query_string = spot_cancel_query(inst, order_id, cli_order_id);
 
         let res: RestResBinance<RestOrderAckBinanceSpot> = self
             .api_key
@@ -635,9 +664,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Delete,
                 Some(&query_string),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_SPOT_CANCEL_ORDER,
             )
             .await?;
@@ -670,9 +700,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query_string),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_SPOT_OPEN_ORDERS,
             )
             .await?;
@@ -696,9 +727,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 None,
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_SPOT_ACCOUNT_INFO,
             )
             .await?;
@@ -722,6 +754,46 @@ impl BinanceSpotCli {
         Ok(data)
     }
 
+    async fn _get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        let mut query_string = format!("symbol={}", cli_spot_to_binance_spot(inst));
+        match (order_id, cli_order_id) {
+            (Some(order_id), _) => query_string.push_str(&format!("&orderId={order_id}")),
+            (None, Some(cli_order_id)) => {
+                query_string.push_str(&format!("&origClientOrderId={cli_order_id}"))
+            },
+            (None, None) => {
+                return Err(InfraError::ApiCliError(
+                    "Binance Spot get_order requires order_id or cli_order_id".into(),
+                ));
+            },
+        }
+
+        let res: RestResBinance<RestOrderHistoryBinanceSpot> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Get,
+                Some(&query_string),
+                &self.endpoints.rest,
+                BINANCE_SPOT_PLACE_ORDER,
+            )
+            .await?;
+
+        Ok(res
+            .into_vec()?
+            .into_iter()
+            .next()
+            .map(OrderDetailData::from))
+    }
+
     async fn _get_order_history(
         &self,
         inst: &str,
@@ -753,9 +825,10 @@ impl BinanceSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query_string),
-                BINANCE_SPOT_BASE_URL,
+                &self.endpoints.rest,
                 endpoint,
             )
             .await?;
@@ -777,7 +850,7 @@ impl BinanceSpotCli {
 
         let recv_window = 5000_u64;
         let query_string = format!("apiKey={}&recvWindow={}", api_key.api_key, recv_window);
-        let signature = api_key.ws_sign(&query_string)?;
+        let signature = api_key.ws_sign(&self.endpoints.rest, &query_string)?;
 
         let msg = json!({
             "id": 1,
@@ -792,4 +865,119 @@ impl BinanceSpotCli {
 
         Ok(msg.to_string())
     }
+
+    /// The WebSocket API has no batch order method, so requests carry one
+    /// order each.
+    fn _ws_place_orders_msg(&self, id: u64, orders: &[OrderParams]) -> InfraResult<String> {
+        let [order] = orders else {
+            return Err(InfraError::ApiCliError(
+                "Binance Spot WebSocket API places one order per request".into(),
+            ));
+        };
+        order.validate_side_and_type()?;
+
+        self.api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .ws_api_request(
+                &self.endpoints.rest,
+                id,
+                "order.place",
+                &spot_order_query(order),
+            )
+    }
+
+    fn _ws_cancel_orders_msg(&self, id: u64, cancels: &[CancelOrderParams]) -> InfraResult<String> {
+        let [cancel] = cancels else {
+            return Err(InfraError::ApiCliError(
+                "Binance Spot WebSocket API cancels one order per request".into(),
+            ));
+        };
+        if cancel.order_id.is_none() && cancel.cli_order_id.is_none() {
+            return Err(InfraError::ApiCliError(
+                "Binance Spot cancel_order requires order_id or cli_order_id".into(),
+            ));
+        }
+
+        let query_string = spot_cancel_query(
+            &cancel.inst,
+            cancel.order_id.as_deref(),
+            cancel.cli_order_id.as_deref(),
+        );
+        self.api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .ws_api_request(&self.endpoints.rest, id, "order.cancel", &query_string)
+    }
+}
+/// Query parameters of one order, sent by REST and the WebSocket API.
+fn spot_order_query(order_params: &OrderParams) -> String {
+    let (quantity_param, quantity) =
+        if let Some(quote_order_qty) = order_params.extra.get("quoteOrderQty") {
+            ("quoteOrderQty", quote_order_qty)
+        } else {
+            ("quantity", &order_params.size)
+        };
+    let mut query_string = format!(
+        "symbol={}&side={}&type={}&{}={}&newOrderRespType=RESULT",
+        order_params.inst.to_uppercase(),
+        match order_params.side {
+            OrderSide::BUY => "BUY",
+            OrderSide::SELL => "SELL",
+            _ => "BUY",
+        },
+        match order_params.order_type {
+            OrderType::Limit => "LIMIT",
+            OrderType::Market => "MARKET",
+            OrderType::PostOnly => "LIMIT_MAKER",
+            OrderType::Fok => "FOK",
+            OrderType::Ioc => "IOC",
+            OrderType::Unknown => "MARKET",
+        },
+        quantity_param,
+        quantity,
+    );
+
+    if let Some(price) = &order_params.price {
+        query_string.push_str(&format!("&price={}", price));
+    }
+
+    if let Some(tif) = &order_params.time_in_force {
+        let tif_str = match tif {
+            TimeInForce::GTC => "GTC",
+            TimeInForce::IOC => "IOC",
+            TimeInForce::FOK => "FOK",
+            TimeInForce::GTD => "GTD",
+            TimeInForce::Unknown => "GTC",
+        };
+        query_string.push_str(&format!("&timeInForce={}", tif_str));
+    }
+
+    if let Some(cid) = &order_params.client_order_id {
+        query_string.push_str(&format!("&newClientOrderId={}", cid));
+    }
+
+    for (k, v) in &order_params.extra {
+        if k == "quoteOrderQty" {
+            continue;
+        }
+        query_string.push_str(&format!("&{}={}", k, v));
+    }
+
+    query_string
+}
+
+/// Query parameters of one cancel, sent by REST and the WebSocket API.
+fn spot_cancel_query(inst: &str, order_id: Option<&str>, cli_order_id: Option<&str>) -> String {
+    let mut query_string = format!("symbol={}", cli_spot_to_binance_spot(inst));
+
+    if let Some(oid) = order_id {
+        query_string.push_str(&format!("&orderId={}", oid));
+    }
+
+    if let Some(cid) = cli_order_id {
+        query_string.push_str(&format!("&origClientOrderId={}", cid));
+    }
+
+    query_string
 }
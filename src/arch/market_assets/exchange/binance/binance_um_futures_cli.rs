@@ -1,19 +1,28 @@
 use reqwest::Client;
 use serde_json::Value;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tracing::error;
 
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
     market_assets::{
         api_data::{account_data::*, price_data::*, utils_data::*},
         api_general::*,
         base_data::*,
-        exchange::binance::binance_rest_msg::RestResBinance,
+        exchange::{
+            binance::{
+                binance_rest_msg::{RestResBinance, RestServerTimeBinance},
+                binance_ws_msg::BinanceWsApiRes,
+            },
+            endpoints::EndpointProfile,
+        },
     },
+    strategy_base::command::command_core::{ListenKeySource, SnapshotSource, WsConnectTarget},
     task_execution::task_ws::*,
     traits::{
         conversion::IntoInfraVec,
-        market_lob::{LobPrivateRest, LobPublicRest, LobWebsocket, MarketLobApi},
+        market_lob::{LobPrivateRest, LobPublicRest, LobWebsocket, LobWsOrderEntry, MarketLobApi},
     },
 };
 use crate::errors::{InfraError, InfraResult};
@@ -42,13 +51,16 @@ use super::{
     },
 };
 
-const BINANCE_UM_BATCH_PLACE_LIMIT: usize = 5;
-const BINANCE_UM_BATCH_CANCEL_LIMIT: usize = 10;
+pub(crate) const BINANCE_UM_BATCH_PLACE_LIMIT: usize = 5;
+pub(crate) const BINANCE_UM_BATCH_CANCEL_LIMIT: usize = 10;
+pub(crate) const BINANCE_UM_BATCH_AMEND_LIMIT: usize = 5;
 
 #[derive(Clone, Debug)]
 pub struct BinanceUmCli {
     pub client: Arc<Client>,
     pub api_key: Option<BinanceKey>,
+    pub endpoints: EndpointProfile,
+    pub retry: RetryPolicy,
 }
 
 impl Default for BinanceUmCli {
@@ -97,6 +109,10 @@ impl LobPublicRest for BinanceUmCli {
         self._get_instrument_info(inst_type).await
     }
 
+    async fn get_server_time(&self) -> InfraResult<u64> {
+        self._get_server_time().await
+    }
+
     async fn get_live_instruments(&self, inst_type: InstrumentType) -> InfraResult<Vec<String>> {
         self._get_live_instruments(inst_type).await
     }
@@ -115,7 +131,9 @@ impl LobPrivateRest for BinanceUmCli {
     }
 
     async fn place_order(&self, order_params: OrderParams) -> InfraResult<OrderAckData> {
-        self._place_order(order_params).await
+        self.retry
+            .place_order(self, order_params, |params| self._place_order(params))
+            .await
     }
 
     async fn place_orders(&self, order_params: Vec<OrderParams>) -> InfraResult<Vec<OrderAckData>> {
@@ -138,6 +156,25 @@ impl LobPrivateRest for BinanceUmCli {
         self._cancel_orders(cancel_params).await
     }
 
+    async fn amend_order(&self, amend_params: AmendOrderParams) -> InfraResult<OrderAckData> {
+        self._amend_order(amend_params).await
+    }
+
+    async fn amend_orders(
+        &self,
+        amend_params: Vec<AmendOrderParams>,
+    ) -> InfraResult<Vec<OrderAckData>> {
+        self._amend_orders(amend_params).await
+    }
+
+    async fn cancel_all_orders(&self, inst: Option<&str>) -> InfraResult<Vec<OrderAckData>> {
+        self._cancel_all_orders(inst).await
+    }
+
+    async fn set_cancel_after(&self, inst: Option<&str>, timeout: Duration) -> InfraResult<()> {
+        self._set_cancel_after(inst, timeout).await
+    }
+
     async fn get_open_orders(
         &self,
         inst: &str,
@@ -146,6 +183,15 @@ impl LobPrivateRest for BinanceUmCli {
         self._get_open_orders(inst, limit).await
     }
 
+    async fn get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        self._get_order(inst, order_id, cli_order_id).await
+    }
+
     async fn get_balance(&self, assets: Option<&[String]>) -> InfraResult<Vec<BalanceData>> {
         self._get_balance(assets).await
     }
@@ -176,6 +222,15 @@ impl LobWebsocket for BinanceUmCli {
         self._get_public_sub_msg(channel, insts)
     }
 
+    async fn get_public_unsub_msg(
+        &self,
+        channel: &WsChannel,
+        insts: Option<&[String]>,
+    ) -> InfraResult<String> {
+        let sub_msg = self._get_public_sub_msg(channel, insts)?;
+        ws_unsubscribe_msg(&sub_msg, "method", UNSUBSCRIBE)
+    }
+
     async fn get_private_sub_msg(&self, _channel: &WsChannel) -> InfraResult<String> {
         Ok(String::new())
     }
@@ -184,9 +239,51 @@ impl LobWebsocket for BinanceUmCli {
         self._get_public_connect_msg(channel)
     }
 
+    async fn get_public_connect_target(&self, channel: &WsChannel) -> InfraResult<WsConnectTarget> {
+        let target = WsConnectTarget::new(self._get_public_connect_msg(channel)?);
+        match channel {
+            WsChannel::Lob(_) => {
+                Ok(target.with_snapshot_source(SnapshotSource::BinanceUm(self.clone())))
+            },
+            _ => Ok(target),
+        }
+    }
+
     async fn get_private_connect_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         self._get_private_connect_msg(channel).await
     }
+
+    async fn get_private_connect_target(
+        &self,
+        channel: &WsChannel,
+    ) -> InfraResult<WsConnectTarget> {
+        self._get_private_connect_target(channel)
+    }
+}
+
+/// User data stream events carrying `channel`.
+fn user_data_events(channel: &WsChannel) -> InfraResult<&'static str> {
+    match channel {
+        WsChannel::AccountOrders | WsChannel::AccountFills => Ok("ORDER_TRADE_UPDATE"),
+        WsChannel::AccountPositions | WsChannel::AccountBalAndPos => Ok("ACCOUNT_UPDATE"),
+        _ => Err(InfraError::Unimplemented),
+    }
+}
+
+impl LobWsOrderEntry for BinanceUmCli {
+    fn ws_place_orders_msg(&self, id: u64, orders: &[OrderParams]) -> InfraResult<String> {
+        self._ws_place_orders_msg(id, orders)
+    }
+
+    fn ws_cancel_orders_msg(&self, id: u64, cancels: &[CancelOrderParams]) -> InfraResult<String> {
+        self._ws_cancel_orders_msg(id, cancels)
+    }
+
+    fn parse_ws_order_response(&self, frame: &[u8]) -> Option<WsOrderResponse> {
+        serde_json::from_slice::<BinanceWsApiRes<RestOrderAckBinanceUM>>(frame)
+            .ok()?
+            .into_order_response()
+    }
 }
 
 impl BinanceUmCli {
@@ -194,9 +291,24 @@ impl BinanceUmCli {
         Self {
             client: shared_client,
             api_key: None,
+            endpoints: EndpointProfile::binance_um_production(),
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Sends requests to `endpoints` instead of Binance USDⓈ-M futures production.
+    pub fn with_endpoints(mut self, endpoints: EndpointProfile) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Times out and retries REST requests with `retry` instead of
+    /// [`RetryPolicy::default`].
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     pub async fn create_listen_key(&self) -> InfraResult<BinanceListenKey> {
         let api_key = self
             .api_key
@@ -206,9 +318,10 @@ impl BinanceUmCli {
         let res: RestResBinance<BinanceListenKey> = api_key
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 None,
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_LISTEN_KEY,
             )
             .await?;
@@ -233,9 +346,10 @@ impl BinanceUmCli {
         let res: RestResBinance<BinanceListenKey> = api_key
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Put,
                 None,
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_LISTEN_KEY,
             )
             .await?;
@@ -268,9 +382,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 Some(&query_string),
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_CHANGE_LEVERAGE,
             )
             .await?;
@@ -291,9 +406,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 None,
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_POSITION_MODE,
             )
             .await?;
@@ -321,9 +437,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 Some(&query_string),
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_POSITION_MODE,
             )
             .await?;
@@ -346,9 +463,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 None,
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_ACCOUNT_CONFIG,
             )
             .await?;
@@ -376,9 +494,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 query_string.as_deref(),
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_SYMBOL_CONFIG,
             )
             .await?;
@@ -396,7 +515,7 @@ impl BinanceUmCli {
     ) -> InfraResult<Vec<CandleData>> {
         let mut url = format!(
             "{}{}?symbol={}&interval={}",
-            BINANCE_UM_FUTURES_BASE_URL,
+            self.endpoints.rest,
             BINANCE_UM_FUTURES_PREMIUM_INDEX_KLINES,
             cli_perp_to_pure_uppercase(inst),
             interval
@@ -412,9 +531,13 @@ impl BinanceUmCli {
             url.push_str(&format!("&endTime={}", end));
         }
 
-        let response = self.client.get(url).send().await?;
-        let res: RestResBinance<Vec<Value>> =
-            parse_json_response("BinanceUmFutures premium_index_klines", response).await?;
+        let response = self.retry.send(self.client.get(url)).await?;
+        let res: RestResBinance<Vec<Value>> = parse_venue_response(
+            Venue::Binance,
+            "BinanceUmFutures premium_index_klines",
+            response,
+        )
+        .await?;
 
         let candles = res
             .into_vec()?
@@ -438,7 +561,7 @@ impl BinanceUmCli {
         inst: Option<&str>,
     ) -> InfraResult<Vec<RestPremiumIndexBinanceUM>> {
         let mut url = [
-            BINANCE_UM_FUTURES_BASE_URL,
+            self.endpoints.rest.as_str(),
             BINANCE_UM_FUTURES_PREMIUM_INDEX,
         ]
         .concat();
@@ -448,9 +571,10 @@ impl BinanceUmCli {
             url.push_str(&format!("?symbol={}", normalized));
         }
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResBinance<RestPremiumIndexBinanceUM> =
-            parse_json_response("BinanceUmFutures premium_index", response).await?;
+            parse_venue_response(Venue::Binance, "BinanceUmFutures premium_index", response)
+                .await?;
 
         res.into_vec()
     }
@@ -460,7 +584,7 @@ impl BinanceUmCli {
         inst: Option<&str>,
     ) -> InfraResult<Vec<FundingRateData>> {
         let mut url = [
-            BINANCE_UM_FUTURES_BASE_URL,
+            self.endpoints.rest.as_str(),
             BINANCE_UM_FUTURES_PREMIUM_INDEX,
         ]
         .concat();
@@ -470,9 +594,13 @@ impl BinanceUmCli {
             url.push_str(&format!("?symbol={}", normalized));
         }
 
-        let response = self.client.get(url).send().await?;
-        let res: RestResBinance<RestPremiumIndexBinanceUM> =
-            parse_json_response("BinanceUmFutures funding_rate_live", response).await?;
+        let response = self.retry.send(self.client.get(url)).await?;
+        let res: RestResBinance<RestPremiumIndexBinanceUM> = parse_venue_response(
+            Venue::Binance,
+            "BinanceUmFutures funding_rate_live",
+            response,
+        )
+        .await?;
 
         let data = res
             .into_vec()?
@@ -484,11 +612,15 @@ impl BinanceUmCli {
     }
 
     pub async fn get_funding_info(&self) -> InfraResult<Vec<FundingRateInfo>> {
-        let url = [BINANCE_UM_FUTURES_BASE_URL, BINANCE_UM_FUTURES_FUNDING_INFO].concat();
+        let url = [
+            self.endpoints.rest.as_str(),
+            BINANCE_UM_FUTURES_FUNDING_INFO,
+        ]
+        .concat();
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResBinance<RestFundingInfoBinanceUM> =
-            parse_json_response("BinanceUmFutures funding_info", response).await?;
+            parse_venue_response(Venue::Binance, "BinanceUmFutures funding_info", response).await?;
 
         let data = res
             .into_vec()?
@@ -506,7 +638,7 @@ impl BinanceUmCli {
         start_time: Option<u64>,
         end_time: Option<u64>,
     ) -> InfraResult<Vec<FundingRateData>> {
-        let mut url = format!("{}/fapi/v1/fundingRate?", BINANCE_UM_FUTURES_BASE_URL);
+        let mut url = format!("{}/fapi/v1/fundingRate?", self.endpoints.rest);
 
         if let Some(s) = inst {
             url.push_str(&format!("symbol={}", cli_perp_to_pure_uppercase(s)));
@@ -521,9 +653,13 @@ impl BinanceUmCli {
             url.push_str(&format!("&endTime={}", e));
         }
 
-        let response = self.client.get(url).send().await?;
-        let res: RestResBinance<RestFundingRateBinanceUM> =
-            parse_json_response("BinanceUmFutures funding_rate_history", response).await?;
+        let response = self.retry.send(self.client.get(url)).await?;
+        let res: RestResBinance<RestFundingRateBinanceUM> = parse_venue_response(
+            Venue::Binance,
+            "BinanceUmFutures funding_rate_history",
+            response,
+        )
+        .await?;
 
         let data = res
             .into_vec()?
@@ -544,7 +680,7 @@ impl BinanceUmCli {
     ) -> InfraResult<Vec<OpenInterest>> {
         let mut url = format!(
             "{}/futures/data/openInterestHist?symbol={}&period={}",
-            BINANCE_UM_FUTURES_BASE_URL,
+            self.endpoints.rest,
             cli_perp_to_pure_uppercase(inst),
             period,
         );
@@ -559,9 +695,13 @@ impl BinanceUmCli {
             url.push_str(&format!("&endTime={}", e));
         }
 
-        let response = self.client.get(url).send().await?;
-        let res: RestResBinance<RestOpenInterestBinanceUM> =
-            parse_json_response("BinanceUmFutures open_interest_hist", response).await?;
+        let response = self.retry.send(self.client.get(url)).await?;
+        let res: RestResBinance<RestOpenInterestBinanceUM> = parse_venue_response(
+            Venue::Binance,
+            "BinanceUmFutures open_interest_hist",
+            response,
+        )
+        .await?;
 
         let data = res
             .into_vec()?
@@ -596,14 +736,14 @@ impl BinanceUmCli {
 
         let url = format!(
             "{}{}?{}",
-            BINANCE_UM_FUTURES_BASE_URL,
+            self.endpoints.rest,
             BINANCE_UM_FUTURES_KLINES,
             params.join("&")
         );
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResBinance<RestCandleBinanceUM> =
-            parse_json_response("BinanceUmFutures candles", response).await?;
+            parse_venue_response(Venue::Binance, "BinanceUmFutures candles", response).await?;
 
         let mut data: Vec<CandleData> = res
             .into_vec()?
@@ -635,13 +775,13 @@ impl BinanceUmCli {
 
         let url = format!(
             "{}{}?{}",
-            BINANCE_UM_FUTURES_BASE_URL,
+            self.endpoints.rest,
             BINANCE_UM_FUTURES_DEPTH,
             params.join("&")
         );
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResBinance<RestOrderBookBinanceUM> =
-            parse_json_response("BinanceUmFutures orderbook", response).await?;
+            parse_venue_response(Venue::Binance, "BinanceUmFutures orderbook", response).await?;
 
         res.into_vec()?
             .into_iter()
@@ -655,11 +795,11 @@ impl BinanceUmCli {
         insts: Option<&[String]>,
         _inst_type: Option<InstrumentType>,
     ) -> InfraResult<Vec<TickerData>> {
-        let url = [BINANCE_UM_FUTURES_BASE_URL, BINANCE_UM_FUTURES_TICKERS].concat();
+        let url = [self.endpoints.rest.as_str(), BINANCE_UM_FUTURES_TICKERS].concat();
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResBinance<RestTickerBinanceUM> =
-            parse_json_response("BinanceUmFutures tickers", response).await?;
+            parse_venue_response(Venue::Binance, "BinanceUmFutures tickers", response).await?;
 
         let data = res
             .into_vec()?
@@ -674,19 +814,36 @@ impl BinanceUmCli {
         Ok(data)
     }
 
+    async fn _get_server_time(&self) -> InfraResult<u64> {
+        let url = [self.endpoints.rest.as_str(), BINANCE_UM_FUTURES_SERVER_TIME].concat();
+
+        let response = self.retry.send(self.client.get(url)).await?;
+        let res: RestResBinance<RestServerTimeBinance> =
+            parse_venue_response(Venue::Binance, "BinanceUmFutures server_time", response).await?;
+
+        let data = res
+            .into_vec()?
+            .into_iter()
+            .next()
+            .ok_or(InfraError::ApiCliError("No server time returned".into()))?;
+
+        Ok(data.serverTime)
+    }
+
     async fn _get_instrument_info(
         &self,
         inst_type: InstrumentType,
     ) -> InfraResult<Vec<InstrumentInfo>> {
         let url = [
-            BINANCE_UM_FUTURES_BASE_URL,
+            self.endpoints.rest.as_str(),
             BINANCE_UM_FUTURES_EXCHANGE_INFO,
         ]
         .concat();
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.retry.send(self.client.get(&url)).await?;
         let res: RestResBinance<RestExchangeInfoBinanceUM> =
-            parse_json_response("BinanceUmFutures instrument_info", response).await?;
+            parse_venue_response(Venue::Binance, "BinanceUmFutures instrument_info", response)
+                .await?;
 
         let data = res
             .into_vec()?
@@ -719,61 +876,7 @@ impl BinanceUmCli {
     async fn _place_order(&self, order_params: OrderParams) -> InfraResult<OrderAckData> {
         order_params.validate_side_and_type()?;
 
-        let mut query_string = format!(
-            "symbol={}&side={}&type={}&quantity={}",
-            cli_perp_to_pure_uppercase(&order_params.inst),
-            match order_params.side {
-                OrderSide::BUY => "BUY",
-                OrderSide::SELL => "SELL",
-                _ => "BUY",
-            },
-            match order_params.order_type {
-                OrderType::Limit => "LIMIT",
-                OrderType::Market => "MARKET",
-                OrderType::PostOnly => "POST_ONLY",
-                OrderType::Fok => "FOK",
-                OrderType::Ioc => "IOC",
-                OrderType::Unknown => "MARKET",
-            },
-            order_params.size,
-        );
-
-        if let Some(price) = &order_params.price {
-            query_string.push_str(&format!("&price={}", price));
-        }
-
-        if let Some(ro) = order_params.reduce_only {
-            query_string.push_str(&format!("&reduceOnly={}", ro));
-        }
-
-        if let Some(ps) = &order_params.position_side {
-            let ps_str = match ps {
-                PositionSide::Long => "LONG",
-                PositionSide::Short => "SHORT",
-                PositionSide::Both => "BOTH",
-                PositionSide::Unknown => "BOTH",
-            };
-            query_string.push_str(&format!("&positionSide={}", ps_str));
-        }
-
-        if let Some(tif) = &order_params.time_in_force {
-            let tif_str = match tif {
-                TimeInForce::GTC => "GTC",
-                TimeInForce::IOC => "IOC",
-                TimeInForce::FOK => "FOK",
-                TimeInForce::GTD => "GTD",
-                TimeInForce::Unknown => "GTC",
-            };
-            query_string.push_str(&format!("&timeInForce={}", tif_str));
-        }
-
-        if let Some(cid) = &order_params.client_order_id {
-            query_string.push_str(&format!("&newClientOrderId={}", cid));
-        }
-
-        for (k, v) in &order_params.extra {
-            query_string.push_str(&format!("&{}={}", k, v));
-        }
+        let query_string = um_order_query(&order_params);
 
         let res: RestResBinance<RestOrderAckBinanceUM> = self
             .api_key
@@ -781,9 +884,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 Some(&query_string),
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_PLACE_ORDER_INFO,
             )
             .await?;
@@ -822,9 +926,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 Some(&query_string),
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_BATCH_ORDERS,
             )
             .await?;
@@ -859,13 +964,7 @@ impl BinanceUmCli {
             ));
         }
 
-        let mut query_string = format!("symbol={}", cli_perp_to_pure_uppercase(inst));
-        if let Some(order_id) = order_id {
-            query_string.push_str(&format!("&orderId={order_id}"));
-        }
-        if let Some(cli_order_id) = cli_order_id {
-            query_string.push_str(&format!("&origClientOrderId={cli_order_id}"));
-        }
+        let query_string = um_cancel_query(inst, order_id, cli_order_id);
 
         let res: RestResBinance<RestOrderAckBinanceUM> = self
             .api_key
@@ -873,9 +972,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Delete,
                 Some(&query_string),
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_CANCEL_ORDER,
             )
             .await?;
@@ -892,6 +992,85 @@ impl BinanceUmCli {
         Ok(data)
     }
 
+    async fn _amend_order(&self, amend_params: AmendOrderParams) -> InfraResult<OrderAckData> {
+        let query_string =
+            RestAmendOrderParamsBinanceUM::try_from(&amend_params)?.to_query_string();
+        let res: RestResBinance<RestOrderAckBinanceUM> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Put,
+                Some(&query_string),
+                &self.endpoints.rest,
+                BINANCE_UM_FUTURES_PLACE_ORDER_INFO,
+            )
+            .await?;
+
+        let data: OrderAckData = res
+            .into_vec()?
+            .into_iter()
+            .map(OrderAckData::from)
+            .next()
+            .ok_or(InfraError::ApiCliError(
+                "No Binance UM amend ack data returned".into(),
+            ))?;
+
+        Ok(data)
+    }
+
+    async fn _amend_orders(
+        &self,
+        amend_params: Vec<AmendOrderParams>,
+    ) -> InfraResult<Vec<OrderAckData>> {
+        if amend_params.is_empty() {
+            return Ok(Vec::new());
+        }
+        if amend_params.len() > BINANCE_UM_BATCH_AMEND_LIMIT {
+            return Err(InfraError::ApiCliError(format!(
+                "Binance UM batch amend supports at most {BINANCE_UM_BATCH_AMEND_LIMIT} orders"
+            )));
+        }
+
+        let orders = amend_params
+            .iter()
+            .map(RestAmendOrderParamsBinanceUM::try_from)
+            .collect::<InfraResult<Vec<_>>>()?;
+        let query_string = format!("batchOrders={}", serde_json::to_string(&orders)?);
+        let res: RestResBinance<RestBatchOrderAckBinanceUM> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Put,
+                Some(&query_string),
+                &self.endpoints.rest,
+                BINANCE_UM_FUTURES_BATCH_ORDERS,
+            )
+            .await?;
+
+        let response = res.into_vec()?;
+        if response.len() != amend_params.len() {
+            return Err(InfraError::ApiCliError(format!(
+                "Binance UM batch amend returned {} result(s) for {} order(s)",
+                response.len(),
+                amend_params.len()
+            )));
+        }
+
+        let data: Vec<OrderAckData> = response
+            .into_iter()
+            .zip(amend_params)
+            .map(|(ack, amend)| ack.into_order_ack(amend.order_id, amend.cli_order_id))
+            .collect();
+
+        Ok(data)
+    }
+
     async fn _cancel_orders(
         &self,
         cancel_params: Vec<CancelOrderParams>,
@@ -965,9 +1144,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Delete,
                 Some(&query_string),
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_BATCH_ORDERS,
             )
             .await?;
@@ -990,6 +1170,59 @@ impl BinanceUmCli {
         Ok(data)
     }
 
+    async fn _cancel_all_orders(&self, inst: Option<&str>) -> InfraResult<Vec<OrderAckData>> {
+        let inst = inst.ok_or(InfraError::ApiCliError(
+            "Binance UM cancel_all_orders requires inst".into(),
+        ))?;
+        let query_string = format!("symbol={}", cli_perp_to_pure_uppercase(inst));
+
+        // Binance only confirms the request, without listing the canceled orders.
+        let res: RestResBinance<Value> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Delete,
+                Some(&query_string),
+                &self.endpoints.rest,
+                BINANCE_UM_FUTURES_ALL_OPEN_ORDERS,
+            )
+            .await?;
+        res.into_vec()?;
+
+        Ok(Vec::new())
+    }
+
+    async fn _set_cancel_after(&self, inst: Option<&str>, timeout: Duration) -> InfraResult<()> {
+        let inst = inst.ok_or(InfraError::ApiCliError(
+            "Binance UM set_cancel_after requires inst".into(),
+        ))?;
+        let query_string = format!(
+            "symbol={}&countdownTime={}",
+            cli_perp_to_pure_uppercase(inst),
+            timeout.as_millis()
+        );
+
+        let res: RestResBinance<Value> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Post,
+                Some(&query_string),
+                &self.endpoints.rest,
+                BINANCE_UM_FUTURES_COUNTDOWN_CANCEL_ALL,
+            )
+            .await?;
+        res.into_vec()?;
+
+        Ok(())
+    }
+
     async fn _get_open_orders(
         &self,
         inst: &str,
@@ -1006,9 +1239,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query_string),
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_OPEN_ORDERS,
             )
             .await?;
@@ -1032,9 +1266,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 None,
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_BALANCE_INFO,
             )
             .await?;
@@ -1059,9 +1294,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 None,
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_POSITION_RISK_INFO,
             )
             .await?;
@@ -1079,6 +1315,46 @@ impl BinanceUmCli {
         Ok(data)
     }
 
+    async fn _get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        let mut query_string = format!("symbol={}", cli_perp_to_pure_uppercase(inst));
+        match (order_id, cli_order_id) {
+            (Some(order_id), _) => query_string.push_str(&format!("&orderId={order_id}")),
+            (None, Some(cli_order_id)) => {
+                query_string.push_str(&format!("&origClientOrderId={cli_order_id}"))
+            },
+            (None, None) => {
+                return Err(InfraError::ApiCliError(
+                    "Binance UM get_order requires order_id or cli_order_id".into(),
+                ));
+            },
+        }
+
+        let res: RestResBinance<RestOrderHistoryBinanceUM> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Get,
+                Some(&query_string),
+                &self.endpoints.rest,
+                BINANCE_UM_FUTURES_PLACE_ORDER_INFO,
+            )
+            .await?;
+
+        Ok(res
+            .into_vec()?
+            .into_iter()
+            .next()
+            .map(OrderDetailData::from))
+    }
+
     async fn _get_order_history(
         &self,
         inst: &str,
@@ -1111,9 +1387,10 @@ impl BinanceUmCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query_string),
-                BINANCE_UM_FUTURES_BASE_URL,
+                &self.endpoints.rest,
                 BINANCE_UM_FUTURES_ALL_ORDERS,
             )
             .await?;
@@ -1142,8 +1419,8 @@ impl BinanceUmCli {
 
     fn _get_public_connect_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         let url = match channel {
-            WsChannel::Candles(_) | WsChannel::Trades(_) => BINANCE_UM_FUTURES_WS_MKT,
-            WsChannel::Lob(_) => BINANCE_UM_FUTURES_WS_PUB,
+            WsChannel::Candles(_) | WsChannel::Trades(_) => &self.endpoints.ws_business,
+            WsChannel::Lob(_) => &self.endpoints.ws_public,
             _ => return Err(InfraError::Unimplemented),
         };
 
@@ -1151,20 +1428,77 @@ impl BinanceUmCli {
     }
 
     async fn _get_private_connect_msg(&self, channel: &WsChannel) -> InfraResult<String> {
-        let events = match channel {
-            WsChannel::AccountOrders => "ORDER_TRADE_UPDATE",
-            WsChannel::AccountPositions | WsChannel::AccountBalAndPos => "ACCOUNT_UPDATE",
-            _ => return Err(InfraError::Unimplemented),
-        };
+        if channel == &WsChannel::OrderEntry {
+            return Ok(self.endpoints.ws_trade.clone());
+        }
+        let events = user_data_events(channel)?;
 
         let listen_key = self.create_listen_key().await?;
 
         Ok(format!(
             "{}?listenKey={}&events={}",
-            BINANCE_UM_FUTURES_WS_PRI, listen_key.listenKey, events
+            self.endpoints.ws_private, listen_key.listenKey, events
         ))
     }
 
+    /// User data stream targets leave the listen key to the relay, which
+    /// creates it on connect and keeps it alive.
+    fn _get_private_connect_target(&self, channel: &WsChannel) -> InfraResult<WsConnectTarget> {
+        if channel == &WsChannel::OrderEntry {
+            return Ok(WsConnectTarget::new(&self.endpoints.ws_trade));
+        }
+        let events = user_data_events(channel)?;
+
+        Ok(
+            WsConnectTarget::new(format!("{}?events={}", self.endpoints.ws_private, events))
+                .with_listen_key(ListenKeySource::BinanceUm(self.clone())),
+        )
+    }
+
+    /// The WebSocket API has no batch order method, so requests carry one
+    /// order each.
+    fn _ws_place_orders_msg(&self, id: u64, orders: &[OrderParams]) -> InfraResult<String> {
+        let [order] = orders else {
+            return Err(InfraError::ApiCliError(
+                "Binance UM WebSocket API places one order per request".into(),
+            ));
+        };
+        order.validate_side_and_type()?;
+
+        self.api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .ws_api_request(
+                &self.endpoints.rest,
+                id,
+                "order.place",
+                &um_order_query(order),
+            )
+    }
+
+    fn _ws_cancel_orders_msg(&self, id: u64, cancels: &[CancelOrderParams]) -> InfraResult<String> {
+        let [cancel] = cancels else {
+            return Err(InfraError::ApiCliError(
+                "Binance UM WebSocket API cancels one order per request".into(),
+            ));
+        };
+        if cancel.order_id.is_none() && cancel.cli_order_id.is_none() {
+            return Err(InfraError::ApiCliError(
+                "Binance UM cancel_order requires order_id or cli_order_id".into(),
+            ));
+        }
+
+        let query_string = um_cancel_query(
+            &cancel.inst,
+            cancel.order_id.as_deref(),
+            cancel.cli_order_id.as_deref(),
+        );
+        self.api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .ws_api_request(&self.endpoints.rest, id, "order.cancel", &query_string)
+    }
+
     fn _ws_subscribe_candle(
         &self,
         candle_param: &Option<CandleParam>,
@@ -1191,3 +1525,164 @@ impl BinanceUmCli {
         Ok(ws_subscribe_msg_binance(&channel, insts))
     }
 }
+
+/// Query parameters of one order, sent by REST and the WebSocket API.
+fn um_order_query(order_params: &OrderParams) -> String {
+    let mut query_string = format!(
+        "symbol={}&side={}&type={}&quantity={}",
+        cli_perp_to_pure_uppercase(&order_params.inst),
+        match order_params.side {
+            OrderSide::BUY => "BUY",
+            OrderSide::SELL => "SELL",
+            _ => "BUY",
+        },
+        match order_params.order_type {
+            OrderType::Limit => "LIMIT",
+            OrderType::Market => "MARKET",
+            OrderType::PostOnly => "POST_ONLY",
+            OrderType::Fok => "FOK",
+            OrderType::Ioc => "IOC",
+            OrderType::Unknown => "MARKET",
+        },
+        order_params.size,
+    );
+
+    if let Some(price) = &order_params.price {
+        query_string.push_str(&format!("&price={}", price));
+    }
+
+    if let Some(ro) = order_params.reduce_only {
+        query_string.push_str(&format!("&reduceOnly={}", ro));
+    }
+
+    if let Some(ps) = &order_params.position_side {
+        let ps_str = match ps {
+            PositionSide::Long => "LONG",
+            PositionSide::Short => "SHORT",
+            PositionSide::Both => "BOTH",
+            PositionSide::Unknown => "BOTH",
+        };
+        query_string.push_str(&format!("&positionSide={}", ps_str));
+    }
+
+    if let Some(tif) = &order_params.time_in_force {
+        let tif_str = match tif {
+            TimeInForce::GTC => "GTC",
+            TimeInForce::IOC => "IOC",
+            TimeInForce::FOK => "FOK",
+            TimeInForce::GTD => "GTD",
+            TimeInForce::Unknown => "GTC",
+        };
+        query_string.push_str(&format!("&timeInForce={}", tif_str));
+    }
+
+    if let Some(cid) = &order_params.client_order_id {
+        query_string.push_str(&format!("&newClientOrderId={}", cid));
+    }
+
+    for (k, v) in &order_params.extra {
+        query_string.push_str(&format!("&{}={}", k, v));
+    }
+
+    query_string
+}
+
+/// Query parameters of one cancel, sent by REST and the WebSocket API.
+fn um_cancel_query(inst: &str, order_id: Option<&str>, cli_order_id: Option<&str>) -> String {
+    let mut query_string = format!("symbol={}", cli_perp_to_pure_uppercase(inst));
+    if let Some(order_id) = order_id {
+        query_string.push_str(&format!("&orderId={order_id}"));
+    }
+    if let Some(cli_order_id) = cli_order_id {
+        query_string.push_str(&format!("&origClientOrderId={cli_order_id}"));
+    }
+
+    query_string
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn testnet_profile_routes_public_streams_to_testnet() {
+        let cli = BinanceUmCli::default().with_endpoints(EndpointProfile::binance_um_testnet());
+
+        assert_eq!(
+            cli._get_public_connect_msg(&WsChannel::Trades(None))
+                .unwrap(),
+            BINANCE_UM_FUTURES_TESTNET_WS_MKT
+        );
+        assert_eq!(
+            cli._get_public_connect_msg(&WsChannel::Lob(None)).unwrap(),
+            BINANCE_UM_FUTURES_TESTNET_WS_PUB
+        );
+    }
+
+    #[tokio::test]
+    async fn private_targets_leave_the_listen_key_to_the_relay() {
+        let cli = BinanceUmCli::default();
+
+        let target = cli
+            .get_private_connect_target(&WsChannel::AccountFills)
+            .await
+            .unwrap();
+        assert_eq!(
+            target.url,
+            format!("{}?events=ORDER_TRADE_UPDATE", cli.endpoints.ws_private)
+        );
+        assert!(matches!(
+            target.listen_key.as_deref(),
+            Some(ListenKeySource::BinanceUm(_))
+        ));
+
+        let order_entry = cli
+            .get_private_connect_target(&WsChannel::OrderEntry)
+            .await
+            .unwrap();
+        assert_eq!(order_entry.url, cli.endpoints.ws_trade);
+        assert!(order_entry.listen_key.is_none());
+    }
+
+    #[tokio::test]
+    async fn order_entry_connects_to_ws_api_and_reads_responses() {
+        let cli = BinanceUmCli::default().with_endpoints(EndpointProfile::binance_um_testnet());
+        assert_eq!(
+            cli.get_private_connect_msg(&WsChannel::OrderEntry)
+                .await
+                .unwrap(),
+            BINANCE_UM_FUTURES_TESTNET_WS_API
+        );
+
+        let placed = cli
+            .parse_ws_order_response(
+                br#"{"id":"3","status":200,"result":{"orderId":325078477,"symbol":"BTCUSDT",
+                "status":"NEW","clientOrderId":"a1","updateTime":1700000000000},"rateLimits":[]}"#,
+            )
+            .unwrap();
+        assert_eq!(placed.id, 3);
+        let ack = &placed.result.unwrap()[0];
+        assert_eq!(ack.order_id, "325078477");
+        assert_eq!(ack.order_status, OrderStatus::Live);
+
+        let failed = cli
+            .parse_ws_order_response(
+                br#"{"id":"4","status":400,"error":{"code":-2011,"msg":"Unknown order sent."}}"#,
+            )
+            .unwrap();
+        assert_eq!(failed.id, 4);
+        assert!(failed.result.is_err());
+
+        let order = OrderParams {
+            inst: "BTC_USDT_PERP".into(),
+            side: OrderSide::BUY,
+            size: "1".into(),
+            order_type: OrderType::Market,
+            ..Default::default()
+        };
+        assert!(matches!(
+            cli.ws_place_orders_msg(5, &[order.clone(), order]),
+            Err(InfraError::ApiCliError(_))
+        ));
+    }
+}
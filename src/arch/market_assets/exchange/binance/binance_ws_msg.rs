@@ -3,7 +3,12 @@ use serde_json::Value;
 use tracing::{info, warn};
 
 use crate::arch::{
-    task_execution::ws_runner::ws_decode::decode_preferred, traits::conversion::IntoWsData,
+    market_assets::{
+        api_data::account_data::{OrderAckData, WsOrderResponse},
+        exchange::exchange_error::ExchangeError,
+    },
+    task_execution::ws_runner::ws_decode::decode_preferred,
+    traits::conversion::IntoWsData,
 };
 
 #[derive(Clone, Debug, Deserialize)]
@@ -41,6 +46,37 @@ pub struct BinanceWsError {
     pub msg: String,
 }
 
+/// Response to a WebSocket API request such as `order.place` or
+/// `order.cancel`.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct BinanceWsApiRes<T> {
+    pub id: Value,
+    pub status: u16,
+    pub result: Option<T>,
+    pub error: Option<BinanceWsError>,
+}
+
+impl<T> BinanceWsApiRes<T>
+where
+    OrderAckData: From<T>,
+{
+    /// Returns `None` for responses without a numeric request id or without
+    /// a result or error.
+    pub(crate) fn into_order_response(self) -> Option<WsOrderResponse> {
+        let id = match &self.id {
+            Value::String(id) => id.parse().ok()?,
+            id => id.as_u64()?,
+        };
+        let result = match (self.result, self.error) {
+            (Some(result), _) if self.status == 200 => Ok(vec![OrderAckData::from(result)]),
+            (_, Some(error)) => Err(ExchangeError::binance(error.code, error.msg).into()),
+            _ => return None,
+        };
+
+        Some(WsOrderResponse { id, result })
+    }
+}
+
 impl<T: DeserializeOwned> BinanceWsData<T> {
     pub(crate) fn decode_single(frame: &[u8]) -> serde_json::Result<Self> {
         decode_preferred(frame, Self::ChannelSingle)
@@ -1,7 +1,18 @@
+use reqwest::Method;
+use tokio::time::Duration;
+
+use crate::arch::market_assets::exchange::{
+    endpoints::EndpointProfile,
+    rate_limit::{EndpointRule, HeaderHint, HintKind, RateLimitProfile, RequestPriority},
+};
+
 /// Spot API
 pub const BINANCE_SPOT_BASE_URL: &str = "https://api1.binance.com";
 pub const BINANCE_SPOT_WS_API: &str = "wss://ws-api.binance.com:443/ws-api/v3";
+pub const BINANCE_SPOT_TESTNET_BASE_URL: &str = "https://testnet.binance.vision";
+pub const BINANCE_SPOT_TESTNET_WS_API: &str = "wss://ws-api.testnet.binance.vision/ws-api/v3";
 pub const BINANCE_SPOT_EXCHANGE_INFO: &str = "/api/v3/exchangeInfo";
+pub const BINANCE_SPOT_SERVER_TIME: &str = "/api/v3/time";
 pub const BINANCE_SPOT_TICKERS: &str = "/api/v3/ticker/price";
 pub const BINANCE_SPOT_PLACE_ORDER: &str = "/api/v3/order";
 pub const BINANCE_SPOT_CANCEL_ORDER: &str = "/api/v3/order";
@@ -24,14 +35,23 @@ pub const BINANCE_WITHDRAW_HISTORY: &str = "/sapi/v1/capital/withdraw/history";
 pub const BINANCE_UM_FUTURES_WS_PRI: &str = "wss://fstream.binance.com/private/ws";
 pub const BINANCE_UM_FUTURES_WS_PUB: &str = "wss://fstream.binance.com/public/ws";
 pub const BINANCE_UM_FUTURES_WS_MKT: &str = "wss://fstream.binance.com/market/ws";
+pub const BINANCE_UM_FUTURES_WS_API: &str = "wss://ws-fapi.binance.com/ws-fapi/v1";
 pub const BINANCE_UM_FUTURES_BASE_URL: &str = "https://fapi.binance.com";
+pub const BINANCE_UM_FUTURES_TESTNET_WS_PRI: &str = "wss://fstream.binancefuture.com/private/ws";
+pub const BINANCE_UM_FUTURES_TESTNET_WS_PUB: &str = "wss://fstream.binancefuture.com/public/ws";
+pub const BINANCE_UM_FUTURES_TESTNET_WS_MKT: &str = "wss://fstream.binancefuture.com/market/ws";
+pub const BINANCE_UM_FUTURES_TESTNET_WS_API: &str = "wss://testnet.binancefuture.com/ws-fapi/v1";
+pub const BINANCE_UM_FUTURES_TESTNET_BASE_URL: &str = "https://testnet.binancefuture.com";
 pub const BINANCE_UM_FUTURES_EXCHANGE_INFO: &str = "/fapi/v1/exchangeInfo";
+pub const BINANCE_UM_FUTURES_SERVER_TIME: &str = "/fapi/v1/time";
 pub const BINANCE_UM_FUTURES_ACCOUNT_INFO: &str = "/fapi/v3/account";
 pub const BINANCE_UM_FUTURES_BALANCE_INFO: &str = "/fapi/v3/balance";
 pub const BINANCE_UM_FUTURES_PLACE_ORDER_INFO: &str = "/fapi/v1/order";
 pub const BINANCE_UM_FUTURES_CANCEL_ORDER: &str = "/fapi/v1/order";
 pub const BINANCE_UM_FUTURES_BATCH_ORDERS: &str = "/fapi/v1/batchOrders";
 pub const BINANCE_UM_FUTURES_OPEN_ORDERS: &str = "/fapi/v1/openOrders";
+pub const BINANCE_UM_FUTURES_ALL_OPEN_ORDERS: &str = "/fapi/v1/allOpenOrders";
+pub const BINANCE_UM_FUTURES_COUNTDOWN_CANCEL_ALL: &str = "/fapi/v1/countdownCancelAll";
 pub const BINANCE_UM_FUTURES_CHANGE_LEVERAGE: &str = "/fapi/v1/leverage";
 pub const BINANCE_UM_FUTURES_POSITION_MODE: &str = "/fapi/v1/positionSide/dual";
 pub const BINANCE_UM_FUTURES_ACCOUNT_CONFIG: &str = "/fapi/v1/accountConfig";
@@ -51,8 +71,207 @@ pub const BINANCE_CM_FUTURES_WS_PRI: &str = "wss://dstream.binance.com/private/w
 pub const BINANCE_CM_FUTURES_WS_PUB: &str = "wss://dstream.binance.com/public/ws";
 pub const BINANCE_CM_FUTURES_WS_MKT: &str = "wss://dstream.binance.com/market/ws";
 pub const BINANCE_CM_FUTURES_BASE_URL: &str = "https://dapi.binance.com";
+pub const BINANCE_CM_FUTURES_TESTNET_WS_PRI: &str = "wss://dstream.binancefuture.com/private/ws";
+pub const BINANCE_CM_FUTURES_TESTNET_WS_PUB: &str = "wss://dstream.binancefuture.com/public/ws";
+pub const BINANCE_CM_FUTURES_TESTNET_WS_MKT: &str = "wss://dstream.binancefuture.com/market/ws";
+pub const BINANCE_CM_FUTURES_TESTNET_BASE_URL: &str = "https://testnet.binancefuture.com";
 pub const BINANCE_CM_FUTURES_EXCHANGE_INFO: &str = "/dapi/v1/exchangeInfo";
+pub const BINANCE_CM_FUTURES_SERVER_TIME: &str = "/dapi/v1/time";
 
 pub const BINANCE_CM_FUTURES_ACCOUNT_INFO: &str = "/dapi/v1/account";
 pub const BINANCE_CM_FUTURES_BALANCE_INFO: &str = "/dapi/v1/balance";
 pub const BINANCE_CM_FUTURES_LISTEN_KEY: &str = "/dapi/v1/listenKey";
+
+// Binance futures profiles put the `market` stream endpoint in `ws_business`
+// and the USDⓈ-M WebSocket API in `ws_trade`.
+impl EndpointProfile {
+    /// Binance spot production. Every websocket URL is the WebSocket API.
+    pub fn binance_spot_production() -> Self {
+        Self::new(BINANCE_SPOT_BASE_URL, BINANCE_SPOT_WS_API)
+    }
+
+    /// Binance spot testnet; testnet API keys are required.
+    pub fn binance_spot_testnet() -> Self {
+        Self::new(BINANCE_SPOT_TESTNET_BASE_URL, BINANCE_SPOT_TESTNET_WS_API).with_testnet(true)
+    }
+
+    /// Binance USDⓈ-M futures production.
+    pub fn binance_um_production() -> Self {
+        Self::new(BINANCE_UM_FUTURES_BASE_URL, BINANCE_UM_FUTURES_WS_PUB)
+            .with_ws_private(BINANCE_UM_FUTURES_WS_PRI)
+            .with_ws_business(BINANCE_UM_FUTURES_WS_MKT)
+            .with_ws_trade(BINANCE_UM_FUTURES_WS_API)
+    }
+
+    /// Binance USDⓈ-M futures testnet; testnet API keys are required.
+    pub fn binance_um_testnet() -> Self {
+        Self::new(
+            BINANCE_UM_FUTURES_TESTNET_BASE_URL,
+            BINANCE_UM_FUTURES_TESTNET_WS_PUB,
+        )
+        .with_ws_private(BINANCE_UM_FUTURES_TESTNET_WS_PRI)
+        .with_ws_business(BINANCE_UM_FUTURES_TESTNET_WS_MKT)
+        .with_ws_trade(BINANCE_UM_FUTURES_TESTNET_WS_API)
+        .with_testnet(true)
+    }
+
+    /// Binance COIN-M futures production.
+    pub fn binance_cm_production() -> Self {
+        Self::new(BINANCE_CM_FUTURES_BASE_URL, BINANCE_CM_FUTURES_WS_PUB)
+            .with_ws_private(BINANCE_CM_FUTURES_WS_PRI)
+            .with_ws_business(BINANCE_CM_FUTURES_WS_MKT)
+    }
+
+    /// Binance COIN-M futures testnet; testnet API keys are required.
+    pub fn binance_cm_testnet() -> Self {
+        Self::new(
+            BINANCE_CM_FUTURES_TESTNET_BASE_URL,
+            BINANCE_CM_FUTURES_TESTNET_WS_PUB,
+        )
+        .with_ws_private(BINANCE_CM_FUTURES_TESTNET_WS_PRI)
+        .with_ws_business(BINANCE_CM_FUTURES_TESTNET_WS_MKT)
+        .with_testnet(true)
+    }
+}
+
+/// Binance rate limit buckets, named as in `exchangeInfo.rateLimits`.
+pub const BINANCE_REQUEST_WEIGHT: &str = "REQUEST_WEIGHT";
+pub const BINANCE_ORDERS_10S: &str = "ORDERS_10S";
+pub const BINANCE_ORDERS_1M: &str = "ORDERS_1M";
+
+const MINUTE: Duration = Duration::from_secs(60);
+const TEN_SECONDS: Duration = Duration::from_secs(10);
+
+fn weight(path: &str, weight: u32) -> EndpointRule {
+    EndpointRule::exact(path).with_cost(BINANCE_REQUEST_WEIGHT, weight)
+}
+
+/// Order placement, counted against the order buckets as well.
+fn order(rule: EndpointRule, count: u32) -> EndpointRule {
+    rule.with_cost(BINANCE_ORDERS_10S, count)
+        .with_cost(BINANCE_ORDERS_1M, count)
+        .with_priority(RequestPriority::Trade)
+}
+
+fn cancel(path: &str) -> EndpointRule {
+    weight(path, 1)
+        .with_method(Method::DELETE)
+        .with_priority(RequestPriority::Cancel)
+}
+
+/// Headers Binance reports the IP weight and account order count in. Order
+/// counts are kept per `X-MBX-APIKEY`.
+fn with_binance_hints(profile: RateLimitProfile) -> RateLimitProfile {
+    profile
+        .with_account_header("X-MBX-APIKEY")
+        .with_hint(HeaderHint::new(
+            "X-MBX-USED-WEIGHT-1M",
+            Some(BINANCE_REQUEST_WEIGHT),
+            HintKind::Used,
+        ))
+        .with_hint(HeaderHint::new(
+            "X-MBX-ORDER-COUNT-10S",
+            Some(BINANCE_ORDERS_10S),
+            HintKind::Used,
+        ))
+        .with_hint(HeaderHint::new(
+            "X-MBX-ORDER-COUNT-1M",
+            Some(BINANCE_ORDERS_1M),
+            HintKind::Used,
+        ))
+}
+
+// Weights follow the Binance docs for the parameters the clients send;
+// depth and klines are charged at their common limits, and the usage
+// headers correct the count when a call costs more.
+impl RateLimitProfile {
+    /// Binance USDⓈ-M futures default limits: 2400 weight per minute per IP,
+    /// 300 orders per 10s and 1200 per minute per account.
+    pub fn binance_um() -> Self {
+        let profile = Self::new()
+            .with_bucket(BINANCE_REQUEST_WEIGHT, 2400, MINUTE)
+            .with_account_bucket(BINANCE_ORDERS_10S, 300, TEN_SECONDS)
+            .with_account_bucket(BINANCE_ORDERS_1M, 1200, MINUTE)
+            .with_rule(cancel(BINANCE_UM_FUTURES_CANCEL_ORDER))
+            .with_rule(cancel(BINANCE_UM_FUTURES_BATCH_ORDERS))
+            .with_rule(cancel(BINANCE_UM_FUTURES_ALL_OPEN_ORDERS))
+            .with_rule(
+                weight(BINANCE_UM_FUTURES_COUNTDOWN_CANCEL_ALL, 10)
+                    .with_priority(RequestPriority::Cancel),
+            )
+            .with_rule(order(
+                weight(BINANCE_UM_FUTURES_PLACE_ORDER_INFO, 1).with_method(Method::POST),
+                1,
+            ))
+            .with_rule(order(
+                weight(BINANCE_UM_FUTURES_PLACE_ORDER_INFO, 1).with_method(Method::PUT),
+                1,
+            ))
+            .with_rule(order(
+                weight(BINANCE_UM_FUTURES_BATCH_ORDERS, 5).with_method(Method::POST),
+                5,
+            ))
+            .with_rule(order(
+                weight(BINANCE_UM_FUTURES_BATCH_ORDERS, 5).with_method(Method::PUT),
+                5,
+            ))
+            .with_rule(weight(BINANCE_UM_FUTURES_DEPTH, 10))
+            .with_rule(weight(BINANCE_UM_FUTURES_KLINES, 5))
+            .with_rule(weight(BINANCE_UM_FUTURES_PREMIUM_INDEX_KLINES, 5))
+            .with_rule(weight(BINANCE_UM_FUTURES_ACCOUNT_INFO, 5))
+            .with_rule(weight(BINANCE_UM_FUTURES_BALANCE_INFO, 5))
+            .with_rule(weight(BINANCE_UM_FUTURES_POSITION_RISK_INFO, 5))
+            .with_rule(weight(BINANCE_UM_FUTURES_ALL_ORDERS, 5))
+            .with_rule(weight(BINANCE_UM_FUTURES_OPEN_ORDERS, 5))
+            .with_rule(weight(BINANCE_UM_FUTURES_ACCOUNT_CONFIG, 5))
+            .with_rule(weight(BINANCE_UM_FUTURES_SYMBOL_CONFIG, 5))
+            .with_rule(weight(BINANCE_UM_FUTURES_TICKERS, 2))
+            .with_rule(weight(BINANCE_UM_FUTURES_PREMIUM_INDEX, 10))
+            .with_rule(EndpointRule::prefix("/fapi").with_cost(BINANCE_REQUEST_WEIGHT, 1));
+        with_binance_hints(profile)
+    }
+
+    /// Binance COIN-M futures default limits: 2400 weight per minute per IP
+    /// and 1200 orders per minute per account.
+    pub fn binance_cm() -> Self {
+        let profile = Self::new()
+            .with_bucket(BINANCE_REQUEST_WEIGHT, 2400, MINUTE)
+            .with_account_bucket(BINANCE_ORDERS_1M, 1200, MINUTE)
+            .with_rule(cancel("/dapi/v1/order"))
+            .with_rule(cancel("/dapi/v1/batchOrders"))
+            .with_rule(cancel("/dapi/v1/allOpenOrders"))
+            .with_rule(
+                weight("/dapi/v1/order", 1)
+                    .with_cost(BINANCE_ORDERS_1M, 1)
+                    .with_priority(RequestPriority::Trade),
+            )
+            .with_rule(weight(BINANCE_CM_FUTURES_ACCOUNT_INFO, 5))
+            .with_rule(weight(BINANCE_CM_FUTURES_BALANCE_INFO, 1))
+            .with_rule(EndpointRule::prefix("/dapi").with_cost(BINANCE_REQUEST_WEIGHT, 1));
+        with_binance_hints(profile)
+    }
+
+    /// Binance spot default limits: 6000 weight per minute per IP and 100
+    /// orders per 10s per account.
+    pub fn binance_spot() -> Self {
+        let profile = Self::new()
+            .with_bucket(BINANCE_REQUEST_WEIGHT, 6000, MINUTE)
+            .with_account_bucket(BINANCE_ORDERS_10S, 100, TEN_SECONDS)
+            .with_rule(cancel(BINANCE_SPOT_CANCEL_ORDER))
+            .with_rule(cancel(BINANCE_SPOT_OPEN_ORDERS))
+            .with_rule(
+                weight(BINANCE_SPOT_PLACE_ORDER, 1)
+                    .with_method(Method::POST)
+                    .with_cost(BINANCE_ORDERS_10S, 1)
+                    .with_priority(RequestPriority::Trade),
+            )
+            .with_rule(weight(BINANCE_SPOT_EXCHANGE_INFO, 20))
+            .with_rule(weight(BINANCE_SPOT_ACCOUNT_INFO, 20))
+            .with_rule(weight(BINANCE_SPOT_MY_TRADES, 20))
+            .with_rule(weight(BINANCE_SPOT_ALL_ORDERS, 20))
+            .with_rule(weight(BINANCE_SPOT_OPEN_ORDERS, 6))
+            .with_rule(weight(BINANCE_SPOT_TICKERS, 4))
+            .with_rule(EndpointRule::prefix("/").with_cost(BINANCE_REQUEST_WEIGHT, 1));
+        with_binance_hints(profile)
+    }
+}
@@ -6,7 +6,7 @@ use crate::arch::{
         base_data::{InstrumentType, OrderSide, OrderStatus, OrderType},
         market_core::Market,
     },
-    strategy_base::handler::lob_events::WsAccOrder,
+    strategy_base::handler::lob_events::{FillLiquidity, WsAccOrder, WsFill},
     traits::conversion::IntoWsData,
 };
 
@@ -32,8 +32,27 @@ pub(crate) struct WsAccountOrderBinanceSpot {
     L: String, // Filled price
     z: String, // Cumulative filled quantity
     X: String, // Order status
+    #[serde(default)]
+    x: Option<String>, // Execution type
+    #[serde(default)]
+    l: Option<String>, // Last executed quantity
+    #[serde(default)]
+    n: Option<String>, // Commission amount
+    #[serde(default)]
+    N: Option<String>, // Commission asset
+    #[serde(default)]
+    t: Option<i64>, // Trade id, -1 when there is no trade
+    #[serde(default)]
+    m: Option<bool>, // Is maker?
+    #[serde(default)]
+    T: Option<u64>, // Transaction time
 }
 
+/// Fill view of an `executionReport`; only `TRADE` executions yield a fill.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct WsFillEnvelopeBinanceSpot(WsAccountOrderEnvelopeBinanceSpot);
+
 impl IntoWsData for WsAccountOrderEnvelopeBinanceSpot {
     type Output = WsAccOrder;
 
@@ -49,11 +68,7 @@ impl IntoWsData for WsAccountOrderEnvelopeBinanceSpot {
             price: event.L.parse().unwrap_or_default(),
             size: event.q.parse::<f64>().unwrap_or_default().abs(),
             filled_size: event.z.parse::<f64>().unwrap_or_default().abs(),
-            side: match event.S.as_str() {
-                "BUY" => OrderSide::BUY,
-                "SELL" => OrderSide::SELL,
-                _ => OrderSide::Unknown,
-            },
+            side: parse_side(&event.S),
             status: match event.X.as_str() {
                 "NEW" => OrderStatus::Live,
                 "PARTIALLY_FILLED" => OrderStatus::PartiallyFilled,
@@ -74,6 +89,50 @@ impl IntoWsData for WsAccountOrderEnvelopeBinanceSpot {
     }
 }
 
+impl IntoWsData for WsFillEnvelopeBinanceSpot {
+    type Output = Option<WsFill>;
+
+    fn into_ws(self) -> Self::Output {
+        let event = self.0.event;
+        let size = event
+            .l
+            .as_deref()
+            .and_then(|l| l.parse::<f64>().ok())
+            .unwrap_or_default();
+        if event.x.as_deref() != Some("TRADE") || size <= 0.0 {
+            return None;
+        }
+
+        Some(WsFill {
+            timestamp: ts_to_micros(event.T.unwrap_or(event.E)),
+            market: Market::BinanceSpot,
+            inst: event.s,
+            inst_type: InstrumentType::Spot,
+            side: parse_side(&event.S),
+            price: event.L.parse().unwrap_or_default(),
+            size,
+            fee: event
+                .n
+                .as_deref()
+                .and_then(|n| n.parse().ok())
+                .unwrap_or_default(),
+            fee_currency: event.N,
+            liquidity: FillLiquidity::from_maker(event.m),
+            trade_id: event.t.filter(|t| *t >= 0).map(|t| t.to_string()),
+            order_id: event.i.map(|id| id.to_string()),
+            cli_order_id: Some(event.c),
+        })
+    }
+}
+
+fn parse_side(side: &str) -> OrderSide {
+    match side {
+        "BUY" => OrderSide::BUY,
+        "SELL" => OrderSide::SELL,
+        _ => OrderSide::Unknown,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -107,4 +166,41 @@ mod tests {
         assert_eq!(ws.order_id.as_deref(), Some("370702544401581041"));
         assert_eq!(ws.cli_order_id.as_deref(), Some("spot-client-id"));
     }
+
+    #[test]
+    fn fill_view_reads_last_execution_fields() {
+        let raw: WsFillEnvelopeBinanceSpot = serde_json::from_value(json!({
+            "subscriptionId": 1_u64,
+            "event": {
+                "E": 1781905826733_u64,
+                "s": "REUSDT",
+                "c": "spot-client-id",
+                "i": 370702544401581041_u64,
+                "S": "SELL",
+                "o": "LIMIT",
+                "q": "16",
+                "p": "0.873",
+                "L": "0.87295",
+                "z": "10",
+                "X": "PARTIALLY_FILLED",
+                "x": "TRADE",
+                "l": "6",
+                "n": "0.0052377",
+                "N": "USDT",
+                "t": 55012_i64,
+                "m": true,
+                "T": 1781905826730_u64
+            }
+        }))
+        .unwrap();
+
+        let fill = raw.into_ws().unwrap();
+
+        assert_eq!(fill.timestamp, 1_781_905_826_730_000);
+        assert_eq!(fill.side, OrderSide::SELL);
+        assert_eq!(fill.size, 6.0);
+        assert_eq!(fill.fee, 0.0052377);
+        assert_eq!(fill.liquidity, FillLiquidity::Maker);
+        assert_eq!(fill.trade_id.as_deref(), Some("55012"));
+    }
 }
@@ -20,6 +20,7 @@ impl RestOrderBookBinanceUM {
             inst: inst.to_string(),
             bids: levels_to_pairs(self.bids),
             asks: levels_to_pairs(self.asks),
+            update_id: Some(self.lastUpdateId),
         }
     }
 }
@@ -51,5 +52,6 @@ mod tests {
         assert_eq!(book.inst, "BTC_USDT_PERP");
         assert_eq!(book.bids, vec![(4.0, 431.0)]);
         assert_eq!(book.asks, vec![(4.000002, 12.0)]);
+        assert_eq!(book.update_id, Some(1027024));
     }
 }
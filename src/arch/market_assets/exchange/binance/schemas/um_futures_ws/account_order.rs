@@ -7,7 +7,7 @@ use crate::arch::{
         exchange::binance::api_utils::binance_fut_inst_to_cli,
         market_core::Market,
     },
-    strategy_base::handler::lob_events::WsAccOrder,
+    strategy_base::handler::lob_events::{FillLiquidity, WsAccOrder, WsFill},
     traits::conversion::IntoWsData,
 };
 
@@ -60,6 +60,11 @@ pub(crate) struct OrderUpdateDetail {
     er: Option<String>, // Expired reason
 }
 
+/// Fill view of an `ORDER_TRADE_UPDATE`; only `TRADE` executions yield a fill.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct WsFillBinanceUM(WsAccountOrderBinanceUM);
+
 impl IntoWsData for WsAccountOrderBinanceUM {
     type Output = WsAccOrder;
 
@@ -68,21 +73,11 @@ impl IntoWsData for WsAccountOrderBinanceUM {
             timestamp: ts_to_micros(self.E),
             market: Market::BinanceUmFutures,
             inst: binance_fut_inst_to_cli(&self.o.s),
-            inst_type: {
-                if self.o.s.contains("_") {
-                    InstrumentType::Futures
-                } else {
-                    InstrumentType::Perpetual
-                }
-            },
+            inst_type: parse_inst_type(&self.o.s),
             price: self.o.ap.parse().unwrap_or_default(),
             size: self.o.q.parse().unwrap_or_default(),
             filled_size: self.o.z.parse().unwrap_or_default(),
-            side: match self.o.S.as_str() {
-                "BUY" => OrderSide::BUY,
-                "SELL" => OrderSide::SELL,
-                _ => OrderSide::Unknown,
-            },
+            side: parse_side(&self.o.S),
             status: match self.o.X.as_str() {
                 "NEW" => OrderStatus::Live,
                 "PARTIALLY_FILLED" => OrderStatus::PartiallyFilled,
@@ -102,6 +97,54 @@ impl IntoWsData for WsAccountOrderBinanceUM {
     }
 }
 
+impl IntoWsData for WsFillBinanceUM {
+    type Output = Option<WsFill>;
+
+    fn into_ws(self) -> Self::Output {
+        let detail = self.0.o;
+        let size = detail.l.parse::<f64>().unwrap_or_default();
+        if detail.x != "TRADE" || size <= 0.0 {
+            return None;
+        }
+
+        Some(WsFill {
+            timestamp: ts_to_micros(detail.T),
+            market: Market::BinanceUmFutures,
+            inst: binance_fut_inst_to_cli(&detail.s),
+            inst_type: parse_inst_type(&detail.s),
+            side: parse_side(&detail.S),
+            price: detail.L.parse().unwrap_or_default(),
+            size,
+            fee: detail
+                .n
+                .as_deref()
+                .and_then(|n| n.parse().ok())
+                .unwrap_or_default(),
+            fee_currency: detail.N,
+            liquidity: FillLiquidity::from_maker(Some(detail.m)),
+            trade_id: Some(detail.t.to_string()),
+            order_id: Some(detail.i.to_string()),
+            cli_order_id: Some(detail.c),
+        })
+    }
+}
+
+fn parse_inst_type(symbol: &str) -> InstrumentType {
+    if symbol.contains("_") {
+        InstrumentType::Futures
+    } else {
+        InstrumentType::Perpetual
+    }
+}
+
+fn parse_side(side: &str) -> OrderSide {
+    match side {
+        "BUY" => OrderSide::BUY,
+        "SELL" => OrderSide::SELL,
+        _ => OrderSide::Unknown,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -110,9 +153,8 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn into_ws_preserves_exchange_and_client_order_ids() {
-        let raw: WsAccountOrderBinanceUM = serde_json::from_value(json!({
+    fn order_trade_update(execution_type: &str) -> serde_json::Value {
+        json!({
             "e": "ORDER_TRADE_UPDATE",
             "E": 1781905826733_u64,
             "T": 1781905826733_u64,
@@ -126,7 +168,7 @@ mod tests {
                 "p": "0",
                 "ap": "0.005857",
                 "sp": "0",
-                "x": "TRADE",
+                "x": execution_type,
                 "X": "FILLED",
                 "i": 1272696572_u64,
                 "l": "4350",
@@ -153,12 +195,36 @@ mod tests {
                 "gtd": 0_u64,
                 "er": "0"
             }
-        }))
-        .unwrap();
+        })
+    }
+
+    #[test]
+    fn into_ws_preserves_exchange_and_client_order_ids() {
+        let raw: WsAccountOrderBinanceUM =
+            serde_json::from_value(order_trade_update("TRADE")).unwrap();
 
         let ws = raw.into_ws();
 
         assert_eq!(ws.order_id.as_deref(), Some("1272696572"));
         assert_eq!(ws.cli_order_id.as_deref(), Some("CYA3pfUhF2yFO3kbBgIDMP"));
     }
+
+    #[test]
+    fn fill_view_only_reports_trade_executions() {
+        let fill = serde_json::from_value::<WsFillBinanceUM>(order_trade_update("TRADE"))
+            .unwrap()
+            .into_ws()
+            .unwrap();
+        let new = serde_json::from_value::<WsFillBinanceUM>(order_trade_update("NEW"))
+            .unwrap()
+            .into_ws();
+
+        assert_eq!(fill.price, 0.005857);
+        assert_eq!(fill.size, 4350.0);
+        assert_eq!(fill.fee_currency.as_deref(), Some("USDT"));
+        assert_eq!(fill.liquidity, FillLiquidity::Taker);
+        assert_eq!(fill.trade_id.as_deref(), Some("1"));
+        assert_eq!(fill.order_id.as_deref(), Some("1272696572"));
+        assert!(new.is_none());
+    }
 }
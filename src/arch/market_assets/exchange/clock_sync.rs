@@ -0,0 +1,305 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+};
+
+use reqwest::Url;
+use tokio::{
+    task::JoinHandle,
+    time::{Duration, interval},
+};
+use tracing::{info, warn};
+
+use crate::arch::{
+    market_assets::{api_general::get_micros_timestamp, exchange::exchange_error::Venue},
+    traits::market_lob::LobPublicRest,
+};
+use crate::errors::{InfraError, InfraResult};
+
+/// Latest clock estimate by venue and REST host, read when signing requests.
+/// Production, testnet and mock deployments of one venue keep separate
+/// estimates.
+static ESTIMATES: LazyLock<Mutex<HashMap<(Venue, String), ClockEstimate>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Offset of a venue's clock from the local one, taken from one round trip
+/// to its time endpoint.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClockEstimate {
+    /// Venue time minus local time, in microseconds.
+    pub offset_micros: i64,
+    /// Round trip of the sampling request, in microseconds. The offset is
+    /// off by at most half of it.
+    pub rtt_micros: u64,
+    /// Local time the sample was taken, in microseconds since the Unix epoch.
+    pub sampled_at: u64,
+}
+
+impl ClockEstimate {
+    /// Estimate from a request sent at local time `sent` and answered at
+    /// `received` with venue time `server`, assuming the venue read its clock
+    /// halfway through the round trip.
+    pub fn from_sample(sent: u64, server: u64, received: u64) -> Self {
+        let rtt_micros = received.saturating_sub(sent);
+        let midpoint = sent + rtt_micros / 2;
+        Self {
+            offset_micros: server as i64 - midpoint as i64,
+            rtt_micros,
+            sampled_at: received,
+        }
+    }
+
+    /// Venue time at local time `local`.
+    pub fn venue_time(&self, local: u64) -> u64 {
+        local.saturating_add_signed(self.offset_micros)
+    }
+
+    /// Time from the venue stamping a message at `exchange_ts` to the local
+    /// runtime receiving it at `received_at`, both in microseconds. Negative
+    /// when the estimate is stale or the venue stamps late.
+    pub fn latency_micros(&self, exchange_ts: u64, received_at: u64) -> i64 {
+        self.venue_time(received_at) as i64 - exchange_ts as i64
+    }
+}
+
+/// Samples a venue's server time to estimate how far its clock is from the
+/// local one. Signing for that venue then uses venue time, so timestamps and
+/// nonces stay inside its receive window when the local clock drifts.
+///
+/// Estimates are kept per venue and REST base URL, i.e. a client's
+/// `endpoints.rest`, so each endpoint profile gets its own offset.
+#[derive(Clone, Debug)]
+pub struct ClockSync {
+    /// Round trips per sync. The one with the shortest RTT wins.
+    pub samples: usize,
+    /// Time between syncs of [`ClockSync::spawn_sync`].
+    pub interval: Duration,
+    /// Offset above which a sync logs a warning.
+    pub drift_warning: Duration,
+}
+
+impl Default for ClockSync {
+    fn default() -> Self {
+        Self {
+            samples: 5,
+            interval: Duration::from_secs(60),
+            drift_warning: Duration::from_millis(500),
+        }
+    }
+}
+
+impl ClockSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_drift_warning(mut self, drift_warning: Duration) -> Self {
+        self.drift_warning = drift_warning;
+        self
+    }
+
+    /// Latest estimate for `venue` at `rest`, if it has been synced.
+    pub fn estimate(venue: Venue, rest: &str) -> Option<ClockEstimate> {
+        lock(&ESTIMATES).get(&(venue, rest_host(rest))).copied()
+    }
+
+    /// Replaces the estimate for `venue` at `rest`, e.g. for venues without
+    /// a time endpoint whose offset is measured some other way.
+    pub fn set(venue: Venue, rest: &str, estimate: ClockEstimate) {
+        lock(&ESTIMATES).insert((venue, rest_host(rest)), estimate);
+    }
+
+    /// Current venue time in microseconds, or local time before the first
+    /// sync.
+    pub fn now_micros(venue: Venue, rest: &str) -> u64 {
+        let local = get_micros_timestamp();
+        Self::estimate(venue, rest).map_or(local, |estimate| estimate.venue_time(local))
+    }
+
+    /// Current venue time in milliseconds.
+    pub fn now_millis(venue: Venue, rest: &str) -> u64 {
+        Self::now_micros(venue, rest) / 1_000
+    }
+
+    /// Current venue time in seconds.
+    pub fn now_seconds(venue: Venue, rest: &str) -> u64 {
+        Self::now_micros(venue, rest) / 1_000_000
+    }
+
+    /// Exchange-to-local latency of a message from `venue` at `rest`, see
+    /// [`ClockEstimate::latency_micros`].
+    pub fn latency_micros(venue: Venue, rest: &str, exchange_ts: u64, received_at: u64) -> i64 {
+        Self::estimate(venue, rest)
+            .unwrap_or_default()
+            .latency_micros(exchange_ts, received_at)
+    }
+
+    /// Samples `cli`'s server time and stores the estimate for `venue` at
+    /// `rest`, the base URL `cli` sends its requests to.
+    pub async fn sync<C: LobPublicRest>(
+        &self,
+        cli: &C,
+        venue: Venue,
+        rest: &str,
+    ) -> InfraResult<ClockEstimate> {
+        let mut best: Option<ClockEstimate> = None;
+        for _ in 0..self.samples.max(1) {
+            let sent = get_micros_timestamp();
+            let server = cli.get_server_time().await?;
+            let sample = ClockEstimate::from_sample(sent, server, get_micros_timestamp());
+            if best.is_none_or(|b| sample.rtt_micros < b.rtt_micros) {
+                best = Some(sample);
+            }
+        }
+        let estimate = best.ok_or(InfraError::ApiCliError("No clock samples taken".into()))?;
+
+        let drift = Duration::from_micros(estimate.offset_micros.unsigned_abs());
+        if drift > self.drift_warning {
+            warn!(
+                "{:?} clock is {:?} {} the local clock (rtt {}us)",
+                venue,
+                drift,
+                if estimate.offset_micros > 0 {
+                    "ahead of"
+                } else {
+                    "behind"
+                },
+                estimate.rtt_micros
+            );
+        }
+
+        Self::set(venue, rest, estimate);
+        Ok(estimate)
+    }
+
+    /// Syncs every [`ClockSync::interval`], starting immediately, until the
+    /// returned task is aborted.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn spawn_sync<C>(self, cli: C, venue: Venue, rest: impl Into<String>) -> JoinHandle<()>
+    where
+        C: LobPublicRest + Send + Sync + 'static,
+    {
+        let rest = rest.into();
+        tokio::spawn(async move {
+            let mut ticker = interval(self.interval);
+            loop {
+                ticker.tick().await;
+                match self.sync(&cli, venue, &rest).await {
+                    Ok(estimate) => info!(
+                        "{:?} clock offset {}us, rtt {}us",
+                        venue, estimate.offset_micros, estimate.rtt_micros
+                    ),
+                    Err(e) => warn!("{:?} clock sync failed: {}", venue, e),
+                }
+            }
+        })
+    }
+}
+
+/// Host and port of a REST base URL, or the URL itself when it does not
+/// parse, so `https://host` and `https://host/` share one estimate.
+fn rest_host(rest: &str) -> String {
+    Url::parse(rest)
+        .ok()
+        .and_then(|url| {
+            url.host_str()
+                .map(|host| match url.port_or_known_default() {
+                    Some(port) => format!("{}:{}", host, port),
+                    None => host.to_string(),
+                })
+        })
+        .unwrap_or_else(|| rest.to_string())
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_offset_is_measured_from_the_round_trip_midpoint() {
+        let estimate = ClockEstimate::from_sample(1_000, 1_600, 1_200);
+
+        assert_eq!(estimate.rtt_micros, 200);
+        assert_eq!(estimate.offset_micros, 500);
+        assert_eq!(estimate.sampled_at, 1_200);
+
+        let behind = ClockEstimate::from_sample(1_000, 900, 1_200);
+        assert_eq!(behind.offset_micros, -200);
+    }
+
+    #[test]
+    fn venue_time_applies_the_offset() {
+        let ahead = ClockEstimate {
+            offset_micros: 250,
+            ..Default::default()
+        };
+        let behind = ClockEstimate {
+            offset_micros: -250,
+            ..Default::default()
+        };
+
+        assert_eq!(ahead.venue_time(1_000), 1_250);
+        assert_eq!(behind.venue_time(1_000), 750);
+        assert_eq!(behind.venue_time(100), 0);
+    }
+
+    #[test]
+    fn latency_corrects_for_the_venue_clock() {
+        // The venue runs 300us ahead, so a message it stamped at 10_200 and
+        // we received at local 10_000 spent 100us in flight.
+        let estimate = ClockEstimate {
+            offset_micros: 300,
+            ..Default::default()
+        };
+
+        assert_eq!(estimate.latency_micros(10_200, 10_000), 100);
+        assert_eq!(
+            ClockEstimate::default().latency_micros(10_200, 10_000),
+            -200
+        );
+    }
+
+    #[test]
+    fn estimates_are_kept_per_rest_host() {
+        let testnet = ClockEstimate {
+            offset_micros: 5_000_000,
+            ..Default::default()
+        };
+        ClockSync::set(
+            Venue::Hyperliquid,
+            "https://api.hyperliquid-testnet.xyz",
+            testnet,
+        );
+
+        assert_eq!(
+            ClockSync::estimate(Venue::Hyperliquid, "https://api.hyperliquid-testnet.xyz/"),
+            Some(testnet)
+        );
+        assert_eq!(
+            ClockSync::estimate(Venue::Hyperliquid, "https://api.hyperliquid.xyz"),
+            None
+        );
+        assert_eq!(
+            ClockSync::estimate(Venue::Okx, "https://api.hyperliquid-testnet.xyz"),
+            None
+        );
+        assert_eq!(rest_host("http://127.0.0.1:8080"), "127.0.0.1:8080");
+    }
+}
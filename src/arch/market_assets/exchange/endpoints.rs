@@ -0,0 +1,105 @@
+/// Base URLs an exchange client sends REST requests and websocket
+/// connections to.
+///
+/// Clients start from their production profile. Each exchange module adds
+/// presets for its deployments, e.g. [`EndpointProfile::okx_demo`] or
+/// [`EndpointProfile::hyperliquid_testnet`]; pass one to the client's
+/// `with_endpoints` builder. Any URL can be overridden on top of a preset with
+/// the `with_*` methods below, e.g. to reach an in-process mock venue.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EndpointProfile {
+    /// REST base URL without a trailing slash, e.g. `https://www.okx.com`.
+    pub rest: String,
+    /// Public market-data websocket URL.
+    pub ws_public: String,
+    /// Private account websocket URL.
+    pub ws_private: String,
+    /// Websocket URL for channels served apart from `ws_public`, such as the
+    /// OKX `business` endpoint or the Binance futures `market` endpoint.
+    /// Venues without that split reuse `ws_public`.
+    pub ws_business: String,
+    /// Websocket URL for order entry, e.g. the Binance futures WebSocket
+    /// API. Venues that trade on their private or public connection reuse
+    /// that URL.
+    pub ws_trade: String,
+    /// Extra headers sent with every signed REST request, e.g. OKX's
+    /// `x-simulated-trading` on demo trading.
+    pub rest_headers: Vec<(String, String)>,
+    /// Whether requests are signed for a test network. Only Hyperliquid
+    /// signs differently; other venues tell deployments apart by URL alone.
+    pub testnet: bool,
+}
+
+impl EndpointProfile {
+    /// Profile serving every websocket channel from one URL, with no extra
+    /// headers.
+    pub fn new(rest: impl Into<String>, ws: impl Into<String>) -> Self {
+        let ws = ws.into();
+        Self {
+            rest: rest.into(),
+            ws_public: ws.clone(),
+            ws_private: ws.clone(),
+            ws_business: ws.clone(),
+            ws_trade: ws,
+            rest_headers: Vec::new(),
+            testnet: false,
+        }
+    }
+
+    pub fn with_rest(mut self, rest: impl Into<String>) -> Self {
+        self.rest = rest.into();
+        self
+    }
+
+    pub fn with_ws_public(mut self, ws_public: impl Into<String>) -> Self {
+        self.ws_public = ws_public.into();
+        self
+    }
+
+    pub fn with_ws_private(mut self, ws_private: impl Into<String>) -> Self {
+        self.ws_private = ws_private.into();
+        self
+    }
+
+    pub fn with_ws_business(mut self, ws_business: impl Into<String>) -> Self {
+        self.ws_business = ws_business.into();
+        self
+    }
+
+    pub fn with_ws_trade(mut self, ws_trade: impl Into<String>) -> Self {
+        self.ws_trade = ws_trade.into();
+        self
+    }
+
+    /// Adds a header to every signed REST request.
+    pub fn with_rest_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.rest_headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_testnet(mut self, testnet: bool) -> Self {
+        self.testnet = testnet;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_replace_single_urls() {
+        let profile = EndpointProfile::new("https://rest", "wss://ws")
+            .with_ws_private("wss://private")
+            .with_rest_header("x-test", "1")
+            .with_testnet(true);
+
+        assert_eq!(profile.rest, "https://rest");
+        assert_eq!(profile.ws_public, "wss://ws");
+        assert_eq!(profile.ws_business, "wss://ws");
+        assert_eq!(profile.ws_trade, "wss://ws");
+        assert_eq!(profile.ws_private, "wss://private");
+        assert_eq!(profile.rest_headers, vec![("x-test".into(), "1".into())]);
+        assert!(profile.testnet);
+    }
+}
@@ -0,0 +1,171 @@
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::arch::market_assets::market_core::Market;
+
+/// Exchange a request was sent to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Venue {
+    Binance,
+    Gate,
+    Hyperliquid,
+    Okx,
+}
+
+impl Venue {
+    /// Venue operating `market`, or `None` for markets without a client.
+    pub fn from_market(market: &Market) -> Option<Self> {
+        match market {
+            Market::HyperLiquid => Some(Venue::Hyperliquid),
+            Market::BinanceCmFutures | Market::BinanceSpot | Market::BinanceUmFutures => {
+                Some(Venue::Binance)
+            },
+            Market::GateDelivery | Market::GateFutures | Market::GateSpot | Market::GateUni => {
+                Some(Venue::Gate)
+            },
+            Market::Okx => Some(Venue::Okx),
+            Market::Coinbase => None,
+        }
+    }
+}
+
+/// Venue-independent reason for a rejection, to decide how to react
+/// without matching on venue codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorCategory {
+    InsufficientBalance,
+    RateLimited,
+    InvalidPrice,
+    PostOnlyWouldCross,
+    ReduceOnlyRejected,
+    UnknownOrder,
+    AuthFailed,
+    Maintenance,
+    Timeout,
+    /// Any rejection without a category of its own.
+    Other,
+}
+
+impl ErrorCategory {
+    /// Whether the same request may succeed when sent again later.
+    pub fn is_transient(self) -> bool {
+        matches!(self, Self::RateLimited | Self::Maintenance | Self::Timeout)
+    }
+
+    /// Category implied by an HTTP status alone.
+    pub fn from_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::IM_A_TEAPOT => Self::RateLimited,
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::AuthFailed,
+            StatusCode::SERVICE_UNAVAILABLE => Self::Maintenance,
+            StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => Self::Timeout,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Request rejected by an exchange.
+///
+/// `code` is the venue's own error code: OKX `code` or per-order `sCode`,
+/// Binance `code`, Gate `label`, or the Hyperliquid error text.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("{venue:?} error {code} ({category:?}{}): {message}", status.map(|s| format!(", HTTP {s}")).unwrap_or_default())]
+pub struct ExchangeError {
+    pub venue: Venue,
+    pub category: ErrorCategory,
+    pub code: String,
+    pub message: String,
+    /// HTTP status of a response the venue failed at the HTTP level; `None`
+    /// when the error came in a successful response or over a websocket.
+    pub status: Option<u16>,
+}
+
+impl ExchangeError {
+    pub fn new(
+        venue: Venue,
+        category: ErrorCategory,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            venue,
+            category,
+            code: code.into(),
+            message: message.into(),
+            status: None,
+        }
+    }
+
+    /// Sets the HTTP status, falling back to the category it implies when the
+    /// venue code has none.
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status.as_u16());
+        if self.category == ErrorCategory::Other {
+            self.category = ErrorCategory::from_status(status);
+        }
+        self
+    }
+
+    /// Error for a non-success HTTP response. Bodies in the venue's error
+    /// format keep its code; others are reported by status.
+    pub(crate) fn from_response(venue: Venue, status: StatusCode, body: &[u8]) -> Self {
+        let error = match venue {
+            #[cfg(feature = "binance")]
+            Venue::Binance => Self::binance_body(body),
+            #[cfg(feature = "gate")]
+            Venue::Gate => Self::gate_body(body),
+            #[cfg(feature = "hyperliquid")]
+            Venue::Hyperliquid => Self::hyperliquid_body(body),
+            #[cfg(feature = "okx")]
+            Venue::Okx => Self::okx_body(body),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        };
+
+        error
+            .unwrap_or_else(|| {
+                let preview = String::from_utf8_lossy(&body[..body.len().min(500)]);
+                Self::new(
+                    venue,
+                    ErrorCategory::Other,
+                    status.as_str(),
+                    preview.into_owned(),
+                )
+            })
+            .with_status(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_fills_in_missing_categories() {
+        let unmapped = ExchangeError::new(Venue::Okx, ErrorCategory::Other, "50000", "busy")
+            .with_status(StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(unmapped.category, ErrorCategory::RateLimited);
+        assert_eq!(unmapped.status, Some(429));
+
+        let mapped = ExchangeError::new(Venue::Okx, ErrorCategory::UnknownOrder, "51603", "gone")
+            .with_status(StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(mapped.category, ErrorCategory::UnknownOrder);
+        assert_eq!(
+            mapped.to_string(),
+            "Okx error 51603 (UnknownOrder, HTTP 503): gone"
+        );
+    }
+
+    #[test]
+    fn unparsable_bodies_are_reported_by_status() {
+        let error = ExchangeError::from_response(
+            Venue::Gate,
+            StatusCode::BAD_GATEWAY,
+            b"<html>bad gateway</html>",
+        );
+        assert_eq!(error.code, "502");
+        assert_eq!(error.category, ErrorCategory::Other);
+        assert_eq!(error.message, "<html>bad gateway</html>");
+    }
+}
@@ -2,10 +2,13 @@ use data_encoding::HEXLOWER;
 use hmac::{KeyInit, Mac};
 use sha2::{Digest, Sha512};
 
-use reqwest::{Client, Response};
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::{Value, json};
 
+use crate::arch::market_assets::exchange::clock_sync::ClockSync;
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
     market_assets::api_general::*,
     redaction::{redact_identifier, redact_secret},
@@ -83,12 +86,13 @@ impl GateKey {
 
     pub fn sign_now(
         &self,
+        rest: &str,
         method: &str,
         full_path: &str,
         query_string: Option<&str>,
         body: Option<&str>,
     ) -> InfraResult<Signature<u64>> {
-        let timestamp = get_seconds_timestamp();
+        let timestamp = ClockSync::now_seconds(Venue::Gate, rest);
         self.sign(method, full_path, query_string, body, timestamp)
     }
 
@@ -106,43 +110,55 @@ impl GateKey {
         }))
     }
 
-    pub(crate) async fn get_request(
+    /// Payload of a websocket API login request on `channel`, e.g.
+    /// `futures.login`.
+    pub fn ws_api_login(&self, channel: &str, req_id: &str, timestamp: u64) -> InfraResult<Value> {
+        let raw_sign = format!("api\n{}\n\n{}", channel, timestamp);
+        let mut mac = HmacSha512::new_from_slice(self.secret_key.as_bytes())
+            .map_err(|_| InfraError::SecretKeyLength)?;
+        mac.update(raw_sign.as_bytes());
+        let signature = HEXLOWER.encode(&mac.finalize().into_bytes());
+
+        Ok(json!({
+            "api_key": self.api_key,
+            "signature": signature,
+            "timestamp": timestamp.to_string(),
+            "req_id": req_id,
+        }))
+    }
+
+    pub(crate) fn get_request(
         &self,
         client: &Client,
         signature: &Signature<u64>,
         url: &str,
-    ) -> InfraResult<Response> {
-        let res = client
+    ) -> RequestBuilder {
+        client
             .get(url)
             .header("KEY", &self.api_key)
             .header("SIGN", &signature.signature)
             .header("Timestamp", signature.timestamp.to_string())
             .header(GATE_SIZE_DECIMAL_HEADER, GATE_SIZE_DECIMAL_HEADER_VALUE)
-            .send()
-            .await?;
-
-        Ok(res)
     }
 
-    pub(crate) async fn post_request(
+    pub(crate) fn post_request(
         &self,
         client: &Client,
         signature: &Signature<u64>,
         body: &str,
         url: &str,
-    ) -> InfraResult<Response> {
+    ) -> RequestBuilder {
         self.post_request_with_channel_id(client, signature, body, url, None)
-            .await
     }
 
-    pub(crate) async fn post_request_with_channel_id(
+    pub(crate) fn post_request_with_channel_id(
         &self,
         client: &Client,
         signature: &Signature<u64>,
         body: &str,
         url: &str,
         channel_id: Option<&str>,
-    ) -> InfraResult<Response> {
+    ) -> RequestBuilder {
         let mut request = client
             .post(url)
             .header("KEY", &self.api_key)
@@ -155,19 +171,17 @@ impl GateKey {
             request = request.header(GATE_CHANNEL_ID_HEADER, channel_id);
         }
 
-        let res = request.body(body.to_string()).send().await?;
-
-        Ok(res)
+        request.body(body.to_string())
     }
 
-    pub(crate) async fn put_request(
+    pub(crate) fn put_request(
         &self,
         client: &Client,
         signature: &Signature<u64>,
         body: &str,
         url: &str,
-    ) -> InfraResult<Response> {
-        let res = client
+    ) -> RequestBuilder {
+        client
             .put(url)
             .header("KEY", &self.api_key)
             .header("SIGN", &signature.signature)
@@ -175,20 +189,16 @@ impl GateKey {
             .header(GATE_SIZE_DECIMAL_HEADER, GATE_SIZE_DECIMAL_HEADER_VALUE)
             .header("Content-Type", "application/json")
             .body(body.to_string())
-            .send()
-            .await?;
-
-        Ok(res)
     }
 
-    pub(crate) async fn delete_request(
+    pub(crate) fn delete_request(
         &self,
         client: &Client,
         signature: &Signature<u64>,
         body: &str,
         url: &str,
-    ) -> InfraResult<Response> {
-        let res = client
+    ) -> RequestBuilder {
+        client
             .delete(url)
             .header("KEY", &self.api_key)
             .header("SIGN", &signature.signature)
@@ -196,15 +206,13 @@ impl GateKey {
             .header(GATE_SIZE_DECIMAL_HEADER, GATE_SIZE_DECIMAL_HEADER_VALUE)
             .header("Content-Type", "application/json")
             .body(body.to_string())
-            .send()
-            .await?;
-
-        Ok(res)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn send_signed_request<T>(
         &self,
         client: &Client,
+        retry: &RetryPolicy,
         method: RequestMethod,
         query_string: Option<&str>,
         body: Option<&str>,
@@ -222,34 +230,38 @@ impl GateKey {
         };
 
         let encoded_query = encode_query_string(query_string);
-        let signature = self.sign_now(method_str, endpoint, encoded_query.as_deref(), body)?;
         let url = gate_build_full_url(base_url, endpoint, encoded_query.as_deref());
+        let body_str = body.unwrap_or("");
 
-        let response = match method {
-            RequestMethod::Get => self.get_request(client, &signature, &url).await?,
-            RequestMethod::Post => {
-                let body_str = body.unwrap_or("");
-                self.post_request(client, &signature, body_str, &url)
-                    .await?
-            },
-            RequestMethod::Put => {
-                let body_str = body.unwrap_or("");
-                self.put_request(client, &signature, body_str, &url).await?
-            },
-            RequestMethod::Delete => {
-                let body_str = body.unwrap_or("");
-                self.delete_request(client, &signature, body_str, &url)
-                    .await?
-            },
-        };
+        let response = retry
+            .send_with(|| {
+                let signature = self.sign_now(
+                    base_url,
+                    method_str,
+                    endpoint,
+                    encoded_query.as_deref(),
+                    body,
+                )?;
+                Ok(match method {
+                    RequestMethod::Get => self.get_request(client, &signature, &url),
+                    RequestMethod::Post => self.post_request(client, &signature, body_str, &url),
+                    RequestMethod::Put => self.put_request(client, &signature, body_str, &url),
+                    RequestMethod::Delete => {
+                        self.delete_request(client, &signature, body_str, &url)
+                    },
+                })
+            })
+            .await?;
 
         let label = format!("Gate {:?} {}", method, endpoint);
-        parse_json_response(&label, response).await
+        parse_venue_response(Venue::Gate, &label, response).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn send_signed_post_request_with_channel_id<T>(
         &self,
         client: &Client,
+        retry: &RetryPolicy,
         query_string: Option<&str>,
         body: Option<&str>,
         base_url: &str,
@@ -261,15 +273,23 @@ impl GateKey {
     {
         let encoded_query = encode_query_string(query_string);
         let body_str = body.unwrap_or("");
-        let signature =
-            self.sign_now("POST", endpoint, encoded_query.as_deref(), Some(body_str))?;
         let url = gate_build_full_url(base_url, endpoint, encoded_query.as_deref());
-        let response = self
-            .post_request_with_channel_id(client, &signature, body_str, &url, channel_id)
+        let response = retry
+            .send_with(|| {
+                let signature = self.sign_now(
+                    base_url,
+                    "POST",
+                    endpoint,
+                    encoded_query.as_deref(),
+                    Some(body_str),
+                )?;
+                Ok(self
+                    .post_request_with_channel_id(client, &signature, body_str, &url, channel_id))
+            })
             .await?;
 
         let label = format!("Gate Post {}", endpoint);
-        parse_json_response(&label, response).await
+        parse_venue_response(Venue::Gate, &label, response).await
     }
 }
 
@@ -305,4 +325,19 @@ mod tests {
         assert!(!debug.contains("52955084"));
         assert!(debug.contains("[REDACTED]"));
     }
+
+    #[test]
+    fn signs_ws_api_login() {
+        let key = GateKey::new("key", "secret", "1");
+        let payload = key
+            .ws_api_login("futures.login", "login", 1_700_000_000)
+            .unwrap();
+
+        assert_eq!(payload["api_key"], "key");
+        assert_eq!(payload["timestamp"], "1700000000");
+        assert_eq!(
+            payload["signature"],
+            "f39035057b3528fc2c5aff4b9cfa9f43673c88d3ff823c55468608173205809999a8b45d7ed898ebf49c15a4f6e5131de175ded143be5eeb58431f600e1d4085"
+        );
+    }
 }
@@ -1,18 +1,29 @@
 use std::collections::HashMap;
 
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use tracing::{error, warn};
 
 use crate::arch::{
     market_assets::{
-        api_general::{CancelOrderParams, OrderParams, get_seconds_timestamp},
+        api_general::{
+            AmendOrderParams, CancelOrderParams, OrderParams, get_seconds_timestamp,
+            parse_venue_response,
+        },
         base_data::{OrderSide, OrderType, SUBSCRIBE_LOWER},
+        exchange::{exchange_error::Venue, retry::RetryPolicy},
     },
     task_execution::task_ws::LobFrequency,
+    traits::conversion::IntoInfraVec,
 };
 use crate::errors::{InfraError, InfraResult};
 
+use super::{
+    config_assets::GATE_SPOT_TIME,
+    gate_rest_msg::{RestResGate, RestServerTimeGate},
+};
+
 pub const GATE_CHANNEL_ID_EXTRA_KEY: &str = "gate_channel_id";
 pub const GATE_CHANNEL_ID_HEADER: &str = "X-Gate-Channel-Id";
 pub const GATE_SIZE_DECIMAL_HEADER: &str = "X-Gate-Size-Decimal";
@@ -137,6 +148,95 @@ impl TryFrom<&CancelOrderParams> for GateFuturesBatchCancelParams {
     }
 }
 
+/// Body of `PUT /futures/{settle}/orders/{order_id}`.
+#[derive(Clone, Debug, Serialize)]
+pub struct RestAmendOrderParamsGateFutures {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price: Option<String>,
+    #[serde(flatten)]
+    extra: HashMap<String, String>,
+}
+
+/// One entry of `POST /futures/{settle}/batch_amend_orders`.
+#[derive(Clone, Debug, Serialize)]
+pub struct RestBatchAmendOrderParamsGateFutures {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(flatten)]
+    pub amend: RestAmendOrderParamsGateFutures,
+}
+
+#[derive(Clone, Debug)]
+pub struct GateFuturesAmendParams {
+    pub settle: String,
+    /// Exchange order id, or the `t-` prefixed client order id.
+    pub path_id: String,
+    pub order: RestBatchAmendOrderParamsGateFutures,
+}
+
+impl TryFrom<&AmendOrderParams> for GateFuturesAmendParams {
+    type Error = InfraError;
+
+    fn try_from(amend: &AmendOrderParams) -> Result<Self, Self::Error> {
+        amend.validate_target()?;
+
+        let mut extra = amend.extra.clone();
+        let settle = extra
+            .remove("settle")
+            .unwrap_or_else(|| infer_settle_from_inst(&amend.inst));
+
+        // Gate futures sizes carry the order direction, so a new size needs the side.
+        let size = match amend.new_size.as_deref() {
+            Some(size) => {
+                let size = size.trim().trim_start_matches(['+', '-']);
+                if size.is_empty() {
+                    return Err(InfraError::ApiCliError("Invalid order size".into()));
+                }
+                match amend.side {
+                    Some(OrderSide::BUY) => Some(size.to_string()),
+                    Some(OrderSide::SELL) => Some(format!("-{size}")),
+                    _ => {
+                        return Err(InfraError::ApiCliError(
+                            "Gate Futures amend with new_size requires side".into(),
+                        ));
+                    },
+                }
+            },
+            None => None,
+        };
+
+        let text = amend.cli_order_id.as_deref().map(normalize_gate_text);
+        let (path_id, order_id, text) = match (&amend.order_id, text) {
+            (Some(order_id), _) => {
+                let numeric = order_id.parse::<u64>().map_err(|_| {
+                    InfraError::ApiCliError(format!("Invalid Gate Futures order_id: {order_id}"))
+                })?;
+                (order_id.clone(), Some(numeric), None)
+            },
+            (None, Some(text)) => (text.clone(), None, Some(text)),
+            (None, None) => unreachable!("validated above"),
+        };
+
+        Ok(Self {
+            settle,
+            path_id,
+            order: RestBatchAmendOrderParamsGateFutures {
+                order_id,
+                text,
+                amend: RestAmendOrderParamsGateFutures {
+                    size,
+                    price: amend.new_price.clone(),
+                    extra,
+                },
+            },
+        })
+    }
+}
+
 pub(crate) fn value_to_order_id(value: Option<&Value>) -> Option<String> {
     match value {
         Some(Value::String(id)) if !id.is_empty() && id != "-" => Some(id.clone()),
@@ -145,6 +245,39 @@ pub(crate) fn value_to_order_id(value: Option<&Value>) -> Option<String> {
     }
 }
 
+/// Gate's current time. Spot, futures and delivery share one clock, so
+/// every Gate client reads it from the spot endpoint.
+pub(crate) async fn get_gate_server_time(
+    client: &Client,
+    retry: &RetryPolicy,
+    rest: &str,
+) -> InfraResult<u64> {
+    let url = [rest, GATE_SPOT_TIME].concat();
+
+    let response = retry.send(client.get(url)).await?;
+    let res: RestResGate<RestServerTimeGate> =
+        parse_venue_response(Venue::Gate, "Gate server_time", response).await?;
+
+    let data = res
+        .into_vec()?
+        .into_iter()
+        .next()
+        .ok_or(InfraError::ApiCliError(
+            "No Gate server time returned".into(),
+        ))?;
+
+    Ok(data.server_time)
+}
+
+/// Reads Gate millisecond timestamps sent as integers or decimal strings.
+pub(crate) fn value_to_u64_ms(v: &Value) -> Option<u64> {
+    v.as_u64().or_else(|| {
+        v.as_str()
+            .and_then(|s| s.split('.').next())
+            .and_then(|s| s.parse::<u64>().ok())
+    })
+}
+
 pub fn ws_subscribe_msg_gate_futures(channel: &str, payload: Vec<String>) -> String {
     let msg = json!({
         "time": get_seconds_timestamp(),
@@ -732,4 +865,34 @@ mod tests {
             Some("client_order_id=order-1&tx_id=tx-1")
         );
     }
+
+    #[test]
+    fn builds_gate_futures_amend_with_signed_size() {
+        let amend = AmendOrderParams {
+            inst: "BTC_USDT_PERP".into(),
+            cli_order_id: Some("quote1".into()),
+            side: Some(OrderSide::SELL),
+            new_size: Some("3".into()),
+            new_price: Some("101".into()),
+            ..Default::default()
+        };
+        let params = GateFuturesAmendParams::try_from(&amend).unwrap();
+
+        assert_eq!(params.settle, "usdt");
+        assert_eq!(params.path_id, "t-quote1");
+        assert_eq!(
+            serde_json::to_value(&params.order).unwrap(),
+            json!({ "text": "t-quote1", "size": "-3", "price": "101" })
+        );
+        assert_eq!(
+            serde_json::to_value(&params.order.amend).unwrap(),
+            json!({ "size": "-3", "price": "101" })
+        );
+
+        let unsided = AmendOrderParams {
+            side: None,
+            ..amend
+        };
+        assert!(GateFuturesAmendParams::try_from(&unsided).is_err());
+    }
 }
@@ -1,12 +1,34 @@
+use reqwest::Method;
+use tokio::time::Duration;
+
+use crate::arch::market_assets::exchange::{
+    endpoints::EndpointProfile,
+    rate_limit::{EndpointRule, HeaderHint, HintKind, RateLimitProfile, RequestPriority},
+};
+
 /// Gate API Base
 pub const GATE_BASE_URL: &str = "https://api.gateio.ws";
 pub const GATE_WS_BASE_URL: &str = "wss://api.gateio.ws/ws/v4/";
 pub const GATE_FUTURES_WS_USDT: &str = "wss://fx-ws.gateio.ws/v4/ws/usdt";
 pub const GATE_FUTURES_WS_BTC: &str = "wss://fx-ws.gateio.ws/v4/ws/btc";
 
+impl EndpointProfile {
+    /// Gate production with the spot websocket, used by the spot, delivery
+    /// and unified account clients.
+    pub fn gate_production() -> Self {
+        Self::new(GATE_BASE_URL, GATE_WS_BASE_URL)
+    }
+
+    /// Gate production with the USDT-settled futures websocket.
+    pub fn gate_futures_production() -> Self {
+        Self::new(GATE_BASE_URL, GATE_FUTURES_WS_USDT)
+    }
+}
+
 /// Spot endpoints/channels
 pub const GATE_WS_SPOT_ORDERS: &str = "spot.orders";
 pub const GATE_WS_SPOT_ORDERS_V2: &str = "spot.orders_v2";
+pub const GATE_WS_SPOT_USERTRADES: &str = "spot.usertrades";
 pub const GATE_WS_SPOT_BALANCES: &str = "spot.balances";
 pub const GATE_WS_SPOT_CROSS_BALANCES: &str = "spot.cross_balances";
 pub const GATE_SPOT_CURRENCY_PAIRS: &str = "/api/v4/spot/currency_pairs";
@@ -14,6 +36,7 @@ pub const GATE_SPOT_ORDERS: &str = "/api/v4/spot/orders";
 pub const GATE_SPOT_ORDER: &str = "/api/v4/spot/orders/{order_id}";
 pub const GATE_SPOT_TICKERS: &str = "/api/v4/spot/tickers";
 pub const GATE_SPOT_ACCOUNTS: &str = "/api/v4/spot/accounts";
+pub const GATE_SPOT_TIME: &str = "/api/v4/spot/time";
 
 /// Uni (margin/unified) REST endpoints
 pub const GATE_UNI_MARGIN_CURRENCY_PAIRS: &str = "/api/v4/margin/uni/currency_pairs";
@@ -43,17 +66,25 @@ pub const GATE_FUTURES_SET_LEVERAGE: &str =
     "/api/v4/futures/{settle}/positions/{contract}/leverage";
 pub const GATE_FUTURES_POSITIONS: &str = "/api/v4/futures/{settle}/positions";
 pub const GATE_FUTURES_ORDERS: &str = "/api/v4/futures/{settle}/orders";
+pub const GATE_FUTURES_COUNTDOWN_CANCEL_ALL: &str = "/api/v4/futures/{settle}/countdown_cancel_all";
 pub const GATE_FUTURES_BATCH_ORDERS: &str = "/api/v4/futures/{settle}/batch_orders";
 pub const GATE_FUTURES_BATCH_CANCEL_ORDERS: &str = "/api/v4/futures/{settle}/batch_cancel_orders";
 pub const GATE_FUTURES_ORDER: &str = "/api/v4/futures/{settle}/orders/{order_id}";
+pub const GATE_FUTURES_BATCH_AMEND_ORDERS: &str = "/api/v4/futures/{settle}/batch_amend_orders";
 pub const GATE_WS_FUTURES_ORDERS: &str = "futures.orders";
 pub const GATE_WS_FUTURES_BALANCES: &str = "futures.balances";
 pub const GATE_WS_FUTURES_POSITIONS: &str = "futures.positions";
+pub const GATE_WS_FUTURES_USERTRADES: &str = "futures.usertrades";
 pub const GATE_WS_FUTURES_TRADES: &str = "futures.trades";
 pub const GATE_WS_FUTURES_CANDLES: &str = "futures.candlesticks";
 pub const GATE_WS_FUTURES_BOOK_TICKER: &str = "futures.book_ticker";
 pub const GATE_WS_FUTURES_ORDER_BOOK: &str = "futures.order_book";
 pub const GATE_WS_FUTURES_ORDER_BOOK_UPDATE: &str = "futures.order_book_update";
+pub const GATE_WS_FUTURES_LOGIN: &str = "futures.login";
+pub const GATE_WS_FUTURES_ORDER_PLACE: &str = "futures.order_place";
+pub const GATE_WS_FUTURES_ORDER_BATCH_PLACE: &str = "futures.order_batch_place";
+pub const GATE_WS_FUTURES_ORDER_CANCEL: &str = "futures.order_cancel";
+pub const GATE_WS_FUTURES_ORDER_CANCEL_IDS: &str = "futures.order_cancel_ids";
 
 /// Delivery REST endpoints
 pub const GATE_DELIVERY_CONTRACTS: &str = "/api/v4/delivery/{settle}/contracts";
@@ -74,3 +105,65 @@ pub const GATE_WALLET_ORDER_STATUS: &str = "/api/v4/wallet/order_status";
 pub const GATE_WITHDRAWALS: &str = "/api/v4/withdrawals";
 pub const GATE_WALLET_WITHDRAWALS_LIST: &str = "/api/v4/wallet/withdrawals";
 pub const GATE_WALLET_DEPOSITS_LIST: &str = "/api/v4/wallet/deposits";
+
+/// Header Gate reports what is left of the endpoint's limit in.
+pub const GATE_RATE_LIMIT_REMAIN: &str = "X-Gate-RateLimit-Requests-Remain";
+
+impl RateLimitProfile {
+    /// Gate limits: 100 order placements or amends and 200 cancels per
+    /// second, 200 public and 150 other private requests per 10s. Private
+    /// limits are per user and kept per `KEY`, public ones per IP.
+    pub fn gate() -> Self {
+        let second = Duration::from_secs(1);
+        let ten_seconds = Duration::from_secs(10);
+        let cancel = |method: Method, path: &str| {
+            EndpointRule::exact(path)
+                .with_method(method)
+                .with_cost("cancels", 1)
+                .with_priority(RequestPriority::Cancel)
+        };
+        let order = |method: Method, path: &str| {
+            EndpointRule::exact(path)
+                .with_method(method)
+                .with_cost("orders", 1)
+                .with_priority(RequestPriority::Trade)
+        };
+        let public = |path: &str| EndpointRule::exact(path).with_cost("public", 1);
+
+        Self::new()
+            .with_account_header("KEY")
+            .with_account_bucket("orders", 100, second)
+            .with_account_bucket("cancels", 200, second)
+            .with_bucket("public", 200, ten_seconds)
+            .with_account_bucket("private", 150, ten_seconds)
+            .with_rule(cancel(Method::DELETE, GATE_FUTURES_ORDERS))
+            .with_rule(cancel(Method::DELETE, GATE_FUTURES_ORDER))
+            .with_rule(cancel(Method::POST, GATE_FUTURES_BATCH_CANCEL_ORDERS))
+            .with_rule(cancel(Method::POST, GATE_FUTURES_COUNTDOWN_CANCEL_ALL))
+            .with_rule(cancel(Method::DELETE, GATE_SPOT_ORDERS))
+            .with_rule(cancel(Method::DELETE, GATE_SPOT_ORDER))
+            .with_rule(order(Method::POST, GATE_FUTURES_ORDERS))
+            .with_rule(order(Method::POST, GATE_FUTURES_BATCH_ORDERS))
+            .with_rule(order(Method::PUT, GATE_FUTURES_ORDER))
+            .with_rule(order(Method::POST, GATE_FUTURES_BATCH_AMEND_ORDERS))
+            .with_rule(order(Method::POST, GATE_SPOT_ORDERS))
+            .with_rule(order(Method::PATCH, GATE_SPOT_ORDER))
+            .with_rule(public(GATE_FUTURES_CONTRACTS))
+            .with_rule(public(GATE_FUTURES_CONTRACT))
+            .with_rule(public(GATE_FUTURES_TICKERS))
+            .with_rule(public(GATE_FUTURES_CANDLESTICKS))
+            .with_rule(public(GATE_FUTURES_ORDER_BOOK))
+            .with_rule(public(GATE_FUTURES_PREMIUM_INDEX))
+            .with_rule(public(GATE_FUTURES_FUNDING_RATE))
+            .with_rule(public(GATE_DELIVERY_CONTRACTS))
+            .with_rule(public(GATE_DELIVERY_CONTRACT))
+            .with_rule(public(GATE_SPOT_CURRENCY_PAIRS))
+            .with_rule(public(GATE_SPOT_TICKERS))
+            .with_rule(EndpointRule::prefix("/").with_cost("private", 1))
+            .with_hint(HeaderHint::new(
+                GATE_RATE_LIMIT_REMAIN,
+                None,
+                HintKind::Remaining,
+            ))
+    }
+}
@@ -1,11 +1,14 @@
 use reqwest::Client;
 use std::sync::Arc;
 
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
     market_assets::{
         api_data::utils_data::InstrumentInfo,
-        api_general::{get_seconds_timestamp, parse_json_response},
+        api_general::{get_seconds_timestamp, parse_venue_response},
         base_data::InstrumentType,
+        exchange::endpoints::EndpointProfile,
     },
     traits::{
         conversion::IntoInfraVec,
@@ -16,8 +19,8 @@ use crate::errors::{InfraError, InfraResult};
 
 use super::{
     api_key::{GateKey, read_gate_env_key},
-    api_utils::gate_fut_inst_to_cli,
-    config_assets::{GATE_BASE_URL, GATE_DELIVERY_CONTRACTS},
+    api_utils::{gate_fut_inst_to_cli, get_gate_server_time},
+    config_assets::GATE_DELIVERY_CONTRACTS,
     gate_rest_msg::RestResGate,
     schemas::delivery_rest::contract_delivery::RestContractGateDelivery,
 };
@@ -28,6 +31,8 @@ use tracing::error;
 pub struct GateDeliveryCli {
     pub client: Arc<Client>,
     pub api_key: Option<GateKey>,
+    pub endpoints: EndpointProfile,
+    pub retry: RetryPolicy,
 }
 
 impl Default for GateDeliveryCli {
@@ -46,6 +51,10 @@ impl LobPublicRest for GateDeliveryCli {
         self._get_instrument_info(inst_type).await
     }
 
+    async fn get_server_time(&self) -> InfraResult<u64> {
+        get_gate_server_time(&self.client, &self.retry, &self.endpoints.rest).await
+    }
+
     async fn get_live_instruments(&self, inst_type: InstrumentType) -> InfraResult<Vec<String>> {
         self._get_live_instruments(inst_type).await
     }
@@ -71,9 +80,24 @@ impl GateDeliveryCli {
         Self {
             client: shared_client,
             api_key: None,
+            endpoints: EndpointProfile::gate_production(),
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Sends requests to `endpoints` instead of Gate production.
+    pub fn with_endpoints(mut self, endpoints: EndpointProfile) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Times out and retries REST requests with `retry` instead of
+    /// [`RetryPolicy::default`].
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     async fn _get_delivery_contracts(
         &self,
         settle: &str,
@@ -91,14 +115,14 @@ impl GateDeliveryCli {
         }
 
         let url = if params.is_empty() {
-            [GATE_BASE_URL, &endpoint].concat()
+            [self.endpoints.rest.as_str(), &endpoint].concat()
         } else {
-            format!("{}{}?{}", GATE_BASE_URL, endpoint, params.join("&"))
+            format!("{}{}?{}", self.endpoints.rest, endpoint, params.join("&"))
         };
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResGate<RestContractGateDelivery> =
-            parse_json_response("GateDelivery contracts", response).await?;
+            parse_venue_response(Venue::Gate, "GateDelivery contracts", response).await?;
 
         res.into_vec()
     }
@@ -1,26 +1,32 @@
 use reqwest::Client;
-use serde_json::json;
-use std::sync::Arc;
+use serde_json::{Value, json};
+use std::{sync::Arc, time::Duration};
 use tracing::error;
 
+use crate::arch::market_assets::exchange::clock_sync::ClockSync;
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
     market_assets::{
         api_data::{
-            account_data::{OrderAckData, OrderDetailData, PositionData},
+            account_data::{OrderAckData, OrderDetailData, PositionData, WsOrderResponse},
             price_data::{CandleData, OrderBookData, TickerData},
             utils_data::{FundingRateData, FundingRateInfo, InstrumentInfo},
         },
         api_general::{
-            CancelOrderParams, OrderParams, RequestMethod, get_seconds_timestamp,
-            micros_to_seconds, parse_json_response, value_to_f64,
+            AmendOrderParams, CancelOrderParams, OrderParams, RequestMethod, get_seconds_timestamp,
+            micros_to_seconds, parse_venue_response, value_to_f64, ws_unsubscribe_msg,
         },
-        base_data::{InstrumentType, MarginMode, OrderSide, OrderType, SUBSCRIBE_LOWER},
+        base_data::{
+            InstrumentType, MarginMode, OrderSide, OrderType, SUBSCRIBE_LOWER, UNSUBSCRIBE_LOWER,
+        },
+        exchange::endpoints::EndpointProfile,
     },
-    strategy_base::command::command_core::WsConnectTarget,
+    strategy_base::command::command_core::{SnapshotSource, WsConnectTarget},
     task_execution::task_ws::{CandleParam, LobParam, WsChannel},
     traits::{
         conversion::IntoInfraVec,
-        market_lob::{LobPrivateRest, LobPublicRest, LobWebsocket, MarketLobApi},
+        market_lob::{LobPrivateRest, LobPublicRest, LobWebsocket, LobWsOrderEntry, MarketLobApi},
     },
 };
 use crate::errors::{InfraError, InfraResult};
@@ -30,28 +36,35 @@ use super::{
     api_utils::*,
     config_assets::*,
     gate_rest_msg::RestResGate,
-    schemas::futures_rest::{
-        account_position::RestAccountPosGateFutures,
-        candle::RestCandleGateFutures,
-        contract_futures::RestContractGateFutures,
-        funding_rate::RestFundingRateGateFutures,
-        order::{
-            RestBatchCancelOrderGateFutures, RestBatchOrderGateFutures, RestFuturesOrderGateFutures,
+    schemas::{
+        futures_rest::{
+            account_position::RestAccountPosGateFutures,
+            candle::RestCandleGateFutures,
+            contract_futures::RestContractGateFutures,
+            funding_rate::RestFundingRateGateFutures,
+            order::{
+                RestBatchCancelOrderGateFutures, RestBatchOrderGateFutures,
+                RestFuturesOrderGateFutures,
+            },
+            order_history::RestFuturesOrderHistoryGateFutures,
+            orderbook::RestOrderBookGateFutures,
+            ticker::RestTickerGateFutures,
         },
-        order_history::RestFuturesOrderHistoryGateFutures,
-        orderbook::RestOrderBookGateFutures,
-        ticker::RestTickerGateFutures,
+        futures_ws::trade_order::WsApiResGateFutures,
     },
 };
 
 const OPEN_ORDERS_PAGE_LIMIT: u32 = 100;
-const GATE_FUTURES_BATCH_PLACE_LIMIT: usize = 10;
-const GATE_FUTURES_BATCH_CANCEL_LIMIT: usize = 20;
+pub(crate) const GATE_FUTURES_BATCH_PLACE_LIMIT: usize = 10;
+pub(crate) const GATE_FUTURES_BATCH_CANCEL_LIMIT: usize = 20;
+pub(crate) const GATE_FUTURES_BATCH_AMEND_LIMIT: usize = 10;
 
 #[derive(Clone, Debug)]
 pub struct GateFuturesCli {
     pub client: Arc<Client>,
     pub api_key: Option<GateKey>,
+    pub endpoints: EndpointProfile,
+    pub retry: RetryPolicy,
     public_settles: Vec<String>,
 }
 
@@ -101,6 +114,10 @@ impl LobPublicRest for GateFuturesCli {
         self._get_instrument_info(inst_type).await
     }
 
+    async fn get_server_time(&self) -> InfraResult<u64> {
+        get_gate_server_time(&self.client, &self.retry, &self.endpoints.rest).await
+    }
+
     async fn get_live_instruments(&self, inst_type: InstrumentType) -> InfraResult<Vec<String>> {
         self._get_live_instruments(inst_type).await
     }
@@ -119,7 +136,9 @@ impl LobPrivateRest for GateFuturesCli {
     }
 
     async fn place_order(&self, order_params: OrderParams) -> InfraResult<OrderAckData> {
-        self._place_order(order_params).await
+        self.retry
+            .place_order(self, order_params, |params| self._place_order(params))
+            .await
     }
 
     async fn place_orders(&self, order_params: Vec<OrderParams>) -> InfraResult<Vec<OrderAckData>> {
@@ -142,6 +161,25 @@ impl LobPrivateRest for GateFuturesCli {
         self._cancel_orders(cancel_params).await
     }
 
+    async fn amend_order(&self, amend_params: AmendOrderParams) -> InfraResult<OrderAckData> {
+        self._amend_order(amend_params).await
+    }
+
+    async fn amend_orders(
+        &self,
+        amend_params: Vec<AmendOrderParams>,
+    ) -> InfraResult<Vec<OrderAckData>> {
+        self._amend_orders(amend_params).await
+    }
+
+    async fn cancel_all_orders(&self, inst: Option<&str>) -> InfraResult<Vec<OrderAckData>> {
+        self._cancel_all_orders(inst).await
+    }
+
+    async fn set_cancel_after(&self, inst: Option<&str>, timeout: Duration) -> InfraResult<()> {
+        self._set_cancel_after(inst, timeout).await
+    }
+
     async fn get_open_orders(
         &self,
         inst: &str,
@@ -150,6 +188,15 @@ impl LobPrivateRest for GateFuturesCli {
         self._get_open_orders(inst, limit).await
     }
 
+    async fn get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        self._get_order(inst, order_id, cli_order_id).await
+    }
+
     async fn get_positions(&self, insts: Option<&[String]>) -> InfraResult<Vec<PositionData>> {
         self._get_positions(insts).await
     }
@@ -176,36 +223,84 @@ impl LobWebsocket for GateFuturesCli {
         self._get_public_sub_msg(channel, insts)
     }
 
+    async fn get_public_unsub_msg(
+        &self,
+        channel: &WsChannel,
+        insts: Option<&[String]>,
+    ) -> InfraResult<String> {
+        let sub_msg = self._get_public_sub_msg(channel, insts)?;
+        ws_unsubscribe_msg(&sub_msg, "event", UNSUBSCRIBE_LOWER)
+    }
+
     async fn get_private_sub_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         self._get_private_sub_msg(channel)
     }
 
     async fn get_public_connect_msg(&self, _channel: &WsChannel) -> InfraResult<String> {
-        Ok(GATE_FUTURES_WS_USDT.into())
+        Ok(self.endpoints.ws_public.clone())
     }
 
-    async fn get_private_connect_msg(&self, _channel: &WsChannel) -> InfraResult<String> {
-        Ok(GATE_FUTURES_WS_USDT.into())
+    async fn get_private_connect_msg(&self, channel: &WsChannel) -> InfraResult<String> {
+        Ok(self.private_ws_url(channel).into())
     }
 
-    async fn get_public_connect_target(
-        &self,
-        _channel: &WsChannel,
-    ) -> InfraResult<WsConnectTarget> {
-        Ok(gate_futures_connect_target())
+    async fn get_public_connect_target(&self, channel: &WsChannel) -> InfraResult<WsConnectTarget> {
+        let target = gate_futures_connect_target(&self.endpoints.ws_public);
+        match channel {
+            WsChannel::Lob(_) => {
+                Ok(target.with_snapshot_source(SnapshotSource::GateFutures(self.clone())))
+            },
+            _ => Ok(target),
+        }
     }
 
     async fn get_private_connect_target(
         &self,
-        _channel: &WsChannel,
+        channel: &WsChannel,
     ) -> InfraResult<WsConnectTarget> {
-        Ok(gate_futures_connect_target())
+        Ok(gate_futures_connect_target(self.private_ws_url(channel)))
     }
 }
 
-fn gate_futures_connect_target() -> WsConnectTarget {
-    WsConnectTarget::new(GATE_FUTURES_WS_USDT)
-        .with_header(GATE_SIZE_DECIMAL_HEADER, GATE_SIZE_DECIMAL_HEADER_VALUE)
+impl LobWsOrderEntry for GateFuturesCli {
+    fn ws_place_orders_msg(&self, id: u64, orders: &[OrderParams]) -> InfraResult<String> {
+        self._ws_place_orders_msg(id, orders)
+    }
+
+    fn ws_cancel_orders_msg(&self, id: u64, cancels: &[CancelOrderParams]) -> InfraResult<String> {
+        self._ws_cancel_orders_msg(id, cancels)
+    }
+
+    fn parse_ws_order_response(&self, frame: &[u8]) -> Option<WsOrderResponse> {
+        serde_json::from_slice::<WsApiResGateFutures>(frame)
+            .ok()?
+            .into_response()
+    }
+}
+
+fn gate_futures_connect_target(url: &str) -> WsConnectTarget {
+    WsConnectTarget::new(url).with_header(GATE_SIZE_DECIMAL_HEADER, GATE_SIZE_DECIMAL_HEADER_VALUE)
+}
+
+/// Websocket API request on a logged-in session; the login authenticates
+/// every request after it.
+fn ws_api_msg(id: u64, channel: &str, req_param: Value, channel_id: Option<&str>) -> String {
+    let mut payload = json!({
+        "req_id": id.to_string(),
+        "req_param": req_param,
+    });
+    if let Some(channel_id) = channel_id {
+        payload["req_header"] = json!({ GATE_CHANNEL_ID_HEADER: channel_id });
+    }
+
+    let msg = json!({
+        "time": get_seconds_timestamp(),
+        "channel": channel,
+        "event": "api",
+        "payload": payload,
+    });
+
+    msg.to_string()
 }
 
 impl GateFuturesCli {
@@ -214,9 +309,24 @@ impl GateFuturesCli {
             client: shared_client,
             api_key: None,
             public_settles: vec!["usdt".into(), "btc".into()],
+            endpoints: EndpointProfile::gate_futures_production(),
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Sends requests to `endpoints` instead of Gate production.
+    pub fn with_endpoints(mut self, endpoints: EndpointProfile) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Times out and retries REST requests with `retry` instead of
+    /// [`RetryPolicy::default`].
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     pub fn with_public_settles(mut self, settles: &[&str]) -> Self {
         self.public_settles = settles.iter().map(|settle| (*settle).into()).collect();
         self
@@ -228,7 +338,7 @@ impl GateFuturesCli {
             .as_ref()
             .ok_or(InfraError::ApiCliNotInitialized)?;
 
-        let timestamp = get_seconds_timestamp();
+        let timestamp = ClockSync::now_seconds(Venue::Gate, &self.endpoints.rest);
         let auth = api_key.ws_auth(channel, SUBSCRIBE_LOWER, timestamp)?;
         let payload = vec![api_key.user_id.clone(), "!all".into()];
 
@@ -243,6 +353,25 @@ impl GateFuturesCli {
         Ok(msg.to_string())
     }
 
+    /// Logs the websocket session in for order entry. Send it after
+    /// connecting a `WsChannel::OrderEntry` task and before any order.
+    pub fn ws_login_msg(&self) -> InfraResult<String> {
+        let api_key = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?;
+
+        let timestamp = ClockSync::now_seconds(Venue::Gate, &self.endpoints.rest);
+        let msg = json!({
+            "time": timestamp,
+            "channel": GATE_WS_FUTURES_LOGIN,
+            "event": "api",
+            "payload": api_key.ws_api_login(GATE_WS_FUTURES_LOGIN, "login", timestamp)?,
+        });
+
+        Ok(msg.to_string())
+    }
+
     pub async fn get_funding_rate_history(
         &self,
         settle: &str,
@@ -266,14 +395,14 @@ impl GateFuturesCli {
         }
 
         let url = if params.is_empty() {
-            [GATE_BASE_URL, &endpoint].concat()
+            [self.endpoints.rest.as_str(), &endpoint].concat()
         } else {
-            format!("{}{}?{}", GATE_BASE_URL, endpoint, params.join("&"))
+            format!("{}{}?{}", self.endpoints.rest, endpoint, params.join("&"))
         };
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResGate<RestFundingRateGateFutures> =
-            parse_json_response("GateFutures funding_rate_history", response).await?;
+            parse_venue_response(Venue::Gate, "GateFutures funding_rate_history", response).await?;
 
         let data = res
             .into_vec()?
@@ -319,10 +448,11 @@ impl GateFuturesCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 Some(&params.join("&")),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 &endpoint,
             )
             .await?;
@@ -353,14 +483,14 @@ impl GateFuturesCli {
         }
 
         let url = if params.is_empty() {
-            [GATE_BASE_URL, &endpoint].concat()
+            [self.endpoints.rest.as_str(), &endpoint].concat()
         } else {
-            format!("{}{}?{}", GATE_BASE_URL, endpoint, params.join("&"))
+            format!("{}{}?{}", self.endpoints.rest, endpoint, params.join("&"))
         };
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResGate<RestContractGateFutures> =
-            parse_json_response("GateFutures funding_rate_info", response).await?;
+            parse_venue_response(Venue::Gate, "GateFutures funding_rate_info", response).await?;
 
         let data = res
             .into_vec()?
@@ -388,14 +518,15 @@ impl GateFuturesCli {
         }
 
         let url = if params.is_empty() {
-            [GATE_BASE_URL, &endpoint].concat()
+            [self.endpoints.rest.as_str(), &endpoint].concat()
         } else {
-            format!("{}{}?{}", GATE_BASE_URL, endpoint, params.join("&"))
+            format!("{}{}?{}", self.endpoints.rest, endpoint, params.join("&"))
         };
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResGate<RestContractGateFutures> =
-            parse_json_response("GateFutures funding_rate_live_all", response).await?;
+            parse_venue_response(Venue::Gate, "GateFutures funding_rate_live_all", response)
+                .await?;
 
         let data = res
             .into_vec()?
@@ -415,10 +546,10 @@ impl GateFuturesCli {
             .replace("{settle}", settle)
             .replace("{contract}", &cli_perp_to_gate_inst(inst));
 
-        let url = [GATE_BASE_URL, &endpoint].concat();
-        let response = self.client.get(url).send().await?;
+        let url = [self.endpoints.rest.as_str(), &endpoint].concat();
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResGate<RestContractGateFutures> =
-            parse_json_response("GateFutures funding_rate_live", response).await?;
+            parse_venue_response(Venue::Gate, "GateFutures funding_rate_live", response).await?;
 
         let data = res
             .into_vec()?
@@ -446,19 +577,18 @@ impl GateFuturesCli {
         }
 
         let url = if params.is_empty() {
-            [GATE_BASE_URL, &endpoint].concat()
+            [self.endpoints.rest.as_str(), &endpoint].concat()
         } else {
-            format!("{}{}?{}", GATE_BASE_URL, endpoint, params.join("&"))
+            format!("{}{}?{}", self.endpoints.rest, endpoint, params.join("&"))
         };
 
-        let response = self
+        let request = self
             .client
             .get(url)
-            .header(GATE_SIZE_DECIMAL_HEADER, GATE_SIZE_DECIMAL_HEADER_VALUE)
-            .send()
-            .await?;
+            .header(GATE_SIZE_DECIMAL_HEADER, GATE_SIZE_DECIMAL_HEADER_VALUE);
+        let response = self.retry.send(request).await?;
         let res: RestResGate<RestContractGateFutures> =
-            parse_json_response("GateFutures futures_contracts", response).await?;
+            parse_venue_response(Venue::Gate, "GateFutures futures_contracts", response).await?;
 
         res.into_vec()
     }
@@ -472,11 +602,11 @@ impl GateFuturesCli {
 
         for settle in &self.public_settles {
             let endpoint = GATE_FUTURES_TICKERS.replace("{settle}", settle);
-            let url = [GATE_BASE_URL, &endpoint].concat();
+            let url = [self.endpoints.rest.as_str(), &endpoint].concat();
 
-            let response = self.client.get(url).send().await?;
+            let response = self.retry.send(self.client.get(url)).await?;
             let res: RestResGate<RestTickerGateFutures> =
-                parse_json_response("GateFutures tickers", response).await?;
+                parse_venue_response(Venue::Gate, "GateFutures tickers", response).await?;
 
             data.extend(
                 res.into_vec()?
@@ -517,11 +647,11 @@ impl GateFuturesCli {
             params.push(format!("to={}", end_time_us / 1_000_000));
         }
 
-        let url = format!("{}{}?{}", GATE_BASE_URL, endpoint, params.join("&"));
+        let url = format!("{}{}?{}", self.endpoints.rest, endpoint, params.join("&"));
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResGate<RestCandleGateFutures> =
-            parse_json_response("GateFutures candles", response).await?;
+            parse_venue_response(Venue::Gate, "GateFutures candles", response).await?;
 
         let mut data: Vec<CandleData> = res
             .into_vec()?
@@ -564,12 +694,13 @@ impl GateFuturesCli {
         let params = [
             format!("contract={}", cli_perp_to_gate_inst(inst)),
             format!("limit={depth}"),
+            "with_id=true".to_string(),
         ];
-        let url = format!("{}{}?{}", GATE_BASE_URL, endpoint, params.join("&"));
+        let url = format!("{}{}?{}", self.endpoints.rest, endpoint, params.join("&"));
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResGate<RestOrderBookGateFutures> =
-            parse_json_response("GateFutures orderbook", response).await?;
+            parse_venue_response(Venue::Gate, "GateFutures orderbook", response).await?;
 
         res.into_vec()?
             .into_iter()
@@ -679,9 +810,10 @@ impl GateFuturesCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_post_request_with_channel_id(
                 &self.client,
+                &self.retry,
                 None,
                 Some(&body.to_string()),
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 &endpoint,
                 gate_channel_id.as_deref(),
             )
@@ -738,9 +870,10 @@ impl GateFuturesCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_post_request_with_channel_id(
                 &self.client,
+                &self.retry,
                 None,
                 Some(&body),
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 &endpoint,
                 channel_id.as_deref(),
             )
@@ -790,10 +923,11 @@ impl GateFuturesCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Delete,
                 None,
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 &endpoint,
             )
             .await?;
@@ -847,10 +981,11 @@ impl GateFuturesCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 None,
                 Some(&body),
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 &endpoint,
             )
             .await?;
@@ -873,6 +1008,104 @@ impl GateFuturesCli {
         Ok(data)
     }
 
+    async fn _amend_order(&self, amend_params: AmendOrderParams) -> InfraResult<OrderAckData> {
+        let params = GateFuturesAmendParams::try_from(&amend_params)?;
+        let endpoint = GATE_FUTURES_ORDER
+            .replace("{settle}", &params.settle)
+            .replace("{order_id}", &params.path_id);
+        let body = serde_json::to_string(&params.order.amend)?;
+
+        let res: RestResGate<RestFuturesOrderGateFutures> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Put,
+                None,
+                Some(&body),
+                &self.endpoints.rest,
+                &endpoint,
+            )
+            .await?;
+
+        let data = res
+            .into_vec()?
+            .into_iter()
+            .map(OrderAckData::from)
+            .next()
+            .ok_or(InfraError::ApiCliError(
+                "No Gate Futures amend ack data returned".into(),
+            ))?;
+
+        Ok(data)
+    }
+
+    async fn _amend_orders(
+        &self,
+        amend_params: Vec<AmendOrderParams>,
+    ) -> InfraResult<Vec<OrderAckData>> {
+        if amend_params.is_empty() {
+            return Ok(Vec::new());
+        }
+        if amend_params.len() > GATE_FUTURES_BATCH_AMEND_LIMIT {
+            return Err(InfraError::ApiCliError(format!(
+                "Gate Futures batch amend supports at most {GATE_FUTURES_BATCH_AMEND_LIMIT} orders"
+            )));
+        }
+
+        let orders = amend_params
+            .iter()
+            .map(GateFuturesAmendParams::try_from)
+            .collect::<InfraResult<Vec<_>>>()?;
+        let settle = orders[0].settle.clone();
+        if orders.iter().any(|order| order.settle != settle) {
+            return Err(InfraError::ApiCliError(
+                "Gate Futures batch amend requires one settle".into(),
+            ));
+        }
+
+        let endpoint = GATE_FUTURES_BATCH_AMEND_ORDERS.replace("{settle}", &settle);
+        let body = serde_json::to_string(
+            &orders
+                .into_iter()
+                .map(|order| order.order)
+                .collect::<Vec<_>>(),
+        )?;
+        let res: RestResGate<RestBatchOrderGateFutures> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Post,
+                None,
+                Some(&body),
+                &self.endpoints.rest,
+                &endpoint,
+            )
+            .await?;
+
+        let responds = res.into_vec()?;
+        if responds.len() != amend_params.len() {
+            return Err(InfraError::ApiCliError(format!(
+                "Gate Futures batch amend returned {} result(s) for {} order(s)",
+                responds.len(),
+                amend_params.len()
+            )));
+        }
+
+        let data: Vec<OrderAckData> = responds
+            .into_iter()
+            .zip(amend_params)
+            .map(|(ack, amend)| ack.into_order_ack(amend.cli_order_id))
+            .collect();
+
+        Ok(data)
+    }
+
     async fn _get_positions(&self, insts: Option<&[String]>) -> InfraResult<Vec<PositionData>> {
         let settles: Vec<String> = if let Some(list) = insts {
             let mut s = Vec::new();
@@ -896,10 +1129,11 @@ impl GateFuturesCli {
                 .ok_or(InfraError::ApiCliNotInitialized)?
                 .send_signed_request(
                     &self.client,
+                    &self.retry,
                     RequestMethod::Get,
                     None,
                     None,
-                    GATE_BASE_URL,
+                    &self.endpoints.rest,
                     &endpoint,
                 )
                 .await?;
@@ -931,6 +1165,75 @@ impl GateFuturesCli {
         Ok(data)
     }
 
+    async fn _cancel_all_orders(&self, inst: Option<&str>) -> InfraResult<Vec<OrderAckData>> {
+        let inst = inst.ok_or(InfraError::ApiCliError(
+            "Gate Futures cancel_all_orders requires inst".into(),
+        ))?;
+        let settle = infer_settle_from_inst(inst);
+        let endpoint = GATE_FUTURES_ORDERS.replace("{settle}", &settle);
+        let query_string = format!("contract={}", cli_perp_to_gate_inst(inst));
+
+        let res: RestResGate<RestFuturesOrderGateFutures> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Delete,
+                Some(&query_string),
+                None,
+                &self.endpoints.rest,
+                &endpoint,
+            )
+            .await?;
+
+        let data = res
+            .into_vec()?
+            .into_iter()
+            .map(OrderAckData::from)
+            .collect();
+
+        Ok(data)
+    }
+
+    /// Arms the countdown for `inst`, or for the whole USDT-settled account
+    /// when `inst` is `None`.
+    async fn _set_cancel_after(&self, inst: Option<&str>, timeout: Duration) -> InfraResult<()> {
+        let secs = timeout.as_secs();
+        if secs != 0 && secs < 5 {
+            return Err(InfraError::ApiCliError(
+                "Gate Futures countdown timeout must be 0 or at least 5 seconds".into(),
+            ));
+        }
+
+        let settle = inst.map_or_else(|| "usdt".to_string(), infer_settle_from_inst);
+        let endpoint = GATE_FUTURES_COUNTDOWN_CANCEL_ALL.replace("{settle}", &settle);
+        let mut body = json!({ "timeout": secs });
+        if let Some(inst) = inst {
+            body["contract"] = json!(cli_perp_to_gate_inst(inst));
+        }
+        let body = body.to_string();
+
+        let res: RestResGate<serde_json::Value> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Post,
+                None,
+                Some(&body),
+                &self.endpoints.rest,
+                &endpoint,
+            )
+            .await?;
+        res.into_vec()?;
+
+        Ok(())
+    }
+
     async fn _get_open_orders(
         &self,
         inst: &str,
@@ -995,10 +1298,11 @@ impl GateFuturesCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query_string),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 &endpoint,
             )
             .await?;
@@ -1013,6 +1317,31 @@ impl GateFuturesCli {
         Ok(data)
     }
 
+    /// Gate resolves a `t-` client order id while the order is open and
+    /// for a short while after it finishes.
+    async fn _get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        let order_id = match (order_id, cli_order_id) {
+            (Some(order_id), _) => order_id.to_string(),
+            (None, Some(cli_order_id)) => normalize_gate_text(cli_order_id),
+            (None, None) => {
+                return Err(InfraError::ApiCliError(
+                    "Gate Futures get_order requires order_id or cli_order_id".into(),
+                ));
+            },
+        };
+
+        let orders = self
+            ._get_order_history(inst, None, None, None, Some(&order_id))
+            .await?;
+
+        Ok(orders.into_iter().next())
+    }
+
     async fn _get_order_history(
         &self,
         inst: &str,
@@ -1054,10 +1383,11 @@ impl GateFuturesCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 query_string.as_deref(),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 &endpoint,
             )
             .await?;
@@ -1149,10 +1479,69 @@ impl GateFuturesCli {
         }
     }
 
+    fn private_ws_url(&self, channel: &WsChannel) -> &str {
+        match channel {
+            WsChannel::OrderEntry => &self.endpoints.ws_trade,
+            _ => &self.endpoints.ws_private,
+        }
+    }
+
+    fn _ws_place_orders_msg(&self, id: u64, orders: &[OrderParams]) -> InfraResult<String> {
+        if orders.is_empty() || orders.len() > GATE_FUTURES_BATCH_PLACE_LIMIT {
+            return Err(InfraError::ApiCliError(format!(
+                "Gate Futures websocket place takes 1 to {GATE_FUTURES_BATCH_PLACE_LIMIT} orders"
+            )));
+        }
+
+        let orders = orders
+            .iter()
+            .map(GateFuturesBatchOrderParams::try_from)
+            .collect::<InfraResult<Vec<_>>>()?;
+        let channel_id = orders[0].channel_id.clone();
+        if orders.iter().any(|order| order.channel_id != channel_id) {
+            return Err(InfraError::ApiCliError(
+                "Gate Futures batch place requires one channel id".into(),
+            ));
+        }
+
+        let mut orders: Vec<_> = orders.into_iter().map(|order| order.order).collect();
+        let (channel, req_param) = if orders.len() == 1 {
+            (GATE_WS_FUTURES_ORDER_PLACE, json!(orders.remove(0)))
+        } else {
+            (GATE_WS_FUTURES_ORDER_BATCH_PLACE, json!(orders))
+        };
+
+        Ok(ws_api_msg(id, channel, req_param, channel_id.as_deref()))
+    }
+
+    fn _ws_cancel_orders_msg(&self, id: u64, cancels: &[CancelOrderParams]) -> InfraResult<String> {
+        if cancels.is_empty() || cancels.len() > GATE_FUTURES_BATCH_CANCEL_LIMIT {
+            return Err(InfraError::ApiCliError(format!(
+                "Gate Futures websocket cancel takes 1 to {GATE_FUTURES_BATCH_CANCEL_LIMIT} orders"
+            )));
+        }
+
+        let mut order_ids: Vec<_> = cancels
+            .iter()
+            .map(|cancel| GateFuturesBatchCancelParams::try_from(cancel).map(|c| c.order_id))
+            .collect::<InfraResult<_>>()?;
+        let (channel, req_param) = if order_ids.len() == 1 {
+            (
+                GATE_WS_FUTURES_ORDER_CANCEL,
+                json!({ "order_id": order_ids.remove(0) }),
+            )
+        } else {
+            (GATE_WS_FUTURES_ORDER_CANCEL_IDS, json!(order_ids))
+        };
+
+        Ok(ws_api_msg(id, channel, req_param, None))
+    }
+
     fn _get_private_sub_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         let topic = match channel {
             WsChannel::AccountOrders => GATE_WS_FUTURES_ORDERS,
             WsChannel::AccountPositions => GATE_WS_FUTURES_POSITIONS,
+            WsChannel::AccountFills => GATE_WS_FUTURES_USERTRADES,
             _ => return Err(InfraError::Unimplemented),
         };
         self.ws_subscribe_private(topic)
@@ -1161,13 +1550,23 @@ impl GateFuturesCli {
 
 #[cfg(test)]
 mod tests {
+    use serde_json::Value;
+
     use crate::arch::{
-        market_assets::exchange::gate::{
-            api_utils::{GATE_SIZE_DECIMAL_HEADER, GATE_SIZE_DECIMAL_HEADER_VALUE},
-            gate_futures_cli::GateFuturesCli,
+        market_assets::{
+            api_general::{CancelOrderParams, OrderParams},
+            base_data::{OrderSide, OrderType},
+            exchange::{
+                endpoints::EndpointProfile,
+                gate::{
+                    api_utils::{GATE_SIZE_DECIMAL_HEADER, GATE_SIZE_DECIMAL_HEADER_VALUE},
+                    gate_futures_cli::GateFuturesCli,
+                },
+            },
         },
+        strategy_base::command::command_core::SnapshotSource,
         task_execution::task_ws::{LobFrequency, LobParam, WsChannel},
-        traits::market_lob::LobWebsocket,
+        traits::market_lob::{LobWebsocket, LobWsOrderEntry},
     };
 
     #[tokio::test]
@@ -1262,4 +1661,93 @@ mod tests {
             .await;
         assert!(err.is_err());
     }
+
+    #[tokio::test]
+    async fn gate_futures_connect_target_follows_endpoint_override() {
+        let endpoints = EndpointProfile::gate_futures_production()
+            .with_ws_public("ws://127.0.0.1:9000/public")
+            .with_ws_private("ws://127.0.0.1:9000/private");
+        let cli = GateFuturesCli::default().with_endpoints(endpoints);
+
+        let public = cli
+            .get_public_connect_target(&WsChannel::Lob(None))
+            .await
+            .unwrap();
+        let private = cli
+            .get_private_connect_target(&WsChannel::AccountOrders)
+            .await
+            .unwrap();
+
+        assert_eq!(public.url, "ws://127.0.0.1:9000/public");
+        assert_eq!(private.url, "ws://127.0.0.1:9000/private");
+        assert!(
+            private
+                .headers
+                .iter()
+                .any(|(name, _)| name == GATE_SIZE_DECIMAL_HEADER)
+        );
+        assert_eq!(
+            public.snapshot.as_deref(),
+            Some(&SnapshotSource::GateFutures(cli.clone()))
+        );
+        assert_eq!(private.snapshot, None);
+    }
+
+    #[tokio::test]
+    async fn builds_ws_order_entry_requests() {
+        let endpoints =
+            EndpointProfile::gate_futures_production().with_ws_trade("ws://127.0.0.1:9000/trade");
+        let cli = GateFuturesCli::default().with_endpoints(endpoints);
+        let target = cli
+            .get_private_connect_target(&WsChannel::OrderEntry)
+            .await
+            .unwrap();
+        assert_eq!(target.url, "ws://127.0.0.1:9000/trade");
+
+        let order = OrderParams {
+            inst: "BTC_USDT_PERP".into(),
+            side: OrderSide::SELL,
+            size: "2".into(),
+            order_type: OrderType::Limit,
+            price: Some("50000".into()),
+            client_order_id: Some("a1".into()),
+            ..Default::default()
+        };
+        let single: Value = serde_json::from_str(
+            &cli.ws_place_orders_msg(7, std::slice::from_ref(&order))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(single["channel"], "futures.order_place");
+        assert_eq!(single["payload"]["req_id"], "7");
+        assert_eq!(single["payload"]["req_param"]["contract"], "BTC_USDT");
+        assert_eq!(single["payload"]["req_param"]["size"], "-2");
+
+        let batch: Value =
+            serde_json::from_str(&cli.ws_place_orders_msg(8, &[order.clone(), order]).unwrap())
+                .unwrap();
+        assert_eq!(batch["channel"], "futures.order_batch_place");
+        assert_eq!(batch["payload"]["req_param"].as_array().unwrap().len(), 2);
+
+        let cancel = |order_id: &str| CancelOrderParams {
+            inst: "BTC_USDT_PERP".into(),
+            order_id: Some(order_id.into()),
+            cli_order_id: None,
+        };
+        let single: Value =
+            serde_json::from_str(&cli.ws_cancel_orders_msg(9, &[cancel("1")]).unwrap()).unwrap();
+        assert_eq!(single["channel"], "futures.order_cancel");
+        assert_eq!(single["payload"]["req_param"]["order_id"], "1");
+
+        let batch: Value = serde_json::from_str(
+            &cli.ws_cancel_orders_msg(10, &[cancel("1"), cancel("2")])
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(batch["channel"], "futures.order_cancel_ids");
+        assert_eq!(batch["payload"]["req_param"], serde_json::json!(["1", "2"]));
+
+        assert!(cli.ws_place_orders_msg(11, &[]).is_err());
+        assert!(cli.ws_login_msg().is_err());
+    }
 }
@@ -1,8 +1,14 @@
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 
-use crate::arch::traits::conversion::IntoInfraVec;
-use crate::errors::{InfraError, InfraResult};
+use crate::arch::{
+    market_assets::{
+        api_general::de_micros_from_int,
+        exchange::exchange_error::{ErrorCategory, ExchangeError, Venue},
+    },
+    traits::conversion::IntoInfraVec,
+};
+use crate::errors::InfraResult;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(untagged)]
@@ -23,11 +29,85 @@ impl<T> IntoInfraVec<T> for RestResGate<T> {
             Self::ObjectField { data } => Ok(data.into_iter().collect()),
             Self::Error { label, message } => {
                 warn!("Gate REST error {}: {}", label, message);
-                Err(InfraError::ApiCliError(format!(
-                    "Gate REST error (label={}): {}",
-                    label, message
-                )))
+                Err(ExchangeError::gate(&label, message).into())
             },
         }
     }
 }
+
+/// Body of the spot `time` endpoint.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RestServerTimeGate {
+    #[serde(deserialize_with = "de_micros_from_int")]
+    pub server_time: u64,
+}
+
+/// Error body Gate sends with non-success HTTP statuses.
+#[derive(Deserialize)]
+struct GateErrorBody {
+    label: String,
+    #[serde(default)]
+    message: String,
+}
+
+impl ExchangeError {
+    /// Gate rejection with its `label`.
+    pub(crate) fn gate(label: &str, message: impl Into<String>) -> Self {
+        Self::new(Venue::Gate, gate_category(label), label, message)
+    }
+
+    pub(crate) fn gate_body(body: &[u8]) -> Option<Self> {
+        let body: GateErrorBody = serde_json::from_slice(body).ok()?;
+        Some(Self::gate(&body.label, body.message))
+    }
+}
+
+fn gate_category(label: &str) -> ErrorCategory {
+    match label {
+        "BALANCE_NOT_ENOUGH" | "INSUFFICIENT_AVAILABLE" | "MARGIN_BALANCE_NOT_ENOUGH" => {
+            ErrorCategory::InsufficientBalance
+        },
+        "TOO_MANY_REQUESTS" => ErrorCategory::RateLimited,
+        "PRICE_TOO_DEVIATED" | "INVALID_PRICE" => ErrorCategory::InvalidPrice,
+        "ORDER_POC_IMMEDIATE" | "POC_FILL_IMMEDIATELY" => ErrorCategory::PostOnlyWouldCross,
+        "REDUCE_EXCEEDED" | "REDUCE_ONLY_FAIL" => ErrorCategory::ReduceOnlyRejected,
+        "ORDER_NOT_FOUND" | "ORDER_CLOSED" => ErrorCategory::UnknownOrder,
+        "INVALID_KEY"
+        | "INVALID_SIGNATURE"
+        | "MISSING_REQUIRED_HEADER"
+        | "REQUEST_EXPIRED"
+        | "IP_FORBIDDEN"
+        | "FORBIDDEN" => ErrorCategory::AuthFailed,
+        "SERVER_ERROR" => ErrorCategory::Maintenance,
+        _ => ErrorCategory::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::InfraError;
+
+    #[test]
+    fn maps_labels_to_categories() {
+        let res: RestResGate<serde_json::Value> = serde_json::from_str(
+            r#"{"label":"ORDER_POC_IMMEDIATE","message":"order would match immediately"}"#,
+        )
+        .unwrap();
+        match res.into_vec() {
+            Err(InfraError::Exchange(e)) => {
+                assert_eq!(e.category, ErrorCategory::PostOnlyWouldCross);
+                assert_eq!(e.code, "ORDER_POC_IMMEDIATE");
+            },
+            other => panic!("unexpected result {other:?}"),
+        }
+
+        assert_eq!(
+            ExchangeError::gate_body(br#"{"label":"INVALID_KEY","message":"Invalid key"}"#)
+                .unwrap()
+                .category,
+            ErrorCategory::AuthFailed
+        );
+        assert!(ExchangeError::gate_body(b"<html></html>").is_none());
+    }
+}
@@ -3,6 +3,9 @@ use serde_json::json;
 use std::sync::Arc;
 use tracing::error;
 
+use crate::arch::market_assets::exchange::clock_sync::ClockSync;
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
     market_assets::{
         api_data::{
@@ -10,11 +13,9 @@ use crate::arch::{
             price_data::TickerData,
             utils_data::InstrumentInfo,
         },
-        api_general::{
-            OrderParams, RequestMethod, get_seconds_timestamp, micros_to_seconds,
-            parse_json_response,
-        },
+        api_general::{OrderParams, RequestMethod, micros_to_seconds, parse_venue_response},
         base_data::{InstrumentType, OrderSide, OrderType, SUBSCRIBE_LOWER, TimeInForce},
+        exchange::endpoints::EndpointProfile,
         exchange::gate::{
             config_assets::*,
             gate_rest_msg::RestResGate,
@@ -60,6 +61,8 @@ const OPEN_ORDERS_PAGE_LIMIT: u32 = 100;
 pub struct GateSpotCli {
     pub client: Arc<Client>,
     pub api_key: Option<GateKey>,
+    pub endpoints: EndpointProfile,
+    pub retry: RetryPolicy,
 }
 
 impl Default for GateSpotCli {
@@ -86,6 +89,10 @@ impl LobPublicRest for GateSpotCli {
         self._get_instrument_info(inst_type).await
     }
 
+    async fn get_server_time(&self) -> InfraResult<u64> {
+        get_gate_server_time(&self.client, &self.retry, &self.endpoints.rest).await
+    }
+
     async fn get_live_instruments(&self, inst_type: InstrumentType) -> InfraResult<Vec<String>> {
         self._get_live_instruments(inst_type).await
     }
@@ -104,7 +111,9 @@ impl LobPrivateRest for GateSpotCli {
     }
 
     async fn place_order(&self, order_params: OrderParams) -> InfraResult<OrderAckData> {
-        self._place_order(order_params).await
+        self.retry
+            .place_order(self, order_params, |params| self._place_order(params))
+            .await
     }
 
     async fn cancel_order(
@@ -124,6 +133,15 @@ impl LobPrivateRest for GateSpotCli {
         self._get_open_orders(inst, limit).await
     }
 
+    async fn get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        self._get_order(inst, order_id, cli_order_id).await
+    }
+
     async fn get_balance(&self, assets: Option<&[String]>) -> InfraResult<Vec<BalanceData>> {
         self._get_balance(assets).await
     }
@@ -147,14 +165,14 @@ impl LobWebsocket for GateSpotCli {
     }
 
     async fn get_private_connect_msg(&self, _channel: &WsChannel) -> InfraResult<String> {
-        Ok(GATE_WS_BASE_URL.into())
+        Ok(self.endpoints.ws_private.clone())
     }
 
     async fn get_private_connect_target(
         &self,
         _channel: &WsChannel,
     ) -> InfraResult<WsConnectTarget> {
-        Ok(WsConnectTarget::new(GATE_WS_BASE_URL))
+        Ok(WsConnectTarget::new(self.endpoints.ws_private.clone()))
     }
 }
 
@@ -163,9 +181,24 @@ impl GateSpotCli {
         Self {
             client: shared_client,
             api_key: None,
+            endpoints: EndpointProfile::gate_production(),
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Sends requests to `endpoints` instead of Gate production.
+    pub fn with_endpoints(mut self, endpoints: EndpointProfile) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Times out and retries REST requests with `retry` instead of
+    /// [`RetryPolicy::default`].
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     pub async fn withdraw(&self, req: GateWithdrawReq) -> InfraResult<RestWithdrawGate> {
         let res: RestResGate<RestWithdrawGate> = self
             .api_key
@@ -173,10 +206,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 None,
                 Some(&req.to_body_string()),
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_WITHDRAWALS,
             )
             .await?;
@@ -204,10 +238,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 query.as_deref(),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_WALLET_WITHDRAWALS_LIST,
             )
             .await?;
@@ -227,10 +262,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 query.as_deref(),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_WALLET_DEPOSITS_LIST,
             )
             .await?;
@@ -248,10 +284,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 None,
                 Some(&req.to_body_string()),
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_WALLET_SUB_ACCOUNT_TRANSFERS,
             )
             .await?;
@@ -279,10 +316,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 query.as_deref(),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_WALLET_SUB_ACCOUNT_TRANSFERS,
             )
             .await?;
@@ -300,10 +338,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 None,
                 Some(&req.to_body_string()),
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_WALLET_SUB_ACCOUNT_TO_SUB_ACCOUNT,
             )
             .await?;
@@ -335,10 +374,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_WALLET_ORDER_STATUS,
             )
             .await?;
@@ -365,10 +405,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_WALLET_CURRENCY_CHAINS,
             )
             .await?;
@@ -384,10 +425,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_WALLET_DEPOSIT_ADDRESS,
             )
             .await?;
@@ -424,10 +466,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_WALLET_SAVED_ADDRESS,
             )
             .await?;
@@ -441,7 +484,7 @@ impl GateSpotCli {
             .as_ref()
             .ok_or(InfraError::ApiCliNotInitialized)?;
 
-        let timestamp = get_seconds_timestamp();
+        let timestamp = ClockSync::now_seconds(Venue::Gate, &self.endpoints.rest);
         let auth = api_key.ws_auth(channel, SUBSCRIBE_LOWER, timestamp)?;
         let payload = vec!["!all".to_string()];
 
@@ -461,11 +504,11 @@ impl GateSpotCli {
         insts: Option<&[String]>,
         _inst_type: Option<InstrumentType>,
     ) -> InfraResult<Vec<TickerData>> {
-        let url = [GATE_BASE_URL, GATE_SPOT_TICKERS].concat();
+        let url = [self.endpoints.rest.as_str(), GATE_SPOT_TICKERS].concat();
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResGate<RestTickerGateSpot> =
-            parse_json_response("GateSpot tickers", response).await?;
+            parse_venue_response(Venue::Gate, "GateSpot tickers", response).await?;
 
         let data = res
             .into_vec()?
@@ -484,11 +527,11 @@ impl GateSpotCli {
         &self,
         _inst_type: InstrumentType,
     ) -> InfraResult<Vec<InstrumentInfo>> {
-        let url = [GATE_BASE_URL, GATE_SPOT_CURRENCY_PAIRS].concat();
+        let url = [self.endpoints.rest.as_str(), GATE_SPOT_CURRENCY_PAIRS].concat();
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResGate<RestCurrencyPairGateSpot> =
-            parse_json_response("GateSpot instrument_info", response).await?;
+            parse_venue_response(Venue::Gate, "GateSpot instrument_info", response).await?;
 
         let data = res
             .into_vec()?
@@ -500,11 +543,11 @@ impl GateSpotCli {
     }
 
     async fn _get_live_instruments(&self, _inst_type: InstrumentType) -> InfraResult<Vec<String>> {
-        let url = [GATE_BASE_URL, GATE_SPOT_CURRENCY_PAIRS].concat();
+        let url = [self.endpoints.rest.as_str(), GATE_SPOT_CURRENCY_PAIRS].concat();
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResGate<RestCurrencyPairGateSpot> =
-            parse_json_response("GateSpot live_instruments", response).await?;
+            parse_venue_response(Venue::Gate, "GateSpot live_instruments", response).await?;
 
         let data = res
             .into_vec()?
@@ -523,10 +566,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 None,
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_SPOT_ACCOUNTS,
             )
             .await?;
@@ -598,10 +642,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query_string),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_SPOT_ORDERS,
             )
             .await?;
@@ -616,6 +661,31 @@ impl GateSpotCli {
         Ok(data)
     }
 
+    /// Gate resolves a `t-` client order id while the order is open and
+    /// for a short while after it finishes.
+    async fn _get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        let order_id = match (order_id, cli_order_id) {
+            (Some(order_id), _) => order_id.to_string(),
+            (None, Some(cli_order_id)) => normalize_gate_text(cli_order_id),
+            (None, None) => {
+                return Err(InfraError::ApiCliError(
+                    "Gate Spot get_order requires order_id or cli_order_id".into(),
+                ));
+            },
+        };
+
+        let orders = self
+            ._get_order_history(inst, None, None, None, Some(&order_id))
+            .await?;
+
+        Ok(orders.into_iter().next())
+    }
+
     async fn _get_order_history(
         &self,
         inst: &str,
@@ -650,10 +720,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query_string),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 &endpoint,
             )
             .await?;
@@ -745,9 +816,10 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_post_request_with_channel_id(
                 &self.client,
+                &self.retry,
                 None,
                 Some(&body.to_string()),
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_SPOT_ORDERS,
                 gate_channel_id.as_deref(),
             )
@@ -788,10 +860,11 @@ impl GateSpotCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Delete,
                 Some(&query_string),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 &endpoint,
             )
             .await?;
@@ -811,6 +884,7 @@ impl GateSpotCli {
     fn _get_private_sub_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         let topic = match channel {
             WsChannel::AccountOrders => GATE_WS_SPOT_ORDERS_V2,
+            WsChannel::AccountFills => GATE_WS_SPOT_USERTRADES,
             _ => return Err(InfraError::Unimplemented),
         };
         self.ws_subscribe_private(topic)
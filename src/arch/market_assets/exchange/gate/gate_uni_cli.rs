@@ -7,9 +7,10 @@ use crate::arch::{
     market_assets::{
         api_data::account_data::{BalanceData, BorrowableData},
         api_general::RequestMethod,
+        exchange::endpoints::EndpointProfile,
         exchange::gate::{
             config_assets::{
-                GATE_BASE_URL, GATE_UNI_ACCOUNTS, GATE_UNI_BATCH_BORROWABLE, GATE_UNI_CURRENCIES,
+                GATE_UNI_ACCOUNTS, GATE_UNI_BATCH_BORROWABLE, GATE_UNI_CURRENCIES,
                 GATE_UNI_ESTIMATE_RATE, GATE_UNI_LOANS,
             },
             gate_rest_msg::RestResGate,
@@ -20,6 +21,7 @@ use crate::arch::{
                 loans::{RestLoanGateUnified, RestLoanTranGateUnified},
             },
         },
+        exchange::retry::RetryPolicy,
     },
     traits::{
         conversion::IntoInfraVec,
@@ -30,13 +32,15 @@ use crate::errors::{InfraError, InfraResult};
 
 use super::{
     api_key::{GateKey, read_gate_env_key},
-    api_utils::normalize_gate_text,
+    api_utils::{get_gate_server_time, normalize_gate_text},
 };
 
 #[derive(Clone, Debug)]
 pub struct GateUniCli {
     pub client: Arc<Client>,
     pub api_key: Option<GateKey>,
+    pub endpoints: EndpointProfile,
+    pub retry: RetryPolicy,
 }
 
 impl Default for GateUniCli {
@@ -47,7 +51,11 @@ impl Default for GateUniCli {
 
 impl MarketLobApi for GateUniCli {}
 
-impl LobPublicRest for GateUniCli {}
+impl LobPublicRest for GateUniCli {
+    async fn get_server_time(&self) -> InfraResult<u64> {
+        get_gate_server_time(&self.client, &self.retry, &self.endpoints.rest).await
+    }
+}
 
 impl LobPrivateRest for GateUniCli {
     fn init_api_key(&mut self) {
@@ -73,9 +81,24 @@ impl GateUniCli {
         Self {
             client: shared_client,
             api_key: None,
+            endpoints: EndpointProfile::gate_production(),
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Sends requests to `endpoints` instead of Gate production.
+    pub fn with_endpoints(mut self, endpoints: EndpointProfile) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Times out and retries REST requests with `retry` instead of
+    /// [`RetryPolicy::default`].
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     pub async fn get_eq(&self) -> InfraResult<f64> {
         let res: RestResGate<RestAccountBalGateUnified> = self
             .api_key
@@ -83,10 +106,11 @@ impl GateUniCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 None,
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_UNI_ACCOUNTS,
             )
             .await?;
@@ -117,10 +141,11 @@ impl GateUniCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_UNI_BATCH_BORROWABLE,
             )
             .await?;
@@ -152,10 +177,11 @@ impl GateUniCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_UNI_CURRENCIES,
             )
             .await?;
@@ -197,10 +223,11 @@ impl GateUniCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 None,
                 Some(&body.to_string()),
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_UNI_LOANS,
             )
             .await?;
@@ -252,10 +279,11 @@ impl GateUniCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 query_opt,
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_UNI_LOANS,
             )
             .await?;
@@ -279,10 +307,11 @@ impl GateUniCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 Some(&query),
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_UNI_ESTIMATE_RATE,
             )
             .await?;
@@ -318,10 +347,11 @@ impl GateUniCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 None,
                 None,
-                GATE_BASE_URL,
+                &self.endpoints.rest,
                 GATE_UNI_ACCOUNTS,
             )
             .await?;
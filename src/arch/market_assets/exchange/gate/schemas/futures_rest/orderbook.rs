@@ -7,6 +7,8 @@ use crate::arch::market_assets::{
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct RestOrderBookGateFutures {
+    #[serde(default)]
+    pub id: Option<u64>,
     #[serde(default)]
     pub current: Option<serde_json::Value>,
     #[serde(default)]
@@ -36,6 +38,7 @@ impl RestOrderBookGateFutures {
             inst: inst.to_string(),
             bids: levels_to_pairs(self.bids),
             asks: levels_to_pairs(self.asks),
+            update_id: self.id,
         }
     }
 }
@@ -77,6 +80,7 @@ mod tests {
     #[test]
     fn parses_gate_futures_object_orderbook_snapshot() {
         let raw = r#"{
+            "id": 93973511,
             "current": 1716803367.123,
             "asks": [{"p": "64321.5", "s": 10}],
             "bids": [{"p": "64320.9", "s": 8}]
@@ -88,6 +92,7 @@ mod tests {
         assert_eq!(book.inst, "BTC_USDT_PERP");
         assert_eq!(book.bids, vec![(64320.9, 8.0)]);
         assert_eq!(book.asks, vec![(64321.5, 10.0)]);
+        assert_eq!(book.update_id, Some(93973511));
     }
 
     #[test]
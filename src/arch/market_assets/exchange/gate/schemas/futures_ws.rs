@@ -2,4 +2,6 @@ pub(crate) mod account_order;
 pub(crate) mod account_position;
 pub(crate) mod candles;
 pub(crate) mod lob;
+pub(crate) mod trade_order;
 pub(crate) mod trades;
+pub(crate) mod user_trades;
@@ -0,0 +1,163 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::arch::market_assets::{
+    api_data::account_data::{OrderAckData, WsOrderResponse},
+    api_general::de_string_from_any,
+    exchange::exchange_error::ExchangeError,
+    exchange::gate::{
+        config_assets::{
+            GATE_WS_FUTURES_ORDER_BATCH_PLACE, GATE_WS_FUTURES_ORDER_CANCEL,
+            GATE_WS_FUTURES_ORDER_CANCEL_IDS, GATE_WS_FUTURES_ORDER_PLACE,
+        },
+        schemas::futures_rest::order::{
+            RestBatchCancelOrderGateFutures, RestBatchOrderGateFutures, RestFuturesOrderGateFutures,
+        },
+    },
+};
+use crate::errors::InfraError;
+
+/// Response frame of a futures websocket API request.
+#[derive(Debug, Deserialize)]
+pub(crate) struct WsApiResGateFutures {
+    request_id: String,
+    #[serde(default)]
+    ack: bool,
+    header: WsApiHeaderGateFutures,
+    #[serde(default)]
+    data: WsApiDataGateFutures,
+}
+
+#[derive(Debug, Deserialize)]
+struct WsApiHeaderGateFutures {
+    #[serde(deserialize_with = "de_string_from_any")]
+    status: String,
+    channel: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WsApiDataGateFutures {
+    result: Option<Value>,
+    errs: Option<WsApiErrsGateFutures>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WsApiErrsGateFutures {
+    #[serde(default)]
+    label: String,
+    #[serde(default)]
+    message: String,
+}
+
+impl WsApiResGateFutures {
+    /// Skips the venue's receipt acks and responses to requests this crate
+    /// did not number, such as the login.
+    pub(crate) fn into_response(self) -> Option<WsOrderResponse> {
+        if self.ack {
+            return None;
+        }
+        let id = self.request_id.parse().ok()?;
+        let channel = self.header.channel;
+
+        let result = match (self.data.errs, self.data.result) {
+            (None, Some(result)) if self.header.status == "200" => parse_result(&channel, result)?,
+            (errs, _) => {
+                let (label, message) = errs
+                    .map(|errs| (errs.label, errs.message))
+                    .unwrap_or_default();
+                Err(ExchangeError::gate(&label, message).into())
+            },
+        };
+
+        Some(WsOrderResponse { id, result })
+    }
+}
+
+fn parse_result(channel: &str, result: Value) -> Option<Result<Vec<OrderAckData>, InfraError>> {
+    let acks = match channel {
+        GATE_WS_FUTURES_ORDER_PLACE | GATE_WS_FUTURES_ORDER_CANCEL => {
+            serde_json::from_value::<RestFuturesOrderGateFutures>(result)
+                .map(|order| vec![OrderAckData::from(order)])
+        },
+        GATE_WS_FUTURES_ORDER_BATCH_PLACE => {
+            serde_json::from_value::<Vec<RestBatchOrderGateFutures>>(result).map(|orders| {
+                orders
+                    .into_iter()
+                    .map(|order| order.into_order_ack(None))
+                    .collect()
+            })
+        },
+        GATE_WS_FUTURES_ORDER_CANCEL_IDS => {
+            serde_json::from_value::<Vec<RestBatchCancelOrderGateFutures>>(result).map(|cancels| {
+                cancels
+                    .into_iter()
+                    .map(|cancel| cancel.into_order_ack(None))
+                    .collect()
+            })
+        },
+        _ => return None,
+    };
+
+    Some(acks.map_err(InfraError::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::market_assets::base_data::OrderStatus;
+
+    fn parse(frame: &str) -> Option<WsOrderResponse> {
+        serde_json::from_str::<WsApiResGateFutures>(frame)
+            .unwrap()
+            .into_response()
+    }
+
+    #[test]
+    fn maps_order_results_and_skips_receipts() {
+        assert!(
+            parse(
+                r#"{"request_id":"7","ack":true,"header":{"status":"200",
+                "channel":"futures.order_place"},"data":{"result":{"req_id":"7"}}}"#
+            )
+            .is_none()
+        );
+        assert!(
+            parse(
+                r#"{"request_id":"login","header":{"status":"200",
+                "channel":"futures.login"},"data":{"result":{"uid":"1"}}}"#
+            )
+            .is_none()
+        );
+
+        let placed = parse(
+            r#"{"request_id":"7","ack":false,"header":{"status":"200",
+            "channel":"futures.order_place"},"data":{"result":{"id":74046514,
+            "status":"open","finish_as":"_new","create_time":1700000000.1,
+            "update_time":1700000000.1,"text":"t-a1"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(placed.id, 7);
+        let acks = placed.result.unwrap();
+        assert_eq!(acks[0].order_id, "74046514");
+        assert_eq!(acks[0].order_status, OrderStatus::Live);
+
+        let canceled = parse(
+            r#"{"request_id":"8","header":{"status":200,
+            "channel":"futures.order_cancel_ids"},"data":{"result":[
+            {"id":"1","succeeded":true},{"id":"2","succeeded":false,"message":"ORDER_NOT_FOUND"}]}}"#,
+        )
+        .unwrap();
+        let acks = canceled.result.unwrap();
+        assert_eq!(acks[0].order_status, OrderStatus::Canceled);
+        assert_eq!(acks[1].order_status, OrderStatus::Rejected);
+
+        let failed = parse(
+            r#"{"request_id":"9","header":{"status":"400",
+            "channel":"futures.order_batch_place"},"data":{"errs":{
+            "label":"INVALID_PARAM_VALUE","message":"size"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(failed.id, 9);
+        assert!(failed.result.is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::arch::{
+    market_assets::{
+        api_general::{ts_to_micros, value_to_f64},
+        base_data::{InstrumentType, OrderSide},
+        exchange::gate::api_utils::{gate_fut_inst_to_cli, value_to_order_id, value_to_u64_ms},
+        market_core::Market,
+    },
+    strategy_base::handler::lob_events::{FillLiquidity, WsFill},
+    traits::conversion::IntoWsData,
+};
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct WsUserTradeGateFutures {
+    id: Value,
+    contract: String,
+    order_id: Value,
+    size: Value,
+    price: Value,
+    role: Option<String>,
+    fee: Option<Value>,
+    create_time: Option<u64>,
+    create_time_ms: Option<Value>,
+    text: Option<String>,
+}
+
+impl IntoWsData for WsUserTradeGateFutures {
+    type Output = WsFill;
+
+    fn into_ws(self) -> WsFill {
+        let size = value_to_f64(&self.size);
+        let timestamp = self
+            .create_time_ms
+            .as_ref()
+            .and_then(value_to_u64_ms)
+            .or(self.create_time)
+            .map(ts_to_micros)
+            .unwrap_or_default();
+
+        WsFill {
+            timestamp,
+            market: Market::GateFutures,
+            inst: gate_fut_inst_to_cli(&self.contract),
+            inst_type: InstrumentType::Perpetual,
+            side: if size >= 0.0 {
+                OrderSide::BUY
+            } else {
+                OrderSide::SELL
+            },
+            price: value_to_f64(&self.price),
+            size: size.abs(),
+            fee: self.fee.as_ref().map(value_to_f64).unwrap_or_default(),
+            // Futures fees are charged in the settle currency.
+            fee_currency: self.contract.split('_').nth(1).map(str::to_uppercase),
+            liquidity: match self.role.as_deref() {
+                Some("maker") => FillLiquidity::Maker,
+                Some("taker") => FillLiquidity::Taker,
+                _ => FillLiquidity::Unknown,
+            },
+            trade_id: value_to_order_id(Some(&self.id)),
+            order_id: value_to_order_id(Some(&self.order_id)),
+            cli_order_id: self.text.filter(|t| !t.is_empty() && t != "-"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::arch::{
+        market_assets::exchange::gate::gate_ws_msg::GateWsData, traits::conversion::IntoWsData,
+    };
+
+    use super::*;
+
+    #[test]
+    fn decodes_futures_usertrades() {
+        let raw = serde_json::to_vec(&json!({
+            "channel": "futures.usertrades",
+            "event": "update",
+            "result": [{
+                "id": "3335259",
+                "create_time": 1628736848_u64,
+                "create_time_ms": 1628736848321_u64,
+                "contract": "BTC_USDT",
+                "order_id": "4872460",
+                "size": "-0.5",
+                "price": "40000.4",
+                "role": "maker",
+                "text": "t-client",
+                "fee": 0.0009290592,
+                "point_fee": 0
+            }]
+        }))
+        .unwrap();
+        let fill = GateWsData::<WsUserTradeGateFutures>::decode_batch(&raw)
+            .unwrap()
+            .into_ws()
+            .pop()
+            .unwrap();
+
+        assert_eq!(fill.timestamp, 1_628_736_848_321_000);
+        assert_eq!(fill.inst, "BTC_USDT_PERP");
+        assert_eq!(fill.side, OrderSide::SELL);
+        assert_eq!(fill.size, 0.5);
+        assert_eq!(fill.price, 40000.4);
+        assert_eq!(fill.fee_currency.as_deref(), Some("USDT"));
+        assert_eq!(fill.liquidity, FillLiquidity::Maker);
+        assert_eq!(fill.trade_id.as_deref(), Some("3335259"));
+        assert_eq!(fill.order_id.as_deref(), Some("4872460"));
+        assert_eq!(fill.cli_order_id.as_deref(), Some("t-client"));
+    }
+}
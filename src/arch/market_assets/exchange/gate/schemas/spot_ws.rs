@@ -1 +1,2 @@
 pub(crate) mod account_order;
+pub(crate) mod user_trades;
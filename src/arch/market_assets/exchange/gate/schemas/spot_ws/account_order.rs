@@ -5,7 +5,7 @@ use crate::arch::{
     market_assets::{
         api_general::ts_to_micros,
         base_data::{InstrumentType, OrderSide, OrderStatus, OrderType},
-        exchange::gate::api_utils::value_to_order_id,
+        exchange::gate::api_utils::{value_to_order_id, value_to_u64_ms},
         market_core::Market,
     },
     strategy_base::handler::lob_events::WsAccOrder,
@@ -161,14 +161,6 @@ fn parse_status(
     }
 }
 
-fn value_to_u64_ms(v: &Value) -> Option<u64> {
-    v.as_u64().or_else(|| {
-        v.as_str()
-            .and_then(|s| s.split('.').next())
-            .and_then(|s| s.parse::<u64>().ok())
-    })
-}
-
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -0,0 +1,117 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::arch::{
+    market_assets::{
+        api_general::{ts_to_micros, value_to_f64},
+        base_data::{InstrumentType, OrderSide},
+        exchange::gate::api_utils::{value_to_order_id, value_to_u64_ms},
+        market_core::Market,
+    },
+    strategy_base::handler::lob_events::{FillLiquidity, WsFill},
+    traits::conversion::IntoWsData,
+};
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct WsUserTradeGateSpot {
+    id: Value,
+    order_id: Value,
+    currency_pair: String,
+    side: String,
+    amount: Value,
+    price: Value,
+    role: Option<String>,
+    fee: Option<Value>,
+    fee_currency: Option<String>,
+    create_time: Option<Value>,
+    create_time_ms: Option<Value>,
+    text: Option<String>,
+}
+
+impl IntoWsData for WsUserTradeGateSpot {
+    type Output = WsFill;
+
+    fn into_ws(self) -> WsFill {
+        let timestamp = self
+            .create_time_ms
+            .as_ref()
+            .and_then(value_to_u64_ms)
+            .or_else(|| self.create_time.as_ref().and_then(value_to_u64_ms))
+            .map(ts_to_micros)
+            .unwrap_or_default();
+
+        WsFill {
+            timestamp,
+            market: Market::GateSpot,
+            inst: self.currency_pair,
+            inst_type: InstrumentType::Spot,
+            side: match self.side.as_str() {
+                "buy" => OrderSide::BUY,
+                "sell" => OrderSide::SELL,
+                _ => OrderSide::Unknown,
+            },
+            price: value_to_f64(&self.price),
+            size: value_to_f64(&self.amount).abs(),
+            fee: self.fee.as_ref().map(value_to_f64).unwrap_or_default(),
+            fee_currency: self.fee_currency.filter(|ccy| !ccy.is_empty()),
+            liquidity: match self.role.as_deref() {
+                Some("maker") => FillLiquidity::Maker,
+                Some("taker") => FillLiquidity::Taker,
+                _ => FillLiquidity::Unknown,
+            },
+            trade_id: value_to_order_id(Some(&self.id)),
+            order_id: value_to_order_id(Some(&self.order_id)),
+            cli_order_id: self.text.filter(|t| !t.is_empty() && t != "-"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::arch::{
+        market_assets::exchange::gate::gate_ws_msg::GateWsData, traits::conversion::IntoWsData,
+    };
+
+    use super::*;
+
+    #[test]
+    fn decodes_spot_usertrades() {
+        let raw = serde_json::to_vec(&json!({
+            "channel": "spot.usertrades",
+            "event": "update",
+            "result": [{
+                "id": 5736713_u64,
+                "user_id": 1000001_u64,
+                "order_id": "30784428",
+                "currency_pair": "BTC_USDT",
+                "create_time": 1605176741_u64,
+                "create_time_ms": "1605176741123.456",
+                "side": "sell",
+                "amount": "1.00000000",
+                "role": "taker",
+                "price": "10000.00000000",
+                "fee": "0.00200000000000",
+                "fee_currency": "USDT",
+                "point_fee": "0",
+                "gt_fee": "0",
+                "text": "apiv4"
+            }]
+        }))
+        .unwrap();
+        let fill = GateWsData::<WsUserTradeGateSpot>::decode_batch(&raw)
+            .unwrap()
+            .into_ws()
+            .pop()
+            .unwrap();
+
+        assert_eq!(fill.timestamp, 1_605_176_741_123_000);
+        assert_eq!(fill.side, OrderSide::SELL);
+        assert_eq!(fill.size, 1.0);
+        assert_eq!(fill.fee, 0.002);
+        assert_eq!(fill.liquidity, FillLiquidity::Taker);
+        assert_eq!(fill.trade_id.as_deref(), Some("5736713"));
+        assert_eq!(fill.order_id.as_deref(), Some("30784428"));
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::{HashMap, HashSet};
 
 use crate::arch::{
     market_assets::{
-        api_general::OrderParams,
+        api_general::{AmendOrderParams, OrderParams},
         base_data::{InstrumentType, OrderSide, OrderType, TimeInForce},
     },
     task_execution::task_ws::{LobFrequency, LobParam},
@@ -408,6 +408,26 @@ pub enum HyperliquidCancelAction {
     },
 }
 
+#[derive(Clone, Debug, Serialize)]
+pub struct HyperliquidBatchModifyAction {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub modifies: Vec<HyperliquidModifyRequest>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HyperliquidModifyRequest {
+    pub oid: HyperliquidModifyTarget,
+    pub order: HyperliquidOrderRequest,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum HyperliquidModifyTarget {
+    Oid(u64),
+    Cloid(String),
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct HyperliquidCancelByOidRequest {
     #[serde(rename = "a")]
@@ -422,6 +442,15 @@ pub struct HyperliquidCancelByCloidRequest {
     pub cloid: String,
 }
 
+/// Schedules a cancel-all at `time` (ms), or clears the schedule when `None`.
+#[derive(Clone, Debug, Serialize)]
+pub struct HyperliquidScheduleCancelAction {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct HyperliquidBuilderFee {
     pub b: String,
@@ -598,6 +627,38 @@ pub fn hyperliquid_order_from_params(
     })
 }
 
+/// Hyperliquid modifies resend the whole order, so side, price and size are required.
+pub fn hyperliquid_modify_from_params(
+    amend_params: &AmendOrderParams,
+    asset: u32,
+) -> InfraResult<HyperliquidModifyRequest> {
+    amend_params.validate_target()?;
+    let (side, price, size) = amend_params.full_order("Hyperliquid")?;
+
+    let oid = match amend_params.order_id.as_deref() {
+        Some(order_id) => HyperliquidModifyTarget::Oid(order_id.parse::<u64>().map_err(|_| {
+            InfraError::ApiCliError(format!(
+                "Invalid Hyperliquid order_id, expected u64 string: {order_id}"
+            ))
+        })?),
+        None => HyperliquidModifyTarget::Cloid(normalize_hyperliquid_cloid(
+            amend_params.cli_order_id.as_deref().unwrap_or_default(),
+        )?),
+    };
+
+    let order = hyperliquid_order_from_params(OrderParams {
+        inst: asset.to_string(),
+        side: side.clone(),
+        size: size.to_string(),
+        price: Some(price.to_string()),
+        order_type: amend_params.order_type.clone().unwrap_or(OrderType::Limit),
+        reduce_only: amend_params.reduce_only,
+        ..Default::default()
+    })?;
+
+    Ok(HyperliquidModifyRequest { oid, order })
+}
+
 fn normalize_hyperliquid_builder_address(address: &str) -> InfraResult<String> {
     let address = address.trim();
     let Some(hex) = address
@@ -758,4 +819,48 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn serializes_batch_modify_by_oid_and_cloid() {
+        let amend = AmendOrderParams {
+            inst: "BTC_USDC_PERP".into(),
+            order_id: Some("42".into()),
+            side: Some(OrderSide::SELL),
+            new_price: Some("101.50".into()),
+            new_size: Some("0.010".into()),
+            ..Default::default()
+        };
+        let by_oid = hyperliquid_modify_from_params(&amend, 3).unwrap();
+        let by_cloid = hyperliquid_modify_from_params(
+            &AmendOrderParams {
+                order_id: None,
+                cli_order_id: Some("0xABCDEF0123456789ABCDEF0123456789".into()),
+                ..amend.clone()
+            },
+            3,
+        )
+        .unwrap();
+
+        let action = serde_json::to_value(HyperliquidBatchModifyAction {
+            kind: "batchModify",
+            modifies: vec![by_oid, by_cloid],
+        })
+        .unwrap();
+        assert_eq!(action["type"], "batchModify");
+        assert_eq!(action["modifies"][0]["oid"], 42);
+        assert_eq!(
+            action["modifies"][1]["oid"],
+            "0xabcdef0123456789abcdef0123456789"
+        );
+        assert_eq!(
+            action["modifies"][0]["order"],
+            json!({"a":3,"b":false,"p":"101.5","s":"0.01","r":false,"t":{"limit":{"tif":"Gtc"}}})
+        );
+
+        let price_only = AmendOrderParams {
+            new_size: None,
+            ..amend
+        };
+        assert!(hyperliquid_modify_from_params(&price_only, 3).is_err());
+    }
 }
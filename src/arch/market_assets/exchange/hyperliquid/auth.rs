@@ -5,8 +5,11 @@ use secp256k1::{Message, Secp256k1, SecretKey};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use sha3::{Digest, Keccak256};
 
+use crate::arch::market_assets::exchange::clock_sync::ClockSync;
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
-    market_assets::api_general::{get_mills_timestamp, parse_json_response},
+    market_assets::{api_general::parse_venue_response, exchange::endpoints::EndpointProfile},
     redaction::{redact_identifier, redact_secret},
 };
 use crate::errors::{InfraError, InfraResult};
@@ -93,6 +96,26 @@ where
     vault_address: Option<&'a str>,
 }
 
+#[derive(Clone, Debug, Serialize)]
+struct HyperliquidWsPost<'a, A>
+where
+    A: Serialize,
+{
+    method: &'static str,
+    id: u64,
+    request: HyperliquidWsPostRequest<'a, A>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct HyperliquidWsPostRequest<'a, A>
+where
+    A: Serialize,
+{
+    #[serde(rename = "type")]
+    kind: &'static str,
+    payload: HyperliquidExchangeRequest<'a, A>,
+}
+
 #[derive(Clone, Debug, Serialize)]
 struct HyperliquidUserSignedExchangeRequest<'a, A>
 where
@@ -111,44 +134,50 @@ impl HyperliquidAuth {
     pub async fn send_withdraw3_raw<T>(
         &self,
         client: &Client,
+        retry: &RetryPolicy,
+        endpoints: &EndpointProfile,
         destination: &str,
         amount: &str,
     ) -> InfraResult<T>
     where
         T: DeserializeOwned + Send + std::fmt::Debug,
     {
-        let nonce = get_mills_timestamp();
-        let action = HyperliquidWithdraw3Action {
-            kind: "withdraw3",
-            destination: normalize_evm_address(destination)?,
-            amount: amount.to_string(),
-            time: nonce,
-            signature_chain_id: HYPERLIQUID_DEFAULT_SIGNATURE_CHAIN_ID.to_string(),
-            hyperliquid_chain: HYPERLIQUID_MAINNET_CHAIN.to_string(),
-        };
-        let signature = self.sign_withdraw3_action(&action)?;
-        let body = HyperliquidExchangeRequest {
-            action: &action,
-            nonce,
-            signature,
-            vault_address: self.vault_address.as_deref(),
-        };
-        let body_string = serde_json::to_string(&body).map_err(|e| {
-            InfraError::ApiCliError(format!(
-                "Serialize Hyperliquid withdraw3 body failed: {}",
-                e
-            ))
-        })?;
-        let url = [HYPERLIQUID_BASE_URL, HYPERLIQUID_EXCHANGE].concat();
-
-        let response = client
-            .post(url)
-            .header("Content-Type", "application/json")
-            .body(body_string)
-            .send()
+        let destination = normalize_evm_address(destination)?;
+        let url = [endpoints.rest.as_str(), HYPERLIQUID_EXCHANGE].concat();
+
+        let response = retry
+            .send_with(|| {
+                let nonce = ClockSync::now_millis(Venue::Hyperliquid, &endpoints.rest);
+                let action = HyperliquidWithdraw3Action {
+                    kind: "withdraw3",
+                    destination: destination.clone(),
+                    amount: amount.to_string(),
+                    time: nonce,
+                    signature_chain_id: HYPERLIQUID_DEFAULT_SIGNATURE_CHAIN_ID.to_string(),
+                    hyperliquid_chain: hyperliquid_chain(endpoints.testnet).to_string(),
+                };
+                let signature = self.sign_withdraw3_action(&action)?;
+                let body = HyperliquidExchangeRequest {
+                    action: &action,
+                    nonce,
+                    signature,
+                    vault_address: self.vault_address.as_deref(),
+                };
+                let body_string = serde_json::to_string(&body).map_err(|e| {
+                    InfraError::ApiCliError(format!(
+                        "Serialize Hyperliquid withdraw3 body failed: {}",
+                        e
+                    ))
+                })?;
+
+                Ok(client
+                    .post(&url)
+                    .header("Content-Type", "application/json")
+                    .body(body_string))
+            })
             .await?;
 
-        parse_json_response("Hyperliquid POST withdraw3", response).await
+        parse_venue_response(Venue::Hyperliquid, "Hyperliquid POST withdraw3", response).await
     }
 
     pub fn sign_withdraw3_action(
@@ -165,15 +194,16 @@ impl HyperliquidAuth {
     pub async fn send_to_evm_with_data_raw<T>(
         &self,
         client: &Client,
+        retry: &RetryPolicy,
+        endpoints: &EndpointProfile,
         params: HyperliquidSendToEvmWithDataParams,
     ) -> InfraResult<T>
     where
         T: DeserializeOwned + Send + std::fmt::Debug,
     {
-        let nonce = get_mills_timestamp();
-        let action = HyperliquidSendToEvmWithDataAction {
+        let mut action = HyperliquidSendToEvmWithDataAction {
             kind: "sendToEvmWithData",
-            hyperliquid_chain: HYPERLIQUID_MAINNET_CHAIN.to_string(),
+            hyperliquid_chain: hyperliquid_chain(endpoints.testnet).to_string(),
             signature_chain_id: HYPERLIQUID_DEFAULT_SIGNATURE_CHAIN_ID.to_string(),
             token: params.token,
             amount: params.amount,
@@ -183,32 +213,41 @@ impl HyperliquidAuth {
             destination_chain_id: params.destination_chain_id,
             gas_limit: params.gas_limit,
             data: normalize_hex_data(&params.data)?,
-            nonce,
+            nonce: 0,
         };
-        let signature = self.sign_send_to_evm_with_data_action(&action)?;
-        let body = HyperliquidUserSignedExchangeRequest {
-            action: &action,
-            nonce,
-            signature,
-            vault_address: None,
-            expires_after: None,
-        };
-        let body_string = serde_json::to_string(&body).map_err(|e| {
-            InfraError::ApiCliError(format!(
-                "Serialize Hyperliquid sendToEvmWithData body failed: {}",
-                e
-            ))
-        })?;
-        let url = [HYPERLIQUID_BASE_URL, HYPERLIQUID_EXCHANGE].concat();
-
-        let response = client
-            .post(url)
-            .header("Content-Type", "application/json")
-            .body(body_string)
-            .send()
+        let url = [endpoints.rest.as_str(), HYPERLIQUID_EXCHANGE].concat();
+
+        let response = retry
+            .send_with(|| {
+                action.nonce = ClockSync::now_millis(Venue::Hyperliquid, &endpoints.rest);
+                let signature = self.sign_send_to_evm_with_data_action(&action)?;
+                let body = HyperliquidUserSignedExchangeRequest {
+                    action: &action,
+                    nonce: action.nonce,
+                    signature,
+                    vault_address: None,
+                    expires_after: None,
+                };
+                let body_string = serde_json::to_string(&body).map_err(|e| {
+                    InfraError::ApiCliError(format!(
+                        "Serialize Hyperliquid sendToEvmWithData body failed: {}",
+                        e
+                    ))
+                })?;
+
+                Ok(client
+                    .post(&url)
+                    .header("Content-Type", "application/json")
+                    .body(body_string))
+            })
             .await?;
 
-        parse_json_response("Hyperliquid POST sendToEvmWithData", response).await
+        parse_venue_response(
+            Venue::Hyperliquid,
+            "Hyperliquid POST sendToEvmWithData",
+            response,
+        )
+        .await
     }
 
     pub(crate) fn sign_send_to_evm_with_data_action(
@@ -225,47 +264,104 @@ impl HyperliquidAuth {
     pub async fn send_signed_exchange_action_raw<T, A>(
         &self,
         client: &Client,
+        retry: &RetryPolicy,
+        endpoints: &EndpointProfile,
         action: &A,
     ) -> InfraResult<T>
     where
         T: DeserializeOwned + Send + std::fmt::Debug,
         A: Serialize,
     {
-        let nonce = get_mills_timestamp();
-        let signature = self.sign_l1_action(action, nonce, self.vault_address.as_deref())?;
-        let body = HyperliquidExchangeRequest {
+        let url = [endpoints.rest.as_str(), HYPERLIQUID_EXCHANGE].concat();
+
+        let response = retry
+            .send_with(|| {
+                let body = self.signed_exchange_request(action, endpoints)?;
+                let body_string = serde_json::to_string(&body).map_err(|e| {
+                    InfraError::ApiCliError(format!(
+                        "Serialize Hyperliquid exchange body failed: {}",
+                        e
+                    ))
+                })?;
+
+                Ok(client
+                    .post(&url)
+                    .header("Content-Type", "application/json")
+                    .body(body_string))
+            })
+            .await?;
+
+        parse_venue_response(Venue::Hyperliquid, "Hyperliquid POST exchange", response).await
+    }
+
+    /// Signs `action` into a websocket `post` request numbered `id`; the
+    /// venue answers on the `post` channel with the same id.
+    pub fn ws_post_action_msg<A>(
+        &self,
+        id: u64,
+        action: &A,
+        endpoints: &EndpointProfile,
+    ) -> InfraResult<String>
+    where
+        A: Serialize,
+    {
+        let msg = HyperliquidWsPost {
+            method: "post",
+            id,
+            request: HyperliquidWsPostRequest {
+                kind: "action",
+                payload: self.signed_exchange_request(action, endpoints)?,
+            },
+        };
+
+        serde_json::to_string(&msg).map_err(|e| {
+            InfraError::ApiCliError(format!("Serialize Hyperliquid post request failed: {}", e))
+        })
+    }
+
+    fn signed_exchange_request<'a, A>(
+        &'a self,
+        action: &'a A,
+        endpoints: &EndpointProfile,
+    ) -> InfraResult<HyperliquidExchangeRequest<'a, A>>
+    where
+        A: Serialize,
+    {
+        let nonce = ClockSync::now_millis(Venue::Hyperliquid, &endpoints.rest);
+        let signature = self.sign_l1_action(
+            action,
+            nonce,
+            self.vault_address.as_deref(),
+            endpoints.testnet,
+        )?;
+
+        Ok(HyperliquidExchangeRequest {
             action,
             nonce,
             signature,
             vault_address: self.vault_address.as_deref(),
-        };
-        let body_string = serde_json::to_string(&body).map_err(|e| {
-            InfraError::ApiCliError(format!("Serialize Hyperliquid exchange body failed: {}", e))
-        })?;
-        let url = [HYPERLIQUID_BASE_URL, HYPERLIQUID_EXCHANGE].concat();
-
-        let response = client
-            .post(url)
-            .header("Content-Type", "application/json")
-            .body(body_string)
-            .send()
-            .await?;
-
-        parse_json_response("Hyperliquid POST exchange", response).await
+        })
     }
 
+    /// Signs an L1 action for mainnet, or for testnet when `testnet` is set.
     pub fn sign_l1_action<A>(
         &self,
         action: &A,
         nonce: u64,
         vault_address: Option<&str>,
+        testnet: bool,
     ) -> InfraResult<HyperliquidSignature>
     where
         A: Serialize,
     {
         let connection_id = self.action_hash(action, nonce, vault_address)?;
         let agent = HyperliquidAgent {
-            source: HYPERLIQUID_MAINNET_SOURCE.to_string(),
+            source: if testnet {
+                HYPERLIQUID_TESTNET_SOURCE
+            } else {
+                HYPERLIQUID_MAINNET_SOURCE
+            }
+            .to_string(),
             connection_id: format!("0x{}", HEXLOWER.encode(&connection_id)),
         };
         let digest = eip712_agent_digest(&agent)?;
@@ -301,6 +397,14 @@ impl HyperliquidAuth {
     }
 }
 
+fn hyperliquid_chain(testnet: bool) -> &'static str {
+    if testnet {
+        HYPERLIQUID_TESTNET_CHAIN
+    } else {
+        HYPERLIQUID_MAINNET_CHAIN
+    }
+}
+
 fn withdraw3_eip712_digest(action: &HyperliquidWithdraw3Action) -> InfraResult<[u8; 32]> {
     let domain_type_hash = keccak256(
         b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
@@ -638,4 +742,54 @@ mod tests {
         assert_eq!(normalize_hex_data("0x0102").unwrap(), "0x0102");
         assert!(normalize_hex_data("0xzz").is_err());
     }
+
+    #[test]
+    fn signs_l1_actions_for_the_profile_network() {
+        let auth = HyperliquidAuth {
+            owner_address: "0x5e9ee1089755c3435139848e47e6635505d5a13a".to_string(),
+            agent_private_key: "0x0123456789012345678901234567890123456789012345678901234567890123"
+                .to_string(),
+            owner_private_key: None,
+            vault_address: None,
+        };
+        let action = serde_json::json!({"type": "scheduleCancel", "time": 1_700_000_000_000u64});
+
+        let mainnet = auth
+            .sign_l1_action(&action, 1_700_000_000_000, None, false)
+            .unwrap();
+        let testnet = auth
+            .sign_l1_action(&action, 1_700_000_000_000, None, true)
+            .unwrap();
+
+        assert_ne!(mainnet.r, testnet.r);
+        assert_eq!(hyperliquid_chain(false), "Mainnet");
+        assert_eq!(hyperliquid_chain(true), "Testnet");
+        assert!(EndpointProfile::hyperliquid_testnet().testnet);
+    }
+
+    #[test]
+    fn wraps_signed_actions_in_ws_post_requests() {
+        let auth = HyperliquidAuth {
+            owner_address: "0x5e9ee1089755c3435139848e47e6635505d5a13a".to_string(),
+            agent_private_key: "0x0123456789012345678901234567890123456789012345678901234567890123"
+                .to_string(),
+            owner_private_key: None,
+            vault_address: None,
+        };
+        let action = serde_json::json!({"type": "scheduleCancel", "time": 1_700_000_000_000u64});
+
+        let msg: serde_json::Value = serde_json::from_str(
+            &auth
+                .ws_post_action_msg(5, &action, &EndpointProfile::hyperliquid_production())
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(msg["method"], "post");
+        assert_eq!(msg["id"], 5);
+        assert_eq!(msg["request"]["type"], "action");
+        assert_eq!(msg["request"]["payload"]["action"], action);
+        assert!(msg["request"]["payload"]["signature"]["r"].is_string());
+        assert!(msg["request"]["payload"].get("vaultAddress").is_none());
+    }
 }
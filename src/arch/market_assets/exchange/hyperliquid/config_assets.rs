@@ -1,9 +1,70 @@
+use tokio::time::Duration;
+
+use crate::arch::market_assets::exchange::{
+    endpoints::EndpointProfile,
+    rate_limit::{EndpointRule, RateLimitProfile, RequestPriority},
+};
+
 pub const HYPERLIQUID_BASE_URL: &str = "https://api.hyperliquid.xyz";
 pub const HYPERLIQUID_INFO: &str = "/info";
 pub const HYPERLIQUID_EXCHANGE: &str = "/exchange";
 pub const HYPERLIQUID_WS: &str = "wss://api.hyperliquid.xyz/ws";
+pub const HYPERLIQUID_TESTNET_BASE_URL: &str = "https://api.hyperliquid-testnet.xyz";
+pub const HYPERLIQUID_TESTNET_WS: &str = "wss://api.hyperliquid-testnet.xyz/ws";
 
 pub const HYPERLIQUID_MAINNET_SOURCE: &str = "a";
 pub const HYPERLIQUID_MAINNET_CHAIN: &str = "Mainnet";
+pub const HYPERLIQUID_TESTNET_SOURCE: &str = "b";
+pub const HYPERLIQUID_TESTNET_CHAIN: &str = "Testnet";
 pub const HYPERLIQUID_DEFAULT_SIGNATURE_CHAIN_ID: &str = "0x66eee";
 pub const HYPERLIQUID_GROUPING_NA: &str = "na";
+
+impl EndpointProfile {
+    /// Hyperliquid mainnet.
+    pub fn hyperliquid_production() -> Self {
+        Self::new(HYPERLIQUID_BASE_URL, HYPERLIQUID_WS)
+    }
+
+    /// Hyperliquid testnet. Actions are signed for the testnet chain.
+    pub fn hyperliquid_testnet() -> Self {
+        Self::new(HYPERLIQUID_TESTNET_BASE_URL, HYPERLIQUID_TESTNET_WS).with_testnet(true)
+    }
+}
+
+/// `/info` request types Hyperliquid weighs at 2 instead of 20.
+const HYPERLIQUID_LIGHT_INFO: [&str; 6] = [
+    "l2Book",
+    "allMids",
+    "clearinghouseState",
+    "orderStatus",
+    "spotClearinghouseState",
+    "exchangeStatus",
+];
+
+impl RateLimitProfile {
+    /// Hyperliquid's 1200 weight per minute per IP. Actions weigh 1,
+    /// info requests 2 or 20 by type.
+    pub fn hyperliquid() -> Self {
+        let mut profile = Self::new()
+            .with_bucket("weight", 1200, Duration::from_secs(60))
+            .with_rule(
+                EndpointRule::exact(HYPERLIQUID_EXCHANGE)
+                    .with_body_containing(r#""type":"cancel"#)
+                    .with_cost("weight", 1)
+                    .with_priority(RequestPriority::Cancel),
+            )
+            .with_rule(
+                EndpointRule::exact(HYPERLIQUID_EXCHANGE)
+                    .with_cost("weight", 1)
+                    .with_priority(RequestPriority::Trade),
+            );
+        for kind in HYPERLIQUID_LIGHT_INFO {
+            profile = profile.with_rule(
+                EndpointRule::exact(HYPERLIQUID_INFO)
+                    .with_body_containing(format!(r#""type":"{kind}""#))
+                    .with_cost("weight", 2),
+            );
+        }
+        profile.with_rule(EndpointRule::exact(HYPERLIQUID_INFO).with_cost("weight", 20))
+    }
+}
@@ -1,25 +1,31 @@
 use reqwest::Client;
 use serde_json::{Value, json};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use tracing::{error, warn};
 
+use crate::arch::market_assets::exchange::clock_sync::ClockSync;
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
     market_assets::{
         api_data::{
-            account_data::{BalanceData, OrderAckData, OrderDetailData, PositionData},
+            account_data::{
+                BalanceData, OrderAckData, OrderDetailData, PositionData, WsOrderResponse,
+            },
             price_data::{CandleData, OrderBookData, TickerData},
             utils_data::{FundingRateData, FundingRateInfo, InstrumentInfo},
         },
         api_general::{
-            CancelOrderParams, OrderParams, candle_interval_millis, get_micros_timestamp,
-            get_mills_timestamp, parse_json_response,
+            AmendOrderParams, CancelOrderParams, OrderParams, candle_interval_millis,
+            get_micros_timestamp, get_mills_timestamp, parse_venue_response, ws_unsubscribe_msg,
         },
-        base_data::{InstrumentType, MarginMode},
+        base_data::{InstrumentType, MarginMode, UNSUBSCRIBE_LOWER},
+        exchange::endpoints::EndpointProfile,
     },
     task_execution::task_ws::{CandleParam, LobParam, TradesParam, WsChannel},
     traits::{
         conversion::IntoInfraVec,
-        market_lob::{LobPrivateRest, LobPublicRest, LobWebsocket, MarketLobApi},
+        market_lob::{LobPrivateRest, LobPublicRest, LobWebsocket, LobWsOrderEntry, MarketLobApi},
     },
 };
 use crate::errors::{InfraError, InfraResult};
@@ -29,25 +35,28 @@ use super::{
     auth::{HyperliquidAuth, read_hyperliquid_env_auth},
     config_assets::*,
     hyperliquid_rest_msg::RestResHyperliquid,
-    schemas::rest::{
-        all_mids::RestAllMidsHyperliquid,
-        asset_ctxs::RestMetaAndAssetCtxsHyperliquid,
-        cancel_order::RestCancelAckHyperliquid,
-        candle::RestCandleHyperliquid,
-        clearinghouse_state::RestClearinghouseStateHyperliquid,
-        funding_history::RestFundingHistoryHyperliquid,
-        meta::RestMetaHyperliquid,
-        non_funding_ledger::RestNonFundingLedgerUpdateHyperliquid,
-        open_order::RestOpenOrderHyperliquid,
-        order_status::{
-            RestOrderStatusHyperliquid, finalize_hyperliquid_order_history,
-            validate_hyperliquid_order_history_range,
+    schemas::{
+        rest::{
+            all_mids::RestAllMidsHyperliquid,
+            asset_ctxs::RestMetaAndAssetCtxsHyperliquid,
+            cancel_order::RestCancelAckHyperliquid,
+            candle::RestCandleHyperliquid,
+            clearinghouse_state::RestClearinghouseStateHyperliquid,
+            funding_history::RestFundingHistoryHyperliquid,
+            meta::RestMetaHyperliquid,
+            non_funding_ledger::RestNonFundingLedgerUpdateHyperliquid,
+            open_order::RestOpenOrderHyperliquid,
+            order_status::{
+                RestOrderStatusHyperliquid, finalize_hyperliquid_order_history,
+                validate_hyperliquid_order_history_range,
+            },
+            orderbook::RestOrderBookHyperliquid,
+            perp_dexs::RestPerpDexHyperliquid,
+            spot_clearinghouse_state::RestSpotClearinghouseStateHyperliquid,
+            spot_meta::RestSpotMetaHyperliquid,
+            trade_order::RestOrderAckHyperliquid,
         },
-        orderbook::RestOrderBookHyperliquid,
-        perp_dexs::RestPerpDexHyperliquid,
-        spot_clearinghouse_state::RestSpotClearinghouseStateHyperliquid,
-        spot_meta::RestSpotMetaHyperliquid,
-        trade_order::RestOrderAckHyperliquid,
+        ws::post::WsPostResHyperliquid,
     },
 };
 
@@ -56,6 +65,8 @@ pub struct HyperliquidCli {
     pub client: Arc<Client>,
     pub auth: Option<HyperliquidAuth>,
     pub market_cache: HyperliquidMarketCache,
+    pub endpoints: EndpointProfile,
+    pub retry: RetryPolicy,
 }
 
 impl Default for HyperliquidCli {
@@ -118,7 +129,9 @@ impl LobPrivateRest for HyperliquidCli {
     }
 
     async fn place_order(&self, order_params: OrderParams) -> InfraResult<OrderAckData> {
-        self._place_order(order_params).await
+        self.retry
+            .place_order(self, order_params, |params| self._place_order(params))
+            .await
     }
 
     async fn place_orders(&self, order_params: Vec<OrderParams>) -> InfraResult<Vec<OrderAckData>> {
@@ -141,6 +154,25 @@ impl LobPrivateRest for HyperliquidCli {
         self._cancel_orders(cancel_params).await
     }
 
+    async fn amend_order(&self, amend_params: AmendOrderParams) -> InfraResult<OrderAckData> {
+        self._amend_order(amend_params).await
+    }
+
+    async fn amend_orders(
+        &self,
+        amend_params: Vec<AmendOrderParams>,
+    ) -> InfraResult<Vec<OrderAckData>> {
+        self._amend_orders(amend_params).await
+    }
+
+    async fn cancel_all_orders(&self, inst: Option<&str>) -> InfraResult<Vec<OrderAckData>> {
+        self._cancel_all_orders(inst).await
+    }
+
+    async fn set_cancel_after(&self, inst: Option<&str>, timeout: Duration) -> InfraResult<()> {
+        self._set_cancel_after(inst, timeout).await
+    }
+
     async fn get_open_orders(
         &self,
         inst: &str,
@@ -149,6 +181,15 @@ impl LobPrivateRest for HyperliquidCli {
         self._get_open_orders(inst, limit).await
     }
 
+    async fn get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        self._get_order(inst, order_id, cli_order_id).await
+    }
+
     async fn get_balance(&self, assets: Option<&[String]>) -> InfraResult<Vec<BalanceData>> {
         self._get_balance(assets).await
     }
@@ -179,6 +220,15 @@ impl LobWebsocket for HyperliquidCli {
         self._get_public_sub_msg(channel, insts)
     }
 
+    async fn get_public_unsub_msg(
+        &self,
+        channel: &WsChannel,
+        insts: Option<&[String]>,
+    ) -> InfraResult<String> {
+        let sub_msg = self._get_public_sub_msg(channel, insts)?;
+        ws_unsubscribe_msg(&sub_msg, "method", UNSUBSCRIBE_LOWER)
+    }
+
     async fn get_private_sub_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         self._get_private_sub_msg(channel)
     }
@@ -192,6 +242,22 @@ impl LobWebsocket for HyperliquidCli {
     }
 }
 
+impl LobWsOrderEntry for HyperliquidCli {
+    fn ws_place_orders_msg(&self, id: u64, orders: &[OrderParams]) -> InfraResult<String> {
+        self._ws_place_orders_msg(id, orders)
+    }
+
+    fn ws_cancel_orders_msg(&self, id: u64, cancels: &[CancelOrderParams]) -> InfraResult<String> {
+        self._ws_cancel_orders_msg(id, cancels)
+    }
+
+    fn parse_ws_order_response(&self, frame: &[u8]) -> Option<WsOrderResponse> {
+        serde_json::from_slice::<WsPostResHyperliquid>(frame)
+            .ok()?
+            .into_response()
+    }
+}
+
 impl HyperliquidCli {
     const DEFAULT_CANDLE_LIMIT: u32 = 7;
 
@@ -200,9 +266,25 @@ impl HyperliquidCli {
             client: shared_client,
             auth: None,
             market_cache: HyperliquidMarketCache::default(),
+            endpoints: EndpointProfile::hyperliquid_production(),
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Sends requests to `endpoints` instead of Hyperliquid mainnet. Exchange
+    /// actions are signed for testnet when `endpoints.testnet` is set.
+    pub fn with_endpoints(mut self, endpoints: EndpointProfile) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Times out and retries REST requests with `retry` instead of
+    /// [`RetryPolicy::default`].
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     pub fn set_perp_dex(&mut self, dex: Option<String>) {
         let normalized_dex = dex.and_then(|dex| {
             let dex = dex.trim().to_string();
@@ -385,7 +467,13 @@ impl HyperliquidCli {
         self.auth
             .as_ref()
             .ok_or(InfraError::ApiCliNotInitialized)?
-            .send_withdraw3_raw(&self.client, destination, amount)
+            .send_withdraw3_raw(
+                &self.client,
+                &self.retry,
+                &self.endpoints,
+                destination,
+                amount,
+            )
             .await
     }
 
@@ -396,7 +484,7 @@ impl HyperliquidCli {
         self.auth
             .as_ref()
             .ok_or(InfraError::ApiCliNotInitialized)?
-            .send_to_evm_with_data_raw(&self.client, params)
+            .send_to_evm_with_data_raw(&self.client, &self.retry, &self.endpoints, params)
             .await
     }
 
@@ -447,7 +535,12 @@ impl HyperliquidCli {
         self.auth
             .as_ref()
             .ok_or(InfraError::ApiCliNotInitialized)?
-            .send_signed_exchange_action_raw::<Value, _>(&self.client, &action)
+            .send_signed_exchange_action_raw::<Value, _>(
+                &self.client,
+                &self.retry,
+                &self.endpoints,
+                &action,
+            )
             .await?;
 
         Ok(())
@@ -664,7 +757,7 @@ impl HyperliquidCli {
             .auth
             .as_ref()
             .ok_or(InfraError::ApiCliNotInitialized)?
-            .send_signed_exchange_action_raw(&self.client, &action)
+            .send_signed_exchange_action_raw(&self.client, &self.retry, &self.endpoints, &action)
             .await?;
 
         let data: OrderAckData = res
@@ -687,32 +780,12 @@ impl HyperliquidCli {
             return Ok(Vec::new());
         }
 
-        let builder = hyperliquid_builder_fee_from_extra(&order_params[0].extra)?;
-        let mut orders = Vec::with_capacity(order_params.len());
-        for order in &order_params {
-            order.validate_side_and_type()?;
-            if hyperliquid_builder_fee_from_extra(&order.extra)? != builder {
-                return Err(InfraError::ApiCliError(
-                    "Hyperliquid batch place requires one builder fee".into(),
-                ));
-            }
-
-            let mut order = order.clone();
-            order.inst = self._inst_to_asset_id(&order.inst)?.to_string();
-            orders.push(hyperliquid_order_from_params(order)?);
-        }
-
-        let action = HyperliquidOrderAction {
-            kind: "order",
-            orders,
-            grouping: HYPERLIQUID_GROUPING_NA,
-            builder,
-        };
+        let action = self._order_action(&order_params)?;
         let res: RestResHyperliquid<RestOrderAckHyperliquid> = self
             .auth
             .as_ref()
             .ok_or(InfraError::ApiCliNotInitialized)?
-            .send_signed_exchange_action_raw(&self.client, &action)
+            .send_signed_exchange_action_raw(&self.client, &self.retry, &self.endpoints, &action)
             .await?;
 
         let responds = res
@@ -787,7 +860,7 @@ impl HyperliquidCli {
             .auth
             .as_ref()
             .ok_or(InfraError::ApiCliNotInitialized)?
-            .send_signed_exchange_action_raw(&self.client, &action)
+            .send_signed_exchange_action_raw(&self.client, &self.retry, &self.endpoints, &action)
             .await?;
 
         let data = res
@@ -810,6 +883,76 @@ impl HyperliquidCli {
             return Ok(Vec::new());
         }
 
+        let (action, returned_ids) = self._cancel_action(&cancel_params)?;
+        let res: RestResHyperliquid<RestCancelAckHyperliquid> = self
+            .auth
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_exchange_action_raw(&self.client, &self.retry, &self.endpoints, &action)
+            .await?;
+        let responds = res
+            .into_vec()?
+            .into_iter()
+            .next()
+            .ok_or(InfraError::ApiCliError(
+                "No Hyperliquid batch cancel ack data returned".into(),
+            ))?;
+        if responds.statuses.len() != cancel_params.len() {
+            return Err(InfraError::ApiCliError(format!(
+                "Hyperliquid batch cancel returned {} result(s) for {} order(s)",
+                responds.statuses.len(),
+                cancel_params.len()
+            )));
+        }
+
+        let data: Vec<OrderAckData> = responds
+            .statuses
+            .into_iter()
+            .zip(returned_ids)
+            .map(|(status, (order_id, cli_order_id))| {
+                status.into_cancel_ack(order_id, cli_order_id)
+            })
+            .collect();
+
+        Ok(data)
+    }
+
+    /// One `order` action for `order_params`, shared by REST and websocket
+    /// order entry.
+    fn _order_action(&self, order_params: &[OrderParams]) -> InfraResult<HyperliquidOrderAction> {
+        let builder = hyperliquid_builder_fee_from_extra(&order_params[0].extra)?;
+        let mut orders = Vec::with_capacity(order_params.len());
+        for order in order_params {
+            order.validate_side_and_type()?;
+            if hyperliquid_builder_fee_from_extra(&order.extra)? != builder {
+                return Err(InfraError::ApiCliError(
+                    "Hyperliquid batch place requires one builder fee".into(),
+                ));
+            }
+
+            let mut order = order.clone();
+            order.inst = self._inst_to_asset_id(&order.inst)?.to_string();
+            orders.push(hyperliquid_order_from_params(order)?);
+        }
+
+        Ok(HyperliquidOrderAction {
+            kind: "order",
+            orders,
+            grouping: HYPERLIQUID_GROUPING_NA,
+            builder,
+        })
+    }
+
+    /// One cancel action for `cancel_params`, with the ids each ack should
+    /// report since cancel statuses carry none.
+    #[allow(clippy::type_complexity)]
+    fn _cancel_action(
+        &self,
+        cancel_params: &[CancelOrderParams],
+    ) -> InfraResult<(
+        HyperliquidCancelAction,
+        Vec<(Option<String>, Option<String>)>,
+    )> {
         let by_order_id = if cancel_params[0].order_id.is_some() {
             true
         } else if cancel_params[0].cli_order_id.is_some() {
@@ -831,7 +974,7 @@ impl HyperliquidCli {
         let mut returned_ids = Vec::with_capacity(cancel_params.len());
         let action = if by_order_id {
             let mut cancels = Vec::with_capacity(cancel_params.len());
-            for cancel in &cancel_params {
+            for cancel in cancel_params {
                 let order_id = cancel.order_id.as_deref().unwrap_or_default();
                 let order_id = order_id.parse::<u64>().map_err(|_| {
                     InfraError::ApiCliError(format!(
@@ -847,7 +990,7 @@ impl HyperliquidCli {
             HyperliquidCancelAction::ByOid { cancels }
         } else {
             let mut cancels = Vec::with_capacity(cancel_params.len());
-            for cancel in &cancel_params {
+            for cancel in cancel_params {
                 let cloid = normalize_hyperliquid_cloid(
                     cancel.cli_order_id.as_deref().unwrap_or_default(),
                 )?;
@@ -860,37 +1003,144 @@ impl HyperliquidCli {
             HyperliquidCancelAction::ByCloid { cancels }
         };
 
-        let res: RestResHyperliquid<RestCancelAckHyperliquid> = self
+        Ok((action, returned_ids))
+    }
+
+    async fn _amend_order(&self, amend_params: AmendOrderParams) -> InfraResult<OrderAckData> {
+        // `modify` only answers "ok" without a status, so route single amends through `batchModify`.
+        self._amend_orders(vec![amend_params])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(InfraError::ApiCliError(
+                "No Hyperliquid amend ack data returned".into(),
+            ))
+    }
+
+    async fn _amend_orders(
+        &self,
+        amend_params: Vec<AmendOrderParams>,
+    ) -> InfraResult<Vec<OrderAckData>> {
+        if amend_params.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut modifies = Vec::with_capacity(amend_params.len());
+        for amend in &amend_params {
+            let asset = self._inst_to_asset_id(&amend.inst)?;
+            modifies.push(hyperliquid_modify_from_params(amend, asset)?);
+        }
+
+        let action = HyperliquidBatchModifyAction {
+            kind: "batchModify",
+            modifies,
+        };
+        let res: RestResHyperliquid<RestOrderAckHyperliquid> = self
             .auth
             .as_ref()
             .ok_or(InfraError::ApiCliNotInitialized)?
-            .send_signed_exchange_action_raw(&self.client, &action)
+            .send_signed_exchange_action_raw(&self.client, &self.retry, &self.endpoints, &action)
             .await?;
+
         let responds = res
             .into_vec()?
             .into_iter()
             .next()
             .ok_or(InfraError::ApiCliError(
-                "No Hyperliquid batch cancel ack data returned".into(),
+                "No Hyperliquid batch amend ack data returned".into(),
             ))?;
-        if responds.statuses.len() != cancel_params.len() {
+        if responds.statuses.len() != amend_params.len() {
             return Err(InfraError::ApiCliError(format!(
-                "Hyperliquid batch cancel returned {} result(s) for {} order(s)",
+                "Hyperliquid batch amend returned {} result(s) for {} order(s)",
                 responds.statuses.len(),
-                cancel_params.len()
+                amend_params.len()
             )));
         }
 
-        let data: Vec<OrderAckData> = responds
-            .statuses
+        let data = responds.into_order_acks();
+
+        Ok(data)
+    }
+
+    async fn _cancel_all_orders(&self, inst: Option<&str>) -> InfraResult<Vec<OrderAckData>> {
+        let user = self._owner_address()?;
+        let raw_coin = inst
+            .map(|inst| self._inst_to_trade_coin(inst))
+            .transpose()?;
+        let res: RestResHyperliquid<RestOpenOrderHyperliquid> = self
+            ._post_info_raw(&json!({
+                "type": "frontendOpenOrders",
+                "user": user,
+                "dex": self._perp_dex(),
+            }))
+            .await?;
+        let open_orders: Vec<_> = res
+            .into_vec()?
             .into_iter()
-            .zip(returned_ids)
-            .map(|(status, (order_id, cli_order_id))| {
-                status.into_cancel_ack(order_id, cli_order_id)
-            })
+            .filter(|order| raw_coin.as_ref().is_none_or(|coin| &order.coin == coin))
             .collect();
+        if open_orders.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        Ok(data)
+        // Hyperliquid has no cancel-all, so map each open order back to a
+        // known instrument and cancel them in one batch.
+        let coin_to_inst: HashMap<String, String> = self
+            .market_cache
+            .inst_index_map
+            .keys()
+            .filter_map(|inst| Some((self._inst_to_trade_coin(inst).ok()?, inst.clone())))
+            .collect();
+        let cancel_params = open_orders
+            .into_iter()
+            .map(|order| {
+                let inst = match inst {
+                    Some(inst) => inst.to_string(),
+                    None => coin_to_inst.get(&order.coin).cloned().ok_or_else(|| {
+                        InfraError::ApiCliError(format!(
+                            "Hyperliquid inst not found in inst_index_map for coin: {}",
+                            order.coin
+                        ))
+                    })?,
+                };
+                Ok(CancelOrderParams {
+                    inst,
+                    order_id: Some(order.oid.to_string()),
+                    ..Default::default()
+                })
+            })
+            .collect::<InfraResult<Vec<_>>>()?;
+
+        self._cancel_orders(cancel_params).await
+    }
+
+    async fn _set_cancel_after(&self, _inst: Option<&str>, timeout: Duration) -> InfraResult<()> {
+        let time = if timeout.is_zero() {
+            None
+        } else if timeout < Duration::from_secs(5) {
+            return Err(InfraError::ApiCliError(
+                "Hyperliquid scheduleCancel timeout must be 0 or at least 5 seconds".into(),
+            ));
+        } else {
+            Some(
+                ClockSync::now_millis(Venue::Hyperliquid, &self.endpoints.rest)
+                    + timeout.as_millis() as u64,
+            )
+        };
+
+        let action = HyperliquidScheduleCancelAction {
+            kind: "scheduleCancel",
+            time,
+        };
+        let res: RestResHyperliquid<Value> = self
+            .auth
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_exchange_action_raw(&self.client, &self.retry, &self.endpoints, &action)
+            .await?;
+        res.into_vec()?;
+
+        Ok(())
     }
 
     async fn _get_open_orders(
@@ -990,6 +1240,54 @@ impl HyperliquidCli {
         Ok(positions)
     }
 
+    async fn _get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        let oid = match (order_id, cli_order_id) {
+            (Some(order_id), _) => json!(order_id.parse::<u64>().map_err(|_| {
+                InfraError::ApiCliError(format!(
+                    "Invalid Hyperliquid order_id, expected u64 string: {}",
+                    order_id
+                ))
+            })?),
+            (None, Some(cli_order_id)) => json!(normalize_hyperliquid_cloid(cli_order_id)?),
+            (None, None) => {
+                return Err(InfraError::ApiCliError(
+                    "Hyperliquid get_order requires order_id or cli_order_id".into(),
+                ));
+            },
+        };
+        let body = json!({
+            "type": "orderStatus",
+            "user": self._owner_address()?,
+            "oid": oid,
+        });
+
+        let normalized_inst = normalize_hyperliquid_cli_inst(inst);
+        let raw_coin = self._inst_to_trade_coin(inst)?;
+        let perp_quote = if is_hyperliquid_cli_perp_inst(&normalized_inst) {
+            Some(hyperliquid_cli_perp_quote(&normalized_inst)?)
+        } else {
+            None
+        };
+
+        let res: RestResHyperliquid<RestOrderStatusHyperliquid> =
+            self._post_info_raw(&body).await?;
+
+        Ok(res
+            .into_vec()?
+            .into_iter()
+            .find(|order| order.order.coin == raw_coin)
+            .map(|order| {
+                let mut data = order.into_order_detail_data(perp_quote.as_deref());
+                data.inst = normalized_inst;
+                data
+            }))
+    }
+
     async fn _get_order_history(
         &self,
         inst: &str,
@@ -1055,18 +1353,51 @@ impl HyperliquidCli {
             WsChannel::Trades(Some(TradesParam::AggTrades))
             | WsChannel::Trades(Some(TradesParam::AllTrades))
             | WsChannel::Trades(None)
-            | WsChannel::Lob(_) => Ok(HYPERLIQUID_WS.into()),
+            | WsChannel::Lob(_) => Ok(self.endpoints.ws_public.clone()),
             _ => Err(InfraError::Unimplemented),
         }
     }
 
     fn _get_private_connect_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         match channel {
-            WsChannel::AccountOrders | WsChannel::AccountPositions => Ok(HYPERLIQUID_WS.into()),
+            WsChannel::AccountOrders | WsChannel::AccountFills | WsChannel::AccountPositions => {
+                Ok(self.endpoints.ws_private.clone())
+            },
+            WsChannel::OrderEntry => Ok(self.endpoints.ws_trade.clone()),
             _ => Err(InfraError::Unimplemented),
         }
     }
 
+    /// Orders and cancels travel as signed `post` actions, so the session
+    /// needs no login.
+    fn _ws_place_orders_msg(&self, id: u64, orders: &[OrderParams]) -> InfraResult<String> {
+        if orders.is_empty() {
+            return Err(InfraError::ApiCliError(
+                "Hyperliquid websocket place requires at least one order".into(),
+            ));
+        }
+
+        let action = self._order_action(orders)?;
+        self.auth
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .ws_post_action_msg(id, &action, &self.endpoints)
+    }
+
+    fn _ws_cancel_orders_msg(&self, id: u64, cancels: &[CancelOrderParams]) -> InfraResult<String> {
+        if cancels.is_empty() {
+            return Err(InfraError::ApiCliError(
+                "Hyperliquid websocket cancel requires at least one order".into(),
+            ));
+        }
+
+        let (action, _) = self._cancel_action(cancels)?;
+        self.auth
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .ws_post_action_msg(id, &action, &self.endpoints)
+    }
+
     fn _get_public_sub_msg(
         &self,
         channel: &WsChannel,
@@ -1093,6 +1424,14 @@ impl HyperliquidCli {
                 }
             })
             .to_string()),
+            WsChannel::AccountFills => Ok(json!({
+                "method": "subscribe",
+                "subscription": {
+                    "type": "userFills",
+                    "user": user,
+                }
+            })
+            .to_string()),
             WsChannel::AccountPositions => Ok(json!({
                 "method": "subscribe",
                 "subscription": {
@@ -1363,11 +1702,11 @@ impl HyperliquidCli {
     where
         T: serde::de::DeserializeOwned,
     {
-        let url = [HYPERLIQUID_BASE_URL, HYPERLIQUID_INFO].concat();
-        let response = self.client.post(url).json(body).send().await?;
+        let url = [self.endpoints.rest.as_str(), HYPERLIQUID_INFO].concat();
+        let response = self.retry.send(self.client.post(url).json(body)).await?;
         let info_type = body.get("type").and_then(|v| v.as_str()).unwrap_or("?");
         let label = format!("Hyperliquid info {}", info_type);
-        parse_json_response(&label, response).await
+        parse_venue_response(Venue::Hyperliquid, &label, response).await
     }
 
     async fn _get_meta_and_asset_ctxs(&self) -> InfraResult<RestMetaAndAssetCtxsHyperliquid> {
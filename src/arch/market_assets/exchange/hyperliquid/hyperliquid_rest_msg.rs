@@ -1,13 +1,21 @@
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 
-use crate::arch::traits::conversion::IntoInfraVec;
-use crate::errors::{InfraError, InfraResult};
+use crate::arch::{
+    market_assets::exchange::exchange_error::{ErrorCategory, ExchangeError, Venue},
+    traits::conversion::IntoInfraVec,
+};
+use crate::errors::InfraResult;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum RestResHyperliquid<T> {
     Null,
+    /// `{"status":"err","response":"<error text>"}`
+    Rejected {
+        status: String,
+        response: String,
+    },
     Order {
         status: String,
         order: T,
@@ -42,24 +50,22 @@ where
     fn into_vec(self) -> InfraResult<Vec<T>> {
         match self {
             Self::Null => Ok(vec![]),
+            Self::Rejected { status, response } => {
+                warn!("Hyperliquid REST error {}: {}", status, response);
+                Err(ExchangeError::hyperliquid(response).into())
+            },
             Self::Order { status, order } => {
                 if status == "order" {
                     Ok(vec![order])
                 } else {
                     warn!("Hyperliquid REST error {}: order payload", status);
-                    Err(InfraError::ApiCliError(format!(
-                        "Hyperliquid REST error (status={}): order payload",
-                        status
-                    )))
+                    Err(ExchangeError::hyperliquid(status).into())
                 }
             },
             Self::Exchange { status, response } => {
                 if status != "ok" {
                     warn!("Hyperliquid REST error {}: {:?}", status, response);
-                    return Err(InfraError::ApiCliError(format!(
-                        "Hyperliquid REST error (status={}): {:?}",
-                        status, response
-                    )));
+                    return Err(ExchangeError::hyperliquid(status).into());
                 }
 
                 match response {
@@ -77,10 +83,7 @@ where
                     Ok(vec![])
                 } else {
                     warn!("Hyperliquid REST error {}: None", status);
-                    Err(InfraError::ApiCliError(format!(
-                        "Hyperliquid REST error (status={}): None",
-                        status
-                    )))
+                    Err(ExchangeError::hyperliquid(status).into())
                 }
             },
             Self::Data(v) => Ok(v),
@@ -88,3 +91,105 @@ where
         }
     }
 }
+
+impl ExchangeError {
+    /// Hyperliquid rejection. Hyperliquid has no error codes, so its error
+    /// text serves as the code.
+    pub(crate) fn hyperliquid(text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self::new(
+            Venue::Hyperliquid,
+            hyperliquid_category(&text),
+            text.clone(),
+            text,
+        )
+    }
+
+    /// Hyperliquid answers failed HTTP requests with plain text or a JSON
+    /// string.
+    pub(crate) fn hyperliquid_body(body: &[u8]) -> Option<Self> {
+        let text = serde_json::from_slice::<String>(body)
+            .ok()
+            .or_else(|| String::from_utf8(body.to_vec()).ok())?;
+        let text = text.trim();
+        (!text.is_empty() && !text.starts_with('<')).then(|| Self::hyperliquid(text))
+    }
+}
+
+fn hyperliquid_category(text: &str) -> ErrorCategory {
+    let text = text.to_ascii_lowercase();
+    let has = |needle: &str| text.contains(needle);
+
+    if has("insufficient margin") || has("insufficient balance") || has("insufficient spot") {
+        ErrorCategory::InsufficientBalance
+    } else if has("too many") || has("rate limit") {
+        ErrorCategory::RateLimited
+    } else if has("post only") {
+        ErrorCategory::PostOnlyWouldCross
+    } else if has("reduce only") {
+        ErrorCategory::ReduceOnlyRejected
+    } else if has("price") && (has("invalid") || has("tick size") || has("too far")) {
+        ErrorCategory::InvalidPrice
+    } else if has("never placed") || has("already canceled") || has("unknown oid") {
+        ErrorCategory::UnknownOrder
+    } else if has("does not exist") || has("signature") || has("not authorized") {
+        ErrorCategory::AuthFailed
+    } else {
+        ErrorCategory::Other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::InfraError;
+
+    #[test]
+    fn maps_error_text_to_categories() {
+        let res: RestResHyperliquid<serde_json::Value> = serde_json::from_str(
+            r#"{"status":"err","response":"User or API Wallet 0xabc does not exist."}"#,
+        )
+        .unwrap();
+        match res.into_vec() {
+            Err(InfraError::Exchange(e)) => {
+                assert_eq!(e.category, ErrorCategory::AuthFailed);
+                assert_eq!(e.code, "User or API Wallet 0xabc does not exist.");
+            },
+            other => panic!("unexpected result {other:?}"),
+        }
+
+        let cases = [
+            (
+                "Insufficient margin to place order. asset=0",
+                ErrorCategory::InsufficientBalance,
+            ),
+            (
+                "Post only order would have immediately matched, bbo was 1@2",
+                ErrorCategory::PostOnlyWouldCross,
+            ),
+            (
+                "Reduce only order would increase position.",
+                ErrorCategory::ReduceOnlyRejected,
+            ),
+            (
+                "Price must be divisible by tick size. asset=0",
+                ErrorCategory::InvalidPrice,
+            ),
+            (
+                "Order was never placed, already canceled, or filled.",
+                ErrorCategory::UnknownOrder,
+            ),
+            ("Something else", ErrorCategory::Other),
+        ];
+        for (text, category) in cases {
+            assert_eq!(hyperliquid_category(text), category, "{text}");
+        }
+    }
+
+    #[test]
+    fn parses_failed_http_bodies() {
+        let error = ExchangeError::hyperliquid_body(b"Too many cumulative requests sent").unwrap();
+        assert_eq!(error.category, ErrorCategory::RateLimited);
+        assert!(ExchangeError::hyperliquid_body(b"<html>502</html>").is_none());
+    }
+}
@@ -32,6 +32,7 @@ struct HyperliquidWsTypedChannel<T> {
 pub enum HyperliquidWsState<T> {
     ChannelBatch(Vec<T>),
     Clearinghouse(WsAccountPositionMsgHyperliquid<T>),
+    UserFills(WsUserFillsMsgHyperliquid<T>),
     ChannelSingle(T),
 }
 
@@ -52,6 +53,16 @@ pub struct WsClearinghouseStateHyperliquid<T> {
     pub time: u64,
 }
 
+#[allow(non_snake_case)]
+#[derive(Clone, Debug, Deserialize)]
+pub struct WsUserFillsMsgHyperliquid<T> {
+    #[serde(default)]
+    pub isSnapshot: bool,
+    #[serde(default)]
+    pub user: String,
+    pub fills: Vec<T>,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct HyperliquidWsEvent {
     pub channel: Option<String>,
@@ -67,6 +78,10 @@ impl<T: DeserializeOwned> HyperliquidWsData<T> {
         Self::decode_state(frame, HyperliquidWsState::Clearinghouse)
     }
 
+    pub(crate) fn decode_user_fills(frame: &[u8]) -> serde_json::Result<Self> {
+        Self::decode_state(frame, HyperliquidWsState::UserFills)
+    }
+
     fn decode_single_with<Message, Wrap>(frame: &[u8], wrap: Wrap) -> serde_json::Result<Self>
     where
         Message: DeserializeOwned,
@@ -119,6 +134,11 @@ where
                     .into_iter()
                     .map(|position| position.into_ws())
                     .collect(),
+                // The subscribe snapshot replays recent history; only live fills are new.
+                HyperliquidWsState::UserFills(msg) if msg.isSnapshot => Vec::new(),
+                HyperliquidWsState::UserFills(msg) => {
+                    msg.fills.into_iter().map(|fill| fill.into_ws()).collect()
+                },
                 HyperliquidWsState::ChannelSingle(data) => vec![data.into_ws()],
             },
             HyperliquidWsData::Event(event) => {
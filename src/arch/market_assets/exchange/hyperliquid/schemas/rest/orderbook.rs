@@ -28,6 +28,7 @@ impl RestOrderBookHyperliquid {
             inst: inst.to_string(),
             bids: levels_to_pairs(bids, depth),
             asks: levels_to_pairs(asks, depth),
+            update_id: None,
         }
     }
 }
@@ -1,4 +1,6 @@
 pub mod account_order;
 pub mod account_position;
 pub mod lob;
+pub(crate) mod post;
 pub mod trades;
+pub mod user_fills;
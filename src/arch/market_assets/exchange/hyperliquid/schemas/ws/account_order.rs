@@ -62,7 +62,7 @@ impl IntoWsData for WsAccountOrderHyperliquid {
     }
 }
 
-fn infer_inst_type(coin: &str) -> InstrumentType {
+pub(super) fn infer_inst_type(coin: &str) -> InstrumentType {
     if coin.contains('/') || coin.starts_with('@') {
         InstrumentType::Spot
     } else {
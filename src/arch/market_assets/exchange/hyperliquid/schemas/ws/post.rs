@@ -0,0 +1,145 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::arch::market_assets::{
+    api_data::account_data::{OrderAckData, WsOrderResponse},
+    exchange::exchange_error::ExchangeError,
+    exchange::hyperliquid::schemas::rest::{
+        cancel_order::RestCancelAckHyperliquid, trade_order::RestOrderAckHyperliquid,
+    },
+};
+use crate::errors::InfraError;
+
+/// Response to a websocket `post` request, on the `post` channel.
+#[derive(Debug, Deserialize)]
+pub(crate) struct WsPostResHyperliquid {
+    channel: String,
+    data: WsPostDataHyperliquid,
+}
+
+#[derive(Debug, Deserialize)]
+struct WsPostDataHyperliquid {
+    id: u64,
+    response: WsPostResponseHyperliquid,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "payload", rename_all = "lowercase")]
+enum WsPostResponseHyperliquid {
+    Action(WsPostActionHyperliquid),
+    Info(Value),
+    Error(String),
+}
+
+/// Same shape as a REST `/exchange` response.
+#[derive(Debug, Deserialize)]
+struct WsPostActionHyperliquid {
+    status: String,
+    response: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct WsPostActionDataHyperliquid {
+    #[serde(rename = "type")]
+    kind: String,
+    data: Value,
+}
+
+impl WsPostResHyperliquid {
+    /// Order and cancel acks carry no ids for canceled orders; the relay
+    /// fills them from the request.
+    pub(crate) fn into_response(self) -> Option<WsOrderResponse> {
+        if self.channel != "post" {
+            return None;
+        }
+
+        let id = self.data.id;
+        let result = match self.data.response {
+            WsPostResponseHyperliquid::Action(action) if action.status == "ok" => {
+                parse_action(action.response)?
+            },
+            WsPostResponseHyperliquid::Action(action) => {
+                let text = match action.response {
+                    Value::String(text) => text,
+                    response => response.to_string(),
+                };
+                Err(ExchangeError::hyperliquid(text).into())
+            },
+            WsPostResponseHyperliquid::Error(msg) => Err(ExchangeError::hyperliquid(msg).into()),
+            WsPostResponseHyperliquid::Info(_) => return None,
+        };
+
+        Some(WsOrderResponse { id, result })
+    }
+}
+
+fn parse_action(response: Value) -> Option<Result<Vec<OrderAckData>, InfraError>> {
+    let response: WsPostActionDataHyperliquid = serde_json::from_value(response).ok()?;
+    let acks = match response.kind.as_str() {
+        "order" => serde_json::from_value::<RestOrderAckHyperliquid>(response.data)
+            .map(RestOrderAckHyperliquid::into_order_acks),
+        "cancel" | "cancelByCloid" => {
+            serde_json::from_value::<RestCancelAckHyperliquid>(response.data).map(|cancels| {
+                cancels
+                    .statuses
+                    .into_iter()
+                    .map(|status| status.into_cancel_ack(None, None))
+                    .collect()
+            })
+        },
+        _ => return None,
+    };
+
+    Some(acks.map_err(InfraError::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::market_assets::base_data::OrderStatus;
+
+    fn parse(frame: &str) -> Option<WsOrderResponse> {
+        serde_json::from_str::<WsPostResHyperliquid>(frame)
+            .unwrap()
+            .into_response()
+    }
+
+    #[test]
+    fn maps_post_order_and_cancel_responses() {
+        let placed = parse(
+            r#"{"channel":"post","data":{"id":3,"response":{"type":"action","payload":{
+            "status":"ok","response":{"type":"order","data":{"statuses":[
+            {"resting":{"oid":77738308}},{"error":"Insufficient margin"}]}}}}}}"#,
+        )
+        .unwrap();
+        assert_eq!(placed.id, 3);
+        let acks = placed.result.unwrap();
+        assert_eq!(acks[0].order_id, "77738308");
+        assert_eq!(acks[0].order_status, OrderStatus::Live);
+        assert_eq!(acks[1].order_status, OrderStatus::Rejected);
+
+        let canceled = parse(
+            r#"{"channel":"post","data":{"id":4,"response":{"type":"action","payload":{
+            "status":"ok","response":{"type":"cancel","data":{"statuses":["success"]}}}}}}"#,
+        )
+        .unwrap();
+        let acks = canceled.result.unwrap();
+        assert_eq!(acks[0].order_status, OrderStatus::Canceled);
+        assert!(acks[0].order_id.is_empty());
+
+        let failed = parse(
+            r#"{"channel":"post","data":{"id":5,"response":{"type":"error",
+            "payload":"Invalid signature"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(failed.id, 5);
+        assert!(failed.result.is_err());
+
+        let rejected = parse(
+            r#"{"channel":"post","data":{"id":6,"response":{"type":"action","payload":{
+            "status":"err","response":"User or API Wallet does not exist."}}}}"#,
+        )
+        .unwrap();
+        assert!(rejected.result.is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use serde::Deserialize;
+
+use crate::arch::{
+    market_assets::{
+        api_general::ts_to_micros, base_data::OrderSide,
+        exchange::hyperliquid::api_utils::hyperliquid_inst_to_cli, market_core::Market,
+    },
+    strategy_base::handler::lob_events::{FillLiquidity, WsFill},
+    traits::conversion::IntoWsData,
+};
+
+use super::account_order::infer_inst_type;
+
+#[allow(non_snake_case)]
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct WsUserFillHyperliquid {
+    coin: String,
+    px: String,
+    sz: String,
+    side: String,
+    time: u64,
+    oid: u64,
+    crossed: bool,
+    fee: String,
+    tid: u64,
+    #[serde(default)]
+    feeToken: Option<String>,
+    #[serde(default)]
+    cloid: Option<String>,
+}
+
+impl IntoWsData for WsUserFillHyperliquid {
+    type Output = WsFill;
+
+    fn into_ws(self) -> Self::Output {
+        WsFill {
+            timestamp: ts_to_micros(self.time),
+            market: Market::HyperLiquid,
+            inst: hyperliquid_inst_to_cli(&self.coin),
+            inst_type: infer_inst_type(&self.coin),
+            side: match self.side.as_str() {
+                "B" => OrderSide::BUY,
+                "A" => OrderSide::SELL,
+                _ => OrderSide::Unknown,
+            },
+            price: self.px.parse().unwrap_or_default(),
+            size: self.sz.parse::<f64>().unwrap_or_default().abs(),
+            fee: self.fee.parse().unwrap_or_default(),
+            fee_currency: self.feeToken.filter(|token| !token.is_empty()),
+            liquidity: FillLiquidity::from_maker(Some(!self.crossed)),
+            trade_id: Some(self.tid.to_string()),
+            order_id: Some(self.oid.to_string()),
+            cli_order_id: self.cloid.filter(|cloid| !cloid.is_empty()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::arch::{
+        market_assets::exchange::hyperliquid::hyperliquid_ws_msg::HyperliquidWsData,
+        traits::conversion::IntoWsData,
+    };
+
+    use super::*;
+
+    fn user_fills(is_snapshot: bool) -> Vec<u8> {
+        serde_json::to_vec(&json!({
+            "channel": "userFills",
+            "data": {
+                "isSnapshot": is_snapshot,
+                "user": "0x0000000000000000000000000000000000000001",
+                "fills": [{
+                    "coin": "BTC",
+                    "px": "65000.5",
+                    "sz": "0.01",
+                    "side": "A",
+                    "time": 1781905826733_u64,
+                    "startPosition": "0.02",
+                    "dir": "Close Long",
+                    "closedPnl": "1.5",
+                    "hash": "0xabc",
+                    "oid": 987654321_u64,
+                    "crossed": false,
+                    "fee": "-0.0013",
+                    "tid": 118906512037719_u64,
+                    "feeToken": "USDC",
+                    "cloid": "0x00000000000000000000000000000001"
+                }]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn decodes_live_user_fills_and_skips_snapshot() {
+        let fills =
+            HyperliquidWsData::<WsUserFillHyperliquid>::decode_user_fills(&user_fills(false))
+                .unwrap()
+                .into_ws();
+        let snapshot =
+            HyperliquidWsData::<WsUserFillHyperliquid>::decode_user_fills(&user_fills(true))
+                .unwrap()
+                .into_ws();
+
+        assert_eq!(fills.len(), 1);
+        assert!(snapshot.is_empty());
+
+        let fill = &fills[0];
+        assert_eq!(fill.side, OrderSide::SELL);
+        assert_eq!(fill.price, 65000.5);
+        assert_eq!(fill.size, 0.01);
+        assert_eq!(fill.fee, -0.0013);
+        assert_eq!(fill.fee_currency.as_deref(), Some("USDC"));
+        assert_eq!(fill.liquidity, FillLiquidity::Maker);
+        assert_eq!(fill.trade_id.as_deref(), Some("118906512037719"));
+        assert_eq!(fill.order_id.as_deref(), Some("987654321"));
+    }
+}
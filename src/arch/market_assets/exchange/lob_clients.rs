@@ -1,10 +1,12 @@
 #![allow(unused_imports)]
 
-use super::prelude::*;
+use std::time::Duration;
+
+use super::{binance::binance_um_futures_cli, gate::gate_futures_cli, okx::okx_cli, prelude::*};
 use crate::arch::{
     market_assets::{
         api_data::{account_data::*, price_data::*, utils_data::*},
-        api_general::{CancelOrderParams, OrderParams},
+        api_general::{AmendOrderParams, CancelOrderParams, OrderParams},
         base_data::InstrumentType,
     },
     strategy_base::command::command_core::WsConnectTarget,
@@ -32,6 +34,75 @@ pub enum LobClients {
     Okx(OkxCli),
 }
 
+/// Hyperliquid documents no bulk-order limit; cap batches to bound request size.
+#[cfg(feature = "lob_clients")]
+const HYPERLIQUID_BATCH_LIMIT: usize = 50;
+
+#[cfg(feature = "lob_clients")]
+impl LobClients {
+    /// Largest batch accepted by [`LobPrivateRest::place_orders`], or `1`
+    /// when the client only places orders one at a time.
+    pub fn place_batch_limit(&self) -> usize {
+        match self {
+            LobClients::Hyperliquid(_) => HYPERLIQUID_BATCH_LIMIT,
+            LobClients::BinanceUm(_) => binance_um_futures_cli::BINANCE_UM_BATCH_PLACE_LIMIT,
+            LobClients::GateFutures(_) => gate_futures_cli::GATE_FUTURES_BATCH_PLACE_LIMIT,
+            LobClients::Okx(_) => okx_cli::OKX_BATCH_ORDER_LIMIT,
+            _ => 1,
+        }
+    }
+
+    /// Largest batch accepted by [`LobPrivateRest::cancel_orders`], or `1`
+    /// when the client only cancels orders one at a time.
+    pub fn cancel_batch_limit(&self) -> usize {
+        match self {
+            LobClients::Hyperliquid(_) => HYPERLIQUID_BATCH_LIMIT,
+            LobClients::BinanceUm(_) => binance_um_futures_cli::BINANCE_UM_BATCH_CANCEL_LIMIT,
+            LobClients::GateFutures(_) => gate_futures_cli::GATE_FUTURES_BATCH_CANCEL_LIMIT,
+            LobClients::Okx(_) => okx_cli::OKX_BATCH_ORDER_LIMIT,
+            _ => 1,
+        }
+    }
+
+    /// Largest batch accepted by [`LobPrivateRest::amend_orders`], or `1`
+    /// when the client only amends orders one at a time.
+    pub fn amend_batch_limit(&self) -> usize {
+        match self {
+            LobClients::Hyperliquid(_) => HYPERLIQUID_BATCH_LIMIT,
+            LobClients::BinanceUm(_) => binance_um_futures_cli::BINANCE_UM_BATCH_AMEND_LIMIT,
+            LobClients::GateFutures(_) => gate_futures_cli::GATE_FUTURES_BATCH_AMEND_LIMIT,
+            LobClients::Okx(_) => okx_cli::OKX_BATCH_ORDER_LIMIT,
+            _ => 1,
+        }
+    }
+
+    /// Whether [`LobPrivateRest::set_cancel_after`] arms the countdown per
+    /// instrument, so it cannot be called with `inst = None`.
+    pub fn cancel_after_needs_inst(&self) -> bool {
+        matches!(self, LobClients::BinanceUm(_))
+    }
+
+    /// Largest batch accepted by [`LobWsOrderEntry::ws_place_orders_msg`].
+    pub fn ws_place_batch_limit(&self) -> usize {
+        match self {
+            LobClients::Hyperliquid(_) => HYPERLIQUID_BATCH_LIMIT,
+            LobClients::GateFutures(_) => gate_futures_cli::GATE_FUTURES_BATCH_PLACE_LIMIT,
+            LobClients::Okx(_) => okx_cli::OKX_BATCH_ORDER_LIMIT,
+            _ => 1,
+        }
+    }
+
+    /// Largest batch accepted by [`LobWsOrderEntry::ws_cancel_orders_msg`].
+    pub fn ws_cancel_batch_limit(&self) -> usize {
+        match self {
+            LobClients::Hyperliquid(_) => HYPERLIQUID_BATCH_LIMIT,
+            LobClients::GateFutures(_) => gate_futures_cli::GATE_FUTURES_BATCH_CANCEL_LIMIT,
+            LobClients::Okx(_) => okx_cli::OKX_BATCH_ORDER_LIMIT,
+            _ => 1,
+        }
+    }
+}
+
 #[cfg(feature = "lob_clients")]
 impl Default for LobClients {
     fn default() -> Self {
@@ -146,6 +217,20 @@ impl LobPublicRest for LobClients {
             LobClients::Okx(c) => c.get_instrument_info(inst_type).await,
         }
     }
+
+    async fn get_server_time(&self) -> InfraResult<u64> {
+        match self {
+            LobClients::Hyperliquid(c) => c.get_server_time().await,
+            LobClients::BinanceCm(c) => c.get_server_time().await,
+            LobClients::BinanceSpot(c) => c.get_server_time().await,
+            LobClients::BinanceUm(c) => c.get_server_time().await,
+            LobClients::GateDelivery(c) => c.get_server_time().await,
+            LobClients::GateFutures(c) => c.get_server_time().await,
+            LobClients::GateSpot(c) => c.get_server_time().await,
+            LobClients::GateUni(c) => c.get_server_time().await,
+            LobClients::Okx(c) => c.get_server_time().await,
+        }
+    }
 }
 
 #[cfg(feature = "lob_clients")]
@@ -220,6 +305,49 @@ impl LobPrivateRest for LobClients {
         }
     }
 
+    async fn amend_order(&self, amend_params: AmendOrderParams) -> InfraResult<OrderAckData> {
+        match self {
+            LobClients::Hyperliquid(c) => c.amend_order(amend_params).await,
+            LobClients::BinanceUm(c) => c.amend_order(amend_params).await,
+            LobClients::GateFutures(c) => c.amend_order(amend_params).await,
+            LobClients::Okx(c) => c.amend_order(amend_params).await,
+            _ => Err(InfraError::Unimplemented),
+        }
+    }
+
+    async fn amend_orders(
+        &self,
+        amend_params: Vec<AmendOrderParams>,
+    ) -> InfraResult<Vec<OrderAckData>> {
+        match self {
+            LobClients::Hyperliquid(c) => c.amend_orders(amend_params).await,
+            LobClients::BinanceUm(c) => c.amend_orders(amend_params).await,
+            LobClients::GateFutures(c) => c.amend_orders(amend_params).await,
+            LobClients::Okx(c) => c.amend_orders(amend_params).await,
+            _ => Err(InfraError::Unimplemented),
+        }
+    }
+
+    async fn cancel_all_orders(&self, inst: Option<&str>) -> InfraResult<Vec<OrderAckData>> {
+        match self {
+            LobClients::Hyperliquid(c) => c.cancel_all_orders(inst).await,
+            LobClients::BinanceUm(c) => c.cancel_all_orders(inst).await,
+            LobClients::GateFutures(c) => c.cancel_all_orders(inst).await,
+            LobClients::Okx(c) => c.cancel_all_orders(inst).await,
+            _ => Err(InfraError::Unimplemented),
+        }
+    }
+
+    async fn set_cancel_after(&self, inst: Option<&str>, timeout: Duration) -> InfraResult<()> {
+        match self {
+            LobClients::Hyperliquid(c) => c.set_cancel_after(inst, timeout).await,
+            LobClients::BinanceUm(c) => c.set_cancel_after(inst, timeout).await,
+            LobClients::GateFutures(c) => c.set_cancel_after(inst, timeout).await,
+            LobClients::Okx(c) => c.set_cancel_after(inst, timeout).await,
+            _ => Err(InfraError::Unimplemented),
+        }
+    }
+
     async fn get_open_orders(
         &self,
         inst: &str,
@@ -264,6 +392,23 @@ impl LobPrivateRest for LobClients {
         }
     }
 
+    async fn get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        match self {
+            LobClients::Hyperliquid(c) => c.get_order(inst, order_id, cli_order_id).await,
+            LobClients::BinanceSpot(c) => c.get_order(inst, order_id, cli_order_id).await,
+            LobClients::BinanceUm(c) => c.get_order(inst, order_id, cli_order_id).await,
+            LobClients::GateFutures(c) => c.get_order(inst, order_id, cli_order_id).await,
+            LobClients::GateSpot(c) => c.get_order(inst, order_id, cli_order_id).await,
+            LobClients::Okx(c) => c.get_order(inst, order_id, cli_order_id).await,
+            _ => Err(InfraError::Unimplemented),
+        }
+    }
+
     async fn get_order_history(
         &self,
         inst: &str,
@@ -322,6 +467,24 @@ impl LobWebsocket for LobClients {
         }
     }
 
+    async fn get_public_unsub_msg(
+        &self,
+        channel: &WsChannel,
+        insts: Option<&[String]>,
+    ) -> InfraResult<String> {
+        match self {
+            LobClients::Hyperliquid(c) => c.get_public_unsub_msg(channel, insts).await,
+            LobClients::BinanceCm(c) => c.get_public_unsub_msg(channel, insts).await,
+            LobClients::BinanceSpot(c) => c.get_public_unsub_msg(channel, insts).await,
+            LobClients::BinanceUm(c) => c.get_public_unsub_msg(channel, insts).await,
+            LobClients::GateDelivery(c) => c.get_public_unsub_msg(channel, insts).await,
+            LobClients::GateFutures(c) => c.get_public_unsub_msg(channel, insts).await,
+            LobClients::GateSpot(c) => c.get_public_unsub_msg(channel, insts).await,
+            LobClients::GateUni(c) => c.get_public_unsub_msg(channel, insts).await,
+            LobClients::Okx(c) => c.get_public_unsub_msg(channel, insts).await,
+        }
+    }
+
     async fn get_private_sub_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         match self {
             LobClients::Hyperliquid(c) => c.get_private_sub_msg(channel).await,
@@ -395,3 +558,39 @@ impl LobWebsocket for LobClients {
         }
     }
 }
+
+#[cfg(feature = "lob_clients")]
+impl LobWsOrderEntry for LobClients {
+    fn ws_place_orders_msg(&self, id: u64, orders: &[OrderParams]) -> InfraResult<String> {
+        match self {
+            LobClients::Hyperliquid(c) => c.ws_place_orders_msg(id, orders),
+            LobClients::BinanceSpot(c) => c.ws_place_orders_msg(id, orders),
+            LobClients::BinanceUm(c) => c.ws_place_orders_msg(id, orders),
+            LobClients::GateFutures(c) => c.ws_place_orders_msg(id, orders),
+            LobClients::Okx(c) => c.ws_place_orders_msg(id, orders),
+            _ => Err(InfraError::Unimplemented),
+        }
+    }
+
+    fn ws_cancel_orders_msg(&self, id: u64, cancels: &[CancelOrderParams]) -> InfraResult<String> {
+        match self {
+            LobClients::Hyperliquid(c) => c.ws_cancel_orders_msg(id, cancels),
+            LobClients::BinanceSpot(c) => c.ws_cancel_orders_msg(id, cancels),
+            LobClients::BinanceUm(c) => c.ws_cancel_orders_msg(id, cancels),
+            LobClients::GateFutures(c) => c.ws_cancel_orders_msg(id, cancels),
+            LobClients::Okx(c) => c.ws_cancel_orders_msg(id, cancels),
+            _ => Err(InfraError::Unimplemented),
+        }
+    }
+
+    fn parse_ws_order_response(&self, frame: &[u8]) -> Option<WsOrderResponse> {
+        match self {
+            LobClients::Hyperliquid(c) => c.parse_ws_order_response(frame),
+            LobClients::BinanceSpot(c) => c.parse_ws_order_response(frame),
+            LobClients::BinanceUm(c) => c.parse_ws_order_response(frame),
+            LobClients::GateFutures(c) => c.parse_ws_order_response(frame),
+            LobClients::Okx(c) => c.parse_ws_order_response(frame),
+            _ => None,
+        }
+    }
+}
@@ -1,12 +1,14 @@
 use data_encoding::BASE64;
 use hmac::{KeyInit, Mac};
 
-use reqwest::{Client, Response};
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::Value;
 
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
-    market_assets::api_general::*,
+    market_assets::{api_general::*, exchange::endpoints::EndpointProfile},
     redaction::{redact_identifier, redact_secret},
 };
 use crate::errors::{InfraError, InfraResult};
@@ -65,11 +67,12 @@ impl OkxKey {
 
     pub fn sign_now(
         &self,
+        rest: &str,
         method: &str,
         uri: &str,
         body: Option<&str>,
     ) -> InfraResult<Signature<String>> {
-        let timestamp = get_okx_timestamp();
+        let timestamp = get_okx_timestamp(rest);
 
         let raw_sign = match body {
             Some(b) => format!("{}{}{}{}", timestamp, method, uri, b),
@@ -79,129 +82,129 @@ impl OkxKey {
         self.sign(raw_sign, timestamp)
     }
 
-    pub(crate) async fn get_request(
+    pub(crate) fn get_request(
         &self,
         client: &Client,
         signature: &Signature<String>,
+        extra_headers: &[(String, String)],
         url: &str,
-    ) -> InfraResult<Response> {
-        let res = client
-            .get(url)
+    ) -> RequestBuilder {
+        with_headers(client.get(url), extra_headers)
             .header("OK-ACCESS-KEY", &self.api_key)
             .header("OK-ACCESS-SIGN", &signature.signature)
             .header("OK-ACCESS-TIMESTAMP", &signature.timestamp)
             .header("OK-ACCESS-PASSPHRASE", &self.passphrase)
             .header("Content-Type", "application/json")
-            .send()
-            .await?;
-
-        Ok(res)
     }
 
-    pub(crate) async fn post_request(
+    pub(crate) fn post_request(
         &self,
         client: &Client,
         signature: &Signature<String>,
+        extra_headers: &[(String, String)],
         body: String,
         url: &str,
-    ) -> InfraResult<Response> {
-        let res = client
-            .post(url)
+    ) -> RequestBuilder {
+        with_headers(client.post(url), extra_headers)
             .header("OK-ACCESS-KEY", &self.api_key)
             .header("OK-ACCESS-SIGN", &signature.signature)
             .header("OK-ACCESS-TIMESTAMP", &signature.timestamp)
             .header("OK-ACCESS-PASSPHRASE", &self.passphrase)
             .header("Content-Type", "application/json")
             .body(body)
-            .send()
-            .await?;
-
-        Ok(res)
     }
 
-    pub(crate) async fn put_request(
+    pub(crate) fn put_request(
         &self,
         client: &Client,
         signature: &Signature<String>,
+        extra_headers: &[(String, String)],
         body: String,
         url: &str,
-    ) -> InfraResult<Response> {
-        let res = client
-            .put(url)
+    ) -> RequestBuilder {
+        with_headers(client.put(url), extra_headers)
             .header("OK-ACCESS-KEY", &self.api_key)
             .header("OK-ACCESS-SIGN", &signature.signature)
             .header("OK-ACCESS-TIMESTAMP", &signature.timestamp)
             .header("OK-ACCESS-PASSPHRASE", &self.passphrase)
             .header("Content-Type", "application/json")
             .body(body)
-            .send()
-            .await?;
-
-        Ok(res)
     }
 
-    pub(crate) async fn delete_request(
+    pub(crate) fn delete_request(
         &self,
         client: &Client,
         signature: &Signature<String>,
+        extra_headers: &[(String, String)],
         body: String,
         url: &str,
-    ) -> InfraResult<Response> {
-        let res = client
-            .delete(url)
+    ) -> RequestBuilder {
+        with_headers(client.delete(url), extra_headers)
             .header("OK-ACCESS-KEY", &self.api_key)
             .header("OK-ACCESS-SIGN", &signature.signature)
             .header("OK-ACCESS-TIMESTAMP", &signature.timestamp)
             .header("OK-ACCESS-PASSPHRASE", &self.passphrase)
             .header("Content-Type", "application/json")
             .body(body)
-            .send()
-            .await?;
-
-        Ok(res)
     }
 
     pub(crate) async fn send_signed_request<T>(
         &self,
         client: &Client,
+        retry: &RetryPolicy,
         method: RequestMethod,
         body: String,
-        base_url: &str,
+        endpoints: &EndpointProfile,
         endpoint: &str,
     ) -> InfraResult<T>
     where
         T: DeserializeOwned + Send,
     {
-        let response = match method {
+        let base_url = endpoints.rest.as_str();
+        let headers = endpoints.rest_headers.as_slice();
+        let request_path = match method {
             RequestMethod::Get => {
                 let query = okx_normalize_get_query(&body)?;
-                let request_path = okx_request_path_with_query(endpoint, query.as_deref());
-                let url = [base_url, &request_path].concat();
-                let signature = self.sign_now("GET", &request_path, None)?;
-                self.get_request(client, &signature, &url).await?
-            },
-            RequestMethod::Post => {
-                let url = [base_url, endpoint].concat();
-                let signature = self.sign_now("POST", endpoint, Some(&body))?;
-                self.post_request(client, &signature, body, &url).await?
-            },
-            RequestMethod::Put => {
-                let url = [base_url, endpoint].concat();
-                let signature = self.sign_now("PUT", endpoint, Some(&body))?;
-                self.put_request(client, &signature, body, &url).await?
-            },
-            RequestMethod::Delete => {
-                let url = [base_url, endpoint].concat();
-                let signature = self.sign_now("DELETE", endpoint, Some(&body))?;
-                self.delete_request(client, &signature, body, &url).await?
+                okx_request_path_with_query(endpoint, query.as_deref())
             },
+            _ => endpoint.to_string(),
         };
+        let url = [base_url, &request_path].concat();
+
+        let response = retry
+            .send_with(|| {
+                Ok(match method {
+                    RequestMethod::Get => {
+                        let signature = self.sign_now(base_url, "GET", &request_path, None)?;
+                        self.get_request(client, &signature, headers, &url)
+                    },
+                    RequestMethod::Post => {
+                        let signature = self.sign_now(base_url, "POST", endpoint, Some(&body))?;
+                        self.post_request(client, &signature, headers, body.clone(), &url)
+                    },
+                    RequestMethod::Put => {
+                        let signature = self.sign_now(base_url, "PUT", endpoint, Some(&body))?;
+                        self.put_request(client, &signature, headers, body.clone(), &url)
+                    },
+                    RequestMethod::Delete => {
+                        let signature = self.sign_now(base_url, "DELETE", endpoint, Some(&body))?;
+                        self.delete_request(client, &signature, headers, body.clone(), &url)
+                    },
+                })
+            })
+            .await?;
 
         let label = format!("Okx {:?} {}", method, endpoint);
-        parse_json_response(&label, response).await
+        parse_venue_response(Venue::Okx, &label, response).await
     }
 }
 
+fn with_headers(builder: RequestBuilder, headers: &[(String, String)]) -> RequestBuilder {
+    headers.iter().fold(builder, |builder, (name, value)| {
+        builder.header(name, value)
+    })
+}
+
 fn okx_request_path_with_query(endpoint: &str, query: Option<&str>) -> String {
     match query {
         Some(query) if !query.is_empty() => format!("{}?{}", endpoint, query),
@@ -266,4 +269,17 @@ mod tests {
         assert!(!debug.contains("passphrase123"));
         assert!(debug.contains("[REDACTED]"));
     }
+
+    #[test]
+    fn profile_headers_are_added_to_signed_requests() {
+        let endpoints = EndpointProfile::okx_demo();
+        let request = with_headers(
+            Client::new().get("https://www.okx.com/api/v5/account/balance"),
+            &endpoints.rest_headers,
+        )
+        .build()
+        .unwrap();
+
+        assert_eq!(request.headers()["x-simulated-trading"], "1");
+    }
 }
@@ -3,9 +3,11 @@ use serde_json::json;
 use std::collections::HashMap;
 use tracing::error;
 
+use crate::arch::market_assets::exchange::clock_sync::ClockSync;
+use crate::arch::market_assets::exchange::exchange_error::Venue;
 use crate::arch::{
     market_assets::{
-        api_general::{CancelOrderParams, OrderParams},
+        api_general::{AmendOrderParams, CancelOrderParams, OrderParams},
         base_data::{
             InstrumentType, MarginMode, OrderSide, OrderType, PositionSide, SUBSCRIBE_LOWER,
         },
@@ -111,6 +113,39 @@ impl TryFrom<&CancelOrderParams> for RestBatchCancelOrderParamsOkx {
     }
 }
 
+#[derive(Clone, Debug, Serialize)]
+pub struct RestAmendOrderParamsOkx {
+    #[serde(rename = "instId")]
+    inst_id: String,
+    #[serde(rename = "ordId", skip_serializing_if = "Option::is_none")]
+    order_id: Option<String>,
+    #[serde(rename = "clOrdId", skip_serializing_if = "Option::is_none")]
+    client_order_id: Option<String>,
+    #[serde(rename = "newPx", skip_serializing_if = "Option::is_none")]
+    new_price: Option<String>,
+    #[serde(rename = "newSz", skip_serializing_if = "Option::is_none")]
+    new_size: Option<String>,
+    #[serde(flatten)]
+    extra: HashMap<String, String>,
+}
+
+impl TryFrom<&AmendOrderParams> for RestAmendOrderParamsOkx {
+    type Error = InfraError;
+
+    fn try_from(amend: &AmendOrderParams) -> Result<Self, Self::Error> {
+        amend.validate_target()?;
+
+        Ok(Self {
+            inst_id: cli_perp_to_okx_inst(&amend.inst),
+            order_id: amend.order_id.clone(),
+            client_order_id: amend.cli_order_id.clone(),
+            new_price: amend.new_price.clone(),
+            new_size: amend.new_size.clone(),
+            extra: amend.extra.clone(),
+        })
+    }
+}
+
 pub fn ws_subscribe_msg_okx(channel: &str, insts: Option<&[String]>) -> String {
     let args: Vec<_> = match insts {
         Some(list) => list
@@ -133,15 +168,12 @@ pub fn ws_subscribe_msg_okx(channel: &str, insts: Option<&[String]>) -> String {
     subscribe_msg.to_string()
 }
 
-pub fn get_okx_timestamp() -> String {
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .expect("Time went backwards");
+/// OKX request timestamp in the venue time of the deployment at `rest`,
+/// e.g. `1700000000.123`.
+pub fn get_okx_timestamp(rest: &str) -> String {
+    let now = ClockSync::now_millis(Venue::Okx, rest);
 
-    let seconds = now.as_secs();
-    let millis = now.subsec_millis();
-
-    format!("{}.{:03}", seconds, millis)
+    format!("{}.{:03}", now / 1_000, now % 1_000)
 }
 
 pub fn cli_perp_to_okx_inst(symbol: &str) -> String {
@@ -710,4 +742,30 @@ mod tests {
             "instId=BTC-USDT-SWAP&ordId=12345&clOrdId=entry-1"
         );
     }
+
+    #[test]
+    fn serializes_okx_amend_with_only_changed_fields() {
+        let amend = AmendOrderParams {
+            inst: "BTC_USDT_PERP".into(),
+            cli_order_id: Some("quote1".into()),
+            new_price: Some("100.5".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            serde_json::to_value(RestAmendOrderParamsOkx::try_from(&amend).unwrap()).unwrap(),
+            json!({
+                "instId": "BTC-USDT-SWAP",
+                "clOrdId": "quote1",
+                "newPx": "100.5",
+            })
+        );
+
+        let no_change = AmendOrderParams {
+            inst: "BTC_USDT_PERP".into(),
+            order_id: Some("42".into()),
+            ..Default::default()
+        };
+        assert!(RestAmendOrderParamsOkx::try_from(&no_change).is_err());
+    }
 }
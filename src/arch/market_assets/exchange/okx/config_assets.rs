@@ -1,8 +1,42 @@
+use reqwest::Method;
+use tokio::time::Duration;
+
+use crate::arch::market_assets::exchange::{
+    endpoints::EndpointProfile,
+    rate_limit::{EndpointRule, RateLimitProfile, RequestPriority},
+};
+
 /// OKX API Base
 pub const OKX_WS_PUB: &str = "wss://ws.okx.com:8443/ws/v5/public";
 pub const OKX_WS_PRI: &str = "wss://ws.okx.com:8443/ws/v5/private";
 pub const OKX_WS_BUS: &str = "wss://ws.okx.com:8443/ws/v5/business";
 pub const OKX_BASE_URL: &str = "https://www.okx.com";
+pub const OKX_DEMO_WS_PUB: &str = "wss://wspap.okx.com:8443/ws/v5/public";
+pub const OKX_DEMO_WS_PRI: &str = "wss://wspap.okx.com:8443/ws/v5/private";
+pub const OKX_DEMO_WS_BUS: &str = "wss://wspap.okx.com:8443/ws/v5/business";
+pub const OKX_SIMULATED_TRADING_HEADER: &str = "x-simulated-trading";
+
+impl EndpointProfile {
+    /// OKX production endpoints.
+    pub fn okx_production() -> Self {
+        Self::new(OKX_BASE_URL, OKX_WS_PUB)
+            .with_ws_private(OKX_WS_PRI)
+            .with_ws_business(OKX_WS_BUS)
+            .with_ws_trade(OKX_WS_PRI)
+    }
+
+    /// OKX demo trading. REST shares the production host and is routed to
+    /// demo accounts by the `x-simulated-trading` header; demo API keys are
+    /// required.
+    pub fn okx_demo() -> Self {
+        Self::new(OKX_BASE_URL, OKX_DEMO_WS_PUB)
+            .with_ws_private(OKX_DEMO_WS_PRI)
+            .with_ws_business(OKX_DEMO_WS_BUS)
+            .with_ws_trade(OKX_DEMO_WS_PRI)
+            .with_rest_header(OKX_SIMULATED_TRADING_HEADER, "1")
+            .with_testnet(true)
+    }
+}
 
 /// REST endpoints
 pub const OKX_ACCOUNT_BALANCE: &str = "/api/v5/account/balance";
@@ -22,12 +56,16 @@ pub const OKX_TRADE_ORDER: &str = "/api/v5/trade/order";
 pub const OKX_TRADE_BATCH_ORDERS: &str = "/api/v5/trade/batch-orders";
 pub const OKX_TRADE_CANCEL_ORDER: &str = "/api/v5/trade/cancel-order";
 pub const OKX_TRADE_CANCEL_BATCH_ORDERS: &str = "/api/v5/trade/cancel-batch-orders";
+pub const OKX_TRADE_AMEND_ORDER: &str = "/api/v5/trade/amend-order";
+pub const OKX_TRADE_AMEND_BATCH_ORDERS: &str = "/api/v5/trade/amend-batch-orders";
+pub const OKX_TRADE_CANCEL_ALL_AFTER: &str = "/api/v5/trade/cancel-all-after";
 pub const OKX_TRADE_ORDERS_HISTORY: &str = "/api/v5/trade/orders-history";
 pub const OKX_TRADE_ORDERS_PENDING: &str = "/api/v5/trade/orders-pending";
 pub const OKX_PUBLIC_INSTRUMENTS: &str = "/api/v5/public/instruments";
 pub const OKX_PUBLIC_FUNDING_RATE: &str = "/api/v5/public/funding-rate";
 pub const OKX_PUBLIC_FUNDING_RATE_HISTORY: &str = "/api/v5/public/funding-rate-history";
 pub const OKX_PUBLIC_PRICE_LIMIT: &str = "/api/v5/public/price-limit";
+pub const OKX_PUBLIC_TIME: &str = "/api/v5/public/time";
 pub const OKX_MARKET_TICKER: &str = "/api/v5/market/ticker";
 pub const OKX_MARKET_TICKERS: &str = "/api/v5/market/tickers";
 pub const OKX_MARKET_CANDLES: &str = "/api/v5/market/candles";
@@ -41,3 +79,73 @@ pub const OKX_CT_LEADTRADER_SUBPOSITIONS_HISTORY: &str =
 
 /// WebSocket channels
 pub const OKX_WS_LOGIN: &str = "GET/users/self/verify";
+
+impl RateLimitProfile {
+    /// OKX limits, which apply per endpoint over 2s windows. Trading and
+    /// account limits are per user and kept per `OK-ACCESS-KEY`, so demo
+    /// trading keys on the same host keep their own budget; market data
+    /// limits are per IP. OKX reports no usage in response headers, so the
+    /// limiter relies on its own count and backs off on 429s.
+    pub fn okx() -> Self {
+        let window = Duration::from_secs(2);
+        let endpoint = |path: &str, bucket: &str| EndpointRule::exact(path).with_cost(bucket, 1);
+
+        Self::new()
+            .with_account_header("OK-ACCESS-KEY")
+            .with_account_bucket("order", 60, window)
+            .with_account_bucket("batch-orders", 300, window)
+            .with_account_bucket("cancel", 60, window)
+            .with_account_bucket("cancel-batch", 300, window)
+            .with_account_bucket("amend", 60, window)
+            .with_account_bucket("amend-batch", 300, window)
+            .with_account_bucket("cancel-all-after", 1, Duration::from_secs(1))
+            .with_account_bucket("orders-pending", 60, window)
+            .with_account_bucket("orders-history", 40, window)
+            .with_account_bucket("account", 10, window)
+            .with_bucket("market", 20, window)
+            .with_bucket("books", 40, window)
+            .with_bucket("candles", 40, window)
+            .with_bucket("public", 20, window)
+            .with_bucket("other", 5, window)
+            .with_rule(
+                endpoint(OKX_TRADE_CANCEL_ORDER, "cancel").with_priority(RequestPriority::Cancel),
+            )
+            // Batches are charged for the 20 orders they may carry.
+            .with_rule(
+                EndpointRule::exact(OKX_TRADE_CANCEL_BATCH_ORDERS)
+                    .with_cost("cancel-batch", 20)
+                    .with_priority(RequestPriority::Cancel),
+            )
+            .with_rule(
+                endpoint(OKX_TRADE_CANCEL_ALL_AFTER, "cancel-all-after")
+                    .with_priority(RequestPriority::Cancel),
+            )
+            .with_rule(
+                endpoint(OKX_TRADE_ORDER, "order")
+                    .with_method(Method::POST)
+                    .with_priority(RequestPriority::Trade),
+            )
+            .with_rule(
+                EndpointRule::exact(OKX_TRADE_BATCH_ORDERS)
+                    .with_cost("batch-orders", 20)
+                    .with_priority(RequestPriority::Trade),
+            )
+            .with_rule(
+                endpoint(OKX_TRADE_AMEND_ORDER, "amend").with_priority(RequestPriority::Trade),
+            )
+            .with_rule(
+                EndpointRule::exact(OKX_TRADE_AMEND_BATCH_ORDERS)
+                    .with_cost("amend-batch", 20)
+                    .with_priority(RequestPriority::Trade),
+            )
+            .with_rule(endpoint(OKX_TRADE_ORDER, "order"))
+            .with_rule(endpoint(OKX_TRADE_ORDERS_PENDING, "orders-pending"))
+            .with_rule(endpoint(OKX_TRADE_ORDERS_HISTORY, "orders-history"))
+            .with_rule(EndpointRule::prefix("/api/v5/account/").with_cost("account", 1))
+            .with_rule(endpoint(OKX_MARKET_BOOKS, "books"))
+            .with_rule(endpoint(OKX_MARKET_CANDLES, "candles"))
+            .with_rule(EndpointRule::prefix("/api/v5/market/").with_cost("market", 1))
+            .with_rule(EndpointRule::prefix("/api/v5/public/").with_cost("public", 1))
+            .with_rule(EndpointRule::prefix("/").with_cost("other", 1))
+    }
+}
@@ -1,18 +1,22 @@
 use reqwest::Client;
 use serde_json::json;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tracing::error;
 
+use crate::arch::market_assets::exchange::exchange_error::Venue;
+use crate::arch::market_assets::exchange::retry::RetryPolicy;
 use crate::arch::{
     market_assets::{
         api_data::{account_data::*, price_data::*, utils_data::*},
         api_general::*,
         base_data::*,
+        exchange::endpoints::EndpointProfile,
     },
+    strategy_base::command::command_core::{SnapshotSource, WsConnectTarget},
     task_execution::task_ws::*,
     traits::{
         conversion::IntoInfraVec,
-        market_lob::{LobPrivateRest, LobPublicRest, LobWebsocket, MarketLobApi},
+        market_lob::{LobPrivateRest, LobPublicRest, LobWebsocket, LobWsOrderEntry, MarketLobApi},
     },
 };
 use crate::errors::{InfraError, InfraResult};
@@ -39,17 +43,21 @@ use super::{
         funding_rate::RestFundingRateOkx, funding_rate_history::RestFundingRateHistoryOkx,
         market_ticker::RestMarketTickerOkx, order_history::RestOrderHistoryOkx,
         orderbook::RestOrderBookOkx, price_limit::RestPriceLimitOkx,
-        public_instruments::RestInstrumentsOkx, trade_order::RestOrderAckOkx,
+        public_instruments::RestInstrumentsOkx, public_time::RestPublicTimeOkx,
+        trade_order::RestOrderAckOkx,
     },
+    schemas::ws::trade_order::WsOrderResOkx,
 };
 
 const OPEN_ORDERS_PAGE_LIMIT: u32 = 100;
-const OKX_BATCH_ORDER_LIMIT: usize = 20;
+pub(crate) const OKX_BATCH_ORDER_LIMIT: usize = 20;
 
 #[derive(Clone, Debug)]
 pub struct OkxCli {
     pub client: Arc<Client>,
     pub api_key: Option<OkxKey>,
+    pub endpoints: EndpointProfile,
+    pub retry: RetryPolicy,
 }
 
 impl Default for OkxCli {
@@ -97,6 +105,10 @@ impl LobPublicRest for OkxCli {
     ) -> InfraResult<Vec<InstrumentInfo>> {
         self._get_instrument_info(inst_type).await
     }
+
+    async fn get_server_time(&self) -> InfraResult<u64> {
+        self._get_server_time().await
+    }
 }
 
 impl LobPrivateRest for OkxCli {
@@ -112,7 +124,9 @@ impl LobPrivateRest for OkxCli {
     }
 
     async fn place_order(&self, order_params: OrderParams) -> InfraResult<OrderAckData> {
-        self._place_order(order_params).await
+        self.retry
+            .place_order(self, order_params, |params| self._place_order(params))
+            .await
     }
 
     async fn place_orders(&self, order_params: Vec<OrderParams>) -> InfraResult<Vec<OrderAckData>> {
@@ -135,6 +149,25 @@ impl LobPrivateRest for OkxCli {
         self._cancel_orders(cancel_params).await
     }
 
+    async fn amend_order(&self, amend_params: AmendOrderParams) -> InfraResult<OrderAckData> {
+        self._amend_order(amend_params).await
+    }
+
+    async fn amend_orders(
+        &self,
+        amend_params: Vec<AmendOrderParams>,
+    ) -> InfraResult<Vec<OrderAckData>> {
+        self._amend_orders(amend_params).await
+    }
+
+    async fn cancel_all_orders(&self, inst: Option<&str>) -> InfraResult<Vec<OrderAckData>> {
+        self._cancel_all_orders(inst).await
+    }
+
+    async fn set_cancel_after(&self, inst: Option<&str>, timeout: Duration) -> InfraResult<()> {
+        self._set_cancel_after(inst, timeout).await
+    }
+
     async fn get_open_orders(
         &self,
         inst: &str,
@@ -143,6 +176,15 @@ impl LobPrivateRest for OkxCli {
         self._get_open_orders(inst, limit).await
     }
 
+    async fn get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        self._get_order(inst, order_id, cli_order_id).await
+    }
+
     async fn get_balance(&self, assets: Option<&[String]>) -> InfraResult<Vec<BalanceData>> {
         self._get_balance(assets).await
     }
@@ -173,6 +215,15 @@ impl LobWebsocket for OkxCli {
         self._get_public_sub_msg(channel, insts)
     }
 
+    async fn get_public_unsub_msg(
+        &self,
+        channel: &WsChannel,
+        insts: Option<&[String]>,
+    ) -> InfraResult<String> {
+        let sub_msg = self._get_public_sub_msg(channel, insts)?;
+        ws_unsubscribe_msg(&sub_msg, "op", UNSUBSCRIBE_LOWER)
+    }
+
     async fn get_private_sub_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         self._get_private_sub_msg(channel)
     }
@@ -181,8 +232,35 @@ impl LobWebsocket for OkxCli {
         self._get_public_connect_msg(channel)
     }
 
-    async fn get_private_connect_msg(&self, _channel: &WsChannel) -> InfraResult<String> {
-        Ok(OKX_WS_PRI.into())
+    async fn get_public_connect_target(&self, channel: &WsChannel) -> InfraResult<WsConnectTarget> {
+        let target = WsConnectTarget::new(self._get_public_connect_msg(channel)?);
+        match channel {
+            WsChannel::Lob(_) => Ok(target.with_snapshot_source(SnapshotSource::Okx(self.clone()))),
+            _ => Ok(target),
+        }
+    }
+
+    async fn get_private_connect_msg(&self, channel: &WsChannel) -> InfraResult<String> {
+        match channel {
+            WsChannel::OrderEntry => Ok(self.endpoints.ws_trade.clone()),
+            _ => Ok(self.endpoints.ws_private.clone()),
+        }
+    }
+}
+
+impl LobWsOrderEntry for OkxCli {
+    fn ws_place_orders_msg(&self, id: u64, orders: &[OrderParams]) -> InfraResult<String> {
+        self._ws_place_orders_msg(id, orders)
+    }
+
+    fn ws_cancel_orders_msg(&self, id: u64, cancels: &[CancelOrderParams]) -> InfraResult<String> {
+        self._ws_cancel_orders_msg(id, cancels)
+    }
+
+    fn parse_ws_order_response(&self, frame: &[u8]) -> Option<WsOrderResponse> {
+        serde_json::from_slice::<WsOrderResOkx>(frame)
+            .ok()?
+            .into_response()
     }
 }
 
@@ -191,16 +269,32 @@ impl OkxCli {
         Self {
             client: shared_client,
             api_key: None,
+            endpoints: EndpointProfile::okx_production(),
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Sends REST requests and websocket connections to `endpoints`, e.g.
+    /// [`EndpointProfile::okx_demo`], instead of OKX production.
+    pub fn with_endpoints(mut self, endpoints: EndpointProfile) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Times out and retries REST requests with `retry` instead of
+    /// [`RetryPolicy::default`].
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     pub fn ws_login_msg(&self) -> InfraResult<String> {
         let api_key = self
             .api_key
             .as_ref()
             .ok_or(InfraError::ApiCliNotInitialized)?;
 
-        let timestamp = get_okx_timestamp();
+        let timestamp = get_okx_timestamp(&self.endpoints.rest);
         let raw_sign = format!("{}{}", timestamp, OKX_WS_LOGIN);
         let signature = api_key.sign(raw_sign, timestamp.clone())?;
 
@@ -228,9 +322,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_TRADE_ORDERS_PENDING,
             )
             .await?;
@@ -255,9 +350,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_TRADE_ORDERS_HISTORY,
             )
             .await?;
@@ -292,9 +388,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_TRADE_ORDER,
             )
             .await?;
@@ -309,9 +406,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 "{}".into(),
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ACCOUNT_CONFIG,
             )
             .await?;
@@ -336,9 +434,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ACCOUNT_SET_POSITION_MODE,
             )
             .await?;
@@ -389,9 +488,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ACCOUNT_SET_LEVERAGE,
             )
             .await?;
@@ -425,9 +525,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ASSET_CURRENCIES,
             )
             .await?;
@@ -453,9 +554,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ASSET_BALANCES,
             )
             .await?;
@@ -475,9 +577,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ASSET_DEPOSIT_ADDRESS,
             )
             .await?;
@@ -497,9 +600,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ASSET_DEPOSIT_HISTORY,
             )
             .await?;
@@ -519,9 +623,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ASSET_TRANSFER,
             )
             .await?;
@@ -549,9 +654,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ASSET_WITHDRAWAL_HISTORY,
             )
             .await?;
@@ -571,9 +677,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ASSET_WITHDRAWAL,
             )
             .await?;
@@ -618,9 +725,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body.to_string(),
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ASSET_TRANSFER_STATE,
             )
             .await?;
@@ -653,9 +761,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_CT_CURRENT_LEADTRADERS,
             )
             .await?;
@@ -679,7 +788,7 @@ impl OkxCli {
 
         let mut url = format!(
             "{}{}?instType={}",
-            OKX_BASE_URL, OKX_CT_PUBLIC_LEADTRADERS, inst_type_str,
+            self.endpoints.rest, OKX_CT_PUBLIC_LEADTRADERS, inst_type_str,
         );
 
         if let Some(sort) = query.sort_type {
@@ -713,9 +822,9 @@ impl OkxCli {
             url.push_str(&format!("&limit={}", limit));
         }
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResOkx<RestPubLeadTradersOkx> =
-            parse_json_response("Okx public_lead_traders", response).await?;
+            parse_venue_response(Venue::Okx, "Okx public_lead_traders", response).await?;
 
         let data = res
             .into_vec()?
@@ -746,12 +855,16 @@ impl OkxCli {
 
         let url = format!(
             "{}{}?uniqueCode={}&instType={}&lastDays={}",
-            OKX_BASE_URL, OKX_CT_PUBLIC_LEADTRADER_STATS, unique_code, inst_type_str, last_days,
+            self.endpoints.rest,
+            OKX_CT_PUBLIC_LEADTRADER_STATS,
+            unique_code,
+            inst_type_str,
+            last_days,
         );
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResOkx<RestPubLeadTraderStatsOkx> =
-            parse_json_response("Okx public_lead_trader_stats", response).await?;
+            parse_venue_response(Venue::Okx, "Okx public_lead_trader_stats", response).await?;
 
         res.into_vec()
     }
@@ -774,16 +887,16 @@ impl OkxCli {
 
         let mut url = format!(
             "{}{}?uniqueCode={}&instType={}",
-            OKX_BASE_URL, OKX_CT_LEADTRADER_SUBPOSITIONS, unique_code, inst_type_str,
+            self.endpoints.rest, OKX_CT_LEADTRADER_SUBPOSITIONS, unique_code, inst_type_str,
         );
 
         if let Some(l) = limit {
             url.push_str(&format!("&limit={}", l));
         }
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResOkx<RestSubPositionOkx> =
-            parse_json_response("Okx lead_trader_subpositions", response).await?;
+            parse_venue_response(Venue::Okx, "Okx lead_trader_subpositions", response).await?;
 
         res.into_vec()
     }
@@ -808,7 +921,7 @@ impl OkxCli {
 
         let mut url = format!(
             "{}{}?uniqueCode={}&instType={}",
-            OKX_BASE_URL, OKX_CT_LEADTRADER_SUBPOSITIONS_HISTORY, unique_code, inst_type_str,
+            self.endpoints.rest, OKX_CT_LEADTRADER_SUBPOSITIONS_HISTORY, unique_code, inst_type_str,
         );
 
         if let Some(l) = limit {
@@ -821,9 +934,10 @@ impl OkxCli {
             url.push_str(&format!("&after={}", a));
         }
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResOkx<RestSubPositionHistoryOkx> =
-            parse_json_response("Okx lead_trader_subpositions_history", response).await?;
+            parse_venue_response(Venue::Okx, "Okx lead_trader_subpositions_history", response)
+                .await?;
 
         res.into_vec()
     }
@@ -838,12 +952,12 @@ impl OkxCli {
 
         let url = format!(
             "{}{}?instId={}",
-            OKX_BASE_URL, OKX_PUBLIC_FUNDING_RATE, inst_id
+            self.endpoints.rest, OKX_PUBLIC_FUNDING_RATE, inst_id
         );
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResOkx<RestFundingRateOkx> =
-            parse_json_response("Okx funding_rate_info", response).await?;
+            parse_venue_response(Venue::Okx, "Okx funding_rate_info", response).await?;
 
         let data = res
             .into_vec()?
@@ -864,12 +978,12 @@ impl OkxCli {
 
         let url = format!(
             "{}{}?instId={}",
-            OKX_BASE_URL, OKX_PUBLIC_FUNDING_RATE, inst_id
+            self.endpoints.rest, OKX_PUBLIC_FUNDING_RATE, inst_id
         );
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResOkx<RestFundingRateOkx> =
-            parse_json_response("Okx funding_rate_live", response).await?;
+            parse_venue_response(Venue::Okx, "Okx funding_rate_live", response).await?;
 
         let data = res
             .into_vec()?
@@ -900,14 +1014,14 @@ impl OkxCli {
 
         let url = format!(
             "{}{}?{}",
-            OKX_BASE_URL,
+            self.endpoints.rest,
             OKX_PUBLIC_FUNDING_RATE_HISTORY,
             params.join("&")
         );
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResOkx<RestFundingRateHistoryOkx> =
-            parse_json_response("Okx funding_rate_history", response).await?;
+            parse_venue_response(Venue::Okx, "Okx funding_rate_history", response).await?;
 
         let data = res
             .into_vec()?
@@ -921,14 +1035,14 @@ impl OkxCli {
     pub async fn get_price_limit(&self, inst: &str) -> InfraResult<Vec<RestPriceLimitOkx>> {
         let url = format!(
             "{}{}?instId={}",
-            OKX_BASE_URL,
+            self.endpoints.rest,
             OKX_PUBLIC_PRICE_LIMIT,
             cli_perp_to_okx_inst(inst)
         );
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResOkx<RestPriceLimitOkx> =
-            parse_json_response("Okx price_limit", response).await?;
+            parse_venue_response(Venue::Okx, "Okx price_limit", response).await?;
 
         res.into_vec()
     }
@@ -950,11 +1064,11 @@ impl OkxCli {
 
         let url = format!(
             "{}{}?instType={}",
-            OKX_BASE_URL, OKX_MARKET_TICKERS, inst_type_str
+            self.endpoints.rest, OKX_MARKET_TICKERS, inst_type_str
         );
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResOkx<RestMarketTickerOkx> =
-            parse_json_response("Okx tickers", response).await?;
+            parse_venue_response(Venue::Okx, "Okx tickers", response).await?;
 
         let data = res
             .into_vec()?
@@ -994,13 +1108,14 @@ impl OkxCli {
 
         let url = format!(
             "{}{}?{}",
-            OKX_BASE_URL,
+            self.endpoints.rest,
             OKX_MARKET_CANDLES,
             params.join("&")
         );
 
-        let response = self.client.get(url).send().await?;
-        let res: RestResOkx<RestCandleOkx> = parse_json_response("Okx candles", response).await?;
+        let response = self.retry.send(self.client.get(url)).await?;
+        let res: RestResOkx<RestCandleOkx> =
+            parse_venue_response(Venue::Okx, "Okx candles", response).await?;
 
         let mut data: Vec<CandleData> = res
             .into_vec()?
@@ -1034,10 +1149,15 @@ impl OkxCli {
             params.push(format!("sz={depth}"));
         }
 
-        let url = format!("{}{}?{}", OKX_BASE_URL, OKX_MARKET_BOOKS, params.join("&"));
-        let response = self.client.get(url).send().await?;
+        let url = format!(
+            "{}{}?{}",
+            self.endpoints.rest,
+            OKX_MARKET_BOOKS,
+            params.join("&")
+        );
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResOkx<RestOrderBookOkx> =
-            parse_json_response("Okx orderbook", response).await?;
+            parse_venue_response(Venue::Okx, "Okx orderbook", response).await?;
 
         res.into_vec()?
             .into_iter()
@@ -1062,12 +1182,12 @@ impl OkxCli {
 
         let url = format!(
             "{}{}?&instType={}",
-            OKX_BASE_URL, OKX_PUBLIC_INSTRUMENTS, inst_type_str,
+            self.endpoints.rest, OKX_PUBLIC_INSTRUMENTS, inst_type_str,
         );
 
-        let response = self.client.get(url).send().await?;
+        let response = self.retry.send(self.client.get(url)).await?;
         let res: RestResOkx<RestInstrumentsOkx> =
-            parse_json_response("Okx instrument_info", response).await?;
+            parse_venue_response(Venue::Okx, "Okx instrument_info", response).await?;
 
         let data: Vec<InstrumentInfo> = res
             .into_vec()?
@@ -1078,6 +1198,24 @@ impl OkxCli {
         Ok(data)
     }
 
+    async fn _get_server_time(&self) -> InfraResult<u64> {
+        let url = format!("{}{}", self.endpoints.rest, OKX_PUBLIC_TIME);
+
+        let response = self.retry.send(self.client.get(url)).await?;
+        let res: RestResOkx<RestPublicTimeOkx> =
+            parse_venue_response(Venue::Okx, "Okx server_time", response).await?;
+
+        let data = res
+            .into_vec()?
+            .into_iter()
+            .next()
+            .ok_or(InfraError::ApiCliError(
+                "No OKX server time returned".into(),
+            ))?;
+
+        Ok(data.ts)
+    }
+
     async fn _place_order(&self, order_params: OrderParams) -> InfraResult<OrderAckData> {
         order_params.validate_side_and_type()?;
 
@@ -1138,18 +1276,17 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 body.to_string(),
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_TRADE_ORDER,
             )
             .await?;
 
         let data: OrderAckData = res
-            .into_vec()?
-            .into_iter()
+            .into_ack()?
             .map(OrderAckData::from)
-            .next()
             .ok_or(InfraError::ApiCliError("No order ack data returned".into()))?;
 
         Ok(data)
@@ -1178,9 +1315,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 serde_json::to_string(&orders)?,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_TRADE_BATCH_ORDERS,
             )
             .await?;
@@ -1225,18 +1363,17 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 body.to_string(),
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_TRADE_CANCEL_ORDER,
             )
             .await?;
 
         let data = res
-            .into_vec()?
-            .into_iter()
+            .into_ack()?
             .map(RestOrderAckOkx::into_cancel_ack)
-            .next()
             .ok_or(InfraError::ApiCliError(
                 "No OKX cancel ack data returned".into(),
             ))?;
@@ -1267,9 +1404,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Post,
                 serde_json::to_string(&orders)?,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_TRADE_CANCEL_BATCH_ORDERS,
             )
             .await?;
@@ -1291,6 +1429,77 @@ impl OkxCli {
         Ok(data)
     }
 
+    async fn _amend_order(&self, amend_params: AmendOrderParams) -> InfraResult<OrderAckData> {
+        let body = RestAmendOrderParamsOkx::try_from(&amend_params)?;
+        let res: RestResOkx<RestOrderAckOkx> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Post,
+                serde_json::to_string(&body)?,
+                &self.endpoints,
+                OKX_TRADE_AMEND_ORDER,
+            )
+            .await?;
+
+        let data = res
+            .into_ack()?
+            .map(OrderAckData::from)
+            .ok_or(InfraError::ApiCliError(
+                "No OKX amend ack data returned".into(),
+            ))?;
+
+        Ok(data)
+    }
+
+    async fn _amend_orders(
+        &self,
+        amend_params: Vec<AmendOrderParams>,
+    ) -> InfraResult<Vec<OrderAckData>> {
+        if amend_params.is_empty() {
+            return Ok(Vec::new());
+        }
+        if amend_params.len() > OKX_BATCH_ORDER_LIMIT {
+            return Err(InfraError::ApiCliError(format!(
+                "OKX batch amend supports at most {OKX_BATCH_ORDER_LIMIT} orders"
+            )));
+        }
+
+        let orders = amend_params
+            .iter()
+            .map(RestAmendOrderParamsOkx::try_from)
+            .collect::<InfraResult<Vec<_>>>()?;
+        let res: RestResOkx<RestOrderAckOkx> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Post,
+                serde_json::to_string(&orders)?,
+                &self.endpoints,
+                OKX_TRADE_AMEND_BATCH_ORDERS,
+            )
+            .await?;
+
+        let responds = res.into_batch_vec()?;
+        if responds.len() != amend_params.len() {
+            return Err(InfraError::ApiCliError(format!(
+                "OKX batch amend returned {} result(s) for {} order(s)",
+                responds.len(),
+                amend_params.len()
+            )));
+        }
+
+        let data: Vec<OrderAckData> = responds.into_iter().map(OrderAckData::from).collect();
+
+        Ok(data)
+    }
+
     async fn _get_balance(&self, assets: Option<&[String]>) -> InfraResult<Vec<BalanceData>> {
         let body = match assets {
             Some(ccys) if !ccys.is_empty() => {
@@ -1306,9 +1515,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ACCOUNT_BALANCE,
             )
             .await?;
@@ -1338,9 +1548,10 @@ impl OkxCli {
             .ok_or(InfraError::ApiCliNotInitialized)?
             .send_signed_request(
                 &self.client,
+                &self.retry,
                 RequestMethod::Get,
                 body,
-                OKX_BASE_URL,
+                &self.endpoints,
                 OKX_ACCOUNT_POSITIONS,
             )
             .await?;
@@ -1354,10 +1565,68 @@ impl OkxCli {
         Ok(data)
     }
 
+    async fn _cancel_all_orders(&self, inst: Option<&str>) -> InfraResult<Vec<OrderAckData>> {
+        let open_orders = self
+            ._get_pending_orders(inst.map(cli_perp_to_okx_inst), None)
+            .await?;
+
+        let mut data = Vec::with_capacity(open_orders.len());
+        for batch in open_orders.chunks(OKX_BATCH_ORDER_LIMIT) {
+            let cancel_params = batch
+                .iter()
+                .map(|order| CancelOrderParams {
+                    inst: order.inst.clone(),
+                    order_id: Some(order.order_id.clone()),
+                    ..Default::default()
+                })
+                .collect();
+            data.extend(self._cancel_orders(cancel_params).await?);
+        }
+
+        Ok(data)
+    }
+
+    async fn _set_cancel_after(&self, _inst: Option<&str>, timeout: Duration) -> InfraResult<()> {
+        let secs = timeout.as_secs();
+        if secs != 0 && !(10..=120).contains(&secs) {
+            return Err(InfraError::ApiCliError(
+                "OKX cancel-all-after timeout must be 0 or 10..=120 seconds".into(),
+            ));
+        }
+
+        let body = json!({ "timeOut": secs.to_string() }).to_string();
+        let res: RestResOkx<serde_json::Value> = self
+            .api_key
+            .as_ref()
+            .ok_or(InfraError::ApiCliNotInitialized)?
+            .send_signed_request(
+                &self.client,
+                &self.retry,
+                RequestMethod::Post,
+                body,
+                &self.endpoints,
+                OKX_TRADE_CANCEL_ALL_AFTER,
+            )
+            .await?;
+        res.into_vec()?;
+
+        Ok(())
+    }
+
     async fn _get_open_orders(
         &self,
         inst: &str,
         limit: Option<u32>,
+    ) -> InfraResult<Vec<OrderDetailData>> {
+        self._get_pending_orders(Some(cli_perp_to_okx_inst(inst)), limit)
+            .await
+    }
+
+    /// Pages through pending orders on one OKX instrument, or on all of them.
+    async fn _get_pending_orders(
+        &self,
+        inst_id: Option<String>,
+        limit: Option<u32>,
     ) -> InfraResult<Vec<OrderDetailData>> {
         if limit == Some(0) {
             return Ok(Vec::new());
@@ -1371,7 +1640,7 @@ impl OkxCli {
 
         loop {
             let req = OkxOpenOrdersReq {
-                inst_id: Some(cli_perp_to_okx_inst(inst)),
+                inst_id: inst_id.clone(),
                 after: after.clone(),
                 limit: Some(page_limit),
                 ..Default::default()
@@ -1402,6 +1671,23 @@ impl OkxCli {
         Ok(data)
     }
 
+    async fn _get_order(
+        &self,
+        inst: &str,
+        order_id: Option<&str>,
+        cli_order_id: Option<&str>,
+    ) -> InfraResult<Option<OrderDetailData>> {
+        let raw = self
+            .get_order_raw(OkxOrderReq {
+                inst_id: cli_perp_to_okx_inst(inst),
+                ord_id: order_id.map(Into::into),
+                cl_ord_id: cli_order_id.map(Into::into),
+            })
+            .await?;
+
+        Ok(raw.into_iter().next().map(OrderDetailData::from))
+    }
+
     async fn _get_order_history(
         &self,
         inst: &str,
@@ -1438,16 +1724,18 @@ impl OkxCli {
     fn _get_public_connect_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         let url = match channel {
             WsChannel::Trades(Some(trades_param)) => match trades_param {
-                TradesParam::AggTrades => OKX_WS_PUB,
-                TradesParam::AllTrades => OKX_WS_BUS,
+                TradesParam::AggTrades => &self.endpoints.ws_public,
+                TradesParam::AllTrades => &self.endpoints.ws_business,
+            },
+            WsChannel::Candles(_) => &self.endpoints.ws_business,
+            WsChannel::Lob(_) | WsChannel::Trades(None) => &self.endpoints.ws_public,
+            WsChannel::Other(s) if s == "instruments" || s == "funding-rate" => {
+                &self.endpoints.ws_business
             },
-            WsChannel::Candles(_) => OKX_WS_BUS,
-            WsChannel::Lob(_) | WsChannel::Trades(None) => OKX_WS_PUB,
-            WsChannel::Other(s) if s == "instruments" || s == "funding-rate" => OKX_WS_BUS,
             _ => return Err(InfraError::Unimplemented),
         };
 
-        Ok(url.into())
+        Ok(url.clone())
     }
 
     fn _get_public_sub_msg(
@@ -1538,7 +1826,7 @@ impl OkxCli {
 
     fn _get_private_sub_msg(&self, channel: &WsChannel) -> InfraResult<String> {
         let args = match channel {
-            WsChannel::AccountOrders => {
+            WsChannel::AccountOrders | WsChannel::AccountFills => {
                 vec![json!({
                     "channel": "orders",
                     "instType": "ANY",
@@ -1565,6 +1853,56 @@ impl OkxCli {
 
         Ok(msg.to_string())
     }
+
+    fn _ws_place_orders_msg(&self, id: u64, orders: &[OrderParams]) -> InfraResult<String> {
+        if orders.is_empty() || orders.len() > OKX_BATCH_ORDER_LIMIT {
+            return Err(InfraError::ApiCliError(format!(
+                "OKX websocket orders take 1 to {OKX_BATCH_ORDER_LIMIT} orders"
+            )));
+        }
+
+        let args = orders
+            .iter()
+            .map(RestBatchOrderParamsOkx::try_from)
+            .collect::<InfraResult<Vec<_>>>()?;
+        let op = if args.len() == 1 {
+            "order"
+        } else {
+            "batch-orders"
+        };
+        let msg = json!({
+            "id": id.to_string(),
+            "op": op,
+            "args": args,
+        });
+
+        Ok(msg.to_string())
+    }
+
+    fn _ws_cancel_orders_msg(&self, id: u64, cancels: &[CancelOrderParams]) -> InfraResult<String> {
+        if cancels.is_empty() || cancels.len() > OKX_BATCH_ORDER_LIMIT {
+            return Err(InfraError::ApiCliError(format!(
+                "OKX websocket cancels take 1 to {OKX_BATCH_ORDER_LIMIT} orders"
+            )));
+        }
+
+        let args = cancels
+            .iter()
+            .map(RestBatchCancelOrderParamsOkx::try_from)
+            .collect::<InfraResult<Vec<_>>>()?;
+        let op = if args.len() == 1 {
+            "cancel-order"
+        } else {
+            "batch-cancel-orders"
+        };
+        let msg = json!({
+            "id": id.to_string(),
+            "op": op,
+            "args": args,
+        });
+
+        Ok(msg.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -1668,4 +2006,74 @@ mod tests {
 
         assert!(matches!(err, InfraError::ApiCliError(_)));
     }
+
+    #[tokio::test]
+    async fn demo_profile_connects_websockets_to_demo_hosts() {
+        let cli = OkxCli::default().with_endpoints(EndpointProfile::okx_demo());
+
+        assert_eq!(
+            cli.get_public_connect_msg(&WsChannel::Trades(None))
+                .await
+                .unwrap(),
+            OKX_DEMO_WS_PUB
+        );
+        assert_eq!(
+            cli.get_public_connect_msg(&WsChannel::Candles(None))
+                .await
+                .unwrap(),
+            OKX_DEMO_WS_BUS
+        );
+        assert_eq!(
+            cli.get_private_connect_msg(&WsChannel::AccountOrders)
+                .await
+                .unwrap(),
+            OKX_DEMO_WS_PRI
+        );
+        assert_eq!(
+            cli.get_private_connect_msg(&WsChannel::OrderEntry)
+                .await
+                .unwrap(),
+            OKX_DEMO_WS_PRI
+        );
+    }
+
+    #[test]
+    fn builds_ws_order_entry_requests() {
+        let cli = OkxCli::default();
+        let order = OrderParams {
+            inst: "BTC_USDT_PERP".into(),
+            side: OrderSide::BUY,
+            size: "1".into(),
+            order_type: OrderType::Limit,
+            price: Some("100".into()),
+            ..Default::default()
+        };
+
+        let single: Value = serde_json::from_str(
+            &cli.ws_place_orders_msg(3, std::slice::from_ref(&order))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(single["id"], "3");
+        assert_eq!(single["op"], "order");
+        assert_eq!(single["args"][0]["instId"], "BTC-USDT-SWAP");
+
+        let batch: Value =
+            serde_json::from_str(&cli.ws_place_orders_msg(4, &[order.clone(), order]).unwrap())
+                .unwrap();
+        assert_eq!(batch["op"], "batch-orders");
+        assert_eq!(batch["args"].as_array().unwrap().len(), 2);
+
+        let cancel = CancelOrderParams {
+            inst: "BTC_USDT_PERP".into(),
+            order_id: Some("42".into()),
+            cli_order_id: None,
+        };
+        let cancel: Value =
+            serde_json::from_str(&cli.ws_cancel_orders_msg(5, &[cancel]).unwrap()).unwrap();
+        assert_eq!(cancel["op"], "cancel-order");
+        assert_eq!(cancel["args"][0]["ordId"], "42");
+
+        assert!(cli.ws_place_orders_msg(6, &[]).is_err());
+    }
 }
@@ -1,8 +1,14 @@
 use serde::Deserialize;
 use tracing::warn;
 
-use crate::arch::traits::conversion::IntoInfraVec;
-use crate::errors::{InfraError, InfraResult};
+use crate::arch::{
+    market_assets::exchange::{
+        exchange_error::{ErrorCategory, ExchangeError, Venue},
+        okx::schemas::rest::trade_order::RestOrderAckOkx,
+    },
+    traits::conversion::IntoInfraVec,
+};
+use crate::errors::InfraResult;
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct RestResOkx<T> {
@@ -18,10 +24,7 @@ impl<T: std::fmt::Debug> IntoInfraVec<T> for RestResOkx<T> {
                 "OKX REST error {}: {:?}, data: {:?}",
                 self.code, self.msg, self.data
             );
-            return Err(InfraError::ApiCliError(format!(
-                "OKX REST error (code={}): {:?}",
-                self.code, self.msg
-            )));
+            return Err(ExchangeError::okx(&self.code, self.msg.unwrap_or_default()).into());
         }
 
         Ok(self.data.unwrap_or_default())
@@ -35,12 +38,110 @@ impl<T: std::fmt::Debug> RestResOkx<T> {
                 "OKX REST batch error {}: {:?}, data: {:?}",
                 self.code, self.msg, self.data
             );
-            return Err(InfraError::ApiCliError(format!(
-                "OKX REST batch error (code={}): {:?}",
-                self.code, self.msg
-            )));
+            return Err(ExchangeError::okx(&self.code, self.msg.unwrap_or_default()).into());
         }
 
         Ok(self.data.unwrap_or_default())
     }
 }
+
+impl RestResOkx<RestOrderAckOkx> {
+    /// Ack of a single order request. When it fails, the reason is in the
+    /// order's `sCode` rather than the top-level code.
+    pub fn into_ack(self) -> InfraResult<Option<RestOrderAckOkx>> {
+        if self.code == "1"
+            && let Some(ack) = self.data.as_ref().and_then(|data| data.first())
+            && ack.sCode != "0"
+        {
+            warn!("OKX order rejected {}: {}", ack.sCode, ack.sMsg);
+            return Err(ExchangeError::okx(&ack.sCode, &ack.sMsg).into());
+        }
+
+        Ok(self.into_vec()?.into_iter().next())
+    }
+}
+
+/// Error body OKX sends with non-success HTTP statuses.
+#[derive(Deserialize)]
+struct OkxErrorBody {
+    code: String,
+    msg: Option<String>,
+}
+
+impl ExchangeError {
+    /// OKX rejection with its top-level `code` or per-order `sCode`.
+    pub(crate) fn okx(code: &str, msg: impl Into<String>) -> Self {
+        Self::new(Venue::Okx, okx_category(code), code, msg)
+    }
+
+    pub(crate) fn okx_body(body: &[u8]) -> Option<Self> {
+        let body: OkxErrorBody = serde_json::from_slice(body).ok()?;
+        Some(Self::okx(&body.code, body.msg.unwrap_or_default()))
+    }
+}
+
+fn okx_category(code: &str) -> ErrorCategory {
+    match code {
+        "51008" | "51119" | "51127" | "51131" => ErrorCategory::InsufficientBalance,
+        "50011" | "50061" => ErrorCategory::RateLimited,
+        "51006" | "51116" | "51137" | "51138" => ErrorCategory::InvalidPrice,
+        "51169" | "51170" => ErrorCategory::ReduceOnlyRejected,
+        "51400" | "51401" | "51402" | "51603" => ErrorCategory::UnknownOrder,
+        "50001" | "50013" => ErrorCategory::Maintenance,
+        "50004" => ErrorCategory::Timeout,
+        code if code
+            .parse::<u32>()
+            .is_ok_and(|code| (50100..=50119).contains(&code)) =>
+        {
+            ErrorCategory::AuthFailed
+        },
+        _ => ErrorCategory::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::InfraError;
+
+    fn category(err: InfraError) -> (ErrorCategory, String) {
+        match err {
+            InfraError::Exchange(e) => (e.category, e.code),
+            other => panic!("unexpected error {other:?}"),
+        }
+    }
+
+    #[test]
+    fn maps_top_level_and_order_codes() {
+        let res: RestResOkx<serde_json::Value> =
+            serde_json::from_str(r#"{"code":"50011","msg":"Too Many Requests","data":[]}"#)
+                .unwrap();
+        assert_eq!(
+            category(res.into_vec().unwrap_err()),
+            (ErrorCategory::RateLimited, "50011".into())
+        );
+
+        let res: RestResOkx<RestOrderAckOkx> = serde_json::from_str(
+            r#"{"code":"1","msg":"All operations failed","data":[{"clOrdId":"a","ordId":"","tag":"","ts":"1","sCode":"51008","sMsg":"Insufficient balance"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            category(res.into_ack().unwrap_err()),
+            (ErrorCategory::InsufficientBalance, "51008".into())
+        );
+    }
+
+    #[test]
+    fn maps_auth_code_range() {
+        assert_eq!(okx_category("50113"), ErrorCategory::AuthFailed);
+        assert_eq!(okx_category("50120"), ErrorCategory::Other);
+        assert_eq!(okx_category("501000"), ErrorCategory::Other);
+        assert_eq!(okx_category("50110a"), ErrorCategory::Other);
+        assert_eq!(
+            ExchangeError::okx_body(br#"{"code":"50111","msg":"Invalid OK-ACCESS-KEY"}"#)
+                .unwrap()
+                .category,
+            ErrorCategory::AuthFailed
+        );
+    }
+}
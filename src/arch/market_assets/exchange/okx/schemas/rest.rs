@@ -24,4 +24,5 @@ pub mod order_history;
 pub mod orderbook;
 pub mod price_limit;
 pub mod public_instruments;
+pub mod public_time;
 pub mod trade_order;
@@ -21,6 +21,7 @@ impl RestOrderBookOkx {
             inst: inst.to_string(),
             bids: levels_to_pairs(self.bids),
             asks: levels_to_pairs(self.asks),
+            update_id: None,
         }
     }
 }
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+use crate::arch::market_assets::api_general::de_micros_from_int;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RestPublicTimeOkx {
+    #[serde(deserialize_with = "de_micros_from_int")]
+    pub ts: u64,
+}
@@ -7,7 +7,7 @@ use crate::arch::{
         exchange::okx::api_utils::okx_inst_to_cli,
         market_core::Market,
     },
-    strategy_base::handler::lob_events::WsAccOrder,
+    strategy_base::handler::lob_events::{FillLiquidity, WsAccOrder, WsFill},
     traits::conversion::IntoWsData,
 };
 
@@ -32,9 +32,20 @@ pub(crate) struct WsAccountOrderOkx {
     tradeId: Option<String>,
     fee: Option<String>,
     feeCcy: Option<String>,
+    #[serde(default)]
+    fillFee: Option<String>,
+    #[serde(default)]
+    fillFeeCcy: Option<String>,
+    #[serde(default)]
+    execType: Option<String>,
     uTime: String,
 }
 
+/// Fill view of an OKX `orders` push; updates without a fill yield `None`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct WsFillOkx(WsAccountOrderOkx);
+
 impl IntoWsData for WsAccountOrderOkx {
     type Output = WsAccOrder;
 
@@ -43,12 +54,7 @@ impl IntoWsData for WsAccountOrderOkx {
             timestamp: ts_to_micros(self.uTime.parse().unwrap_or_default()),
             market: Market::Okx,
             inst: okx_inst_to_cli(&self.instId),
-            inst_type: match self.instType.as_str() {
-                "SPOT" => InstrumentType::Spot,
-                "SWAP" => InstrumentType::Perpetual,
-                "OPTION" => InstrumentType::Options,
-                _ => InstrumentType::Unknown,
-            },
+            inst_type: parse_inst_type(&self.instType),
             price: self
                 .px
                 .as_ref()
@@ -61,11 +67,7 @@ impl IntoWsData for WsAccountOrderOkx {
                 .and_then(|sz| sz.parse::<f64>().ok())
                 .unwrap_or(0.0)
                 .abs(),
-            side: match self.side.as_str() {
-                "buy" => OrderSide::BUY,
-                "sell" => OrderSide::SELL,
-                _ => OrderSide::Unknown,
-            },
+            side: parse_side(&self.side),
             status: match self.state.as_str() {
                 "partially_filled" => OrderStatus::PartiallyFilled,
                 "live" => OrderStatus::Live,
@@ -84,6 +86,64 @@ impl IntoWsData for WsAccountOrderOkx {
     }
 }
 
+impl IntoWsData for WsFillOkx {
+    type Output = Option<WsFill>;
+
+    fn into_ws(self) -> Self::Output {
+        let order = self.0;
+        let size = parse_f64(order.fillSz.as_deref()).abs();
+        if size <= 0.0 {
+            return None;
+        }
+
+        let fill_time = order.fillTime.as_deref().and_then(|t| t.parse().ok());
+
+        Some(WsFill {
+            timestamp: ts_to_micros(
+                fill_time.unwrap_or_else(|| order.uTime.parse().unwrap_or_default()),
+            ),
+            market: Market::Okx,
+            inst: okx_inst_to_cli(&order.instId),
+            inst_type: parse_inst_type(&order.instType),
+            side: parse_side(&order.side),
+            price: parse_f64(order.fillPx.as_deref()),
+            size,
+            // OKX reports charged fees as negative amounts.
+            fee: -parse_f64(order.fillFee.as_deref()),
+            fee_currency: order.fillFeeCcy.filter(|ccy| !ccy.is_empty()),
+            liquidity: match order.execType.as_deref() {
+                Some("M") => FillLiquidity::Maker,
+                Some("T") => FillLiquidity::Taker,
+                _ => FillLiquidity::Unknown,
+            },
+            trade_id: order.tradeId.filter(|id| !id.is_empty()),
+            order_id: (!order.ordId.is_empty()).then_some(order.ordId),
+            cli_order_id: (!order.clOrdId.is_empty()).then_some(order.clOrdId),
+        })
+    }
+}
+
+fn parse_inst_type(inst_type: &str) -> InstrumentType {
+    match inst_type {
+        "SPOT" => InstrumentType::Spot,
+        "SWAP" => InstrumentType::Perpetual,
+        "OPTION" => InstrumentType::Options,
+        _ => InstrumentType::Unknown,
+    }
+}
+
+fn parse_side(side: &str) -> OrderSide {
+    match side {
+        "buy" => OrderSide::BUY,
+        "sell" => OrderSide::SELL,
+        _ => OrderSide::Unknown,
+    }
+}
+
+fn parse_f64(value: Option<&str>) -> f64 {
+    value.and_then(|v| v.parse().ok()).unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -122,4 +182,53 @@ mod tests {
         assert_eq!(ws.order_id.as_deref(), Some("2234567890"));
         assert_eq!(ws.cli_order_id.as_deref(), Some("okx-client-id"));
     }
+
+    #[test]
+    fn fill_view_carries_price_fee_and_liquidity() {
+        let order = |fill_sz: &str| {
+            json!({
+                "ordId": "2234567890",
+                "clOrdId": "okx-client-id",
+                "instId": "BTC-USDT-SWAP",
+                "instType": "SWAP",
+                "side": "sell",
+                "posSide": "net",
+                "tdMode": "cross",
+                "ordType": "limit",
+                "state": "partially_filled",
+                "px": "65000",
+                "sz": "3",
+                "fillPx": "65000.5",
+                "fillSz": fill_sz,
+                "fillPnl": "0",
+                "fillTime": "1781905826733",
+                "tradeId": "778",
+                "fee": "-0.6",
+                "feeCcy": "USDT",
+                "fillFee": "-0.2",
+                "fillFeeCcy": "USDT",
+                "execType": "M",
+                "uTime": "1781905826800"
+            })
+        };
+
+        let fill = serde_json::from_value::<WsFillOkx>(order("1"))
+            .unwrap()
+            .into_ws()
+            .unwrap();
+        let live = serde_json::from_value::<WsFillOkx>(order("0"))
+            .unwrap()
+            .into_ws();
+
+        assert_eq!(fill.timestamp, 1_781_905_826_733_000);
+        assert_eq!(fill.side, OrderSide::SELL);
+        assert_eq!(fill.price, 65000.5);
+        assert_eq!(fill.size, 1.0);
+        assert_eq!(fill.fee, 0.2);
+        assert_eq!(fill.fee_currency.as_deref(), Some("USDT"));
+        assert_eq!(fill.liquidity, FillLiquidity::Maker);
+        assert_eq!(fill.trade_id.as_deref(), Some("778"));
+        assert_eq!(fill.cli_order_id.as_deref(), Some("okx-client-id"));
+        assert!(live.is_none());
+    }
 }
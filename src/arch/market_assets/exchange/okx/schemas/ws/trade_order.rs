@@ -0,0 +1,82 @@
+use serde::Deserialize;
+
+use crate::arch::market_assets::{
+    api_data::account_data::{OrderAckData, WsOrderResponse},
+    exchange::{exchange_error::ExchangeError, okx::schemas::rest::trade_order::RestOrderAckOkx},
+};
+
+/// Response to a websocket `order`, `batch-orders`, `cancel-order`, or
+/// `batch-cancel-orders` request.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct WsOrderResOkx {
+    id: String,
+    op: String,
+    code: String,
+    #[serde(default)]
+    msg: String,
+    #[serde(default)]
+    data: Vec<RestOrderAckOkx>,
+}
+
+impl WsOrderResOkx {
+    /// Returns `None` when `id` is not a numeric request id.
+    pub(crate) fn into_response(self) -> Option<WsOrderResponse> {
+        let id = self.id.parse().ok()?;
+        // As on REST batches, codes `1` and `2` carry per-order failures.
+        let result = if matches!(self.code.as_str(), "0" | "1" | "2") && !self.data.is_empty() {
+            let cancel = self.op.contains("cancel");
+            Ok(self
+                .data
+                .into_iter()
+                .map(|ack| {
+                    if cancel {
+                        ack.into_cancel_ack()
+                    } else {
+                        OrderAckData::from(ack)
+                    }
+                })
+                .collect())
+        } else {
+            Err(ExchangeError::okx(&self.code, self.msg).into())
+        };
+
+        Some(WsOrderResponse { id, result })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::market_assets::base_data::OrderStatus;
+
+    #[test]
+    fn maps_order_and_cancel_responses() {
+        let placed: WsOrderResOkx = serde_json::from_str(
+            r#"{"id":"7","op":"batch-orders","code":"2","msg":"","data":[
+                {"clOrdId":"a","ordId":"1","tag":"","ts":"1000","sCode":"0","sMsg":""},
+                {"clOrdId":"b","ordId":"","tag":"","ts":"1000","sCode":"51000","sMsg":"Parameter sz error"}
+            ]}"#,
+        )
+        .unwrap();
+        let placed = placed.into_response().unwrap();
+        assert_eq!(placed.id, 7);
+        let acks = placed.result.unwrap();
+        assert_eq!(acks[0].order_status, OrderStatus::Live);
+        assert_eq!(acks[1].order_status, OrderStatus::Rejected);
+
+        let canceled: WsOrderResOkx = serde_json::from_str(
+            r#"{"id":"8","op":"cancel-order","code":"0","msg":"","data":[
+                {"clOrdId":"a","ordId":"1","tag":"","ts":"1000","sCode":"0","sMsg":""}
+            ]}"#,
+        )
+        .unwrap();
+        let acks = canceled.into_response().unwrap().result.unwrap();
+        assert_eq!(acks[0].order_status, OrderStatus::Canceled);
+
+        let failed: WsOrderResOkx = serde_json::from_str(
+            r#"{"id":"9","op":"order","code":"60013","msg":"Invalid args","data":[]}"#,
+        )
+        .unwrap();
+        assert!(failed.into_response().unwrap().result.is_err());
+    }
+}
@@ -1,5 +1,14 @@
+pub use crate::arch::market_assets::exchange::clock_sync::{ClockEstimate, ClockSync};
+pub use crate::arch::market_assets::exchange::endpoints::EndpointProfile;
+pub use crate::arch::market_assets::exchange::exchange_error::{
+    ErrorCategory, ExchangeError, Venue,
+};
 #[cfg(feature = "lob_clients")]
 pub use crate::arch::market_assets::exchange::lob_clients::LobClients;
+pub use crate::arch::market_assets::exchange::rate_limit::{
+    EndpointRule, HeaderHint, HintKind, LimitPolicy, RateLimitProfile, RateLimiter, RequestPriority,
+};
+pub use crate::arch::market_assets::exchange::retry::RetryPolicy;
 
 #[cfg(feature = "hyperliquid")]
 pub use crate::arch::market_assets::exchange::hyperliquid::{
@@ -0,0 +1,981 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use reqwest::{
+    Client, Method, Request, Response, StatusCode, Url,
+    header::{HeaderMap, RETRY_AFTER},
+};
+use tokio::time::{Duration, Instant, sleep};
+
+use crate::errors::{InfraError, InfraResult};
+
+/// Pause after a 429 without a `Retry-After` header.
+const TOO_MANY_REQUESTS_PAUSE: Duration = Duration::from_secs(1);
+/// Pause after a Binance 418 (IP ban) without a `Retry-After` header.
+const BANNED_PAUSE: Duration = Duration::from_secs(60);
+
+/// Limiters by URL authority, e.g. `fapi.binance.com` or `127.0.0.1:8080`.
+/// `None` marks hosts without a profile, which send unthrottled.
+static LIMITERS: LazyLock<Mutex<HashMap<String, Option<RateLimiter>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// How urgently a request has to go out. Higher priorities may use budget
+/// that lower ones leave in reserve, so a cancel still fits when queries
+/// have used up everything else.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RequestPriority {
+    Query,
+    Trade,
+    Cancel,
+}
+
+/// What a request does when its buckets are out of budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitPolicy {
+    /// Wait however long it takes.
+    Queue,
+    /// Wait up to the given duration, then fail.
+    Delay(Duration),
+    /// Fail immediately with [`InfraError::RateLimited`].
+    FailFast,
+}
+
+/// Who a venue limit applies to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BucketScope {
+    /// Every request to the host, e.g. an IP weight limit.
+    #[default]
+    Host,
+    /// Requests signed with one API key, e.g. Binance order counts or OKX
+    /// per-user trade limits. See [`RateLimitProfile::account_header`].
+    Account,
+}
+
+/// One venue limit: `capacity` units per rolling `window`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BucketSpec {
+    pub name: String,
+    pub capacity: u32,
+    pub window: Duration,
+    pub scope: BucketScope,
+}
+
+impl BucketSpec {
+    pub fn new(name: impl Into<String>, capacity: u32, window: Duration) -> Self {
+        Self {
+            name: name.into(),
+            capacity,
+            window,
+            scope: BucketScope::Host,
+        }
+    }
+
+    pub fn with_scope(mut self, scope: BucketScope) -> Self {
+        self.scope = scope;
+        self
+    }
+}
+
+/// Cost and priority of the requests matching a method and path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EndpointRule {
+    pub method: Option<Method>,
+    pub path: String,
+    /// Whether `path` matches by prefix instead of exactly.
+    pub prefix: bool,
+    /// Text the request body has to contain, e.g. to tell Hyperliquid
+    /// cancels apart from orders on the shared `/exchange` endpoint.
+    pub body_contains: Option<String>,
+    /// Units taken from each named bucket.
+    pub costs: Vec<(String, u32)>,
+    pub priority: RequestPriority,
+}
+
+impl EndpointRule {
+    /// Rule for requests to exactly `path`. Segments in braces, as in
+    /// `/api/v4/futures/{settle}/orders`, match any single segment.
+    pub fn exact(path: impl Into<String>) -> Self {
+        Self {
+            method: None,
+            path: path.into(),
+            prefix: false,
+            body_contains: None,
+            costs: Vec::new(),
+            priority: RequestPriority::Query,
+        }
+    }
+
+    /// Rule for requests to any path starting with `prefix`; an empty prefix
+    /// matches every request.
+    pub fn prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: true,
+            ..Self::exact(prefix)
+        }
+    }
+
+    pub fn with_method(mut self, method: Method) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    pub fn with_body_containing(mut self, text: impl Into<String>) -> Self {
+        self.body_contains = Some(text.into());
+        self
+    }
+
+    pub fn with_cost(mut self, bucket: impl Into<String>, cost: u32) -> Self {
+        self.costs.push((bucket.into(), cost));
+        self
+    }
+
+    pub fn with_priority(mut self, priority: RequestPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    fn matches(&self, method: &Method, path: &str, body: Option<&[u8]>) -> bool {
+        let path_matches = if self.prefix {
+            path.starts_with(&self.path)
+        } else if self.path.contains('{') {
+            let mut segments = path.split('/');
+            self.path.split('/').all(|pattern| {
+                segments.next().is_some_and(|segment| {
+                    segment == pattern || (pattern.starts_with('{') && pattern.ends_with('}'))
+                })
+            }) && segments.next().is_none()
+        } else {
+            path == self.path
+        };
+
+        path_matches
+            && self.method.as_ref().is_none_or(|m| m == method)
+            && self.body_contains.as_ref().is_none_or(|text| {
+                body.is_some_and(|body| {
+                    body.windows(text.len())
+                        .any(|window| window == text.as_bytes())
+                })
+            })
+    }
+}
+
+/// How a response header reports usage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HintKind {
+    /// Units used in the current window, e.g. Binance `X-MBX-USED-WEIGHT-1M`.
+    Used,
+    /// Units left in the current window, e.g. Gate
+    /// `X-Gate-RateLimit-Requests-Remain`.
+    Remaining,
+}
+
+/// Response header the venue reports its own accounting in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeaderHint {
+    pub header: String,
+    /// Bucket the header describes; `None` applies it to every bucket the
+    /// request was charged to.
+    pub bucket: Option<String>,
+    pub kind: HintKind,
+}
+
+impl HeaderHint {
+    pub fn new(header: impl Into<String>, bucket: Option<&str>, kind: HintKind) -> Self {
+        Self {
+            header: header.into(),
+            bucket: bucket.map(Into::into),
+            kind,
+        }
+    }
+}
+
+/// Rate limits of one venue host.
+///
+/// Each exchange module adds presets, e.g. [`RateLimitProfile::binance_um`]
+/// or [`RateLimitProfile::okx`], which clients pick up by host. The first
+/// [`EndpointRule`] matching a request decides its cost and priority;
+/// requests matching none are sent uncharged.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateLimitProfile {
+    pub buckets: Vec<BucketSpec>,
+    pub rules: Vec<EndpointRule>,
+    pub hints: Vec<HeaderHint>,
+    /// Request header carrying the API key, e.g. OKX `OK-ACCESS-KEY`.
+    /// [`BucketScope::Account`] buckets keep a budget per value of it;
+    /// requests without it are not charged to them.
+    pub account_header: Option<String>,
+    pub query_policy: LimitPolicy,
+    pub trade_policy: LimitPolicy,
+    pub cancel_policy: LimitPolicy,
+    /// Share of each bucket kept back per priority step: trades may not use
+    /// the last `reserve`, queries the last `2 * reserve`.
+    pub reserve: f64,
+}
+
+impl Default for RateLimitProfile {
+    fn default() -> Self {
+        Self {
+            buckets: Vec::new(),
+            rules: Vec::new(),
+            hints: Vec::new(),
+            account_header: None,
+            query_policy: LimitPolicy::Delay(Duration::from_secs(5)),
+            trade_policy: LimitPolicy::Delay(Duration::from_secs(2)),
+            cancel_policy: LimitPolicy::Queue,
+            reserve: 0.1,
+        }
+    }
+}
+
+impl RateLimitProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_bucket(mut self, name: impl Into<String>, capacity: u32, window: Duration) -> Self {
+        self.buckets.push(BucketSpec::new(name, capacity, window));
+        self
+    }
+
+    /// Adds a [`BucketScope::Account`] bucket, kept per API key.
+    pub fn with_account_bucket(
+        mut self,
+        name: impl Into<String>,
+        capacity: u32,
+        window: Duration,
+    ) -> Self {
+        self.buckets
+            .push(BucketSpec::new(name, capacity, window).with_scope(BucketScope::Account));
+        self
+    }
+
+    pub fn with_account_header(mut self, header: impl Into<String>) -> Self {
+        self.account_header = Some(header.into());
+        self
+    }
+
+    pub fn with_rule(mut self, rule: EndpointRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn with_hint(mut self, hint: HeaderHint) -> Self {
+        self.hints.push(hint);
+        self
+    }
+
+    pub fn with_policy(mut self, priority: RequestPriority, policy: LimitPolicy) -> Self {
+        match priority {
+            RequestPriority::Query => self.query_policy = policy,
+            RequestPriority::Trade => self.trade_policy = policy,
+            RequestPriority::Cancel => self.cancel_policy = policy,
+        }
+        self
+    }
+
+    pub fn with_reserve(mut self, reserve: f64) -> Self {
+        self.reserve = reserve.clamp(0.0, 0.5);
+        self
+    }
+
+    pub fn policy(&self, priority: RequestPriority) -> LimitPolicy {
+        match priority {
+            RequestPriority::Query => self.query_policy,
+            RequestPriority::Trade => self.trade_policy,
+            RequestPriority::Cancel => self.cancel_policy,
+        }
+    }
+
+    /// Built-in profile for a REST host, if the venue is known. The Binance
+    /// futures testnet serves USDⓈ-M and COIN-M from one host and gets the
+    /// USDⓈ-M profile.
+    pub fn for_host(host: &str) -> Option<Self> {
+        match host {
+            #[cfg(feature = "binance")]
+            "fapi.binance.com" | "testnet.binancefuture.com" => Some(Self::binance_um()),
+            #[cfg(feature = "binance")]
+            "dapi.binance.com" => Some(Self::binance_cm()),
+            #[cfg(feature = "binance")]
+            "api.binance.com" | "api1.binance.com" | "testnet.binance.vision" => {
+                Some(Self::binance_spot())
+            },
+            #[cfg(feature = "okx")]
+            "www.okx.com" => Some(Self::okx()),
+            #[cfg(feature = "gate")]
+            "api.gateio.ws" => Some(Self::gate()),
+            #[cfg(feature = "hyperliquid")]
+            "api.hyperliquid.xyz" | "api.hyperliquid-testnet.xyz" => Some(Self::hyperliquid()),
+            _ => None,
+        }
+    }
+
+    fn rule(&self, method: &Method, path: &str, body: Option<&[u8]>) -> Option<usize> {
+        self.rules
+            .iter()
+            .position(|rule| rule.matches(method, path, body))
+    }
+
+    /// Priority of the first rule matching the request, if any.
+    pub(crate) fn priority(
+        &self,
+        method: &Method,
+        path: &str,
+        body: Option<&[u8]>,
+    ) -> Option<RequestPriority> {
+        self.rule(method, path, body)
+            .map(|index| self.rules[index].priority)
+    }
+
+    fn bucket(&self, name: &str) -> Option<usize> {
+        self.buckets.iter().position(|bucket| bucket.name == name)
+    }
+}
+
+#[derive(Debug)]
+struct BucketState {
+    used: f64,
+    updated: Instant,
+}
+
+/// Budget left in each bucket, drained by requests and refilled
+/// continuously over the bucket's window. Account buckets are kept per API
+/// key in `accounts`, host buckets in `buckets`.
+#[derive(Debug)]
+struct LimiterState {
+    buckets: Vec<BucketState>,
+    accounts: HashMap<String, Vec<BucketState>>,
+    paused_until: Option<Instant>,
+}
+
+impl LimiterState {
+    fn new(profile: &RateLimitProfile, now: Instant) -> Self {
+        Self {
+            buckets: empty_buckets(profile, now),
+            accounts: HashMap::new(),
+            paused_until: None,
+        }
+    }
+
+    fn refill(&mut self, profile: &RateLimitProfile, now: Instant) {
+        let rates: Vec<f64> = profile
+            .buckets
+            .iter()
+            .map(|spec| spec.capacity as f64 / spec.window.as_secs_f64())
+            .collect();
+        for buckets in std::iter::once(&mut self.buckets).chain(self.accounts.values_mut()) {
+            for (rate, bucket) in rates.iter().zip(buckets) {
+                let elapsed = now.saturating_duration_since(bucket.updated);
+                bucket.used = (bucket.used - elapsed.as_secs_f64() * rate).max(0.0);
+                bucket.updated = now;
+            }
+        }
+    }
+
+    /// Bucket `index` as charged for `account`, or `None` for an account
+    /// bucket of a request without an API key.
+    fn bucket_mut(
+        &mut self,
+        profile: &RateLimitProfile,
+        index: usize,
+        account: Option<&str>,
+        now: Instant,
+    ) -> Option<&mut BucketState> {
+        match profile.buckets[index].scope {
+            BucketScope::Host => Some(&mut self.buckets[index]),
+            BucketScope::Account => {
+                let buckets = self
+                    .accounts
+                    .entry(account?.to_string())
+                    .or_insert_with(|| empty_buckets(profile, now));
+                Some(&mut buckets[index])
+            },
+        }
+    }
+
+    /// Charges `rule` if every bucket it draws on has room above the
+    /// priority's reserve, otherwise returns how long until it would.
+    ///
+    /// A cost larger than what the priority may use at all is let through
+    /// once the bucket is otherwise empty.
+    fn try_acquire(
+        &mut self,
+        profile: &RateLimitProfile,
+        rule: &EndpointRule,
+        account: Option<&str>,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        if let Some(until) = self.paused_until {
+            if until > now {
+                return Err(until - now);
+            }
+            self.paused_until = None;
+        }
+
+        self.refill(profile, now);
+        let floor = match rule.priority {
+            RequestPriority::Cancel => 0.0,
+            RequestPriority::Trade => profile.reserve,
+            RequestPriority::Query => 2.0 * profile.reserve,
+        };
+
+        let mut wait = Duration::ZERO;
+        let mut charges = Vec::with_capacity(rule.costs.len());
+        for (name, cost) in &rule.costs {
+            let Some(index) = profile.bucket(name) else {
+                continue;
+            };
+            let spec = &profile.buckets[index];
+            let Some(bucket) = self.bucket_mut(profile, index, account, now) else {
+                continue;
+            };
+            let cost = *cost as f64;
+            let limit = (spec.capacity as f64 * (1.0 - floor)).max(cost);
+            let excess = bucket.used + cost - limit;
+            if excess > 0.0 {
+                let rate = spec.capacity as f64 / spec.window.as_secs_f64();
+                wait = wait.max(Duration::from_secs_f64(excess / rate));
+            }
+            charges.push((index, cost));
+        }
+
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+        for (index, cost) in charges {
+            if let Some(bucket) = self.bucket_mut(profile, index, account, now) {
+                bucket.used += cost;
+            }
+        }
+        Ok(())
+    }
+
+    /// Raises local usage to what the venue reports and pauses after a 429
+    /// or 418. Reports below the local count are ignored, as the local count
+    /// already includes requests still in flight.
+    fn observe(
+        &mut self,
+        profile: &RateLimitProfile,
+        rule: Option<&EndpointRule>,
+        account: Option<&str>,
+        status: StatusCode,
+        headers: &HeaderMap,
+        now: Instant,
+    ) {
+        self.refill(profile, now);
+        for hint in &profile.hints {
+            let Some(value) = headers
+                .get(hint.header.as_str())
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<f64>().ok())
+            else {
+                continue;
+            };
+
+            let indices: Vec<usize> = match &hint.bucket {
+                Some(name) => profile.bucket(name).into_iter().collect(),
+                None => rule
+                    .map(|rule| {
+                        rule.costs
+                            .iter()
+                            .filter_map(|(name, _)| profile.bucket(name))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            };
+            for index in indices {
+                let capacity = profile.buckets[index].capacity as f64;
+                let reported = match hint.kind {
+                    HintKind::Used => value,
+                    HintKind::Remaining => capacity - value,
+                };
+                let Some(bucket) = self.bucket_mut(profile, index, account, now) else {
+                    continue;
+                };
+                bucket.used = bucket.used.max(reported.min(capacity));
+            }
+        }
+
+        let pause = match status {
+            StatusCode::TOO_MANY_REQUESTS => TOO_MANY_REQUESTS_PAUSE,
+            StatusCode::IM_A_TEAPOT => BANNED_PAUSE,
+            _ => return,
+        };
+        let pause = headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map_or(pause, Duration::from_secs);
+        let until = now + pause;
+        self.paused_until = Some(self.paused_until.map_or(until, |paused| paused.max(until)));
+    }
+}
+
+/// Rate limiter shared by every client sending to the same venue host.
+///
+/// Clients look their limiter up by the host of each REST request, so
+/// separate `OkxCli`s or a `BinanceUmCli` and its listen key relay draw on
+/// one IP budget, while [`BucketScope::Account`] buckets are kept apart per
+/// API key. Known hosts get their built-in [`RateLimitProfile`];
+/// [`RateLimiter::install`] replaces it, e.g. for a higher VIP tier, or adds
+/// one for another host such as a mock venue.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    profile: Arc<RateLimitProfile>,
+    state: Arc<Mutex<LimiterState>>,
+}
+
+impl RateLimiter {
+    pub fn new(profile: RateLimitProfile) -> Self {
+        let state = LimiterState::new(&profile, Instant::now());
+        Self {
+            profile: Arc::new(profile),
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Makes every request to `authority` (`host` or `host:port`) go through
+    /// a limiter with `profile`, replacing the one in use.
+    pub fn install(authority: &str, profile: RateLimitProfile) -> Self {
+        let limiter = Self::new(profile);
+        lock(&LIMITERS).insert(authority.to_string(), Some(limiter.clone()));
+        limiter
+    }
+
+    /// Sends requests to `authority` unthrottled.
+    pub fn uninstall(authority: &str) {
+        lock(&LIMITERS).insert(authority.to_string(), None);
+    }
+
+    /// Limiter for requests to `url`, created from the built-in profile of
+    /// its host on first use.
+    pub fn shared(url: &Url) -> Option<Self> {
+        let authority = url.authority();
+        lock(&LIMITERS)
+            .entry(authority.to_string())
+            .or_insert_with(|| {
+                url.host_str()
+                    .and_then(RateLimitProfile::for_host)
+                    .map(Self::new)
+            })
+            .clone()
+    }
+
+    pub fn profile(&self) -> &RateLimitProfile {
+        &self.profile
+    }
+
+    /// Waits until the request fits its buckets, as long as its priority's
+    /// policy allows, and charges it.
+    ///
+    /// Returns the rule the request matched, for [`RateLimiter::observe`].
+    pub(crate) async fn acquire(
+        &self,
+        method: &Method,
+        path: &str,
+        body: Option<&[u8]>,
+        account: Option<&str>,
+    ) -> InfraResult<Option<usize>> {
+        let Some(index) = self.profile.rule(method, path, body) else {
+            return Ok(None);
+        };
+        let rule = &self.profile.rules[index];
+        let policy = self.profile.policy(rule.priority);
+
+        let started = Instant::now();
+        loop {
+            let now = Instant::now();
+            let wait = match lock(&self.state).try_acquire(&self.profile, rule, account, now) {
+                Ok(()) => return Ok(Some(index)),
+                Err(wait) => wait,
+            };
+
+            let allowed = match policy {
+                LimitPolicy::Queue => true,
+                LimitPolicy::Delay(max) => now - started + wait <= max,
+                LimitPolicy::FailFast => false,
+            };
+            if !allowed {
+                return Err(InfraError::RateLimited(format!(
+                    "{method} {path} would wait {wait:?}"
+                )));
+            }
+            sleep(wait).await;
+        }
+    }
+
+    /// Applies the venue's usage headers and 429/418 backoff from `response`.
+    pub(crate) fn observe(&self, rule: Option<usize>, account: Option<&str>, response: &Response) {
+        let rule = rule.map(|index| &self.profile.rules[index]);
+        lock(&self.state).observe(
+            &self.profile,
+            rule,
+            account,
+            response.status(),
+            response.headers(),
+            Instant::now(),
+        );
+    }
+}
+
+fn empty_buckets(profile: &RateLimitProfile, now: Instant) -> Vec<BucketState> {
+    profile
+        .buckets
+        .iter()
+        .map(|_| BucketState {
+            used: 0.0,
+            updated: now,
+        })
+        .collect()
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Sends `request` through the [`RateLimiter`] of its host, if any.
+pub(crate) async fn send_limited(
+    client: &Client,
+    request: Request,
+    limiter: Option<&RateLimiter>,
+) -> InfraResult<Response> {
+    let Some(limiter) = limiter else {
+        return Ok(client.execute(request).await?);
+    };
+
+    let body = request.body().and_then(|body| body.as_bytes());
+    let account = limiter
+        .profile
+        .account_header
+        .as_ref()
+        .and_then(|header| request.headers().get(header.as_str()))
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let rule = limiter
+        .acquire(
+            request.method(),
+            request.url().path(),
+            body,
+            account.as_deref(),
+        )
+        .await?;
+    let response = client.execute(request).await?;
+    limiter.observe(rule, account.as_deref(), &response);
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn profile() -> RateLimitProfile {
+        RateLimitProfile::new()
+            .with_bucket("weight", 100, Duration::from_secs(10))
+            .with_rule(
+                EndpointRule::exact("/order")
+                    .with_method(Method::DELETE)
+                    .with_cost("weight", 1)
+                    .with_priority(RequestPriority::Cancel),
+            )
+            .with_rule(
+                EndpointRule::exact("/order")
+                    .with_cost("weight", 1)
+                    .with_priority(RequestPriority::Trade),
+            )
+            .with_rule(EndpointRule::exact("/depth").with_cost("weight", 150))
+            .with_rule(EndpointRule::prefix("").with_cost("weight", 10))
+            .with_hint(HeaderHint::new("x-used", Some("weight"), HintKind::Used))
+    }
+
+    fn rule<'a>(profile: &'a RateLimitProfile, method: Method, path: &str) -> &'a EndpointRule {
+        &profile.rules[profile.rule(&method, path, None).unwrap()]
+    }
+
+    #[test]
+    fn first_matching_rule_decides_cost_and_priority() {
+        let profile = profile();
+        assert_eq!(
+            rule(&profile, Method::DELETE, "/order").priority,
+            RequestPriority::Cancel
+        );
+        assert_eq!(
+            rule(&profile, Method::POST, "/order").priority,
+            RequestPriority::Trade
+        );
+        assert_eq!(rule(&profile, Method::GET, "/orders").costs[0].1, 10);
+
+        let templated = EndpointRule::exact("/futures/{settle}/orders/{order_id}");
+        assert!(templated.matches(&Method::DELETE, "/futures/usdt/orders/42", None));
+        assert!(!templated.matches(&Method::DELETE, "/futures/usdt/orders", None));
+        assert!(!templated.matches(&Method::DELETE, "/futures/usdt/orders/42/x", None));
+
+        let cancel = EndpointRule::exact("/exchange").with_body_containing(r#""type":"cancel"#);
+        assert!(cancel.matches(
+            &Method::POST,
+            "/exchange",
+            Some(br#"{"action":{"type":"cancel","cancels":[]}}"#)
+        ));
+        assert!(!cancel.matches(
+            &Method::POST,
+            "/exchange",
+            Some(br#"{"action":{"type":"order"}}"#)
+        ));
+        assert!(!cancel.matches(&Method::POST, "/exchange", None));
+    }
+
+    #[test]
+    fn reserves_budget_for_higher_priorities() {
+        let profile = profile();
+        let start = Instant::now();
+        let mut state = LimiterState::new(&profile, start);
+
+        for _ in 0..8 {
+            state
+                .try_acquire(
+                    &profile,
+                    rule(&profile, Method::GET, "/ticker"),
+                    None,
+                    start,
+                )
+                .unwrap();
+        }
+        let wait = state
+            .try_acquire(
+                &profile,
+                rule(&profile, Method::GET, "/ticker"),
+                None,
+                start,
+            )
+            .unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+
+        for _ in 0..10 {
+            state
+                .try_acquire(
+                    &profile,
+                    rule(&profile, Method::POST, "/order"),
+                    None,
+                    start,
+                )
+                .unwrap();
+        }
+        assert!(
+            state
+                .try_acquire(
+                    &profile,
+                    rule(&profile, Method::POST, "/order"),
+                    None,
+                    start
+                )
+                .is_err()
+        );
+        for _ in 0..10 {
+            state
+                .try_acquire(
+                    &profile,
+                    rule(&profile, Method::DELETE, "/order"),
+                    None,
+                    start,
+                )
+                .unwrap();
+        }
+        assert!(
+            state
+                .try_acquire(
+                    &profile,
+                    rule(&profile, Method::DELETE, "/order"),
+                    None,
+                    start
+                )
+                .is_err()
+        );
+
+        let later = start + Duration::from_secs(1);
+        state
+            .try_acquire(
+                &profile,
+                rule(&profile, Method::DELETE, "/order"),
+                None,
+                later,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn oversized_costs_wait_for_an_empty_bucket() {
+        let profile = profile();
+        let start = Instant::now();
+        let mut state = LimiterState::new(&profile, start);
+
+        state
+            .try_acquire(
+                &profile,
+                rule(&profile, Method::GET, "/ticker"),
+                None,
+                start,
+            )
+            .unwrap();
+        assert_eq!(
+            state.try_acquire(&profile, rule(&profile, Method::GET, "/depth"), None, start),
+            Err(Duration::from_secs(1))
+        );
+        state
+            .try_acquire(
+                &profile,
+                rule(&profile, Method::GET, "/depth"),
+                None,
+                start + Duration::from_secs(1),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn server_hints_raise_usage_and_errors_pause_sending() {
+        let profile = profile().with_hint(HeaderHint::new("x-remain", None, HintKind::Remaining));
+        let start = Instant::now();
+        let mut state = LimiterState::new(&profile, start);
+        let ticker = rule(&profile, Method::GET, "/ticker");
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-used", HeaderValue::from_static("75"));
+        state.observe(
+            &profile,
+            Some(ticker),
+            None,
+            StatusCode::OK,
+            &headers,
+            start,
+        );
+        assert_eq!(state.buckets[0].used, 75.0);
+
+        headers.insert("x-used", HeaderValue::from_static("5"));
+        headers.insert("x-remain", HeaderValue::from_static("10"));
+        state.observe(
+            &profile,
+            Some(ticker),
+            None,
+            StatusCode::OK,
+            &headers,
+            start,
+        );
+        assert_eq!(state.buckets[0].used, 90.0);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        state.observe(
+            &profile,
+            None,
+            None,
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers,
+            start,
+        );
+        assert_eq!(
+            state.try_acquire(
+                &profile,
+                rule(&profile, Method::DELETE, "/order"),
+                None,
+                start
+            ),
+            Err(Duration::from_secs(3))
+        );
+
+        state.observe(
+            &profile,
+            None,
+            None,
+            StatusCode::IM_A_TEAPOT,
+            &HeaderMap::new(),
+            start,
+        );
+        assert_eq!(state.paused_until, Some(start + BANNED_PAUSE));
+    }
+
+    #[test]
+    fn account_buckets_are_kept_per_api_key() {
+        let profile = RateLimitProfile::new()
+            .with_bucket("weight", 100, Duration::from_secs(10))
+            .with_account_bucket("orders", 2, Duration::from_secs(10))
+            .with_account_header("x-key")
+            .with_hint(HeaderHint::new("x-used", Some("orders"), HintKind::Used))
+            .with_rule(
+                EndpointRule::exact("/order")
+                    .with_cost("weight", 1)
+                    .with_cost("orders", 1)
+                    .with_priority(RequestPriority::Cancel),
+            );
+        let start = Instant::now();
+        let mut state = LimiterState::new(&profile, start);
+        let order = rule(&profile, Method::POST, "/order");
+
+        for _ in 0..2 {
+            state
+                .try_acquire(&profile, order, Some("a"), start)
+                .unwrap();
+        }
+        assert!(
+            state
+                .try_acquire(&profile, order, Some("a"), start)
+                .is_err()
+        );
+        state
+            .try_acquire(&profile, order, Some("b"), start)
+            .unwrap();
+        state.try_acquire(&profile, order, None, start).unwrap();
+        assert_eq!(state.buckets[0].used, 4.0);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-used", HeaderValue::from_static("2"));
+        state.observe(
+            &profile,
+            Some(order),
+            Some("c"),
+            StatusCode::OK,
+            &headers,
+            start,
+        );
+        assert!(
+            state
+                .try_acquire(&profile, order, Some("c"), start)
+                .is_err()
+        );
+        state
+            .try_acquire(&profile, order, Some("b"), start)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn policies_decide_between_waiting_and_failing() {
+        let limiter = RateLimiter::new(
+            profile()
+                .with_policy(RequestPriority::Query, LimitPolicy::FailFast)
+                .with_policy(RequestPriority::Trade, LimitPolicy::Delay(Duration::ZERO)),
+        );
+        for _ in 0..8 {
+            limiter
+                .acquire(&Method::GET, "/ticker", None, None)
+                .await
+                .unwrap();
+        }
+        assert!(matches!(
+            limiter.acquire(&Method::GET, "/ticker", None, None).await,
+            Err(InfraError::RateLimited(_))
+        ));
+
+        for _ in 0..10 {
+            limiter
+                .acquire(&Method::POST, "/order", None, None)
+                .await
+                .unwrap();
+        }
+        assert!(matches!(
+            limiter.acquire(&Method::POST, "/order", None, None).await,
+            Err(InfraError::RateLimited(_))
+        ));
+    }
+}
@@ -0,0 +1,494 @@
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use tokio::time::{Duration, sleep};
+use tracing::warn;
+
+use crate::arch::{
+    market_assets::{
+        api_data::account_data::{OrderAckData, OrderDetailData},
+        api_general::OrderParams,
+        exchange::{
+            exchange_error::ErrorCategory,
+            rate_limit::{RateLimiter, RequestPriority, send_limited},
+        },
+    },
+    traits::market_lob::LobPrivateRest,
+};
+use crate::errors::{InfraError, InfraResult};
+
+/// Responses a query or cancel is sent again after.
+const RETRY_STATUSES: [StatusCode; 5] = [
+    StatusCode::TOO_MANY_REQUESTS,
+    StatusCode::INTERNAL_SERVER_ERROR,
+    StatusCode::BAD_GATEWAY,
+    StatusCode::SERVICE_UNAVAILABLE,
+    StatusCode::GATEWAY_TIMEOUT,
+];
+
+/// How an exchange client times out and retries its REST requests.
+///
+/// Requests fall in the classes of [`RequestPriority`]: the host's
+/// [`RateLimiter`] profile decides the class, and hosts without one go by
+/// method (`GET` queries, `DELETE` cancels, anything else trades). Queries
+/// and cancels are sent again after transport errors and 429/5xx responses.
+/// Trades are only sent again when the connection never opened, as the
+/// venue may have acted on anything else. Each attempt is built and signed
+/// anew.
+///
+/// With [`RetryPolicy::with_idempotent_orders`], `place_order` looks an
+/// order up by client order id after a timeout or 5xx, polling with the
+/// same backoff, before placing it again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts after the first one; `0` disables retries.
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for each further one.
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    pub query_timeout: Duration,
+    pub trade_timeout: Duration,
+    pub cancel_timeout: Duration,
+    /// Whether `place_order` reconciles orders with a client order id
+    /// after an unknown outcome instead of returning the error.
+    pub idempotent_orders: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            query_timeout: Duration::from_secs(10),
+            trade_timeout: Duration::from_secs(5),
+            cancel_timeout: Duration::from_secs(5),
+            idempotent_orders: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Waits `initial` before the first retry, doubling up to `max`.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Sets the timeout of each attempt of requests in `class`.
+    pub fn with_timeout(mut self, class: RequestPriority, timeout: Duration) -> Self {
+        match class {
+            RequestPriority::Query => self.query_timeout = timeout,
+            RequestPriority::Trade => self.trade_timeout = timeout,
+            RequestPriority::Cancel => self.cancel_timeout = timeout,
+        }
+        self
+    }
+
+    pub fn with_idempotent_orders(mut self, idempotent_orders: bool) -> Self {
+        self.idempotent_orders = idempotent_orders;
+        self
+    }
+
+    pub fn timeout(&self, class: RequestPriority) -> Duration {
+        match class {
+            RequestPriority::Query => self.query_timeout,
+            RequestPriority::Trade => self.trade_timeout,
+            RequestPriority::Cancel => self.cancel_timeout,
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+
+    /// Sends `request`, resending a copy of it on retries. For requests
+    /// without a signature.
+    pub(crate) async fn send(&self, request: RequestBuilder) -> InfraResult<Response> {
+        self.send_with(|| {
+            request.try_clone().ok_or_else(|| {
+                InfraError::ApiCliError("Streaming request bodies cannot be retried".into())
+            })
+        })
+        .await
+    }
+
+    /// Sends the request `build` returns, calling it again for every retry
+    /// so signed requests carry a fresh timestamp.
+    pub(crate) async fn send_with<F>(&self, mut build: F) -> InfraResult<Response>
+    where
+        F: FnMut() -> InfraResult<RequestBuilder>,
+    {
+        let mut attempt = 0;
+        loop {
+            let (client, request) = build()?.build_split();
+            let mut request = request?;
+            let limiter = RateLimiter::shared(request.url());
+            let body = request.body().and_then(|body| body.as_bytes());
+            let class = limiter
+                .as_ref()
+                .and_then(|limiter| {
+                    limiter
+                        .profile()
+                        .priority(request.method(), request.url().path(), body)
+                })
+                .unwrap_or_else(|| method_class(request.method()));
+            *request.timeout_mut() = Some(self.timeout(class));
+
+            let method = request.method().clone();
+            let path = request.url().path().to_string();
+            let result = send_limited(&client, request, limiter.as_ref()).await;
+            if attempt >= self.max_retries || !should_retry(class, &result) {
+                return result;
+            }
+
+            let wait = self.backoff(attempt);
+            match &result {
+                Ok(response) => warn!(
+                    "{method} {path} returned {}, retrying in {wait:?}",
+                    response.status()
+                ),
+                Err(e) => warn!("{method} {path} failed: {e}, retrying in {wait:?}"),
+            }
+            sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
+    /// Places `order` with `place`. In idempotent mode an order with a client
+    /// order id whose outcome is unknown is looked up on `cli`, and only
+    /// placed again when the venue has no record of it.
+    pub(crate) async fn place_order<C, F, Fut>(
+        &self,
+        cli: &C,
+        order: OrderParams,
+        place: F,
+    ) -> InfraResult<OrderAckData>
+    where
+        C: LobPrivateRest,
+        F: Fn(OrderParams) -> Fut,
+        Fut: Future<Output = InfraResult<OrderAckData>>,
+    {
+        let Some(cli_order_id) = order
+            .client_order_id
+            .clone()
+            .filter(|_| self.idempotent_orders)
+        else {
+            return place(order).await;
+        };
+
+        let mut attempt = 0;
+        loop {
+            let error = match place(order.clone()).await {
+                Err(e) if outcome_unknown(&e) => e,
+                result => return result,
+            };
+
+            match self.find_order(cli, &order.inst, &cli_order_id).await {
+                Ok(Some(ack)) => {
+                    warn!("Order {cli_order_id} landed despite {error}");
+                    return Ok(ack);
+                },
+                Ok(None) if attempt < self.max_retries => {
+                    warn!("Order {cli_order_id} not found after {error}, placing it again");
+                },
+                Ok(None) => return Err(error),
+                Err(lookup) => {
+                    warn!("Order {cli_order_id} lookup failed: {lookup}");
+                    return Err(error);
+                },
+            }
+
+            sleep(self.backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Looks up the order on `inst` with `cli_order_id`, asking again after
+    /// each backoff while the venue has no record of it, as an order that
+    /// landed can take a moment to show up in queries.
+    async fn find_order<C: LobPrivateRest>(
+        &self,
+        cli: &C,
+        inst: &str,
+        cli_order_id: &str,
+    ) -> InfraResult<Option<OrderAckData>> {
+        let mut lookup = 0;
+        loop {
+            match cli.get_order(inst, None, Some(cli_order_id)).await {
+                Ok(Some(order)) => return Ok(Some(order_ack(order))),
+                Ok(None) => {},
+                Err(e) if e.category() == Some(ErrorCategory::UnknownOrder) => {},
+                Err(e) => return Err(e),
+            }
+            if lookup >= self.max_retries {
+                return Ok(None);
+            }
+            sleep(self.backoff(lookup)).await;
+            lookup += 1;
+        }
+    }
+}
+
+/// Class of a request to a host without a [`RateLimiter`] profile.
+fn method_class(method: &Method) -> RequestPriority {
+    match *method {
+        Method::GET | Method::HEAD => RequestPriority::Query,
+        Method::DELETE => RequestPriority::Cancel,
+        _ => RequestPriority::Trade,
+    }
+}
+
+fn should_retry(class: RequestPriority, result: &InfraResult<Response>) -> bool {
+    let resendable = class != RequestPriority::Trade;
+    match result {
+        Ok(response) => resendable && RETRY_STATUSES.contains(&response.status()),
+        // A connection that never opened carried nothing to the venue.
+        Err(InfraError::RestApi(e)) if e.is_connect() => true,
+        Err(InfraError::RestApi(e)) => resendable && (e.is_timeout() || e.is_request()),
+        Err(_) => false,
+    }
+}
+
+/// Whether the venue may have acted on a request that failed with `error`.
+fn outcome_unknown(error: &InfraError) -> bool {
+    match error {
+        InfraError::RestApi(e) => !e.is_connect() && !e.is_builder(),
+        InfraError::Exchange(e) => e.status.is_some_and(|status| status >= 500),
+        _ => false,
+    }
+}
+
+fn order_ack(order: OrderDetailData) -> OrderAckData {
+    OrderAckData {
+        timestamp: order.timestamp,
+        order_status: order.order_status,
+        order_id: order.order_id,
+        cli_order_id: order.cli_order_id,
+        msg: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use reqwest::Client;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+    use crate::arch::market_assets::{
+        base_data::OrderStatus,
+        exchange::exchange_error::{ErrorCategory, ExchangeError, Venue},
+    };
+
+    /// Answers each connection with the next of `statuses`.
+    async fn serve(statuses: Vec<u16>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = [0; 4096];
+                let _ = stream.read(&mut buf).await.unwrap();
+                let reply = format!(
+                    "HTTP/1.1 {status} X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                );
+                stream.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{addr}/")
+    }
+
+    fn fast() -> RetryPolicy {
+        RetryPolicy::new().with_backoff(Duration::from_millis(1), Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn queries_retry_server_errors_but_trades_do_not() {
+        let client = Client::new();
+
+        let url = serve(vec![503, 200]).await;
+        let response = fast().send(client.get(&url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let url = serve(vec![503, 200]).await;
+        let response = fast().send(client.post(&url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let url = serve(vec![503, 503]).await;
+        let response = fast()
+            .with_max_retries(1)
+            .send(client.delete(&url))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = RetryPolicy::new()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(300))
+            .with_timeout(RequestPriority::Cancel, Duration::from_secs(2));
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(5), Duration::from_millis(300));
+        assert_eq!(
+            policy.timeout(RequestPriority::Cancel),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            policy.timeout(RequestPriority::Query),
+            Duration::from_secs(10)
+        );
+    }
+
+    /// Venue holding the orders placed on it so far.
+    #[derive(Default)]
+    struct Book {
+        open: Mutex<Vec<OrderDetailData>>,
+        placed: AtomicUsize,
+        /// Lookups that miss before a placed order shows up.
+        lag: usize,
+        lookups: AtomicUsize,
+    }
+
+    impl Book {
+        fn lagging(lag: usize) -> Self {
+            Self {
+                lag,
+                ..Default::default()
+            }
+        }
+
+        /// Places `order`, failing the first attempt with an HTTP 503 after
+        /// the order landed when `lost_reply`, or before it when not.
+        async fn place(&self, order: OrderParams, lost_reply: bool) -> InfraResult<OrderAckData> {
+            let first = self.placed.fetch_add(1, Ordering::SeqCst) == 0;
+            if first && !lost_reply {
+                return Err(unavailable());
+            }
+
+            let detail = OrderDetailData {
+                order_id: "1".into(),
+                cli_order_id: order.client_order_id,
+                order_status: OrderStatus::Live,
+                ..Default::default()
+            };
+            self.open.lock().unwrap().push(detail.clone());
+            if first {
+                return Err(unavailable());
+            }
+            Ok(order_ack(detail))
+        }
+    }
+
+    fn unavailable() -> InfraError {
+        ExchangeError::new(Venue::Okx, ErrorCategory::Maintenance, "50001", "busy")
+            .with_status(StatusCode::SERVICE_UNAVAILABLE)
+            .into()
+    }
+
+    fn unknown_order() -> InfraError {
+        ExchangeError::new(Venue::Okx, ErrorCategory::UnknownOrder, "51603", "gone").into()
+    }
+
+    impl LobPrivateRest for Book {
+        fn init_api_key(&mut self) {}
+
+        async fn get_order(
+            &self,
+            _inst: &str,
+            _order_id: Option<&str>,
+            cli_order_id: Option<&str>,
+        ) -> InfraResult<Option<OrderDetailData>> {
+            if self.lookups.fetch_add(1, Ordering::SeqCst) < self.lag {
+                return Err(unknown_order());
+            }
+            Ok(self
+                .open
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|order| order.cli_order_id.as_deref() == cli_order_id)
+                .cloned())
+        }
+    }
+
+    fn order() -> OrderParams {
+        OrderParams {
+            inst: "BTC_USDT_PERP".into(),
+            client_order_id: Some("abc".into()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn idempotent_orders_are_reconciled_before_resending() {
+        let policy = fast().with_idempotent_orders(true);
+
+        let landed = Book::default();
+        let ack = policy
+            .place_order(&landed, order(), |order| landed.place(order, true))
+            .await
+            .unwrap();
+        assert_eq!(ack.cli_order_id.as_deref(), Some("abc"));
+        assert_eq!(landed.placed.load(Ordering::SeqCst), 1);
+        assert_eq!(landed.open.lock().unwrap().len(), 1);
+
+        let lost = Book::default();
+        policy
+            .place_order(&lost, order(), |order| lost.place(order, false))
+            .await
+            .unwrap();
+        assert_eq!(lost.placed.load(Ordering::SeqCst), 2);
+        assert_eq!(lost.open.lock().unwrap().len(), 1);
+
+        let plain = Book::default();
+        let error = fast()
+            .place_order(&plain, order(), |order| plain.place(order, false))
+            .await
+            .unwrap_err();
+        assert_eq!(error.category(), Some(ErrorCategory::Maintenance));
+        assert_eq!(plain.placed.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn lookups_are_polled_before_an_order_counts_as_absent() {
+        let policy = fast().with_idempotent_orders(true).with_max_retries(2);
+
+        // Shows up on the last lookup the policy allows.
+        let slow = Book::lagging(2);
+        let ack = policy
+            .place_order(&slow, order(), |order| slow.place(order, true))
+            .await
+            .unwrap();
+        assert_eq!(ack.order_id, "1");
+        assert_eq!(slow.placed.load(Ordering::SeqCst), 1);
+        assert_eq!(slow.lookups.load(Ordering::SeqCst), 3);
+
+        let late = Book::lagging(3);
+        policy
+            .place_order(&late, order(), |order| late.place(order, true))
+            .await
+            .unwrap();
+        assert_eq!(late.placed.load(Ordering::SeqCst), 2);
+        assert_eq!(late.open.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,594 @@
+//! Cached instrument metadata and order normalization.
+//!
+//! [`InstrumentRegistry`] keeps the [`InstrumentInfo`] of every instrument
+//! loaded from the configured exchange clients, keyed by [`InstrumentKey`].
+//! Each refresh of a market and instrument type replaces its previous
+//! snapshot and reports listings, removals, and status changes as
+//! [`InstrumentChange`]s, which are also broadcast to
+//! [`InstrumentRegistry::subscribe`]rs.
+//!
+//! [`InstrumentRegistry::normalize`] snaps an order to the venue grid: prices
+//! round to the tick away from the market (down for buys, up for sells),
+//! sizes round down to the lot, and reduce-only sizes round up so they still
+//! close the position. Orders that end up below the venue minimums are
+//! rejected with a [`RiskRejection`] instead of being sent.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+#[cfg(feature = "lob_clients")]
+use std::time::Duration;
+use tokio::sync::broadcast;
+#[cfg(feature = "lob_clients")]
+use tokio::{task::JoinHandle, time::interval};
+#[cfg(feature = "lob_clients")]
+use tracing::{info, warn};
+
+#[cfg(feature = "lob_clients")]
+use crate::arch::market_assets::exchange::lob_clients::LobClients;
+use crate::arch::{
+    market_assets::{
+        api_data::utils_data::InstrumentInfo,
+        api_general::{OrderParams, normalize_to_string, normalize_to_string_reduce_only},
+        base_data::{InstrumentKey, InstrumentStatus, InstrumentType, OrderSide, OrderType},
+        market_core::Market,
+    },
+    strategy_base::risk::{RiskRejection, is_tradable},
+    traits::market_lob::LobPublicRest,
+};
+use crate::errors::{InfraError, InfraResult};
+
+const CHANGE_CHANNEL_CAPACITY: usize = 1_024;
+
+/// Listing, removal, or status change seen by a refresh.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstrumentChange {
+    pub key: InstrumentKey,
+    /// Status before the refresh, `None` for a new listing.
+    pub previous: Option<InstrumentStatus>,
+    /// Status after the refresh, `None` once the venue stopped listing it.
+    pub current: Option<InstrumentStatus>,
+}
+
+impl InstrumentChange {
+    pub fn is_listing(&self) -> bool {
+        self.previous.is_none()
+    }
+
+    pub fn is_removal(&self) -> bool {
+        self.current.is_none()
+    }
+}
+
+/// Shared cache of venue instrument metadata.
+///
+/// Clones share the same cache and change channel.
+#[derive(Clone, Debug)]
+pub struct InstrumentRegistry {
+    instruments: Arc<RwLock<HashMap<InstrumentKey, InstrumentInfo>>>,
+    changes: broadcast::Sender<InstrumentChange>,
+    #[cfg(feature = "lob_clients")]
+    sources: Vec<InstrumentSource>,
+}
+
+#[cfg(feature = "lob_clients")]
+#[derive(Clone, Debug)]
+struct InstrumentSource {
+    market: Market,
+    client: LobClients,
+    inst_types: Vec<InstrumentType>,
+}
+
+impl Default for InstrumentRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InstrumentRegistry {
+    pub fn new() -> Self {
+        Self {
+            instruments: Default::default(),
+            changes: broadcast::channel(CHANGE_CHANNEL_CAPACITY).0,
+            #[cfg(feature = "lob_clients")]
+            sources: Vec::new(),
+        }
+    }
+
+    /// Builds the key instruments are stored under.
+    pub fn key(market: Market, inst_type: InstrumentType, inst: &str) -> InstrumentKey {
+        InstrumentKey {
+            market: Some(market),
+            inst_type,
+            inst: inst.to_string(),
+            extra: None,
+        }
+    }
+
+    /// Receives every change applied after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<InstrumentChange> {
+        self.changes.subscribe()
+    }
+
+    pub fn get(&self, key: &InstrumentKey) -> Option<InstrumentInfo> {
+        self.read().get(key).cloned()
+    }
+
+    /// Looks an instrument up by market and name, for callers that only hold
+    /// [`OrderParams`].
+    pub fn find(&self, market: &Market, inst: &str) -> Option<InstrumentInfo> {
+        self.read()
+            .iter()
+            .find(|(key, _)| key.market.as_ref() == Some(market) && key.inst == inst)
+            .map(|(_, info)| info.clone())
+    }
+
+    /// Every cached instrument of `market`.
+    pub fn instruments(&self, market: &Market) -> Vec<InstrumentInfo> {
+        let mut infos: Vec<_> = self
+            .read()
+            .iter()
+            .filter(|(key, _)| key.market.as_ref() == Some(market))
+            .map(|(_, info)| info.clone())
+            .collect();
+        infos.sort_by(|a, b| a.inst.cmp(&b.inst));
+        infos
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Replaces the snapshot of `market` and `inst_type` with `infos`.
+    ///
+    /// Instruments missing from `infos` are removed. Returns and broadcasts
+    /// the changes, ordered by instrument name.
+    pub fn apply(
+        &self,
+        market: &Market,
+        inst_type: InstrumentType,
+        infos: Vec<InstrumentInfo>,
+    ) -> Vec<InstrumentChange> {
+        let mut changes = Vec::new();
+        {
+            let mut instruments = self.write();
+            let mut stale: Vec<InstrumentKey> = instruments
+                .keys()
+                .filter(|key| key.market.as_ref() == Some(market) && key.inst_type == inst_type)
+                .cloned()
+                .collect();
+
+            for info in infos {
+                let key = Self::key(market.clone(), inst_type.clone(), &info.inst);
+                stale.retain(|stale| *stale != key);
+                let current = Some(info.state.clone());
+                let previous = instruments.insert(key.clone(), info).map(|old| old.state);
+                if previous != current {
+                    changes.push(InstrumentChange {
+                        key,
+                        previous,
+                        current,
+                    });
+                }
+            }
+            for key in stale {
+                let previous = instruments.remove(&key).map(|old| old.state);
+                changes.push(InstrumentChange {
+                    key,
+                    previous,
+                    current: None,
+                });
+            }
+        }
+
+        changes.sort_by(|a, b| a.key.inst.cmp(&b.key.inst));
+        for change in &changes {
+            let _ = self.changes.send(change.clone());
+        }
+        changes
+    }
+
+    /// Fetches the instruments of `inst_type` from `client` and applies them.
+    pub async fn load<C: LobPublicRest>(
+        &self,
+        market: &Market,
+        client: &C,
+        inst_type: InstrumentType,
+    ) -> InfraResult<Vec<InstrumentChange>> {
+        let infos = client.get_instrument_info(inst_type.clone()).await?;
+        Ok(self.apply(market, inst_type, infos))
+    }
+
+    /// Snaps `params` to the venue grid of `key`.
+    ///
+    /// `params.size` is in venue order units: contracts for derivatives quoted
+    /// in contracts, base units otherwise. Fails with
+    /// [`InfraError::RiskRejected`] when the instrument is unknown or not
+    /// tradable, or when the rounded order breaks a size or notional bound.
+    pub fn normalize(&self, key: &InstrumentKey, params: OrderParams) -> InfraResult<OrderParams> {
+        let info = self.require(key)?;
+        Ok(normalize_order(&info, params)?)
+    }
+
+    /// Like [`InstrumentRegistry::normalize`], with `params.size` given in base
+    /// units and converted to venue order units first.
+    pub fn normalize_base(
+        &self,
+        key: &InstrumentKey,
+        mut params: OrderParams,
+    ) -> InfraResult<OrderParams> {
+        let info = self.require(key)?;
+        let size = parse_size(&params)?;
+        params.size = (size / base_per_contract(&info)).to_string();
+        Ok(normalize_order(&info, params)?)
+    }
+
+    /// Converts a base-unit size of `key` into venue order units.
+    pub fn to_contracts(&self, key: &InstrumentKey, base_size: f64) -> Option<f64> {
+        self.read()
+            .get(key)
+            .map(|info| base_size / base_per_contract(info))
+    }
+
+    /// Converts a size in venue order units of `key` into base units.
+    pub fn to_base(&self, key: &InstrumentKey, contracts: f64) -> Option<f64> {
+        self.read()
+            .get(key)
+            .map(|info| contracts * base_per_contract(info))
+    }
+
+    fn require(&self, key: &InstrumentKey) -> InfraResult<InstrumentInfo> {
+        self.get(key).ok_or_else(|| {
+            InfraError::RiskRejected(RiskRejection::UnknownInstrument {
+                inst: key.inst.clone(),
+            })
+        })
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<InstrumentKey, InstrumentInfo>> {
+        self.instruments
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<InstrumentKey, InstrumentInfo>> {
+        self.instruments
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(feature = "lob_clients")]
+impl InstrumentRegistry {
+    /// Loads `inst_types` of `market` from `client` on every
+    /// [`InstrumentRegistry::refresh`].
+    pub fn with_source(
+        mut self,
+        market: Market,
+        client: LobClients,
+        inst_types: &[InstrumentType],
+    ) -> Self {
+        self.sources.push(InstrumentSource {
+            market,
+            client,
+            inst_types: inst_types.to_vec(),
+        });
+        self
+    }
+
+    /// Reloads every source.
+    ///
+    /// A failing source keeps its previous snapshot; the other sources are
+    /// still refreshed and the first error is returned afterwards.
+    pub async fn refresh(&self) -> InfraResult<Vec<InstrumentChange>> {
+        let mut changes = Vec::new();
+        let mut first_error = None;
+        for source in &self.sources {
+            for inst_type in &source.inst_types {
+                match self
+                    .load(&source.market, &source.client, inst_type.clone())
+                    .await
+                {
+                    Ok(applied) => changes.extend(applied),
+                    Err(e) => {
+                        warn!(
+                            "Instrument refresh failed for {:?} {:?}: {}",
+                            source.market, inst_type, e
+                        );
+                        first_error.get_or_insert(e);
+                    },
+                }
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(changes),
+        }
+    }
+
+    /// Refreshes every `every`, starting immediately, until the returned task
+    /// is aborted. Changes reach [`InstrumentRegistry::subscribe`]rs.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn spawn_refresh(&self, every: Duration) -> JoinHandle<()> {
+        let registry = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = interval(every);
+            loop {
+                ticker.tick().await;
+                if let Ok(changes) = registry.refresh().await
+                    && !changes.is_empty()
+                {
+                    info!("Instrument refresh applied {} changes", changes.len());
+                }
+            }
+        })
+    }
+}
+
+fn base_per_contract(info: &InstrumentInfo) -> f64 {
+    info.contract_value.unwrap_or(1.0) * info.contract_multiplier.unwrap_or(1.0)
+}
+
+fn parse_size(params: &OrderParams) -> Result<f64, RiskRejection> {
+    params
+        .size
+        .parse()
+        .ok()
+        .filter(|size: &f64| size.is_finite() && *size > 0.0)
+        .ok_or_else(|| RiskRejection::InvalidOrder(format!("size {:?}", params.size)))
+}
+
+fn normalize_order(
+    info: &InstrumentInfo,
+    mut params: OrderParams,
+) -> Result<OrderParams, RiskRejection> {
+    if !is_tradable(&info.state) {
+        return Err(RiskRejection::InstrumentNotTradable {
+            status: info.state.clone(),
+        });
+    }
+
+    let size = parse_size(&params)?;
+    params.size = if params.reduce_only == Some(true) {
+        normalize_to_string_reduce_only(size, info.lot_size)
+    } else {
+        normalize_to_string(size, info.lot_size)
+    };
+    let size: f64 = params.size.parse().unwrap_or(0.0);
+
+    let mut price = None;
+    if let Some(raw) = params.price.as_deref() {
+        let value: f64 = raw
+            .parse()
+            .ok()
+            .filter(|price: &f64| price.is_finite() && *price > 0.0)
+            .ok_or_else(|| RiskRejection::InvalidOrder(format!("price {raw:?}")))?;
+        let snapped = match params.side {
+            OrderSide::SELL => normalize_to_string_reduce_only(value, info.tick_size),
+            _ => normalize_to_string(value, info.tick_size),
+        };
+        price = snapped.parse::<f64>().ok().filter(|price| *price > 0.0);
+        if price.is_none() {
+            return Err(RiskRejection::InvalidOrder(format!("price {raw:?}")));
+        }
+        params.price = Some(snapped);
+    }
+
+    let (min, max) = if matches!(params.order_type, OrderType::Market) {
+        (info.min_mkt_size, info.max_mkt_size)
+    } else {
+        (info.min_lmt_size, info.max_lmt_size)
+    };
+    if size <= 0.0 || size < min {
+        return Err(RiskRejection::MinSize {
+            size,
+            min: min.max(info.lot_size),
+        });
+    }
+    if max > 0.0 && size > max {
+        return Err(RiskRejection::MaxSize { size, max });
+    }
+    if let (Some(min), Some(price)) = (info.min_notional, price) {
+        let notional = size * base_per_contract(info) * price;
+        if notional < min {
+            return Err(RiskRejection::MinNotional { notional, min });
+        }
+    }
+    Ok(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::{Future, ready};
+
+    use super::*;
+
+    const INST: &str = "BTC-USDT-SWAP";
+
+    struct StaticInstruments(Vec<InstrumentInfo>);
+
+    impl LobPublicRest for StaticInstruments {
+        fn get_instrument_info(
+            &self,
+            _inst_type: InstrumentType,
+        ) -> impl Future<Output = InfraResult<Vec<InstrumentInfo>>> + Send {
+            ready(Ok(self.0.clone()))
+        }
+    }
+
+    fn swap(inst: &str, state: InstrumentStatus) -> InstrumentInfo {
+        InstrumentInfo {
+            inst: inst.into(),
+            inst_type: InstrumentType::Perpetual,
+            lot_size: 0.1,
+            tick_size: 0.1,
+            min_lmt_size: 0.1,
+            max_lmt_size: 1_000.0,
+            min_mkt_size: 0.1,
+            max_mkt_size: 100.0,
+            min_notional: Some(5.0),
+            contract_value: Some(0.01),
+            contract_multiplier: Some(1.0),
+            state,
+            ..Default::default()
+        }
+    }
+
+    fn key(inst: &str) -> InstrumentKey {
+        InstrumentRegistry::key(Market::Okx, InstrumentType::Perpetual, inst)
+    }
+
+    fn limit(side: OrderSide, size: &str, price: &str) -> OrderParams {
+        OrderParams {
+            inst: INST.into(),
+            side,
+            size: size.into(),
+            order_type: OrderType::Limit,
+            price: Some(price.into()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_reports_listings_removals_and_status_changes() {
+        let registry = InstrumentRegistry::new();
+        let mut changes_rx = registry.subscribe();
+        let first = StaticInstruments(vec![
+            swap(INST, InstrumentStatus::Live),
+            swap("ETH-USDT-SWAP", InstrumentStatus::Live),
+        ]);
+        let changes = registry
+            .load(&Market::Okx, &first, InstrumentType::Perpetual)
+            .await
+            .unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(InstrumentChange::is_listing));
+        assert_eq!(changes_rx.recv().await.unwrap().key, key(INST));
+
+        let second = StaticInstruments(vec![
+            swap(INST, InstrumentStatus::Delisting),
+            swap("SOL-USDT-SWAP", InstrumentStatus::PreOpen),
+        ]);
+        let changes = registry
+            .load(&Market::Okx, &second, InstrumentType::Perpetual)
+            .await
+            .unwrap();
+        assert_eq!(
+            changes,
+            [
+                InstrumentChange {
+                    key: key(INST),
+                    previous: Some(InstrumentStatus::Live),
+                    current: Some(InstrumentStatus::Delisting),
+                },
+                InstrumentChange {
+                    key: key("ETH-USDT-SWAP"),
+                    previous: Some(InstrumentStatus::Live),
+                    current: None,
+                },
+                InstrumentChange {
+                    key: key("SOL-USDT-SWAP"),
+                    previous: None,
+                    current: Some(InstrumentStatus::PreOpen),
+                },
+            ]
+        );
+        assert_eq!(registry.len(), 2);
+        assert!(registry.find(&Market::Okx, "ETH-USDT-SWAP").is_none());
+
+        // An unchanged snapshot reports nothing.
+        let changes = registry
+            .load(&Market::Okx, &second, InstrumentType::Perpetual)
+            .await
+            .unwrap();
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn normalize_snaps_to_tick_and_lot() {
+        let registry = InstrumentRegistry::new();
+        registry.apply(
+            &Market::Okx,
+            InstrumentType::Perpetual,
+            vec![swap(INST, InstrumentStatus::Live)],
+        );
+
+        let buy = registry
+            .normalize(&key(INST), limit(OrderSide::BUY, "1.27", "60000.19"))
+            .unwrap();
+        assert_eq!(
+            (buy.size.as_str(), buy.price.as_deref()),
+            ("1.2", Some("60000.1"))
+        );
+
+        let sell = registry
+            .normalize(&key(INST), limit(OrderSide::SELL, "1.27", "60000.11"))
+            .unwrap();
+        assert_eq!(sell.price.as_deref(), Some("60000.2"));
+
+        let mut close = limit(OrderSide::SELL, "1.21", "60000");
+        close.reduce_only = Some(true);
+        assert_eq!(registry.normalize(&key(INST), close).unwrap().size, "1.3");
+    }
+
+    #[test]
+    fn normalize_base_converts_to_contracts() {
+        let registry = InstrumentRegistry::new();
+        registry.apply(
+            &Market::Okx,
+            InstrumentType::Perpetual,
+            vec![swap(INST, InstrumentStatus::Live)],
+        );
+
+        assert_eq!(registry.to_base(&key(INST), 250.0), Some(2.5));
+        let params = registry
+            .normalize_base(&key(INST), limit(OrderSide::BUY, "0.0155", "60000"))
+            .unwrap();
+        assert_eq!(params.size, "1.5");
+    }
+
+    #[test]
+    fn normalize_rejects_sub_minimum_and_unknown_orders() {
+        let registry = InstrumentRegistry::new();
+        registry.apply(
+            &Market::Okx,
+            InstrumentType::Perpetual,
+            vec![
+                swap(INST, InstrumentStatus::Live),
+                swap("ETH-USDT-SWAP", InstrumentStatus::Suspend),
+            ],
+        );
+        let rejection = |result: InfraResult<OrderParams>| match result {
+            Err(InfraError::RiskRejected(rejection)) => rejection,
+            other => panic!("expected a rejection, got {other:?}"),
+        };
+
+        assert!(matches!(
+            rejection(registry.normalize(&key(INST), limit(OrderSide::BUY, "0.05", "60000"))),
+            RiskRejection::MinSize { .. }
+        ));
+        assert!(matches!(
+            rejection(registry.normalize(&key(INST), limit(OrderSide::BUY, "0.5", "100"))),
+            RiskRejection::MinNotional { .. }
+        ));
+        assert!(matches!(
+            rejection(
+                registry.normalize(&key("ETH-USDT-SWAP"), limit(OrderSide::BUY, "1", "3000"))
+            ),
+            RiskRejection::InstrumentNotTradable { .. }
+        ));
+        assert_eq!(
+            rejection(registry.normalize(&key("DOGE-USDT-SWAP"), limit(OrderSide::BUY, "1", "1"))),
+            RiskRejection::UnknownInstrument {
+                inst: "DOGE-USDT-SWAP".into()
+            }
+        );
+    }
+}
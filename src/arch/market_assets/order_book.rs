@@ -0,0 +1,942 @@
+//! Local L2 order book rebuilt from normalized websocket depth events.
+//!
+//! [`LocalOrderBook`] tracks one instrument. Feed it every [`WsLob`] for that
+//! instrument from `on_lob`; it applies snapshots and deltas, validates the
+//! venue sequence fields in [`LobSeq`], and verifies the OKX CRC32 checksum.
+//!
+//! Sequence rules, using `L` for the last applied update id:
+//!
+//! - a delta whose `seq.prev` equals `L` continues the book (OKX, Binance
+//!   futures `pu`);
+//! - a delta without `prev` continues the book when `seq.first == L + 1`
+//!   (Gate `U`, Binance spot `U`);
+//! - a delta whose `seq.last <= L` is already contained in the book and is
+//!   skipped;
+//! - the first delta after a REST snapshot only has to bridge it, meaning
+//!   `first <= L + 1` and `last > L`, matching the Binance and Gate sync
+//!   procedures.
+//!
+//! Anything else is a gap. The book then reports
+//! [`BookStatus::ResyncNeeded`] and buffers later deltas. Request a REST
+//! `get_orderbook` snapshot and pass it to
+//! [`LocalOrderBook::apply_rest_snapshot`]; buffered deltas newer than the
+//! snapshot are replayed. A websocket snapshot also resynchronizes the book.
+//!
+//! OKX REST snapshots carry no update id. Such a snapshot leaves the book in
+//! [`BookStatus::AwaitingChecksum`] until a buffered or live delta applied on
+//! top of it matches the venue checksum, which proves the delta directly
+//! follows the snapshot.
+//!
+//! [`LobSeq`]: crate::arch::strategy_base::handler::lob_events::LobSeq
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, VecDeque},
+};
+
+use crate::arch::{
+    market_assets::{api_data::price_data::OrderBookData, market_core::Market},
+    strategy_base::handler::lob_events::{LobEventKind, LobLevel, LobLevelAction, WsLob},
+};
+
+/// Default number of deltas buffered while a resync is pending.
+pub const DEFAULT_RESYNC_BUFFER: usize = 4_096;
+
+const OKX_CHECKSUM_DEPTH: usize = 25;
+
+/// Synchronization state of a [`LocalOrderBook`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum BookStatus {
+    /// No snapshot has been applied yet. Deltas are buffered.
+    AwaitingSnapshot,
+    /// The book is consistent with the venue sequence.
+    Synced,
+    /// A snapshot without an update id was applied. The book syncs once a
+    /// delta's checksum confirms the snapshot lines up with the stream.
+    AwaitingChecksum,
+    /// The book is inconsistent. Deltas are buffered until a new snapshot.
+    ResyncNeeded(ResyncReason),
+}
+
+/// Why a [`LocalOrderBook`] lost synchronization.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResyncReason {
+    /// A delta did not continue from the last applied update id.
+    SequenceGap {
+        last_update_id: u64,
+        prev: Option<u64>,
+        first: Option<u64>,
+        last: Option<u64>,
+    },
+    /// The venue checksum did not match the local book after a delta.
+    ChecksumMismatch { expected: i64, computed: i64 },
+    /// More deltas arrived than the resync buffer can hold.
+    BufferOverflow,
+    /// A crossed book was produced, typically after a missed delete.
+    CrossedBook { best_bid: f64, best_ask: f64 },
+}
+
+/// Outcome of feeding one [`WsLob`] into a [`LocalOrderBook`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum LobApply {
+    /// The event changed the book, or advanced its sequence.
+    Applied,
+    /// The event is older than the book and was skipped.
+    Stale,
+    /// The event was buffered while waiting for a snapshot.
+    Buffered,
+    /// The event does not belong to this book, or is a BBO-only update.
+    Ignored,
+    /// The event broke synchronization. Request a new snapshot.
+    ResyncNeeded(ResyncReason),
+}
+
+/// One instrument's L2 book maintained from websocket deltas.
+#[derive(Clone, Debug)]
+pub struct LocalOrderBook {
+    market: Market,
+    inst: String,
+    bids: BTreeMap<PriceKey, f64>,
+    asks: BTreeMap<PriceKey, f64>,
+    last_update_id: Option<u64>,
+    timestamp: u64,
+    status: BookStatus,
+    bridging: bool,
+    verify_checksum: bool,
+    buffer: VecDeque<WsLob>,
+    buffer_limit: usize,
+}
+
+impl LocalOrderBook {
+    pub fn new(market: Market, inst: impl Into<String>) -> Self {
+        Self {
+            market,
+            inst: inst.into(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            last_update_id: None,
+            timestamp: 0,
+            status: BookStatus::AwaitingSnapshot,
+            bridging: false,
+            verify_checksum: true,
+            buffer: VecDeque::new(),
+            buffer_limit: DEFAULT_RESYNC_BUFFER,
+        }
+    }
+
+    /// Sets how many deltas are kept while waiting for a snapshot.
+    pub fn with_buffer_limit(mut self, limit: usize) -> Self {
+        self.buffer_limit = limit;
+        self
+    }
+
+    /// Enables or disables venue checksum verification. Enabled by default.
+    pub fn with_checksum_verification(mut self, enabled: bool) -> Self {
+        self.verify_checksum = enabled;
+        self
+    }
+
+    pub fn market(&self) -> &Market {
+        &self.market
+    }
+
+    pub fn inst(&self) -> &str {
+        &self.inst
+    }
+
+    pub fn status(&self) -> &BookStatus {
+        &self.status
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.status == BookStatus::Synced
+    }
+
+    /// Returns `true` when a REST snapshot should be requested.
+    pub fn needs_resync(&self) -> bool {
+        matches!(self.status, BookStatus::ResyncNeeded(_))
+    }
+
+    pub fn last_update_id(&self) -> Option<u64> {
+        self.last_update_id
+    }
+
+    /// Exchange timestamp in microseconds of the last applied event.
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Number of deltas currently buffered for replay.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Applies one websocket depth event for this book's instrument.
+    pub fn apply(&mut self, lob: &WsLob) -> LobApply {
+        if lob.market != self.market || lob.inst != self.inst {
+            return LobApply::Ignored;
+        }
+
+        match lob.event {
+            LobEventKind::Bbo => LobApply::Ignored,
+            LobEventKind::Snapshot => self.apply_ws_snapshot(lob),
+            LobEventKind::Incremental | LobEventKind::Heartbeat => {
+                if self.status == BookStatus::AwaitingChecksum {
+                    self.align_delta(lob)
+                } else if self.is_synced() {
+                    let outcome = self.apply_delta(lob);
+                    if matches!(
+                        outcome,
+                        LobApply::ResyncNeeded(ResyncReason::SequenceGap { .. })
+                    ) {
+                        // The next snapshot has to cover the gap, so replay
+                        // starts from the delta that found it.
+                        self.buffer.clear();
+                        self.buffer.push_back(lob.clone());
+                    }
+                    outcome
+                } else {
+                    self.buffer_delta(lob)
+                }
+            },
+        }
+    }
+
+    /// Replaces the book with a REST snapshot and replays buffered deltas.
+    ///
+    /// When the snapshot has no `update_id`, buffered deltas are tried on top
+    /// of it in order until one matches the OKX checksum; the book is synced
+    /// from that delta on. Without a match it returns [`LobApply::Buffered`]
+    /// and waits for a live delta to confirm it. Feeds without checksum
+    /// verification cannot be aligned: the buffer is dropped and sequence
+    /// checks resume from the next delta.
+    pub fn apply_rest_snapshot(&mut self, snapshot: &OrderBookData) -> LobApply {
+        if snapshot.inst != self.inst {
+            return LobApply::Ignored;
+        }
+
+        self.reset_levels(snapshot.bids.iter().copied(), snapshot.asks.iter().copied());
+        self.timestamp = snapshot.timestamp;
+        self.last_update_id = snapshot.update_id;
+        self.bridging = snapshot.update_id.is_some();
+        self.status = BookStatus::Synced;
+
+        if snapshot.update_id.is_some() {
+            return self.replay_buffer();
+        }
+        if !self.checks_okx_checksum() {
+            self.buffer.clear();
+            return LobApply::Applied;
+        }
+
+        self.status = BookStatus::AwaitingChecksum;
+        while let Some(lob) = self.buffer.pop_front() {
+            if let Some(Ok(())) = self.try_align(&lob) {
+                return self.replay_buffer();
+            }
+        }
+        LobApply::Buffered
+    }
+
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.bids.iter().next_back().map(|(px, sz)| (px.0, *sz))
+    }
+
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.asks.iter().next().map(|(px, sz)| (px.0, *sz))
+    }
+
+    /// Best bid and best ask as `(price, size)` pairs.
+    pub fn top_of_book(&self) -> Option<((f64, f64), (f64, f64))> {
+        Some((self.best_bid()?, self.best_ask()?))
+    }
+
+    /// Bid levels from best to worst. `depth == 0` returns every level.
+    pub fn bids(&self, depth: usize) -> Vec<(f64, f64)> {
+        take_depth(self.bids.iter().rev(), depth)
+    }
+
+    /// Ask levels from best to worst. `depth == 0` returns every level.
+    pub fn asks(&self, depth: usize) -> Vec<(f64, f64)> {
+        take_depth(self.asks.iter(), depth)
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        let ((bid, _), (ask, _)) = self.top_of_book()?;
+        Some(ask - bid)
+    }
+
+    pub fn mid(&self) -> Option<f64> {
+        let ((bid, _), (ask, _)) = self.top_of_book()?;
+        Some((bid + ask) / 2.0)
+    }
+
+    /// Size-weighted mid price: `(bid * ask_size + ask * bid_size) / (bid_size + ask_size)`.
+    pub fn microprice(&self) -> Option<f64> {
+        let ((bid, bid_sz), (ask, ask_sz)) = self.top_of_book()?;
+        let total = bid_sz + ask_sz;
+        if total <= 0.0 {
+            return None;
+        }
+        Some((bid * ask_sz + ask * bid_sz) / total)
+    }
+
+    /// Volume imbalance over the top `depth` levels, in `[-1, 1]`.
+    ///
+    /// Positive values mean more resting bid size. `depth == 0` uses the full
+    /// book.
+    pub fn imbalance(&self, depth: usize) -> Option<f64> {
+        let bid_vol: f64 = self.bids(depth).iter().map(|(_, sz)| sz).sum();
+        let ask_vol: f64 = self.asks(depth).iter().map(|(_, sz)| sz).sum();
+        let total = bid_vol + ask_vol;
+        if total <= 0.0 {
+            return None;
+        }
+        Some((bid_vol - ask_vol) / total)
+    }
+
+    /// Replaces the book levels, without touching sequence state.
+    fn reset_levels(
+        &mut self,
+        bids: impl IntoIterator<Item = (f64, f64)>,
+        asks: impl IntoIterator<Item = (f64, f64)>,
+    ) {
+        self.bids = collect_side(bids);
+        self.asks = collect_side(asks);
+    }
+
+    fn apply_ws_snapshot(&mut self, lob: &WsLob) -> LobApply {
+        self.reset_levels(
+            lob.bids.iter().map(|l| (l.price, l.size)),
+            lob.asks.iter().map(|l| (l.price, l.size)),
+        );
+        self.timestamp = lob.timestamp;
+        self.last_update_id = lob.seq.as_ref().and_then(|seq| seq.last);
+        self.bridging = false;
+        self.status = BookStatus::Synced;
+
+        if let Some(reason) = self.verify(lob) {
+            self.status = BookStatus::ResyncNeeded(reason.clone());
+            return LobApply::ResyncNeeded(reason);
+        }
+
+        self.replay_buffer()
+    }
+
+    fn apply_delta(&mut self, lob: &WsLob) -> LobApply {
+        match self.check_sequence(lob) {
+            SeqCheck::Continue => {},
+            SeqCheck::Stale => return LobApply::Stale,
+            SeqCheck::Gap(reason) => return self.lose_sync(reason),
+        }
+
+        for level in &lob.bids {
+            apply_level(&mut self.bids, level);
+        }
+        for level in &lob.asks {
+            apply_level(&mut self.asks, level);
+        }
+
+        if let Some(last) = lob.seq.as_ref().and_then(|seq| seq.last) {
+            self.last_update_id = Some(last);
+        }
+        self.timestamp = lob.timestamp;
+        self.bridging = false;
+
+        match self.verify(lob) {
+            Some(reason) => {
+                self.status = BookStatus::ResyncNeeded(reason.clone());
+                LobApply::ResyncNeeded(reason)
+            },
+            None => LobApply::Applied,
+        }
+    }
+
+    fn check_sequence(&self, lob: &WsLob) -> SeqCheck {
+        let (Some(seq), Some(last_id)) = (lob.seq.as_ref(), self.last_update_id) else {
+            return SeqCheck::Continue;
+        };
+
+        if seq.prev == Some(last_id) {
+            return SeqCheck::Continue;
+        }
+        if seq.last.is_some_and(|last| last <= last_id) {
+            return SeqCheck::Stale;
+        }
+
+        let next_id = last_id.saturating_add(1);
+        let continues = match (seq.prev, seq.first) {
+            (_, Some(first)) if self.bridging => first <= next_id,
+            (None, Some(first)) => first == next_id,
+            _ => false,
+        };
+
+        if continues {
+            SeqCheck::Continue
+        } else {
+            SeqCheck::Gap(ResyncReason::SequenceGap {
+                last_update_id: last_id,
+                prev: seq.prev,
+                first: seq.first,
+                last: seq.last,
+            })
+        }
+    }
+
+    /// Feeds `lob` to a book awaiting checksum confirmation. A mismatch means
+    /// the snapshot does not line up with the stream, so a new one is needed.
+    fn align_delta(&mut self, lob: &WsLob) -> LobApply {
+        match self.try_align(lob) {
+            Some(Ok(())) => LobApply::Applied,
+            Some(Err(reason)) => {
+                self.buffer.clear();
+                self.buffer.push_back(lob.clone());
+                self.lose_sync(reason)
+            },
+            None => self.buffer_delta(lob),
+        }
+    }
+
+    /// Applies `lob` on top of an unsequenced snapshot and keeps it when the
+    /// result matches its checksum. Returns `None` when `lob` has no checksum.
+    fn try_align(&mut self, lob: &WsLob) -> Option<Result<(), ResyncReason>> {
+        let expected = lob.checksum.as_deref()?.parse::<i64>().ok()?;
+        let (bids, asks) = (self.bids.clone(), self.asks.clone());
+        for level in &lob.bids {
+            apply_level(&mut self.bids, level);
+        }
+        for level in &lob.asks {
+            apply_level(&mut self.asks, level);
+        }
+
+        let computed = i64::from(self.okx_checksum());
+        if expected != computed {
+            self.bids = bids;
+            self.asks = asks;
+            return Some(Err(ResyncReason::ChecksumMismatch { expected, computed }));
+        }
+
+        self.last_update_id = lob.seq.as_ref().and_then(|seq| seq.last);
+        self.timestamp = lob.timestamp;
+        self.status = BookStatus::Synced;
+        Some(Ok(()))
+    }
+
+    fn checks_okx_checksum(&self) -> bool {
+        self.verify_checksum && self.market == Market::Okx
+    }
+
+    fn verify(&self, lob: &WsLob) -> Option<ResyncReason> {
+        if let (Some((best_bid, _)), Some((best_ask, _))) = (self.best_bid(), self.best_ask())
+            && best_bid >= best_ask
+        {
+            return Some(ResyncReason::CrossedBook { best_bid, best_ask });
+        }
+
+        if !self.checks_okx_checksum() {
+            return None;
+        }
+
+        let expected = lob.checksum.as_deref()?.parse::<i64>().ok()?;
+        let computed = i64::from(self.okx_checksum());
+        if expected == computed {
+            None
+        } else {
+            Some(ResyncReason::ChecksumMismatch { expected, computed })
+        }
+    }
+
+    /// OKX CRC32 over the top 25 levels, interleaved as `bid:size:ask:size`.
+    ///
+    /// Prices and sizes are printed from `f64`, so venues that pad values with
+    /// trailing zeros will not match; disable verification for those feeds.
+    fn okx_checksum(&self) -> i32 {
+        let bids = self.bids(OKX_CHECKSUM_DEPTH);
+        let asks = self.asks(OKX_CHECKSUM_DEPTH);
+        let mut fields = Vec::with_capacity(OKX_CHECKSUM_DEPTH * 4);
+
+        for i in 0..OKX_CHECKSUM_DEPTH {
+            if let Some((px, sz)) = bids.get(i) {
+                fields.push(px.to_string());
+                fields.push(sz.to_string());
+            }
+            if let Some((px, sz)) = asks.get(i) {
+                fields.push(px.to_string());
+                fields.push(sz.to_string());
+            }
+        }
+
+        crc32fast::hash(fields.join(":").as_bytes()) as i32
+    }
+
+    /// Marks the book out of sync. The buffer is left to the caller: live
+    /// deltas restart it, while a replay keeps the deltas it has not reached.
+    fn lose_sync(&mut self, reason: ResyncReason) -> LobApply {
+        self.status = BookStatus::ResyncNeeded(reason.clone());
+        LobApply::ResyncNeeded(reason)
+    }
+
+    fn buffer_delta(&mut self, lob: &WsLob) -> LobApply {
+        if self.buffer.len() >= self.buffer_limit {
+            // The first snapshot covers the oldest deltas, so dropping those
+            // only matters once a resync has already started from them.
+            self.buffer.pop_front();
+            if self.status != BookStatus::AwaitingSnapshot {
+                self.status = BookStatus::ResyncNeeded(ResyncReason::BufferOverflow);
+            }
+        }
+
+        self.buffer.push_back(lob.clone());
+        LobApply::Buffered
+    }
+
+    fn replay_buffer(&mut self) -> LobApply {
+        let mut outcome = LobApply::Applied;
+
+        while let Some(lob) = self.buffer.pop_front() {
+            match self.apply_delta(&lob) {
+                LobApply::ResyncNeeded(reason) => {
+                    // Keep the failing delta and everything after it for the
+                    // next snapshot attempt.
+                    self.buffer.push_front(lob);
+                    return LobApply::ResyncNeeded(reason);
+                },
+                LobApply::Applied => outcome = LobApply::Applied,
+                _ => {},
+            }
+        }
+
+        outcome
+    }
+}
+
+enum SeqCheck {
+    Continue,
+    Stale,
+    Gap(ResyncReason),
+}
+
+/// Total-ordered price key; venue prices are finite.
+#[derive(Clone, Copy, Debug)]
+struct PriceKey(f64);
+
+impl PartialEq for PriceKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PriceKey {}
+
+impl PartialOrd for PriceKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PriceKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+fn collect_side(levels: impl IntoIterator<Item = (f64, f64)>) -> BTreeMap<PriceKey, f64> {
+    levels
+        .into_iter()
+        .filter(|(_, sz)| *sz > 0.0)
+        .map(|(px, sz)| (PriceKey(px), sz))
+        .collect()
+}
+
+fn apply_level(side: &mut BTreeMap<PriceKey, f64>, level: &LobLevel) {
+    let key = PriceKey(level.price);
+    match level.action {
+        LobLevelAction::Upsert if level.size > 0.0 => {
+            side.insert(key, level.size);
+        },
+        LobLevelAction::Upsert | LobLevelAction::Delete => {
+            side.remove(&key);
+        },
+    }
+}
+
+fn take_depth<'a>(
+    levels: impl Iterator<Item = (&'a PriceKey, &'a f64)>,
+    depth: usize,
+) -> Vec<(f64, f64)> {
+    let levels = levels.map(|(px, sz)| (px.0, *sz));
+    if depth == 0 {
+        levels.collect()
+    } else {
+        levels.take(depth).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::strategy_base::handler::lob_events::LobSeq;
+
+    fn level(price: f64, size: f64) -> LobLevel {
+        LobLevel {
+            price,
+            size,
+            action: if size == 0.0 {
+                LobLevelAction::Delete
+            } else {
+                LobLevelAction::Upsert
+            },
+            order_count: None,
+            level_update_id: None,
+        }
+    }
+
+    fn lob(
+        market: Market,
+        event: LobEventKind,
+        bids: Vec<LobLevel>,
+        asks: Vec<LobLevel>,
+        seq: (Option<u64>, Option<u64>, Option<u64>),
+    ) -> WsLob {
+        WsLob {
+            timestamp: 1,
+            market,
+            inst: "BTC_USDT_PERP".into(),
+            event,
+            bids,
+            asks,
+            seq: Some(LobSeq {
+                prev: seq.0,
+                first: seq.1,
+                last: seq.2,
+            }),
+            checksum: None,
+        }
+    }
+
+    fn synced_gate_book() -> LocalOrderBook {
+        let mut book = LocalOrderBook::new(Market::GateFutures, "BTC_USDT_PERP");
+        let snapshot = lob(
+            Market::GateFutures,
+            LobEventKind::Snapshot,
+            vec![level(100.0, 1.0), level(99.0, 2.0)],
+            vec![level(101.0, 3.0), level(102.0, 4.0)],
+            (None, Some(10), Some(10)),
+        );
+        assert_eq!(book.apply(&snapshot), LobApply::Applied);
+        book
+    }
+
+    #[test]
+    fn applies_snapshot_and_incremental_levels() {
+        let mut book = synced_gate_book();
+        let delta = lob(
+            Market::GateFutures,
+            LobEventKind::Incremental,
+            vec![level(100.0, 0.0), level(99.5, 5.0)],
+            vec![level(101.0, 1.0)],
+            (None, Some(11), Some(12)),
+        );
+
+        assert_eq!(book.apply(&delta), LobApply::Applied);
+        assert_eq!(book.last_update_id(), Some(12));
+        assert_eq!(book.bids(0), vec![(99.5, 5.0), (99.0, 2.0)]);
+        assert_eq!(book.asks(1), vec![(101.0, 1.0)]);
+        assert_eq!(book.mid(), Some(100.25));
+        assert_eq!(book.spread(), Some(1.5));
+    }
+
+    #[test]
+    fn computes_microprice_and_imbalance() {
+        let book = synced_gate_book();
+
+        let micro = book.microprice().unwrap();
+        assert!((micro - (100.0 * 3.0 + 101.0 * 1.0) / 4.0).abs() < 1e-9);
+        assert_eq!(book.imbalance(1), Some((1.0 - 3.0) / 4.0));
+        assert_eq!(book.imbalance(0), Some((3.0 - 7.0) / 10.0));
+    }
+
+    #[test]
+    fn gap_requires_resync_and_rest_snapshot_replays_buffer() {
+        let mut book = synced_gate_book();
+        let gap = lob(
+            Market::GateFutures,
+            LobEventKind::Incremental,
+            vec![level(100.0, 7.0)],
+            vec![],
+            (None, Some(15), Some(16)),
+        );
+        let next = lob(
+            Market::GateFutures,
+            LobEventKind::Incremental,
+            vec![],
+            vec![level(101.0, 0.0)],
+            (None, Some(17), Some(17)),
+        );
+
+        assert!(matches!(
+            book.apply(&gap),
+            LobApply::ResyncNeeded(ResyncReason::SequenceGap {
+                last_update_id: 10,
+                ..
+            })
+        ));
+        assert!(book.needs_resync());
+        assert_eq!(book.apply(&next), LobApply::Buffered);
+
+        let snapshot = OrderBookData {
+            timestamp: 2,
+            inst: "BTC_USDT_PERP".into(),
+            bids: vec![(100.0, 1.0)],
+            asks: vec![(101.0, 3.0), (102.0, 4.0)],
+            update_id: Some(15),
+        };
+        assert_eq!(book.apply_rest_snapshot(&snapshot), LobApply::Applied);
+        assert!(book.is_synced());
+        assert_eq!(book.last_update_id(), Some(17));
+        assert_eq!(book.best_bid(), Some((100.0, 7.0)));
+        assert_eq!(book.best_ask(), Some((102.0, 4.0)));
+        assert_eq!(book.buffered(), 0);
+    }
+
+    #[test]
+    fn gap_in_replay_keeps_remaining_deltas_in_order() {
+        let mut book = LocalOrderBook::new(Market::GateFutures, "BTC_USDT_PERP");
+        let deltas: Vec<WsLob> = [(20, 21), (22, 23), (24, 25)]
+            .into_iter()
+            .map(|(first, last)| {
+                lob(
+                    Market::GateFutures,
+                    LobEventKind::Incremental,
+                    vec![level(100.0, last as f64)],
+                    vec![],
+                    (None, Some(first), Some(last)),
+                )
+            })
+            .collect();
+        for delta in &deltas {
+            assert_eq!(book.apply(delta), LobApply::Buffered);
+        }
+
+        // The snapshot is older than the first buffered delta.
+        let stale = OrderBookData {
+            timestamp: 2,
+            inst: "BTC_USDT_PERP".into(),
+            bids: vec![(100.0, 1.0)],
+            asks: vec![(101.0, 1.0)],
+            update_id: Some(15),
+        };
+        assert!(matches!(
+            book.apply_rest_snapshot(&stale),
+            LobApply::ResyncNeeded(ResyncReason::SequenceGap {
+                last_update_id: 15,
+                first: Some(20),
+                ..
+            })
+        ));
+        assert_eq!(book.buffered(), 3);
+
+        let fresh = OrderBookData {
+            update_id: Some(21),
+            ..stale
+        };
+        assert_eq!(book.apply_rest_snapshot(&fresh), LobApply::Applied);
+        assert!(book.is_synced());
+        assert_eq!(book.last_update_id(), Some(25));
+        assert_eq!(book.best_bid(), Some((100.0, 25.0)));
+        assert_eq!(book.buffered(), 0);
+    }
+
+    #[test]
+    fn full_buffer_keeps_newest_deltas_while_awaiting_snapshot() {
+        let mut book =
+            LocalOrderBook::new(Market::GateFutures, "BTC_USDT_PERP").with_buffer_limit(2);
+        for id in 11..=13 {
+            let delta = lob(
+                Market::GateFutures,
+                LobEventKind::Incremental,
+                vec![level(100.0, id as f64)],
+                vec![],
+                (None, Some(id), Some(id)),
+            );
+            assert_eq!(book.apply(&delta), LobApply::Buffered);
+        }
+        assert_eq!(book.buffered(), 2);
+        assert_eq!(book.status(), &BookStatus::AwaitingSnapshot);
+
+        let snapshot = OrderBookData {
+            timestamp: 2,
+            inst: "BTC_USDT_PERP".into(),
+            bids: vec![(100.0, 1.0)],
+            asks: vec![(101.0, 1.0)],
+            update_id: Some(11),
+        };
+        assert_eq!(book.apply_rest_snapshot(&snapshot), LobApply::Applied);
+        assert_eq!(book.last_update_id(), Some(13));
+        assert_eq!(book.best_bid(), Some((100.0, 13.0)));
+    }
+
+    #[test]
+    fn binance_futures_uses_prev_update_id() {
+        let mut book = LocalOrderBook::new(Market::BinanceUmFutures, "BTC_USDT_PERP");
+        let early = lob(
+            Market::BinanceUmFutures,
+            LobEventKind::Incremental,
+            vec![level(100.0, 1.0)],
+            vec![],
+            (Some(90), Some(95), Some(99)),
+        );
+        let bridge = lob(
+            Market::BinanceUmFutures,
+            LobEventKind::Incremental,
+            vec![level(100.0, 2.0)],
+            vec![],
+            (Some(99), Some(100), Some(105)),
+        );
+        let next = lob(
+            Market::BinanceUmFutures,
+            LobEventKind::Incremental,
+            vec![level(100.0, 3.0)],
+            vec![],
+            (Some(105), Some(106), Some(110)),
+        );
+
+        assert_eq!(book.apply(&early), LobApply::Buffered);
+        assert_eq!(book.apply(&bridge), LobApply::Buffered);
+        let snapshot = OrderBookData {
+            timestamp: 2,
+            inst: "BTC_USDT_PERP".into(),
+            bids: vec![(100.0, 1.5)],
+            asks: vec![(101.0, 1.0)],
+            update_id: Some(102),
+        };
+
+        assert_eq!(book.apply_rest_snapshot(&snapshot), LobApply::Applied);
+        assert_eq!(book.last_update_id(), Some(105));
+        assert_eq!(book.apply(&next), LobApply::Applied);
+        assert_eq!(book.best_bid(), Some((100.0, 3.0)));
+        assert_eq!(book.apply(&next), LobApply::Stale);
+    }
+
+    #[test]
+    fn verifies_okx_checksum() {
+        let mut book = LocalOrderBook::new(Market::Okx, "BTC_USDT_PERP");
+        let mut snapshot = lob(
+            Market::Okx,
+            LobEventKind::Snapshot,
+            vec![level(3366.1, 7.0), level(3366.0, 6.0)],
+            vec![level(3366.8, 9.0), level(3368.0, 8.0)],
+            (None, Some(1), Some(1)),
+        );
+        // CRC32 of "3366.1:7:3366.8:9:3366:6:3368:8".
+        snapshot.checksum = Some("-1881014294".into());
+        assert_eq!(book.apply(&snapshot), LobApply::Applied);
+
+        let mut delta = lob(
+            Market::Okx,
+            LobEventKind::Incremental,
+            vec![level(3366.1, 1.0)],
+            vec![],
+            (Some(1), Some(2), Some(2)),
+        );
+        delta.checksum = Some("1".into());
+        assert!(matches!(
+            book.apply(&delta),
+            LobApply::ResyncNeeded(ResyncReason::ChecksumMismatch { expected: 1, .. })
+        ));
+    }
+
+    /// OKX checksum of a book holding `bids` and `asks`.
+    fn okx_checksum(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Option<String> {
+        let mut book = LocalOrderBook::new(Market::Okx, "BTC_USDT_PERP");
+        book.reset_levels(bids.iter().copied(), asks.iter().copied());
+        Some(book.okx_checksum().to_string())
+    }
+
+    fn okx_delta(bid: (f64, f64), prev: u64, checksum: Option<String>) -> WsLob {
+        let mut delta = lob(
+            Market::Okx,
+            LobEventKind::Incremental,
+            vec![level(bid.0, bid.1)],
+            vec![],
+            (Some(prev), Some(prev + 1), Some(prev + 1)),
+        );
+        delta.checksum = checksum;
+        delta
+    }
+
+    #[test]
+    fn unsequenced_snapshot_waits_for_a_matching_checksum() {
+        let snapshot = OrderBookData {
+            timestamp: 1,
+            inst: "BTC_USDT_PERP".into(),
+            bids: vec![(100.0, 1.0)],
+            asks: vec![(101.0, 1.0)],
+            update_id: None,
+        };
+        let asks = [(101.0, 1.0)];
+
+        // A delta the snapshot already contains does not line up with it.
+        let mut book = LocalOrderBook::new(Market::Okx, "BTC_USDT_PERP");
+        let older = okx_delta((99.0, 4.0), 1, okx_checksum(&[(99.0, 4.0)], &asks));
+        assert_eq!(book.apply(&older), LobApply::Buffered);
+        assert_eq!(book.apply_rest_snapshot(&snapshot), LobApply::Buffered);
+        assert_eq!(book.status(), &BookStatus::AwaitingChecksum);
+        assert_eq!(book.buffered(), 0);
+
+        let next = okx_delta((100.0, 2.0), 2, okx_checksum(&[(100.0, 2.0)], &asks));
+        assert_eq!(book.apply(&next), LobApply::Applied);
+        assert!(book.is_synced());
+        assert_eq!(book.last_update_id(), Some(3));
+        assert_eq!(book.best_bid(), Some((100.0, 2.0)));
+        let after = okx_delta((100.0, 3.0), 3, okx_checksum(&[(100.0, 3.0)], &asks));
+        assert_eq!(book.apply(&after), LobApply::Applied);
+
+        // A live delta that skipped updates past the snapshot resyncs.
+        let mut book = LocalOrderBook::new(Market::Okx, "BTC_USDT_PERP");
+        assert_eq!(book.apply_rest_snapshot(&snapshot), LobApply::Buffered);
+        let skipped = okx_delta(
+            (100.0, 2.0),
+            5,
+            okx_checksum(&[(100.0, 2.0), (99.0, 1.0)], &asks),
+        );
+        assert!(matches!(
+            book.apply(&skipped),
+            LobApply::ResyncNeeded(ResyncReason::ChecksumMismatch { .. })
+        ));
+        assert_eq!(book.buffered(), 1);
+
+        // A buffered delta that lines up syncs the book and replays the rest.
+        let mut book = LocalOrderBook::new(Market::Okx, "BTC_USDT_PERP");
+        book.apply(&next);
+        book.apply(&after);
+        assert_eq!(book.apply_rest_snapshot(&snapshot), LobApply::Applied);
+        assert!(book.is_synced());
+        assert_eq!(book.last_update_id(), Some(4));
+        assert_eq!(book.best_bid(), Some((100.0, 3.0)));
+    }
+
+    #[test]
+    fn ignores_other_instruments_and_bbo() {
+        let mut book = synced_gate_book();
+        let mut other = lob(
+            Market::GateFutures,
+            LobEventKind::Incremental,
+            vec![level(1.0, 1.0)],
+            vec![],
+            (None, Some(11), Some(11)),
+        );
+        other.inst = "ETH_USDT_PERP".into();
+        let bbo = lob(
+            Market::GateFutures,
+            LobEventKind::Bbo,
+            vec![level(1.0, 1.0)],
+            vec![],
+            (None, Some(11), Some(11)),
+        );
+
+        assert_eq!(book.apply(&other), LobApply::Ignored);
+        assert_eq!(book.apply(&bbo), LobApply::Ignored);
+        assert_eq!(book.last_update_id(), Some(10));
+    }
+}
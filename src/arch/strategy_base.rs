@@ -8,9 +8,17 @@
 //!   used by strategies to send active commands back to tasks.
 //! - [`hlist_core`] stores heterogeneous strategy modules without forcing them
 //!   behind `Box<dyn Strategy>`.
+//! - [`oms`] tracks order lifecycles across acks, websocket, and REST updates.
+//! - [`portfolio`] tracks positions, balances, and PnL from account streams.
+//! - [`recorder`] persists market and account events to rotating files.
+//! - [`risk`] runs pre-trade checks before orders reach a venue.
 
 pub mod command;
 pub mod handler;
 pub mod hlist_core;
+pub mod oms;
+pub mod portfolio;
+pub mod recorder;
+pub mod risk;
 pub mod strategy_group;
 pub mod strategy_module;
@@ -5,7 +5,10 @@ use tokio::sync::oneshot::Sender;
 /// Strategies can pass an expected status to [`CommandHandle::send_command`]
 /// to sequence relay commands such as connect -> login -> subscribe. An
 /// acknowledgement reports progress inside the local relay; it does not imply
-/// exchange-level authentication, subscription, or order acceptance.
+/// exchange-level authentication, subscription, or order acceptance, except
+/// for the reply statuses of messages sent with a [`WsReplyMatcher`].
+///
+/// [`WsReplyMatcher`]: crate::arch::strategy_base::command::command_core::WsReplyMatcher
 ///
 /// [`CommandHandle::send_command`]: crate::arch::strategy_base::command::command_core::CommandHandle::send_command
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -15,11 +18,19 @@ pub enum AckStatus {
     /// The relay consumed a websocket message command and attempted the write.
     ///
     /// The relay currently returns this status even when the socket write fails.
+    /// Messages sent with a reply matcher are acked with their reply instead.
     WsMessage,
     /// The relay consumed a shutdown command and attempted its payload write.
     ///
     /// The relay currently returns this status even when the socket write fails.
     WsShutdown,
+    /// The venue accepted a websocket message; carries the reply frame.
+    WsAccepted(String),
+    /// The venue rejected a websocket message, or the relay could not send it;
+    /// carries the reply frame or the reason.
+    WsRejected(String),
+    /// No reply to a websocket message arrived before its matcher timed out.
+    WsTimedOut,
     /// An alt task auto-acknowledged an unexpected ack-bearing command.
     AltTask,
     /// Unknown or placeholder acknowledgement.
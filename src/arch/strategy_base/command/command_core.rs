@@ -1,10 +1,20 @@
-use std::{collections::HashMap, sync::Arc};
+use serde_json::Value;
+use std::{collections::HashMap, fmt, mem, sync::Arc, time::Duration};
 use tokio::sync::{mpsc, oneshot};
 
+#[cfg(feature = "binance")]
+use crate::arch::market_assets::exchange::binance::{
+    binance_cm_futures_cli::BinanceCmCli, binance_um_futures_cli::BinanceUmCli,
+};
+#[cfg(feature = "gate")]
+use crate::arch::market_assets::exchange::gate::gate_futures_cli::GateFuturesCli;
+#[cfg(feature = "okx")]
+use crate::arch::market_assets::exchange::okx::okx_cli::OkxCli;
 use crate::arch::{
+    infra_core::clock::RuntimeClock,
     strategy_base::{
         command::ack_handle::{AckHandle, AckStatus},
-        handler::alt_events::{AltIntent, AltOrder, AltTensor},
+        handler::alt_events::{AltCancel, AltIntent, AltOrder, AltTensor},
     },
     task_execution::{TaskInfo, TaskKey, task_alt::AltTaskType, task_ws::WsChannel},
 };
@@ -19,6 +29,7 @@ use crate::errors::{InfraError, InfraResult};
 #[derive(Clone, Debug, Default)]
 pub struct CommandRegistry {
     handles: Arc<HashMap<TaskKey, Arc<CommandHandle>>>,
+    clock: RuntimeClock,
 }
 
 impl CommandRegistry {
@@ -42,9 +53,22 @@ impl CommandRegistry {
 
         Self {
             handles: Arc::new(map),
+            clock: RuntimeClock::system(),
         }
     }
 
+    pub(crate) fn with_clock(mut self, clock: RuntimeClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Clock of the runtime that built this registry.
+    ///
+    /// Backtests return a simulated clock; live runtimes read system time.
+    pub fn clock(&self) -> &RuntimeClock {
+        &self.clock
+    }
+
     /// Finds a non-websocket task handle.
     ///
     /// Use this for `TaskCommand::OrderExecute`, `TaskCommand::InstIntent`, or
@@ -64,7 +88,8 @@ impl CommandRegistry {
     /// Finds a websocket task handle.
     ///
     /// Use this after `on_ws_event` to send `TaskCommand::WsConnect`,
-    /// `TaskCommand::WsMessage`, or `TaskCommand::WsShutdown` to the relay.
+    /// `TaskCommand::WsMessage`, `TaskCommand::Subscribe`, or
+    /// `TaskCommand::WsShutdown` to the relay.
     pub fn find_ws_handle(
         &self,
         ws_channel: &WsChannel,
@@ -98,8 +123,12 @@ impl CommandHandle {
     /// wait until the relay has processed a command-specific acknowledgement
     /// before sending the next message. For websocket text messages and
     /// shutdown requests, the ack means the relay consumed and attempted the
-    /// command; exchange-level acceptance should still be inferred from the
-    /// venue's follow-up messages.
+    /// command, unless the message carries a [`WsReplyMatcher`]: the relay then
+    /// acks with the venue's reply, as `AckStatus::WsAccepted`,
+    /// `AckStatus::WsRejected`, or `AckStatus::WsTimedOut`. Statuses that carry
+    /// a reply compare by variant, so `AckStatus::WsAccepted(String::new())`
+    /// expects any accepting reply. [`CommandHandle::send_ws_request`] returns
+    /// the reply itself.
     ///
     /// Examples of common command flows:
     ///
@@ -125,7 +154,7 @@ impl CommandHandle {
                 InfraError::Msg(format!("Ack channel closed, expected ack: {:?}", expected,))
             })?;
 
-            if ack == expected {
+            if mem::discriminant(&ack) == mem::discriminant(&expected) {
                 Ok(())
             } else {
                 Err(InfraError::Msg(format!(
@@ -137,6 +166,41 @@ impl CommandHandle {
             Ok(())
         }
     }
+
+    /// Sends a websocket message and waits for the venue's reply to it.
+    ///
+    /// Returns the reply frame when `reply` recognizes it as accepting the
+    /// message, and an error when the venue rejects the message, the relay
+    /// cannot send it, or no reply arrives within the matcher's timeout.
+    pub async fn send_ws_request(&self, msg: String, reply: WsReplyMatcher) -> InfraResult<String> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(TaskCommand::WsMessage {
+                msg,
+                ack: AckHandle::new(tx),
+                reply: Some(reply),
+            })
+            .await
+            .map_err(|e| InfraError::Msg(format!("Failed to send Command: {}", e)))?;
+
+        match rx.await {
+            Ok(AckStatus::WsAccepted(frame)) => Ok(frame),
+            Ok(AckStatus::WsRejected(reason)) => Err(InfraError::Msg(format!(
+                "Websocket message rejected: {}",
+                reason
+            ))),
+            Ok(AckStatus::WsTimedOut) => Err(InfraError::Msg(
+                "Timed out waiting for the websocket reply".into(),
+            )),
+            Ok(ack) => Err(InfraError::Msg(format!(
+                "Unexpected ack: {:?}, expected a websocket reply",
+                ack
+            ))),
+            Err(_) => Err(InfraError::Msg(
+                "Ack channel closed, expected a websocket reply".into(),
+            )),
+        }
+    }
 }
 
 /// Command sent from a strategy module to a runtime task.
@@ -160,7 +224,8 @@ impl CommandHandle {
 /// Alt-task commands carry normalized infra data:
 ///
 /// - `OrderExecute` forwards an order batch to an order-execution task, which
-///   then emits `on_order_execution`.
+///   then emits `on_order_execution`, or places the orders and emits
+///   `on_order_ack` on a `LobExecution` task. `OrderCancel` works the same way.
 /// - `InstIntent` forwards allocation/instrument/portfolio intent, which then
 ///   emits `on_inst_intent`.
 /// - `FeatInput` forwards model features to a model task; predictions are later
@@ -193,13 +258,65 @@ pub enum TaskCommand {
     ///
     /// Use this for login, authentication, subscription, unsubscription, ping,
     /// or other exchange-specific control messages. The message body is built
-    /// by the concrete exchange client.
+    /// by the concrete exchange client. On a connection opened with
+    /// [`WsConnectTarget::with_reconnect`], the relay replays logins and
+    /// subscriptions in order after every reconnect.
     WsMessage {
         /// Exchange-specific websocket message body.
         msg: String,
         /// Optional acknowledgement handle. Use `AckHandle::none()` when the
         /// caller does not need to wait.
         ack: AckHandle,
+        /// Recognizes the venue's reply. When set, the relay holds `ack` until
+        /// the reply arrives or the matcher times out; replays after a
+        /// reconnect are not tracked.
+        reply: Option<WsReplyMatcher>,
+    },
+
+    /// Sends a websocket text message produced by a builder.
+    ///
+    /// The relay calls the builder now and again on every reconnect replay, so
+    /// use this for payloads that embed timestamps or signatures, such as
+    /// `OkxCli::ws_login_msg`. Otherwise it behaves like
+    /// [`TaskCommand::WsMessage`] and acknowledges with `AckStatus::WsMessage`,
+    /// or with the venue's reply when `reply` is set.
+    WsBuiltMessage {
+        /// Builder invoked for the initial send and each replay.
+        builder: WsMessageBuilder,
+        /// Optional acknowledgement handle.
+        ack: AckHandle,
+        /// Recognizes the venue's reply to the initial send.
+        reply: Option<WsReplyMatcher>,
+    },
+
+    /// Subscribes an open public relay to `channel` for `insts`.
+    ///
+    /// The relay builds the venue's subscribe messages itself, tracks the
+    /// active subscriptions, and subscribes them again after a relay-managed
+    /// reconnect. Instruments already subscribed are skipped; leave `insts`
+    /// empty for channels without instruments. The ack is `AckStatus::WsMessage`
+    /// once the messages are written, or `AckStatus::WsRejected` when the
+    /// venue does not support the channel or the subscription would exceed the
+    /// venue's per-connection limits.
+    Subscribe {
+        /// Public channel, such as `WsChannel::Trades(None)`.
+        channel: WsChannel,
+        /// Instruments in the infra format, e.g. `BTC_USDT_PERP`.
+        insts: Vec<String>,
+        /// Optional acknowledgement handle.
+        ack: AckHandle,
+    },
+
+    /// Cancels subscriptions made with [`TaskCommand::Subscribe`].
+    ///
+    /// Instruments not currently subscribed are skipped.
+    Unsubscribe {
+        /// Public channel of the subscription.
+        channel: WsChannel,
+        /// Instruments to drop.
+        insts: Vec<String>,
+        /// Optional acknowledgement handle.
+        ack: AckHandle,
     },
 
     /// Requests websocket shutdown.
@@ -215,11 +332,17 @@ pub enum TaskCommand {
 
     /// Sends a normalized batch of orders to an order-execution task.
     ///
-    /// The receiving task publishes the same batch into its task stream, where
-    /// execution modules can handle it through
-    /// `EventHandler::on_order_execution`.
+    /// An `OrderExecution` task publishes the same batch into its task stream,
+    /// where execution modules can handle it through
+    /// `EventHandler::on_order_execution`. A `LobExecution` task places the
+    /// orders and publishes the results through `EventHandler::on_order_ack`.
     OrderExecute(Vec<AltOrder>),
 
+    /// Sends a batch of cancels to a `LobExecution` task.
+    ///
+    /// Results are published through `EventHandler::on_order_ack`.
+    OrderCancel(Vec<AltCancel>),
+
     /// Sends an instrument, allocation, or portfolio intent to an intent task.
     ///
     /// The receiving task publishes the intent into its task stream, where
@@ -243,7 +366,11 @@ impl TaskCommand {
     /// ack. `WsConnect` is acknowledged by the websocket relay's connect path.
     pub fn get_ack(self) -> Option<AckHandle> {
         match self {
-            TaskCommand::WsMessage { ack, .. } | TaskCommand::WsShutdown { ack, .. } => Some(ack),
+            TaskCommand::WsMessage { ack, .. }
+            | TaskCommand::WsBuiltMessage { ack, .. }
+            | TaskCommand::Subscribe { ack, .. }
+            | TaskCommand::Unsubscribe { ack, .. }
+            | TaskCommand::WsShutdown { ack, .. } => Some(ack),
             _ => None,
         }
     }
@@ -256,6 +383,13 @@ pub struct WsConnectTarget {
     pub url: String,
     /// Additional HTTP headers to include in the websocket upgrade request.
     pub headers: Vec<(String, String)>,
+    /// Automatic reconnect policy. `None` hands every reconnect back to the
+    /// strategy through `on_ws_event`.
+    pub reconnect: Option<WsReconnectPolicy>,
+    /// Client whose listen key the relay keeps alive for this connection.
+    pub listen_key: Option<Box<ListenKeySource>>,
+    /// Client the relay fetches REST order book snapshots from.
+    pub snapshot: Option<Box<SnapshotSource>>,
 }
 
 impl WsConnectTarget {
@@ -263,6 +397,9 @@ impl WsConnectTarget {
         Self {
             url: url.into(),
             headers: Vec::new(),
+            reconnect: None,
+            listen_key: None,
+            snapshot: None,
         }
     }
 
@@ -270,6 +407,300 @@ impl WsConnectTarget {
         self.headers.push((key.into(), value.into()));
         self
     }
+
+    /// Lets the relay reconnect by itself after the connection drops.
+    ///
+    /// The relay remembers this target and the logins and subscriptions sent
+    /// as `WsMessage` or `WsBuiltMessage`, reconnects with exponential
+    /// backoff, and replays those messages in order. Pings, orders and other
+    /// requests are never resent, and an unsubscribe drops the streams it
+    /// covers from the replay. Progress is reported
+    /// through `EventHandler::on_ws_connection`. `WsShutdown` ends the managed
+    /// session.
+    pub fn with_reconnect(mut self, policy: WsReconnectPolicy) -> Self {
+        self.reconnect = Some(policy);
+        self
+    }
+
+    /// Lets the relay own the listen key of a user data stream.
+    ///
+    /// Before every connection the relay creates a key through `source` and
+    /// appends it to `url` as the `listenKey` query parameter. It renews the
+    /// key every 30 minutes, and when the venue reports the key expired or
+    /// renewals keep failing, it rotates the key and reconnects. The session
+    /// is managed as under [`WsConnectTarget::with_reconnect`], with the
+    /// default policy unless another is set.
+    pub fn with_listen_key(mut self, source: ListenKeySource) -> Self {
+        self.listen_key = Some(Box::new(source));
+        self
+    }
+
+    /// Sets the client a `LobParam::Incremental` relay seeds and resyncs its
+    /// books from, so a testnet or mock stream is not seeded from production.
+    ///
+    /// Without one, the relay uses the execution client registered for the
+    /// market, or the venue's default client when `url` is its production
+    /// stream.
+    pub fn with_snapshot_source(mut self, source: SnapshotSource) -> Self {
+        self.snapshot = Some(Box::new(source));
+        self
+    }
+}
+
+/// Client that creates and renews a user data stream listen key.
+///
+/// Binance UM and CM futures clients build targets carrying one from
+/// `LobWebsocket::get_private_connect_target`.
+#[derive(Clone, Debug)]
+pub enum ListenKeySource {
+    #[cfg(feature = "binance")]
+    BinanceCm(BinanceCmCli),
+    #[cfg(feature = "binance")]
+    BinanceUm(BinanceUmCli),
+}
+
+/// Sources are equal when they manage keys of the same venue and endpoints.
+impl PartialEq for ListenKeySource {
+    fn eq(&self, _other: &Self) -> bool {
+        match (self, _other) {
+            #[cfg(feature = "binance")]
+            (Self::BinanceCm(a), Self::BinanceCm(b)) => a.endpoints == b.endpoints,
+            #[cfg(feature = "binance")]
+            (Self::BinanceUm(a), Self::BinanceUm(b)) => a.endpoints == b.endpoints,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ListenKeySource {}
+
+/// Client that fetches REST order book snapshots for a relay-maintained book.
+///
+/// Binance UM, Gate futures and OKX clients attach one to the target they
+/// build from `LobWebsocket::get_public_connect_target` for `WsChannel::Lob`.
+#[derive(Clone, Debug)]
+pub enum SnapshotSource {
+    #[cfg(feature = "binance")]
+    BinanceUm(BinanceUmCli),
+    #[cfg(feature = "gate")]
+    GateFutures(GateFuturesCli),
+    #[cfg(feature = "okx")]
+    Okx(OkxCli),
+}
+
+/// Sources are equal when they fetch from the same venue and endpoints.
+impl PartialEq for SnapshotSource {
+    fn eq(&self, _other: &Self) -> bool {
+        match (self, _other) {
+            #[cfg(feature = "binance")]
+            (Self::BinanceUm(a), Self::BinanceUm(b)) => a.endpoints == b.endpoints,
+            #[cfg(feature = "gate")]
+            (Self::GateFutures(a), Self::GateFutures(b)) => a.endpoints == b.endpoints,
+            #[cfg(feature = "okx")]
+            (Self::Okx(a), Self::Okx(b)) => a.endpoints == b.endpoints,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+impl Eq for SnapshotSource {}
+
+/// Backoff settings for relay-managed websocket reconnects.
+///
+/// The delay before attempt `n` is `initial_backoff * 2^(n - 1)`, capped at
+/// `max_backoff`, plus a random jitter of up to `jitter`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WsReconnectPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub jitter: Duration,
+    /// Attempts before giving up. `None` retries forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for WsReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: Duration::from_millis(500),
+            max_attempts: None,
+        }
+    }
+}
+
+impl WsReconnectPolicy {
+    /// Delay before reconnect attempt `attempt` (starting at 1), without jitter.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Callback that produces a websocket message on demand.
+#[derive(Clone)]
+pub struct WsMessageBuilder(Arc<dyn Fn() -> InfraResult<String> + Send + Sync>);
+
+impl WsMessageBuilder {
+    pub fn new(build: impl Fn() -> InfraResult<String> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(build))
+    }
+
+    pub fn build(&self) -> InfraResult<String> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for WsMessageBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WsMessageBuilder(..)")
+    }
+}
+
+/// Verdict of a [`WsReplyMatcher`] on one inbound frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WsReplyVerdict {
+    /// The frame is not a reply to the message.
+    Unrelated,
+    Accepted,
+    Rejected,
+}
+
+/// Recognizes the venue's reply to one websocket message.
+///
+/// The relay offers every inbound JSON frame to its waiting matchers, oldest
+/// first. The first matcher that accepts or rejects a frame consumes it, so the
+/// frame is not published to strategies. Presets cover the common login and
+/// subscription replies; [`WsReplyMatcher::new`] takes any other rule.
+#[derive(Clone)]
+pub struct WsReplyMatcher {
+    rule: Arc<dyn Fn(&Value) -> WsReplyVerdict + Send + Sync>,
+    timeout: Duration,
+}
+
+impl WsReplyMatcher {
+    /// Time a message waits for its reply unless changed with
+    /// [`WsReplyMatcher::with_timeout`].
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new(rule: impl Fn(&Value) -> WsReplyVerdict + Send + Sync + 'static) -> Self {
+        Self {
+            rule: Arc::new(rule),
+            timeout: Self::DEFAULT_TIMEOUT,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn check(&self, frame: &Value) -> WsReplyVerdict {
+        (self.rule)(frame)
+    }
+
+    /// OKX reply with `event` equal to `event`, such as `"login"` or
+    /// `"subscribe"`, accepted unless it carries a non-zero `code`. An
+    /// `"error"` event rejects the message.
+    pub fn okx_event(event: impl Into<String>) -> Self {
+        let event = event.into();
+        Self::new(move |frame| match frame["event"].as_str() {
+            Some("error") => WsReplyVerdict::Rejected,
+            Some(e) if e == event => match frame["code"].as_str() {
+                None | Some("0") => WsReplyVerdict::Accepted,
+                Some(_) => WsReplyVerdict::Rejected,
+            },
+            _ => WsReplyVerdict::Unrelated,
+        })
+    }
+
+    /// Binance reply with the request's `id`, from market streams or the WS
+    /// API. It is rejected when it carries an `error` or a non-2xx `status`.
+    pub fn binance_id(id: impl Into<Value>) -> Self {
+        let id = id.into();
+        Self::new(move |frame| {
+            if frame["id"] != id {
+                return WsReplyVerdict::Unrelated;
+            }
+
+            let status_ok = frame["status"]
+                .as_u64()
+                .is_none_or(|status| (200..300).contains(&status));
+            if frame["error"].is_null() && status_ok {
+                WsReplyVerdict::Accepted
+            } else {
+                WsReplyVerdict::Rejected
+            }
+        })
+    }
+
+    /// Gate reply on `channel` with `event`, such as `"futures.trades"` and
+    /// `"subscribe"`, rejected when it carries an `error` or a non-success
+    /// `result.status`. WS API replies, such as to `futures.login`, match by
+    /// `header.channel` and are rejected on a non-200 `header.status`.
+    pub fn gate_channel(channel: impl Into<String>, event: impl Into<String>) -> Self {
+        let channel = channel.into();
+        let event = event.into();
+        Self::new(move |frame| {
+            let header = &frame["header"];
+            if header["channel"].as_str() == Some(channel.as_str()) {
+                // Order requests get an ack frame before their response.
+                if frame["ack"].as_bool() == Some(true) {
+                    return WsReplyVerdict::Unrelated;
+                }
+                return match header["status"].as_str() {
+                    Some("200") => WsReplyVerdict::Accepted,
+                    _ => WsReplyVerdict::Rejected,
+                };
+            }
+
+            if frame["channel"].as_str() != Some(channel.as_str())
+                || frame["event"].as_str() != Some(event.as_str())
+            {
+                return WsReplyVerdict::Unrelated;
+            }
+
+            let status_ok = frame["result"]["status"]
+                .as_str()
+                .is_none_or(|status| status == "success");
+            if frame["error"].is_null() && status_ok {
+                WsReplyVerdict::Accepted
+            } else {
+                WsReplyVerdict::Rejected
+            }
+        })
+    }
+
+    /// Hyperliquid `subscriptionResponse` for `method`, such as
+    /// `"subscribe"`. An `error` channel frame rejects the message.
+    pub fn hyperliquid_method(method: impl Into<String>) -> Self {
+        let method = method.into();
+        Self::new(move |frame| match frame["channel"].as_str() {
+            Some("error") => WsReplyVerdict::Rejected,
+            Some("subscriptionResponse")
+                if frame["data"]["method"].as_str() == Some(method.as_str()) =>
+            {
+                WsReplyVerdict::Accepted
+            },
+            _ => WsReplyVerdict::Unrelated,
+        })
+    }
+}
+
+impl fmt::Debug for WsReplyMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WsReplyMatcher")
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
 }
 
 #[cfg(test)]
@@ -289,11 +720,69 @@ mod tests {
                 filter_channels: false,
                 chunk: 1,
                 task_base_id: Some(task_id),
+                shards: None,
             })),
             task_id,
         })
     }
 
+    #[test]
+    fn reconnect_backoff_doubles_up_to_cap() {
+        let policy = WsReconnectPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            jitter: Duration::ZERO,
+            max_attempts: None,
+        };
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn reply_presets_recognize_venue_replies() {
+        use WsReplyVerdict::*;
+        use serde_json::json;
+
+        let binance = WsReplyMatcher::binance_id(3);
+        assert_eq!(binance.check(&json!({"result": null, "id": 3})), Accepted);
+        assert_eq!(binance.check(&json!({"result": null, "id": 4})), Unrelated);
+        assert_eq!(
+            binance.check(&json!({"id": 3, "status": 400, "error": {"code": -1102}})),
+            Rejected
+        );
+
+        let gate = WsReplyMatcher::gate_channel("futures.trades", "subscribe");
+        let reply = |error: Value| {
+            json!({"channel": "futures.trades", "event": "subscribe", "error": error,
+                "result": {"status": "success"}})
+        };
+        assert_eq!(gate.check(&reply(Value::Null)), Accepted);
+        assert_eq!(gate.check(&reply(json!({"code": 2}))), Rejected);
+        assert_eq!(
+            gate.check(&json!({"channel": "futures.trades", "event": "update"})),
+            Unrelated
+        );
+
+        let login = WsReplyMatcher::gate_channel("futures.login", "api");
+        let header = |status| json!({"header": {"channel": "futures.login", "status": status}});
+        assert_eq!(login.check(&header("200")), Accepted);
+        assert_eq!(login.check(&header("401")), Rejected);
+
+        let hyperliquid = WsReplyMatcher::hyperliquid_method("subscribe");
+        assert_eq!(
+            hyperliquid.check(&json!({"channel": "subscriptionResponse",
+                "data": {"method": "subscribe", "subscription": {"type": "trades"}}})),
+            Accepted
+        );
+        assert_eq!(
+            hyperliquid.check(&json!({"channel": "error", "data": "Invalid subscription"})),
+            Rejected
+        );
+    }
+
     #[test]
     #[should_panic(expected = "Duplicate TaskKey in registry")]
     fn duplicate_ws_channel_and_id_panics_across_markets() {
@@ -1,8 +1,15 @@
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, time::Duration};
+use thiserror::Error;
 
-use crate::arch::market_assets::{
-    api_general::OrderParams, base_data::InstrumentKey, market_core::Market,
+use crate::arch::{
+    market_assets::{
+        api_data::account_data::OrderAckData,
+        api_general::{CancelOrderParams, OrderParams},
+        base_data::InstrumentKey,
+        market_core::Market,
+    },
+    strategy_base::risk::RiskRejection,
 };
 
 #[derive(Clone, Debug)]
@@ -46,6 +53,46 @@ pub struct AltOrder {
     pub metadata: HashMap<String, String>,
 }
 
+/// Cancel request sent to an execution task with `TaskCommand::OrderCancel`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AltCancel {
+    pub timestamp: u64,
+    pub market: Market,
+    pub cancel_params: CancelOrderParams,
+    pub metadata: HashMap<String, String>,
+}
+
+/// Request that produced an [`AltOrderAck`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum AltOrderRequest {
+    Place(OrderParams),
+    Cancel(CancelOrderParams),
+}
+
+/// Venue response to one order request sent through an execution task.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AltOrderAck {
+    /// Time the response was received, in microseconds.
+    pub timestamp: u64,
+    pub market: Market,
+    pub request: AltOrderRequest,
+    /// Metadata of the originating [`AltOrder`] or [`AltCancel`].
+    pub metadata: HashMap<String, String>,
+    /// Venue acknowledgement, or why the request failed.
+    pub result: Result<OrderAckData, AltOrderError>,
+}
+
+/// Failure reported in an [`AltOrderAck`].
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum AltOrderError {
+    /// Refused by the pre-trade risk gate; nothing was sent.
+    #[error("{0}")]
+    Risk(RiskRejection),
+    /// The request failed or the venue rejected it.
+    #[error("{0}")]
+    Request(String),
+}
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct AltIntent {
     pub timestamp: u64,
@@ -1,9 +1,15 @@
+use serde::{Deserialize, Serialize};
+
 use crate::arch::{
-    market_assets::{base_data::*, market_core::Market},
+    market_assets::{
+        base_data::*,
+        exchange::{clock_sync::ClockSync, exchange_error::Venue},
+        market_core::Market,
+    },
     task_execution::task_ws::CandleParam,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WsTrade {
     pub timestamp: u64,
     pub market: Market,
@@ -14,7 +20,23 @@ pub struct WsTrade {
     pub trade_id: u64,
 }
 
-#[derive(Clone, Debug)]
+impl WsTrade {
+    /// Microseconds from the venue stamping the trade to `received_at`,
+    /// corrected by the [`ClockSync`] estimate for the venue deployment at
+    /// `rest`, the REST base URL of the client that streams it. `None` for
+    /// markets without a venue clock.
+    pub fn latency_micros(&self, rest: &str, received_at: u64) -> Option<i64> {
+        let venue = Venue::from_market(&self.market)?;
+        Some(ClockSync::latency_micros(
+            venue,
+            rest,
+            self.timestamp,
+            received_at,
+        ))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WsLob {
     pub timestamp: u64,
     pub market: Market,
@@ -26,7 +48,21 @@ pub struct WsLob {
     pub checksum: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+impl WsLob {
+    /// Microseconds from the venue stamping the update to `received_at`,
+    /// see [`WsTrade::latency_micros`].
+    pub fn latency_micros(&self, rest: &str, received_at: u64) -> Option<i64> {
+        let venue = Venue::from_market(&self.market)?;
+        Some(ClockSync::latency_micros(
+            venue,
+            rest,
+            self.timestamp,
+            received_at,
+        ))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LobEventKind {
     Bbo,
     Snapshot,
@@ -34,7 +70,7 @@ pub enum LobEventKind {
     Heartbeat,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LobLevel {
     pub price: f64,
     pub size: f64,
@@ -43,21 +79,37 @@ pub struct LobLevel {
     pub level_update_id: Option<u64>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LobLevelAction {
     Upsert,
     Delete,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LobSeq {
     pub prev: Option<u64>,
     pub first: Option<u64>,
     pub last: Option<u64>,
 }
 
+/// Consistent L2 book view published by book-maintaining websocket tasks.
+///
+/// Levels are `(price, size)` pairs ordered from best to worst. `book_version`
+/// increases by one for every view published for the same instrument by the
+/// same task, so consumers can detect skipped views.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WsLobBook {
+    pub timestamp: u64,
+    pub market: Market,
+    pub inst: String,
+    pub book_version: u64,
+    pub update_id: Option<u64>,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
 /// Market-by-order Level Three websocket event.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WsLobMbo {
     pub timestamp: u64,
     pub market: Market,
@@ -67,7 +119,7 @@ pub struct WsLobMbo {
     pub checksum: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MboUpdate {
     pub timestamp: u64,
     pub price: f64,
@@ -79,14 +131,14 @@ pub struct MboUpdate {
     pub update_id: Option<u64>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum MboAction {
     Add,
     Modify,
     Delete,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WsCandle {
     pub timestamp: u64,
     pub market: Market,
@@ -100,7 +152,7 @@ pub struct WsCandle {
     pub confirm: bool,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WsAccOrder {
     pub timestamp: u64,
     pub market: Market,
@@ -116,7 +168,48 @@ pub struct WsAccOrder {
     pub cli_order_id: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+/// One private execution, published per fill rather than per order update.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WsFill {
+    pub timestamp: u64,
+    pub market: Market,
+    pub inst: String,
+    pub inst_type: InstrumentType,
+    pub side: OrderSide,
+    /// Execution price of this fill.
+    pub price: f64,
+    /// Executed size of this fill, in the venue's order size unit.
+    pub size: f64,
+    /// Fee charged for this fill; negative values are rebates.
+    pub fee: f64,
+    pub fee_currency: Option<String>,
+    pub liquidity: FillLiquidity,
+    /// Exchange trade id, when the venue reports one.
+    pub trade_id: Option<String>,
+    pub order_id: Option<String>,
+    pub cli_order_id: Option<String>,
+}
+
+/// Whether a fill added or removed liquidity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FillLiquidity {
+    Maker,
+    Taker,
+    #[default]
+    Unknown,
+}
+
+impl FillLiquidity {
+    pub(crate) fn from_maker(maker: Option<bool>) -> Self {
+        match maker {
+            Some(true) => FillLiquidity::Maker,
+            Some(false) => FillLiquidity::Taker,
+            None => FillLiquidity::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WsAccBalPos {
     pub timestamp: u64,
     pub market: Market,
@@ -125,13 +218,13 @@ pub struct WsAccBalPos {
     pub positions: Vec<WsAccPosition>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WsAccBalance {
     pub inst: String,
     pub balance: f64,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WsAccPosition {
     pub inst: String,
     pub inst_type: InstrumentType,
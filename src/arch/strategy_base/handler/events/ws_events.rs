@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+/// Connection lifecycle of a websocket relay.
+///
+/// `Connected` is published after every successful handshake. The other
+/// states are only published for connections opened with
+/// `WsConnectTarget::with_reconnect` or `WsConnectTarget::with_listen_key`,
+/// where the relay reconnects by itself.
+/// Strategies should treat `Reconnecting` as a signal to invalidate local
+/// state built from the stream, such as books or last-seen sequence ids.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WsConnectionState {
+    /// The websocket handshake completed.
+    Connected,
+    /// The connection dropped and attempt `attempt` starts after `delay`.
+    Reconnecting { attempt: u32, delay: Duration },
+    /// Login and subscription messages were replayed on the new connection.
+    Resubscribed { replayed: usize },
+    /// The reconnect policy ran out of attempts. The relay falls back to
+    /// emitting `on_ws_event` and waiting for a new connect command.
+    GaveUp { attempts: u32 },
+    /// The relay renewed the connection's listen key.
+    ListenKeyRenewed,
+    /// The listen key expired or could not be renewed. The relay closes the
+    /// connection and reconnects with a new key, starting with `Reconnecting`.
+    ListenKeyExpired,
+}
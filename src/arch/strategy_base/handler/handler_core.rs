@@ -32,22 +32,26 @@ fn record_lag(
     })
 }
 
-async fn dispatch_task_event<S>(strategy: &mut S, event: TaskEvent)
+pub(crate) async fn dispatch_task_event<S>(strategy: &mut S, event: TaskEvent)
 where
     S: Strategy,
 {
     match event {
         TaskEvent::Alt(msg) => strategy.on_alt_event(msg).await,
         TaskEvent::Ws(msg) => strategy.on_ws_event(msg).await,
+        TaskEvent::WsConnection(msg) => strategy.on_ws_connection(msg).await,
         TaskEvent::OrderExecute(msg) => strategy.on_order_execution(msg).await,
+        TaskEvent::OrderAck(msg) => strategy.on_order_ack(msg).await,
         TaskEvent::InstIntent(msg) => strategy.on_inst_intent(msg).await,
         TaskEvent::ModelPreds(msg) => strategy.on_preds(msg).await,
         TaskEvent::Schedule(msg) => strategy.on_schedule(msg).await,
         TaskEvent::Trade(msg) => strategy.on_trade(msg).await,
         TaskEvent::Lob(msg) => strategy.on_lob(msg).await,
+        TaskEvent::LobBook(msg) => strategy.on_lob_book(msg).await,
         TaskEvent::LobMbo(msg) => strategy.on_lob_mbo(msg).await,
         TaskEvent::Candle(msg) => strategy.on_candle(msg).await,
         TaskEvent::AccOrder(msg) => strategy.on_acc_order(msg).await,
+        TaskEvent::AccFill(msg) => strategy.on_acc_fill(msg).await,
         TaskEvent::AccBalPos(msg) => strategy.on_acc_bal_pos(msg).await,
         TaskEvent::AccPos(msg) => strategy.on_acc_pos(msg).await,
     }
@@ -245,6 +249,7 @@ mod tests {
     fn schedule_event(task_id: u64) -> TaskEvent {
         TaskEvent::Schedule(InfraMsg {
             task_id,
+            received_at: 0,
             data: Arc::new(AltScheduleEvent {
                 timestamp: task_id,
                 duration: Duration::from_secs(1),
@@ -467,6 +472,7 @@ mod tests {
             .unwrap()
             .send(TaskEvent::Trade(InfraMsg {
                 task_id: 1,
+                received_at: 0,
                 data: Arc::new(Vec::new()),
             }))
             .unwrap();
@@ -475,6 +481,7 @@ mod tests {
             .unwrap()
             .send(TaskEvent::Lob(InfraMsg {
                 task_id: 2,
+                received_at: 0,
                 data: Arc::new(Vec::new()),
             }))
             .unwrap();
@@ -570,6 +577,7 @@ mod tests {
                 .unwrap(),
             Some(MuxItem::Event(TaskEvent::Schedule(InfraMsg {
                 task_id: 3,
+                received_at: 0,
                 ..
             })))
         ));
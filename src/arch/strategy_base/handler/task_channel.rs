@@ -7,8 +7,12 @@ use tokio::sync::broadcast;
 
 use crate::arch::{
     strategy_base::handler::events::{
-        alt_events::{AltIntent, AltOrder, AltScheduleEvent, AltTensor},
-        lob_events::{WsAccBalPos, WsAccOrder, WsAccPosition, WsCandle, WsLob, WsLobMbo, WsTrade},
+        alt_events::{AltIntent, AltOrder, AltOrderAck, AltScheduleEvent, AltTensor},
+        lob_events::{
+            WsAccBalPos, WsAccOrder, WsAccPosition, WsCandle, WsFill, WsLob, WsLobBook, WsLobMbo,
+            WsTrade,
+        },
+        ws_events::WsConnectionState,
     },
     task_execution::{
         TaskKey,
@@ -31,6 +35,7 @@ const LOB_CHANNEL_CAPACITY: usize = 16_384;
 const LOB_MBO_CHANNEL_CAPACITY: usize = 65_536;
 const CANDLE_CHANNEL_CAPACITY: usize = 2_048;
 const ACC_ORDER_CHANNEL_CAPACITY: usize = 8_192;
+const ACC_FILL_CHANNEL_CAPACITY: usize = 8_192;
 const ACC_BAL_POS_CHANNEL_CAPACITY: usize = 8_192;
 const ACC_POS_CHANNEL_CAPACITY: usize = 8_192;
 
@@ -41,19 +46,31 @@ const ACC_POS_CHANNEL_CAPACITY: usize = 8_192;
 pub(crate) enum TaskEvent {
     Alt(InfraMsg<AltTaskInfo>),
     Ws(InfraMsg<WsTaskInfo>),
+    WsConnection(InfraMsg<WsConnectionState>),
     OrderExecute(InfraMsg<Vec<AltOrder>>),
+    OrderAck(InfraMsg<Vec<AltOrderAck>>),
     InstIntent(InfraMsg<AltIntent>),
     ModelPreds(InfraMsg<AltTensor>),
     Schedule(InfraMsg<AltScheduleEvent>),
     Trade(InfraMsg<Vec<WsTrade>>),
     Lob(InfraMsg<Vec<WsLob>>),
+    LobBook(InfraMsg<Vec<WsLobBook>>),
     LobMbo(InfraMsg<Vec<WsLobMbo>>),
     Candle(InfraMsg<Vec<WsCandle>>),
     AccOrder(InfraMsg<Vec<WsAccOrder>>),
+    AccFill(InfraMsg<Vec<WsFill>>),
     AccBalPos(InfraMsg<Vec<WsAccBalPos>>),
     AccPos(InfraMsg<Vec<WsAccPosition>>),
 }
 
+/// Task event tagged with the task that published it.
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+pub struct RoutedEvent {
+    pub(crate) key: TaskKey,
+    pub(crate) event: TaskEvent,
+}
+
 pub(crate) struct TaskReceiver {
     pub(crate) key: TaskKey,
     pub(crate) receiver: broadcast::Receiver<TaskEvent>,
@@ -141,6 +158,10 @@ fn capacity_for(key: &TaskKey) -> usize {
     match key {
         TaskKey::Alt { alt_task_type, .. } => match alt_task_type {
             AltTaskType::OrderExecution => ORDER_EXECUTION_CHANNEL_CAPACITY,
+            #[cfg(feature = "lob_clients")]
+            AltTaskType::LobExecution => ORDER_EXECUTION_CHANNEL_CAPACITY,
+            #[cfg(feature = "lob_clients")]
+            AltTaskType::CancelAfter(_) => SCHEDULE_CHANNEL_CAPACITY,
             AltTaskType::InstIntent => INST_INTENT_CHANNEL_CAPACITY,
             #[cfg(any(feature = "model_onnx", feature = "model_zmq"))]
             AltTaskType::ModelPreds(_) => MODEL_PREDS_CHANNEL_CAPACITY,
@@ -148,12 +169,14 @@ fn capacity_for(key: &TaskKey) -> usize {
         },
         TaskKey::Ws { ws_channel, .. } => match ws_channel {
             WsChannel::AccountOrders => ACC_ORDER_CHANNEL_CAPACITY,
+            WsChannel::AccountFills => ACC_FILL_CHANNEL_CAPACITY,
             WsChannel::AccountBalAndPos => ACC_BAL_POS_CHANNEL_CAPACITY,
             WsChannel::AccountPositions => ACC_POS_CHANNEL_CAPACITY,
             WsChannel::Candles(_) => CANDLE_CHANNEL_CAPACITY,
             WsChannel::Trades(_) => TRADE_CHANNEL_CAPACITY,
             WsChannel::Lob(_) => LOB_CHANNEL_CAPACITY,
             WsChannel::LobMbo => LOB_MBO_CHANNEL_CAPACITY,
+            WsChannel::OrderEntry => ORDER_EXECUTION_CHANNEL_CAPACITY,
             WsChannel::Other(_) => WS_EVENT_CHANNEL_CAPACITY,
         },
     }
@@ -172,6 +195,7 @@ mod tests {
     fn schedule_event(task_id: u64) -> TaskEvent {
         TaskEvent::Schedule(InfraMsg {
             task_id,
+            received_at: 0,
             data: Arc::new(AltScheduleEvent {
                 timestamp: task_id,
                 duration: Duration::from_secs(1),
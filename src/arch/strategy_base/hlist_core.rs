@@ -1,12 +1,15 @@
 use std::sync::Arc;
 
+use tokio::task::JoinHandle;
+
 use crate::arch::{
     strategy_base::{
         command::command_core::CommandRegistry,
         handler::{
             alt_events::*,
             lob_events::*,
-            task_channel::{InfraMsg, TaskChannels},
+            task_channel::{InfraMsg, RoutedEvent, TaskChannels},
+            ws_events::*,
         },
     },
     task_execution::{task_alt::AltTaskInfo, task_ws::WsTaskInfo},
@@ -19,7 +22,12 @@ pub struct HNil;
 impl Strategy for HNil {
     async fn initialize(&mut self) {}
 
-    async fn _spawn_strategy_tasks(&self, _task_channels: &Arc<TaskChannels>) {}
+    async fn _spawn_strategy_tasks(
+        &self,
+        _task_channels: &Arc<TaskChannels>,
+    ) -> Vec<JoinHandle<()>> {
+        Vec::new()
+    }
 }
 impl CommandEmitter for HNil {
     fn command_init(&mut self, _command_handle: Arc<CommandRegistry>) {}
@@ -46,10 +54,26 @@ where
         tokio::join!(fut_head, fut_tail);
     }
 
-    async fn _spawn_strategy_tasks(&self, task_channels: &Arc<TaskChannels>) {
+    async fn on_shutdown(&mut self) {
+        // The tail holds earlier registrations.
+        self.tail.on_shutdown().await;
+        self.head.on_shutdown().await;
+    }
+
+    async fn _spawn_strategy_tasks(
+        &self,
+        task_channels: &Arc<TaskChannels>,
+    ) -> Vec<JoinHandle<()>> {
         let HCons { head, tail } = self;
-        head._spawn_strategy_tasks(task_channels).await;
-        tail._spawn_strategy_tasks(task_channels).await;
+        let mut handles = head._spawn_strategy_tasks(task_channels).await;
+        handles.extend(tail._spawn_strategy_tasks(task_channels).await);
+        handles
+    }
+
+    async fn _dispatch_event(&mut self, event: RoutedEvent) {
+        // The tail holds earlier registrations.
+        self.tail._dispatch_event(event.clone()).await;
+        self.head._dispatch_event(event).await;
     }
 }
 impl<Head, Tail> CommandEmitter for HCons<Head, Tail>
@@ -84,6 +108,12 @@ where
         tokio::join!(fut_head, fut_tail);
     }
 
+    async fn on_order_ack(&mut self, msg: InfraMsg<Vec<AltOrderAck>>) {
+        let fut_head = self.head.on_order_ack(msg.clone());
+        let fut_tail = self.tail.on_order_ack(msg);
+        tokio::join!(fut_head, fut_tail);
+    }
+
     async fn on_inst_intent(&mut self, msg: InfraMsg<AltIntent>) {
         let fut_head = self.head.on_inst_intent(msg.clone());
         let fut_tail = self.tail.on_inst_intent(msg);
@@ -108,6 +138,12 @@ where
         tokio::join!(fut_head, fut_tail);
     }
 
+    async fn on_ws_connection(&mut self, msg: InfraMsg<WsConnectionState>) {
+        let fut_head = self.head.on_ws_connection(msg.clone());
+        let fut_tail = self.tail.on_ws_connection(msg);
+        tokio::join!(fut_head, fut_tail);
+    }
+
     async fn on_trade(&mut self, msg: InfraMsg<Vec<WsTrade>>) {
         let fut_head = self.head.on_trade(msg.clone());
         let fut_tail = self.tail.on_trade(msg);
@@ -120,6 +156,12 @@ where
         tokio::join!(fut_head, fut_tail);
     }
 
+    async fn on_lob_book(&mut self, msg: InfraMsg<Vec<WsLobBook>>) {
+        let fut_head = self.head.on_lob_book(msg.clone());
+        let fut_tail = self.tail.on_lob_book(msg);
+        tokio::join!(fut_head, fut_tail);
+    }
+
     async fn on_lob_mbo(&mut self, msg: InfraMsg<Vec<WsLobMbo>>) {
         let fut_head = self.head.on_lob_mbo(msg.clone());
         let fut_tail = self.tail.on_lob_mbo(msg);
@@ -138,6 +180,12 @@ where
         tokio::join!(fut_head, fut_tail);
     }
 
+    async fn on_acc_fill(&mut self, msg: InfraMsg<Vec<WsFill>>) {
+        let fut_head = self.head.on_acc_fill(msg.clone());
+        let fut_tail = self.tail.on_acc_fill(msg);
+        tokio::join!(fut_head, fut_tail);
+    }
+
     async fn on_acc_bal_pos(&mut self, msg: InfraMsg<Vec<WsAccBalPos>>) {
         let fut_head = self.head.on_acc_bal_pos(msg.clone());
         let fut_tail = self.tail.on_acc_bal_pos(msg);
@@ -168,8 +216,12 @@ mod tests {
     impl Strategy for ProbeStrategy {
         async fn initialize(&mut self) {}
 
-        async fn _spawn_strategy_tasks(&self, _task_channels: &Arc<TaskChannels>) {
+        async fn _spawn_strategy_tasks(
+            &self,
+            _task_channels: &Arc<TaskChannels>,
+        ) -> Vec<JoinHandle<()>> {
             self.spawn_count.fetch_add(1, Ordering::SeqCst);
+            Vec::new()
         }
     }
 
@@ -0,0 +1,805 @@
+//! Order management: tracks each order through its lifecycle.
+//!
+//! [`OrderManager`] keys orders by client order id. Feed it the orders a
+//! strategy sends, the acknowledgements returned by `place_order`, websocket
+//! updates from `on_acc_order`, and REST snapshots from `get_open_orders` and
+//! `get_order_history`. Each input returns the [`OrderTransition`]s it caused,
+//! which the strategy handles like any other event.
+//!
+//! States only move forward:
+//!
+//! ```text
+//! New -> Acked -> PartiallyFilled -> Filled | Canceled | Rejected | Expired
+//! ```
+//!
+//! Steps may be skipped, so a `New` order can go straight to `Filled` or
+//! `Rejected`. `PartiallyFilled` may repeat while the filled size grows. An update that
+//! would move an order backwards, or does not grow its filled size, is stale
+//! or duplicated and is ignored. Venues deliver websocket and REST updates on
+//! different paths, so this rule, rather than timestamps, decides ordering.
+
+use std::collections::HashMap;
+
+use crate::arch::{
+    market_assets::{
+        api_data::account_data::{OrderAckData, OrderDetailData},
+        api_general::OrderParams,
+        base_data::{OrderSide, OrderStatus, OrderType},
+        market_core::Market,
+    },
+    strategy_base::handler::lob_events::WsAccOrder,
+    traits::market_lob::LobPrivateRest,
+};
+use crate::errors::{InfraError, InfraResult};
+
+/// Lifecycle state of a tracked order.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum OrderState {
+    /// Sent by the strategy, not yet acknowledged by the venue.
+    New,
+    /// Resting on the venue with nothing filled.
+    Acked,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Rejected,
+    Expired,
+}
+
+impl OrderState {
+    /// Maps a venue status to a state. A live order with a fill counts as
+    /// partially filled. `Unknown` has no state.
+    pub fn from_status(status: &OrderStatus, filled_size: f64) -> Option<Self> {
+        match status {
+            OrderStatus::Live if filled_size > 0.0 => Some(OrderState::PartiallyFilled),
+            OrderStatus::Live => Some(OrderState::Acked),
+            OrderStatus::PartiallyFilled => Some(OrderState::PartiallyFilled),
+            OrderStatus::Filled => Some(OrderState::Filled),
+            OrderStatus::Canceled => Some(OrderState::Canceled),
+            OrderStatus::Rejected => Some(OrderState::Rejected),
+            OrderStatus::Expired => Some(OrderState::Expired),
+            OrderStatus::Unknown => None,
+        }
+    }
+
+    /// Returns `true` once the order can no longer change.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            OrderState::Filled | OrderState::Canceled | OrderState::Rejected | OrderState::Expired
+        )
+    }
+
+    /// Returns `true` while the order may still fill.
+    pub fn is_open(&self) -> bool {
+        !self.is_terminal()
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            OrderState::New => 0,
+            OrderState::Acked => 1,
+            OrderState::PartiallyFilled => 2,
+            _ => 3,
+        }
+    }
+}
+
+/// Where an order update came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpdateSource {
+    /// [`OrderManager::submit`].
+    Submit,
+    /// A REST order acknowledgement.
+    Ack,
+    /// A websocket account order update.
+    Ws,
+    /// A REST open-order or order-history snapshot.
+    Rest,
+}
+
+/// One order tracked by an [`OrderManager`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackedOrder {
+    pub client_order_id: String,
+    pub order_id: Option<String>,
+    pub market: Market,
+    pub inst: String,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    /// Limit price. `None` for market orders.
+    pub price: Option<f64>,
+    pub size: f64,
+    pub filled_size: f64,
+    /// Average fill price, when the venue reports it.
+    pub avg_price: Option<f64>,
+    pub state: OrderState,
+    /// Venue message attached to a rejection.
+    pub reject_reason: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl TrackedOrder {
+    pub fn remaining_size(&self) -> f64 {
+        (self.size - self.filled_size).max(0.0)
+    }
+}
+
+/// A state change of one order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderTransition {
+    pub client_order_id: String,
+    pub order_id: Option<String>,
+    pub market: Market,
+    pub inst: String,
+    /// Previous state, or `None` when the order was first seen in this update.
+    pub from: Option<OrderState>,
+    pub to: OrderState,
+    /// Cumulative filled size after the transition.
+    pub filled_size: f64,
+    /// Size filled by this transition alone.
+    pub last_fill_size: f64,
+    pub timestamp: u64,
+    pub source: UpdateSource,
+}
+
+/// Result of [`OrderManager::reconcile_open_orders`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReconcileReport {
+    pub transitions: Vec<OrderTransition>,
+    /// Client order ids of orders the manager still considers open, but the
+    /// snapshot no longer lists. Look them up in the order history.
+    pub missing: Vec<String>,
+}
+
+/// Normalized venue view of one order, built from any update source.
+struct OrderUpdate<'a> {
+    market: Market,
+    inst: &'a str,
+    order_id: Option<&'a str>,
+    client_order_id: Option<&'a str>,
+    side: &'a OrderSide,
+    order_type: &'a OrderType,
+    price: f64,
+    size: f64,
+    filled_size: f64,
+    avg_price: Option<f64>,
+    status: &'a OrderStatus,
+    timestamp: u64,
+}
+
+impl<'a> OrderUpdate<'a> {
+    fn from_ws(order: &'a WsAccOrder) -> Self {
+        Self {
+            market: order.market.clone(),
+            inst: &order.inst,
+            order_id: order.order_id.as_deref(),
+            client_order_id: order.cli_order_id.as_deref(),
+            side: &order.side,
+            order_type: &order.order_type,
+            price: order.price,
+            size: order.size,
+            filled_size: order.filled_size,
+            avg_price: None,
+            status: &order.status,
+            timestamp: order.timestamp,
+        }
+    }
+
+    fn from_rest(market: Market, order: &'a OrderDetailData) -> Self {
+        Self {
+            market,
+            inst: &order.inst,
+            order_id: Some(&order.order_id),
+            client_order_id: order.cli_order_id.as_deref(),
+            side: &order.side,
+            order_type: &order.order_type,
+            price: order.price,
+            size: order.size,
+            filled_size: order.executed_size,
+            avg_price: (order.avg_price > 0.0).then_some(order.avg_price),
+            status: &order.order_status,
+            timestamp: order.update_time.max(order.timestamp),
+        }
+    }
+}
+
+/// Tracks orders by client order id and derives their state transitions.
+///
+/// Orders that reach the manager only through venue updates, such as orders
+/// placed from another session, are adopted. Adopted orders without a client
+/// order id are keyed by their exchange order id.
+#[derive(Clone, Debug)]
+pub struct OrderManager {
+    orders: HashMap<String, TrackedOrder>,
+    by_order_id: HashMap<String, String>,
+    id_prefix: String,
+    next_id: u64,
+}
+
+impl Default for OrderManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderManager {
+    pub fn new() -> Self {
+        Self {
+            orders: HashMap::new(),
+            by_order_id: HashMap::new(),
+            id_prefix: "oms".into(),
+            next_id: 0,
+        }
+    }
+
+    /// Sets the prefix of generated client order ids. Venues restrict the
+    /// characters and length they accept, so keep it short and alphanumeric.
+    pub fn with_id_prefix(mut self, id_prefix: impl Into<String>) -> Self {
+        self.id_prefix = id_prefix.into();
+        self
+    }
+
+    /// Starts tracking an order about to be sent.
+    ///
+    /// Assigns a client order id when `params` has none, so send `params`
+    /// after this call. Fails on a client order id that is already tracked,
+    /// or on a size or price that does not parse.
+    pub fn submit(
+        &mut self,
+        market: Market,
+        params: &mut OrderParams,
+        timestamp: u64,
+    ) -> InfraResult<OrderTransition> {
+        let client_order_id = match &params.client_order_id {
+            Some(id) => id.clone(),
+            None => loop {
+                self.next_id += 1;
+                let id = format!("{}{}", self.id_prefix, self.next_id);
+                if !self.orders.contains_key(&id) {
+                    break id;
+                }
+            },
+        };
+        if self.orders.contains_key(&client_order_id) {
+            return Err(InfraError::Msg(format!(
+                "Client order id already tracked: {client_order_id}"
+            )));
+        }
+
+        let size = parse_number("size", &params.size)?;
+        let price = params
+            .price
+            .as_deref()
+            .map(|price| parse_number("price", price))
+            .transpose()?;
+        params.client_order_id = Some(client_order_id.clone());
+
+        let order = TrackedOrder {
+            client_order_id: client_order_id.clone(),
+            order_id: None,
+            market,
+            inst: params.inst.clone(),
+            side: params.side.clone(),
+            order_type: params.order_type.clone(),
+            price,
+            size,
+            filled_size: 0.0,
+            avg_price: None,
+            state: OrderState::New,
+            reject_reason: None,
+            created_at: timestamp,
+            updated_at: timestamp,
+        };
+        let transition = transition(&order, None, 0.0, UpdateSource::Submit);
+        self.orders.insert(client_order_id, order);
+        Ok(transition)
+    }
+
+    /// Applies a REST acknowledgement from `place_order` or `cancel_order`.
+    ///
+    /// Returns `None` for unknown orders and for acks that change nothing.
+    pub fn on_ack(&mut self, ack: &OrderAckData) -> Option<OrderTransition> {
+        let key = self.resolve(ack.cli_order_id.as_deref(), Some(&ack.order_id))?;
+        if !ack.order_id.is_empty() {
+            self.index_order_id(&key, &ack.order_id);
+        }
+
+        let order = self.orders.get_mut(&key)?;
+        let to = OrderState::from_status(&ack.order_status, order.filled_size)?;
+        if to == OrderState::Rejected {
+            order.reject_reason = ack.msg.clone();
+        }
+        let filled_size = order.filled_size;
+        advance(order, to, filled_size, ack.timestamp, UpdateSource::Ack)
+    }
+
+    /// Marks a locally tracked order as rejected before it reached the venue,
+    /// for example when `place_order` returned an error.
+    pub fn reject(
+        &mut self,
+        client_order_id: &str,
+        reason: impl Into<String>,
+        timestamp: u64,
+    ) -> Option<OrderTransition> {
+        let order = self.orders.get_mut(client_order_id)?;
+        order.reject_reason = Some(reason.into());
+        let filled_size = order.filled_size;
+        advance(
+            order,
+            OrderState::Rejected,
+            filled_size,
+            timestamp,
+            UpdateSource::Submit,
+        )
+    }
+
+    /// Applies websocket order updates from `on_acc_order`.
+    pub fn on_ws_orders(&mut self, orders: &[WsAccOrder]) -> Vec<OrderTransition> {
+        orders
+            .iter()
+            .filter_map(|order| self.apply(OrderUpdate::from_ws(order), UpdateSource::Ws))
+            .collect()
+    }
+
+    /// Applies order details from `get_order_history` or single order
+    /// lookups.
+    pub fn on_rest_orders(
+        &mut self,
+        market: Market,
+        orders: &[OrderDetailData],
+    ) -> Vec<OrderTransition> {
+        orders
+            .iter()
+            .filter_map(|order| {
+                self.apply(
+                    OrderUpdate::from_rest(market.clone(), order),
+                    UpdateSource::Rest,
+                )
+            })
+            .collect()
+    }
+
+    /// Applies a complete `get_open_orders` snapshot for one instrument.
+    ///
+    /// Orders acknowledged earlier but absent from the snapshot were closed
+    /// while updates were missed; they are listed in
+    /// [`ReconcileReport::missing`]. Orders still in `New` are not listed,
+    /// since their placement may not have reached the venue yet.
+    pub fn reconcile_open_orders(
+        &mut self,
+        market: Market,
+        inst: &str,
+        open_orders: &[OrderDetailData],
+    ) -> ReconcileReport {
+        let transitions = self.on_rest_orders(market.clone(), open_orders);
+
+        let mut missing: Vec<_> = self
+            .orders
+            .values()
+            .filter(|order| {
+                order.market == market
+                    && order.inst == inst
+                    && matches!(order.state, OrderState::Acked | OrderState::PartiallyFilled)
+                    && !open_orders.iter().any(|open| {
+                        order.order_id.as_deref() == Some(open.order_id.as_str())
+                            || open.cli_order_id.as_deref() == Some(order.client_order_id.as_str())
+                    })
+            })
+            .map(|order| order.client_order_id.clone())
+            .collect();
+        missing.sort();
+
+        ReconcileReport {
+            transitions,
+            missing,
+        }
+    }
+
+    /// Fetches open orders for `inst` and looks up the final state of every
+    /// order missing from them.
+    ///
+    /// Missing orders without an exchange order id, or absent from the
+    /// history too, keep their state.
+    pub async fn reconcile<C: LobPrivateRest>(
+        &mut self,
+        client: &C,
+        market: Market,
+        inst: &str,
+    ) -> InfraResult<Vec<OrderTransition>> {
+        let open_orders = client.get_open_orders(inst, None).await?;
+        let report = self.reconcile_open_orders(market.clone(), inst, &open_orders);
+        let mut transitions = report.transitions;
+
+        for client_order_id in report.missing {
+            let Some(order_id) = self
+                .orders
+                .get(&client_order_id)
+                .and_then(|order| order.order_id.clone())
+            else {
+                continue;
+            };
+            let history = client
+                .get_order_history(inst, None, None, None, Some(&order_id))
+                .await?;
+            transitions.extend(self.on_rest_orders(market.clone(), &history));
+        }
+        Ok(transitions)
+    }
+
+    pub fn get(&self, client_order_id: &str) -> Option<&TrackedOrder> {
+        self.orders.get(client_order_id)
+    }
+
+    pub fn get_by_order_id(&self, order_id: &str) -> Option<&TrackedOrder> {
+        self.orders.get(self.by_order_id.get(order_id)?)
+    }
+
+    /// All orders that are not in a terminal state.
+    pub fn open_orders(&self) -> impl Iterator<Item = &TrackedOrder> {
+        self.orders.values().filter(|order| order.state.is_open())
+    }
+
+    /// Open orders on one market.
+    pub fn open_orders_on(&self, market: &Market) -> impl Iterator<Item = &TrackedOrder> {
+        self.open_orders()
+            .filter(move |order| &order.market == market)
+    }
+
+    /// Open orders for one instrument on one market.
+    pub fn open_orders_for<'a>(
+        &'a self,
+        market: &'a Market,
+        inst: &'a str,
+    ) -> impl Iterator<Item = &'a TrackedOrder> {
+        self.open_orders_on(market)
+            .filter(move |order| order.inst == inst)
+    }
+
+    /// Forgets terminal orders last updated before `timestamp`.
+    pub fn prune_terminal(&mut self, timestamp: u64) {
+        self.orders
+            .retain(|_, order| order.state.is_open() || order.updated_at >= timestamp);
+        let orders = &self.orders;
+        self.by_order_id
+            .retain(|_, client_order_id| orders.contains_key(client_order_id));
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    fn apply(&mut self, update: OrderUpdate<'_>, source: UpdateSource) -> Option<OrderTransition> {
+        let to = OrderState::from_status(update.status, update.filled_size)?;
+        let key = match self.resolve(update.client_order_id, update.order_id) {
+            Some(key) => key,
+            None => return self.adopt(update, to, source),
+        };
+        if let Some(order_id) = update.order_id {
+            self.index_order_id(&key, order_id);
+        }
+
+        let order = self.orders.get_mut(&key)?;
+        if let Some(avg_price) = update.avg_price
+            && update.filled_size >= order.filled_size
+        {
+            order.avg_price = Some(avg_price);
+        }
+        advance(order, to, update.filled_size, update.timestamp, source)
+    }
+
+    fn adopt(
+        &mut self,
+        update: OrderUpdate<'_>,
+        state: OrderState,
+        source: UpdateSource,
+    ) -> Option<OrderTransition> {
+        let key = update.client_order_id.or(update.order_id)?.to_string();
+        let order = TrackedOrder {
+            client_order_id: key.clone(),
+            order_id: update.order_id.map(str::to_string),
+            market: update.market,
+            inst: update.inst.to_string(),
+            side: update.side.clone(),
+            order_type: update.order_type.clone(),
+            price: (update.price > 0.0).then_some(update.price),
+            size: update.size,
+            filled_size: update.filled_size,
+            avg_price: update.avg_price,
+            state,
+            reject_reason: None,
+            created_at: update.timestamp,
+            updated_at: update.timestamp,
+        };
+        let transition = transition(&order, None, update.filled_size, source);
+        if let Some(order_id) = update.order_id {
+            self.by_order_id.insert(order_id.to_string(), key.clone());
+        }
+        self.orders.insert(key, order);
+        Some(transition)
+    }
+
+    fn resolve(&self, client_order_id: Option<&str>, order_id: Option<&str>) -> Option<String> {
+        if let Some(id) = client_order_id
+            && self.orders.contains_key(id)
+        {
+            return Some(id.to_string());
+        }
+        order_id.and_then(|id| self.by_order_id.get(id).cloned())
+    }
+
+    fn index_order_id(&mut self, client_order_id: &str, order_id: &str) {
+        if let Some(order) = self.orders.get_mut(client_order_id)
+            && order.order_id.is_none()
+        {
+            order.order_id = Some(order_id.to_string());
+            self.by_order_id
+                .insert(order_id.to_string(), client_order_id.to_string());
+        }
+    }
+}
+
+/// Moves `order` to `to` if that is a forward step, returning the transition.
+fn advance(
+    order: &mut TrackedOrder,
+    to: OrderState,
+    filled_size: f64,
+    timestamp: u64,
+    source: UpdateSource,
+) -> Option<OrderTransition> {
+    let from = order.state;
+    if from.is_terminal() {
+        return None;
+    }
+
+    let fills = filled_size > order.filled_size;
+    let forward = to.rank() > from.rank() || (to == OrderState::PartiallyFilled && fills);
+    if !forward {
+        return None;
+    }
+
+    let last_fill_size = (filled_size - order.filled_size).max(0.0);
+    order.filled_size = order.filled_size.max(filled_size);
+    order.state = to;
+    order.updated_at = order.updated_at.max(timestamp);
+    Some(OrderTransition {
+        timestamp,
+        ..transition(order, Some(from), last_fill_size, source)
+    })
+}
+
+fn transition(
+    order: &TrackedOrder,
+    from: Option<OrderState>,
+    last_fill_size: f64,
+    source: UpdateSource,
+) -> OrderTransition {
+    OrderTransition {
+        client_order_id: order.client_order_id.clone(),
+        order_id: order.order_id.clone(),
+        market: order.market.clone(),
+        inst: order.inst.clone(),
+        from,
+        to: order.state,
+        filled_size: order.filled_size,
+        last_fill_size,
+        timestamp: order.updated_at,
+        source,
+    }
+}
+
+fn parse_number(field: &str, value: &str) -> InfraResult<f64> {
+    value
+        .parse()
+        .map_err(|_| InfraError::Msg(format!("Invalid order {field}: {value}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::market_assets::base_data::InstrumentType;
+
+    const INST: &str = "BTC-USDT-SWAP";
+
+    fn params(client_order_id: Option<&str>) -> OrderParams {
+        OrderParams {
+            inst: INST.into(),
+            side: OrderSide::BUY,
+            size: "2".into(),
+            order_type: OrderType::Limit,
+            price: Some("100".into()),
+            client_order_id: client_order_id.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn ack(client_order_id: &str, status: OrderStatus) -> OrderAckData {
+        OrderAckData {
+            timestamp: 2,
+            order_status: status,
+            order_id: "ex-1".into(),
+            cli_order_id: Some(client_order_id.into()),
+            msg: None,
+        }
+    }
+
+    fn ws(order_id: &str, status: OrderStatus, filled_size: f64, timestamp: u64) -> WsAccOrder {
+        WsAccOrder {
+            timestamp,
+            market: Market::Okx,
+            inst: INST.into(),
+            inst_type: InstrumentType::Perpetual,
+            price: 100.0,
+            size: 2.0,
+            filled_size,
+            side: OrderSide::BUY,
+            status,
+            order_type: OrderType::Limit,
+            order_id: Some(order_id.into()),
+            cli_order_id: None,
+        }
+    }
+
+    fn detail(order_id: &str, status: OrderStatus, executed_size: f64) -> OrderDetailData {
+        OrderDetailData {
+            timestamp: 1,
+            inst: INST.into(),
+            order_id: order_id.into(),
+            side: OrderSide::BUY,
+            order_type: OrderType::Limit,
+            order_status: status,
+            price: 100.0,
+            avg_price: if executed_size > 0.0 { 100.0 } else { 0.0 },
+            size: 2.0,
+            executed_size,
+            update_time: 10,
+            ..Default::default()
+        }
+    }
+
+    fn states(transitions: &[OrderTransition]) -> Vec<(Option<OrderState>, OrderState)> {
+        transitions.iter().map(|t| (t.from, t.to)).collect()
+    }
+
+    #[test]
+    fn order_moves_through_lifecycle() {
+        let mut oms = OrderManager::new().with_id_prefix("s1");
+        let mut order = params(None);
+        let new = oms.submit(Market::Okx, &mut order, 1).unwrap();
+        assert_eq!(order.client_order_id.as_deref(), Some("s11"));
+        assert_eq!((new.from, new.to), (None, OrderState::New));
+
+        let acked = oms.on_ack(&ack("s11", OrderStatus::Live)).unwrap();
+        assert_eq!(acked.to, OrderState::Acked);
+        assert_eq!(acked.order_id.as_deref(), Some("ex-1"));
+
+        let transitions = oms.on_ws_orders(&[
+            ws("ex-1", OrderStatus::PartiallyFilled, 0.5, 3),
+            ws("ex-1", OrderStatus::PartiallyFilled, 1.5, 4),
+            ws("ex-1", OrderStatus::Filled, 2.0, 5),
+        ]);
+        assert_eq!(
+            states(&transitions),
+            [
+                (Some(OrderState::Acked), OrderState::PartiallyFilled),
+                (
+                    Some(OrderState::PartiallyFilled),
+                    OrderState::PartiallyFilled
+                ),
+                (Some(OrderState::PartiallyFilled), OrderState::Filled),
+            ]
+        );
+        let fills: Vec<_> = transitions.iter().map(|t| t.last_fill_size).collect();
+        assert_eq!(fills, [0.5, 1.0, 0.5]);
+        assert_eq!(oms.open_orders().count(), 0);
+        assert_eq!(oms.get_by_order_id("ex-1").unwrap().filled_size, 2.0);
+    }
+
+    #[test]
+    fn stale_and_duplicate_updates_are_ignored() {
+        let mut oms = OrderManager::new();
+        oms.submit(Market::Okx, &mut params(Some("a")), 1).unwrap();
+        oms.on_ack(&ack("a", OrderStatus::Live)).unwrap();
+
+        let transitions = oms.on_ws_orders(&[
+            ws("ex-1", OrderStatus::PartiallyFilled, 1.0, 4),
+            ws("ex-1", OrderStatus::PartiallyFilled, 1.0, 4),
+            ws("ex-1", OrderStatus::Live, 0.0, 2),
+            ws("ex-1", OrderStatus::PartiallyFilled, 0.5, 3),
+            ws("ex-1", OrderStatus::Canceled, 1.0, 6),
+            ws("ex-1", OrderStatus::Filled, 2.0, 7),
+        ]);
+        assert_eq!(
+            states(&transitions),
+            [
+                (Some(OrderState::Acked), OrderState::PartiallyFilled),
+                (Some(OrderState::PartiallyFilled), OrderState::Canceled),
+            ]
+        );
+        let order = oms.get("a").unwrap();
+        assert_eq!(order.state, OrderState::Canceled);
+        assert_eq!(order.filled_size, 1.0);
+        assert_eq!(order.remaining_size(), 1.0);
+        assert!(oms.on_ack(&ack("a", OrderStatus::Live)).is_none());
+    }
+
+    #[test]
+    fn rejections_record_reason() {
+        let mut oms = OrderManager::new();
+        oms.submit(Market::Okx, &mut params(Some("a")), 1).unwrap();
+        oms.submit(Market::Okx, &mut params(Some("b")), 1).unwrap();
+        assert!(oms.submit(Market::Okx, &mut params(Some("a")), 1).is_err());
+
+        let rejected = oms
+            .on_ack(&OrderAckData {
+                msg: Some("insufficient margin".into()),
+                ..ack("a", OrderStatus::Rejected)
+            })
+            .unwrap();
+        assert_eq!(
+            (rejected.from, rejected.to),
+            (Some(OrderState::New), OrderState::Rejected)
+        );
+        assert_eq!(
+            oms.get("a").unwrap().reject_reason.as_deref(),
+            Some("insufficient margin")
+        );
+
+        let local = oms.reject("b", "timeout", 3).unwrap();
+        assert_eq!(local.to, OrderState::Rejected);
+        assert_eq!(local.source, UpdateSource::Submit);
+    }
+
+    #[test]
+    fn unknown_venue_orders_are_adopted() {
+        let mut oms = OrderManager::new();
+        let adopted = oms.on_ws_orders(&[ws("ex-9", OrderStatus::Live, 0.0, 5)]);
+        assert_eq!(states(&adopted), [(None, OrderState::Acked)]);
+        assert_eq!(oms.get("ex-9").unwrap().price, Some(100.0));
+        assert_eq!(oms.open_orders_for(&Market::Okx, INST).count(), 1);
+        assert_eq!(
+            oms.open_orders_for(&Market::BinanceUmFutures, INST).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn reconcile_reports_orders_missing_from_snapshot() {
+        let mut oms = OrderManager::new();
+        oms.submit(Market::Okx, &mut params(Some("a")), 1).unwrap();
+        oms.on_ack(&ack("a", OrderStatus::Live)).unwrap();
+        oms.submit(Market::Okx, &mut params(Some("b")), 1).unwrap();
+        oms.submit(Market::Okx, &mut params(Some("c")), 1).unwrap();
+        oms.on_ack(&OrderAckData {
+            order_id: "ex-3".into(),
+            ..ack("c", OrderStatus::Live)
+        })
+        .unwrap();
+
+        let report = oms.reconcile_open_orders(
+            Market::Okx,
+            INST,
+            &[detail("ex-3", OrderStatus::PartiallyFilled, 1.0)],
+        );
+        assert_eq!(
+            states(&report.transitions),
+            [(Some(OrderState::Acked), OrderState::PartiallyFilled)]
+        );
+        assert_eq!(report.missing, ["a"]);
+        assert_eq!(oms.get("c").unwrap().avg_price, Some(100.0));
+
+        let closed = oms.on_rest_orders(Market::Okx, &[detail("ex-1", OrderStatus::Filled, 2.0)]);
+        assert_eq!(closed[0].to, OrderState::Filled);
+        assert_eq!(closed[0].source, UpdateSource::Rest);
+
+        oms.prune_terminal(u64::MAX);
+        assert!(oms.get("a").is_none());
+        assert!(oms.get_by_order_id("ex-1").is_none());
+        assert_eq!(oms.len(), 2);
+    }
+}
@@ -0,0 +1,742 @@
+//! Position, balance, and PnL tracking.
+//!
+//! [`PortfolioState`] keeps one [`PositionState`] per [`InstrumentKey`] with
+//! its net size, average entry, realized and unrealized PnL, fees, and
+//! funding. Feed it from the strategy callbacks:
+//!
+//! - fills move the position and realize PnL, either passed to
+//!   [`PortfolioState::on_fill`], taken from `on_acc_fill` executions, or
+//!   derived from the filled size growth of `on_acc_order` updates;
+//! - `on_acc_pos`, `on_acc_bal_pos`, and REST `get_positions` snapshots are
+//!   the venue's view and overwrite size and average entry;
+//! - trades, book tops, and mark prices set the mark used for unrealized PnL.
+//!
+//! Venue snapshots win over locally applied fills. Run
+//! [`PortfolioState::reconcile`] periodically, for example from
+//! `on_schedule`, to compare against REST and report [`PositionDrift`]s.
+
+use std::collections::HashMap;
+
+use tracing::warn;
+
+use crate::arch::{
+    market_assets::{
+        api_data::account_data::{BalanceData, PositionData},
+        base_data::{InstrumentKey, InstrumentType, OrderSide, OrderStatus, PositionSide},
+        instrument_registry::InstrumentRegistry,
+        market_core::Market,
+    },
+    strategy_base::handler::lob_events::{
+        LobEventKind, LobLevel, LobLevelAction, WsAccBalPos, WsAccOrder, WsAccPosition, WsFill,
+        WsLob, WsTrade,
+    },
+    traits::market_lob::LobPrivateRest,
+};
+use crate::errors::InfraResult;
+
+/// Default size difference tolerated by [`PortfolioState::reconcile`].
+pub const DEFAULT_DRIFT_TOLERANCE: f64 = 1e-9;
+
+/// Position and PnL of one instrument.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PositionState {
+    pub key: InstrumentKey,
+    /// Signed net size in venue order units; negative values are short.
+    pub size: f64,
+    pub avg_price: f64,
+    pub realized_pnl: f64,
+    /// Fees paid; rebates are negative.
+    pub fees: f64,
+    /// Funding received; payments are negative.
+    pub funding: f64,
+    pub mark_price: Option<f64>,
+    /// Base units per order unit, `1.0` unless known from the registry.
+    pub contract_size: f64,
+    /// Time of the last change, in microseconds.
+    pub updated_at: u64,
+}
+
+impl PositionState {
+    fn new(key: InstrumentKey, contract_size: f64) -> Self {
+        Self {
+            key,
+            size: 0.0,
+            avg_price: 0.0,
+            realized_pnl: 0.0,
+            fees: 0.0,
+            funding: 0.0,
+            mark_price: None,
+            contract_size,
+            updated_at: 0,
+        }
+    }
+
+    /// PnL of the open size at the mark price, `0.0` without a mark.
+    pub fn unrealized_pnl(&self) -> f64 {
+        self.mark_price.map_or(0.0, |mark| {
+            (mark - self.avg_price) * self.size * self.contract_size
+        })
+    }
+
+    /// Realized plus unrealized PnL and funding, net of fees.
+    pub fn total_pnl(&self) -> f64 {
+        self.realized_pnl + self.unrealized_pnl() + self.funding - self.fees
+    }
+
+    /// Mark value of the open size, signed like the position.
+    pub fn notional(&self) -> f64 {
+        self.size * self.mark_price.unwrap_or(self.avg_price) * self.contract_size
+    }
+
+    fn apply_fill(&mut self, side: &OrderSide, price: f64, size: f64) {
+        let qty = match side {
+            OrderSide::SELL => -size,
+            _ => size,
+        };
+        if self.size == 0.0 || self.size.signum() == qty.signum() {
+            let open = self.size.abs();
+            self.avg_price = (self.avg_price * open + price * size) / (open + size);
+            self.size += qty;
+            return;
+        }
+
+        let closed = size.min(self.size.abs());
+        self.realized_pnl +=
+            (price - self.avg_price) * closed * self.size.signum() * self.contract_size;
+        let remaining = self.size + qty;
+        if remaining.abs() <= f64::EPSILON * size.max(1.0) {
+            self.size = 0.0;
+            self.avg_price = 0.0;
+        } else {
+            if remaining.signum() != self.size.signum() {
+                self.avg_price = price;
+            }
+            self.size = remaining;
+        }
+    }
+}
+
+/// Local and venue sizes that disagreed during reconciliation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PositionDrift {
+    pub key: InstrumentKey,
+    pub local_size: f64,
+    pub venue_size: f64,
+}
+
+/// Position, balance, and PnL book built from account streams.
+#[derive(Clone, Debug, Default)]
+pub struct PortfolioState {
+    positions: HashMap<InstrumentKey, PositionState>,
+    balances: HashMap<(Market, String), BalanceData>,
+    marks: HashMap<(Market, String), f64>,
+    /// Filled size last seen per order, keyed by market and order id.
+    order_fills: HashMap<(Market, String), f64>,
+    registry: Option<InstrumentRegistry>,
+    drift_tolerance: f64,
+}
+
+impl PortfolioState {
+    pub fn new() -> Self {
+        Self {
+            drift_tolerance: DEFAULT_DRIFT_TOLERANCE,
+            ..Default::default()
+        }
+    }
+
+    /// Reads contract sizes from `registry` so PnL is in quote currency for
+    /// instruments traded in contracts.
+    pub fn with_registry(mut self, registry: InstrumentRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Sets the size difference [`PortfolioState::reconcile`] tolerates
+    /// before reporting drift. Defaults to [`DEFAULT_DRIFT_TOLERANCE`].
+    pub fn with_drift_tolerance(mut self, tolerance: f64) -> Self {
+        self.drift_tolerance = tolerance;
+        self
+    }
+
+    /// Applies one execution.
+    ///
+    /// `fee` is the fee paid in quote currency; pass a negative value for a
+    /// rebate.
+    pub fn on_fill(
+        &mut self,
+        key: &InstrumentKey,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+        fee: f64,
+        timestamp: u64,
+    ) {
+        if size <= 0.0 || !price.is_finite() {
+            return;
+        }
+        let position = self.position_mut(key);
+        position.apply_fill(&side, price, size);
+        position.fees += fee;
+        position.updated_at = timestamp;
+    }
+
+    /// Books executions from `on_acc_fill` at their fill price and fee.
+    ///
+    /// Fees are booked as reported; convert them first when `fee_currency`
+    /// is not the quote currency.
+    pub fn on_acc_fill(&mut self, fills: &[WsFill]) {
+        for fill in fills {
+            let key = instrument_key(&fill.market, &fill.inst_type, &fill.inst);
+            self.on_fill(
+                &key,
+                fill.side.clone(),
+                fill.price,
+                fill.size,
+                fill.fee,
+                fill.timestamp,
+            );
+        }
+    }
+
+    /// Derives fills from the filled size growth of order updates.
+    ///
+    /// Order updates carry no execution price, so fills are booked at the
+    /// order price, or the mark for market orders, and without fees. Prefer
+    /// [`PortfolioState::on_acc_fill`] when executions are available, and do
+    /// not feed both for the same orders.
+    pub fn on_acc_order(&mut self, orders: &[WsAccOrder]) {
+        for order in orders {
+            let Some(id) = order.order_id.as_ref().or(order.cli_order_id.as_ref()) else {
+                continue;
+            };
+            let order_key = (order.market.clone(), id.clone());
+            let seen = self.order_fills.get(&order_key).copied().unwrap_or(0.0);
+            let delta = order.filled_size - seen;
+            if delta > 0.0 {
+                let key = instrument_key(&order.market, &order.inst_type, &order.inst);
+                let price = if order.price > 0.0 {
+                    Some(order.price)
+                } else {
+                    self.mark(&order.market, &order.inst)
+                };
+                match price {
+                    Some(price) => {
+                        self.on_fill(&key, order.side.clone(), price, delta, 0.0, order.timestamp)
+                    },
+                    None => warn!(
+                        "No price to book a {} fill on {:?} {}",
+                        delta, order.market, order.inst
+                    ),
+                }
+                self.order_fills
+                    .insert(order_key.clone(), order.filled_size);
+            }
+
+            if matches!(
+                order.status,
+                OrderStatus::Filled
+                    | OrderStatus::Canceled
+                    | OrderStatus::Rejected
+                    | OrderStatus::Expired
+            ) {
+                self.order_fills.remove(&order_key);
+            }
+        }
+    }
+
+    /// Adopts venue positions from `on_acc_pos`.
+    pub fn on_acc_pos(&mut self, market: &Market, positions: &[WsAccPosition], timestamp: u64) {
+        let legs = positions.iter().map(|position| {
+            (
+                position.inst.as_str(),
+                &position.inst_type,
+                signed_size(&position.position_side, position.size),
+                position.avg_price,
+            )
+        });
+        self.adopt_positions(market, legs.collect(), timestamp);
+    }
+
+    /// Adopts balances and positions from `on_acc_bal_pos`.
+    pub fn on_acc_bal_pos(&mut self, updates: &[WsAccBalPos]) {
+        for update in updates {
+            for balance in &update.balances {
+                let entry = self
+                    .balances
+                    .entry((update.market.clone(), balance.inst.clone()))
+                    .or_default();
+                entry.timestamp = update.timestamp;
+                entry.asset = balance.inst.clone();
+                entry.total = balance.balance;
+            }
+            self.on_acc_pos(&update.market, &update.positions, update.timestamp);
+        }
+    }
+
+    /// Adopts a REST `get_positions` snapshot, including its mark prices.
+    ///
+    /// Returns instruments whose size disagreed with the local state by more
+    /// than the drift tolerance. Positions absent from the snapshot are not
+    /// touched; use [`PortfolioState::reconcile`] to close them.
+    pub fn on_rest_positions(
+        &mut self,
+        market: &Market,
+        positions: &[PositionData],
+    ) -> Vec<PositionDrift> {
+        let mut legs = Vec::new();
+        for position in positions {
+            if position.mark_price > 0.0 {
+                self.marks
+                    .insert((market.clone(), position.inst.clone()), position.mark_price);
+            }
+            legs.push((
+                position.inst.as_str(),
+                &position.inst_type,
+                signed_size(&position.position_side, position.size),
+                position.avg_price,
+            ));
+        }
+        let timestamp = positions.iter().map(|p| p.timestamp).max().unwrap_or(0);
+        self.adopt_positions(market, legs, timestamp)
+    }
+
+    /// Adopts a REST `get_balance` snapshot.
+    pub fn on_rest_balances(&mut self, market: &Market, balances: &[BalanceData]) {
+        for balance in balances {
+            self.balances
+                .insert((market.clone(), balance.asset.clone()), balance.clone());
+        }
+    }
+
+    /// Fetches positions and balances from `client` and adopts them.
+    ///
+    /// Local positions on `market` missing from the venue snapshot are
+    /// closed. Every drift beyond the tolerance is logged and returned.
+    pub async fn reconcile<C: LobPrivateRest>(
+        &mut self,
+        client: &C,
+        market: &Market,
+    ) -> InfraResult<Vec<PositionDrift>> {
+        let positions = client.get_positions(None).await?;
+        let balances = client.get_balance(None).await?;
+
+        let mut drift = self.on_rest_positions(market, &positions);
+        let stale: Vec<InstrumentKey> = self
+            .positions
+            .values()
+            .filter(|state| state.key.market.as_ref() == Some(market) && state.size != 0.0)
+            .filter(|state| {
+                !positions
+                    .iter()
+                    .any(|p| p.inst == state.key.inst && p.size != 0.0)
+            })
+            .map(|state| state.key.clone())
+            .collect();
+        for key in stale {
+            let Some(state) = self.positions.get_mut(&key) else {
+                continue;
+            };
+            if state.size.abs() > self.drift_tolerance {
+                drift.push(PositionDrift {
+                    key,
+                    local_size: state.size,
+                    venue_size: 0.0,
+                });
+            }
+            state.size = 0.0;
+            state.avg_price = 0.0;
+        }
+        self.on_rest_balances(market, &balances);
+
+        for d in &drift {
+            warn!(
+                "Position drift on {:?} {}: local {} venue {}",
+                d.key.market, d.key.inst, d.local_size, d.venue_size
+            );
+        }
+        Ok(drift)
+    }
+
+    /// Sets the mark price of `inst` on `market`.
+    pub fn set_mark(&mut self, market: &Market, inst: &str, price: f64) {
+        if price > 0.0 && price.is_finite() {
+            self.marks.insert((market.clone(), inst.to_string()), price);
+            self.refresh_mark(market, inst);
+        }
+    }
+
+    /// Marks instruments at their last trade price.
+    pub fn on_trades(&mut self, trades: &[WsTrade]) {
+        for trade in trades {
+            self.set_mark(&trade.market, &trade.inst, trade.price);
+        }
+    }
+
+    /// Marks instruments at the mid of top-of-book and snapshot updates.
+    ///
+    /// Incremental updates do not carry the book top and are skipped; feed
+    /// `LocalOrderBook::mid` to [`PortfolioState::set_mark`] instead.
+    pub fn on_lob(&mut self, books: &[WsLob]) {
+        for book in books {
+            if !matches!(book.event, LobEventKind::Bbo | LobEventKind::Snapshot) {
+                continue;
+            }
+            if let (Some(bid), Some(ask)) = (best_price(&book.bids), best_price(&book.asks)) {
+                self.set_mark(&book.market, &book.inst, (bid + ask) / 2.0);
+            }
+        }
+    }
+
+    /// Books a funding payment; negative `amount`s were paid.
+    pub fn on_funding(&mut self, key: &InstrumentKey, amount: f64, timestamp: u64) {
+        let position = self.position_mut(key);
+        position.funding += amount;
+        position.updated_at = timestamp;
+    }
+
+    pub fn position(&self, key: &InstrumentKey) -> Option<&PositionState> {
+        self.positions.get(key)
+    }
+
+    /// Every tracked instrument, including closed ones with realized PnL.
+    pub fn positions(&self) -> impl Iterator<Item = &PositionState> {
+        self.positions.values()
+    }
+
+    /// Instruments with a non-zero size.
+    pub fn open_positions(&self) -> impl Iterator<Item = &PositionState> {
+        self.positions.values().filter(|state| state.size != 0.0)
+    }
+
+    pub fn balance(&self, market: &Market, asset: &str) -> Option<&BalanceData> {
+        self.balances.get(&(market.clone(), asset.to_string()))
+    }
+
+    pub fn mark(&self, market: &Market, inst: &str) -> Option<f64> {
+        self.marks.get(&(market.clone(), inst.to_string())).copied()
+    }
+
+    pub fn realized_pnl(&self) -> f64 {
+        self.positions
+            .values()
+            .map(|state| state.realized_pnl)
+            .sum()
+    }
+
+    pub fn unrealized_pnl(&self) -> f64 {
+        self.positions
+            .values()
+            .map(PositionState::unrealized_pnl)
+            .sum()
+    }
+
+    pub fn total_pnl(&self) -> f64 {
+        self.positions.values().map(PositionState::total_pnl).sum()
+    }
+
+    /// Overwrites size and entry of each instrument with the venue legs,
+    /// netting hedge-mode legs, and returns sizes that drifted.
+    fn adopt_positions(
+        &mut self,
+        market: &Market,
+        legs: Vec<(&str, &InstrumentType, f64, f64)>,
+        timestamp: u64,
+    ) -> Vec<PositionDrift> {
+        let mut net: Vec<(InstrumentKey, f64, f64)> = Vec::new();
+        for (inst, inst_type, size, avg_price) in legs {
+            let key = instrument_key(market, inst_type, inst);
+            match net.iter_mut().find(|(k, _, _)| *k == key) {
+                Some((_, net_size, net_price)) => {
+                    // Keep the entry of the larger leg, which decides the net direction.
+                    if size.abs() > net_size.abs() {
+                        *net_price = avg_price;
+                    }
+                    *net_size += size;
+                },
+                None => net.push((key, size, avg_price)),
+            }
+        }
+
+        let tolerance = self.drift_tolerance;
+        let mut drift = Vec::new();
+        for (key, size, avg_price) in net {
+            let mark = self.mark(market, &key.inst);
+            let state = self.position_mut(&key);
+            if (state.size - size).abs() > tolerance {
+                drift.push(PositionDrift {
+                    key: key.clone(),
+                    local_size: state.size,
+                    venue_size: size,
+                });
+            }
+            state.size = size;
+            state.avg_price = if size == 0.0 { 0.0 } else { avg_price };
+            state.mark_price = mark.or(state.mark_price);
+            state.updated_at = timestamp;
+        }
+        drift
+    }
+
+    fn refresh_mark(&mut self, market: &Market, inst: &str) {
+        let mark = self.mark(market, inst);
+        for state in self.positions.values_mut() {
+            if state.key.market.as_ref() == Some(market) && state.key.inst == inst {
+                state.mark_price = mark;
+            }
+        }
+    }
+
+    fn position_mut(&mut self, key: &InstrumentKey) -> &mut PositionState {
+        if !self.positions.contains_key(key) {
+            let contract_size = self
+                .registry
+                .as_ref()
+                .and_then(|registry| registry.to_base(key, 1.0))
+                .unwrap_or(1.0);
+            let mut state = PositionState::new(key.clone(), contract_size);
+            state.mark_price = key
+                .market
+                .as_ref()
+                .and_then(|market| self.mark(market, &key.inst));
+            self.positions.insert(key.clone(), state);
+        }
+        self.positions
+            .get_mut(key)
+            .expect("position inserted above")
+    }
+}
+
+fn instrument_key(market: &Market, inst_type: &InstrumentType, inst: &str) -> InstrumentKey {
+    InstrumentRegistry::key(market.clone(), inst_type.clone(), inst)
+}
+
+fn best_price(levels: &[LobLevel]) -> Option<f64> {
+    levels
+        .iter()
+        .find(|level| matches!(level.action, LobLevelAction::Upsert) && level.size > 0.0)
+        .map(|level| level.price)
+}
+
+fn signed_size(side: &PositionSide, size: f64) -> f64 {
+    match side {
+        PositionSide::Short => -size.abs(),
+        _ => size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::{Future, ready};
+
+    use super::*;
+    use crate::arch::{
+        market_assets::{
+            api_data::utils_data::InstrumentInfo,
+            base_data::{InstrumentStatus, MarginMode, OrderType},
+        },
+        strategy_base::handler::lob_events::FillLiquidity,
+    };
+
+    const INST: &str = "BTC-USDT-SWAP";
+
+    fn key() -> InstrumentKey {
+        InstrumentRegistry::key(Market::Okx, InstrumentType::Perpetual, INST)
+    }
+
+    fn order_update(filled_size: f64, status: OrderStatus) -> WsAccOrder {
+        WsAccOrder {
+            timestamp: 10,
+            market: Market::Okx,
+            inst: INST.into(),
+            inst_type: InstrumentType::Perpetual,
+            price: 100.0,
+            size: 3.0,
+            filled_size,
+            side: OrderSide::BUY,
+            status,
+            order_type: OrderType::Limit,
+            order_id: Some("1".into()),
+            cli_order_id: None,
+        }
+    }
+
+    struct SnapshotClient(Vec<PositionData>);
+
+    impl LobPrivateRest for SnapshotClient {
+        fn init_api_key(&mut self) {}
+
+        fn get_balance(
+            &self,
+            _insts: Option<&[String]>,
+        ) -> impl Future<Output = InfraResult<Vec<BalanceData>>> + Send {
+            ready(Ok(vec![BalanceData {
+                asset: "USDT".into(),
+                total: 1_000.0,
+                ..Default::default()
+            }]))
+        }
+
+        fn get_positions(
+            &self,
+            _insts: Option<&[String]>,
+        ) -> impl Future<Output = InfraResult<Vec<PositionData>>> + Send {
+            ready(Ok(self.0.clone()))
+        }
+    }
+
+    #[test]
+    fn fills_track_entry_and_realized_pnl() {
+        let mut portfolio = PortfolioState::new();
+        portfolio.on_fill(&key(), OrderSide::BUY, 100.0, 2.0, 0.1, 1);
+        portfolio.on_fill(&key(), OrderSide::BUY, 110.0, 2.0, 0.1, 2);
+        assert_eq!(portfolio.position(&key()).unwrap().avg_price, 105.0);
+
+        portfolio.on_fill(&key(), OrderSide::SELL, 120.0, 3.0, 0.1, 3);
+        let position = portfolio.position(&key()).unwrap();
+        assert_eq!((position.size, position.realized_pnl), (1.0, 45.0));
+
+        // Selling through zero realizes the rest and opens a short at the fill.
+        portfolio.on_fill(&key(), OrderSide::SELL, 100.0, 2.0, 0.1, 4);
+        portfolio.set_mark(&Market::Okx, INST, 90.0);
+        let position = portfolio.position(&key()).unwrap();
+        assert_eq!((position.size, position.avg_price), (-1.0, 100.0));
+        assert_eq!(position.realized_pnl, 40.0);
+        assert_eq!(position.unrealized_pnl(), 10.0);
+        assert!((position.total_pnl() - 49.6).abs() < 1e-9);
+        assert_eq!(position.updated_at, 4);
+    }
+
+    #[test]
+    fn executions_book_fill_price_and_fee() {
+        let fill = |side, price, fee| WsFill {
+            timestamp: 5,
+            market: Market::Okx,
+            inst: INST.into(),
+            inst_type: InstrumentType::Perpetual,
+            side,
+            price,
+            size: 1.0,
+            fee,
+            fee_currency: Some("USDT".into()),
+            liquidity: FillLiquidity::Taker,
+            trade_id: None,
+            order_id: Some("1".into()),
+            cli_order_id: None,
+        };
+        let mut portfolio = PortfolioState::new();
+
+        portfolio.on_acc_fill(&[
+            fill(OrderSide::BUY, 100.0, 0.05),
+            fill(OrderSide::SELL, 104.0, -0.01),
+        ]);
+
+        let position = portfolio.position(&key()).unwrap();
+        assert_eq!((position.size, position.realized_pnl), (0.0, 4.0));
+        assert!((position.fees - 0.04).abs() < 1e-12);
+    }
+
+    #[test]
+    fn order_updates_book_fill_growth_in_contracts() {
+        let registry = InstrumentRegistry::new();
+        registry.apply(
+            &Market::Okx,
+            InstrumentType::Perpetual,
+            vec![InstrumentInfo {
+                inst: INST.into(),
+                contract_value: Some(0.01),
+                state: InstrumentStatus::Live,
+                ..Default::default()
+            }],
+        );
+        let mut portfolio = PortfolioState::new().with_registry(registry);
+
+        portfolio.on_acc_order(&[order_update(1.0, OrderStatus::PartiallyFilled)]);
+        portfolio.on_acc_order(&[order_update(1.0, OrderStatus::PartiallyFilled)]);
+        portfolio.on_acc_order(&[order_update(3.0, OrderStatus::Filled)]);
+        portfolio.on_trades(&[WsTrade {
+            timestamp: 11,
+            market: Market::Okx,
+            inst: INST.into(),
+            price: 200.0,
+            size: 1.0,
+            side: OrderSide::SELL,
+            trade_id: 1,
+        }]);
+
+        let position = portfolio.position(&key()).unwrap();
+        assert_eq!(position.size, 3.0);
+        assert!((position.unrealized_pnl() - 3.0).abs() < 1e-9);
+        assert!(portfolio.order_fills.is_empty());
+    }
+
+    #[test]
+    fn venue_positions_override_and_report_drift() {
+        let mut portfolio = PortfolioState::new();
+        portfolio.on_fill(&key(), OrderSide::BUY, 100.0, 2.0, 0.0, 1);
+        portfolio.on_acc_pos(
+            &Market::Okx,
+            &[
+                WsAccPosition {
+                    inst: INST.into(),
+                    inst_type: InstrumentType::Perpetual,
+                    avg_price: 101.0,
+                    size: 3.0,
+                    position_side: PositionSide::Long,
+                    margin_mode: MarginMode::Cross,
+                },
+                WsAccPosition {
+                    inst: INST.into(),
+                    inst_type: InstrumentType::Perpetual,
+                    avg_price: 99.0,
+                    size: 1.0,
+                    position_side: PositionSide::Short,
+                    margin_mode: MarginMode::Cross,
+                },
+            ],
+            5,
+        );
+        let position = portfolio.position(&key()).unwrap();
+        assert_eq!((position.size, position.avg_price), (2.0, 101.0));
+
+        let drift = portfolio.on_rest_positions(
+            &Market::Okx,
+            &[PositionData {
+                timestamp: 6,
+                inst: INST.into(),
+                inst_type: InstrumentType::Perpetual,
+                position_side: PositionSide::Both,
+                size: 1.5,
+                avg_price: 101.0,
+                mark_price: 105.0,
+                ..Default::default()
+            }],
+        );
+        assert_eq!(
+            drift,
+            [PositionDrift {
+                key: key(),
+                local_size: 2.0,
+                venue_size: 1.5,
+            }]
+        );
+        assert_eq!(portfolio.position(&key()).unwrap().mark_price, Some(105.0));
+    }
+
+    #[tokio::test]
+    async fn reconcile_closes_positions_missing_on_the_venue() {
+        let mut portfolio = PortfolioState::new();
+        portfolio.on_fill(&key(), OrderSide::SELL, 100.0, 1.0, 0.0, 1);
+
+        let drift = portfolio
+            .reconcile(&SnapshotClient(Vec::new()), &Market::Okx)
+            .await
+            .unwrap();
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].local_size, -1.0);
+        assert_eq!(portfolio.open_positions().count(), 0);
+        assert_eq!(
+            portfolio.balance(&Market::Okx, "USDT").unwrap().total,
+            1_000.0
+        );
+    }
+}
@@ -0,0 +1,600 @@
+//! Strategy-side sink that persists normalized market and account events.
+//!
+//! [`MarketRecorder`] is an ordinary strategy module. Register it with
+//! `EnvBuilder::with_strategy_module_on` to choose which task streams are
+//! recorded. Events are handed to a writer thread, so callbacks only wait
+//! when the writer falls behind by more than
+//! [`RecorderConfig::channel_capacity`] events.
+//!
+//! Files rotate by size and age. Every finished file is appended to
+//! `<file_prefix>.index.jsonl` in the output directory together with the time
+//! range it covers; use [`read_index`] to select files and
+//! [`RecordingReader`] to read MessagePack recordings back.
+
+#[cfg(feature = "polars")]
+mod parquet;
+mod writer;
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, ErrorKind, Read},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tracing::error;
+
+use crate::arch::{
+    infra_core::backtest::{BacktestData, BacktestRecord},
+    strategy_base::{
+        command::command_core::CommandRegistry,
+        handler::{
+            events::InfraMsg,
+            lob_events::{
+                WsAccBalPos, WsAccOrder, WsAccPosition, WsCandle, WsFill, WsLob, WsLobMbo, WsTrade,
+            },
+        },
+    },
+    traits::strategy::{CommandEmitter, EventHandler, Strategy},
+};
+use crate::errors::{InfraError, InfraResult};
+
+use writer::{RecordWriter, WriterMsg};
+
+/// Default encoded bytes written to one file before it rotates.
+pub const DEFAULT_ROTATE_BYTES: u64 = 256 * 1024 * 1024;
+/// Default age of one file before it rotates.
+pub const DEFAULT_ROTATE_INTERVAL: Duration = Duration::from_secs(3_600);
+
+/// On-disk encoding of recorded events.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// Little-endian `u32` length prefix followed by one MessagePack-encoded
+    /// [`RecordedEvent`] with named fields, repeated.
+    #[default]
+    MsgPack,
+    /// One Parquet row per event with `timestamp`, `task_id`, `kind`,
+    /// `market`, `inst`, and `event_timestamp` columns for filtering, plus the
+    /// MessagePack-encoded [`RecordedData`] in a binary `payload` column.
+    #[cfg(feature = "polars")]
+    Parquet,
+}
+
+/// Output settings for [`MarketRecorder`].
+#[derive(Clone, Debug)]
+pub struct RecorderConfig {
+    /// Directory for recordings and the index. Created if missing.
+    pub dir: PathBuf,
+    /// Prefix for file names and the index file.
+    pub file_prefix: String,
+    pub format: RecordFormat,
+    /// Gzip MessagePack files, or use Zstd pages in Parquet files.
+    pub compress: bool,
+    /// Encoded bytes after which the current file is closed.
+    pub rotate_bytes: u64,
+    /// Age after which the current file is closed.
+    pub rotate_interval: Duration,
+    /// Events buffered between strategy callbacks and the writer thread.
+    pub channel_capacity: usize,
+}
+
+impl RecorderConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            file_prefix: "market".into(),
+            format: RecordFormat::default(),
+            compress: true,
+            rotate_bytes: DEFAULT_ROTATE_BYTES,
+            rotate_interval: DEFAULT_ROTATE_INTERVAL,
+            channel_capacity: 65_536,
+        }
+    }
+
+    pub fn with_file_prefix(mut self, file_prefix: impl Into<String>) -> Self {
+        self.file_prefix = file_prefix.into();
+        self
+    }
+
+    pub fn with_format(mut self, format: RecordFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_compression(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotate_bytes: u64, rotate_interval: Duration) -> Self {
+        self.rotate_bytes = rotate_bytes;
+        self.rotate_interval = rotate_interval;
+        self
+    }
+
+    fn index_path(&self) -> PathBuf {
+        index_path(&self.dir, &self.file_prefix)
+    }
+}
+
+/// Payload of one recorded event.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RecordedData {
+    Trade(Arc<Vec<WsTrade>>),
+    Lob(Arc<Vec<WsLob>>),
+    LobMbo(Arc<Vec<WsLobMbo>>),
+    Candle(Arc<Vec<WsCandle>>),
+    AccOrder(Arc<Vec<WsAccOrder>>),
+    AccFill(Arc<Vec<WsFill>>),
+    AccBalPos(Arc<Vec<WsAccBalPos>>),
+    AccPos(Arc<Vec<WsAccPosition>>),
+}
+
+impl RecordedData {
+    /// Name of the event family, as stored in Parquet `kind` columns.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RecordedData::Trade(_) => "Trade",
+            RecordedData::Lob(_) => "Lob",
+            RecordedData::LobMbo(_) => "LobMbo",
+            RecordedData::Candle(_) => "Candle",
+            RecordedData::AccOrder(_) => "AccOrder",
+            RecordedData::AccFill(_) => "AccFill",
+            RecordedData::AccBalPos(_) => "AccBalPos",
+            RecordedData::AccPos(_) => "AccPos",
+        }
+    }
+}
+
+/// One event as received by the recorder.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// Receipt time in microseconds, read from the runtime clock.
+    pub timestamp: u64,
+    /// Task id from the event envelope.
+    pub task_id: u64,
+    pub data: RecordedData,
+}
+
+impl RecordedEvent {
+    /// Converts market data into a record for [`BacktestEnv::run`].
+    ///
+    /// Returns `None` for event families the backtest does not replay.
+    ///
+    /// [`BacktestEnv::run`]: crate::arch::infra_core::backtest::BacktestEnv::run
+    pub fn into_backtest_record(self) -> Option<BacktestRecord> {
+        let data = match self.data {
+            RecordedData::Trade(data) => BacktestData::Trade(Arc::unwrap_or_clone(data)),
+            RecordedData::Lob(data) => BacktestData::Lob(Arc::unwrap_or_clone(data)),
+            RecordedData::Candle(data) => BacktestData::Candle(Arc::unwrap_or_clone(data)),
+            _ => return None,
+        };
+        Some(BacktestRecord::new(self.timestamp, self.task_id, data))
+    }
+}
+
+/// Index entry for one finished recording file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordingFile {
+    /// File name relative to the recording directory.
+    pub file: String,
+    /// Receipt time of the first event in the file.
+    pub first_timestamp: u64,
+    /// Receipt time of the last event in the file.
+    pub last_timestamp: u64,
+    pub records: u64,
+}
+
+impl RecordingFile {
+    /// Returns `true` when the file holds events between `start` and `end`,
+    /// inclusive.
+    pub fn overlaps(&self, start: u64, end: u64) -> bool {
+        self.first_timestamp <= end && self.last_timestamp >= start
+    }
+}
+
+/// Reads the index written for `file_prefix` in `dir`, oldest file first.
+pub fn read_index(dir: impl AsRef<Path>, file_prefix: &str) -> InfraResult<Vec<RecordingFile>> {
+    let file = File::open(index_path(dir.as_ref(), file_prefix))?;
+    BufReader::new(file)
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+fn index_path(dir: &Path, file_prefix: &str) -> PathBuf {
+    dir.join(format!("{file_prefix}.index.jsonl"))
+}
+
+/// Iterator over the events of one MessagePack recording.
+///
+/// Files ending in `.gz` are decompressed transparently. A truncated final
+/// frame, as left behind by a crash, ends the iteration without an error.
+pub struct RecordingReader {
+    reader: Box<dyn Read + Send>,
+}
+
+impl RecordingReader {
+    pub fn open(path: impl AsRef<Path>) -> InfraResult<Self> {
+        let path = path.as_ref();
+        let file = BufReader::new(File::open(path)?);
+        let reader: Box<dyn Read + Send> = if path.extension().is_some_and(|ext| ext == "gz") {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+        Ok(Self { reader })
+    }
+}
+
+impl Iterator for RecordingReader {
+    type Item = InfraResult<RecordedEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut len = [0u8; 4];
+        match self.reader.read_exact(&mut len) {
+            Ok(()) => {},
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return None,
+            Err(e) => return Some(Err(e.into())),
+        }
+
+        let mut frame = vec![0u8; u32::from_le_bytes(len) as usize];
+        match self.reader.read_exact(&mut frame) {
+            Ok(()) => {},
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return None,
+            Err(e) => return Some(Err(e.into())),
+        }
+
+        Some(
+            rmp_serde::from_slice(&frame)
+                .map_err(|e| InfraError::Msg(format!("Failed to decode recorded event: {e}"))),
+        )
+    }
+}
+
+/// Strategy module that records every event it receives.
+///
+/// Cloning shares the writer. Call [`MarketRecorder::new`] from inside or
+/// outside a tokio runtime; the writer runs on its own thread and closes the
+/// last file once every clone has been dropped. `on_shutdown` closes the
+/// current file so everything received so far is on disk and indexed.
+#[derive(Clone)]
+pub struct MarketRecorder {
+    tx: mpsc::Sender<WriterMsg>,
+    registry: Arc<CommandRegistry>,
+    shared: Arc<RecorderShared>,
+}
+
+struct RecorderShared {
+    writer: Mutex<Option<JoinHandle<()>>>,
+    failed: AtomicU64,
+}
+
+impl Drop for RecorderShared {
+    fn drop(&mut self) {
+        // Every sender is gone by now, so the writer is closing its last file.
+        let writer = self.writer.lock().ok().and_then(|mut writer| writer.take());
+        if let Some(writer) = writer {
+            let _ = writer.join();
+        }
+    }
+}
+
+impl MarketRecorder {
+    /// Creates the output directory and starts the writer thread.
+    pub fn new(config: RecorderConfig) -> InfraResult<Self> {
+        std::fs::create_dir_all(&config.dir)?;
+        let (tx, rx) = mpsc::channel(config.channel_capacity.max(1));
+        let writer = RecordWriter::new(config);
+        let handle = std::thread::Builder::new()
+            .name("market-recorder".into())
+            .spawn(move || writer.run(rx))?;
+
+        Ok(Self {
+            tx,
+            registry: Arc::new(CommandRegistry::default()),
+            shared: Arc::new(RecorderShared {
+                writer: Mutex::new(Some(handle)),
+                failed: AtomicU64::new(0),
+            }),
+        })
+    }
+
+    /// Number of events that could not be handed to the writer.
+    pub fn failed_events(&self) -> u64 {
+        self.shared.failed.load(Ordering::Relaxed)
+    }
+
+    /// Closes the current file and waits until it is indexed.
+    pub async fn flush(&self) -> InfraResult<()> {
+        let (tx, rx) = oneshot::channel();
+        self.tx
+            .send(WriterMsg::Flush(tx))
+            .await
+            .map_err(|_| InfraError::Msg("Recorder writer stopped".into()))?;
+        rx.await
+            .map_err(|_| InfraError::Msg("Recorder writer stopped".into()))?
+    }
+
+    async fn record(&self, task_id: u64, data: RecordedData) {
+        let event = RecordedEvent {
+            timestamp: self.now_micros(),
+            task_id,
+            data,
+        };
+
+        if self.tx.send(WriterMsg::Event(event)).await.is_err() {
+            self.shared.failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Strategy for MarketRecorder {
+    async fn initialize(&mut self) {}
+
+    async fn on_shutdown(&mut self) {
+        if let Err(e) = self.flush().await {
+            error!("Failed to flush market recorder: {:?}", e);
+        }
+    }
+
+    fn strategy_name(&self) -> &'static str {
+        "MarketRecorder"
+    }
+}
+
+impl CommandEmitter for MarketRecorder {
+    fn command_init(&mut self, registry: Arc<CommandRegistry>) {
+        self.registry = registry;
+    }
+
+    fn command_registry(&self) -> Arc<CommandRegistry> {
+        self.registry.clone()
+    }
+}
+
+impl EventHandler for MarketRecorder {
+    async fn on_trade(&mut self, msg: InfraMsg<Vec<WsTrade>>) {
+        self.record(msg.task_id, RecordedData::Trade(msg.data))
+            .await;
+    }
+
+    async fn on_lob(&mut self, msg: InfraMsg<Vec<WsLob>>) {
+        self.record(msg.task_id, RecordedData::Lob(msg.data)).await;
+    }
+
+    async fn on_lob_mbo(&mut self, msg: InfraMsg<Vec<WsLobMbo>>) {
+        self.record(msg.task_id, RecordedData::LobMbo(msg.data))
+            .await;
+    }
+
+    async fn on_candle(&mut self, msg: InfraMsg<Vec<WsCandle>>) {
+        self.record(msg.task_id, RecordedData::Candle(msg.data))
+            .await;
+    }
+
+    async fn on_acc_order(&mut self, msg: InfraMsg<Vec<WsAccOrder>>) {
+        self.record(msg.task_id, RecordedData::AccOrder(msg.data))
+            .await;
+    }
+
+    async fn on_acc_fill(&mut self, msg: InfraMsg<Vec<WsFill>>) {
+        self.record(msg.task_id, RecordedData::AccFill(msg.data))
+            .await;
+    }
+
+    async fn on_acc_bal_pos(&mut self, msg: InfraMsg<Vec<WsAccBalPos>>) {
+        self.record(msg.task_id, RecordedData::AccBalPos(msg.data))
+            .await;
+    }
+
+    async fn on_acc_pos(&mut self, msg: InfraMsg<Vec<WsAccPosition>>) {
+        self.record(msg.task_id, RecordedData::AccPos(msg.data))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+    use crate::arch::market_assets::{base_data::OrderSide, market_core::Market};
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "extrema-recorder-{name}-{}-{nanos}",
+            std::process::id()
+        ))
+    }
+
+    fn trade(timestamp: u64, trade_id: u64) -> WsTrade {
+        WsTrade {
+            timestamp,
+            market: Market::Okx,
+            inst: "BTC-USDT-SWAP".into(),
+            price: 100.0 + trade_id as f64,
+            size: 1.0,
+            side: OrderSide::BUY,
+            trade_id,
+        }
+    }
+
+    fn event(timestamp: u64, trade_id: u64) -> RecordedEvent {
+        RecordedEvent {
+            timestamp,
+            task_id: 7,
+            data: RecordedData::Trade(Arc::new(vec![trade(timestamp, trade_id)])),
+        }
+    }
+
+    fn read_all(dir: &Path, files: &[RecordingFile]) -> Vec<RecordedEvent> {
+        files
+            .iter()
+            .flat_map(|file| RecordingReader::open(dir.join(&file.file)).unwrap())
+            .map(|event| event.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn writer_rotates_by_age_and_indexes_time_ranges() {
+        let dir = scratch_dir("age");
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = RecorderConfig::new(&dir)
+            .with_compression(false)
+            .with_rotation(u64::MAX, Duration::from_secs(1));
+        let (tx, rx) = mpsc::channel(16);
+        for (i, ts) in [1_000_000, 1_400_000, 2_000_000, 2_100_000, 3_500_000]
+            .into_iter()
+            .enumerate()
+        {
+            tx.try_send(WriterMsg::Event(event(ts, i as u64))).unwrap();
+        }
+        drop(tx);
+        RecordWriter::new(config).run(rx);
+
+        let index = read_index(&dir, "market").unwrap();
+        let ranges: Vec<_> = index
+            .iter()
+            .map(|f| (f.first_timestamp, f.last_timestamp, f.records))
+            .collect();
+        assert_eq!(
+            ranges,
+            [
+                (1_000_000, 1_400_000, 2),
+                (2_000_000, 2_100_000, 2),
+                (3_500_000, 3_500_000, 1)
+            ]
+        );
+        assert!(index.iter().all(|f| f.file.ends_with(".mpk")));
+        assert_eq!(
+            index
+                .iter()
+                .filter(|f| f.overlaps(1_500_000, 2_000_000))
+                .count(),
+            1
+        );
+
+        let ids: Vec<_> = read_all(&dir, &index)
+            .into_iter()
+            .map(|e| match e.data {
+                RecordedData::Trade(trades) => trades[0].trade_id,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        assert_eq!(ids, [0, 1, 2, 3, 4]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reader_stops_at_truncated_frame() {
+        let dir = scratch_dir("truncated");
+        std::fs::create_dir_all(&dir).unwrap();
+        let (tx, rx) = mpsc::channel(4);
+        tx.try_send(WriterMsg::Event(event(1, 1))).unwrap();
+        tx.try_send(WriterMsg::Event(event(2, 2))).unwrap();
+        drop(tx);
+        RecordWriter::new(RecorderConfig::new(&dir).with_compression(false)).run(rx);
+
+        let path = dir.join(&read_index(&dir, "market").unwrap()[0].file);
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+
+        assert_eq!(RecordingReader::open(&path).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn recorder_writes_gzip_files_and_flushes_on_shutdown() {
+        let dir = scratch_dir("gzip");
+        let mut recorder = MarketRecorder::new(
+            RecorderConfig::new(&dir)
+                .with_file_prefix("okx")
+                .with_rotation(64, DEFAULT_ROTATE_INTERVAL),
+        )
+        .unwrap();
+
+        for trade_id in 0..3 {
+            recorder
+                .on_trade(InfraMsg {
+                    task_id: 7,
+                    received_at: 0,
+                    data: Arc::new(vec![trade(trade_id, trade_id)]),
+                })
+                .await;
+        }
+        recorder
+            .on_acc_pos(InfraMsg {
+                task_id: 8,
+                received_at: 0,
+                data: Arc::new(Vec::new()),
+            })
+            .await;
+        recorder.on_shutdown().await;
+
+        let index = read_index(&dir, "okx").unwrap();
+        assert!(index.len() > 1);
+        assert!(index.iter().all(|f| f.file.ends_with(".mpk.gz")));
+        assert_eq!(index.iter().map(|f| f.records).sum::<u64>(), 4);
+
+        let events = read_all(&dir, &index);
+        assert_eq!(events.iter().filter(|e| e.task_id == 7).count(), 3);
+        let records: Vec<_> = events
+            .into_iter()
+            .filter_map(RecordedEvent::into_backtest_record)
+            .collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(recorder.failed_events(), 0);
+
+        drop(recorder);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "polars")]
+    #[test]
+    fn writer_emits_parquet_rows() {
+        use polars::prelude::{ParquetReader, SerReader};
+
+        let dir = scratch_dir("parquet");
+        std::fs::create_dir_all(&dir).unwrap();
+        let (tx, rx) = mpsc::channel(4);
+        tx.try_send(WriterMsg::Event(event(10, 1))).unwrap();
+        tx.try_send(WriterMsg::Event(event(20, 2))).unwrap();
+        drop(tx);
+        RecordWriter::new(RecorderConfig::new(&dir).with_format(RecordFormat::Parquet)).run(rx);
+
+        let index = read_index(&dir, "market").unwrap();
+        assert_eq!(index.len(), 1);
+        let file = File::open(dir.join(&index[0].file)).unwrap();
+        let df = ParquetReader::new(file).finish().unwrap();
+        assert_eq!(df.height(), 2);
+        assert_eq!(
+            df.column("inst").unwrap().str().unwrap().get(0),
+            Some("BTC-USDT-SWAP")
+        );
+
+        let payload = df
+            .column("payload")
+            .unwrap()
+            .binary()
+            .unwrap()
+            .get(1)
+            .unwrap();
+        let data: RecordedData = rmp_serde::from_slice(payload).unwrap();
+        assert!(matches!(data, RecordedData::Trade(trades) if trades[0].trade_id == 2));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,113 @@
+use std::fs::File;
+
+use polars::prelude::*;
+
+use super::{RecordedData, RecordedEvent};
+use crate::errors::InfraResult;
+
+/// Column buffers for one Parquet file, written out when the file rotates.
+pub(crate) struct ParquetBuffer {
+    compress: bool,
+    timestamp: Vec<u64>,
+    task_id: Vec<u64>,
+    kind: Vec<&'static str>,
+    market: Vec<Option<String>>,
+    inst: Vec<Option<String>>,
+    event_timestamp: Vec<Option<u64>>,
+    payload: Vec<Vec<u8>>,
+}
+
+impl ParquetBuffer {
+    pub(crate) fn new(compress: bool) -> Self {
+        Self {
+            compress,
+            timestamp: Vec::new(),
+            task_id: Vec::new(),
+            kind: Vec::new(),
+            market: Vec::new(),
+            inst: Vec::new(),
+            event_timestamp: Vec::new(),
+            payload: Vec::new(),
+        }
+    }
+
+    /// Buffers one row. `payload` is the MessagePack-encoded event data.
+    pub(crate) fn push(&mut self, event: &RecordedEvent, payload: Vec<u8>) {
+        let (market, inst, event_timestamp) = first_key(&event.data);
+        self.timestamp.push(event.timestamp);
+        self.task_id.push(event.task_id);
+        self.kind.push(event.data.kind());
+        self.market.push(market);
+        self.inst.push(inst);
+        self.event_timestamp.push(event_timestamp);
+        self.payload.push(payload);
+    }
+
+    pub(crate) fn finish(self, file: File) -> InfraResult<()> {
+        let height = self.timestamp.len();
+        let mut df = DataFrame::new(
+            height,
+            vec![
+                Column::new("timestamp".into(), self.timestamp),
+                Column::new("task_id".into(), self.task_id),
+                Column::new("kind".into(), self.kind),
+                Column::new("market".into(), self.market),
+                Column::new("inst".into(), self.inst),
+                Column::new("event_timestamp".into(), self.event_timestamp),
+                Column::new("payload".into(), self.payload),
+            ],
+        )?;
+
+        let compression = if self.compress {
+            ParquetCompression::Zstd(None)
+        } else {
+            ParquetCompression::Uncompressed
+        };
+        ParquetWriter::new(&file)
+            .with_compression(compression)
+            .finish(&mut df)?;
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+/// Market, instrument, and exchange timestamp of the first item, if any.
+fn first_key(data: &RecordedData) -> (Option<String>, Option<String>, Option<u64>) {
+    macro_rules! first {
+        ($items:expr) => {
+            $items
+                .first()
+                .map(|item| {
+                    (
+                        Some(format!("{:?}", item.market)),
+                        Some(item.inst.clone()),
+                        Some(item.timestamp),
+                    )
+                })
+                .unwrap_or_default()
+        };
+    }
+
+    match data {
+        RecordedData::Trade(items) => first!(items),
+        RecordedData::Lob(items) => first!(items),
+        RecordedData::LobMbo(items) => first!(items),
+        RecordedData::Candle(items) => first!(items),
+        RecordedData::AccOrder(items) => first!(items),
+        RecordedData::AccFill(items) => first!(items),
+        RecordedData::AccPos(items) => items
+            .first()
+            .map(|item| (None, Some(item.inst.clone()), None))
+            .unwrap_or_default(),
+        RecordedData::AccBalPos(items) => items
+            .first()
+            .map(|item| {
+                (
+                    Some(format!("{:?}", item.market)),
+                    None,
+                    Some(item.timestamp),
+                )
+            })
+            .unwrap_or_default(),
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, ErrorKind, Write},
+    path::PathBuf,
+};
+
+use flate2::{Compression, write::GzEncoder};
+use tokio::sync::{mpsc, oneshot};
+use tracing::error;
+
+#[cfg(feature = "polars")]
+use super::parquet::ParquetBuffer;
+use super::{RecordFormat, RecordedEvent, RecorderConfig, RecordingFile};
+use crate::errors::{InfraError, InfraResult};
+
+pub(crate) enum WriterMsg {
+    Event(RecordedEvent),
+    Flush(oneshot::Sender<InfraResult<()>>),
+}
+
+enum Sink {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    #[cfg(feature = "polars")]
+    Parquet(File, ParquetBuffer),
+}
+
+impl Sink {
+    fn write(&mut self, event: &RecordedEvent) -> InfraResult<u64> {
+        let frame = match self {
+            Sink::Plain(_) | Sink::Gzip(_) => rmp_serde::to_vec_named(event),
+            #[cfg(feature = "polars")]
+            Sink::Parquet(_, _) => rmp_serde::to_vec_named(&event.data),
+        }
+        .map_err(|e| InfraError::Msg(format!("Failed to encode recorded event: {e}")))?;
+        let len = u32::try_from(frame.len())
+            .map_err(|_| InfraError::Msg("Recorded event exceeds 4 GiB".into()))?;
+
+        match self {
+            Sink::Plain(w) => {
+                w.write_all(&len.to_le_bytes())?;
+                w.write_all(&frame)?;
+            },
+            Sink::Gzip(w) => {
+                w.write_all(&len.to_le_bytes())?;
+                w.write_all(&frame)?;
+            },
+            #[cfg(feature = "polars")]
+            Sink::Parquet(_, buffer) => buffer.push(event, frame),
+        }
+        Ok(u64::from(len) + 4)
+    }
+
+    fn finish(self) -> InfraResult<()> {
+        match self {
+            Sink::Plain(w) => {
+                w.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+            },
+            Sink::Gzip(w) => {
+                w.finish()?
+                    .into_inner()
+                    .map_err(|e| e.into_error())?
+                    .sync_all()?;
+            },
+            #[cfg(feature = "polars")]
+            Sink::Parquet(file, buffer) => buffer.finish(file)?,
+        }
+        Ok(())
+    }
+}
+
+struct OpenFile {
+    sink: Sink,
+    entry: RecordingFile,
+    bytes: u64,
+}
+
+/// Owns the current file on the writer thread.
+pub(crate) struct RecordWriter {
+    config: RecorderConfig,
+    current: Option<OpenFile>,
+    seq: u64,
+}
+
+impl RecordWriter {
+    pub(crate) fn new(config: RecorderConfig) -> Self {
+        Self {
+            config,
+            current: None,
+            seq: 0,
+        }
+    }
+
+    pub(crate) fn run(mut self, mut rx: mpsc::Receiver<WriterMsg>) {
+        while let Some(msg) = rx.blocking_recv() {
+            match msg {
+                WriterMsg::Event(event) => {
+                    if let Err(e) = self.write(&event) {
+                        error!("Failed to record event: {:?}", e);
+                    }
+                },
+                WriterMsg::Flush(ack) => {
+                    let _ = ack.send(self.close());
+                },
+            }
+        }
+
+        if let Err(e) = self.close() {
+            error!("Failed to close recording: {:?}", e);
+        }
+    }
+
+    fn write(&mut self, event: &RecordedEvent) -> InfraResult<()> {
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|file| self.is_due(file, event))
+        {
+            self.close()?;
+        }
+
+        let mut file = match self.current.take() {
+            Some(file) => file,
+            None => self.open(event.timestamp)?,
+        };
+        let written = file.sink.write(event);
+        if let Ok(bytes) = written {
+            file.bytes += bytes;
+            file.entry.last_timestamp = event.timestamp;
+            file.entry.records += 1;
+        }
+        self.current = Some(file);
+        written.map(|_| ())
+    }
+
+    /// Rotation age is measured on receipt timestamps, so recordings made
+    /// under a simulated clock rotate the same way on every run.
+    fn is_due(&self, file: &OpenFile, event: &RecordedEvent) -> bool {
+        let age = event.timestamp.saturating_sub(file.entry.first_timestamp);
+        file.bytes >= self.config.rotate_bytes
+            || age >= self.config.rotate_interval.as_micros() as u64
+    }
+
+    fn open(&mut self, timestamp: u64) -> InfraResult<OpenFile> {
+        loop {
+            self.seq += 1;
+            let name = format!(
+                "{}-{}-{:05}.{}",
+                self.config.file_prefix,
+                timestamp,
+                self.seq,
+                self.extension()
+            );
+
+            let file = match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(self.config.dir.join(&name))
+            {
+                Ok(file) => file,
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            };
+
+            return Ok(OpenFile {
+                sink: self.sink(file),
+                entry: RecordingFile {
+                    file: name,
+                    first_timestamp: timestamp,
+                    last_timestamp: timestamp,
+                    records: 0,
+                },
+                bytes: 0,
+            });
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match (&self.config.format, self.config.compress) {
+            (RecordFormat::MsgPack, false) => "mpk",
+            (RecordFormat::MsgPack, true) => "mpk.gz",
+            #[cfg(feature = "polars")]
+            (RecordFormat::Parquet, _) => "parquet",
+        }
+    }
+
+    fn sink(&self, file: File) -> Sink {
+        match (&self.config.format, self.config.compress) {
+            (RecordFormat::MsgPack, false) => Sink::Plain(BufWriter::new(file)),
+            (RecordFormat::MsgPack, true) => {
+                Sink::Gzip(GzEncoder::new(BufWriter::new(file), Compression::default()))
+            },
+            #[cfg(feature = "polars")]
+            (RecordFormat::Parquet, compress) => Sink::Parquet(file, ParquetBuffer::new(compress)),
+        }
+    }
+
+    /// Finishes the current file and appends it to the index.
+    fn close(&mut self) -> InfraResult<()> {
+        let Some(file) = self.current.take() else {
+            return Ok(());
+        };
+        file.sink.finish()?;
+
+        let mut line = serde_json::to_string(&file.entry)?;
+        line.push('\n');
+        let mut index = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.index_path())?;
+        index.write_all(line.as_bytes())?;
+        index.sync_all()?;
+        Ok(())
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.config.index_path()
+    }
+}
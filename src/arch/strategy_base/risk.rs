@@ -0,0 +1,849 @@
+//! Pre-trade risk checks.
+//!
+//! [`RiskGate`] validates an order before it is sent to a venue and answers
+//! with a [`RiskRejection`] describing the first limit it breaks. The gate is
+//! a cheap cloneable handle: the execution path calls [`RiskGate::approve`]
+//! while strategies keep its view of prices, positions, and open orders fresh
+//! from their callbacks, and can trip the kill switch from anywhere.
+//!
+//! Limits resolve per field from the instrument, then the market, then the
+//! global [`RiskLimits`]. Instrument rules from [`InstrumentInfo`] apply once
+//! registered with [`RiskGate::with_instruments`] or
+//! [`RiskGate::set_instruments`].
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use thiserror::Error;
+
+use crate::arch::{
+    market_assets::{
+        api_data::utils_data::InstrumentInfo,
+        api_general::OrderParams,
+        base_data::{InstrumentStatus, OrderSide, OrderStatus, OrderType, PositionSide},
+        market_core::Market,
+    },
+    strategy_base::handler::lob_events::{WsAccOrder, WsAccPosition, WsTrade},
+};
+
+type InstKey = (Market, String);
+
+/// Relative tolerance when checking sizes and prices against increments.
+const INCREMENT_TOLERANCE: f64 = 1e-9;
+
+/// Order rate allowed within a sliding window, counted per market.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrderRateLimit {
+    pub max_orders: u32,
+    pub window: Duration,
+}
+
+/// Risk limits. `None` disables a check.
+///
+/// Notional values are `size * price * contract_value`, using the limit price,
+/// or the reference price for market orders.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RiskLimits {
+    /// Notional of a single order.
+    pub max_order_notional: Option<f64>,
+    /// Notional of the position in one instrument after every open order on
+    /// the same side, and this one, fills.
+    pub max_inst_notional: Option<f64>,
+    /// Gross notional of positions and open orders across one market,
+    /// including this order.
+    pub max_market_notional: Option<f64>,
+    /// Absolute position size in one instrument after every open order on the
+    /// same side, and this one, fills.
+    pub max_position: Option<f64>,
+    /// Open orders in one instrument.
+    pub max_open_orders: Option<usize>,
+    /// Largest relative distance of a limit price from the reference price,
+    /// for example `0.05` for 5%.
+    pub price_collar: Option<f64>,
+    /// Orders sent per market. Instrument overrides do not apply.
+    pub order_rate: Option<OrderRateLimit>,
+}
+
+impl RiskLimits {
+    /// Fills every unset field from `fallback`.
+    fn or(&self, fallback: &RiskLimits) -> RiskLimits {
+        RiskLimits {
+            max_order_notional: self.max_order_notional.or(fallback.max_order_notional),
+            max_inst_notional: self.max_inst_notional.or(fallback.max_inst_notional),
+            max_market_notional: self.max_market_notional.or(fallback.max_market_notional),
+            max_position: self.max_position.or(fallback.max_position),
+            max_open_orders: self.max_open_orders.or(fallback.max_open_orders),
+            price_collar: self.price_collar.or(fallback.price_collar),
+            order_rate: self.order_rate.or(fallback.order_rate),
+        }
+    }
+}
+
+/// Why the [`RiskGate`] refused an order.
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum RiskRejection {
+    #[error("kill switch is active")]
+    KillSwitch,
+    #[error("invalid order: {0}")]
+    InvalidOrder(String),
+    #[error("unknown instrument {inst}")]
+    UnknownInstrument { inst: String },
+    #[error("instrument is not tradable: {status:?}")]
+    InstrumentNotTradable { status: InstrumentStatus },
+    #[error("no reference price for {inst}")]
+    MissingReferencePrice { inst: String },
+    #[error("size {size} is not a multiple of lot size {lot_size}")]
+    LotSize { size: f64, lot_size: f64 },
+    #[error("price {price} is not a multiple of tick size {tick_size}")]
+    TickSize { price: f64, tick_size: f64 },
+    #[error("size {size} is below the minimum {min}")]
+    MinSize { size: f64, min: f64 },
+    #[error("size {size} is above the maximum {max}")]
+    MaxSize { size: f64, max: f64 },
+    #[error("notional {notional} is below the minimum {min}")]
+    MinNotional { notional: f64, min: f64 },
+    #[error("price {price} is {deviation} away from reference {reference}, limit {limit}")]
+    PriceCollar {
+        price: f64,
+        reference: f64,
+        deviation: f64,
+        limit: f64,
+    },
+    #[error("order notional {notional} exceeds {limit}")]
+    OrderNotional { notional: f64, limit: f64 },
+    #[error("instrument notional {notional} would exceed {limit}")]
+    InstNotional { notional: f64, limit: f64 },
+    #[error("market notional {notional} would exceed {limit}")]
+    MarketNotional { notional: f64, limit: f64 },
+    #[error("position {position} would exceed {limit}")]
+    Position { position: f64, limit: f64 },
+    #[error("{open} open orders, limit {limit}")]
+    OpenOrders { open: usize, limit: usize },
+    #[error("{max_orders} orders already sent within {window:?}")]
+    OrderRate { max_orders: u32, window: Duration },
+}
+
+/// Cloneable handle to shared pre-trade risk state.
+#[derive(Clone, Debug)]
+pub struct RiskGate {
+    shared: Arc<RiskShared>,
+}
+
+#[derive(Debug)]
+struct RiskShared {
+    killed: AtomicBool,
+    state: Mutex<RiskState>,
+}
+
+#[derive(Debug, Default)]
+struct RiskState {
+    limits: RiskLimits,
+    market_limits: HashMap<Market, RiskLimits>,
+    inst_limits: HashMap<InstKey, RiskLimits>,
+    instruments: HashMap<InstKey, InstrumentInfo>,
+    prices: HashMap<InstKey, f64>,
+    positions: HashMap<InstKey, f64>,
+    /// Open orders keyed by market and client order id, or exchange order id
+    /// when the venue reported none.
+    open_orders: HashMap<InstKey, OpenOrder>,
+    sent: HashMap<Market, VecDeque<u64>>,
+}
+
+#[derive(Clone, Debug)]
+struct OpenOrder {
+    inst: String,
+    /// Remaining size, negative for sells.
+    remaining: f64,
+    price: Option<f64>,
+}
+
+/// Order fields the checks work on.
+struct Candidate<'a> {
+    key: InstKey,
+    params: &'a OrderParams,
+    /// Signed size, negative for sells.
+    signed: f64,
+    limit_price: Option<f64>,
+}
+
+impl RiskGate {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            shared: Arc::new(RiskShared {
+                killed: AtomicBool::new(false),
+                state: Mutex::new(RiskState {
+                    limits,
+                    ..Default::default()
+                }),
+            }),
+        }
+    }
+
+    /// Overrides limits for every instrument on `market`.
+    pub fn with_market_limits(self, market: Market, limits: RiskLimits) -> Self {
+        self.state().market_limits.insert(market, limits);
+        self
+    }
+
+    /// Overrides limits for one instrument.
+    pub fn with_inst_limits(self, market: Market, inst: &str, limits: RiskLimits) -> Self {
+        self.state()
+            .inst_limits
+            .insert((market, inst.to_string()), limits);
+        self
+    }
+
+    pub fn with_instruments(self, market: Market, instruments: Vec<InstrumentInfo>) -> Self {
+        self.set_instruments(market, instruments);
+        self
+    }
+
+    /// Registers instrument rules, for example from `get_instrument_info`.
+    pub fn set_instruments(&self, market: Market, instruments: Vec<InstrumentInfo>) {
+        let mut state = self.state();
+        for info in instruments {
+            state
+                .instruments
+                .insert((market.clone(), info.inst.clone()), info);
+        }
+    }
+
+    /// Rejects every order until [`RiskGate::resume`] is called.
+    pub fn kill(&self) {
+        self.shared.killed.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.shared.killed.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.shared.killed.load(Ordering::SeqCst)
+    }
+
+    /// Sets the reference price for collars and market-order notionals.
+    pub fn update_price(&self, market: Market, inst: &str, price: f64) {
+        self.state()
+            .prices
+            .insert((market, inst.to_string()), price);
+    }
+
+    /// Uses the latest trade of each instrument as its reference price.
+    pub fn on_trades(&self, trades: &[WsTrade]) {
+        let mut state = self.state();
+        for trade in trades {
+            state
+                .prices
+                .insert((trade.market.clone(), trade.inst.clone()), trade.price);
+        }
+    }
+
+    /// Sets the signed net position of one instrument.
+    pub fn set_position(&self, market: Market, inst: &str, size: f64) {
+        self.state()
+            .positions
+            .insert((market, inst.to_string()), size);
+    }
+
+    /// Replaces the positions of every instrument in `positions`.
+    ///
+    /// `Short` sizes count as negative, so hedge-mode legs net out.
+    pub fn on_positions(&self, market: Market, positions: &[WsAccPosition]) {
+        let mut net: HashMap<&str, f64> = HashMap::new();
+        for position in positions {
+            let size = match position.position_side {
+                PositionSide::Short => -position.size.abs(),
+                _ => position.size,
+            };
+            *net.entry(&position.inst).or_default() += size;
+        }
+
+        let mut state = self.state();
+        for (inst, size) in net {
+            state
+                .positions
+                .insert((market.clone(), inst.to_string()), size);
+        }
+    }
+
+    /// Tracks open orders from websocket order updates.
+    pub fn on_orders(&self, orders: &[WsAccOrder]) {
+        let mut state = self.state();
+        for order in orders {
+            let Some(id) = order.cli_order_id.as_ref().or(order.order_id.as_ref()) else {
+                continue;
+            };
+            let key = (order.market.clone(), id.clone());
+            match order.status {
+                OrderStatus::Live | OrderStatus::PartiallyFilled => {
+                    let remaining = (order.size - order.filled_size).max(0.0);
+                    state.open_orders.insert(
+                        key,
+                        OpenOrder {
+                            inst: order.inst.clone(),
+                            remaining: signed(&order.side, remaining),
+                            price: (order.price > 0.0).then_some(order.price),
+                        },
+                    );
+                },
+                OrderStatus::Unknown => {},
+                _ => {
+                    state.open_orders.remove(&key);
+                },
+            }
+        }
+    }
+
+    /// Checks `params` without recording it.
+    pub fn check(
+        &self,
+        market: &Market,
+        params: &OrderParams,
+        now: u64,
+    ) -> Result<(), RiskRejection> {
+        if self.is_killed() {
+            return Err(RiskRejection::KillSwitch);
+        }
+        let mut state = self.state();
+        state.prune_rate(market, now);
+        state.check(market, params)
+    }
+
+    /// Checks `params` and, when it passes, counts it against the order rate
+    /// and, if it carries a client order id, as an open order.
+    pub fn approve(
+        &self,
+        market: &Market,
+        params: &OrderParams,
+        now: u64,
+    ) -> Result<(), RiskRejection> {
+        if self.is_killed() {
+            return Err(RiskRejection::KillSwitch);
+        }
+        let mut state = self.state();
+        state.prune_rate(market, now);
+        state.check(market, params)?;
+
+        state.sent.entry(market.clone()).or_default().push_back(now);
+        if let Some(id) = &params.client_order_id {
+            let candidate = Candidate::new(market, params)?;
+            let price = state.notional_price(&candidate);
+            state.open_orders.insert(
+                (market.clone(), id.clone()),
+                OpenOrder {
+                    inst: params.inst.clone(),
+                    remaining: candidate.signed,
+                    price,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Stops counting the order with client order id `cli_order_id` as open.
+    ///
+    /// Call it when a placement approved by [`RiskGate::approve`] failed or
+    /// was rejected outright, since no order update will follow to remove it.
+    pub fn release(&self, market: &Market, cli_order_id: &str) {
+        self.state()
+            .open_orders
+            .remove(&(market.clone(), cli_order_id.to_string()));
+    }
+
+    fn state(&self) -> MutexGuard<'_, RiskState> {
+        self.shared
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<'a> Candidate<'a> {
+    fn new(market: &Market, params: &'a OrderParams) -> Result<Self, RiskRejection> {
+        let size: f64 = params
+            .size
+            .parse()
+            .ok()
+            .filter(|size: &f64| size.is_finite() && *size > 0.0)
+            .ok_or_else(|| RiskRejection::InvalidOrder(format!("size {:?}", params.size)))?;
+        if matches!(params.side, OrderSide::Unknown) {
+            return Err(RiskRejection::InvalidOrder("side is not set".into()));
+        }
+
+        let limit_price = match params.price.as_deref() {
+            Some(price) => Some(
+                price
+                    .parse()
+                    .ok()
+                    .filter(|price: &f64| price.is_finite() && *price > 0.0)
+                    .ok_or_else(|| RiskRejection::InvalidOrder(format!("price {price:?}")))?,
+            ),
+            None if matches!(params.order_type, OrderType::Market) => None,
+            None => return Err(RiskRejection::InvalidOrder("price is not set".into())),
+        };
+
+        Ok(Self {
+            key: (market.clone(), params.inst.clone()),
+            params,
+            signed: signed(&params.side, size),
+            limit_price,
+        })
+    }
+
+    fn size(&self) -> f64 {
+        self.signed.abs()
+    }
+}
+
+impl RiskState {
+    fn market_limits_for(&self, market: &Market) -> RiskLimits {
+        self.market_limits
+            .get(market)
+            .map_or_else(|| self.limits.clone(), |limits| limits.or(&self.limits))
+    }
+
+    fn limits_for(&self, key: &InstKey) -> RiskLimits {
+        let market = self.market_limits_for(&key.0);
+        self.inst_limits
+            .get(key)
+            .map_or(market.clone(), |limits| limits.or(&market))
+    }
+
+    fn prune_rate(&mut self, market: &Market, now: u64) {
+        let window = self
+            .market_limits_for(market)
+            .order_rate
+            .map_or(0, |rate| rate.window.as_micros() as u64);
+        let Some(sent) = self.sent.get_mut(market) else {
+            return;
+        };
+        while sent
+            .front()
+            .is_some_and(|sent_at| now.saturating_sub(*sent_at) >= window)
+        {
+            sent.pop_front();
+        }
+    }
+
+    fn contract_value(&self, key: &InstKey) -> f64 {
+        self.instruments
+            .get(key)
+            .and_then(|info| info.contract_value)
+            .unwrap_or(1.0)
+    }
+
+    /// Price used for notionals: the limit price, else the reference price.
+    fn notional_price(&self, candidate: &Candidate) -> Option<f64> {
+        candidate
+            .limit_price
+            .or_else(|| self.prices.get(&candidate.key).copied())
+    }
+
+    fn check(&self, market: &Market, params: &OrderParams) -> Result<(), RiskRejection> {
+        let candidate = Candidate::new(market, params)?;
+        let limits = self.limits_for(&candidate.key);
+        self.check_instrument(&candidate)?;
+
+        let reference = self.prices.get(&candidate.key).copied();
+        if let (Some(limit), Some(price), Some(reference)) =
+            (limits.price_collar, candidate.limit_price, reference)
+        {
+            let deviation = (price - reference).abs() / reference;
+            if deviation > limit {
+                return Err(RiskRejection::PriceCollar {
+                    price,
+                    reference,
+                    deviation,
+                    limit,
+                });
+            }
+        }
+
+        let needs_price = limits.max_order_notional.is_some()
+            || limits.max_inst_notional.is_some()
+            || limits.max_market_notional.is_some()
+            || self
+                .instruments
+                .get(&candidate.key)
+                .is_some_and(|info| info.min_notional.is_some());
+        let price = match self.notional_price(&candidate) {
+            Some(price) => price,
+            None if needs_price => {
+                return Err(RiskRejection::MissingReferencePrice {
+                    inst: params.inst.clone(),
+                });
+            },
+            None => 0.0,
+        };
+        let contract_value = self.contract_value(&candidate.key);
+        let notional = candidate.size() * price * contract_value;
+
+        if let Some(min) = self
+            .instruments
+            .get(&candidate.key)
+            .and_then(|info| info.min_notional)
+            && notional < min
+        {
+            return Err(RiskRejection::MinNotional { notional, min });
+        }
+        if let Some(limit) = limits.max_order_notional
+            && notional > limit
+        {
+            return Err(RiskRejection::OrderNotional { notional, limit });
+        }
+
+        let position = self.positions.get(&candidate.key).copied().unwrap_or(0.0);
+        let pending: f64 = self
+            .open_orders_in(&candidate.key)
+            .map(|order| order.remaining)
+            .filter(|remaining| remaining.signum() == candidate.signed.signum())
+            .sum();
+        let projected = position + pending + candidate.signed;
+
+        if let Some(limit) = limits.max_position
+            && projected.abs() > limit
+        {
+            return Err(RiskRejection::Position {
+                position: projected,
+                limit,
+            });
+        }
+        if let Some(limit) = limits.max_inst_notional {
+            let notional = projected.abs() * price * contract_value;
+            if notional > limit {
+                return Err(RiskRejection::InstNotional { notional, limit });
+            }
+        }
+        if let Some(limit) = limits.max_market_notional {
+            let notional = self.market_exposure(market) + notional;
+            if notional > limit {
+                return Err(RiskRejection::MarketNotional { notional, limit });
+            }
+        }
+
+        if let Some(limit) = limits.max_open_orders {
+            let open = self.open_orders_in(&candidate.key).count();
+            if open >= limit {
+                return Err(RiskRejection::OpenOrders { open, limit });
+            }
+        }
+        if let Some(rate) = self.market_limits_for(market).order_rate {
+            let sent = self.sent.get(market).map_or(0, VecDeque::len);
+            if sent >= rate.max_orders as usize {
+                return Err(RiskRejection::OrderRate {
+                    max_orders: rate.max_orders,
+                    window: rate.window,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn check_instrument(&self, candidate: &Candidate) -> Result<(), RiskRejection> {
+        let Some(info) = self.instruments.get(&candidate.key) else {
+            return Ok(());
+        };
+        if !is_tradable(&info.state) {
+            return Err(RiskRejection::InstrumentNotTradable {
+                status: info.state.clone(),
+            });
+        }
+
+        let size = candidate.size();
+        if !is_multiple(size, info.lot_size) {
+            return Err(RiskRejection::LotSize {
+                size,
+                lot_size: info.lot_size,
+            });
+        }
+        if let Some(price) = candidate.limit_price
+            && !is_multiple(price, info.tick_size)
+        {
+            return Err(RiskRejection::TickSize {
+                price,
+                tick_size: info.tick_size,
+            });
+        }
+
+        let (min, max) = if matches!(candidate.params.order_type, OrderType::Market) {
+            (info.min_mkt_size, info.max_mkt_size)
+        } else {
+            (info.min_lmt_size, info.max_lmt_size)
+        };
+        if min > 0.0 && size < min {
+            return Err(RiskRejection::MinSize { size, min });
+        }
+        if max > 0.0 && size > max {
+            return Err(RiskRejection::MaxSize { size, max });
+        }
+        Ok(())
+    }
+
+    fn open_orders_in<'a>(&'a self, key: &'a InstKey) -> impl Iterator<Item = &'a OpenOrder> {
+        self.open_orders
+            .iter()
+            .filter(move |((market, _), order)| *market == key.0 && order.inst == key.1)
+            .map(|(_, order)| order)
+    }
+
+    /// Gross notional of positions and open orders on `market`.
+    fn market_exposure(&self, market: &Market) -> f64 {
+        let positions: f64 = self
+            .positions
+            .iter()
+            .filter(|(key, _)| key.0 == *market)
+            .map(|(key, size)| {
+                let price = self.prices.get(key).copied().unwrap_or(0.0);
+                size.abs() * price * self.contract_value(key)
+            })
+            .sum();
+        let orders: f64 = self
+            .open_orders
+            .iter()
+            .filter(|((order_market, _), _)| order_market == market)
+            .map(|((market, _), order)| {
+                let key = (market.clone(), order.inst.clone());
+                let price = order
+                    .price
+                    .or_else(|| self.prices.get(&key).copied())
+                    .unwrap_or(0.0);
+                order.remaining.abs() * price * self.contract_value(&key)
+            })
+            .sum();
+        positions + orders
+    }
+}
+
+/// Whether new orders may be sent to an instrument in `status`.
+pub(crate) fn is_tradable(status: &InstrumentStatus) -> bool {
+    !matches!(
+        status,
+        InstrumentStatus::Suspend | InstrumentStatus::PreOpen | InstrumentStatus::Closed
+    )
+}
+
+fn signed(side: &OrderSide, size: f64) -> f64 {
+    match side {
+        OrderSide::SELL => -size,
+        _ => size,
+    }
+}
+
+fn is_multiple(value: f64, increment: f64) -> bool {
+    if increment <= 0.0 {
+        return true;
+    }
+    let steps = value / increment;
+    (steps - steps.round()).abs() <= INCREMENT_TOLERANCE * steps.abs().max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INST: &str = "BTC-USDT-SWAP";
+
+    fn order(side: OrderSide, size: &str, price: Option<&str>) -> OrderParams {
+        OrderParams {
+            inst: INST.into(),
+            side,
+            size: size.into(),
+            order_type: if price.is_some() {
+                OrderType::Limit
+            } else {
+                OrderType::Market
+            },
+            price: price.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn instrument() -> InstrumentInfo {
+        InstrumentInfo {
+            inst: INST.into(),
+            lot_size: 0.01,
+            tick_size: 0.1,
+            min_lmt_size: 0.01,
+            max_lmt_size: 100.0,
+            min_mkt_size: 0.01,
+            max_mkt_size: 10.0,
+            min_notional: Some(10.0),
+            contract_value: Some(0.01),
+            state: InstrumentStatus::Live,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn instrument_rules_catch_fat_fingers() {
+        let gate =
+            RiskGate::new(RiskLimits::default()).with_instruments(Market::Okx, vec![instrument()]);
+        gate.update_price(Market::Okx, INST, 60_000.0);
+        let check = |params: OrderParams| gate.check(&Market::Okx, &params, 0).err();
+
+        assert_eq!(check(order(OrderSide::BUY, "1.5", Some("60000.1"))), None);
+        assert!(matches!(
+            check(order(OrderSide::BUY, "1.505", Some("60000"))),
+            Some(RiskRejection::LotSize { .. })
+        ));
+        assert!(matches!(
+            check(order(OrderSide::BUY, "1", Some("60000.05"))),
+            Some(RiskRejection::TickSize { .. })
+        ));
+        assert!(matches!(
+            check(order(OrderSide::BUY, "20", None)),
+            Some(RiskRejection::MaxSize { max: 10.0, .. })
+        ));
+        assert!(matches!(
+            check(order(OrderSide::BUY, "0.01", None)),
+            Some(RiskRejection::MinNotional { .. })
+        ));
+        assert!(matches!(
+            check(order(OrderSide::Unknown, "1", None)),
+            Some(RiskRejection::InvalidOrder(_))
+        ));
+    }
+
+    #[test]
+    fn price_collar_and_notional_limits() {
+        let gate = RiskGate::new(RiskLimits {
+            max_order_notional: Some(10_000.0),
+            price_collar: Some(0.05),
+            ..Default::default()
+        });
+        let rejection = gate
+            .check(&Market::Okx, &order(OrderSide::BUY, "1", None), 0)
+            .unwrap_err();
+        assert_eq!(
+            rejection,
+            RiskRejection::MissingReferencePrice { inst: INST.into() }
+        );
+
+        gate.on_trades(&[WsTrade {
+            timestamp: 1,
+            market: Market::Okx,
+            inst: INST.into(),
+            price: 100.0,
+            size: 1.0,
+            side: OrderSide::BUY,
+            trade_id: 1,
+        }]);
+        assert!(
+            gate.check(&Market::Okx, &order(OrderSide::SELL, "50", Some("104")), 0)
+                .is_ok()
+        );
+        assert!(matches!(
+            gate.check(&Market::Okx, &order(OrderSide::SELL, "50", Some("94")), 0),
+            Err(RiskRejection::PriceCollar { .. })
+        ));
+        assert_eq!(
+            gate.check(&Market::Okx, &order(OrderSide::BUY, "101", None), 0),
+            Err(RiskRejection::OrderNotional {
+                notional: 10_100.0,
+                limit: 10_000.0
+            })
+        );
+    }
+
+    #[test]
+    fn position_and_open_order_limits_count_pending_orders() {
+        let gate = RiskGate::new(RiskLimits {
+            max_position: Some(5.0),
+            max_open_orders: Some(2),
+            ..Default::default()
+        })
+        .with_inst_limits(
+            Market::Okx,
+            "ETH-USDT-SWAP",
+            RiskLimits {
+                max_position: Some(100.0),
+                ..Default::default()
+            },
+        );
+        gate.set_position(Market::Okx, INST, 2.0);
+
+        let mut first = order(OrderSide::BUY, "2", Some("100"));
+        first.client_order_id = Some("a".into());
+        gate.approve(&Market::Okx, &first, 0).unwrap();
+        assert_eq!(
+            gate.check(&Market::Okx, &order(OrderSide::BUY, "2", Some("100")), 0),
+            Err(RiskRejection::Position {
+                position: 6.0,
+                limit: 5.0
+            })
+        );
+        // Sells reduce the position and ignore resting buys.
+        assert!(
+            gate.check(&Market::Okx, &order(OrderSide::SELL, "6", Some("100")), 0)
+                .is_ok()
+        );
+
+        let mut second = order(OrderSide::SELL, "1", Some("100"));
+        second.client_order_id = Some("b".into());
+        gate.approve(&Market::Okx, &second, 0).unwrap();
+        assert_eq!(
+            gate.check(&Market::Okx, &order(OrderSide::SELL, "1", Some("100")), 0),
+            Err(RiskRejection::OpenOrders { open: 2, limit: 2 })
+        );
+
+        gate.on_orders(&[WsAccOrder {
+            timestamp: 1,
+            market: Market::Okx,
+            inst: INST.into(),
+            inst_type: Default::default(),
+            price: 100.0,
+            size: 1.0,
+            filled_size: 0.0,
+            side: OrderSide::SELL,
+            status: OrderStatus::Canceled,
+            order_type: OrderType::Limit,
+            order_id: Some("2".into()),
+            cli_order_id: Some("b".into()),
+        }]);
+        assert!(
+            gate.check(&Market::Okx, &order(OrderSide::SELL, "1", Some("100")), 0)
+                .is_ok()
+        );
+
+        let mut eth = order(OrderSide::BUY, "50", Some("100"));
+        eth.inst = "ETH-USDT-SWAP".into();
+        assert!(gate.check(&Market::Okx, &eth, 0).is_ok());
+    }
+
+    #[test]
+    fn order_rate_and_kill_switch() {
+        let gate = RiskGate::new(RiskLimits::default()).with_market_limits(
+            Market::Okx,
+            RiskLimits {
+                order_rate: Some(OrderRateLimit {
+                    max_orders: 2,
+                    window: Duration::from_secs(1),
+                }),
+                ..Default::default()
+            },
+        );
+        let params = order(OrderSide::BUY, "1", Some("100"));
+        gate.approve(&Market::Okx, &params, 0).unwrap();
+        gate.approve(&Market::Okx, &params, 500_000).unwrap();
+        assert!(matches!(
+            gate.approve(&Market::Okx, &params, 900_000),
+            Err(RiskRejection::OrderRate { max_orders: 2, .. })
+        ));
+        assert!(gate.approve(&Market::GateFutures, &params, 900_000).is_ok());
+        assert!(gate.approve(&Market::Okx, &params, 1_000_000).is_ok());
+
+        gate.clone().kill();
+        assert_eq!(
+            gate.check(&Market::GateFutures, &params, 2_000_000),
+            Err(RiskRejection::KillSwitch)
+        );
+        gate.resume();
+        assert!(gate.check(&Market::GateFutures, &params, 2_000_000).is_ok());
+    }
+}
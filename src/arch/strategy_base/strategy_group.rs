@@ -1,12 +1,17 @@
 use std::sync::Arc;
 
 use futures::future;
+use tokio::task::JoinHandle;
 use tracing::info;
 
 use crate::arch::{
     strategy_base::{
         command::command_core::CommandRegistry,
-        handler::{handler_core::strategy_handler_loop, task_channel::TaskChannels},
+        handler::{
+            handler_core::{dispatch_task_event, strategy_handler_loop},
+            task_channel::{RoutedEvent, TaskChannels},
+        },
+        strategy_module::is_bound,
     },
     task_execution::TaskKey,
     traits::strategy::{CommandEmitter, EventHandler, Strategy},
@@ -61,11 +66,21 @@ where
         );
     }
 
+    async fn on_shutdown(&mut self) {
+        for (strategy, _) in &mut self.strategies {
+            strategy.on_shutdown().await;
+        }
+    }
+
     fn strategy_name(&self) -> &'static str {
         "InnerStrategyGroup"
     }
 
-    async fn _spawn_strategy_tasks(&self, task_channels: &Arc<TaskChannels>) {
+    async fn _spawn_strategy_tasks(
+        &self,
+        task_channels: &Arc<TaskChannels>,
+    ) -> Vec<JoinHandle<()>> {
+        let mut handles = Vec::with_capacity(self.strategies.len());
         for (strategy, task_keys) in self.strategies.iter().cloned() {
             let receivers = match task_keys {
                 Some(task_keys) => task_channels
@@ -74,10 +89,20 @@ where
                 None => task_channels.subscribe_all(),
             };
 
-            tokio::spawn(async move {
+            handles.push(tokio::spawn(async move {
                 info!("Spawned strategy task for {}", strategy.strategy_name());
                 strategy_handler_loop(strategy, receivers).await;
-            });
+            }));
+        }
+
+        handles
+    }
+
+    async fn _dispatch_event(&mut self, event: RoutedEvent) {
+        for (strategy, task_keys) in &mut self.strategies {
+            if is_bound(task_keys.as_deref(), &event.key) {
+                dispatch_task_event(strategy, event.event.clone()).await;
+            }
         }
     }
 }
@@ -1,11 +1,15 @@
 use std::sync::Arc;
 
+use tokio::task::JoinHandle;
 use tracing::info;
 
 use crate::arch::{
     strategy_base::{
         command::command_core::CommandRegistry,
-        handler::{handler_core::strategy_handler_loop, task_channel::TaskChannels},
+        handler::{
+            handler_core::{dispatch_task_event, strategy_handler_loop},
+            task_channel::{RoutedEvent, TaskChannels},
+        },
     },
     task_execution::TaskKey,
     traits::strategy::{CommandEmitter, EventHandler, Strategy},
@@ -46,11 +50,18 @@ where
         self.strategy.initialize().await;
     }
 
+    async fn on_shutdown(&mut self) {
+        self.strategy.on_shutdown().await;
+    }
+
     fn strategy_name(&self) -> &'static str {
         self.strategy.strategy_name()
     }
 
-    async fn _spawn_strategy_tasks(&self, task_channels: &Arc<TaskChannels>) {
+    async fn _spawn_strategy_tasks(
+        &self,
+        task_channels: &Arc<TaskChannels>,
+    ) -> Vec<JoinHandle<()>> {
         let receivers = match &self.task_keys {
             Some(task_keys) => task_channels
                 .subscribe(task_keys.iter().cloned())
@@ -59,10 +70,16 @@ where
         };
         let strategy = self.strategy.clone();
 
-        tokio::spawn(async move {
+        vec![tokio::spawn(async move {
             info!("Spawned strategy task for {}", strategy.strategy_name());
             strategy_handler_loop(strategy, receivers).await;
-        });
+        })]
+    }
+
+    async fn _dispatch_event(&mut self, event: RoutedEvent) {
+        if is_bound(self.task_keys.as_deref(), &event.key) {
+            dispatch_task_event(&mut self.strategy, event.event).await;
+        }
     }
 }
 
@@ -81,6 +98,11 @@ where
 
 impl<S> EventHandler for InnerStrategyModule<S> where S: Strategy + Clone + Send + Sync + 'static {}
 
+/// Returns `true` when a module with these bindings receives `key`.
+pub(crate) fn is_bound(task_keys: Option<&[TaskKey]>, key: &TaskKey) -> bool {
+    task_keys.is_none_or(|task_keys| task_keys.contains(key))
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::{
@@ -1,9 +1,15 @@
+#[cfg(feature = "lob_clients")]
+mod cancel_after;
+#[cfg(feature = "lob_clients")]
+pub(crate) mod lob_execution;
 #[cfg(feature = "model_onnx")]
 mod model_onnx;
 
 #[cfg(feature = "model_zmq")]
 mod model_zmq;
 
+#[cfg(feature = "lob_clients")]
+use std::collections::HashMap;
 use std::{sync::Arc, time::Duration};
 use tokio::{
     select,
@@ -19,7 +25,12 @@ use super::{
     task_alt::{AltTaskInfo, AltTaskType},
     task_general::LogLevel,
 };
+#[cfg(feature = "lob_clients")]
+use crate::arch::market_assets::{exchange::lob_clients::LobClients, market_core::Market};
+#[cfg(feature = "lob_clients")]
+use crate::arch::strategy_base::risk::RiskGate;
 use crate::arch::{
+    infra_core::shutdown::ShutdownSignal,
     market_assets::api_general::get_micros_timestamp,
     strategy_base::{
         command::{ack_handle::AckStatus, command_core::TaskCommand},
@@ -36,19 +47,36 @@ pub(crate) struct AltTaskRunner {
     pub event_tx: broadcast::Sender<TaskEvent>,
     pub alt_info: Arc<AltTaskInfo>,
     pub task_id: u64,
+    pub shutdown: ShutdownSignal,
+    /// Clients used by `LobExecution` tasks, keyed by venue.
+    #[cfg(feature = "lob_clients")]
+    pub exec_clients: Arc<HashMap<Market, LobClients>>,
+    /// Pre-trade checks every `LobExecution` order passes before it is sent.
+    #[cfg(feature = "lob_clients")]
+    pub risk_gate: Option<RiskGate>,
 }
 
 impl AltTaskRunner {
+    /// Receives the next command, or `None` once the channel closed or
+    /// runtime shutdown was requested.
+    async fn recv_cmd(&mut self) -> Option<TaskCommand> {
+        select! {
+            cmd = self.cmd_rx.recv() => {
+                if cmd.is_none() {
+                    self.log(LogLevel::Error, "Command channel closed");
+                }
+                cmd
+            },
+            _ = self.shutdown.recv() => None,
+        }
+    }
+
     #[allow(dead_code)]
     async fn recv_feat_input(&mut self) -> Option<AltTensor> {
         loop {
-            match self.cmd_rx.recv().await {
-                Some(TaskCommand::FeatInput(tensor)) => return Some(tensor),
-                Some(cmd) => self.handle_cmd(cmd),
-                None => {
-                    self.log(LogLevel::Error, "Command channel closed");
-                    return None;
-                },
+            match self.recv_cmd().await? {
+                TaskCommand::FeatInput(tensor) => return Some(tensor),
+                cmd => self.handle_cmd(cmd),
             }
         }
     }
@@ -57,6 +85,7 @@ impl AltTaskRunner {
     fn emit_model_preds(&self, tensor: AltTensor) {
         let _ = self.event_tx.send(TaskEvent::ModelPreds(InfraMsg {
             task_id: self.task_id,
+            received_at: get_micros_timestamp(),
             data: Arc::new(tensor),
         }));
     }
@@ -72,11 +101,12 @@ impl AltTaskRunner {
     }
 
     async fn order_execution(&mut self) {
-        while let Some(cmd) = self.cmd_rx.recv().await {
+        while let Some(cmd) = self.recv_cmd().await {
             match cmd {
                 TaskCommand::OrderExecute(alt_orders) => {
                     let _ = self.event_tx.send(TaskEvent::OrderExecute(InfraMsg {
                         task_id: self.task_id,
+                        received_at: get_micros_timestamp(),
                         data: Arc::new(alt_orders),
                     }));
                 },
@@ -86,11 +116,12 @@ impl AltTaskRunner {
     }
 
     async fn inst_intent(&mut self) {
-        while let Some(cmd) = self.cmd_rx.recv().await {
+        while let Some(cmd) = self.recv_cmd().await {
             match cmd {
                 TaskCommand::InstIntent(alt_intent) => {
                     let _ = self.event_tx.send(TaskEvent::InstIntent(InfraMsg {
                         task_id: self.task_id,
+                        received_at: get_micros_timestamp(),
                         data: Arc::new(alt_intent),
                     }));
                 },
@@ -107,6 +138,7 @@ impl AltTaskRunner {
                     let _ = self.event_tx.send(
                         TaskEvent::Schedule(InfraMsg {
                             task_id: self.task_id,
+                            received_at: get_micros_timestamp(),
                             data: Arc::new(AltScheduleEvent {
                                 timestamp: get_micros_timestamp(),
                                 duration,
@@ -114,13 +146,10 @@ impl AltTaskRunner {
                         })
                     );
                 },
-                result = self.cmd_rx.recv() => {
+                result = self.recv_cmd() => {
                     match result {
                         Some(cmd) => self.handle_cmd(cmd),
-                        None => {
-                            self.log(LogLevel::Error, "Command channel closed");
-                            break;
-                        },
+                        None => break,
                     };
                 },
             }
@@ -132,6 +161,14 @@ impl AltTaskRunner {
             AltTaskType::OrderExecution => {
                 self.order_execution().await;
             },
+            #[cfg(feature = "lob_clients")]
+            AltTaskType::LobExecution => {
+                self.lob_execution().await;
+            },
+            #[cfg(feature = "lob_clients")]
+            AltTaskType::CancelAfter(config) => {
+                self.cancel_after(config).await;
+            },
             AltTaskType::InstIntent => {
                 self.inst_intent().await;
             },
@@ -152,6 +189,7 @@ impl AltTaskRunner {
     fn alt_event(&self) {
         let msg = TaskEvent::Alt(InfraMsg {
             task_id: self.task_id,
+            received_at: get_micros_timestamp(),
             data: self.alt_info.clone(),
         });
 
@@ -164,11 +202,19 @@ impl AltTaskRunner {
         let sleep_interval = Duration::from_secs(5);
         self.log(LogLevel::Info, "Spawned alt task");
         loop {
-            sleep(sleep_interval).await;
+            select! {
+                _ = sleep(sleep_interval) => {},
+                _ = self.shutdown.recv() => break,
+            }
             self.alt_event();
             self.log(LogLevel::Info, "Initiated");
             self.alt_task_distribution().await;
+
+            if self.shutdown.is_shutdown() {
+                break;
+            }
         }
+        self.log(LogLevel::Info, "Stopped alt task");
     }
 
     fn log(&self, level: LogLevel, msg: &str) {
@@ -0,0 +1,132 @@
+use std::{collections::HashMap, time::Duration};
+
+use tokio::{select, time::interval};
+
+use super::AltTaskRunner;
+use crate::arch::{
+    market_assets::{exchange::lob_clients::LobClients, market_core::Market},
+    task_execution::{task_alt::CancelAfterConfig, task_general::LogLevel},
+    traits::market_lob::LobPrivateRest,
+};
+use crate::errors::InfraError;
+
+impl AltTaskRunner {
+    /// Re-arms the venues' cancel-after countdown every `config.refresh`.
+    ///
+    /// The first refresh is immediate. A failed refresh is logged and retried
+    /// on the next tick; the countdown armed before keeps running meanwhile.
+    /// An invalid config is logged once and arms nothing.
+    pub(super) async fn cancel_after(&mut self, config: CancelAfterConfig) {
+        let targets = match cancel_after_targets(&config, &self.exec_clients) {
+            Ok(targets) => targets,
+            Err(msg) => {
+                self.log(LogLevel::Error, &msg);
+                while let Some(cmd) = self.recv_cmd().await {
+                    self.handle_cmd(cmd);
+                }
+                return;
+            },
+        };
+
+        let mut ticker = interval(config.refresh);
+        loop {
+            select! {
+                _ = ticker.tick() => self.arm_cancel_after(&targets, config.timeout).await,
+                result = self.recv_cmd() => {
+                    match result {
+                        Some(cmd) => self.handle_cmd(cmd),
+                        None => break,
+                    };
+                },
+            }
+        }
+    }
+
+    async fn arm_cancel_after(&self, targets: &[(Market, Option<String>)], timeout: Duration) {
+        for (market, inst) in targets {
+            let result = match self.exec_clients.get(market) {
+                Some(client) => client.set_cancel_after(inst.as_deref(), timeout).await,
+                None => Err(InfraError::Msg(format!(
+                    "No execution client registered for {market:?}"
+                ))),
+            };
+            if let Err(e) = result {
+                self.log(
+                    LogLevel::Warn,
+                    &format!("Cancel-after refresh failed for {market:?} {inst:?}: {e}"),
+                );
+            }
+        }
+    }
+}
+
+/// Scopes to arm for `config`. Empty targets arm every execution client with
+/// `inst = None`, which venues that arm per instrument refuse.
+fn cancel_after_targets(
+    config: &CancelAfterConfig,
+    exec_clients: &HashMap<Market, LobClients>,
+) -> Result<Vec<(Market, Option<String>)>, String> {
+    if config.refresh.is_zero() || config.refresh >= config.timeout {
+        return Err(
+            "Cancel-after refresh must be greater than zero and shorter than the timeout".into(),
+        );
+    }
+    if !config.targets.is_empty() {
+        return Ok(config.targets.clone());
+    }
+
+    let per_inst: Vec<_> = exec_clients
+        .iter()
+        .filter(|(_, client)| client.cancel_after_needs_inst())
+        .map(|(market, _)| market)
+        .collect();
+    if !per_inst.is_empty() {
+        return Err(format!(
+            "Cancel-after on {per_inst:?} arms per instrument; list its instruments in targets"
+        ));
+    }
+
+    Ok(exec_clients
+        .keys()
+        .map(|market| (market.clone(), None))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::market_assets::exchange::{
+        binance::binance_um_futures_cli::BinanceUmCli, okx::okx_cli::OkxCli,
+    };
+
+    fn config(targets: Vec<(Market, Option<String>)>) -> CancelAfterConfig {
+        CancelAfterConfig {
+            timeout: Duration::from_secs(60),
+            refresh: Duration::from_secs(15),
+            targets,
+        }
+    }
+
+    #[test]
+    fn empty_targets_are_refused_for_per_instrument_venues() {
+        let okx = HashMap::from([(Market::Okx, LobClients::Okx(OkxCli::default()))]);
+        assert_eq!(
+            cancel_after_targets(&config(Vec::new()), &okx),
+            Ok(vec![(Market::Okx, None)])
+        );
+
+        let mut clients = okx;
+        clients.insert(
+            Market::BinanceUmFutures,
+            LobClients::BinanceUm(BinanceUmCli::default()),
+        );
+        let error = cancel_after_targets(&config(Vec::new()), &clients).unwrap_err();
+        assert!(error.contains("BinanceUmFutures"), "{error}");
+
+        let targets = vec![(Market::BinanceUmFutures, Some("BTC_USDT_PERP".to_string()))];
+        assert_eq!(
+            cancel_after_targets(&config(targets.clone()), &clients),
+            Ok(targets)
+        );
+    }
+}
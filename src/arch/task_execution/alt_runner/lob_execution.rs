@@ -0,0 +1,455 @@
+use std::sync::Arc;
+
+use super::AltTaskRunner;
+use crate::arch::{
+    market_assets::{
+        api_data::account_data::OrderAckData, api_general::get_micros_timestamp,
+        base_data::OrderStatus, exchange::lob_clients::LobClients, market_core::Market,
+    },
+    strategy_base::{
+        command::command_core::TaskCommand,
+        handler::{
+            alt_events::{AltCancel, AltOrder, AltOrderAck, AltOrderError, AltOrderRequest},
+            task_channel::{InfraMsg, TaskEvent},
+        },
+        risk::RiskGate,
+    },
+    traits::market_lob::LobPrivateRest,
+};
+use crate::errors::{InfraError, InfraResult};
+
+impl AltTaskRunner {
+    /// Serves orders and cancels through the registered exchange clients.
+    ///
+    /// Commands run one at a time, so a cancel sent after an order reaches the
+    /// venue after that order. Within a command, requests are grouped by
+    /// market and split into batches the venue accepts. Every batch publishes
+    /// one `OrderAck` event as soon as its response arrives. Orders refused by
+    /// the risk gate are acknowledged together, before anything is sent.
+    pub(super) async fn lob_execution(&mut self) {
+        while let Some(cmd) = self.recv_cmd().await {
+            match cmd {
+                TaskCommand::OrderExecute(orders) => self.place_orders(orders).await,
+                TaskCommand::OrderCancel(cancels) => self.cancel_orders(cancels).await,
+                _ => self.handle_cmd(cmd),
+            };
+        }
+    }
+
+    async fn place_orders(&self, orders: Vec<AltOrder>) {
+        let orders = self.screen_orders(orders);
+        for (market, orders) in group_by_market(orders, |order| &order.market) {
+            let client = self.exec_clients.get(&market);
+            let limit = client.map_or(orders.len(), LobClients::place_batch_limit);
+
+            for batch in orders.chunks(limit.max(1)) {
+                let results = match client {
+                    None => no_client(&market, batch.len()),
+                    Some(client) if batch.len() == 1 => {
+                        let params = batch[0].order_params.clone();
+                        vec![client.place_order(params).await.map_err(request_error)]
+                    },
+                    Some(client) => {
+                        let params = batch.iter().map(|o| o.order_params.clone()).collect();
+                        spread(client.place_orders(params).await, batch.len())
+                    },
+                };
+
+                let acks: Vec<_> = batch
+                    .iter()
+                    .zip(results)
+                    .map(|(order, result)| AltOrderAck {
+                        timestamp: get_micros_timestamp(),
+                        market: market.clone(),
+                        request: AltOrderRequest::Place(order.order_params.clone()),
+                        metadata: order.metadata.clone(),
+                        result,
+                    })
+                    .collect();
+                release_failed(self.risk_gate.as_ref(), &acks);
+                self.publish_acks(acks);
+            }
+        }
+    }
+
+    async fn cancel_orders(&self, cancels: Vec<AltCancel>) {
+        for (market, cancels) in group_by_market(cancels, |cancel| &cancel.market) {
+            let client = self.exec_clients.get(&market);
+            let limit = client.map_or(cancels.len(), LobClients::cancel_batch_limit);
+
+            for batch in cancels.chunks(limit.max(1)) {
+                let results = match client {
+                    None => no_client(&market, batch.len()),
+                    Some(client) if batch.len() == 1 => {
+                        let params = &batch[0].cancel_params;
+                        let result = client
+                            .cancel_order(
+                                &params.inst,
+                                params.order_id.as_deref(),
+                                params.cli_order_id.as_deref(),
+                            )
+                            .await;
+                        vec![result.map_err(request_error)]
+                    },
+                    Some(client) => {
+                        let params = batch.iter().map(|c| c.cancel_params.clone()).collect();
+                        spread(client.cancel_orders(params).await, batch.len())
+                    },
+                };
+
+                let acks = batch
+                    .iter()
+                    .zip(results)
+                    .map(|(cancel, result)| AltOrderAck {
+                        timestamp: get_micros_timestamp(),
+                        market: market.clone(),
+                        request: AltOrderRequest::Cancel(cancel.cancel_params.clone()),
+                        metadata: cancel.metadata.clone(),
+                        result,
+                    });
+                self.publish_acks(acks.collect());
+            }
+        }
+    }
+
+    /// Runs every order through the risk gate and acknowledges the rejected
+    /// ones, returning the orders that may be sent.
+    fn screen_orders(&self, orders: Vec<AltOrder>) -> Vec<AltOrder> {
+        let (approved, rejected) = screen_orders(self.risk_gate.as_ref(), orders);
+        if !rejected.is_empty() {
+            self.publish_acks(rejected);
+        }
+        approved
+    }
+
+    fn publish_acks(&self, acks: Vec<AltOrderAck>) {
+        let _ = self.event_tx.send(TaskEvent::OrderAck(InfraMsg {
+            task_id: self.task_id,
+            received_at: get_micros_timestamp(),
+            data: Arc::new(acks),
+        }));
+    }
+}
+
+/// Splits orders into those `gate` approves and acks for those it refuses.
+pub(crate) fn screen_orders(
+    gate: Option<&RiskGate>,
+    orders: Vec<AltOrder>,
+) -> (Vec<AltOrder>, Vec<AltOrderAck>) {
+    let Some(gate) = gate else {
+        return (orders, Vec::new());
+    };
+
+    let mut approved = Vec::with_capacity(orders.len());
+    let mut rejected = Vec::new();
+    for order in orders {
+        let now = get_micros_timestamp();
+        match gate.approve(&order.market, &order.order_params, now) {
+            Ok(()) => approved.push(order),
+            Err(rejection) => rejected.push(AltOrderAck {
+                timestamp: now,
+                market: order.market,
+                request: AltOrderRequest::Place(order.order_params),
+                metadata: order.metadata,
+                result: Err(AltOrderError::Risk(rejection)),
+            }),
+        }
+    }
+    (approved, rejected)
+}
+
+/// Releases the open-order slots `gate` holds for placements that failed or
+/// were rejected by the venue, since no order update will free them.
+pub(crate) fn release_failed(gate: Option<&RiskGate>, acks: &[AltOrderAck]) {
+    let Some(gate) = gate else {
+        return;
+    };
+    for ack in acks {
+        let AltOrderRequest::Place(params) = &ack.request else {
+            continue;
+        };
+        let failed = match &ack.result {
+            Ok(data) => matches!(data.order_status, OrderStatus::Rejected),
+            Err(_) => true,
+        };
+        if failed && let Some(id) = &params.client_order_id {
+            gate.release(&ack.market, id);
+        }
+    }
+}
+
+/// Groups requests by market, keeping the order of first appearance and the
+/// request order within each market.
+fn group_by_market<T>(items: Vec<T>, market: impl Fn(&T) -> &Market) -> Vec<(Market, Vec<T>)> {
+    let mut groups: Vec<(Market, Vec<T>)> = Vec::new();
+    for item in items {
+        match groups.iter_mut().find(|(m, _)| m == market(&item)) {
+            Some((_, group)) => group.push(item),
+            None => groups.push((market(&item).clone(), vec![item])),
+        }
+    }
+    groups
+}
+
+/// Splits a batch response into one result per request.
+///
+/// A failed request fails every entry. Acks missing from a short response are
+/// reported as errors rather than silently dropped.
+pub(crate) fn spread(
+    result: InfraResult<Vec<OrderAckData>>,
+    len: usize,
+) -> Vec<Result<OrderAckData, AltOrderError>> {
+    match result {
+        Ok(acks) => {
+            let received = acks.len();
+            let mut results: Vec<_> = acks.into_iter().take(len).map(Ok).collect();
+            results.resize_with(len, || {
+                Err(AltOrderError::Request(format!(
+                    "Batch response holds {received} acks for {len} requests"
+                )))
+            });
+            results
+        },
+        Err(e) => vec![Err(request_error(e)); len],
+    }
+}
+
+pub(crate) fn request_error(e: InfraError) -> AltOrderError {
+    match e {
+        InfraError::RiskRejected(rejection) => AltOrderError::Risk(rejection),
+        e => AltOrderError::Request(e.to_string()),
+    }
+}
+
+pub(crate) fn no_client(market: &Market, len: usize) -> Vec<Result<OrderAckData, AltOrderError>> {
+    let msg = format!("No execution client registered for {market:?}");
+    vec![Err(AltOrderError::Request(msg)); len]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use tokio::sync::{broadcast, mpsc};
+
+    use super::*;
+    use crate::arch::{
+        infra_core::shutdown::ShutdownHandle,
+        market_assets::{
+            api_general::{CancelOrderParams, OrderParams},
+            base_data::{OrderSide, OrderType},
+        },
+        strategy_base::risk::{RiskGate, RiskLimits, RiskRejection},
+        task_execution::task_alt::{AltTaskInfo, AltTaskType},
+    };
+
+    fn ack(order_id: &str) -> OrderAckData {
+        OrderAckData {
+            timestamp: 1,
+            order_status: OrderStatus::Live,
+            order_id: order_id.into(),
+            cli_order_id: None,
+            msg: None,
+        }
+    }
+
+    #[test]
+    fn groups_keep_request_order() {
+        let items = vec![(Market::Okx, 1), (Market::GateFutures, 2), (Market::Okx, 3)];
+        let groups = group_by_market(items, |(market, _)| market);
+        let ids: Vec<(Market, Vec<i32>)> = groups
+            .into_iter()
+            .map(|(market, items)| (market, items.into_iter().map(|(_, id)| id).collect()))
+            .collect();
+        assert_eq!(
+            ids,
+            [(Market::Okx, vec![1, 3]), (Market::GateFutures, vec![2])]
+        );
+    }
+
+    #[test]
+    fn spread_reports_missing_and_failed_acks() {
+        let results = spread(Ok(vec![ack("a")]), 2);
+        assert_eq!(results[0], Ok(ack("a")));
+        assert!(results[1].is_err());
+
+        let results = spread(Err(InfraError::Msg("rate limited".into())), 2);
+        let failed = Err(AltOrderError::Request("rate limited".into()));
+        assert_eq!(results, [failed.clone(), failed]);
+    }
+
+    fn runner(
+        risk_gate: Option<RiskGate>,
+    ) -> (
+        AltTaskRunner,
+        mpsc::Sender<TaskCommand>,
+        broadcast::Receiver<TaskEvent>,
+        ShutdownHandle,
+    ) {
+        let (cmd_tx, cmd_rx) = mpsc::channel(4);
+        let (event_tx, event_rx) = broadcast::channel(4);
+        let shutdown = ShutdownHandle::new();
+        let runner = AltTaskRunner {
+            cmd_rx,
+            event_tx,
+            alt_info: Arc::new(AltTaskInfo {
+                alt_task_type: AltTaskType::LobExecution,
+                chunk: 1,
+                task_base_id: Some(3),
+            }),
+            task_id: 3,
+            shutdown: shutdown.signal(),
+            exec_clients: Arc::new(HashMap::new()),
+            risk_gate,
+        };
+        (runner, cmd_tx, event_rx, shutdown)
+    }
+
+    #[tokio::test]
+    async fn requests_without_client_are_acked_with_errors() {
+        let (mut runner, cmd_tx, mut event_rx, shutdown) = runner(None);
+        let task = tokio::spawn(async move { runner.lob_execution().await });
+
+        let order = AltOrder {
+            market: Market::Okx,
+            order_params: OrderParams {
+                inst: "BTC-USDT-SWAP".into(),
+                ..Default::default()
+            },
+            metadata: HashMap::from([("tag".into(), "entry".into())]),
+            ..Default::default()
+        };
+        let cancel = AltCancel {
+            market: Market::Okx,
+            cancel_params: CancelOrderParams {
+                inst: "BTC-USDT-SWAP".into(),
+                order_id: Some("1".into()),
+                cli_order_id: None,
+            },
+            ..Default::default()
+        };
+        cmd_tx
+            .send(TaskCommand::OrderExecute(vec![order.clone(), order]))
+            .await
+            .unwrap();
+        cmd_tx
+            .send(TaskCommand::OrderCancel(vec![cancel]))
+            .await
+            .unwrap();
+
+        let Ok(TaskEvent::OrderAck(placed)) = event_rx.recv().await else {
+            panic!("expected order acks");
+        };
+        assert_eq!(placed.task_id, 3);
+        assert_eq!(placed.data.len(), 2);
+        assert!(placed.data.iter().all(|ack| ack.result.is_err()));
+        assert_eq!(placed.data[0].metadata["tag"], "entry");
+
+        let Ok(TaskEvent::OrderAck(canceled)) = event_rx.recv().await else {
+            panic!("expected cancel acks");
+        };
+        assert!(matches!(
+            canceled.data[0].request,
+            AltOrderRequest::Cancel(_)
+        ));
+
+        shutdown.shutdown();
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn risk_rejections_are_acked_before_sending() {
+        let gate = RiskGate::new(RiskLimits {
+            max_order_notional: Some(1_000.0),
+            ..Default::default()
+        });
+        let (mut runner, cmd_tx, mut event_rx, shutdown) = runner(Some(gate));
+        let task = tokio::spawn(async move { runner.lob_execution().await });
+
+        let order = |size: &str| AltOrder {
+            market: Market::Okx,
+            order_params: OrderParams {
+                inst: "BTC-USDT-SWAP".into(),
+                side: OrderSide::BUY,
+                size: size.into(),
+                order_type: OrderType::Limit,
+                price: Some("100".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        cmd_tx
+            .send(TaskCommand::OrderExecute(vec![order("20"), order("1")]))
+            .await
+            .unwrap();
+
+        let Ok(TaskEvent::OrderAck(rejected)) = event_rx.recv().await else {
+            panic!("expected risk rejections");
+        };
+        assert_eq!(rejected.data.len(), 1);
+        assert_eq!(
+            rejected.data[0].result,
+            Err(AltOrderError::Risk(RiskRejection::OrderNotional {
+                notional: 2_000.0,
+                limit: 1_000.0,
+            }))
+        );
+
+        // The approved order still goes out, and fails for lack of a client.
+        let Ok(TaskEvent::OrderAck(sent)) = event_rx.recv().await else {
+            panic!("expected order acks");
+        };
+        assert!(matches!(
+            sent.data[0].result,
+            Err(AltOrderError::Request(_))
+        ));
+
+        shutdown.shutdown();
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_placements_release_their_open_order_slot() {
+        let gate = RiskGate::new(RiskLimits {
+            max_open_orders: Some(1),
+            ..Default::default()
+        });
+        let (mut runner, cmd_tx, mut event_rx, shutdown) = runner(Some(gate.clone()));
+        let task = tokio::spawn(async move { runner.lob_execution().await });
+
+        let order = |id: &str| AltOrder {
+            market: Market::Okx,
+            order_params: OrderParams {
+                inst: "BTC-USDT-SWAP".into(),
+                side: OrderSide::BUY,
+                size: "1".into(),
+                order_type: OrderType::Limit,
+                price: Some("100".into()),
+                client_order_id: Some(id.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Without a client the placement fails, and no order update follows.
+        cmd_tx
+            .send(TaskCommand::OrderExecute(vec![order("a")]))
+            .await
+            .unwrap();
+        let Ok(TaskEvent::OrderAck(failed)) = event_rx.recv().await else {
+            panic!("expected order acks");
+        };
+        assert!(matches!(
+            failed.data[0].result,
+            Err(AltOrderError::Request(_))
+        ));
+
+        assert_eq!(
+            gate.approve(&Market::Okx, &order("b").order_params, 0),
+            Ok(())
+        );
+
+        shutdown.shutdown();
+        task.await.unwrap();
+    }
+}
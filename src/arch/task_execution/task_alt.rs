@@ -1,5 +1,8 @@
 use std::time::Duration;
 
+#[cfg(feature = "lob_clients")]
+use crate::arch::market_assets::market_core::Market;
+
 /// Descriptor for a non-websocket runtime task.
 ///
 /// Alt tasks cover scheduler ticks, order execution relays, instrument intents,
@@ -19,7 +22,24 @@ pub struct AltTaskInfo {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum AltTaskType {
     /// Order execution task.
+    ///
+    /// Re-publishes `OrderExecute` batches to strategies, which place the
+    /// orders themselves.
     OrderExecution,
+    /// Order execution task that places and cancels orders itself.
+    ///
+    /// Serves `OrderExecute` and `OrderCancel` through the clients registered
+    /// with `EnvMediator::with_execution_client`, and publishes each result as
+    /// an `AltOrderAck`.
+    #[cfg(feature = "lob_clients")]
+    LobExecution,
+    /// Dead man's switch keeper.
+    ///
+    /// Re-arms `set_cancel_after` on the clients registered with
+    /// `EnvMediator::with_execution_client` every `refresh`, so the venues
+    /// pull open orders if the process hangs or loses connectivity.
+    #[cfg(feature = "lob_clients")]
+    CancelAfter(CancelAfterConfig),
     /// Instrument, allocation, or portfolio intent task.
     InstIntent,
     /// Model prediction worker.
@@ -33,6 +53,24 @@ pub enum AltTaskType {
     TimeScheduler(Duration),
 }
 
+/// Settings of an [`AltTaskType::CancelAfter`] task.
+///
+/// `refresh` must be greater than zero and shorter than `timeout`. The
+/// countdown stays armed when the task stops, so a clean shutdown also pulls
+/// the orders once it lapses unless the strategy disarms it.
+#[cfg(feature = "lob_clients")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CancelAfterConfig {
+    /// Countdown armed on every refresh.
+    pub timeout: Duration,
+    /// Time between refreshes.
+    pub refresh: Duration,
+    /// Scopes to arm as `(market, inst)`. Empty arms every registered
+    /// execution client with `inst = None`, and is refused when a client
+    /// arms per instrument, such as Binance UM; list its instruments here.
+    pub targets: Vec<(Market, Option<String>)>,
+}
+
 /// Supported model worker backends.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ModelRunner {
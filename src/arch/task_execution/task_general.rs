@@ -111,13 +111,51 @@ impl WsTaskInfo {
     ///
     /// IDs start at `task_base_id`, or at `1` when it is `None`, and continue
     /// consecutively for `chunk` entries. A zero `chunk` returns an empty vector.
+    /// A sharded declaration gets one entry per shard instead.
     ///
-    /// Returns an error when the configured task-id range exceeds `u64`.
+    /// Returns an error when the configured task-id range exceeds `u64`, or
+    /// when `shards` has no instruments or a zero `per_connection`.
     pub fn task_keys(&self) -> InfraResult<Vec<TaskKey>> {
-        expand_task_keys(self.chunk, self.task_base_id, |task_id| {
+        expand_task_keys(self.relay_count()?, self.task_base_id, |task_id| {
             TaskKey::ws(&self.ws_channel, task_id)
         })
     }
+
+    /// Instruments assigned to the relay with `task_id`, when sharded.
+    ///
+    /// Shares differ by at most one instrument and keep the order of
+    /// `shards.insts`, so a universe of 450 at 200 per connection splits
+    /// into 150 per relay rather than 200, 200 and 50.
+    pub fn shard_insts(&self, task_id: u64) -> Option<&[String]> {
+        let shards = self.shards.as_ref()?;
+        let count = shard_count(shards.insts.len(), shards.per_connection);
+        let index = task_id.checked_sub(self.task_base_id.unwrap_or(1))? as usize;
+        if index >= count {
+            return None;
+        }
+
+        let (size, extra) = (shards.insts.len() / count, shards.insts.len() % count);
+        let start = index * size + index.min(extra);
+        let end = start + size + usize::from(index < extra);
+        Some(&shards.insts[start..end])
+    }
+
+    fn relay_count(&self) -> InfraResult<u64> {
+        let Some(shards) = &self.shards else {
+            return Ok(self.chunk);
+        };
+        if shards.insts.is_empty() || shards.per_connection == 0 {
+            return Err(InfraError::Msg(format!(
+                "sharded {:?} task needs instruments and a non-zero per_connection",
+                self.ws_channel
+            )));
+        }
+        Ok(shard_count(shards.insts.len(), shards.per_connection) as u64)
+    }
+}
+
+fn shard_count(insts: usize, per_connection: usize) -> usize {
+    insts.div_ceil(per_connection.max(1))
 }
 
 impl AltTaskInfo {
@@ -136,7 +174,7 @@ impl AltTaskInfo {
 
 impl TaskInfo {
     pub(crate) fn task_ids(&self) -> InfraResult<Vec<u64>> {
-        let (chunk, task_base_id) = self.task_range();
+        let (chunk, task_base_id) = self.task_range()?;
         expand_task_ids(chunk, task_base_id)
     }
 
@@ -158,10 +196,10 @@ impl TaskInfo {
         }
     }
 
-    fn task_range(&self) -> (u64, Option<u64>) {
+    fn task_range(&self) -> InfraResult<(u64, Option<u64>)> {
         match self {
-            Self::WsTask(task) => (task.chunk, task.task_base_id),
-            Self::AltTask(task) => (task.chunk, task.task_base_id),
+            Self::WsTask(task) => Ok((task.relay_count()?, task.task_base_id)),
+            Self::AltTask(task) => Ok((task.chunk, task.task_base_id)),
         }
     }
 }
@@ -182,12 +220,26 @@ mod tests {
         task_execution::{
             TaskKey,
             task_alt::AltTaskType,
-            task_ws::{TradesParam, WsChannel, WsTaskInfo},
+            task_ws::{TradesParam, WsChannel, WsShards, WsTaskInfo},
         },
     };
 
     use super::*;
 
+    fn sharded(insts: usize, per_connection: usize) -> WsTaskInfo {
+        WsTaskInfo {
+            market: Market::BinanceUmFutures,
+            ws_channel: WsChannel::Trades(None),
+            filter_channels: false,
+            chunk: 1,
+            task_base_id: Some(5),
+            shards: Some(WsShards {
+                insts: (0..insts).map(|i| format!("INST{i}")).collect(),
+                per_connection,
+            }),
+        }
+    }
+
     #[test]
     fn task_keys_expand_chunk_from_base_id() {
         let task = TaskInfo::WsTask(Arc::new(WsTaskInfo {
@@ -196,6 +248,7 @@ mod tests {
             filter_channels: false,
             chunk: 3,
             task_base_id: Some(10),
+            shards: None,
         }));
 
         assert_eq!(
@@ -216,6 +269,7 @@ mod tests {
             filter_channels: false,
             chunk: 3,
             task_base_id: Some(10),
+            shards: None,
         };
 
         assert_eq!(
@@ -253,6 +307,7 @@ mod tests {
             filter_channels: false,
             chunk: 1,
             task_base_id: Some(u64::MAX),
+            shards: None,
         }));
 
         assert_eq!(task.task_ids().unwrap(), vec![u64::MAX]);
@@ -266,12 +321,46 @@ mod tests {
             filter_channels: false,
             chunk: 2,
             task_base_id: Some(u64::MAX),
+            shards: None,
         }));
 
         assert!(task.task_ids().is_err());
         assert!(task.task_keys().is_err());
     }
 
+    #[test]
+    fn shards_expand_into_balanced_relays() {
+        let task = sharded(450, 200);
+        let channel = WsChannel::Trades(None);
+
+        assert_eq!(
+            task.task_keys().unwrap(),
+            vec![
+                TaskKey::ws(&channel, 5),
+                TaskKey::ws(&channel, 6),
+                TaskKey::ws(&channel, 7),
+            ]
+        );
+        let shares: Vec<_> = (5..8).map(|id| task.shard_insts(id).unwrap()).collect();
+        assert!(shares.iter().all(|share| share.len() == 150));
+        assert_eq!(shares[1][0], "INST150");
+        assert_eq!(shares.concat(), task.shards.as_ref().unwrap().insts);
+        assert_eq!(task.shard_insts(4), None);
+        assert_eq!(task.shard_insts(8), None);
+
+        let uneven = sharded(7, 3);
+        let sizes: Vec<_> = (5..8)
+            .map(|id| uneven.shard_insts(id).unwrap().len())
+            .collect();
+        assert_eq!(sizes, vec![3, 2, 2]);
+    }
+
+    #[test]
+    fn empty_or_zero_capacity_shards_are_rejected() {
+        assert!(sharded(0, 200).task_keys().is_err());
+        assert!(TaskInfo::from(sharded(10, 0)).task_ids().is_err());
+    }
+
     #[test]
     fn websocket_channel_configuration_is_part_of_identity() {
         let aggregated = TaskKey::ws(&WsChannel::Trades(Some(TradesParam::AggTrades)), 7);
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 use crate::arch::market_assets::market_core::Market;
 
 /// Descriptor for a websocket relay task.
@@ -18,9 +20,28 @@ pub struct WsTaskInfo {
     /// non-target messages. Failed payloads are dropped in either mode.
     pub filter_channels: bool,
     /// Number of task instances to spawn.
+    ///
+    /// Ignored when `shards` is set, which decides the count instead.
     pub chunk: u64,
     /// Optional first task id for generated task instances.
     pub task_base_id: Option<u64>,
+    /// Optional instrument universe split across as many relays as needed.
+    pub shards: Option<WsShards>,
+}
+
+/// Instrument universe of a sharded websocket task.
+///
+/// The task spawns one relay per `per_connection` instruments, each with its
+/// own task id, and assigns every relay a contiguous share of `insts`. Once
+/// the strategy connects a relay from `on_ws_event`, the relay subscribes
+/// `ws_channel` for its share itself, and again after managed reconnects.
+/// Strategies bound to the task receive the events of every shard.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WsShards {
+    /// Instruments to subscribe, in exchange-neutral form.
+    pub insts: Vec<String>,
+    /// Most instruments one connection carries.
+    pub per_connection: usize,
 }
 
 /// Websocket channel categories used by websocket task declarations.
@@ -31,6 +52,8 @@ pub struct WsTaskInfo {
 pub enum WsChannel {
     /// Private account order updates.
     AccountOrders,
+    /// Private per-execution fills with price, fee and liquidity.
+    AccountFills,
     /// Private balance and position updates.
     AccountBalAndPos,
     /// Private position-only updates.
@@ -43,12 +66,19 @@ pub enum WsChannel {
     Lob(Option<LobParam>),
     /// Public market-by-order order book stream.
     LobMbo,
+    /// Authenticated trading session serving `TaskCommand::OrderExecute` and
+    /// `TaskCommand::OrderCancel` over the websocket.
+    ///
+    /// Responses are published as `on_order_ack` events, like those of an
+    /// `AltTaskType::LobExecution` task. Requires the `lob_clients` feature
+    /// and an execution client registered for the market.
+    OrderEntry,
     /// Exchange-specific or custom stream.
     Other(String),
 }
 
 /// Candle interval used by candle websocket and REST APIs.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CandleParam {
     OneSecond,
     OneMinute,
@@ -118,6 +148,11 @@ pub enum LobParam {
         frequency: Option<LobFrequency>,
     },
     /// Incremental book update stream for maintaining a local book.
+    ///
+    /// On Binance UM futures, Gate futures, and OKX the relay also maintains
+    /// the book per instrument. It seeds and resynchronizes books from REST
+    /// snapshots and publishes `on_lob_book` views alongside the raw `on_lob`
+    /// deltas.
     Incremental {
         /// Optional number of price levels to request.
         depth: Option<u16>,
@@ -18,12 +18,25 @@ mod okx;
 ))]
 pub(crate) mod ws_decode;
 
+#[cfg(any(feature = "binance", feature = "gate", feature = "okx"))]
+mod lob_book;
+
+#[cfg(feature = "lob_clients")]
+mod order_entry;
+
+mod listen_key;
+mod reply;
+mod session;
+mod subscription;
+
 use futures_util::{SinkExt, StreamExt};
-use std::sync::Arc;
+#[cfg(feature = "lob_clients")]
+use std::collections::HashMap;
+use std::{future, sync::Arc};
 use tokio::{
     net::TcpStream,
     sync::{broadcast, mpsc},
-    time::{Duration, error::Elapsed, sleep, timeout},
+    time::{Duration, Instant, error::Elapsed, sleep, sleep_until, timeout},
 };
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
 use tungstenite::{
@@ -36,23 +49,55 @@ use tungstenite::{
 use tracing::{error, info, warn};
 
 use crate::arch::{
-    market_assets::market_core::Market,
+    infra_core::shutdown::ShutdownSignal,
+    market_assets::{api_general::get_micros_timestamp, market_core::Market},
     redaction::{contains_sensitive_content, redact_secret},
     strategy_base::{
         command::{
             ack_handle::{AckHandle, AckStatus},
-            command_core::{TaskCommand, WsConnectTarget},
+            command_core::{SnapshotSource, TaskCommand, WsConnectTarget, WsReplyMatcher},
+        },
+        handler::{
+            task_channel::{InfraMsg, TaskEvent},
+            ws_events::WsConnectionState,
         },
-        handler::task_channel::{InfraMsg, TaskEvent},
     },
     traits::conversion::IntoWsData,
 };
+#[cfg(feature = "lob_clients")]
+use crate::arch::{
+    market_assets::exchange::lob_clients::LobClients, strategy_base::risk::RiskGate,
+    task_execution::task_ws::WsChannel,
+};
 use crate::errors::{InfraError, InfraResult};
 
 use super::{task_general::LogLevel, task_ws::WsTaskInfo};
+use listen_key::ListenKey;
+use reply::PendingReplies;
+use session::{WsReplayMsg, WsSession};
+use subscription::Subscriptions;
 
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 static _PING: Bytes = Bytes::from_static(b"ping");
+const WS_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Completes at `deadline`, or never when there is none.
+async fn until_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => sleep_until(deadline).await,
+        None => future::pending().await,
+    }
+}
+
+/// Result of reading one websocket frame.
+enum WsRead<T> {
+    /// A decoded payload ready to publish.
+    Data(T),
+    /// Nothing to publish; keep reading.
+    Idle,
+    /// The connection ended; return to the connect cycle.
+    Closed,
+}
 
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -61,6 +106,22 @@ pub(crate) struct WsTaskRunner {
     pub event_tx: broadcast::Sender<TaskEvent>,
     pub ws_info: Arc<WsTaskInfo>,
     pub task_id: u64,
+    pub shutdown: ShutdownSignal,
+    /// Present while the current connection is managed by a reconnect policy.
+    pub session: Option<WsSession>,
+    /// Sent messages whose acks wait for the venue's reply.
+    pub replies: PendingReplies,
+    /// Public streams subscribed through `TaskCommand::Subscribe`.
+    pub subscriptions: Subscriptions,
+    /// Listen key of a connection opened with `WsConnectTarget::with_listen_key`.
+    pub listen_key: Option<ListenKey>,
+    /// Client `LobParam::Incremental` relays fetch book snapshots from.
+    pub snapshot_source: Option<SnapshotSource>,
+    /// Clients that build and read `OrderEntry` requests, by market.
+    #[cfg(feature = "lob_clients")]
+    pub exec_clients: Arc<HashMap<Market, LobClients>>,
+    #[cfg(feature = "lob_clients")]
+    pub risk_gate: Option<RiskGate>,
 }
 
 #[allow(dead_code)]
@@ -87,13 +148,12 @@ impl WsTaskRunner {
         })?;
         Ok(ws_stream)
     }
-    async fn handle_ws_msg<WsData, Decode>(
+    async fn read_ws_msg<WsData, Decode>(
         &mut self,
         msg: Result<Option<Result<Message, Error>>, Elapsed>,
         ws_stream: &mut WsStream,
-        into_event: impl Fn(InfraMsg<WsData::Output>) -> TaskEvent + Copy + Send,
         decode: Decode,
-    ) -> bool
+    ) -> WsRead<WsData::Output>
     where
         WsData: IntoWsData + Send + 'static,
         WsData::Output: Send + Sync + 'static,
@@ -101,16 +161,18 @@ impl WsTaskRunner {
     {
         match msg {
             Ok(Some(Ok(Message::Text(text)))) => {
+                if self.replies.resolve(text.as_ref()) {
+                    return WsRead::Idle;
+                }
+                if self.listen_key_expired(text.as_ref(), ws_stream).await {
+                    return WsRead::Closed;
+                }
+
                 match decode(text.as_ref()) {
-                    Ok(parsed_raw) => {
-                        let _ = self.event_tx.send(into_event(InfraMsg {
-                            task_id: self.task_id,
-                            data: Arc::new(parsed_raw.into_ws()),
-                        }));
-                    },
+                    Ok(parsed_raw) => return WsRead::Data(parsed_raw.into_ws()),
                     Err(e) => {
                         if self.ws_info.filter_channels {
-                            return false;
+                            return WsRead::Idle;
                         }
 
                         self.log(
@@ -121,16 +183,15 @@ impl WsTaskRunner {
                 };
             },
             Ok(Some(Ok(Message::Binary(bytes)))) => {
+                if self.replies.resolve(bytes.as_ref()) {
+                    return WsRead::Idle;
+                }
+
                 match decode(bytes.as_ref()) {
-                    Ok(parsed_raw) => {
-                        let _ = self.event_tx.send(into_event(InfraMsg {
-                            task_id: self.task_id,
-                            data: Arc::new(parsed_raw.into_ws()),
-                        }));
-                    },
+                    Ok(parsed_raw) => return WsRead::Data(parsed_raw.into_ws()),
                     Err(e) => {
                         if self.ws_info.filter_channels {
-                            return false;
+                            return WsRead::Idle;
                         }
 
                         self.log(
@@ -145,18 +206,18 @@ impl WsTaskRunner {
             },
             Ok(Some(Ok(Message::Close(frame)))) => {
                 self.log(LogLevel::Error, &format!("WebSocket closed: {:?}", frame));
-                return true;
+                return WsRead::Closed;
             },
             Ok(Some(Err(e))) => {
                 self.log(
                     LogLevel::Error,
                     &format!("Error receiving WS message: {:?}", e),
                 );
-                return true;
+                return WsRead::Closed;
             },
             Ok(None) => {
                 self.log(LogLevel::Error, "WebSocket stream ended");
-                return true;
+                return WsRead::Closed;
             },
             Err(_) => {
                 let keepalive = match &self.ws_info.market {
@@ -169,24 +230,77 @@ impl WsTaskRunner {
                         LogLevel::Error,
                         &format!("Failed to send keepalive: {:?}", e),
                     );
-                    return true;
+                    return WsRead::Closed;
                 }
             },
             _ => {},
         };
 
-        false
+        WsRead::Idle
+    }
+
+    fn publish<T>(&self, into_event: impl Fn(InfraMsg<T>) -> TaskEvent, data: T) {
+        let _ = self.event_tx.send(into_event(InfraMsg {
+            task_id: self.task_id,
+            received_at: get_micros_timestamp(),
+            data: Arc::new(data),
+        }));
+    }
+
+    async fn close_on_shutdown(&self, ws_stream: &mut WsStream) {
+        if let Err(e) = ws_stream.close(None).await {
+            self.log(
+                LogLevel::Warn,
+                &format!("Failed to close ws on shutdown: {:?}", e),
+            );
+        }
     }
 
     async fn handle_command(&mut self, cmd: Option<TaskCommand>, ws_stream: &mut WsStream) -> bool {
         if let Some(cmd) = cmd {
             match cmd {
-                TaskCommand::WsMessage { msg, ack } => {
-                    self.send_cmd(ws_stream, msg, ack, AckStatus::WsMessage)
+                TaskCommand::WsMessage { msg, ack, reply } => {
+                    self.record_session(WsReplayMsg::Text(msg.clone()), &msg);
+                    self.send_cmd(ws_stream, msg, ack, AckStatus::WsMessage, reply)
                         .await
                 },
+                TaskCommand::WsBuiltMessage {
+                    builder,
+                    ack,
+                    reply,
+                } => match builder.build() {
+                    Ok(msg) => {
+                        self.record_session(WsReplayMsg::Built(builder), &msg);
+                        self.send_cmd(ws_stream, msg, ack, AckStatus::WsMessage, reply)
+                            .await
+                    },
+                    Err(e) => {
+                        self.log(
+                            LogLevel::Error,
+                            &format!("Failed to build ws message: {:?}", e),
+                        );
+                        match reply {
+                            Some(_) => ack.respond(AckStatus::WsRejected(format!(
+                                "Failed to build ws message: {}",
+                                e
+                            ))),
+                            None => ack.respond(AckStatus::WsMessage),
+                        }
+                    },
+                },
+                TaskCommand::Subscribe {
+                    channel,
+                    insts,
+                    ack,
+                } => self.subscribe(channel, insts, ack, ws_stream).await,
+                TaskCommand::Unsubscribe {
+                    channel,
+                    insts,
+                    ack,
+                } => self.unsubscribe(channel, insts, ack, ws_stream).await,
                 TaskCommand::WsShutdown { msg, ack } => {
-                    self.send_cmd(ws_stream, msg, ack, AckStatus::WsShutdown)
+                    self.session = None;
+                    self.send_cmd(ws_stream, msg, ack, AckStatus::WsShutdown, None)
                         .await;
                     return true;
                 },
@@ -200,14 +314,18 @@ impl WsTaskRunner {
         false
     }
 
+    /// Sends a command's text and acks it with `ack_status`, or, when `reply`
+    /// is set, leaves the ack waiting for the venue's reply.
     async fn send_cmd(
         &mut self,
         ws_stream: &mut WsStream,
         msg: String,
         ack_handle: AckHandle,
         ack_status: AckStatus,
+        reply: Option<WsReplyMatcher>,
     ) {
-        if ws_stream.send(Message::text(msg.clone())).await.is_err() {
+        let sent = ws_stream.send(Message::text(msg.clone())).await;
+        if let Err(e) = &sent {
             if contains_sensitive_content(&msg) {
                 self.log(
                     LogLevel::Error,
@@ -224,9 +342,31 @@ impl WsTaskRunner {
                     &format!("Failed to send {:?}: {}", ack_status, msg),
                 );
             }
+
+            if reply.is_some() {
+                ack_handle.respond(AckStatus::WsRejected(format!(
+                    "Failed to send ws message: {}",
+                    e
+                )));
+                return;
+            }
         }
 
-        ack_handle.respond(ack_status);
+        match reply {
+            Some(reply) => self.replies.push(reply, ack_handle),
+            None => ack_handle.respond(ack_status),
+        }
+    }
+
+    /// Acks messages whose replies did not arrive in time.
+    fn expire_replies(&mut self) {
+        let expired = self.replies.expire(Instant::now());
+        if expired > 0 {
+            self.log(
+                LogLevel::Warn,
+                &format!("{} ws messages timed out waiting for a reply", expired),
+            );
+        }
     }
 
     async fn ws_loop<WsData, Decode>(
@@ -239,25 +379,40 @@ impl WsTaskRunner {
         WsData::Output: Send + Sync + 'static,
         Decode: Fn(&[u8]) -> serde_json::Result<WsData> + Copy + Send,
     {
-        let timeout_sec = Duration::from_secs(10);
-
         loop {
             tokio::select! {
-                msg = timeout(timeout_sec, ws_stream.next()) => {
-                    if self.handle_ws_msg::<WsData, Decode>(msg, ws_stream, into_event, decode).await {
-                        break;
-                    };
+                msg = timeout(WS_READ_TIMEOUT, ws_stream.next()) => {
+                    match self.read_ws_msg::<WsData, Decode>(msg, ws_stream, decode).await {
+                        WsRead::Data(data) => self.publish(into_event, data),
+                        WsRead::Idle => {},
+                        WsRead::Closed => break,
+                    }
                 },
                 cmd = self.cmd_rx.recv() => {
                     if self.handle_command(cmd, ws_stream).await {
                         break;
                     }
                 },
+                _ = until_deadline(self.replies.next_deadline()) => self.expire_replies(),
+                _ = until_deadline(self.listen_key.as_ref().map(ListenKey::renew_at)) => {
+                    if self.renew_listen_key(ws_stream).await {
+                        break;
+                    }
+                },
+                _ = self.shutdown.recv() => {
+                    self.close_on_shutdown(ws_stream).await;
+                    break;
+                },
             }
         }
     }
 
     pub async fn ws_channel_distribution(&mut self, _ws_stream: &mut WsStream) {
+        #[cfg(feature = "lob_clients")]
+        if self.ws_info.ws_channel == WsChannel::OrderEntry {
+            return self.ws_order_entry(_ws_stream).await;
+        }
+
         match &self.ws_info.market {
             #[cfg(feature = "hyperliquid")]
             Market::HyperLiquid => {
@@ -294,6 +449,7 @@ impl WsTaskRunner {
     fn ws_event(&self) {
         let msg = TaskEvent::Ws(InfraMsg {
             task_id: self.task_id,
+            received_at: get_micros_timestamp(),
             data: self.ws_info.clone(),
         });
 
@@ -302,16 +458,133 @@ impl WsTaskRunner {
         }
     }
 
+    /// Keeps a login or subscribe sent on a managed connection for replay.
+    fn record_session(&mut self, entry: WsReplayMsg, sent: &str) {
+        let recorded = self
+            .session
+            .as_mut()
+            .is_none_or(|session| session.record(entry, sent));
+        if !recorded {
+            self.log(
+                LogLevel::Warn,
+                "Replay list is full, message will not be resent after a reconnect",
+            );
+        }
+    }
+
+    /// Sends the recorded session messages on a fresh connection.
+    async fn replay_session(&self, ws_stream: &mut WsStream) -> InfraResult<usize> {
+        let Some(session) = self.session.as_ref() else {
+            return Ok(0);
+        };
+
+        let mut replayed = 0;
+        for entry in session.replay() {
+            let msg = match entry.build() {
+                Ok(msg) => msg,
+                Err(e) => {
+                    self.log(
+                        LogLevel::Error,
+                        &format!("Skipped replay message that failed to build: {:?}", e),
+                    );
+                    continue;
+                },
+            };
+
+            ws_stream
+                .send(Message::text(msg))
+                .await
+                .map_err(|e| InfraError::WebSocket(Box::new(e)))?;
+            replayed += 1;
+        }
+
+        Ok(replayed)
+    }
+
+    /// Reconnects a managed session and replays it.
+    ///
+    /// Returns `None` for unmanaged connections, on shutdown, or once the
+    /// policy runs out of attempts.
+    async fn reconnect(&mut self) -> Option<WsStream> {
+        let session = self.session.clone()?;
+        let mut attempts = 0;
+
+        loop {
+            if session.exhausted(attempts) {
+                self.log(
+                    LogLevel::Error,
+                    &format!("Gave up reconnecting after {} attempts", attempts),
+                );
+                self.session = None;
+                self.publish(
+                    TaskEvent::WsConnection,
+                    WsConnectionState::GaveUp { attempts },
+                );
+                return None;
+            }
+
+            attempts += 1;
+            let delay = session.delay(attempts);
+            self.publish(
+                TaskEvent::WsConnection,
+                WsConnectionState::Reconnecting {
+                    attempt: attempts,
+                    delay,
+                },
+            );
+            tokio::select! {
+                _ = sleep(delay) => {},
+                _ = self.shutdown.recv() => return None,
+            }
+
+            let Ok(mut ws_stream) = self.connect_target(&session.target).await else {
+                continue;
+            };
+            self.publish(TaskEvent::WsConnection, WsConnectionState::Connected);
+
+            let replayed = match self.replay_session(&mut ws_stream).await {
+                Ok(replayed) => self
+                    .resubscribe(&mut ws_stream)
+                    .await
+                    .map(|resubscribed| replayed + resubscribed),
+                Err(e) => Err(e),
+            };
+            match replayed {
+                Ok(replayed) => {
+                    self.log(
+                        LogLevel::Info,
+                        &format!("Reconnected and replayed {} messages", replayed),
+                    );
+                    self.publish(
+                        TaskEvent::WsConnection,
+                        WsConnectionState::Resubscribed { replayed },
+                    );
+                    return Some(ws_stream);
+                },
+                Err(e) => self.log(
+                    LogLevel::Warn,
+                    &format!("Session replay failed, retrying: {:?}", e),
+                ),
+            }
+        }
+    }
+
     pub(crate) async fn ws_mid_relay(&mut self) {
         let sleep_interval = Duration::from_secs(5);
         self.log(LogLevel::Info, "Spawned ws task");
 
         loop {
-            sleep(sleep_interval).await;
+            tokio::select! {
+                _ = sleep(sleep_interval) => {},
+                _ = self.shutdown.recv() => break,
+            }
             self.ws_event();
             self.log(LogLevel::Info, "Initiated");
 
-            let initial_command = self.cmd_rx.recv().await;
+            let initial_command = tokio::select! {
+                cmd = self.cmd_rx.recv() => cmd,
+                _ = self.shutdown.recv() => break,
+            };
             let (target, ack) = match initial_command {
                 Some(TaskCommand::WsConnect { msg, ack }) => (WsConnectTarget::new(msg), ack),
                 Some(TaskCommand::WsConnectWithTarget { target, ack }) => (target, ack),
@@ -328,18 +601,46 @@ impl WsTaskRunner {
                 },
             };
 
-            let mut ws_stream = match self.connect_websocket(target).await {
+            let mut ws_stream = match self.connect_target(&target).await {
                 Ok(ws) => ws,
                 Err(e) => {
                     self.log(LogLevel::Error, &format!("Failed to connect ws: {:?}", e));
-                    sleep(Duration::from_secs(5)).await;
-                    continue;
+                    tokio::select! {
+                        _ = sleep(sleep_interval) => continue,
+                        _ = self.shutdown.recv() => break,
+                    }
                 },
             };
 
             ack.respond(AckStatus::WsConnect);
-            self.ws_channel_distribution(&mut ws_stream).await;
+            #[cfg(any(feature = "binance", feature = "gate", feature = "okx"))]
+            {
+                self.snapshot_source = self.book_snapshot_source(&target);
+            }
+            self.session = WsSession::from_target(&target);
+            self.subscriptions.clear();
+            self.subscribe_shard(&mut ws_stream).await;
+            self.publish(TaskEvent::WsConnection, WsConnectionState::Connected);
+
+            loop {
+                self.ws_channel_distribution(&mut ws_stream).await;
+                self.replies
+                    .reject_all("Connection closed before the venue replied");
+                if self.shutdown.is_shutdown() {
+                    break;
+                }
+
+                match self.reconnect().await {
+                    Some(stream) => ws_stream = stream,
+                    None => break,
+                }
+            }
+
+            if self.shutdown.is_shutdown() {
+                break;
+            }
         }
+        self.log(LogLevel::Info, "Stopped ws task");
     }
 
     fn log(&self, level: LogLevel, msg: &str) {
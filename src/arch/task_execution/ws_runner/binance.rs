@@ -5,10 +5,12 @@ use crate::arch::{
             cm_futures_ws::lob::{
                 WsBookTickerBinanceCM, WsDiffDepthBinanceCM, WsPartialDepthBinanceCM,
             },
-            spot_ws::account_order::WsAccountOrderEnvelopeBinanceSpot,
+            spot_ws::account_order::{
+                WsAccountOrderEnvelopeBinanceSpot, WsFillEnvelopeBinanceSpot,
+            },
             um_futures_ws::{
                 account_bal_and_pos::WsBalAndPosBinanceUM,
-                account_order::WsAccountOrderBinanceUM,
+                account_order::{WsAccountOrderBinanceUM, WsFillBinanceUM},
                 account_position::WsAccountPositionBinanceUM,
                 agg_trades::WsAggTradeBinanceUM,
                 candles::WsCandleBinanceUM,
@@ -23,7 +25,7 @@ use crate::arch::{
     },
 };
 
-use super::{WsStream, WsTaskRunner};
+use super::{WsStream, WsTaskRunner, ws_decode::FillBatch};
 
 impl WsTaskRunner {
     pub(super) async fn ws_channel_binance_um(&mut self, ws_stream: &mut WsStream) {
@@ -36,6 +38,12 @@ impl WsTaskRunner {
                 )
                 .await;
             },
+            WsChannel::AccountFills => {
+                self.ws_loop(TaskEvent::AccFill, ws_stream, |frame: &[u8]| {
+                    BinanceWsData::<WsFillBinanceUM>::decode_single(frame).map(FillBatch)
+                })
+                .await;
+            },
             WsChannel::AccountBalAndPos => {
                 self.ws_loop(
                     TaskEvent::AccBalPos,
@@ -85,7 +93,16 @@ impl WsTaskRunner {
                     )
                     .await;
                 },
-                None | Some(LobParam::Incremental { .. }) => {
+                Some(LobParam::Incremental { depth, .. }) => {
+                    let depth = *depth;
+                    self.ws_lob_book_loop(
+                        ws_stream,
+                        BinanceWsData::<WsDiffDepthBinanceUM>::decode_single,
+                        depth,
+                    )
+                    .await;
+                },
+                None => {
                     self.ws_loop(
                         TaskEvent::Lob,
                         ws_stream,
@@ -113,6 +130,12 @@ impl WsTaskRunner {
                 )
                 .await;
             },
+            WsChannel::AccountFills => {
+                self.ws_loop(TaskEvent::AccFill, ws_stream, |frame: &[u8]| {
+                    BinanceWsData::<WsFillEnvelopeBinanceSpot>::decode_single(frame).map(FillBatch)
+                })
+                .await;
+            },
             c => {
                 self.log(
                     LogLevel::Warn,
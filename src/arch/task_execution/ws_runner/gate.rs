@@ -7,8 +7,11 @@ use crate::arch::{
             candles::WsCandleGateFutures,
             lob::{WsBookTickerGateFutures, WsOrderBookGateFutures, WsOrderBookUpdateGateFutures},
             trades::WsTradeGateFutures,
+            user_trades::WsUserTradeGateFutures,
+        },
+        schemas::spot_ws::{
+            account_order::WsAccountOrderGateSpot, user_trades::WsUserTradeGateSpot,
         },
-        schemas::spot_ws::account_order::WsAccountOrderGateSpot,
     },
     strategy_base::handler::task_channel::TaskEvent,
     task_execution::{
@@ -30,6 +33,14 @@ impl WsTaskRunner {
                 )
                 .await;
             },
+            WsChannel::AccountFills => {
+                self.ws_loop(
+                    TaskEvent::AccFill,
+                    ws_stream,
+                    GateWsData::<WsUserTradeGateFutures>::decode_batch,
+                )
+                .await;
+            },
             WsChannel::AccountPositions => {
                 self.ws_loop(
                     TaskEvent::AccPos,
@@ -71,7 +82,16 @@ impl WsTaskRunner {
                     )
                     .await;
                 },
-                None | Some(LobParam::Incremental { .. }) => {
+                Some(LobParam::Incremental { depth, .. }) => {
+                    let depth = *depth;
+                    self.ws_lob_book_loop(
+                        ws_stream,
+                        GateWsData::<WsOrderBookUpdateGateFutures>::decode_single,
+                        depth,
+                    )
+                    .await;
+                },
+                None => {
                     self.ws_loop(
                         TaskEvent::Lob,
                         ws_stream,
@@ -99,6 +119,14 @@ impl WsTaskRunner {
                 )
                 .await;
             },
+            WsChannel::AccountFills => {
+                self.ws_loop(
+                    TaskEvent::AccFill,
+                    ws_stream,
+                    GateWsData::<WsUserTradeGateSpot>::decode_batch,
+                )
+                .await;
+            },
             c => {
                 self.log(
                     LogLevel::Warn,
@@ -4,7 +4,7 @@ use crate::arch::{
         schemas::ws::{
             account_order::WsAccountOrderHyperliquid,
             account_position::WsAccountPositionHyperliquid, lob::WsLobHyperliquid,
-            trades::WsTradeHyperliquid,
+            trades::WsTradeHyperliquid, user_fills::WsUserFillHyperliquid,
         },
     },
     strategy_base::handler::task_channel::TaskEvent,
@@ -27,6 +27,14 @@ impl WsTaskRunner {
                 )
                 .await;
             },
+            WsChannel::AccountFills => {
+                self.ws_loop(
+                    TaskEvent::AccFill,
+                    ws_stream,
+                    HyperliquidWsData::<WsUserFillHyperliquid>::decode_user_fills,
+                )
+                .await;
+            },
             WsChannel::AccountPositions => {
                 self.ws_loop(
                     TaskEvent::AccPos,
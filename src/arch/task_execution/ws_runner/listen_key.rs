@@ -0,0 +1,261 @@
+use serde_json::Value;
+use tokio::time::{Duration, Instant, timeout};
+
+use crate::arch::{
+    strategy_base::{
+        command::command_core::{ListenKeySource, WsConnectTarget},
+        handler::{task_channel::TaskEvent, ws_events::WsConnectionState},
+    },
+    task_execution::task_general::LogLevel,
+};
+use crate::errors::{InfraError, InfraResult};
+
+use super::{WsStream, WsTaskRunner};
+
+/// Time between renewals. Binance expires a key an hour after its last one.
+const RENEW_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// Time after a renewal until the venue expires the key.
+const KEY_VALIDITY: Duration = Duration::from_secs(60 * 60);
+/// Delay before retrying a failed renewal.
+const RENEW_RETRY: Duration = Duration::from_secs(60);
+/// Longest a create or renew request may take.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// User data stream event announcing that the key expired.
+const EXPIRED_EVENT: &str = "listenKeyExpired";
+
+impl ListenKeySource {
+    async fn create(&self) -> InfraResult<String> {
+        match self {
+            #[cfg(feature = "binance")]
+            Self::BinanceCm(cli) => Ok(cli.create_listen_key().await?.listenKey),
+            #[cfg(feature = "binance")]
+            Self::BinanceUm(cli) => Ok(cli.create_listen_key().await?.listenKey),
+            #[allow(unreachable_patterns)]
+            _ => Err(InfraError::Unimplemented),
+        }
+    }
+
+    async fn renew(&self) -> InfraResult<String> {
+        match self {
+            #[cfg(feature = "binance")]
+            Self::BinanceCm(cli) => Ok(cli.renew_listen_key().await?.listenKey),
+            #[cfg(feature = "binance")]
+            Self::BinanceUm(cli) => Ok(cli.renew_listen_key().await?.listenKey),
+            #[allow(unreachable_patterns)]
+            _ => Err(InfraError::Unimplemented),
+        }
+    }
+}
+
+/// Listen key of the current connection and its renewal schedule.
+#[derive(Debug)]
+pub(crate) struct ListenKey {
+    source: ListenKeySource,
+    key: String,
+    renew_at: Instant,
+    expires_at: Instant,
+}
+
+impl ListenKey {
+    fn new(source: ListenKeySource, key: String, now: Instant) -> Self {
+        Self {
+            source,
+            key,
+            renew_at: now + RENEW_INTERVAL,
+            expires_at: now + KEY_VALIDITY,
+        }
+    }
+
+    /// When the key is due for renewal.
+    pub(crate) fn renew_at(&self) -> Instant {
+        self.renew_at
+    }
+
+    fn renewed(&mut self, key: String, now: Instant) {
+        self.key = key;
+        self.renew_at = now + RENEW_INTERVAL;
+        self.expires_at = now + KEY_VALIDITY;
+    }
+
+    /// Schedules another attempt after a failed renewal.
+    ///
+    /// Returns `false` when the key would expire before that attempt.
+    fn retry(&mut self, now: Instant) -> bool {
+        self.renew_at = now + RENEW_RETRY;
+        self.renew_at < self.expires_at
+    }
+}
+
+/// Appends `key` to a user data stream URL.
+fn keyed_url(url: &str, key: &str) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{url}{separator}listenKey={key}")
+}
+
+/// Whether `frame` is the venue's notice that `key` expired.
+fn is_expiry(frame: &[u8], key: &str) -> bool {
+    if !frame
+        .windows(EXPIRED_EVENT.len())
+        .any(|window| window == EXPIRED_EVENT.as_bytes())
+    {
+        return false;
+    }
+
+    serde_json::from_slice::<Value>(frame).is_ok_and(|value| {
+        let event = if value["e"].is_string() {
+            &value
+        } else {
+            &value["data"]
+        };
+        event["e"] == EXPIRED_EVENT
+            && event["listenKey"]
+                .as_str()
+                .is_none_or(|expired| expired == key)
+    })
+}
+
+impl WsTaskRunner {
+    /// Connects to `target`, creating a listen key first when it has a source.
+    pub(super) async fn connect_target(
+        &mut self,
+        target: &WsConnectTarget,
+    ) -> InfraResult<WsStream> {
+        self.listen_key = None;
+        let Some(source) = &target.listen_key else {
+            return self.connect_websocket(target.clone()).await;
+        };
+
+        let key = timeout(REQUEST_TIMEOUT, source.create())
+            .await
+            .map_err(|_| InfraError::Msg("Listen key creation timed out".into()))??;
+        let mut keyed = target.clone();
+        keyed.url = keyed_url(&target.url, &key);
+
+        self.listen_key = Some(ListenKey::new(*source.clone(), key, Instant::now()));
+        self.connect_websocket(keyed).await
+    }
+
+    /// Closes the connection when `frame` reports its listen key expired.
+    ///
+    /// Returns `true` when it did, so the relay reconnects with a new key.
+    pub(super) async fn listen_key_expired(
+        &mut self,
+        frame: &[u8],
+        ws_stream: &mut WsStream,
+    ) -> bool {
+        let expired = self
+            .listen_key
+            .as_ref()
+            .is_some_and(|listen_key| is_expiry(frame, &listen_key.key));
+        if !expired {
+            return false;
+        }
+
+        self.expire_listen_key("Listen key expired", ws_stream)
+            .await;
+        true
+    }
+
+    /// Renews the connection's listen key.
+    ///
+    /// A failed renewal is retried every minute while the key is still valid.
+    /// Returns `true` once it no longer is and the connection was closed.
+    pub(super) async fn renew_listen_key(&mut self, ws_stream: &mut WsStream) -> bool {
+        let Some(listen_key) = self.listen_key.as_mut() else {
+            return false;
+        };
+
+        let renewed = timeout(REQUEST_TIMEOUT, listen_key.source.renew())
+            .await
+            .unwrap_or_else(|_| Err(InfraError::Msg("request timed out".into())));
+        let now = Instant::now();
+        let error = match renewed {
+            Ok(key) => {
+                listen_key.renewed(key, now);
+                self.publish(TaskEvent::WsConnection, WsConnectionState::ListenKeyRenewed);
+                return false;
+            },
+            Err(e) => e,
+        };
+
+        if listen_key.retry(now) {
+            self.log(
+                LogLevel::Warn,
+                &format!("Listen key renewal failed, retrying: {}", error),
+            );
+            return false;
+        }
+
+        let reason = format!("Listen key renewal failed until expiry: {}", error);
+        self.expire_listen_key(&reason, ws_stream).await;
+        true
+    }
+
+    async fn expire_listen_key(&mut self, reason: &str, ws_stream: &mut WsStream) {
+        self.log(LogLevel::Warn, &format!("{}, rotating key", reason));
+        self.listen_key = None;
+        self.publish(TaskEvent::WsConnection, WsConnectionState::ListenKeyExpired);
+
+        if let Err(e) = ws_stream.close(None).await {
+            self.log(
+                LogLevel::Warn,
+                &format!("Failed to close ws with expired listen key: {:?}", e),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_expiry_events() {
+        let expired = br#"{"e":"listenKeyExpired","E":1576653824250,"listenKey":"abc"}"#;
+        assert!(is_expiry(expired, "abc"));
+        assert!(!is_expiry(expired, "rotated"));
+        assert!(is_expiry(
+            br#"{"stream":"abc","data":{"e":"listenKeyExpired","E":1}}"#,
+            "abc"
+        ));
+        assert!(!is_expiry(
+            br#"{"e":"ORDER_TRADE_UPDATE","note":"listenKeyExpired"}"#,
+            "abc"
+        ));
+        assert!(!is_expiry(br#"{"e":"ACCOUNT_UPDATE"}"#, "abc"));
+    }
+
+    #[test]
+    fn appends_the_key_to_the_stream_url() {
+        assert_eq!(
+            keyed_url(
+                "wss://fstream.binance.com/private/ws?events=ACCOUNT_UPDATE",
+                "k1"
+            ),
+            "wss://fstream.binance.com/private/ws?events=ACCOUNT_UPDATE&listenKey=k1"
+        );
+        assert_eq!(
+            keyed_url("wss://example.invalid/ws", "k1"),
+            "wss://example.invalid/ws?listenKey=k1"
+        );
+    }
+
+    #[cfg(feature = "binance")]
+    #[test]
+    fn retries_failed_renewals_until_the_key_expires() {
+        use crate::arch::market_assets::exchange::binance::binance_um_futures_cli::BinanceUmCli;
+
+        let start = Instant::now();
+        let source = ListenKeySource::BinanceUm(BinanceUmCli::default());
+        let mut listen_key = ListenKey::new(source, "k1".into(), start);
+        assert_eq!(listen_key.renew_at, start + RENEW_INTERVAL);
+
+        assert!(listen_key.retry(start + RENEW_INTERVAL));
+        assert_eq!(listen_key.renew_at, start + RENEW_INTERVAL + RENEW_RETRY);
+        assert!(!listen_key.retry(start + KEY_VALIDITY - RENEW_RETRY));
+
+        listen_key.renewed("k2".into(), start + KEY_VALIDITY);
+        assert_eq!(listen_key.key, "k2");
+        assert!(listen_key.retry(start + KEY_VALIDITY + RENEW_INTERVAL));
+    }
+}
@@ -0,0 +1,548 @@
+use futures_util::StreamExt;
+use reqwest::Url;
+use std::collections::HashMap;
+use tokio::{
+    sync::mpsc,
+    time::{Duration, Instant, timeout},
+};
+use tracing::warn;
+
+#[cfg(feature = "binance")]
+use crate::arch::market_assets::exchange::binance::binance_um_futures_cli::BinanceUmCli;
+#[cfg(feature = "gate")]
+use crate::arch::market_assets::exchange::gate::gate_futures_cli::GateFuturesCli;
+#[cfg(feature = "lob_clients")]
+use crate::arch::market_assets::exchange::lob_clients::LobClients;
+#[cfg(feature = "okx")]
+use crate::arch::market_assets::exchange::okx::okx_cli::OkxCli;
+use crate::arch::{
+    market_assets::{
+        api_data::price_data::OrderBookData,
+        base_data::InstrumentType,
+        market_core::Market,
+        order_book::{LobApply, LocalOrderBook},
+    },
+    strategy_base::{
+        command::command_core::{SnapshotSource, WsConnectTarget},
+        handler::{
+            lob_events::{WsLob, WsLobBook},
+            task_channel::TaskEvent,
+        },
+    },
+    task_execution::task_ws::{LobParam, WsChannel},
+    traits::{conversion::IntoWsData, market_lob::LobPublicRest},
+};
+use crate::errors::{InfraError, InfraResult};
+
+use super::{WS_READ_TIMEOUT, WsRead, WsStream, WsTaskRunner, until_deadline};
+
+/// Levels per side published in each [`WsLobBook`] view.
+const BOOK_VIEW_DEPTH: usize = 20;
+/// Minimum delay between REST snapshot requests for one instrument.
+const SNAPSHOT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+type SnapshotResult = (String, InfraResult<OrderBookData>);
+
+impl WsTaskRunner {
+    /// Relays raw depth events and publishes a consistent book per instrument.
+    ///
+    /// Books are rebuilt for every connection. Instruments are discovered from
+    /// the stream; a REST snapshot is requested when an instrument has no
+    /// websocket snapshot yet or its book loses synchronization.
+    pub(super) async fn ws_lob_book_loop<WsData, Decode>(
+        &mut self,
+        ws_stream: &mut WsStream,
+        decode: Decode,
+        stream_depth: Option<u16>,
+    ) where
+        WsData: IntoWsData<Output = Vec<WsLob>> + Send + 'static,
+        Decode: Fn(&[u8]) -> serde_json::Result<WsData> + Copy + Send,
+    {
+        let (snapshot_tx, mut snapshot_rx) = mpsc::unbounded_channel();
+        let mut keeper = BookKeeper::new(
+            self.ws_info.market.clone(),
+            self.snapshot_source.clone(),
+            stream_depth,
+            snapshot_tx,
+        );
+
+        loop {
+            tokio::select! {
+                msg = timeout(WS_READ_TIMEOUT, ws_stream.next()) => {
+                    match self.read_ws_msg::<WsData, Decode>(msg, ws_stream, decode).await {
+                        WsRead::Data(lobs) => {
+                            let books = keeper.apply_batch(&lobs);
+                            self.publish(TaskEvent::Lob, lobs);
+                            if !books.is_empty() {
+                                self.publish(TaskEvent::LobBook, books);
+                            }
+                        },
+                        WsRead::Idle => {},
+                        WsRead::Closed => break,
+                    }
+                },
+                Some((inst, snapshot)) = snapshot_rx.recv() => {
+                    if let Some(book) = keeper.apply_snapshot(&inst, snapshot) {
+                        self.publish(TaskEvent::LobBook, vec![book]);
+                    }
+                },
+                cmd = self.cmd_rx.recv() => {
+                    if self.handle_command(cmd, ws_stream).await {
+                        break;
+                    }
+                },
+                _ = until_deadline(self.replies.next_deadline()) => self.expire_replies(),
+                _ = self.shutdown.recv() => {
+                    self.close_on_shutdown(ws_stream).await;
+                    break;
+                },
+            }
+        }
+    }
+}
+
+impl WsTaskRunner {
+    /// Snapshot client of a book relay connecting to `target`; other relays
+    /// need none.
+    pub(super) fn book_snapshot_source(&self, target: &WsConnectTarget) -> Option<SnapshotSource> {
+        if !matches!(
+            self.ws_info.ws_channel,
+            WsChannel::Lob(Some(LobParam::Incremental { .. }))
+        ) {
+            return None;
+        }
+
+        #[cfg(feature = "lob_clients")]
+        let registered = self
+            .exec_clients
+            .get(&self.ws_info.market)
+            .and_then(SnapshotSource::from_exec_client);
+        #[cfg(not(feature = "lob_clients"))]
+        let registered = None;
+
+        let source = SnapshotSource::for_target(target, &self.ws_info.market, registered);
+        if source.is_none() && SnapshotSource::production(&self.ws_info.market).is_some() {
+            warn!(
+                "{:?} book relay has no snapshot client for {}; set one with \
+                 WsConnectTarget::with_snapshot_source",
+                self.ws_info.market, target.url
+            );
+        }
+        source
+    }
+}
+
+impl SnapshotSource {
+    /// The source `target` carries, else the execution client `registered`
+    /// for the market, else the market's default client when `target` is its
+    /// production stream.
+    fn for_target(
+        target: &WsConnectTarget,
+        market: &Market,
+        registered: Option<Self>,
+    ) -> Option<Self> {
+        if let Some(source) = &target.snapshot {
+            return Some(*source.clone());
+        }
+        if registered.is_some() {
+            return registered;
+        }
+        Self::production(market).filter(|source| host(source.ws_public()) == host(&target.url))
+    }
+
+    /// Default client of `market`, which talks to production.
+    fn production(market: &Market) -> Option<Self> {
+        match market {
+            #[cfg(feature = "binance")]
+            Market::BinanceUmFutures => Some(Self::BinanceUm(BinanceUmCli::default())),
+            #[cfg(feature = "gate")]
+            Market::GateFutures => Some(Self::GateFutures(GateFuturesCli::default())),
+            #[cfg(feature = "okx")]
+            Market::Okx => Some(Self::Okx(OkxCli::default())),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "lob_clients")]
+    fn from_exec_client(client: &LobClients) -> Option<Self> {
+        match client {
+            LobClients::BinanceUm(cli) => Some(Self::BinanceUm(cli.clone())),
+            LobClients::GateFutures(cli) => Some(Self::GateFutures(cli.clone())),
+            LobClients::Okx(cli) => Some(Self::Okx(cli.clone())),
+            _ => None,
+        }
+    }
+
+    fn ws_public(&self) -> &str {
+        match self {
+            #[cfg(feature = "binance")]
+            Self::BinanceUm(cli) => &cli.endpoints.ws_public,
+            #[cfg(feature = "gate")]
+            Self::GateFutures(cli) => &cli.endpoints.ws_public,
+            #[cfg(feature = "okx")]
+            Self::Okx(cli) => &cli.endpoints.ws_public,
+            #[allow(unreachable_patterns)]
+            _ => "",
+        }
+    }
+
+    /// `stream_depth` only matters to Gate.
+    #[cfg_attr(not(feature = "gate"), allow(unused_variables))]
+    async fn fetch(&self, inst: &str, stream_depth: Option<u16>) -> InfraResult<OrderBookData> {
+        let inst_type = inst_type_from_cli(inst);
+        match self {
+            #[cfg(feature = "binance")]
+            Self::BinanceUm(cli) => cli.get_orderbook(inst, inst_type, 1000).await,
+            // Gate only pushes updates inside the subscribed depth.
+            #[cfg(feature = "gate")]
+            Self::GateFutures(cli) => {
+                let depth = usize::from(stream_depth.unwrap_or(20));
+                cli.get_orderbook(inst, inst_type, depth).await
+            },
+            #[cfg(feature = "okx")]
+            Self::Okx(cli) => cli.get_orderbook(inst, inst_type, 400).await,
+            #[allow(unreachable_patterns)]
+            _ => Err(InfraError::Unimplemented),
+        }
+    }
+}
+
+fn host(url: &str) -> Option<String> {
+    Url::parse(url).ok()?.host_str().map(str::to_string)
+}
+
+fn inst_type_from_cli(inst: &str) -> InstrumentType {
+    if inst.ends_with("_PERP") {
+        InstrumentType::Perpetual
+    } else if inst.contains("_FUT_") {
+        InstrumentType::Futures
+    } else {
+        InstrumentType::Spot
+    }
+}
+
+struct BookEntry {
+    book: LocalOrderBook,
+    version: u64,
+    snapshot_pending: bool,
+    last_snapshot_request: Option<Instant>,
+}
+
+/// Per-connection set of relay-maintained books.
+struct BookKeeper {
+    market: Market,
+    source: Option<SnapshotSource>,
+    stream_depth: Option<u16>,
+    snapshot_tx: mpsc::UnboundedSender<SnapshotResult>,
+    books: HashMap<String, BookEntry>,
+}
+
+impl BookKeeper {
+    fn new(
+        market: Market,
+        source: Option<SnapshotSource>,
+        stream_depth: Option<u16>,
+        snapshot_tx: mpsc::UnboundedSender<SnapshotResult>,
+    ) -> Self {
+        Self {
+            market,
+            source,
+            stream_depth,
+            snapshot_tx,
+            books: HashMap::new(),
+        }
+    }
+
+    fn apply_batch(&mut self, lobs: &[WsLob]) -> Vec<WsLobBook> {
+        lobs.iter().filter_map(|lob| self.apply(lob)).collect()
+    }
+
+    fn apply(&mut self, lob: &WsLob) -> Option<WsLobBook> {
+        let market = self.market.clone();
+        let entry = self
+            .books
+            .entry(lob.inst.clone())
+            .or_insert_with(|| BookEntry {
+                book: LocalOrderBook::new(market, lob.inst.clone()),
+                version: 0,
+                snapshot_pending: false,
+                last_snapshot_request: None,
+            });
+
+        match entry.book.apply(lob) {
+            LobApply::Applied => Some(next_view(entry)),
+            LobApply::ResyncNeeded(reason) => {
+                warn!(
+                    "{:?} book for {} lost sync: {:?}",
+                    self.market, lob.inst, reason
+                );
+                self.request_snapshot(&lob.inst);
+                None
+            },
+            LobApply::Buffered => {
+                self.request_snapshot(&lob.inst);
+                None
+            },
+            LobApply::Stale | LobApply::Ignored => None,
+        }
+    }
+
+    fn apply_snapshot(
+        &mut self,
+        inst: &str,
+        snapshot: InfraResult<OrderBookData>,
+    ) -> Option<WsLobBook> {
+        let entry = self.books.get_mut(inst)?;
+        entry.snapshot_pending = false;
+
+        let snapshot = match snapshot {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                warn!(
+                    "{:?} book snapshot for {} failed: {:?}",
+                    self.market, inst, e
+                );
+                return None;
+            },
+        };
+
+        // A websocket snapshot may have resynchronized the book meanwhile.
+        if entry.book.is_synced() {
+            return None;
+        }
+
+        match entry.book.apply_rest_snapshot(&snapshot) {
+            LobApply::Applied => Some(next_view(entry)),
+            // An unsequenced snapshot is published once a delta confirms it.
+            LobApply::Buffered => None,
+            outcome => {
+                warn!(
+                    "{:?} book for {} did not sync from snapshot: {:?}",
+                    self.market, inst, outcome
+                );
+                None
+            },
+        }
+    }
+
+    fn request_snapshot(&mut self, inst: &str) {
+        let Some(source) = self.source.clone() else {
+            return;
+        };
+        let Some(entry) = self.books.get_mut(inst) else {
+            return;
+        };
+
+        let now = Instant::now();
+        let throttled = entry
+            .last_snapshot_request
+            .is_some_and(|at| now.duration_since(at) < SNAPSHOT_RETRY_INTERVAL);
+        if entry.snapshot_pending || throttled {
+            return;
+        }
+
+        entry.snapshot_pending = true;
+        entry.last_snapshot_request = Some(now);
+
+        let inst = inst.to_string();
+        let stream_depth = self.stream_depth;
+        let snapshot_tx = self.snapshot_tx.clone();
+        tokio::spawn(async move {
+            let snapshot = source.fetch(&inst, stream_depth).await;
+            let _ = snapshot_tx.send((inst, snapshot));
+        });
+    }
+}
+
+fn next_view(entry: &mut BookEntry) -> WsLobBook {
+    entry.version += 1;
+    let book = &entry.book;
+
+    WsLobBook {
+        timestamp: book.timestamp(),
+        market: book.market().clone(),
+        inst: book.inst().to_string(),
+        book_version: entry.version,
+        update_id: book.last_update_id(),
+        bids: book.bids(BOOK_VIEW_DEPTH),
+        asks: book.asks(BOOK_VIEW_DEPTH),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arch::strategy_base::handler::lob_events::{
+        LobEventKind, LobLevel, LobLevelAction, LobSeq,
+    };
+
+    fn gate_lob(event: LobEventKind, bid: (f64, f64), first: u64, last: u64) -> WsLob {
+        WsLob {
+            timestamp: last,
+            market: Market::GateFutures,
+            inst: "BTC_USDT_PERP".into(),
+            event,
+            bids: vec![LobLevel {
+                price: bid.0,
+                size: bid.1,
+                action: LobLevelAction::Upsert,
+                order_count: None,
+                level_update_id: None,
+            }],
+            asks: vec![LobLevel {
+                price: 101.0,
+                size: 1.0,
+                action: LobLevelAction::Upsert,
+                order_count: None,
+                level_update_id: None,
+            }],
+            seq: Some(LobSeq {
+                prev: None,
+                first: Some(first),
+                last: Some(last),
+            }),
+            checksum: None,
+        }
+    }
+
+    fn keeper() -> BookKeeper {
+        let (snapshot_tx, _) = mpsc::unbounded_channel();
+        BookKeeper::new(Market::GateFutures, None, None, snapshot_tx)
+    }
+
+    #[test]
+    fn publishes_versioned_views_after_sync() {
+        let mut keeper = keeper();
+
+        let books = keeper.apply_batch(&[
+            gate_lob(LobEventKind::Snapshot, (100.0, 1.0), 10, 10),
+            gate_lob(LobEventKind::Incremental, (100.0, 2.0), 11, 11),
+        ]);
+
+        assert_eq!(books.len(), 2);
+        assert_eq!(books[0].book_version, 1);
+        assert_eq!(books[1].book_version, 2);
+        assert_eq!(books[1].update_id, Some(11));
+        assert_eq!(books[1].bids, vec![(100.0, 2.0)]);
+    }
+
+    #[test]
+    fn gap_suppresses_views_until_snapshot() {
+        let mut keeper = keeper();
+        keeper.apply(&gate_lob(LobEventKind::Snapshot, (100.0, 1.0), 10, 10));
+
+        assert!(
+            keeper
+                .apply(&gate_lob(LobEventKind::Incremental, (100.0, 2.0), 20, 20))
+                .is_none()
+        );
+        assert!(
+            keeper
+                .apply(&gate_lob(LobEventKind::Incremental, (100.0, 3.0), 21, 21))
+                .is_none()
+        );
+
+        let snapshot = OrderBookData {
+            timestamp: 1,
+            inst: "BTC_USDT_PERP".into(),
+            bids: vec![(100.0, 1.5)],
+            asks: vec![(101.0, 1.0)],
+            update_id: Some(19),
+        };
+        let book = keeper
+            .apply_snapshot("BTC_USDT_PERP", Ok(snapshot))
+            .unwrap();
+
+        assert_eq!(book.book_version, 2);
+        assert_eq!(book.update_id, Some(21));
+        assert_eq!(book.bids, vec![(100.0, 3.0)]);
+    }
+
+    #[test]
+    fn unsequenced_snapshots_wait_for_a_checksummed_delta() {
+        let (snapshot_tx, _) = mpsc::unbounded_channel();
+        let mut keeper = BookKeeper::new(Market::Okx, None, None, snapshot_tx);
+        let delta = |bid: f64, prev: u64, book: &str| {
+            let mut lob = gate_lob(LobEventKind::Incremental, (100.0, bid), prev + 1, prev + 1);
+            lob.market = Market::Okx;
+            lob.seq.as_mut().unwrap().prev = Some(prev);
+            lob.checksum = Some((crc32fast::hash(book.as_bytes()) as i32).to_string());
+            lob
+        };
+        // Sent before the snapshot, when the ask was still larger.
+        assert!(keeper.apply(&delta(0.5, 1, "100:0.5:101:2")).is_none());
+
+        let snapshot = OrderBookData {
+            timestamp: 1,
+            inst: "BTC_USDT_PERP".into(),
+            bids: vec![(100.0, 1.0)],
+            asks: vec![(101.0, 1.0)],
+            update_id: None,
+        };
+        assert!(
+            keeper
+                .apply_snapshot("BTC_USDT_PERP", Ok(snapshot))
+                .is_none()
+        );
+
+        let book = keeper.apply(&delta(2.0, 5, "100:2:101:1")).unwrap();
+        assert_eq!(book.book_version, 1);
+        assert_eq!(book.update_id, Some(6));
+        assert_eq!(book.bids, vec![(100.0, 2.0)]);
+    }
+
+    #[test]
+    fn infers_instrument_type_from_cli_name() {
+        assert_eq!(
+            inst_type_from_cli("BTC_USDT_PERP"),
+            InstrumentType::Perpetual
+        );
+        assert_eq!(
+            inst_type_from_cli("BTC_USDT_FUT_250627"),
+            InstrumentType::Futures
+        );
+        assert_eq!(inst_type_from_cli("BTC_USDT"), InstrumentType::Spot);
+    }
+
+    #[cfg(feature = "okx")]
+    #[test]
+    fn snapshots_come_from_the_stream_environment() {
+        use crate::arch::market_assets::exchange::endpoints::EndpointProfile;
+
+        let mock = OkxCli::default().with_endpoints(EndpointProfile::new(
+            "http://127.0.0.1:9000",
+            "ws://127.0.0.1:9000/public",
+        ));
+        let mock_target = WsConnectTarget::new("ws://127.0.0.1:9000/public");
+
+        assert_eq!(
+            SnapshotSource::for_target(&mock_target, &Market::Okx, None),
+            None
+        );
+        assert_eq!(
+            SnapshotSource::for_target(
+                &mock_target
+                    .clone()
+                    .with_snapshot_source(SnapshotSource::Okx(mock.clone())),
+                &Market::Okx,
+                None,
+            ),
+            Some(SnapshotSource::Okx(mock.clone()))
+        );
+        assert_eq!(
+            SnapshotSource::for_target(
+                &mock_target,
+                &Market::Okx,
+                Some(SnapshotSource::Okx(mock.clone()))
+            ),
+            Some(SnapshotSource::Okx(mock))
+        );
+
+        let production = OkxCli::default();
+        let production_target = WsConnectTarget::new(production.endpoints.ws_public.clone());
+        assert_eq!(
+            SnapshotSource::for_target(&production_target, &Market::Okx, None),
+            Some(SnapshotSource::Okx(production))
+        );
+    }
+}
@@ -2,16 +2,22 @@ use crate::arch::{
     market_assets::exchange::okx::{
         okx_ws_msg::OkxWsData,
         schemas::ws::{
-            account_bal_and_pos::WsBalAndPosOkx, account_order::WsAccountOrderOkx,
-            account_position::WsAccountPositionOkx, candles::WsCandleOkx, lob::OkxWsLobBook,
+            account_bal_and_pos::WsBalAndPosOkx,
+            account_order::{WsAccountOrderOkx, WsFillOkx},
+            account_position::WsAccountPositionOkx,
+            candles::WsCandleOkx,
+            lob::OkxWsLobBook,
             trades::WsTradesOkx,
         },
     },
     strategy_base::handler::task_channel::TaskEvent,
-    task_execution::{task_general::LogLevel, task_ws::WsChannel},
+    task_execution::{
+        task_general::LogLevel,
+        task_ws::{LobParam, WsChannel},
+    },
 };
 
-use super::{WsStream, WsTaskRunner};
+use super::{WsStream, WsTaskRunner, ws_decode::FillBatch};
 
 impl WsTaskRunner {
     pub(super) async fn ws_channel_okx(&mut self, ws_stream: &mut WsStream) {
@@ -24,6 +30,12 @@ impl WsTaskRunner {
                 )
                 .await;
             },
+            WsChannel::AccountFills => {
+                self.ws_loop(TaskEvent::AccFill, ws_stream, |frame: &[u8]| {
+                    OkxWsData::<WsFillOkx>::decode_batch(frame).map(FillBatch)
+                })
+                .await;
+            },
             WsChannel::AccountBalAndPos => {
                 self.ws_loop(
                     TaskEvent::AccBalPos,
@@ -56,6 +68,11 @@ impl WsTaskRunner {
                 )
                 .await;
             },
+            WsChannel::Lob(Some(LobParam::Incremental { depth, .. })) => {
+                let depth = *depth;
+                self.ws_lob_book_loop(ws_stream, OkxWsData::<OkxWsLobBook>::decode_batch, depth)
+                    .await;
+            },
             WsChannel::Lob(..) => {
                 self.ws_loop(
                     TaskEvent::Lob,
@@ -0,0 +1,546 @@
+use std::collections::HashMap;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::time::{Duration, Instant, timeout};
+use tungstenite::protocol::Message;
+
+use super::{WS_READ_TIMEOUT, WsRead, WsStream, WsTaskRunner, until_deadline};
+use crate::arch::{
+    market_assets::{
+        api_data::account_data::{OrderAckData, WsOrderResponse},
+        api_general::{CancelOrderParams, OrderParams, get_micros_timestamp},
+        exchange::lob_clients::LobClients,
+        market_core::Market,
+    },
+    strategy_base::{
+        command::command_core::TaskCommand,
+        handler::{
+            alt_events::{AltCancel, AltOrder, AltOrderAck, AltOrderError, AltOrderRequest},
+            task_channel::TaskEvent,
+        },
+    },
+    task_execution::{
+        alt_runner::lob_execution::{
+            no_client, release_failed, request_error, screen_orders, spread,
+        },
+        task_general::LogLevel,
+    },
+    traits::{conversion::IntoWsData, market_lob::LobWsOrderEntry},
+};
+use crate::errors::InfraResult;
+
+/// A frame read on an order entry session, which answers a request or not.
+struct OrderEntryFrame(Option<WsOrderResponse>);
+
+impl IntoWsData for OrderEntryFrame {
+    type Output = Option<WsOrderResponse>;
+
+    fn into_ws(self) -> Self::Output {
+        self.0
+    }
+}
+
+/// One order or cancel waiting for its venue response.
+struct PendingEntry {
+    request: AltOrderRequest,
+    metadata: HashMap<String, String>,
+}
+
+/// How long a request waits for its venue response before it is acked as
+/// failed, so a dropped response cannot hold it forever.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Entries sent under one request id.
+struct PendingBatch {
+    entries: Vec<PendingEntry>,
+    sent_at: Instant,
+}
+
+/// Requests sent on the current connection, by request id.
+#[derive(Default)]
+struct PendingRequests {
+    next_id: u64,
+    requests: HashMap<u64, PendingBatch>,
+}
+
+impl PendingRequests {
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn insert(&mut self, id: u64, entries: Vec<PendingEntry>) {
+        let sent_at = Instant::now();
+        self.requests.insert(id, PendingBatch { entries, sent_at });
+    }
+
+    fn remove(&mut self, id: u64) -> Option<Vec<PendingEntry>> {
+        self.requests.remove(&id).map(|batch| batch.entries)
+    }
+
+    /// Earliest time a waiting request times out.
+    fn next_deadline(&self) -> Option<Instant> {
+        self.requests
+            .values()
+            .map(|batch| batch.sent_at + RESPONSE_TIMEOUT)
+            .min()
+    }
+
+    /// Removes the requests that have waited past [`RESPONSE_TIMEOUT`].
+    fn expire(&mut self, now: Instant) -> Vec<PendingEntry> {
+        let expired: Vec<u64> = self
+            .requests
+            .iter()
+            .filter(|(_, batch)| batch.sent_at + RESPONSE_TIMEOUT <= now)
+            .map(|(id, _)| *id)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|id| self.remove(id))
+            .flatten()
+            .collect()
+    }
+
+    fn drain(&mut self) -> Vec<PendingEntry> {
+        self.requests
+            .drain()
+            .flat_map(|(_, batch)| batch.entries)
+            .collect()
+    }
+}
+
+impl WsTaskRunner {
+    /// Serves orders and cancels over the connected trading session.
+    ///
+    /// Orders pass the risk gate first, then go out in batches the venue
+    /// accepts, each under a fresh request id. Every response publishes one
+    /// `OrderAck` event for its batch. Requests still unanswered after
+    /// [`RESPONSE_TIMEOUT`] or when the connection ends are acknowledged with
+    /// an error, since they may or may not have reached the venue. Other commands, such as a login
+    /// `WsMessage`, are handled as on any websocket task.
+    pub(super) async fn ws_order_entry(&mut self, ws_stream: &mut WsStream) {
+        let clients = self.exec_clients.clone();
+        let client = clients.get(&self.ws_info.market);
+        if client.is_none() {
+            self.log(
+                LogLevel::Warn,
+                "No execution client registered; order entry requests will be rejected",
+            );
+        }
+
+        let decode = |frame: &[u8]| {
+            Ok(OrderEntryFrame(
+                client.and_then(|client| client.parse_ws_order_response(frame)),
+            ))
+        };
+        let mut pending = PendingRequests::default();
+
+        loop {
+            tokio::select! {
+                msg = timeout(WS_READ_TIMEOUT, ws_stream.next()) => {
+                    match self.read_ws_msg::<OrderEntryFrame, _>(msg, ws_stream, decode).await {
+                        WsRead::Data(Some(response)) => self.settle(&mut pending, response),
+                        WsRead::Data(None) | WsRead::Idle => {},
+                        WsRead::Closed => break,
+                    }
+                },
+                cmd = self.cmd_rx.recv() => match cmd {
+                    Some(TaskCommand::OrderExecute(orders)) => {
+                        self.send_orders(client, &mut pending, orders, ws_stream).await;
+                    },
+                    Some(TaskCommand::OrderCancel(cancels)) => {
+                        self.send_cancels(client, &mut pending, cancels, ws_stream).await;
+                    },
+                    cmd => {
+                        if self.handle_command(cmd, ws_stream).await {
+                            break;
+                        }
+                    },
+                },
+                _ = until_deadline(
+                    [self.replies.next_deadline(), pending.next_deadline()]
+                        .into_iter()
+                        .flatten()
+                        .min()
+                ) => {
+                    self.expire_replies();
+                    self.expire_requests(&mut pending);
+                },
+                _ = self.shutdown.recv() => {
+                    self.close_on_shutdown(ws_stream).await;
+                    break;
+                },
+            }
+        }
+
+        self.fail_unanswered(
+            pending.drain(),
+            "Order entry connection closed before the venue answered",
+        );
+    }
+
+    /// Acks requests whose response did not arrive in time.
+    fn expire_requests(&self, pending: &mut PendingRequests) {
+        let expired = pending.expire(Instant::now());
+        if !expired.is_empty() {
+            self.log(
+                LogLevel::Warn,
+                &format!("{} order entry requests timed out", expired.len()),
+            );
+        }
+        self.fail_unanswered(expired, "Order entry response timed out");
+    }
+
+    fn fail_unanswered(&self, entries: Vec<PendingEntry>, reason: &str) {
+        if entries.is_empty() {
+            return;
+        }
+        let results = vec![Err(AltOrderError::Request(reason.into())); entries.len()];
+        self.publish_entry_acks(entries, results);
+    }
+
+    async fn send_orders(
+        &self,
+        client: Option<&LobClients>,
+        pending: &mut PendingRequests,
+        orders: Vec<AltOrder>,
+        ws_stream: &mut WsStream,
+    ) {
+        let (orders, rejected) = screen_orders(self.risk_gate.as_ref(), orders);
+        if !rejected.is_empty() {
+            self.publish(TaskEvent::OrderAck, rejected);
+        }
+
+        let (orders, foreign) = self.split_foreign(orders, |order| &order.market);
+        self.reject_foreign(foreign.into_iter().map(|order| PendingEntry {
+            request: AltOrderRequest::Place(order.order_params),
+            metadata: order.metadata,
+        }));
+
+        let limit = client.map_or(orders.len(), LobClients::ws_place_batch_limit);
+        for batch in orders.chunks(limit.max(1)) {
+            let entries = batch
+                .iter()
+                .map(|order| PendingEntry {
+                    request: AltOrderRequest::Place(order.order_params.clone()),
+                    metadata: order.metadata.clone(),
+                })
+                .collect();
+            let params: Vec<OrderParams> = batch
+                .iter()
+                .map(|order| order.order_params.clone())
+                .collect();
+
+            let id = pending.next_id();
+            let frame = client.map(|client| client.ws_place_orders_msg(id, &params));
+            self.send_request(pending, id, frame, entries, ws_stream)
+                .await;
+        }
+    }
+
+    async fn send_cancels(
+        &self,
+        client: Option<&LobClients>,
+        pending: &mut PendingRequests,
+        cancels: Vec<AltCancel>,
+        ws_stream: &mut WsStream,
+    ) {
+        let (cancels, foreign) = self.split_foreign(cancels, |cancel| &cancel.market);
+        self.reject_foreign(foreign.into_iter().map(|cancel| PendingEntry {
+            request: AltOrderRequest::Cancel(cancel.cancel_params),
+            metadata: cancel.metadata,
+        }));
+
+        let limit = client.map_or(cancels.len(), LobClients::ws_cancel_batch_limit);
+        for batch in cancels.chunks(limit.max(1)) {
+            let entries = batch
+                .iter()
+                .map(|cancel| PendingEntry {
+                    request: AltOrderRequest::Cancel(cancel.cancel_params.clone()),
+                    metadata: cancel.metadata.clone(),
+                })
+                .collect();
+            let params: Vec<CancelOrderParams> = batch
+                .iter()
+                .map(|cancel| cancel.cancel_params.clone())
+                .collect();
+
+            let id = pending.next_id();
+            let frame = client.map(|client| client.ws_cancel_orders_msg(id, &params));
+            self.send_request(pending, id, frame, entries, ws_stream)
+                .await;
+        }
+    }
+
+    /// Sends one request and waits for its response in `pending`, or acks
+    /// every entry with the reason it could not be sent.
+    async fn send_request(
+        &self,
+        pending: &mut PendingRequests,
+        id: u64,
+        frame: Option<InfraResult<String>>,
+        entries: Vec<PendingEntry>,
+        ws_stream: &mut WsStream,
+    ) {
+        let results = match frame {
+            None => no_client(&self.ws_info.market, entries.len()),
+            Some(Err(e)) => vec![Err(request_error(e)); entries.len()],
+            Some(Ok(frame)) => match ws_stream.send(Message::text(frame)).await {
+                Ok(()) => {
+                    pending.insert(id, entries);
+                    return;
+                },
+                Err(e) => {
+                    let error = AltOrderError::Request(format!("Order entry send failed: {e}"));
+                    vec![Err(error); entries.len()]
+                },
+            },
+        };
+
+        self.publish_entry_acks(entries, results);
+    }
+
+    fn settle(&self, pending: &mut PendingRequests, response: WsOrderResponse) {
+        let Some(entries) = pending.remove(response.id) else {
+            self.log(
+                LogLevel::Warn,
+                &format!("Response to unknown order entry request {}", response.id),
+            );
+            return;
+        };
+
+        let results = spread(response.result, entries.len())
+            .into_iter()
+            .zip(&entries)
+            .map(|(result, entry)| result.map(|ack| fill_request_ids(ack, &entry.request)))
+            .collect();
+        self.publish_entry_acks(entries, results);
+    }
+
+    /// Splits off requests for markets other than this task's.
+    fn split_foreign<T>(&self, items: Vec<T>, market: impl Fn(&T) -> &Market) -> (Vec<T>, Vec<T>) {
+        items
+            .into_iter()
+            .partition(|item| market(item) == &self.ws_info.market)
+    }
+
+    fn reject_foreign(&self, entries: impl Iterator<Item = PendingEntry>) {
+        let entries: Vec<_> = entries.collect();
+        if entries.is_empty() {
+            return;
+        }
+
+        let error = AltOrderError::Request(format!(
+            "Order entry task for {:?} only routes its own market",
+            self.ws_info.market
+        ));
+        let results = vec![Err(error); entries.len()];
+        self.publish_entry_acks(entries, results);
+    }
+
+    /// Acks sent or unsendable entries. Orders that failed to send, timed
+    /// out, or were rejected give their risk gate slot back, since no order
+    /// update will free it.
+    fn publish_entry_acks(
+        &self,
+        entries: Vec<PendingEntry>,
+        results: Vec<Result<OrderAckData, AltOrderError>>,
+    ) {
+        let timestamp = get_micros_timestamp();
+        let acks: Vec<_> = entries
+            .into_iter()
+            .zip(results)
+            .map(|(entry, result)| AltOrderAck {
+                timestamp,
+                market: self.ws_info.market.clone(),
+                request: entry.request,
+                metadata: entry.metadata,
+                result,
+            })
+            .collect();
+        release_failed(self.risk_gate.as_ref(), &acks);
+        self.publish(TaskEvent::OrderAck, acks);
+    }
+}
+
+/// Fills ids the venue left out of an ack, e.g. Hyperliquid cancel statuses,
+/// from the request it answers.
+fn fill_request_ids(mut ack: OrderAckData, request: &AltOrderRequest) -> OrderAckData {
+    let (order_id, cli_order_id) = match request {
+        AltOrderRequest::Place(params) => (None, params.client_order_id.as_ref()),
+        AltOrderRequest::Cancel(params) => (params.order_id.as_ref(), params.cli_order_id.as_ref()),
+    };
+
+    if ack.order_id.is_empty()
+        && let Some(order_id) = order_id
+    {
+        ack.order_id = order_id.clone();
+    }
+    if ack.cli_order_id.is_none() {
+        ack.cli_order_id = cli_order_id.cloned();
+    }
+    ack
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::sync::{broadcast, mpsc};
+
+    use super::*;
+    use crate::arch::{
+        infra_core::shutdown::ShutdownHandle,
+        market_assets::base_data::{OrderSide, OrderStatus, OrderType},
+        strategy_base::risk::{RiskGate, RiskLimits},
+        task_execution::task_ws::{WsChannel, WsTaskInfo},
+    };
+
+    fn runner(risk_gate: RiskGate) -> WsTaskRunner {
+        let (_, cmd_rx) = mpsc::channel(1);
+        let (event_tx, _) = broadcast::channel(4);
+        WsTaskRunner {
+            cmd_rx,
+            event_tx,
+            ws_info: Arc::new(WsTaskInfo {
+                market: Market::Okx,
+                ws_channel: WsChannel::OrderEntry,
+                filter_channels: false,
+                chunk: 1,
+                task_base_id: None,
+                shards: None,
+            }),
+            task_id: 1,
+            shutdown: ShutdownHandle::new().signal(),
+            session: None,
+            replies: Default::default(),
+            subscriptions: Default::default(),
+            listen_key: None,
+            snapshot_source: None,
+            exec_clients: Arc::new(HashMap::new()),
+            risk_gate: Some(risk_gate),
+        }
+    }
+
+    #[test]
+    fn failed_orders_release_their_risk_gate_slot() {
+        let gate = RiskGate::new(RiskLimits {
+            max_open_orders: Some(1),
+            ..Default::default()
+        });
+        let runner = runner(gate.clone());
+        let params = |id: &str| OrderParams {
+            inst: "BTC-USDT-SWAP".into(),
+            side: OrderSide::BUY,
+            size: "1".into(),
+            order_type: OrderType::Limit,
+            price: Some("100".into()),
+            client_order_id: Some(id.into()),
+            ..Default::default()
+        };
+        let entry = |id: &str| PendingEntry {
+            request: AltOrderRequest::Place(params(id)),
+            metadata: HashMap::new(),
+        };
+        let full = || gate.check(&Market::Okx, &params("next"), 0).is_err();
+
+        // Timed out or unsendable.
+        gate.approve(&Market::Okx, &params("a"), 0).unwrap();
+        assert!(full());
+        runner.fail_unanswered(vec![entry("a")], "Order entry response timed out");
+        assert!(!full());
+
+        // Rejected by the venue.
+        gate.approve(&Market::Okx, &params("b"), 0).unwrap();
+        let mut pending = PendingRequests::default();
+        let id = pending.next_id();
+        pending.insert(id, vec![entry("b")]);
+        runner.settle(
+            &mut pending,
+            WsOrderResponse {
+                id,
+                result: Ok(vec![OrderAckData {
+                    timestamp: 1,
+                    order_status: OrderStatus::Rejected,
+                    order_id: String::new(),
+                    cli_order_id: Some("b".into()),
+                    msg: Some("insufficient margin".into()),
+                }]),
+            },
+        );
+        assert!(!full());
+
+        // Accepted orders stay open until an order update closes them.
+        gate.approve(&Market::Okx, &params("c"), 0).unwrap();
+        let id = pending.next_id();
+        pending.insert(id, vec![entry("c")]);
+        runner.settle(
+            &mut pending,
+            WsOrderResponse {
+                id,
+                result: Ok(vec![OrderAckData {
+                    timestamp: 1,
+                    order_status: OrderStatus::Live,
+                    order_id: "1".into(),
+                    cli_order_id: Some("c".into()),
+                    msg: None,
+                }]),
+            },
+        );
+        assert!(full());
+    }
+
+    #[test]
+    fn unanswered_requests_expire_after_the_response_timeout() {
+        let mut pending = PendingRequests::default();
+        let entry = || PendingEntry {
+            request: AltOrderRequest::Place(OrderParams::default()),
+            metadata: HashMap::new(),
+        };
+        let id = pending.next_id();
+        pending.insert(id, vec![entry(), entry()]);
+
+        let deadline = pending.next_deadline().unwrap();
+        assert!(
+            pending
+                .expire(deadline - Duration::from_millis(1))
+                .is_empty()
+        );
+        assert_eq!(pending.expire(deadline).len(), 2);
+        assert_eq!(pending.next_deadline(), None);
+        assert!(pending.remove(id).is_none());
+    }
+
+    #[test]
+    fn fills_missing_ids_from_the_request() {
+        let ack = OrderAckData {
+            timestamp: 1,
+            order_status: OrderStatus::Canceled,
+            order_id: String::new(),
+            cli_order_id: None,
+            msg: None,
+        };
+        let cancel = AltOrderRequest::Cancel(CancelOrderParams {
+            inst: "BTC_USDT_PERP".into(),
+            order_id: Some("42".into()),
+            cli_order_id: Some("c1".into()),
+        });
+
+        let filled = fill_request_ids(ack.clone(), &cancel);
+        assert_eq!(filled.order_id, "42");
+        assert_eq!(filled.cli_order_id.as_deref(), Some("c1"));
+
+        let placed = OrderAckData {
+            order_id: "7".into(),
+            cli_order_id: Some("venue-c1".into()),
+            ..ack
+        };
+        let place = AltOrderRequest::Place(OrderParams {
+            client_order_id: Some("c1".into()),
+            ..Default::default()
+        });
+        let kept = fill_request_ids(placed, &place);
+        assert_eq!(kept.order_id, "7");
+        assert_eq!(kept.cli_order_id.as_deref(), Some("venue-c1"));
+    }
+}
@@ -0,0 +1,152 @@
+use serde_json::Value;
+use tokio::time::Instant;
+
+use crate::arch::strategy_base::command::{
+    ack_handle::{AckHandle, AckStatus},
+    command_core::{WsReplyMatcher, WsReplyVerdict},
+};
+
+/// One sent message whose ack waits for the venue's reply.
+#[derive(Debug)]
+struct PendingReply {
+    matcher: WsReplyMatcher,
+    ack: AckHandle,
+    deadline: Instant,
+}
+
+/// Messages waiting for their replies on the current connection, oldest first.
+#[derive(Debug, Default)]
+pub(crate) struct PendingReplies {
+    waiting: Vec<PendingReply>,
+}
+
+impl PendingReplies {
+    pub(crate) fn push(&mut self, matcher: WsReplyMatcher, ack: AckHandle) {
+        let deadline = Instant::now() + matcher.timeout();
+        self.waiting.push(PendingReply {
+            matcher,
+            ack,
+            deadline,
+        });
+    }
+
+    /// Acks the oldest message that `frame` answers.
+    ///
+    /// Returns `true` when the frame was a reply and should not be published.
+    pub(crate) fn resolve(&mut self, frame: &[u8]) -> bool {
+        if self.waiting.is_empty() {
+            return false;
+        }
+        let Ok(value) = serde_json::from_slice::<Value>(frame) else {
+            return false;
+        };
+
+        let found = self.waiting.iter().enumerate().find_map(|(i, pending)| {
+            match pending.matcher.check(&value) {
+                WsReplyVerdict::Unrelated => None,
+                verdict => Some((i, verdict)),
+            }
+        });
+        let Some((i, verdict)) = found else {
+            return false;
+        };
+
+        let text = String::from_utf8_lossy(frame).into_owned();
+        let status = match verdict {
+            WsReplyVerdict::Accepted => AckStatus::WsAccepted(text),
+            _ => AckStatus::WsRejected(text),
+        };
+        self.waiting.remove(i).ack.respond(status);
+        true
+    }
+
+    /// Earliest deadline among waiting messages.
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.waiting.iter().map(|pending| pending.deadline).min()
+    }
+
+    /// Acks every message whose deadline passed with `AckStatus::WsTimedOut`.
+    pub(crate) fn expire(&mut self, now: Instant) -> usize {
+        let (expired, waiting) = self
+            .waiting
+            .drain(..)
+            .partition::<Vec<_>, _>(|pending| pending.deadline <= now);
+        self.waiting = waiting;
+
+        let count = expired.len();
+        for pending in expired {
+            pending.ack.respond(AckStatus::WsTimedOut);
+        }
+        count
+    }
+
+    /// Rejects every waiting message, e.g. when the connection ends.
+    pub(crate) fn reject_all(&mut self, reason: &str) {
+        for pending in self.waiting.drain(..) {
+            pending
+                .ack
+                .respond(AckStatus::WsRejected(reason.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    fn waiting(
+        replies: &mut PendingReplies,
+        matcher: WsReplyMatcher,
+    ) -> oneshot::Receiver<AckStatus> {
+        let (tx, rx) = oneshot::channel();
+        replies.push(matcher, AckHandle::new(tx));
+        rx
+    }
+
+    #[tokio::test]
+    async fn resolves_replies_in_order_and_times_out_the_rest() {
+        let mut replies = PendingReplies::default();
+        let mut login = waiting(&mut replies, WsReplyMatcher::okx_event("login"));
+        let mut subscribe = waiting(&mut replies, WsReplyMatcher::okx_event("subscribe"));
+        let mut stale = waiting(
+            &mut replies,
+            WsReplyMatcher::binance_id(7).with_timeout(Duration::ZERO),
+        );
+
+        assert!(!replies.resolve(br#"{"arg":{"channel":"trades"},"data":[]}"#));
+        assert!(replies.resolve(br#"{"event":"login","code":"0","msg":"","connId":"a1"}"#));
+        assert_eq!(
+            login.try_recv().unwrap(),
+            AckStatus::WsAccepted(r#"{"event":"login","code":"0","msg":"","connId":"a1"}"#.into())
+        );
+        assert!(subscribe.try_recv().is_err());
+
+        assert_eq!(replies.expire(Instant::now()), 1);
+        assert_eq!(stale.try_recv().unwrap(), AckStatus::WsTimedOut);
+
+        let error = r#"{"event":"error","code":"60012","msg":"Invalid request","connId":"a1"}"#;
+        assert!(replies.resolve(error.as_bytes()));
+        assert_eq!(
+            subscribe.try_recv().unwrap(),
+            AckStatus::WsRejected(error.into())
+        );
+        assert_eq!(replies.next_deadline(), None);
+    }
+
+    #[test]
+    fn rejects_waiting_messages_when_the_connection_ends() {
+        let mut replies = PendingReplies::default();
+        let mut rx = waiting(&mut replies, WsReplyMatcher::okx_event("login"));
+
+        replies.reject_all("closed");
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            AckStatus::WsRejected("closed".into())
+        );
+        assert!(!replies.resolve(br#"{"event":"login","code":"0"}"#));
+    }
+}
@@ -0,0 +1,374 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use serde_json::Value;
+
+use crate::arch::strategy_base::command::command_core::{
+    WsConnectTarget, WsMessageBuilder, WsReconnectPolicy,
+};
+use crate::errors::InfraResult;
+
+/// Most setup messages a session keeps for replay.
+const REPLAY_LIMIT: usize = 256;
+
+/// Message replayed after a managed reconnect.
+#[derive(Clone, Debug)]
+pub(crate) enum WsReplayMsg {
+    Text(String),
+    Built(WsMessageBuilder),
+}
+
+impl WsReplayMsg {
+    pub(crate) fn build(&self) -> InfraResult<String> {
+        match self {
+            WsReplayMsg::Text(msg) => Ok(msg.clone()),
+            WsReplayMsg::Built(builder) => builder.build(),
+        }
+    }
+}
+
+/// Role of a sent message in the session, read from the venue envelope.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum WsControl {
+    Login,
+    /// One key per stream the message subscribes to.
+    Subscribe(Vec<String>),
+    /// One key per stream the message unsubscribes from.
+    Unsubscribe(Vec<String>),
+    /// Pings, orders and other requests that must not be sent again.
+    Other,
+}
+
+impl WsControl {
+    pub(crate) fn classify(msg: &str) -> Self {
+        let Ok(Value::Object(obj)) = serde_json::from_str::<Value>(msg) else {
+            return Self::Other;
+        };
+        let text = |key: &str| obj.get(key).and_then(Value::as_str);
+
+        // OKX: {"op": "subscribe", "args": [...]}
+        if let Some(op) = text("op") {
+            return Self::from_action(op, stream_keys(obj.get("args"), ""));
+        }
+        // Gate: {"channel": "futures.orders", "event": "subscribe", "payload": [...]}
+        if let (Some(channel), Some(event)) = (text("channel"), text("event")) {
+            if channel.ends_with(".login") {
+                return Self::Login;
+            }
+            return Self::from_action(event, stream_keys(obj.get("payload"), channel));
+        }
+        // Binance: {"method": "SUBSCRIBE", "params": [...]}
+        // Hyperliquid: {"method": "subscribe", "subscription": {...}}
+        if let Some(method) = text("method") {
+            if method == "session.logon" {
+                return Self::Login;
+            }
+            let streams = obj.get("params").or_else(|| obj.get("subscription"));
+            return Self::from_action(&method.to_ascii_lowercase(), stream_keys(streams, ""));
+        }
+
+        Self::Other
+    }
+
+    fn from_action(action: &str, keys: Vec<String>) -> Self {
+        match action {
+            "login" => Self::Login,
+            "subscribe" => Self::Subscribe(keys),
+            "unsubscribe" => Self::Unsubscribe(keys),
+            _ => Self::Other,
+        }
+    }
+}
+
+fn stream_keys(streams: Option<&Value>, scope: &str) -> Vec<String> {
+    match streams {
+        Some(Value::Array(items)) => items.iter().map(|item| format!("{scope}:{item}")).collect(),
+        Some(item) => vec![format!("{scope}:{item}")],
+        None => vec![scope.to_string()],
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ReplayEntry {
+    msg: WsReplayMsg,
+    control: WsControl,
+}
+
+/// Connection state remembered by a relay opened with a reconnect policy.
+#[derive(Clone, Debug)]
+pub(crate) struct WsSession {
+    pub target: WsConnectTarget,
+    pub policy: WsReconnectPolicy,
+    replay: Vec<ReplayEntry>,
+}
+
+impl WsSession {
+    /// Returns a session only when the target opted into reconnects, which a
+    /// listen key target does with the default policy.
+    pub(crate) fn from_target(target: &WsConnectTarget) -> Option<Self> {
+        let policy = match (&target.reconnect, &target.listen_key) {
+            (Some(policy), _) => Some(policy.clone()),
+            (None, Some(_)) => Some(WsReconnectPolicy::default()),
+            (None, None) => None,
+        };
+        policy.map(|policy| Self {
+            target: target.clone(),
+            policy,
+            replay: Vec::new(),
+        })
+    }
+
+    /// Keeps `msg` for replay when `sent`, its text as sent, is a login or
+    /// subscribe. A new login replaces the previous one, repeated subscribes
+    /// are kept once, and an unsubscribe removes the streams it covers.
+    ///
+    /// Returns `false` when the message was dropped because the replay list
+    /// is full.
+    pub(crate) fn record(&mut self, msg: WsReplayMsg, sent: &str) -> bool {
+        match WsControl::classify(sent) {
+            WsControl::Other => true,
+            WsControl::Login => {
+                match self
+                    .replay
+                    .iter_mut()
+                    .find(|entry| entry.control == WsControl::Login)
+                {
+                    Some(entry) => {
+                        entry.msg = msg;
+                        true
+                    },
+                    None => self.push(msg, WsControl::Login),
+                }
+            },
+            WsControl::Subscribe(keys) => {
+                let control = WsControl::Subscribe(keys);
+                if self.replay.iter().any(|entry| entry.control == control) {
+                    return true;
+                }
+                self.push(msg, control)
+            },
+            WsControl::Unsubscribe(keys) => {
+                // Messages covering other streams too are still replayed whole,
+                // so the unsubscribe has to follow them.
+                let mut partial = false;
+                self.replay.retain_mut(|entry| {
+                    let WsControl::Subscribe(streams) = &mut entry.control else {
+                        return true;
+                    };
+                    let before = streams.len();
+                    streams.retain(|stream| !keys.contains(stream));
+                    partial |= !streams.is_empty() && streams.len() < before;
+                    !streams.is_empty()
+                });
+                if partial {
+                    self.push(msg, WsControl::Unsubscribe(keys))
+                } else {
+                    true
+                }
+            },
+        }
+    }
+
+    fn push(&mut self, msg: WsReplayMsg, control: WsControl) -> bool {
+        if self.replay.len() >= REPLAY_LIMIT {
+            return false;
+        }
+        self.replay.push(ReplayEntry { msg, control });
+        true
+    }
+
+    pub(crate) fn replay(&self) -> impl Iterator<Item = &WsReplayMsg> {
+        self.replay.iter().map(|entry| &entry.msg)
+    }
+
+    /// Backoff for `attempt` plus a random jitter bounded by the policy.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let jitter_nanos = self.policy.jitter.as_nanos() as u64;
+        let jitter = if jitter_nanos == 0 {
+            Duration::ZERO
+        } else {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u32(attempt);
+            Duration::from_nanos(hasher.finish() % (jitter_nanos + 1))
+        };
+
+        self.policy.backoff(attempt).saturating_add(jitter)
+    }
+
+    /// Returns `true` once `attempts` reconnects have been made and the policy
+    /// allows no more.
+    pub(crate) fn exhausted(&self, attempts: u32) -> bool {
+        self.policy
+            .max_attempts
+            .is_some_and(|max_attempts| attempts >= max_attempts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::InfraError;
+
+    fn managed_target(jitter: Duration, max_attempts: Option<u32>) -> WsConnectTarget {
+        WsConnectTarget::new("wss://example.invalid/ws").with_reconnect(WsReconnectPolicy {
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(1),
+            jitter,
+            max_attempts,
+        })
+    }
+
+    #[test]
+    fn session_requires_reconnect_policy() {
+        assert!(WsSession::from_target(&WsConnectTarget::new("wss://example.invalid")).is_none());
+        assert!(WsSession::from_target(&managed_target(Duration::ZERO, None)).is_some());
+    }
+
+    #[cfg(feature = "binance")]
+    #[test]
+    fn listen_key_targets_reconnect_with_the_default_policy() {
+        use crate::arch::{
+            market_assets::exchange::binance::binance_um_futures_cli::BinanceUmCli,
+            strategy_base::command::command_core::ListenKeySource,
+        };
+
+        let target = WsConnectTarget::new("wss://example.invalid/ws")
+            .with_listen_key(ListenKeySource::BinanceUm(BinanceUmCli::default()));
+        let session = WsSession::from_target(&target).unwrap();
+        assert_eq!(session.policy, WsReconnectPolicy::default());
+    }
+
+    #[test]
+    fn replay_keeps_order_and_rebuilds_messages() {
+        let mut session = WsSession::from_target(&managed_target(Duration::ZERO, None)).unwrap();
+        let counter = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let builder_counter = counter.clone();
+
+        session.record(
+            WsReplayMsg::Built(WsMessageBuilder::new(move || {
+                let n = builder_counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(format!(r#"{{"op":"login","args":[{{"sign":"{}"}}]}}"#, n))
+            })),
+            r#"{"op":"login","args":[]}"#,
+        );
+        let subscribe = r#"{"op":"subscribe","args":[{"channel":"orders"}]}"#;
+        session.record(WsReplayMsg::Text(subscribe.into()), subscribe);
+        session.record(
+            WsReplayMsg::Built(WsMessageBuilder::new(|| {
+                Err(InfraError::Msg("unsigned".into()))
+            })),
+            r#"{"op":"subscribe","args":[{"channel":"account"}]}"#,
+        );
+
+        let first: Vec<_> = session.replay().map(|m| m.build().ok()).collect();
+        let second: Vec<_> = session.replay().map(|m| m.build().ok()).collect();
+
+        assert_eq!(
+            first,
+            vec![
+                Some(r#"{"op":"login","args":[{"sign":"0"}]}"#.into()),
+                Some(subscribe.into()),
+                None
+            ]
+        );
+        assert_eq!(
+            second[0].as_deref(),
+            Some(r#"{"op":"login","args":[{"sign":"1"}]}"#)
+        );
+    }
+
+    #[test]
+    fn classifies_venue_setup_messages() {
+        assert_eq!(
+            WsControl::classify(r#"{"op":"login","args":[]}"#),
+            WsControl::Login
+        );
+        assert_eq!(
+            WsControl::classify(r#"{"channel":"futures.login","event":"api","payload":{}}"#),
+            WsControl::Login
+        );
+        assert_eq!(
+            WsControl::classify(
+                r#"{"time":1,"channel":"futures.trades","event":"subscribe","payload":["BTC_USDT"]}"#
+            ),
+            WsControl::Subscribe(vec![r#"futures.trades:"BTC_USDT""#.into()])
+        );
+        assert_eq!(
+            WsControl::classify(r#"{"method":"UNSUBSCRIBE","params":["btcusdt@trade"],"id":2}"#),
+            WsControl::Unsubscribe(vec![r#":"btcusdt@trade""#.into()])
+        );
+        assert!(matches!(
+            WsControl::classify(
+                r#"{"method":"subscribe","subscription":{"type":"trades","coin":"BTC"}}"#
+            ),
+            WsControl::Subscribe(_)
+        ));
+        for other in [
+            "ping",
+            r#"{"op":"order","args":[{"instId":"BTC-USDT-SWAP"}]}"#,
+            r#"{"method":"order.place","params":{"symbol":"BTCUSDT"},"id":3}"#,
+            r#"{"channel":"futures.order_place","event":"api","payload":{}}"#,
+            r#"{"method":"post","request":{"type":"action"}}"#,
+        ] {
+            assert_eq!(WsControl::classify(other), WsControl::Other, "{other}");
+        }
+    }
+
+    #[test]
+    fn records_only_current_setup_messages() {
+        let mut session = WsSession::from_target(&managed_target(Duration::ZERO, None)).unwrap();
+        let text = |msg: &str| WsReplayMsg::Text(msg.into());
+        let sent = |session: &WsSession| -> Vec<String> {
+            session.replay().map(|m| m.build().unwrap()).collect()
+        };
+
+        let login = r#"{"op":"login","args":[{"sign":"a"}]}"#;
+        let relogin = r#"{"op":"login","args":[{"sign":"b"}]}"#;
+        let both = r#"{"op":"subscribe","args":[{"channel":"orders"},{"channel":"account"}]}"#;
+        let trades = r#"{"op":"subscribe","args":[{"channel":"trades"}]}"#;
+        let unsub_trades = r#"{"op":"unsubscribe","args":[{"channel":"trades"}]}"#;
+        let unsub_account = r#"{"op":"unsubscribe","args":[{"channel":"account"}]}"#;
+        for msg in [login, "ping", both, trades, trades, relogin, unsub_trades] {
+            assert!(session.record(text(msg), msg));
+        }
+        assert!(session.record(
+            text(r#"{"op":"order","args":[]}"#),
+            r#"{"op":"order","args":[]}"#
+        ));
+        assert_eq!(sent(&session), vec![relogin, both]);
+
+        assert!(session.record(text(unsub_account), unsub_account));
+        assert_eq!(sent(&session), vec![relogin, both, unsub_account]);
+    }
+
+    #[test]
+    fn replay_list_is_bounded() {
+        let mut session = WsSession::from_target(&managed_target(Duration::ZERO, None)).unwrap();
+        for n in 0..REPLAY_LIMIT {
+            let msg = format!(r#"{{"op":"subscribe","args":[{{"channel":"c{n}"}}]}}"#);
+            assert!(session.record(WsReplayMsg::Text(msg.clone()), &msg));
+        }
+
+        let extra = r#"{"op":"subscribe","args":[{"channel":"extra"}]}"#;
+        assert!(!session.record(WsReplayMsg::Text(extra.into()), extra));
+        assert_eq!(session.replay().count(), REPLAY_LIMIT);
+    }
+
+    #[test]
+    fn delay_stays_within_jitter_bounds() {
+        let session =
+            WsSession::from_target(&managed_target(Duration::from_millis(50), Some(3))).unwrap();
+
+        for attempt in 1..=5 {
+            let base = session.policy.backoff(attempt);
+            let delay = session.delay(attempt);
+            assert!(delay >= base && delay <= base + Duration::from_millis(50));
+        }
+
+        assert!(!session.exhausted(2));
+        assert!(session.exhausted(3));
+    }
+}
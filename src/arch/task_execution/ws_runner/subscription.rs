@@ -0,0 +1,556 @@
+use futures_util::SinkExt;
+use std::collections::VecDeque;
+use tokio::{
+    sync::oneshot,
+    time::{Duration, Instant, sleep},
+};
+use tungstenite::protocol::Message;
+
+#[cfg(feature = "binance")]
+use crate::arch::market_assets::exchange::binance::{
+    binance_cm_futures_cli::BinanceCmCli, binance_um_futures_cli::BinanceUmCli,
+};
+#[cfg(feature = "hyperliquid")]
+use crate::arch::market_assets::exchange::hyperliquid::hyperliquid_cli::HyperliquidCli;
+#[cfg(feature = "lob_clients")]
+use crate::arch::market_assets::exchange::lob_clients::LobClients;
+#[cfg(feature = "okx")]
+use crate::arch::market_assets::exchange::okx::okx_cli::OkxCli;
+#[cfg(any(
+    feature = "hyperliquid",
+    feature = "binance",
+    feature = "gate",
+    feature = "okx"
+))]
+use crate::arch::traits::market_lob::LobWebsocket;
+#[cfg(feature = "gate")]
+use crate::arch::{
+    market_assets::exchange::gate::gate_futures_cli::GateFuturesCli,
+    task_execution::task_ws::LobParam,
+};
+use crate::arch::{
+    market_assets::market_core::Market,
+    strategy_base::command::ack_handle::{AckHandle, AckStatus},
+    task_execution::{task_general::LogLevel, task_ws::WsChannel},
+};
+use crate::errors::{InfraError, InfraResult};
+
+use super::{WsStream, WsTaskRunner};
+
+/// Longest a subscription command waits for the venue's message budget before
+/// it is rejected instead.
+const MAX_SUBSCRIPTION_PACING: Duration = Duration::from_secs(5);
+
+/// One subscribed stream: a channel, for one instrument or for none.
+type Stream = (WsChannel, Option<String>);
+
+/// Venue limits on one public websocket connection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct SubscriptionLimits {
+    /// Streams one connection may hold.
+    pub max_streams: Option<usize>,
+    /// Subscribe and unsubscribe messages allowed per window.
+    pub max_msgs: Option<(usize, Duration)>,
+}
+
+/// Client that builds a market's public subscription messages.
+#[derive(Clone, Debug)]
+enum SubscriptionSource {
+    #[cfg(feature = "binance")]
+    BinanceCm(BinanceCmCli),
+    #[cfg(feature = "binance")]
+    BinanceUm(BinanceUmCli),
+    #[cfg(feature = "gate")]
+    GateFutures(GateFuturesCli),
+    #[cfg(feature = "hyperliquid")]
+    Hyperliquid(HyperliquidCli),
+    #[cfg(feature = "okx")]
+    Okx(OkxCli),
+}
+
+impl SubscriptionSource {
+    /// Default client of `market`, for relays without a registered client.
+    fn for_market(market: &Market) -> Option<Self> {
+        match market {
+            #[cfg(feature = "binance")]
+            Market::BinanceCmFutures => Some(Self::BinanceCm(BinanceCmCli::default())),
+            #[cfg(feature = "binance")]
+            Market::BinanceUmFutures => Some(Self::BinanceUm(BinanceUmCli::default())),
+            #[cfg(feature = "gate")]
+            Market::GateFutures => Some(Self::GateFutures(GateFuturesCli::default())),
+            #[cfg(feature = "hyperliquid")]
+            Market::HyperLiquid => Some(Self::Hyperliquid(HyperliquidCli::default())),
+            #[cfg(feature = "okx")]
+            Market::Okx => Some(Self::Okx(OkxCli::default())),
+            _ => None,
+        }
+    }
+
+    /// Source that reuses a registered execution client, so its endpoints
+    /// and instrument metadata, e.g. Hyperliquid spot and HIP-3 assets,
+    /// apply to subscription messages.
+    #[cfg(feature = "lob_clients")]
+    fn from_exec_client(client: &LobClients) -> Option<Self> {
+        match client {
+            LobClients::BinanceCm(cli) => Some(Self::BinanceCm(cli.clone())),
+            LobClients::BinanceUm(cli) => Some(Self::BinanceUm(cli.clone())),
+            LobClients::GateFutures(cli) => Some(Self::GateFutures(cli.clone())),
+            LobClients::Hyperliquid(cli) => Some(Self::Hyperliquid(cli.clone())),
+            LobClients::Okx(cli) => Some(Self::Okx(cli.clone())),
+            _ => None,
+        }
+    }
+
+    /// Limits published by the venue: Binance futures allow 200 streams and
+    /// 10 messages a second per connection, OKX 480 subscription requests an
+    /// hour, and Hyperliquid 1000 subscriptions and 2000 messages a minute.
+    fn limits(&self) -> SubscriptionLimits {
+        match self {
+            #[cfg(feature = "binance")]
+            Self::BinanceCm(_) | Self::BinanceUm(_) => SubscriptionLimits {
+                max_streams: Some(200),
+                max_msgs: Some((10, Duration::from_secs(1))),
+            },
+            #[cfg(feature = "hyperliquid")]
+            Self::Hyperliquid(_) => SubscriptionLimits {
+                max_streams: Some(1000),
+                max_msgs: Some((2000, Duration::from_secs(60))),
+            },
+            #[cfg(feature = "okx")]
+            Self::Okx(_) => SubscriptionLimits {
+                max_streams: None,
+                max_msgs: Some((480, Duration::from_secs(3600))),
+            },
+            #[allow(unreachable_patterns)]
+            _ => SubscriptionLimits::default(),
+        }
+    }
+
+    /// Instruments one message can carry on `channel`.
+    fn insts_per_msg(&self, _channel: &WsChannel) -> usize {
+        match self {
+            #[cfg(feature = "gate")]
+            Self::GateFutures(_) => match _channel {
+                WsChannel::Trades(_) | WsChannel::Lob(Some(LobParam::Bbo { .. })) => usize::MAX,
+                _ => 1,
+            },
+            #[cfg(feature = "hyperliquid")]
+            Self::Hyperliquid(_) => 1,
+            #[allow(unreachable_patterns)]
+            _ => usize::MAX,
+        }
+    }
+
+    async fn msg(
+        &self,
+        _channel: &WsChannel,
+        _insts: Option<&[String]>,
+        _subscribe: bool,
+    ) -> InfraResult<String> {
+        match self {
+            #[cfg(feature = "binance")]
+            Self::BinanceCm(cli) => build_msg(cli, _channel, _insts, _subscribe).await,
+            #[cfg(feature = "binance")]
+            Self::BinanceUm(cli) => build_msg(cli, _channel, _insts, _subscribe).await,
+            #[cfg(feature = "gate")]
+            Self::GateFutures(cli) => build_msg(cli, _channel, _insts, _subscribe).await,
+            #[cfg(feature = "hyperliquid")]
+            Self::Hyperliquid(cli) => build_msg(cli, _channel, _insts, _subscribe).await,
+            #[cfg(feature = "okx")]
+            Self::Okx(cli) => build_msg(cli, _channel, _insts, _subscribe).await,
+            #[allow(unreachable_patterns)]
+            _ => Err(InfraError::Unimplemented),
+        }
+    }
+
+    /// Messages that (un)subscribe `streams`, all on `channel`.
+    async fn msgs(
+        &self,
+        channel: &WsChannel,
+        streams: &[Stream],
+        subscribe: bool,
+    ) -> InfraResult<Vec<String>> {
+        let insts: Vec<String> = streams
+            .iter()
+            .filter_map(|(_, inst)| inst.clone())
+            .collect();
+        if insts.is_empty() {
+            return Ok(vec![self.msg(channel, None, subscribe).await?]);
+        }
+
+        let mut msgs = Vec::new();
+        for chunk in insts.chunks(self.insts_per_msg(channel)) {
+            msgs.push(self.msg(channel, Some(chunk), subscribe).await?);
+        }
+        Ok(msgs)
+    }
+}
+
+#[cfg(any(
+    feature = "hyperliquid",
+    feature = "binance",
+    feature = "gate",
+    feature = "okx"
+))]
+async fn build_msg(
+    cli: &impl LobWebsocket,
+    channel: &WsChannel,
+    insts: Option<&[String]>,
+    subscribe: bool,
+) -> InfraResult<String> {
+    if subscribe {
+        cli.get_public_sub_msg(channel, insts).await
+    } else {
+        cli.get_public_unsub_msg(channel, insts).await
+    }
+}
+
+/// Public subscriptions active on a relay's connection.
+#[derive(Debug, Default)]
+pub(crate) struct Subscriptions {
+    source: Option<SubscriptionSource>,
+    active: Vec<Stream>,
+    /// Send times of recent subscription messages, oldest first.
+    sent: VecDeque<Instant>,
+}
+
+impl Subscriptions {
+    /// Forgets the subscriptions of a connection the strategy replaced.
+    pub(crate) fn clear(&mut self) {
+        self.active.clear();
+        self.sent.clear();
+    }
+
+    /// Delay before the last of `count` further messages may be sent under
+    /// `limits`, counting from `now`.
+    fn pacing(&self, limits: &SubscriptionLimits, count: usize, now: Instant) -> Duration {
+        let Some((max_msgs, window)) = limits.max_msgs else {
+            return Duration::ZERO;
+        };
+
+        let mut times: Vec<Instant> = self.sent.iter().copied().collect();
+        let mut at = now;
+        for _ in 0..count {
+            let send_at = match times.len().checked_sub(max_msgs) {
+                Some(i) => (times[i] + window).max(at),
+                None => at,
+            };
+            times.push(send_at);
+            at = send_at;
+        }
+        at.saturating_duration_since(now)
+    }
+
+    fn record_send(&mut self, limits: &SubscriptionLimits, now: Instant) {
+        let Some((max_msgs, window)) = limits.max_msgs else {
+            return;
+        };
+
+        self.sent.push_back(now);
+        while self.sent.len() > max_msgs
+            || self
+                .sent
+                .front()
+                .is_some_and(|sent| now.saturating_duration_since(*sent) >= window)
+        {
+            self.sent.pop_front();
+        }
+    }
+}
+
+fn streams(channel: &WsChannel, insts: Vec<String>) -> Vec<Stream> {
+    if insts.is_empty() {
+        return vec![(channel.clone(), None)];
+    }
+
+    let mut streams: Vec<Stream> = Vec::with_capacity(insts.len());
+    for inst in insts {
+        let stream = (channel.clone(), Some(inst));
+        if !streams.contains(&stream) {
+            streams.push(stream);
+        }
+    }
+    streams
+}
+
+impl WsTaskRunner {
+    /// Client that builds this relay's subscription messages: the execution
+    /// client registered for its market, else the market's default client.
+    fn subscription_source(&mut self) -> Option<SubscriptionSource> {
+        if self.subscriptions.source.is_none() {
+            #[cfg(feature = "lob_clients")]
+            let registered = self
+                .exec_clients
+                .get(&self.ws_info.market)
+                .and_then(SubscriptionSource::from_exec_client);
+            #[cfg(not(feature = "lob_clients"))]
+            let registered = None;
+
+            self.subscriptions.source =
+                registered.or_else(|| SubscriptionSource::for_market(&self.ws_info.market));
+        }
+        self.subscriptions.source.clone()
+    }
+
+    /// Serves `TaskCommand::Subscribe`.
+    pub(super) async fn subscribe(
+        &mut self,
+        channel: WsChannel,
+        insts: Vec<String>,
+        ack: AckHandle,
+        ws_stream: &mut WsStream,
+    ) {
+        let Some(source) = self.subscription_source() else {
+            return self.reject_subscription(ack, "typed subscriptions are unsupported".into());
+        };
+        let limits = source.limits();
+
+        let new: Vec<_> = streams(&channel, insts)
+            .into_iter()
+            .filter(|stream| !self.subscriptions.active.contains(stream))
+            .collect();
+        if new.is_empty() {
+            return ack.respond(AckStatus::WsMessage);
+        }
+
+        let total = self.subscriptions.active.len() + new.len();
+        if let Some(max_streams) = limits.max_streams
+            && total > max_streams
+        {
+            let reason = format!("{total} streams would exceed the limit of {max_streams}");
+            return self.reject_subscription(ack, reason);
+        }
+
+        match self
+            .send_subscription_msgs(&source, &channel, &new, true, ws_stream)
+            .await
+        {
+            Ok(()) => {
+                self.subscriptions.active.extend(new);
+                ack.respond(AckStatus::WsMessage);
+            },
+            Err(e) => self.reject_subscription(ack, e.to_string()),
+        }
+    }
+
+    /// Serves `TaskCommand::Unsubscribe`.
+    pub(super) async fn unsubscribe(
+        &mut self,
+        channel: WsChannel,
+        insts: Vec<String>,
+        ack: AckHandle,
+        ws_stream: &mut WsStream,
+    ) {
+        let Some(source) = self.subscription_source() else {
+            return self.reject_subscription(ack, "typed subscriptions are unsupported".into());
+        };
+
+        let dropped: Vec<_> = streams(&channel, insts)
+            .into_iter()
+            .filter(|stream| self.subscriptions.active.contains(stream))
+            .collect();
+        if dropped.is_empty() {
+            return ack.respond(AckStatus::WsMessage);
+        }
+
+        match self
+            .send_subscription_msgs(&source, &channel, &dropped, false, ws_stream)
+            .await
+        {
+            Ok(()) => {
+                self.subscriptions
+                    .active
+                    .retain(|stream| !dropped.contains(stream));
+                ack.respond(AckStatus::WsMessage);
+            },
+            Err(e) => self.reject_subscription(ack, e.to_string()),
+        }
+    }
+
+    /// Subscribes a sharded relay to its share of the instrument universe.
+    pub(super) async fn subscribe_shard(&mut self, ws_stream: &mut WsStream) {
+        let Some(insts) = self.ws_info.shard_insts(self.task_id) else {
+            return;
+        };
+
+        let (channel, insts) = (self.ws_info.ws_channel.clone(), insts.to_vec());
+        let count = insts.len();
+        let (tx, mut rx) = oneshot::channel();
+        self.subscribe(channel, insts, AckHandle::new(tx), ws_stream)
+            .await;
+        if rx.try_recv() == Ok(AckStatus::WsMessage) {
+            self.log(
+                LogLevel::Info,
+                &format!("Subscribed shard of {count} instruments"),
+            );
+        }
+    }
+
+    /// Subscribes a reconnected relay to every active stream again.
+    pub(super) async fn resubscribe(&mut self, ws_stream: &mut WsStream) -> InfraResult<usize> {
+        self.subscriptions.sent.clear();
+        let Some(source) = self.subscriptions.source.clone() else {
+            return Ok(0);
+        };
+        let limits = source.limits();
+
+        let mut channels: Vec<WsChannel> = Vec::new();
+        for (channel, _) in &self.subscriptions.active {
+            if !channels.contains(channel) {
+                channels.push(channel.clone());
+            }
+        }
+
+        let mut sent = 0;
+        for channel in channels {
+            let streams: Vec<_> = self
+                .subscriptions
+                .active
+                .iter()
+                .filter(|(c, _)| c == &channel)
+                .cloned()
+                .collect();
+
+            for msg in source.msgs(&channel, &streams, true).await? {
+                sleep(self.subscriptions.pacing(&limits, 1, Instant::now())).await;
+                ws_stream
+                    .send(Message::text(msg))
+                    .await
+                    .map_err(|e| InfraError::WebSocket(Box::new(e)))?;
+                self.subscriptions.record_send(&limits, Instant::now());
+                sent += 1;
+            }
+        }
+
+        Ok(sent)
+    }
+
+    async fn send_subscription_msgs(
+        &mut self,
+        source: &SubscriptionSource,
+        channel: &WsChannel,
+        streams: &[Stream],
+        subscribe: bool,
+        ws_stream: &mut WsStream,
+    ) -> InfraResult<()> {
+        let limits = source.limits();
+        let msgs = source.msgs(channel, streams, subscribe).await?;
+
+        let pacing = self
+            .subscriptions
+            .pacing(&limits, msgs.len(), Instant::now());
+        if pacing > MAX_SUBSCRIPTION_PACING {
+            return Err(InfraError::Msg(format!(
+                "venue message limit leaves no room for {} messages before {:?}",
+                msgs.len(),
+                pacing
+            )));
+        }
+
+        for msg in msgs {
+            sleep(self.subscriptions.pacing(&limits, 1, Instant::now())).await;
+            ws_stream
+                .send(Message::text(msg))
+                .await
+                .map_err(|e| InfraError::WebSocket(Box::new(e)))?;
+            self.subscriptions.record_send(&limits, Instant::now());
+        }
+
+        Ok(())
+    }
+
+    fn reject_subscription(&self, ack: AckHandle, reason: String) {
+        self.log(
+            LogLevel::Warn,
+            &format!("Subscription rejected: {}", reason),
+        );
+        ack.respond(AckStatus::WsRejected(reason));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paces_messages_inside_the_venue_window() {
+        let limits = SubscriptionLimits {
+            max_streams: None,
+            max_msgs: Some((2, Duration::from_secs(1))),
+        };
+        let start = Instant::now();
+        let mut subs = Subscriptions::default();
+
+        assert_eq!(subs.pacing(&limits, 2, start), Duration::ZERO);
+        subs.record_send(&limits, start);
+        subs.record_send(&limits, start + Duration::from_millis(400));
+
+        let now = start + Duration::from_millis(500);
+        assert_eq!(subs.pacing(&limits, 1, now), Duration::from_millis(500));
+        assert_eq!(subs.pacing(&limits, 2, now), Duration::from_millis(900));
+        assert_eq!(subs.pacing(&limits, 3, now), Duration::from_millis(1500));
+
+        subs.record_send(&limits, start + Duration::from_millis(1200));
+        assert_eq!(subs.sent.len(), 2);
+    }
+
+    #[test]
+    fn splits_commands_into_unique_streams() {
+        let channel = WsChannel::Trades(None);
+        let btc = (channel.clone(), Some("BTC_USDT_PERP".to_string()));
+
+        assert_eq!(
+            streams(
+                &channel,
+                vec!["BTC_USDT_PERP".into(), "BTC_USDT_PERP".into()]
+            ),
+            vec![btc]
+        );
+        assert_eq!(streams(&channel, vec![]), vec![(channel, None)]);
+    }
+
+    #[cfg(feature = "okx")]
+    #[tokio::test]
+    async fn builds_okx_subscribe_and_unsubscribe_messages() {
+        let source = SubscriptionSource::for_market(&Market::Okx).unwrap();
+        let channel = WsChannel::Trades(None);
+        let streams = streams(
+            &channel,
+            vec!["BTC_USDT_PERP".into(), "ETH_USDT_PERP".into()],
+        );
+
+        let subscribe = source.msgs(&channel, &streams, true).await.unwrap();
+        let msg: serde_json::Value = serde_json::from_str(&subscribe[0]).unwrap();
+        assert_eq!(subscribe.len(), 1);
+        assert_eq!(msg["op"], "subscribe");
+        assert_eq!(msg["args"].as_array().unwrap().len(), 2);
+
+        let unsubscribe = source.msgs(&channel, &streams, false).await.unwrap();
+        let msg: serde_json::Value = serde_json::from_str(&unsubscribe[0]).unwrap();
+        assert_eq!(msg["op"], "unsubscribe");
+    }
+
+    #[cfg(feature = "lob_clients")]
+    #[test]
+    fn reuses_the_registered_client() {
+        use crate::arch::market_assets::exchange::{
+            binance::binance_spot_cli::BinanceSpotCli, endpoints::EndpointProfile,
+        };
+
+        let endpoints = EndpointProfile::new("http://127.0.0.1:9000", "ws://127.0.0.1:9000");
+        let cli = OkxCli::default().with_endpoints(endpoints.clone());
+        let Some(SubscriptionSource::Okx(source)) =
+            SubscriptionSource::from_exec_client(&LobClients::Okx(cli))
+        else {
+            panic!("expected an OKX source");
+        };
+        assert_eq!(source.endpoints, endpoints);
+
+        assert!(
+            SubscriptionSource::from_exec_client(&LobClients::BinanceSpot(
+                BinanceSpotCli::default()
+            ))
+            .is_none()
+        );
+    }
+}
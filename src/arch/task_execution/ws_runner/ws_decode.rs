@@ -1,5 +1,8 @@
 use serde::de::DeserializeOwned;
 
+#[cfg(any(feature = "okx", feature = "binance"))]
+use crate::arch::{strategy_base::handler::lob_events::WsFill, traits::conversion::IntoWsData};
+
 /// Tries the runner's expected data shape before falling back to the full frame.
 pub(crate) fn decode_preferred<Frame, Preferred, Wrap>(
     frame: &[u8],
@@ -23,6 +26,25 @@ where
     }
 }
 
+/// Decoded private order frame reduced to the updates that carried a fill.
+///
+/// Exchange fill views map each order update to `Option<WsFill>`; this wrapper
+/// drops the `None`s so `on_acc_fill` only sees executions.
+#[cfg(any(feature = "okx", feature = "binance"))]
+pub(crate) struct FillBatch<D>(pub(crate) D);
+
+#[cfg(any(feature = "okx", feature = "binance"))]
+impl<D> IntoWsData for FillBatch<D>
+where
+    D: IntoWsData<Output = Vec<Option<WsFill>>>,
+{
+    type Output = Vec<WsFill>;
+
+    fn into_ws(self) -> Self::Output {
+        self.0.into_ws().into_iter().flatten().collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde::Deserialize;
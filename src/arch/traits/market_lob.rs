@@ -1,9 +1,12 @@
-use std::future::{Future, ready};
+use std::{
+    future::{Future, ready},
+    time::Duration,
+};
 
 use crate::arch::{
     market_assets::{
         api_data::{account_data::*, price_data::*, utils_data::*},
-        api_general::{CancelOrderParams, OrderParams},
+        api_general::{AmendOrderParams, CancelOrderParams, OrderParams},
         base_data::InstrumentType,
     },
     strategy_base::command::command_core::WsConnectTarget,
@@ -70,6 +73,11 @@ pub trait LobPublicRest: Send + Sync {
     ) -> impl Future<Output = InfraResult<Vec<String>>> + Send {
         ready(Err(InfraError::Unimplemented))
     }
+
+    /// Fetches the venue's current time in microseconds since the Unix epoch.
+    fn get_server_time(&self) -> impl Future<Output = InfraResult<u64>> + Send {
+        ready(Err(InfraError::Unimplemented))
+    }
 }
 
 /// Private REST operations for LOB-style exchanges.
@@ -116,6 +124,47 @@ pub trait LobPrivateRest: Send + Sync {
         ready(Err(InfraError::Unimplemented))
     }
 
+    /// Changes the price and/or size of one resting order in place, keeping
+    /// its queue priority where the venue allows it.
+    fn amend_order(
+        &self,
+        _amend_params: AmendOrderParams,
+    ) -> impl Future<Output = InfraResult<OrderAckData>> + Send {
+        ready(Err(InfraError::Unimplemented))
+    }
+
+    /// Amends multiple orders through one exchange-native batch request.
+    fn amend_orders(
+        &self,
+        _amend_params: Vec<AmendOrderParams>,
+    ) -> impl Future<Output = InfraResult<Vec<OrderAckData>>> + Send {
+        ready(Err(InfraError::Unimplemented))
+    }
+
+    /// Cancels every open order on `inst`, or on every instrument when `inst`
+    /// is `None` and the venue allows it.
+    ///
+    /// Returns one ack per canceled order when the venue reports them.
+    fn cancel_all_orders(
+        &self,
+        _inst: Option<&str>,
+    ) -> impl Future<Output = InfraResult<Vec<OrderAckData>>> + Send {
+        ready(Err(InfraError::Unimplemented))
+    }
+
+    /// Arms the venue's dead man's switch: open orders are canceled unless
+    /// this is called again within `timeout`. `Duration::ZERO` disarms it.
+    ///
+    /// `inst` narrows the scope on venues that arm the countdown per
+    /// instrument or per settle currency, and is ignored elsewhere.
+    fn set_cancel_after(
+        &self,
+        _inst: Option<&str>,
+        _timeout: Duration,
+    ) -> impl Future<Output = InfraResult<()>> + Send {
+        ready(Err(InfraError::Unimplemented))
+    }
+
     /// Fetches open orders for one instrument.
     ///
     /// `limit` caps the total number of returned orders. `None` requests all
@@ -128,6 +177,22 @@ pub trait LobPrivateRest: Send + Sync {
         ready(Err(InfraError::Unimplemented))
     }
 
+    /// Fetches one open or finished order by exchange order id or client
+    /// order id.
+    ///
+    /// Returns `None`, or an [`ErrorCategory::UnknownOrder`] rejection, when
+    /// the venue has no record of the order.
+    ///
+    /// [`ErrorCategory::UnknownOrder`]: crate::arch::market_assets::exchange::exchange_error::ErrorCategory::UnknownOrder
+    fn get_order(
+        &self,
+        _inst: &str,
+        _order_id: Option<&str>,
+        _cli_order_id: Option<&str>,
+    ) -> impl Future<Output = InfraResult<Option<OrderDetailData>>> + Send {
+        ready(Err(InfraError::Unimplemented))
+    }
+
     /// Fetches account balances.
     fn get_balance(
         &self,
@@ -176,6 +241,15 @@ pub trait LobWebsocket: Send + Sync {
         ready(Err(InfraError::Unimplemented))
     }
 
+    /// Builds the message that cancels a public subscription.
+    fn get_public_unsub_msg(
+        &self,
+        _channel: &WsChannel,
+        _insts: Option<&[String]>,
+    ) -> impl Future<Output = InfraResult<String>> + Send {
+        ready(Err(InfraError::Unimplemented))
+    }
+
     /// Builds a private subscription message.
     fn get_private_sub_msg(
         &self,
@@ -228,3 +302,33 @@ pub trait LobWebsocket: Send + Sync {
         ready(Err(InfraError::Unimplemented))
     }
 }
+
+/// Websocket order entry for LOB-style exchanges.
+///
+/// Implementations build signed trading requests and read the venue's
+/// responses; a `WsChannel::OrderEntry` relay task owns IO and correlates
+/// responses with requests by id. The session URL comes from
+/// [`LobWebsocket::get_private_connect_msg`] for `WsChannel::OrderEntry`.
+/// Venues that require a session login before trading expose it as a helper
+/// on the concrete client, e.g. `OkxCli::ws_login_msg`.
+pub trait LobWsOrderEntry: Send + Sync {
+    /// Builds one request placing `orders` under request `id`.
+    fn ws_place_orders_msg(&self, _id: u64, _orders: &[OrderParams]) -> InfraResult<String> {
+        Err(InfraError::Unimplemented)
+    }
+
+    /// Builds one request canceling `cancels` under request `id`.
+    fn ws_cancel_orders_msg(
+        &self,
+        _id: u64,
+        _cancels: &[CancelOrderParams],
+    ) -> InfraResult<String> {
+        Err(InfraError::Unimplemented)
+    }
+
+    /// Reads a response frame, or returns `None` for frames that answer no
+    /// request, such as login confirmations and intermediate acks.
+    fn parse_ws_order_response(&self, _frame: &[u8]) -> Option<WsOrderResponse> {
+        None
+    }
+}
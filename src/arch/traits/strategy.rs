@@ -1,11 +1,13 @@
 use std::{future::ready, sync::Arc};
 
+use tokio::task::JoinHandle;
+
 use crate::arch::{
     strategy_base::{
         command::command_core::{CommandHandle, CommandRegistry},
         handler::{
-            events::{InfraMsg, alt_events::*, lob_events::*},
-            task_channel::TaskChannels,
+            events::{InfraMsg, alt_events::*, lob_events::*, ws_events::*},
+            task_channel::{RoutedEvent, TaskChannels},
         },
     },
     task_execution::{
@@ -30,6 +32,18 @@ pub trait Strategy: CommandEmitter + EventHandler {
     /// strategy event loops after initialization.
     fn initialize(&mut self) -> impl Future<Output = ()> + Send;
 
+    /// Runs once after shutdown was requested and task relays have stopped.
+    ///
+    /// Use this hook to cancel resting orders through REST clients, flush
+    /// local state, or close files. The runtime calls it on every registered
+    /// module in registration order, then drains the strategy event loops
+    /// within the configured shutdown deadline. The hook runs on the module
+    /// instance kept by the runtime, not on the clones owned by event loops,
+    /// so shared state should live behind `Arc`.
+    fn on_shutdown(&mut self) -> impl Future<Output = ()> + Send {
+        ready(())
+    }
+
     /// Human-readable module name used in runtime logs.
     fn strategy_name(&self) -> &'static str {
         std::any::type_name::<Self>()
@@ -39,11 +53,22 @@ pub trait Strategy: CommandEmitter + EventHandler {
     ///
     /// Strategy modules registered through `EnvBuilder` normally should not
     /// override this method. The heterogeneous strategy list provides the
-    /// runtime implementation that spawns each module's event handler loop.
+    /// runtime implementation that spawns each module's event handler loop and
+    /// returns their join handles so shutdown can wait for them to drain.
     fn _spawn_strategy_tasks(
         &self,
         _task_channels: &Arc<TaskChannels>,
-    ) -> impl Future<Output = ()> + Send {
+    ) -> impl Future<Output = Vec<JoinHandle<()>>> + Send {
+        ready(Vec::new())
+    }
+
+    /// Internal hook used by the backtest runtime.
+    ///
+    /// Strategy modules registered through `EnvBuilder` normally should not
+    /// override this method. The strategy-list runtime hands the event to every
+    /// module bound to its task, one module at a time in registration order,
+    /// instead of spawning event loops.
+    fn _dispatch_event(&mut self, _event: RoutedEvent) -> impl Future<Output = ()> + Send {
         ready(())
     }
 }
@@ -121,6 +146,14 @@ pub trait CommandEmitter: Clone + Send + Sync + 'static {
     /// task handles.
     fn command_registry(&self) -> Arc<CommandRegistry>;
 
+    /// Current runtime time in microseconds since the Unix epoch.
+    ///
+    /// Prefer this over `get_micros_timestamp` in strategy logic: backtests
+    /// answer with the simulated time of the event being dispatched.
+    fn now_micros(&self) -> u64 {
+        self.command_registry().clock().now_micros()
+    }
+
     /// Finds an alt-task command handle by task type and task id.
     ///
     /// Use this for scheduler/model/order/intent style tasks. For example, a
@@ -216,6 +249,19 @@ pub trait EventHandler {
         ready(())
     }
 
+    /// Receives venue responses to orders and cancels.
+    ///
+    /// This is emitted by an `AltTaskType::LobExecution` task after it served
+    /// `TaskCommand::OrderExecute` or `TaskCommand::OrderCancel`. Each entry
+    /// carries the request, the metadata of the originating command, and the
+    /// acknowledgement or error returned by the venue.
+    fn on_order_ack(
+        &mut self,
+        _msg: InfraMsg<Vec<AltOrderAck>>,
+    ) -> impl Future<Output = ()> + Send {
+        ready(())
+    }
+
     /// Receives instrument, portfolio, or allocation intents.
     ///
     /// This is emitted by an `AltTaskType::InstIntent` task after a strategy
@@ -253,6 +299,19 @@ pub trait EventHandler {
         ready(())
     }
 
+    /// Receives websocket connection lifecycle changes.
+    ///
+    /// Every relay reports [`WsConnectionState::Connected`]. Relays connected
+    /// with `WsConnectTarget::with_reconnect` also report reconnect progress,
+    /// replay completion, and give-up, and those connected with
+    /// `WsConnectTarget::with_listen_key` report listen key renewal and expiry.
+    fn on_ws_connection(
+        &mut self,
+        _msg: InfraMsg<WsConnectionState>,
+    ) -> impl Future<Output = ()> + Send {
+        ready(())
+    }
+
     /// Receives normalized public trade batches.
     ///
     /// A concrete websocket task configured with [`WsChannel::Trades`] publishes
@@ -269,6 +328,17 @@ pub trait EventHandler {
         ready(())
     }
 
+    /// Receives consistent L2 book views.
+    ///
+    /// Emitted by websocket tasks configured with
+    /// `WsChannel::Lob(Some(LobParam::Incremental { .. }))` on venues where the
+    /// relay maintains the book itself. Every module bound to the task receives
+    /// the same view, so none of them needs to resynchronize on its own. Raw
+    /// deltas are still delivered to [`EventHandler::on_lob`].
+    fn on_lob_book(&mut self, _msg: InfraMsg<Vec<WsLobBook>>) -> impl Future<Output = ()> + Send {
+        ready(())
+    }
+
     /// Receives normalized market-by-order order book updates.
     ///
     /// A concrete exchange task that implements [`WsChannel::LobMbo`] routing
@@ -294,6 +364,16 @@ pub trait EventHandler {
         ready(())
     }
 
+    /// Receives private executions, one [`WsFill`] per fill.
+    ///
+    /// Emitted by private websocket relays configured with
+    /// [`WsChannel::AccountFills`]. Unlike [`on_acc_order`](Self::on_acc_order),
+    /// each item carries the execution price, fee, liquidity flag, and trade
+    /// id, so use this for TCA and PnL.
+    fn on_acc_fill(&mut self, _msg: InfraMsg<Vec<WsFill>>) -> impl Future<Output = ()> + Send {
+        ready(())
+    }
+
     /// Receives private account balance and position updates.
     ///
     /// Emitted by private websocket relays configured with
@@ -4,10 +4,16 @@
 //! which is a crate-local alias around [`Result`] with [`InfraError`]. The error
 //! enum keeps exchange, parsing, environment-variable, and
 //! unimplemented-endpoint failures in one type so strategy code can propagate
-//! errors without depending on exchange-specific error enums.
+//! errors without depending on exchange-specific error enums. Exchange
+//! rejections arrive as [`ExchangeError`] with a normalized [`ErrorCategory`].
 
 use thiserror::Error;
 
+use crate::arch::{
+    market_assets::exchange::exchange_error::{ErrorCategory, ExchangeError},
+    strategy_base::risk::RiskRejection,
+};
+
 #[derive(Error, Debug)]
 pub enum InfraError {
     #[error("IO error: {0}")]
@@ -32,6 +38,9 @@ pub enum InfraError {
     #[error("API cli error: {0}")]
     ApiCliError(String),
 
+    #[error("Exchange error: {0}")]
+    Exchange(#[from] ExchangeError),
+
     #[error("API cli not initialized")]
     ApiCliNotInitialized,
 
@@ -41,6 +50,12 @@ pub enum InfraError {
     #[error("Environment variable missing: {0}")]
     EnvVarMissing(String),
 
+    #[error("Risk rejected: {0}")]
+    RiskRejected(#[from] RiskRejection),
+
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
     #[error("Unimplemented method")]
     Unimplemented,
 
@@ -48,4 +63,17 @@ pub enum InfraError {
     Msg(String),
 }
 
+impl InfraError {
+    /// Normalized reason for exchange rejections, local rate limiting, and
+    /// request timeouts; `None` for every other error.
+    pub fn category(&self) -> Option<ErrorCategory> {
+        match self {
+            Self::Exchange(e) => Some(e.category),
+            Self::RateLimited(_) => Some(ErrorCategory::RateLimited),
+            Self::RestApi(e) if e.is_timeout() => Some(ErrorCategory::Timeout),
+            _ => None,
+        }
+    }
+}
+
 pub type InfraResult<T> = Result<T, InfraError>;
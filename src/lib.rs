@@ -82,6 +82,11 @@
 //!       -> tasks publish InfraMsg<T>
 //!       -> EventHandler callbacks react
 //!       -> strategies send TaskCommand through CommandHandle when needed
+//!       -> on ShutdownHandle::shutdown(): stop tasks, Strategy::on_shutdown(),
+//!          drain strategy event loops
+//!   -> or EnvMediator::into_backtest().run(records)
+//!       -> replay records on their TaskKey with a simulated clock
+//!       -> OrderExecute filled by a simulated matcher
 //! ```
 //!
 //! `InfraMsg<T>` always carries the `task_id` that emitted the event, but not the
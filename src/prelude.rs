@@ -14,18 +14,38 @@
 pub use crate::errors::{InfraError, InfraResult};
 
 pub use crate::arch::{
-    infra_core::{env_builder::EnvBuilder, env_mediator::EnvMediator},
+    infra_core::{
+        backtest::*,
+        clock::RuntimeClock,
+        env_builder::EnvBuilder,
+        env_mediator::{DEFAULT_SHUTDOWN_TIMEOUT, EnvMediator},
+        shutdown::ShutdownHandle,
+    },
     market_assets::{
         base_data::*,
+        instrument_registry::{InstrumentChange, InstrumentRegistry},
         market_core::{Market, MarketScope},
+        order_book::*,
     },
     strategy_base::{
         command::{
             ack_handle::{AckHandle, AckStatus},
             command_core::*,
         },
-        handler::events::{InfraMsg, alt_events::*, lob_events::*},
+        handler::events::{InfraMsg, alt_events::*, lob_events::*, ws_events::*},
+        oms::{
+            OrderManager, OrderState, OrderTransition, ReconcileReport, TrackedOrder, UpdateSource,
+        },
+        portfolio::{DEFAULT_DRIFT_TOLERANCE, PortfolioState, PositionDrift, PositionState},
+        recorder::{
+            MarketRecorder, RecordFormat, RecordedData, RecordedEvent, RecorderConfig,
+            RecordingFile, RecordingReader, read_index,
+        },
+        risk::{OrderRateLimit, RiskGate, RiskLimits, RiskRejection},
     },
     task_execution::{TaskInfo, TaskKey, task_alt::*, task_ws::*},
     traits::{conversion::*, market_lob::*, strategy::*},
 };
+
+#[cfg(feature = "mock_venue")]
+pub use crate::arch::infra_core::mock_venue::{MockVenue, MockVenueConfig};
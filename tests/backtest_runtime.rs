@@ -0,0 +1,370 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[cfg(feature = "lob_clients")]
+use extrema_infra::arch::market_assets::api_general::CancelOrderParams;
+use extrema_infra::{arch::market_assets::api_general::OrderParams, prelude::*};
+
+#[derive(Clone)]
+struct ReplayProbe {
+    registry: Arc<CommandRegistry>,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl ReplayProbe {
+    fn new() -> Self {
+        Self {
+            registry: Arc::new(CommandRegistry::default()),
+            log: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn record(&self, entry: String) {
+        self.log.lock().unwrap().push(entry);
+    }
+}
+
+impl Strategy for ReplayProbe {
+    async fn initialize(&mut self) {}
+}
+
+impl CommandEmitter for ReplayProbe {
+    fn command_init(&mut self, registry: Arc<CommandRegistry>) {
+        self.registry = registry;
+    }
+
+    fn command_registry(&self) -> Arc<CommandRegistry> {
+        self.registry.clone()
+    }
+}
+
+impl EventHandler for ReplayProbe {
+    async fn on_ws_event(&mut self, msg: InfraMsg<WsTaskInfo>) {
+        let handle = self
+            .find_ws_handle(&msg.data.ws_channel, msg.task_id)
+            .unwrap();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let connected = handle
+            .send_command(
+                TaskCommand::WsConnect {
+                    msg: "wss://replay.invalid".into(),
+                    ack: AckHandle::new(tx),
+                },
+                Some((AckStatus::WsConnect, rx)),
+            )
+            .await;
+        self.record(format!("connected {:?}", connected.is_ok()));
+    }
+
+    async fn on_schedule(&mut self, _msg: InfraMsg<AltScheduleEvent>) {
+        self.record(format!("tick {}", self.now_micros()));
+    }
+
+    async fn on_trade(&mut self, msg: InfraMsg<Vec<WsTrade>>) {
+        let trade = &msg.data[0];
+        self.record(format!("trade {} at {}", trade.price, self.now_micros()));
+        if trade.trade_id != 1 {
+            return;
+        }
+
+        let order = AltOrder {
+            timestamp: self.now_micros(),
+            market: Market::Okx,
+            order_params: OrderParams {
+                inst: trade.inst.clone(),
+                side: OrderSide::BUY,
+                size: "2".into(),
+                order_type: OrderType::Limit,
+                price: Some("99".into()),
+                ..Default::default()
+            },
+            metadata: HashMap::new(),
+        };
+        self.find_alt_handle(&AltTaskType::OrderExecution, 1)
+            .unwrap()
+            .send_command(TaskCommand::OrderExecute(vec![order]), None)
+            .await
+            .unwrap();
+    }
+
+    async fn on_acc_order(&mut self, msg: InfraMsg<Vec<WsAccOrder>>) {
+        for order in msg.data.iter() {
+            self.record(format!(
+                "order {:?} {} at {}",
+                order.status,
+                order.price,
+                self.now_micros()
+            ));
+        }
+    }
+
+    async fn on_acc_fill(&mut self, msg: InfraMsg<Vec<WsFill>>) {
+        for fill in msg.data.iter() {
+            self.record(format!("fill {} {:?}", fill.price, fill.liquidity));
+        }
+    }
+
+    async fn on_acc_pos(&mut self, msg: InfraMsg<Vec<WsAccPosition>>) {
+        self.record(format!("position {}", msg.data[0].size));
+    }
+}
+
+fn trade(timestamp: u64, trade_id: u64, price: f64) -> BacktestRecord {
+    BacktestRecord::new(
+        timestamp,
+        1,
+        BacktestData::Trade(vec![WsTrade {
+            timestamp,
+            market: Market::Okx,
+            inst: "BTC-USDT-SWAP".into(),
+            price,
+            size: 1.0,
+            side: OrderSide::SELL,
+            trade_id,
+        }]),
+    )
+}
+
+fn ws_task(ws_channel: WsChannel) -> WsTaskInfo {
+    WsTaskInfo {
+        market: Market::Okx,
+        ws_channel,
+        filter_channels: false,
+        chunk: 1,
+        task_base_id: Some(1),
+        shards: None,
+    }
+}
+
+fn alt_task(alt_task_type: AltTaskType) -> AltTaskInfo {
+    AltTaskInfo {
+        alt_task_type,
+        chunk: 1,
+        task_base_id: Some(1),
+    }
+}
+
+async fn run_once() -> (BacktestReport, Vec<String>) {
+    let probe = ReplayProbe::new();
+    let log = probe.log.clone();
+    let env = EnvBuilder::new()
+        .with_task(ws_task(WsChannel::Trades(None)))
+        .with_task(ws_task(WsChannel::AccountOrders))
+        .with_task(ws_task(WsChannel::AccountFills))
+        .with_task(ws_task(WsChannel::AccountPositions))
+        .with_task(alt_task(AltTaskType::OrderExecution))
+        .with_task(alt_task(AltTaskType::TimeScheduler(Duration::from_secs(1))))
+        .with_strategy_module(probe)
+        .build()
+        .unwrap();
+
+    let records = vec![
+        trade(1_000_000, 1, 100.0),
+        trade(1_500_000, 2, 99.5),
+        trade(2_500_000, 3, 98.5),
+    ];
+    let report = env
+        .into_backtest()
+        .with_config(BacktestConfig {
+            order_latency: Duration::from_millis(100),
+            maker_fee_rate: 0.0002,
+            ..Default::default()
+        })
+        .run(records)
+        .await
+        .unwrap();
+
+    let log = log.lock().unwrap().clone();
+    (report, log)
+}
+
+#[tokio::test]
+async fn backtest_replays_records_and_simulates_fills() {
+    let (report, log) = run_once().await;
+
+    assert_eq!(report.records, 3);
+    assert_eq!(report.orders, 1);
+    assert_eq!(report.fills.len(), 1);
+    assert_eq!(report.fills[0].price, 99.0);
+    assert_eq!(report.fills[0].timestamp, 2_500_000);
+    assert!(report.fills[0].maker);
+    assert_eq!(report.positions[0].size, 2.0);
+    assert_eq!(report.end_timestamp, 2_500_000);
+
+    assert!(log.contains(&"connected true".to_string()));
+    assert!(log.contains(&"order Live 99 at 1100000".to_string()));
+    assert!(log.contains(&"order Filled 99 at 2500000".to_string()));
+    assert!(log.contains(&"fill 99 Maker".to_string()));
+    assert!(log.contains(&"position 2".to_string()));
+    let ticks: Vec<_> = log.iter().filter(|e| e.starts_with("tick")).collect();
+    assert_eq!(ticks, ["tick 1000000", "tick 2000000"]);
+}
+
+#[tokio::test]
+async fn backtest_runs_are_reproducible() {
+    let first = run_once().await;
+    let second = run_once().await;
+
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn backtest_rejects_out_of_order_records() {
+    let env = EnvBuilder::new()
+        .with_task(ws_task(WsChannel::Trades(None)))
+        .with_strategy_module(ReplayProbe::new())
+        .build()
+        .unwrap();
+
+    let result = env
+        .into_backtest()
+        .run(vec![trade(2_000, 1, 100.0), trade(1_000, 2, 100.0)])
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "lob_clients")]
+#[derive(Clone)]
+struct ExecutionProbe {
+    registry: Arc<CommandRegistry>,
+    acks: Arc<Mutex<Vec<AltOrderAck>>>,
+}
+
+#[cfg(feature = "lob_clients")]
+impl Strategy for ExecutionProbe {
+    async fn initialize(&mut self) {}
+}
+
+#[cfg(feature = "lob_clients")]
+impl CommandEmitter for ExecutionProbe {
+    fn command_init(&mut self, registry: Arc<CommandRegistry>) {
+        self.registry = registry;
+    }
+
+    fn command_registry(&self) -> Arc<CommandRegistry> {
+        self.registry.clone()
+    }
+}
+
+#[cfg(feature = "lob_clients")]
+impl EventHandler for ExecutionProbe {
+    async fn on_trade(&mut self, msg: InfraMsg<Vec<WsTrade>>) {
+        if msg.data[0].trade_id != 1 {
+            return;
+        }
+        let order = AltOrder {
+            timestamp: self.now_micros(),
+            market: Market::Okx,
+            order_params: OrderParams {
+                inst: "BTC-USDT-SWAP".into(),
+                side: OrderSide::BUY,
+                size: "1".into(),
+                order_type: OrderType::Limit,
+                price: Some("90".into()),
+                client_order_id: Some("entry".into()),
+                ..Default::default()
+            },
+            metadata: HashMap::from([("leg".into(), "entry".into())]),
+        };
+        self.find_alt_handle(&AltTaskType::LobExecution, 1)
+            .unwrap()
+            .send_command(TaskCommand::OrderExecute(vec![order]), None)
+            .await
+            .unwrap();
+    }
+
+    async fn on_order_ack(&mut self, msg: InfraMsg<Vec<AltOrderAck>>) {
+        self.acks.lock().unwrap().extend(msg.data.iter().cloned());
+        let AltOrderRequest::Place(params) = &msg.data[0].request else {
+            return;
+        };
+        let cancel = AltCancel {
+            timestamp: self.now_micros(),
+            market: Market::Okx,
+            cancel_params: CancelOrderParams {
+                inst: params.inst.clone(),
+                order_id: None,
+                cli_order_id: params.client_order_id.clone(),
+            },
+            metadata: HashMap::new(),
+        };
+        self.find_alt_handle(&AltTaskType::LobExecution, 1)
+            .unwrap()
+            .send_command(TaskCommand::OrderCancel(vec![cancel]), None)
+            .await
+            .unwrap();
+    }
+}
+
+#[cfg(feature = "lob_clients")]
+#[tokio::test]
+async fn backtest_lob_execution_publishes_acks() {
+    let probe = ExecutionProbe {
+        registry: Arc::new(CommandRegistry::default()),
+        acks: Arc::new(Mutex::new(Vec::new())),
+    };
+    let acks = probe.acks.clone();
+    let report = EnvBuilder::new()
+        .with_task(ws_task(WsChannel::Trades(None)))
+        .with_task(alt_task(AltTaskType::LobExecution))
+        .with_strategy_module(probe)
+        .build()
+        .unwrap()
+        .into_backtest()
+        .run(vec![trade(1_000, 1, 100.0), trade(2_000, 2, 100.0)])
+        .await
+        .unwrap();
+
+    assert_eq!(report.orders, 1);
+    assert!(report.fills.is_empty());
+    let acks = acks.lock().unwrap();
+    assert_eq!(acks.len(), 2);
+    let placed = acks[0].result.as_ref().unwrap();
+    assert_eq!(placed.order_status, OrderStatus::Live);
+    assert_eq!(acks[0].metadata["leg"], "entry");
+    assert!(matches!(acks[1].request, AltOrderRequest::Cancel(_)));
+    assert_eq!(
+        acks[1].result.as_ref().unwrap().order_status,
+        OrderStatus::Canceled
+    );
+}
+
+#[cfg(feature = "lob_clients")]
+#[tokio::test]
+async fn backtest_risk_gate_rejects_before_matching() {
+    let probe = ExecutionProbe {
+        registry: Arc::new(CommandRegistry::default()),
+        acks: Arc::new(Mutex::new(Vec::new())),
+    };
+    let acks = probe.acks.clone();
+    let gate = RiskGate::new(RiskLimits {
+        max_order_notional: Some(50.0),
+        ..Default::default()
+    });
+    EnvBuilder::new()
+        .with_task(ws_task(WsChannel::Trades(None)))
+        .with_task(alt_task(AltTaskType::LobExecution))
+        .with_strategy_module(probe)
+        .build()
+        .unwrap()
+        .with_risk_gate(gate)
+        .into_backtest()
+        .run(vec![trade(1_000, 1, 100.0), trade(2_000, 2, 100.0)])
+        .await
+        .unwrap();
+
+    let acks = acks.lock().unwrap();
+    assert_eq!(
+        acks[0].result,
+        Err(AltOrderError::Risk(RiskRejection::OrderNotional {
+            notional: 90.0,
+            limit: 50.0,
+        }))
+    );
+    // The rejected order never reached the matcher, so its cancel misses.
+    assert!(matches!(acks[1].result, Err(AltOrderError::Request(_))));
+}
@@ -0,0 +1,846 @@
+#![cfg(feature = "mock_venue")]
+
+use std::{sync::Arc, time::Duration};
+
+use extrema_infra::{
+    arch::market_assets::{
+        api_general::{CancelOrderParams, OrderParams, get_micros_timestamp},
+        exchange::prelude::*,
+    },
+    prelude::*,
+};
+use tokio::sync::{mpsc, oneshot};
+
+const INST: &str = "BTC_USDT_PERP";
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+async fn start_venue() -> MockVenue {
+    MockVenue::start(MockVenueConfig {
+        maker_fee_rate: 0.0002,
+        taker_fee_rate: 0.0005,
+        balances: vec![("USDT".into(), 10_000.0)],
+    })
+    .await
+    .unwrap()
+}
+
+fn venue_cli(venue: &MockVenue) -> OkxCli {
+    let mut cli = OkxCli::default().with_endpoints(venue.okx_endpoints());
+    cli.api_key = Some(OkxKey {
+        api_key: "mock-key".into(),
+        secret_key: "mock-secret".into(),
+        passphrase: "mock-passphrase".into(),
+    });
+    cli
+}
+
+fn bbo(bid: f64, ask: f64) -> WsLob {
+    let level = |price| LobLevel {
+        price,
+        size: 5.0,
+        action: LobLevelAction::Upsert,
+        order_count: Some(1),
+        level_update_id: None,
+    };
+    WsLob {
+        timestamp: 1_000_000,
+        market: Market::Okx,
+        inst: INST.into(),
+        event: LobEventKind::Bbo,
+        bids: vec![level(bid)],
+        asks: vec![level(ask)],
+        seq: None,
+        checksum: None,
+    }
+}
+
+fn trade(trade_id: u64, price: f64) -> WsTrade {
+    WsTrade {
+        timestamp: 2_000_000,
+        market: Market::Okx,
+        inst: INST.into(),
+        price,
+        size: 1.0,
+        side: OrderSide::SELL,
+        trade_id,
+    }
+}
+
+fn limit(side: OrderSide, price: &str, cli_order_id: &str) -> OrderParams {
+    OrderParams {
+        inst: INST.into(),
+        side,
+        size: "1".into(),
+        order_type: OrderType::Limit,
+        price: Some(price.into()),
+        client_order_id: Some(cli_order_id.into()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn okx_cli_trades_against_mock_venue_over_rest() {
+    let venue = start_venue().await;
+    let cli = venue_cli(&venue);
+    venue.publish_lobs(&[bbo(99.0, 101.0)]);
+
+    let ack = cli
+        .place_order(limit(OrderSide::BUY, "100", "bid1"))
+        .await
+        .unwrap();
+    assert_eq!(ack.order_status, OrderStatus::Live);
+    assert_eq!(ack.cli_order_id.as_deref(), Some("bid1"));
+
+    let open = cli.get_open_orders(INST, None).await.unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].order_id, ack.order_id);
+    assert_eq!(open[0].price, 100.0);
+
+    // A print through the limit fills the resting bid as maker.
+    venue.publish_trades(&[trade(1, 99.5)]);
+    assert!(cli.get_open_orders(INST, None).await.unwrap().is_empty());
+    let positions = cli.get_positions(None).await.unwrap();
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].inst, INST);
+    assert_eq!(positions[0].size, 1.0);
+    assert_eq!(positions[0].avg_price, 100.0);
+
+    // A market sell closes at the bid as taker.
+    let close = OrderParams {
+        inst: INST.into(),
+        side: OrderSide::SELL,
+        size: "1".into(),
+        order_type: OrderType::Market,
+        ..Default::default()
+    };
+    cli.place_order(close).await.unwrap();
+    assert!(cli.get_positions(None).await.unwrap().is_empty());
+
+    let balance = cli.get_balance(None).await.unwrap();
+    let expected = 10_000.0 - 1.0 - 100.0 * 0.0002 - 99.0 * 0.0005;
+    assert_eq!(balance[0].asset, "USDT");
+    assert!((balance[0].total - expected).abs() < 1e-9);
+
+    let acks = cli
+        .place_orders(vec![
+            limit(OrderSide::BUY, "90", "bid2"),
+            limit(OrderSide::SELL, "110", "ask2"),
+        ])
+        .await
+        .unwrap();
+    assert!(acks.iter().all(|ack| ack.order_status == OrderStatus::Live));
+    assert_eq!(cli.get_open_orders(INST, None).await.unwrap().len(), 2);
+
+    let cancels = acks
+        .iter()
+        .map(|ack| CancelOrderParams {
+            inst: INST.into(),
+            order_id: Some(ack.order_id.clone()),
+            cli_order_id: None,
+        })
+        .collect();
+    let canceled = cli.cancel_orders(cancels).await.unwrap();
+    assert!(
+        canceled
+            .iter()
+            .all(|ack| ack.order_status == OrderStatus::Canceled)
+    );
+    assert!(cli.get_open_orders(INST, None).await.unwrap().is_empty());
+
+    // Canceling an order that is no longer open is rejected by the venue.
+    assert!(
+        cli.cancel_order(INST, Some(&acks[0].order_id), None)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn okx_cli_cancels_all_orders_and_arms_cancel_after() {
+    let venue = start_venue().await;
+    let cli = venue_cli(&venue);
+    venue.publish_lobs(&[bbo(99.0, 101.0)]);
+
+    cli.place_orders(vec![
+        limit(OrderSide::BUY, "90", "bid1"),
+        limit(OrderSide::SELL, "110", "ask1"),
+    ])
+    .await
+    .unwrap();
+    let canceled = cli.cancel_all_orders(None).await.unwrap();
+    assert_eq!(canceled.len(), 2);
+    assert!(
+        canceled
+            .iter()
+            .all(|ack| ack.order_status == OrderStatus::Canceled)
+    );
+    assert!(cli.get_open_orders(INST, None).await.unwrap().is_empty());
+
+    cli.set_cancel_after(None, Duration::from_secs(60))
+        .await
+        .unwrap();
+    assert!(venue.cancel_all_after().is_some());
+    assert!(
+        cli.set_cancel_after(None, Duration::from_secs(5))
+            .await
+            .is_err()
+    );
+    cli.set_cancel_after(None, Duration::ZERO).await.unwrap();
+    assert_eq!(venue.cancel_all_after(), None);
+}
+
+#[tokio::test]
+async fn okx_rejections_arrive_as_typed_exchange_errors() {
+    let venue = start_venue().await;
+    let cli = venue_cli(&venue);
+
+    let Err(InfraError::Exchange(unknown)) = cli.cancel_order(INST, Some("999"), None).await else {
+        panic!("cancel of an unknown order succeeded");
+    };
+    assert_eq!(unknown.venue, Venue::Okx);
+    assert_eq!(unknown.category, ErrorCategory::UnknownOrder);
+    assert_eq!(unknown.code, "51400");
+    assert_eq!(unknown.status, None);
+
+    // The mock venue serves no market data and answers in OKX's error
+    // format with an HTTP 404.
+    let Err(InfraError::Exchange(unserved)) = cli.get_tickers(None, None).await else {
+        panic!("mock venue served tickers");
+    };
+    assert_eq!(unserved.code, "404");
+    assert_eq!(unserved.category, ErrorCategory::Other);
+    assert_eq!(unserved.status, Some(404));
+}
+
+#[tokio::test]
+async fn clock_sync_estimates_mock_venue_offset() {
+    let venue = start_venue().await;
+    let cli = venue_cli(&venue);
+
+    let estimate = ClockSync::new()
+        .with_samples(3)
+        .sync(&cli, Venue::Okx, &cli.endpoints.rest)
+        .await
+        .unwrap();
+
+    // The mock venue shares the local clock and answers in milliseconds.
+    assert!(estimate.offset_micros.abs() < 50_000, "{estimate:?}");
+    assert!(estimate.rtt_micros < TIMEOUT.as_micros() as u64);
+    assert_eq!(
+        ClockSync::estimate(Venue::Okx, &cli.endpoints.rest),
+        Some(estimate)
+    );
+    // Production OKX keeps its own estimate.
+    assert_eq!(
+        ClockSync::estimate(Venue::Okx, &OkxCli::default().endpoints.rest),
+        None
+    );
+
+    let received_at = get_micros_timestamp();
+    let mut trade = trade(1, 100.0);
+    trade.timestamp = received_at - 2_000;
+    let latency = trade
+        .latency_micros(&cli.endpoints.rest, received_at)
+        .unwrap();
+    assert!((latency - 2_000).abs() < 50_000, "{latency}");
+
+    trade.market = Market::Coinbase;
+    assert_eq!(trade.latency_micros(&cli.endpoints.rest, received_at), None);
+}
+
+#[tokio::test]
+async fn rate_limiter_keeps_budget_for_cancels_on_mock_venue() {
+    let venue = start_venue().await;
+    let cli = venue_cli(&venue);
+    venue.publish_lobs(&[bbo(99.0, 101.0)]);
+
+    // Two orders a minute, half of them reserved for cancels.
+    let authority = cli.endpoints.rest.trim_start_matches("http://").to_string();
+    RateLimiter::install(
+        &authority,
+        RateLimitProfile::new()
+            .with_bucket("orders", 2, Duration::from_secs(60))
+            .with_rule(
+                EndpointRule::exact("/api/v5/trade/cancel-order")
+                    .with_cost("orders", 1)
+                    .with_priority(RequestPriority::Cancel),
+            )
+            .with_rule(
+                EndpointRule::exact("/api/v5/trade/order")
+                    .with_cost("orders", 1)
+                    .with_priority(RequestPriority::Trade),
+            )
+            .with_policy(RequestPriority::Trade, LimitPolicy::FailFast)
+            .with_reserve(0.5),
+    );
+
+    let ack = cli
+        .place_order(limit(OrderSide::BUY, "90", "bid1"))
+        .await
+        .unwrap();
+    let err = cli
+        .place_order(limit(OrderSide::BUY, "91", "bid2"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, InfraError::RateLimited(_)));
+
+    let canceled = cli
+        .cancel_order(INST, Some(&ack.order_id), None)
+        .await
+        .unwrap();
+    assert_eq!(canceled.order_status, OrderStatus::Canceled);
+    assert!(cli.get_open_orders(INST, None).await.unwrap().is_empty());
+    RateLimiter::uninstall(&authority);
+}
+
+#[derive(Clone)]
+struct VenueProbe {
+    registry: Arc<CommandRegistry>,
+    cli: OkxCli,
+    events: mpsc::UnboundedSender<String>,
+}
+
+impl VenueProbe {
+    /// Connects with `msg` when `reply` is `None`, otherwise sends it and
+    /// waits for the venue's `reply` event.
+    async fn send(&self, channel: &WsChannel, task_id: u64, msg: String, reply: Option<&str>) {
+        let handle = self.find_ws_handle(channel, task_id).unwrap();
+        let (tx, rx) = oneshot::channel();
+        let ack = AckHandle::new(tx);
+        let (cmd, status) = match reply {
+            None => (TaskCommand::WsConnect { msg, ack }, AckStatus::WsConnect),
+            Some(event) => {
+                let reply = Some(WsReplyMatcher::okx_event(event));
+                let cmd = TaskCommand::WsMessage { msg, ack, reply };
+                (cmd, AckStatus::WsAccepted(String::new()))
+            },
+        };
+        handle.send_command(cmd, Some((status, rx))).await.unwrap();
+    }
+}
+
+impl Strategy for VenueProbe {
+    async fn initialize(&mut self) {}
+}
+
+impl CommandEmitter for VenueProbe {
+    fn command_init(&mut self, registry: Arc<CommandRegistry>) {
+        self.registry = registry;
+    }
+
+    fn command_registry(&self) -> Arc<CommandRegistry> {
+        self.registry.clone()
+    }
+}
+
+impl EventHandler for VenueProbe {
+    async fn on_ws_event(&mut self, msg: InfraMsg<WsTaskInfo>) {
+        let channel = &msg.data.ws_channel;
+        if matches!(channel, WsChannel::Trades(_)) {
+            let url = self.cli.get_public_connect_msg(channel).await.unwrap();
+            let insts = [INST.to_string()];
+            let sub = self
+                .cli
+                .get_public_sub_msg(channel, Some(&insts))
+                .await
+                .unwrap();
+            self.send(channel, msg.task_id, url, None).await;
+            self.send(channel, msg.task_id, sub, Some("subscribe"))
+                .await;
+        } else if matches!(channel, WsChannel::OrderEntry) {
+            let url = self.cli.get_private_connect_msg(channel).await.unwrap();
+            let login = self.cli.ws_login_msg().unwrap();
+            self.send(channel, msg.task_id, url, None).await;
+            self.send(channel, msg.task_id, login, Some("login")).await;
+
+            let order = |price, cli_order_id| AltOrder {
+                market: Market::Okx,
+                order_params: limit(OrderSide::BUY, price, cli_order_id),
+                ..Default::default()
+            };
+            let orders = vec![order("100", "ws1"), order("99", "ws2")];
+            self.find_ws_handle(channel, msg.task_id)
+                .unwrap()
+                .send_command(TaskCommand::OrderExecute(orders), None)
+                .await
+                .unwrap();
+        } else {
+            let url = self.cli.get_private_connect_msg(channel).await.unwrap();
+            let login = self.cli.ws_login_msg().unwrap();
+            let sub = self.cli.get_private_sub_msg(channel).await.unwrap();
+            self.send(channel, msg.task_id, url, None).await;
+            self.send(channel, msg.task_id, login, Some("login")).await;
+            self.send(channel, msg.task_id, sub, Some("subscribe"))
+                .await;
+        }
+    }
+
+    async fn on_trade(&mut self, msg: InfraMsg<Vec<WsTrade>>) {
+        for trade in msg.data.iter() {
+            let _ = self
+                .events
+                .send(format!("trade {} {}", trade.inst, trade.price));
+        }
+    }
+
+    async fn on_acc_order(&mut self, msg: InfraMsg<Vec<WsAccOrder>>) {
+        for order in msg.data.iter() {
+            let _ = self.events.send(format!(
+                "order {:?} {}",
+                order.status,
+                order.cli_order_id.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+
+    async fn on_acc_fill(&mut self, msg: InfraMsg<Vec<WsFill>>) {
+        for fill in msg.data.iter() {
+            let _ = self.events.send(format!(
+                "fill {} {:?} {:.2}",
+                fill.price, fill.liquidity, fill.fee
+            ));
+        }
+    }
+
+    async fn on_order_ack(&mut self, msg: InfraMsg<Vec<AltOrderAck>>) {
+        for ack in msg.data.iter() {
+            let status = match &ack.result {
+                Ok(data) => format!("{:?}", data.order_status),
+                Err(e) => format!("error {e}"),
+            };
+            let (kind, cli_order_id) = match &ack.request {
+                AltOrderRequest::Place(params) => ("place", params.client_order_id.clone()),
+                AltOrderRequest::Cancel(_) => (
+                    "cancel",
+                    ack.result
+                        .as_ref()
+                        .ok()
+                        .and_then(|d| d.cli_order_id.clone()),
+                ),
+            };
+            let _ = self.events.send(format!(
+                "ack {kind} {status} {}",
+                cli_order_id.unwrap_or_default()
+            ));
+
+            // Cancel the first resting order by its venue id.
+            if let (AltOrderRequest::Place(params), Ok(data)) = (&ack.request, &ack.result)
+                && params.client_order_id.as_deref() == Some("ws1")
+            {
+                let cancel = AltCancel {
+                    market: Market::Okx,
+                    cancel_params: CancelOrderParams {
+                        inst: INST.into(),
+                        order_id: Some(data.order_id.clone()),
+                        cli_order_id: None,
+                    },
+                    ..Default::default()
+                };
+                self.find_ws_handle(&WsChannel::OrderEntry, msg.task_id)
+                    .unwrap()
+                    .send_command(TaskCommand::OrderCancel(vec![cancel]), None)
+                    .await
+                    .unwrap();
+            }
+        }
+    }
+
+    async fn on_acc_pos(&mut self, msg: InfraMsg<Vec<WsAccPosition>>) {
+        for position in msg.data.iter() {
+            let _ = self
+                .events
+                .send(format!("position {} {}", position.inst, position.size));
+        }
+    }
+}
+
+/// Subscribes through typed relay commands and reports each ack.
+#[derive(Clone)]
+struct SubscriptionProbe {
+    registry: Arc<CommandRegistry>,
+    cli: OkxCli,
+    events: mpsc::UnboundedSender<String>,
+}
+
+impl SubscriptionProbe {
+    async fn command(&self, task_id: u64, cmd: impl FnOnce(AckHandle) -> TaskCommand) -> AckStatus {
+        let handle = self
+            .find_ws_handle(&WsChannel::Trades(None), task_id)
+            .unwrap();
+        let (tx, rx) = oneshot::channel();
+        handle
+            .send_command(cmd(AckHandle::new(tx)), None)
+            .await
+            .unwrap();
+        rx.await.unwrap()
+    }
+}
+
+impl Strategy for SubscriptionProbe {
+    async fn initialize(&mut self) {}
+}
+
+impl CommandEmitter for SubscriptionProbe {
+    fn command_init(&mut self, registry: Arc<CommandRegistry>) {
+        self.registry = registry;
+    }
+
+    fn command_registry(&self) -> Arc<CommandRegistry> {
+        self.registry.clone()
+    }
+}
+
+impl EventHandler for SubscriptionProbe {
+    async fn on_ws_event(&mut self, msg: InfraMsg<WsTaskInfo>) {
+        let channel = WsChannel::Trades(None);
+        let url = self.cli.get_public_connect_msg(&channel).await.unwrap();
+        let connected = self
+            .command(msg.task_id, |ack| TaskCommand::WsConnect { msg: url, ack })
+            .await;
+        assert_eq!(connected, AckStatus::WsConnect);
+
+        let insts = vec![INST.to_string(), "ETH_USDT_PERP".to_string()];
+        let subscribed = self
+            .command(msg.task_id, |ack| TaskCommand::Subscribe {
+                channel: channel.clone(),
+                insts,
+                ack,
+            })
+            .await;
+        let _ = self.events.send(format!("subscribe {subscribed:?}"));
+
+        let dropped = vec!["ETH_USDT_PERP".to_string()];
+        let unsubscribed = self
+            .command(msg.task_id, |ack| TaskCommand::Unsubscribe {
+                channel,
+                insts: dropped,
+                ack,
+            })
+            .await;
+        let _ = self.events.send(format!("unsubscribe {unsubscribed:?}"));
+    }
+
+    async fn on_trade(&mut self, msg: InfraMsg<Vec<WsTrade>>) {
+        for trade in msg.data.iter() {
+            let _ = self
+                .events
+                .send(format!("trade {} {}", trade.inst, trade.price));
+        }
+    }
+}
+
+/// Connects sharded relays and reports which relay delivered each trade.
+#[derive(Clone)]
+struct ShardProbe {
+    registry: Arc<CommandRegistry>,
+    cli: OkxCli,
+    events: mpsc::UnboundedSender<String>,
+}
+
+impl Strategy for ShardProbe {
+    async fn initialize(&mut self) {}
+}
+
+impl CommandEmitter for ShardProbe {
+    fn command_init(&mut self, registry: Arc<CommandRegistry>) {
+        self.registry = registry;
+    }
+
+    fn command_registry(&self) -> Arc<CommandRegistry> {
+        self.registry.clone()
+    }
+}
+
+impl EventHandler for ShardProbe {
+    async fn on_ws_event(&mut self, msg: InfraMsg<WsTaskInfo>) {
+        let channel = WsChannel::Trades(None);
+        let url = self.cli.get_public_connect_msg(&channel).await.unwrap();
+        let handle = self.find_ws_handle(&channel, msg.task_id).unwrap();
+        let (tx, rx) = oneshot::channel();
+        handle
+            .send_command(
+                TaskCommand::WsConnect {
+                    msg: url,
+                    ack: AckHandle::new(tx),
+                },
+                Some((AckStatus::WsConnect, rx)),
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn on_trade(&mut self, msg: InfraMsg<Vec<WsTrade>>) {
+        for trade in msg.data.iter() {
+            let _ = self
+                .events
+                .send(format!("trade {} {}", msg.task_id, trade.inst));
+        }
+    }
+}
+
+fn ws_task(ws_channel: WsChannel) -> WsTaskInfo {
+    WsTaskInfo {
+        market: Market::Okx,
+        ws_channel,
+        filter_channels: false,
+        chunk: 1,
+        task_base_id: Some(1),
+        shards: None,
+    }
+}
+
+async fn expect_events(events: &mut mpsc::UnboundedReceiver<String>, expected: &[&str]) {
+    let mut pending: Vec<&str> = expected.to_vec();
+    tokio::time::timeout(TIMEOUT, async {
+        while !pending.is_empty() {
+            let event = events.recv().await.expect("strategy event channel closed");
+            pending.retain(|expected| *expected != event);
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("strategy never received {pending:?}"));
+}
+
+#[tokio::test]
+async fn runtime_streams_market_data_and_account_updates_from_mock_venue() {
+    let venue = start_venue().await;
+    let cli = venue_cli(&venue);
+    let (events, mut received) = mpsc::unbounded_channel();
+    let probe = VenueProbe {
+        registry: Arc::new(CommandRegistry::default()),
+        cli: cli.clone(),
+        events,
+    };
+
+    let env = EnvBuilder::new()
+        .with_task(ws_task(WsChannel::Trades(None)))
+        .with_task(ws_task(WsChannel::AccountOrders))
+        .with_task(ws_task(WsChannel::AccountFills))
+        .with_task(ws_task(WsChannel::AccountPositions))
+        .with_strategy_module(probe)
+        .build()
+        .unwrap();
+    let shutdown = env.shutdown_handle();
+    let runtime = tokio::spawn(env.execute());
+
+    tokio::time::timeout(TIMEOUT, async {
+        venue.wait_for_subscriptions("trades", 1).await;
+        venue.wait_for_subscriptions("orders", 2).await;
+        venue.wait_for_subscriptions("positions", 1).await;
+    })
+    .await
+    .expect("runtime never subscribed to the mock venue");
+
+    cli.place_order(limit(OrderSide::BUY, "100", "bid1"))
+        .await
+        .unwrap();
+    expect_events(&mut received, &["order Live bid1"]).await;
+
+    venue.publish_trades(&[trade(7, 99.5)]);
+    expect_events(
+        &mut received,
+        &[
+            "trade BTC_USDT_PERP 99.5",
+            "order Filled bid1",
+            "fill 100 Maker 0.02",
+            "position BTC_USDT_PERP 1",
+        ],
+    )
+    .await;
+
+    shutdown.shutdown();
+    tokio::time::timeout(TIMEOUT, runtime)
+        .await
+        .expect("runtime stops after shutdown")
+        .unwrap();
+}
+
+#[cfg(feature = "lob_clients")]
+#[tokio::test]
+async fn cancel_after_task_refreshes_countdown_on_mock_venue() {
+    let venue = start_venue().await;
+    let env = EnvBuilder::new()
+        .with_task(AltTaskInfo {
+            alt_task_type: AltTaskType::CancelAfter(CancelAfterConfig {
+                timeout: Duration::from_secs(30),
+                refresh: Duration::from_millis(200),
+                targets: Vec::new(),
+            }),
+            chunk: 1,
+            task_base_id: Some(1),
+        })
+        .build()
+        .unwrap()
+        .with_execution_client(Market::Okx, LobClients::Okx(venue_cli(&venue)));
+    let shutdown = env.shutdown_handle();
+    let runtime = tokio::spawn(env.execute());
+
+    // Alt tasks start after a five-second delay.
+    let armed = tokio::time::timeout(TIMEOUT, async {
+        loop {
+            if let Some(deadline) = venue.cancel_all_after() {
+                return deadline;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("cancel-after task never armed the countdown");
+    tokio::time::timeout(TIMEOUT, async {
+        while venue.cancel_all_after() <= Some(armed) {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("cancel-after task never refreshed the countdown");
+
+    shutdown.shutdown();
+    tokio::time::timeout(TIMEOUT, runtime)
+        .await
+        .expect("runtime stops after shutdown")
+        .unwrap();
+}
+
+#[cfg(feature = "lob_clients")]
+#[tokio::test]
+async fn order_entry_task_trades_over_websocket_on_mock_venue() {
+    let venue = start_venue().await;
+    let cli = venue_cli(&venue);
+    let (events, mut received) = mpsc::unbounded_channel();
+    let probe = VenueProbe {
+        registry: Arc::new(CommandRegistry::default()),
+        cli: cli.clone(),
+        events,
+    };
+
+    let env = EnvBuilder::new()
+        .with_task(ws_task(WsChannel::OrderEntry))
+        .with_strategy_module(probe)
+        .build()
+        .unwrap()
+        .with_execution_client(Market::Okx, LobClients::Okx(cli.clone()));
+    let shutdown = env.shutdown_handle();
+    let runtime = tokio::spawn(env.execute());
+
+    expect_events(
+        &mut received,
+        &[
+            "ack place Live ws1",
+            "ack place Live ws2",
+            "ack cancel Canceled ws1",
+        ],
+    )
+    .await;
+    let open: Vec<_> = cli
+        .get_open_orders(INST, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|order| order.cli_order_id)
+        .collect();
+    assert_eq!(open, ["ws2"]);
+
+    shutdown.shutdown();
+    tokio::time::timeout(TIMEOUT, runtime)
+        .await
+        .expect("runtime stops after shutdown")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn typed_subscriptions_track_streams_on_mock_venue() {
+    let venue = start_venue().await;
+    let (events, mut received) = mpsc::unbounded_channel();
+    let probe = SubscriptionProbe {
+        registry: Arc::new(CommandRegistry::default()),
+        cli: venue_cli(&venue),
+        events,
+    };
+
+    let env = EnvBuilder::new()
+        .with_task(ws_task(WsChannel::Trades(None)))
+        .with_strategy_module(probe)
+        .build()
+        .unwrap();
+    let shutdown = env.shutdown_handle();
+    let runtime = tokio::spawn(env.execute());
+
+    expect_events(
+        &mut received,
+        &["subscribe WsMessage", "unsubscribe WsMessage"],
+    )
+    .await;
+    // The unsubscribe ack follows the write, so give the venue a moment.
+    tokio::time::timeout(TIMEOUT, async {
+        while venue.subscriptions("trades") != 1 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("venue never dropped the unsubscribed stream");
+
+    venue.publish_trades(&[trade(3, 101.0)]);
+    expect_events(&mut received, &["trade BTC_USDT_PERP 101"]).await;
+
+    shutdown.shutdown();
+    tokio::time::timeout(TIMEOUT, runtime)
+        .await
+        .expect("runtime stops after shutdown")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn sharded_task_spreads_instruments_across_relays_on_mock_venue() {
+    let venue = start_venue().await;
+    let (events, mut received) = mpsc::unbounded_channel();
+    let probe = ShardProbe {
+        registry: Arc::new(CommandRegistry::default()),
+        cli: venue_cli(&venue),
+        events,
+    };
+
+    let insts = [INST, "ETH_USDT_PERP", "SOL_USDT_PERP"];
+    let task = WsTaskInfo {
+        shards: Some(WsShards {
+            insts: insts.iter().map(|inst| inst.to_string()).collect(),
+            per_connection: 2,
+        }),
+        ..ws_task(WsChannel::Trades(None))
+    };
+    let env = EnvBuilder::new()
+        .with_task(task)
+        .with_strategy_module(probe)
+        .build()
+        .unwrap();
+    let shutdown = env.shutdown_handle();
+    let runtime = tokio::spawn(env.execute());
+
+    tokio::time::timeout(TIMEOUT, venue.wait_for_subscriptions("trades", 3))
+        .await
+        .expect("shards never subscribed their instruments");
+
+    let trades: Vec<_> = insts
+        .iter()
+        .zip(1..)
+        .map(|(inst, trade_id)| WsTrade {
+            inst: inst.to_string(),
+            ..trade(trade_id, 100.0)
+        })
+        .collect();
+    venue.publish_trades(&trades);
+    expect_events(
+        &mut received,
+        &[
+            "trade 1 BTC_USDT_PERP",
+            "trade 1 ETH_USDT_PERP",
+            "trade 2 SOL_USDT_PERP",
+        ],
+    )
+    .await;
+
+    shutdown.shutdown();
+    tokio::time::timeout(TIMEOUT, runtime)
+        .await
+        .expect("runtime stops after shutdown")
+        .unwrap();
+}
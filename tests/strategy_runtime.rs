@@ -1,4 +1,11 @@
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
 
 use extrema_infra::prelude::*;
 use tokio::sync::mpsc;
@@ -10,6 +17,7 @@ const ISOLATION_WINDOW: Duration = Duration::from_millis(100);
 struct LifecycleProbe {
     events: mpsc::UnboundedSender<TaskKey>,
     registry: Arc<CommandRegistry>,
+    shutdown_calls: Arc<AtomicUsize>,
 }
 
 impl LifecycleProbe {
@@ -19,6 +27,7 @@ impl LifecycleProbe {
             Self {
                 events,
                 registry: Arc::new(CommandRegistry::default()),
+                shutdown_calls: Arc::new(AtomicUsize::new(0)),
             },
             receiver,
         )
@@ -28,6 +37,10 @@ impl LifecycleProbe {
 impl Strategy for LifecycleProbe {
     async fn initialize(&mut self) {}
 
+    async fn on_shutdown(&mut self) {
+        self.shutdown_calls.fetch_add(1, Ordering::SeqCst);
+    }
+
     fn strategy_name(&self) -> &'static str {
         "LifecycleProbe"
     }
@@ -57,6 +70,7 @@ fn ws_task(ws_channel: WsChannel, chunk: u64) -> TaskInfo {
         filter_channels: false,
         chunk,
         task_base_id: Some(1),
+        shards: None,
     }))
 }
 
@@ -262,3 +276,55 @@ async fn startup_lifecycle_event_is_not_lost() {
 
     runtime.abort();
 }
+
+#[tokio::test]
+async fn shutdown_handle_stops_runtime_and_runs_hooks() {
+    let trade_1 = trade_key(1);
+    let (first, mut first_events) = LifecycleProbe::new();
+    let (second, _second_events) = LifecycleProbe::new();
+    let first_calls = first.shutdown_calls.clone();
+    let second_calls = second.shutdown_calls.clone();
+    let env = EnvBuilder::new()
+        .with_task(ws_task(WsChannel::Trades(None), 1))
+        .with_strategy_module_on(first, [trade_1.clone()])
+        .with_strategy_module(second)
+        .build()
+        .unwrap()
+        .with_shutdown_timeout(Duration::from_secs(2));
+    let shutdown = env.shutdown_handle();
+
+    let runtime = tokio::spawn(env.execute());
+    assert_eq!(
+        receive_keys(&mut first_events, 1).await,
+        HashSet::from([trade_1])
+    );
+
+    shutdown.shutdown();
+    tokio::time::timeout(STARTUP_TIMEOUT, runtime)
+        .await
+        .expect("runtime stops after shutdown")
+        .unwrap();
+
+    assert!(shutdown.is_shutdown());
+    assert_eq!(first_calls.load(Ordering::SeqCst), 1);
+    assert_eq!(second_calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn shutdown_before_startup_returns_without_events() {
+    let (strategy, mut events) = LifecycleProbe::new();
+    let calls = strategy.shutdown_calls.clone();
+    let env = EnvBuilder::new()
+        .with_task(ws_task(WsChannel::Trades(None), 1))
+        .with_strategy_module(strategy)
+        .build()
+        .unwrap();
+    env.shutdown_handle().shutdown();
+
+    tokio::time::timeout(Duration::from_secs(1), env.execute())
+        .await
+        .expect("runtime stops before the startup delay elapses");
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(events.try_recv().is_err());
+}